@@ -2,14 +2,17 @@
 //!
 //! Provides typed Arrow conversions that map n8n `GenericValue` variants to
 //! native Arrow column types instead of wrapping everything in JSON strings.
+//! Columns mixing variants are stored as dense unions so heterogeneous item
+//! streams round-trip without losing types.
 
 use crate::error::ArrowError;
-use crate::schema;
+use crate::schema::{self, ValueKind};
 use arrow_array::{
     Array, ArrayRef, BooleanArray, Float64Array, Int32Array, Int64Array, NullArray, RecordBatch,
-    StringArray, TimestampMillisecondArray, UInt32Array,
+    StringArray, TimestampMillisecondArray, UInt32Array, UnionArray,
 };
-use arrow_schema::DataType;
+use arrow_buffer::ScalarBuffer;
+use arrow_schema::{DataType, Field, UnionFields, UnionMode};
 use n8n_workflow::{DataObject, GenericValue, NodeExecutionData, Run, Workflow};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -133,8 +136,12 @@ pub fn run_data_to_batch(
 /// - `Integer(i64)`     -> `Int64`
 /// - `Float(f64)`       -> `Float64`
 /// - `Bool(bool)`       -> `Boolean`
-/// - `Null`             -> `Null` (or `Utf8` if other rows have strings)
-/// - `Object` / `Array` -> `Utf8` (JSON-serialized)
+/// - `Null`             -> `Null` (or null entries in a typed column)
+/// - `Object` / `Array` -> `Utf8` (JSON-serialized, tagged `json`)
+///
+/// A key whose items hold different variants becomes a dense `Union`
+/// column with one child per variant, so mixed streams keep their types;
+/// see [`schema::infer_node_execution_data_schema`].
 pub fn node_execution_data_to_batch(
     data: &[NodeExecutionData],
 ) -> Result<RecordBatch, ArrowError> {
@@ -156,26 +163,26 @@ pub fn node_execution_data_to_batch(
     let mut columns: Vec<ArrayRef> = Vec::with_capacity(num_cols);
 
     for field in schema.fields() {
-        let col_name = field.name();
-        let col = build_column_for_field(field.data_type(), col_name, data, num_rows)?;
+        let values: Vec<Option<&GenericValue>> =
+            data.iter().map(|item| item.json.get(field.name())).collect();
+        let col = build_column_for_field(field, &values, num_rows)?;
         columns.push(col);
     }
 
     RecordBatch::try_new(schema, columns).map_err(ArrowError::from)
 }
 
-/// Build a single Arrow column from the items' DataObject values for the given key.
+/// Build a single Arrow column for `field` from one value (or none) per row.
 fn build_column_for_field(
-    data_type: &DataType,
-    key: &str,
-    items: &[NodeExecutionData],
+    field: &Field,
+    values: &[Option<&GenericValue>],
     num_rows: usize,
 ) -> Result<ArrayRef, ArrowError> {
-    match data_type {
+    match field.data_type() {
         DataType::Utf8 => {
-            let values: Vec<Option<String>> = items
+            let values: Vec<Option<String>> = values
                 .iter()
-                .map(|item| match item.json.get(key) {
+                .map(|value| match value {
                     Some(GenericValue::String(s)) => Some(s.clone()),
                     Some(GenericValue::Array(arr)) => {
                         Some(serde_json::to_string(arr).unwrap_or_default())
@@ -184,18 +191,17 @@ fn build_column_for_field(
                         Some(serde_json::to_string(obj).unwrap_or_default())
                     }
                     Some(GenericValue::Null) | None => None,
-                    // If the inferred type is Utf8 but the actual value is a
-                    // different primitive (e.g. a heterogeneous schema promoted
-                    // a column to string), convert it.
+                    // The inferred schema never puts another primitive in a
+                    // Utf8 column; convert it rather than drop it.
                     Some(other) => Some(serde_json::to_string(other).unwrap_or_default()),
                 })
                 .collect();
             Ok(Arc::new(StringArray::from(values)) as ArrayRef)
         }
         DataType::Int64 => {
-            let values: Vec<Option<i64>> = items
+            let values: Vec<Option<i64>> = values
                 .iter()
-                .map(|item| match item.json.get(key) {
+                .map(|value| match value {
                     Some(GenericValue::Integer(n)) => Some(*n),
                     _ => None,
                 })
@@ -203,9 +209,9 @@ fn build_column_for_field(
             Ok(Arc::new(Int64Array::from(values)) as ArrayRef)
         }
         DataType::Float64 => {
-            let values: Vec<Option<f64>> = items
+            let values: Vec<Option<f64>> = values
                 .iter()
-                .map(|item| match item.json.get(key) {
+                .map(|value| match value {
                     Some(GenericValue::Float(f)) => Some(*f),
                     Some(GenericValue::Integer(n)) => Some(*n as f64),
                     _ => None,
@@ -214,9 +220,9 @@ fn build_column_for_field(
             Ok(Arc::new(Float64Array::from(values)) as ArrayRef)
         }
         DataType::Boolean => {
-            let values: Vec<Option<bool>> = items
+            let values: Vec<Option<bool>> = values
                 .iter()
-                .map(|item| match item.json.get(key) {
+                .map(|value| match value {
                     Some(GenericValue::Bool(b)) => Some(*b),
                     _ => None,
                 })
@@ -224,11 +230,12 @@ fn build_column_for_field(
             Ok(Arc::new(BooleanArray::from(values)) as ArrayRef)
         }
         DataType::Null => Ok(Arc::new(NullArray::new(num_rows)) as ArrayRef),
+        DataType::Union(fields, UnionMode::Dense) => build_union_column(fields, values),
         _ => {
             // Fallback: serialize to JSON string.
-            let values: Vec<Option<String>> = items
+            let values: Vec<Option<String>> = values
                 .iter()
-                .map(|item| match item.json.get(key) {
+                .map(|value| match value {
                     Some(GenericValue::Null) | None => None,
                     Some(v) => Some(serde_json::to_string(v).unwrap_or_default()),
                 })
@@ -238,6 +245,56 @@ fn build_column_for_field(
     }
 }
 
+/// Build a dense union column: each row goes to the child of its value's
+/// kind, missing values and nulls to the `null` child.
+fn build_union_column(
+    fields: &UnionFields,
+    values: &[Option<&GenericValue>],
+) -> Result<ArrayRef, ArrowError> {
+    let mut type_ids: Vec<i8> = Vec::with_capacity(values.len());
+    let mut offsets: Vec<i32> = Vec::with_capacity(values.len());
+    let mut child_values: Vec<Vec<Option<&GenericValue>>> = vec![Vec::new(); fields.len()];
+
+    let children: Vec<(i8, ValueKind)> = fields
+        .iter()
+        .map(|(type_id, field)| {
+            ValueKind::of_field(field)
+                .map(|kind| (type_id, kind))
+                .ok_or_else(|| {
+                    ArrowError::SchemaMismatch(format!("Union child {} has no kind", field.name()))
+                })
+        })
+        .collect::<Result<_, _>>()?;
+
+    for value in values {
+        let kind = value.map(ValueKind::of).unwrap_or(ValueKind::Null);
+        let (slot, (type_id, _)) = children
+            .iter()
+            .enumerate()
+            .find(|(_, (_, k))| *k == kind)
+            .ok_or_else(|| {
+                ArrowError::SchemaMismatch(format!("No union child for {} values", kind.as_str()))
+            })?;
+        type_ids.push(*type_id);
+        offsets.push(child_values[slot].len() as i32);
+        child_values[slot].push(*value);
+    }
+
+    let arrays: Vec<ArrayRef> = fields
+        .iter()
+        .zip(&child_values)
+        .map(|((_, field), values)| build_column_for_field(field, values, values.len()))
+        .collect::<Result<_, _>>()?;
+
+    let union = UnionArray::try_new(
+        fields.clone(),
+        ScalarBuffer::from(type_ids),
+        Some(ScalarBuffer::from(offsets)),
+        arrays,
+    )?;
+    Ok(Arc::new(union) as ArrayRef)
+}
+
 // ---------------------------------------------------------------------------
 // batch_to_node_execution_data  (reverse conversion)
 // ---------------------------------------------------------------------------
@@ -252,6 +309,12 @@ fn build_column_for_field(
 /// - `Float64` -> `GenericValue::Float`
 /// - `Boolean` -> `GenericValue::Bool`
 /// - `Null`    -> skipped (key is absent)
+/// - `Union`   -> the value of the row's child
+///
+/// Utf8 columns tagged `json` are parsed back into objects and arrays, and
+/// those tagged `string` are never parsed. Untagged Utf8 columns (batches
+/// written before [`schema::SCHEMA_VERSION`] 2) are parsed when a value
+/// looks like a JSON object or array.
 ///
 /// Null values in any column are omitted from the resulting DataObject.
 pub fn batch_to_node_execution_data(
@@ -271,7 +334,7 @@ pub fn batch_to_node_execution_data(
             }
 
             let key = field.name().clone();
-            let value = arrow_value_to_generic(col.as_ref(), row, field.data_type())?;
+            let value = arrow_value_to_generic(col.as_ref(), row, field)?;
             if let Some(v) = value {
                 obj.insert(key, v);
             }
@@ -287,13 +350,13 @@ pub fn batch_to_node_execution_data(
 fn arrow_value_to_generic(
     col: &dyn Array,
     row: usize,
-    data_type: &DataType,
+    field: &Field,
 ) -> Result<Option<GenericValue>, ArrowError> {
     if col.is_null(row) {
         return Ok(None);
     }
 
-    match data_type {
+    match field.data_type() {
         DataType::Utf8 => {
             let arr = col
                 .as_any()
@@ -302,11 +365,17 @@ fn arrow_value_to_generic(
                     ArrowError::ConversionError("Expected StringArray for Utf8 column".into())
                 })?;
             let s = arr.value(row);
-            // Attempt to detect if this was a JSON-serialized object or array.
-            if (s.starts_with('{') && s.ends_with('}')) || (s.starts_with('[') && s.ends_with(']'))
-            {
+            let parse = match ValueKind::of_field(field) {
+                Some(kind) => kind == ValueKind::Json,
+                // Untagged column: detect a JSON-serialized object or array.
+                None => {
+                    (s.starts_with('{') && s.ends_with('}'))
+                        || (s.starts_with('[') && s.ends_with(']'))
+                }
+            };
+            if parse {
                 if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(s) {
-                    return Ok(Some(json_value_to_generic(parsed)));
+                    return Ok(Some(GenericValue::from(parsed)));
                 }
             }
             Ok(Some(GenericValue::String(s.to_string())))
@@ -336,6 +405,20 @@ fn arrow_value_to_generic(
             Ok(Some(GenericValue::Bool(arr.value(row))))
         }
         DataType::Null => Ok(None),
+        DataType::Union(fields, _) => {
+            let arr = col.as_any().downcast_ref::<UnionArray>().ok_or_else(|| {
+                ArrowError::ConversionError("Expected UnionArray".into())
+            })?;
+            let type_id = arr.type_id(row);
+            let child_field = fields
+                .iter()
+                .find(|(id, _)| *id == type_id)
+                .map(|(_, f)| f)
+                .ok_or_else(|| {
+                    ArrowError::ConversionError(format!("Unknown union type id {}", type_id))
+                })?;
+            arrow_value_to_generic(arr.child(type_id).as_ref(), arr.value_offset(row), child_field)
+        }
         _ => {
             // Fallback: try to read as string.
             if let Some(arr) = col.as_any().downcast_ref::<StringArray>() {
@@ -347,34 +430,6 @@ fn arrow_value_to_generic(
     }
 }
 
-/// Convert a serde_json::Value to a GenericValue.
-fn json_value_to_generic(val: serde_json::Value) -> GenericValue {
-    match val {
-        serde_json::Value::Null => GenericValue::Null,
-        serde_json::Value::Bool(b) => GenericValue::Bool(b),
-        serde_json::Value::Number(n) => {
-            if let Some(i) = n.as_i64() {
-                GenericValue::Integer(i)
-            } else if let Some(f) = n.as_f64() {
-                GenericValue::Float(f)
-            } else {
-                GenericValue::Null
-            }
-        }
-        serde_json::Value::String(s) => GenericValue::String(s),
-        serde_json::Value::Array(arr) => {
-            GenericValue::Array(arr.into_iter().map(json_value_to_generic).collect())
-        }
-        serde_json::Value::Object(map) => {
-            let obj: DataObject = map
-                .into_iter()
-                .map(|(k, v)| (k, json_value_to_generic(v)))
-                .collect();
-            GenericValue::Object(obj)
-        }
-    }
-}
-
 // ---------------------------------------------------------------------------
 // Workflow-level conversions (unchanged signatures)
 // ---------------------------------------------------------------------------
@@ -678,4 +733,75 @@ mod tests {
         );
         assert!(recovered[1].json.get("a").is_none());
     }
+
+    #[test]
+    fn test_mixed_types_roundtrip_through_union() {
+        let mut obj = DataObject::new();
+        obj.insert("k".to_string(), GenericValue::Bool(true));
+        let values = vec![
+            GenericValue::Integer(7),
+            GenericValue::String("7".into()),
+            GenericValue::Float(7.5),
+            GenericValue::Object(obj),
+            GenericValue::Null,
+            GenericValue::Integer(8),
+        ];
+        let mut items: Vec<NodeExecutionData> = values
+            .iter()
+            .map(|v| make_item(vec![("value", v.clone())]))
+            .collect();
+        items.push(make_item(vec![("other", GenericValue::Bool(false))]));
+
+        let batch = node_execution_data_to_batch(&items).unwrap();
+        assert_eq!(
+            batch.schema().metadata().get(schema::SCHEMA_VERSION_KEY).map(String::as_str),
+            Some(schema::SCHEMA_VERSION)
+        );
+        let field = batch.schema().field_with_name("value").unwrap().clone();
+        match field.data_type() {
+            DataType::Union(fields, UnionMode::Dense) => {
+                let names: Vec<_> = fields.iter().map(|(_, f)| f.name().clone()).collect();
+                assert_eq!(names, vec!["null", "integer", "float", "string", "json"]);
+            }
+            other => panic!("Expected dense union, got {:?}", other),
+        }
+
+        let recovered = batch_to_node_execution_data(&batch).unwrap();
+        for (item, value) in recovered.iter().zip(&values) {
+            match value {
+                GenericValue::Null => assert!(item.json.get("value").is_none()),
+                v => assert_eq!(item.json.get("value"), Some(v)),
+            }
+        }
+        assert!(recovered[6].json.get("value").is_none());
+        assert_eq!(recovered[6].json.get("other"), Some(&GenericValue::Bool(false)));
+    }
+
+    #[test]
+    fn test_json_like_strings_stay_strings() {
+        let items = vec![make_item(vec![(
+            "body",
+            GenericValue::String("{\"a\": 1}".into()),
+        )])];
+
+        let batch = node_execution_data_to_batch(&items).unwrap();
+        assert_eq!(
+            ValueKind::of_field(batch.schema().field(0)),
+            Some(ValueKind::String)
+        );
+        let recovered = batch_to_node_execution_data(&batch).unwrap();
+        assert_eq!(
+            recovered[0].json.get("body"),
+            Some(&GenericValue::String("{\"a\": 1}".into()))
+        );
+
+        // Untagged (version 1) columns still detect serialized objects.
+        let legacy = RecordBatch::try_new(
+            Arc::new(arrow_schema::Schema::new(vec![Field::new("body", DataType::Utf8, true)])),
+            vec![batch.column(0).clone()],
+        )
+        .unwrap();
+        let recovered = batch_to_node_execution_data(&legacy).unwrap();
+        assert!(matches!(recovered[0].json.get("body"), Some(GenericValue::Object(_))));
+    }
 }
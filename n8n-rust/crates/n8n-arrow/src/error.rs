@@ -25,6 +25,9 @@ pub enum ArrowError {
 
     #[error("Invalid data: {0}")]
     InvalidData(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 impl From<serde_json::Error> for ArrowError {
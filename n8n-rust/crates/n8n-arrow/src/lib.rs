@@ -7,15 +7,20 @@
 //! - Zero-copy data streaming via Arrow IPC
 //! - DataFusion integration for SQL queries on workflow data
 //! - Arrow Flight server/client for efficient network transfer
+//! - Shared-memory segments for handing data to co-located processes (Linux)
 
 pub mod convert;
 pub mod error;
 pub mod flight;
 pub mod ipc;
 pub mod schema;
+#[cfg(target_os = "linux")]
+pub mod shm;
 
 pub use convert::*;
 pub use error::*;
 pub use flight::*;
 pub use ipc::*;
 pub use schema::*;
+#[cfg(target_os = "linux")]
+pub use shm::SharedSegment;
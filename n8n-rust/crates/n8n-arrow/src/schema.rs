@@ -1,66 +1,170 @@
 //! Arrow schema definitions for n8n workflow data.
 
-use arrow_schema::{DataType, Field, Schema, TimeUnit};
-use std::collections::BTreeMap;
+use arrow_schema::{DataType, Field, Schema, TimeUnit, UnionFields, UnionMode};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use n8n_workflow::{GenericValue, NodeExecutionData};
 
+/// Field metadata key naming the [`ValueKind`] a node data column holds.
+pub const VALUE_KIND_KEY: &str = "n8n.kind";
+
+/// Schema metadata key carrying the node data schema version.
+pub const SCHEMA_VERSION_KEY: &str = "n8n.schema_version";
+
+/// Current node data schema version.
+///
+/// Version 1 batches carry no metadata: every column has the type of the
+/// first non-null value seen, and objects and arrays are JSON strings told
+/// apart from plain strings by their shape. Version 2 tags every column
+/// with its [`ValueKind`] and stores columns mixing kinds as dense unions.
+pub const SCHEMA_VERSION: &str = "2";
+
+/// Kind of a `GenericValue` as stored in an Arrow column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ValueKind {
+    Null,
+    Bool,
+    Integer,
+    Float,
+    String,
+    /// Objects and arrays, stored as JSON text.
+    Json,
+}
+
+impl ValueKind {
+    pub fn of(value: &GenericValue) -> Self {
+        match value {
+            GenericValue::Null => ValueKind::Null,
+            GenericValue::Bool(_) => ValueKind::Bool,
+            GenericValue::Integer(_) => ValueKind::Integer,
+            GenericValue::Float(_) => ValueKind::Float,
+            GenericValue::String(_) => ValueKind::String,
+            GenericValue::Array(_) | GenericValue::Object(_) => ValueKind::Json,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ValueKind::Null => "null",
+            ValueKind::Bool => "bool",
+            ValueKind::Integer => "integer",
+            ValueKind::Float => "float",
+            ValueKind::String => "string",
+            ValueKind::Json => "json",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "null" => Some(ValueKind::Null),
+            "bool" => Some(ValueKind::Bool),
+            "integer" => Some(ValueKind::Integer),
+            "float" => Some(ValueKind::Float),
+            "string" => Some(ValueKind::String),
+            "json" => Some(ValueKind::Json),
+            _ => None,
+        }
+    }
+
+    /// Arrow type of a column holding only this kind.
+    pub fn data_type(&self) -> DataType {
+        match self {
+            ValueKind::Null => DataType::Null,
+            ValueKind::Bool => DataType::Boolean,
+            ValueKind::Integer => DataType::Int64,
+            ValueKind::Float => DataType::Float64,
+            ValueKind::String | ValueKind::Json => DataType::Utf8,
+        }
+    }
+
+    /// Nullable field named `name` holding this kind.
+    pub fn field(&self, name: impl Into<String>) -> Field {
+        Field::new(name, self.data_type(), true).with_metadata(HashMap::from([(
+            VALUE_KIND_KEY.to_string(),
+            self.as_str().to_string(),
+        )]))
+    }
+
+    /// Kind recorded in a field's metadata, if any.
+    pub fn of_field(field: &Field) -> Option<Self> {
+        field
+            .metadata()
+            .get(VALUE_KIND_KEY)
+            .and_then(|kind| ValueKind::from_str(kind))
+    }
+}
+
 /// Schema for node execution data items - dynamic schema inferred from DataObject keys.
 ///
-/// Each key in the DataObject becomes a column with a type determined by the
-/// first non-null value seen for that key across all items.
+/// Each key in the DataObject becomes a column. A key holding a single
+/// [`ValueKind`] across all items (nulls aside) gets that kind's native
+/// type; a key holding several kinds becomes a dense union with a `null`
+/// child and one child per kind, named after the kind. Every field records
+/// its kind under [`VALUE_KIND_KEY`] (`union` for unions) and the schema
+/// records [`SCHEMA_VERSION`].
 pub fn infer_node_execution_data_schema(items: &[NodeExecutionData]) -> Schema {
     if items.is_empty() {
         return Schema::empty();
     }
 
-    // Collect all keys and infer their types from the superset of all items.
-    // Use BTreeMap for deterministic column ordering.
-    let mut key_types: BTreeMap<String, DataType> = BTreeMap::new();
+    // Collect all keys and the kinds seen for each. BTreeMap/BTreeSet keep
+    // column and union child order deterministic.
+    let mut key_kinds: BTreeMap<String, BTreeSet<ValueKind>> = BTreeMap::new();
 
     for item in items {
         for (key, value) in &item.json {
-            // Only update if we haven't seen a concrete type yet (i.e. the key is
-            // absent or was previously Null).
-            let current = key_types.get(key);
-            let needs_update = match current {
-                None => true,
-                Some(DataType::Null) => true,
-                _ => false,
-            };
-            if needs_update {
-                let dt = generic_value_to_data_type(value);
-                key_types.insert(key.clone(), dt);
+            let kinds = key_kinds.entry(key.clone()).or_default();
+            let kind = ValueKind::of(value);
+            if kind != ValueKind::Null {
+                kinds.insert(kind);
             }
         }
     }
 
     // If we ended up with no keys at all, return a single-column schema to
     // avoid empty batches.
-    if key_types.is_empty() {
+    if key_kinds.is_empty() {
         return Schema::new(vec![Field::new("_empty", DataType::Null, true)]);
     }
 
-    let fields: Vec<Field> = key_types
+    let fields: Vec<Field> = key_kinds
         .into_iter()
-        .map(|(name, dt)| Field::new(name, dt, true))
+        .map(|(name, kinds)| match kinds.len() {
+            0 => ValueKind::Null.field(name),
+            1 => kinds.into_iter().next().unwrap_or(ValueKind::Null).field(name),
+            _ => union_field(name, kinds),
+        })
         .collect();
 
-    Schema::new(fields)
+    Schema::new(fields).with_metadata(HashMap::from([(
+        SCHEMA_VERSION_KEY.to_string(),
+        SCHEMA_VERSION.to_string(),
+    )]))
+}
+
+/// Dense union field with a `null` child followed by one child per kind.
+/// Type IDs are the children's positions.
+fn union_field(name: String, kinds: BTreeSet<ValueKind>) -> Field {
+    let children: Vec<Field> = std::iter::once(ValueKind::Null)
+        .chain(kinds)
+        .map(|kind| kind.field(kind.as_str()))
+        .collect();
+    let type_ids = 0..children.len() as i8;
+    Field::new(
+        name,
+        DataType::Union(UnionFields::new(type_ids, children), UnionMode::Dense),
+        true,
+    )
+    .with_metadata(HashMap::from([(
+        VALUE_KIND_KEY.to_string(),
+        "union".to_string(),
+    )]))
 }
 
 /// Map a GenericValue to the corresponding Arrow DataType.
 pub fn generic_value_to_data_type(value: &GenericValue) -> DataType {
-    match value {
-        GenericValue::Null => DataType::Null,
-        GenericValue::Bool(_) => DataType::Boolean,
-        GenericValue::Integer(_) => DataType::Int64,
-        GenericValue::Float(_) => DataType::Float64,
-        GenericValue::String(_) => DataType::Utf8,
-        // Complex types are JSON-serialized to strings.
-        GenericValue::Array(_) => DataType::Utf8,
-        GenericValue::Object(_) => DataType::Utf8,
-    }
+    // Complex types are JSON-serialized to strings.
+    ValueKind::of(value).data_type()
 }
 
 /// Schema for task data (node execution results).
@@ -0,0 +1,221 @@
+//! Arrow IPC in shared memory.
+//!
+//! A [`SharedSegment`] is an anonymous memory file (`memfd`) holding an
+//! Arrow IPC stream. The writer seals it so neither side can change it any
+//! more, then passes its file descriptor to a process on the same host. The
+//! reader maps the file and decodes record batches whose buffers point into
+//! the mapping, so handing over gigabytes costs a file descriptor rather
+//! than a copy.
+//!
+//! Linux only.
+
+use crate::error::ArrowError;
+use crate::ipc::aligned_ipc_options;
+use arrow_array::RecordBatch;
+use arrow_buffer::Buffer;
+use arrow_ipc::reader::StreamDecoder;
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::Schema;
+use std::ffi::CString;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+/// Seals of a finished segment: size and contents are fixed.
+const SEALS: libc::c_int =
+    libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_WRITE | libc::F_SEAL_SEAL;
+
+/// A sealed memory file holding an Arrow IPC stream.
+#[derive(Debug)]
+pub struct SharedSegment {
+    fd: OwnedFd,
+    len: usize,
+}
+
+impl SharedSegment {
+    /// Write `batches` into a new sealed segment. `name` only shows up in
+    /// `/proc/<pid>/fd` listings.
+    pub fn from_batches(
+        name: &str,
+        schema: &Schema,
+        batches: &[RecordBatch],
+    ) -> Result<Self, ArrowError> {
+        let file = File::from(memfd(name)?);
+        let mut writer = StreamWriter::try_new_with_options(
+            BufWriter::new(&file),
+            schema,
+            aligned_ipc_options(),
+        )?;
+        for batch in batches {
+            writer.write(batch)?;
+        }
+        writer.finish()?;
+        writer
+            .into_inner()?
+            .into_inner()
+            .map_err(|e| ArrowError::Io(e.into_error()))?;
+
+        let len = file.metadata()?.len() as usize;
+        if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_ADD_SEALS, SEALS) } < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(Self {
+            fd: file.into(),
+            len,
+        })
+    }
+
+    /// Adopt a segment received from another process. It must be sealed
+    /// against writes and resizing, so the sender cannot change data the
+    /// receiver is reading.
+    pub fn from_fd(fd: OwnedFd) -> Result<Self, ArrowError> {
+        let seals = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GET_SEALS) };
+        if seals < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        let required = libc::F_SEAL_SHRINK | libc::F_SEAL_WRITE;
+        if seals & required != required {
+            return Err(ArrowError::InvalidData(
+                "Shared segment is not sealed".to_string(),
+            ));
+        }
+        let file = File::from(fd);
+        let len = file.metadata()?.len() as usize;
+        Ok(Self {
+            fd: file.into(),
+            len,
+        })
+    }
+
+    /// Size of the IPC stream in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Map the segment read-only. The buffer, and every array sliced from
+    /// it, keeps the mapping alive.
+    pub fn map(&self) -> Result<Buffer, ArrowError> {
+        if self.len == 0 {
+            return Ok(Buffer::from_vec(Vec::<u8>::new()));
+        }
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                self.len,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                self.fd.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error().into());
+        }
+        let ptr = NonNull::new(ptr.cast::<u8>())
+            .ok_or_else(|| ArrowError::InvalidData("mmap returned null".to_string()))?;
+        let mapping = Arc::new(Mapping { ptr, len: self.len });
+        // SAFETY: the mapping is `len` readable bytes, sealed against writes,
+        // and unmapped only when the last buffer referencing it is dropped.
+        Ok(unsafe { Buffer::from_custom_allocation(ptr, self.len, mapping) })
+    }
+
+    /// Decode the record batches in the segment without copying them.
+    pub fn read_batches(&self) -> Result<Vec<RecordBatch>, ArrowError> {
+        let mut buffer = self.map()?;
+        let mut decoder = StreamDecoder::new();
+        let mut batches = Vec::new();
+        while !buffer.is_empty() {
+            if let Some(batch) = decoder.decode(&mut buffer)? {
+                batches.push(batch);
+            }
+        }
+        decoder.finish()?;
+        Ok(batches)
+    }
+
+    pub fn into_fd(self) -> OwnedFd {
+        self.fd
+    }
+}
+
+impl AsFd for SharedSegment {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+/// Create a memory file that can be sealed.
+fn memfd(name: &str) -> Result<OwnedFd, ArrowError> {
+    let name = CString::new(name)
+        .map_err(|_| ArrowError::InvalidData("Segment name contains NUL".to_string()))?;
+    let fd = unsafe { libc::memfd_create(name.as_ptr(), libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    // SAFETY: `fd` was just created and is owned by nobody else.
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// A read-only mapping, unmapped on drop.
+struct Mapping {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// SAFETY: the mapping is read-only and never moves.
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr.as_ptr().cast(), self.len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Array, Int64Array, StringArray};
+    use arrow_schema::{DataType, Field};
+
+    #[test]
+    fn test_segment_roundtrip_is_sealed_and_mapped() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])),
+            ],
+        )
+        .unwrap();
+
+        let segment = SharedSegment::from_batches("test", &schema, &[batch.clone(), batch]).unwrap();
+        assert!(!segment.is_empty());
+
+        // What a receiving process sees: a duplicated descriptor.
+        let received = SharedSegment::from_fd(segment.as_fd().try_clone_to_owned().unwrap()).unwrap();
+        let batches = received.read_batches().unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[1].num_rows(), 3);
+        assert!(batches[1].column(1).is_null(1));
+
+        // The sealed file cannot be written.
+        let file = File::from(received.into_fd());
+        assert!(io::Write::write(&mut &file, b"x").is_err());
+
+        let unsealed = File::from(memfd("unsealed").unwrap());
+        assert!(SharedSegment::from_fd(unsealed.into()).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+//! Client error types.
+
+use thiserror::Error;
+
+/// Errors returned by [`Client`](crate::Client) and, with the `grpc`
+/// feature, [`GrpcClient`](crate::grpc::GrpcClient).
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// The request could not be sent or the response not read.
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The server answered with an error status.
+    #[error("API error {status}: {message}")]
+    Api { status: u16, message: String },
+
+    /// The response body did not match the expected shape.
+    #[error("Invalid response: {0}")]
+    Decode(#[from] serde_json::Error),
+
+    /// The base URL or endpoint could not be parsed.
+    #[error("Invalid endpoint: {0}")]
+    InvalidEndpoint(String),
+
+    /// A gRPC call failed.
+    #[cfg(feature = "grpc")]
+    #[error("gRPC error: {0}")]
+    Grpc(#[from] tonic::Status),
+
+    /// The gRPC channel could not be established.
+    #[cfg(feature = "grpc")]
+    #[error("gRPC transport error: {0}")]
+    Transport(#[from] tonic::transport::Error),
+}
+
+impl ClientError {
+    /// HTTP status of an API error, if this is one.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ClientError::Api { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Whether the server reported the resource as missing.
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+}
+
+/// Result type for client calls.
+pub type Result<T> = std::result::Result<T, ClientError>;
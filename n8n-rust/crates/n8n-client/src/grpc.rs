@@ -0,0 +1,95 @@
+//! gRPC client (feature `grpc`).
+//!
+//! Thin wrapper over the tonic clients generated in [`n8n_grpc::pb`]. Only
+//! useful against servers that register `WorkflowService` and
+//! `HammingService` on their gRPC listener; use
+//! [`Client::negotiate`](crate::Client::negotiate) to find out whether a
+//! server does.
+
+use futures::{Stream, StreamExt};
+use n8n_grpc::pb;
+use n8n_grpc::pb::hamming_service_client::HammingServiceClient;
+use n8n_grpc::pb::workflow_service_client::WorkflowServiceClient;
+use tonic::transport::Channel;
+
+use crate::error::{ClientError, Result};
+
+/// Async client for the gRPC surface.
+#[derive(Debug, Clone)]
+pub struct GrpcClient {
+    workflows: WorkflowServiceClient<Channel>,
+    hamming: HammingServiceClient<Channel>,
+}
+
+impl GrpcClient {
+    /// Connect to a gRPC endpoint, e.g. `http://localhost:50051`.
+    pub async fn connect(endpoint: impl Into<String>) -> Result<Self> {
+        let channel = Channel::from_shared(endpoint.into())
+            .map_err(|e| ClientError::InvalidEndpoint(e.to_string()))?
+            .connect()
+            .await?;
+        Ok(Self::from_channel(channel))
+    }
+
+    /// Build on an existing channel.
+    pub fn from_channel(channel: Channel) -> Self {
+        Self {
+            workflows: WorkflowServiceClient::new(channel.clone()),
+            hamming: HammingServiceClient::new(channel),
+        }
+    }
+
+    pub async fn get_workflow(&self, id: &str) -> Result<Option<pb::Workflow>> {
+        let response = self
+            .workflows
+            .clone()
+            .get_workflow(pb::GetWorkflowRequest { workflow_id: id.to_string() })
+            .await?;
+        Ok(response.into_inner().workflow)
+    }
+
+    pub async fn list_workflows(&self, request: pb::ListWorkflowsRequest) -> Result<Vec<pb::Workflow>> {
+        let response = self.workflows.clone().list_workflows(request).await?;
+        Ok(response.into_inner().workflows)
+    }
+
+    /// Execute a workflow and wait for the result.
+    pub async fn execute(&self, request: pb::ExecuteWorkflowRequest) -> Result<Option<pb::ExecutionResult>> {
+        let response = self.workflows.clone().execute_workflow(request).await?;
+        Ok(response.into_inner().result)
+    }
+
+    /// Execute a workflow and stream its events as they happen.
+    pub async fn execute_stream(
+        &self,
+        request: pb::ExecuteWorkflowRequest,
+    ) -> Result<impl Stream<Item = Result<pb::ExecutionEvent>>> {
+        let stream = self
+            .workflows
+            .clone()
+            .execute_workflow_stream(request)
+            .await?
+            .into_inner();
+        Ok(stream.map(|event| event.map_err(ClientError::from)))
+    }
+
+    /// Nearest fingerprints to `query`.
+    pub async fn find_similar(
+        &self,
+        query: pb::Fingerprint,
+        top_k: u32,
+        max_distance: Option<u32>,
+    ) -> Result<Vec<pb::SimilarityMatch>> {
+        let response = self
+            .hamming
+            .clone()
+            .find_similar(pb::SimilaritySearchRequest {
+                query: Some(query),
+                top_k,
+                max_distance,
+                collection: None,
+            })
+            .await?;
+        Ok(response.into_inner().matches)
+    }
+}
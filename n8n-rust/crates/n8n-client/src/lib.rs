@@ -0,0 +1,321 @@
+//! # n8n-client
+//!
+//! Async client for the n8n-rust API, for services that want to manage and
+//! run workflows without hand-rolling HTTP.
+//!
+//! - [`Client`] covers the REST surface: workflow CRUD, activation,
+//!   explain/lint, executions and transport negotiation
+//! - [`GrpcClient`](grpc::GrpcClient) (feature `grpc`) covers streamed
+//!   execution events and Hamming similarity search
+//!
+//! ```rust,no_run
+//! use n8n_client::{Client, ExecutionRequest};
+//! use std::time::Duration;
+//! use futures::StreamExt;
+//!
+//! # async fn example() -> n8n_client::Result<()> {
+//! let client = Client::new("http://localhost:8080")?;
+//! let workflows = client.list_workflows(&Default::default()).await?;
+//!
+//! let execution = client
+//!     .execute(&ExecutionRequest {
+//!         workflow_id: workflows.data[0].id.clone(),
+//!         mode: None,
+//!         data: None,
+//!     })
+//!     .await?;
+//!
+//! let mut updates = Box::pin(client.watch_execution(&execution.id, Duration::from_secs(1)));
+//! while let Some(update) = updates.next().await {
+//!     println!("{:?}", update?.status);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod error;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod types;
+
+pub use error::{ClientError, Result};
+pub use types::*;
+
+use futures::Stream;
+use reqwest::{Method, RequestBuilder, Url};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+/// Async client for the REST API.
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: Url,
+}
+
+impl Client {
+    /// Create a client for a server root URL, e.g. `http://localhost:8080`.
+    pub fn new(base_url: &str) -> Result<Self> {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+
+    /// Create a client that sends requests through `http` (timeouts,
+    /// default headers, proxies).
+    pub fn with_http_client(base_url: &str, http: reqwest::Client) -> Result<Self> {
+        let mut base_url =
+            Url::parse(base_url).map_err(|e| ClientError::InvalidEndpoint(e.to_string()))?;
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        Ok(Self { http, base_url })
+    }
+
+    /// Server root URL.
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    // ========================================================================
+    // Transport negotiation
+    // ========================================================================
+
+    /// `GET /api/v1/capabilities`.
+    pub async fn capabilities(&self) -> Result<serde_json::Value> {
+        self.send(self.request(Method::GET, "api/v1/capabilities")?).await
+    }
+
+    /// Ask the server which format and transport to use for a workload.
+    /// Offers protobuf over gRPC only when built with the `grpc` feature.
+    pub async fn negotiate(&self, hints: DataHints) -> Result<Negotiation> {
+        let mut formats = vec!["json"];
+        let mut transports = vec!["rest"];
+        if cfg!(feature = "grpc") {
+            formats.insert(0, "protobuf");
+            transports.insert(0, "grpc");
+        }
+
+        let body = serde_json::json!({
+            "formats": formats,
+            "transports": transports,
+            "data_hints": hints,
+        });
+        self.send(self.request(Method::POST, "api/v1/negotiate")?.json(&body)).await
+    }
+
+    // ========================================================================
+    // Workflows
+    // ========================================================================
+
+    pub async fn list_workflows(
+        &self,
+        query: &ListWorkflowsQuery,
+    ) -> Result<ListResponse<WorkflowResponse>> {
+        self.send(self.request(Method::GET, "api/v1/workflows")?.query(query)).await
+    }
+
+    pub async fn get_workflow(&self, id: &str) -> Result<WorkflowResponse> {
+        self.send(self.request(Method::GET, &format!("api/v1/workflows/{}", id))?).await
+    }
+
+    pub async fn create_workflow(&self, workflow: &WorkflowRequest) -> Result<WorkflowResponse> {
+        self.send(self.request(Method::POST, "api/v1/workflows")?.json(workflow)).await
+    }
+
+    pub async fn update_workflow(
+        &self,
+        id: &str,
+        workflow: &WorkflowRequest,
+    ) -> Result<WorkflowResponse> {
+        self.send(
+            self.request(Method::PUT, &format!("api/v1/workflows/{}", id))?
+                .json(workflow),
+        )
+        .await
+    }
+
+    pub async fn delete_workflow(&self, id: &str) -> Result<()> {
+        self.send_empty(self.request(Method::DELETE, &format!("api/v1/workflows/{}", id))?)
+            .await
+    }
+
+    pub async fn activate_workflow(&self, id: &str) -> Result<WorkflowResponse> {
+        self.send(self.request(Method::POST, &format!("api/v1/workflows/{}/activate", id))?)
+            .await
+    }
+
+    pub async fn deactivate_workflow(&self, id: &str) -> Result<WorkflowResponse> {
+        self.send(self.request(Method::POST, &format!("api/v1/workflows/{}/deactivate", id))?)
+            .await
+    }
+
+    /// `POST /api/v1/workflows/:id/clone`.
+    pub async fn clone_workflow(
+        &self,
+        id: &str,
+        request: &CloneWorkflowRequest,
+    ) -> Result<CloneWorkflowResponse> {
+        self.send(
+            self.request(Method::POST, &format!("api/v1/workflows/{}/clone", id))?
+                .json(request),
+        )
+        .await
+    }
+
+    /// `GET /api/v1/workflows/:id/explain`.
+    pub async fn explain_workflow(&self, id: &str) -> Result<serde_json::Value> {
+        self.send(self.request(Method::GET, &format!("api/v1/workflows/{}/explain", id))?)
+            .await
+    }
+
+    /// `GET /api/v1/workflows/:id/lint`.
+    pub async fn lint_workflow(&self, id: &str) -> Result<serde_json::Value> {
+        self.send(self.request(Method::GET, &format!("api/v1/workflows/{}/lint", id))?)
+            .await
+    }
+
+    // ========================================================================
+    // Executions
+    // ========================================================================
+
+    /// Start an execution.
+    pub async fn execute(&self, request: &ExecutionRequest) -> Result<ExecutionResponse> {
+        self.send(self.request(Method::POST, "api/v1/executions")?.json(request)).await
+    }
+
+    pub async fn list_executions(
+        &self,
+        query: &ListExecutionsQuery,
+    ) -> Result<ListResponse<ExecutionResponse>> {
+        self.send(self.request(Method::GET, "api/v1/executions")?.query(query)).await
+    }
+
+    pub async fn get_execution(&self, id: &str) -> Result<ExecutionResponse> {
+        self.send(self.request(Method::GET, &format!("api/v1/executions/{}", id))?).await
+    }
+
+    pub async fn delete_execution(&self, id: &str) -> Result<()> {
+        self.send_empty(self.request(Method::DELETE, &format!("api/v1/executions/{}", id))?)
+            .await
+    }
+
+    pub async fn stop_execution(&self, id: &str) -> Result<ExecutionResponse> {
+        self.send(self.request(Method::POST, &format!("api/v1/executions/{}/stop", id))?)
+            .await
+    }
+
+    pub async fn retry_execution(&self, id: &str) -> Result<ExecutionResponse> {
+        self.send(self.request(Method::POST, &format!("api/v1/executions/{}/retry", id))?)
+            .await
+    }
+
+    /// Poll an execution every `interval`, yielding it each time its status
+    /// changes. The stream ends after the execution finishes or on the first
+    /// error.
+    pub fn watch_execution(
+        &self,
+        id: &str,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<ExecutionResponse>> + '_ {
+        struct Watch {
+            id: String,
+            last: Option<n8n_workflow::ExecutionStatus>,
+            done: bool,
+        }
+
+        let initial = Watch { id: id.to_string(), last: None, done: false };
+        futures::stream::unfold(initial, move |mut watch| async move {
+            if watch.done {
+                return None;
+            }
+            loop {
+                if watch.last.is_some() {
+                    tokio::time::sleep(interval).await;
+                }
+                match self.get_execution(&watch.id).await {
+                    Ok(execution) => {
+                        if watch.last == Some(execution.status) && !execution.finished {
+                            continue;
+                        }
+                        watch.last = Some(execution.status);
+                        watch.done = execution.finished;
+                        return Some((Ok(execution), watch));
+                    }
+                    Err(e) => {
+                        watch.done = true;
+                        return Some((Err(e), watch));
+                    }
+                }
+            }
+        })
+    }
+
+    // ========================================================================
+    // Plumbing
+    // ========================================================================
+
+    fn request(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        let url = self
+            .base_url
+            .join(path)
+            .map_err(|e| ClientError::InvalidEndpoint(e.to_string()))?;
+        Ok(self.http.request(method, url))
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        let body = self.send_raw(request).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    async fn send_empty(&self, request: RequestBuilder) -> Result<()> {
+        self.send_raw(request).await.map(|_| ())
+    }
+
+    async fn send_raw(&self, request: RequestBuilder) -> Result<Vec<u8>> {
+        let response = request.send().await?;
+        let status = response.status();
+        let body = response.bytes().await?;
+        if status.is_success() {
+            return Ok(body.to_vec());
+        }
+
+        let message = serde_json::from_slice::<ApiErrorBody>(&body)
+            .map(|e| e.message)
+            .unwrap_or_else(|_| String::from_utf8_lossy(&body).into_owned());
+        Err(ClientError::Api { status: status.as_u16(), message })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_keeps_prefix() {
+        let client = Client::new("http://localhost:8080/n8n").unwrap();
+        let request = client
+            .request(Method::GET, "api/v1/workflows")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(request.url().as_str(), "http://localhost:8080/n8n/api/v1/workflows");
+
+        assert!(Client::new("not a url").is_err());
+    }
+
+    #[test]
+    fn test_execution_response_decodes_server_json() {
+        let json = serde_json::json!({
+            "id": "e1",
+            "workflowId": "w1",
+            "finished": true,
+            "mode": "manual",
+            "status": "success",
+            "startedAt": "2024-01-01T00:00:00Z",
+            "stoppedAt": "2024-01-01T00:00:01Z",
+            "data": { "resultData": { "runData": {} } }
+        });
+        let execution: ExecutionResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(execution.status, n8n_workflow::ExecutionStatus::Success);
+        assert!(execution.stopped_at.is_some());
+    }
+}
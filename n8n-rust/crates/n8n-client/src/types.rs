@@ -0,0 +1,227 @@
+//! Request and response types of the REST API.
+//!
+//! These mirror the server's JSON shapes (`n8n_grpc::transport::api`), with
+//! both directions derived so they can be built, sent and inspected.
+
+use chrono::{DateTime, Utc};
+use n8n_workflow::{
+    ExecutionStatus, Node, NodeCredentialRef, Workflow, WorkflowConnections, WorkflowSettings,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Body of `POST /workflows` and `PUT /workflows/:id`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowRequest {
+    pub name: String,
+    #[serde(default)]
+    pub nodes: Vec<Node>,
+    #[serde(default)]
+    pub connections: WorkflowConnections,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub static_data: Option<serde_json::Value>,
+}
+
+impl From<&Workflow> for WorkflowRequest {
+    fn from(w: &Workflow) -> Self {
+        Self {
+            name: w.name.clone(),
+            nodes: w.nodes.clone(),
+            connections: w.connections.clone(),
+            settings: serde_json::to_value(&w.settings).ok(),
+            static_data: w.static_data.as_ref().and_then(|d| serde_json::to_value(d).ok()),
+        }
+    }
+}
+
+/// A workflow as returned by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowResponse {
+    pub id: String,
+    pub name: String,
+    pub active: bool,
+    #[serde(default)]
+    pub nodes: Vec<Node>,
+    #[serde(default)]
+    pub connections: WorkflowConnections,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub static_data: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorkflowResponse {
+    /// Convert into the workflow model.
+    pub fn into_workflow(self) -> Workflow {
+        let settings: WorkflowSettings = self
+            .settings
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+
+        Workflow {
+            id: self.id,
+            name: self.name,
+            active: self.active,
+            nodes: self.nodes,
+            connections: self.connections,
+            settings,
+            static_data: self.static_data.and_then(|v| serde_json::from_value(v).ok()),
+            pin_data: None,
+            description: None,
+            version_id: None,
+            created_at: Some(self.created_at),
+            updated_at: Some(self.updated_at),
+        }
+    }
+}
+
+/// Body of `POST /workflows/:id/clone`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneWorkflowRequest {
+    /// Name of the copy; defaults to the source name plus `name_suffix`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_suffix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    /// Source credential ID (or name) to its replacement.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub credentials: HashMap<String, NodeCredentialRef>,
+    /// Source variable name to its replacement.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, String>,
+}
+
+/// A cloned workflow as returned by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneWorkflowResponse {
+    #[serde(flatten)]
+    pub workflow: WorkflowResponse,
+    pub cloned_from: String,
+    pub remapping: CloneRemapping,
+}
+
+/// What a clone remapped.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneRemapping {
+    pub remapped_credentials: usize,
+    pub remapped_variables: usize,
+    /// Credentials the copy still shares with its source.
+    #[serde(default)]
+    pub unmapped_credentials: Vec<String>,
+}
+
+/// Paginated list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListResponse<T> {
+    pub data: Vec<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Filters for `GET /workflows`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWorkflowsQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// Filters for `GET /executions`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListExecutionsQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// Body of `POST /executions`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionRequest {
+    pub workflow_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+/// An execution as returned by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionResponse {
+    pub id: String,
+    #[serde(default)]
+    pub workflow_id: Option<String>,
+    pub finished: bool,
+    pub mode: String,
+    pub status: ExecutionStatus,
+    pub started_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stopped_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_till: Option<DateTime<Utc>>,
+    /// `resultData` with `runData`, `lastNodeExecuted` and `error`.
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+/// Data characteristics sent with a negotiation request.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataHints {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streaming: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columnar: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record_count: Option<u64>,
+    /// Client runs on the server's host; allows the shared-memory transport.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colocated: Option<bool>,
+}
+
+/// Result of `POST /negotiate`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Negotiation {
+    pub format: String,
+    pub transport: String,
+    pub endpoint: String,
+    pub reason: String,
+    #[serde(default)]
+    pub alternatives: Vec<serde_json::Value>,
+}
+
+/// Error body returned by the server.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ApiErrorBody {
+    pub message: String,
+}
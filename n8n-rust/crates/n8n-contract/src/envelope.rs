@@ -2,7 +2,7 @@
 
 use crate::types::{DataEnvelope, EnvelopeMetadata};
 use chrono::Utc;
-use n8n_workflow::NodeExecutionData;
+use n8n_workflow::{object_from_json, object_to_json, GenericValue, NodeExecutionData};
 use serde_json::Value;
 
 /// Convert n8n node output items into a `DataEnvelope`.
@@ -11,7 +11,7 @@ use serde_json::Value;
 pub fn from_n8n_output(items: &[NodeExecutionData], source_step: &str) -> DataEnvelope {
     let data: Vec<Value> = items
         .iter()
-        .filter_map(|item| serde_json::to_value(&item.json).ok())
+        .map(|item| Value::Object(object_to_json(&item.json)))
         .collect();
 
     DataEnvelope {
@@ -58,16 +58,10 @@ pub fn to_n8n_items(envelope: &DataEnvelope) -> Vec<NodeExecutionData> {
             .iter()
             .map(|v| {
                 let json = match v {
-                    Value::Object(map) => {
-                        map.iter()
-                            .map(|(k, v)| {
-                                (k.clone(), json_value_to_generic(v))
-                            })
-                            .collect()
-                    }
+                    Value::Object(map) => object_from_json(map.clone()),
                     other => {
                         let mut m = std::collections::HashMap::new();
-                        m.insert("data".to_string(), json_value_to_generic(other));
+                        m.insert("data".to_string(), GenericValue::from(other));
                         m
                     }
                 };
@@ -77,7 +71,7 @@ pub fn to_n8n_items(envelope: &DataEnvelope) -> Vec<NodeExecutionData> {
         Value::Null => vec![NodeExecutionData::default()],
         other => {
             let mut m = std::collections::HashMap::new();
-            m.insert("data".to_string(), json_value_to_generic(other));
+            m.insert("data".to_string(), GenericValue::from(other));
             vec![NodeExecutionData::new(m)]
         }
     }
@@ -88,32 +82,6 @@ pub fn passthrough(envelope: DataEnvelope) -> DataEnvelope {
     envelope
 }
 
-/// Convert serde_json::Value to n8n GenericValue.
-fn json_value_to_generic(v: &Value) -> n8n_workflow::GenericValue {
-    match v {
-        Value::Null => n8n_workflow::GenericValue::Null,
-        Value::Bool(b) => n8n_workflow::GenericValue::Bool(*b),
-        Value::Number(n) => {
-            if let Some(i) = n.as_i64() {
-                n8n_workflow::GenericValue::Integer(i)
-            } else {
-                n8n_workflow::GenericValue::Float(n.as_f64().unwrap_or(0.0))
-            }
-        }
-        Value::String(s) => n8n_workflow::GenericValue::String(s.clone()),
-        Value::Array(arr) => {
-            n8n_workflow::GenericValue::Array(arr.iter().map(json_value_to_generic).collect())
-        }
-        Value::Object(map) => {
-            let obj = map
-                .iter()
-                .map(|(k, v)| (k.clone(), json_value_to_generic(v)))
-                .collect();
-            n8n_workflow::GenericValue::Object(obj)
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
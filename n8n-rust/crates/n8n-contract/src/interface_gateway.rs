@@ -26,6 +26,8 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+#[cfg(feature = "ladybug")]
+use std::sync::Arc;
 use thiserror::Error;
 
 // Wire opcode constants — from ladybug-contract when available, legacy_dto otherwise.
@@ -33,6 +35,8 @@ use thiserror::Error;
 use ladybug_contract::wire::{self, CogPacket};
 #[cfg(feature = "ladybug")]
 use ladybug_contract::container::Container;
+#[cfg(feature = "ladybug")]
+use crate::packet_capture::PacketCapture;
 
 // Standalone wire opcode constants for register_defaults()
 #[cfg(not(feature = "ladybug"))]
@@ -180,6 +184,9 @@ pub struct InterfaceGateway {
     by_protocol: HashMap<String, Vec<String>>,
     /// Interface IDs grouped by tag.
     by_tag: HashMap<String, Vec<String>>,
+    /// Capture of routed packets, when enabled.
+    #[cfg(feature = "ladybug")]
+    capture: Option<Arc<PacketCapture>>,
 }
 
 impl InterfaceGateway {
@@ -189,9 +196,24 @@ impl InterfaceGateway {
             interfaces: HashMap::new(),
             by_protocol: HashMap::new(),
             by_tag: HashMap::new(),
+            #[cfg(feature = "ladybug")]
+            capture: None,
         }
     }
 
+    /// Record every packet built by [`route_to_packet`](Self::route_to_packet)
+    /// in `capture`.
+    #[cfg(feature = "ladybug")]
+    pub fn set_capture(&mut self, capture: Arc<PacketCapture>) {
+        self.capture = Some(capture);
+    }
+
+    /// The packet capture, when enabled.
+    #[cfg(feature = "ladybug")]
+    pub fn capture(&self) -> Option<&Arc<PacketCapture>> {
+        self.capture.as_ref()
+    }
+
     /// Register an interface definition.
     pub fn register(&mut self, iface: InterfaceDefinition) {
         let id = iface.id.clone();
@@ -281,26 +303,26 @@ impl InterfaceGateway {
         role: &str,
         max_impact: ImpactLevel,
     ) -> Result<CogPacket, GatewayError> {
-        let iface = self
-            .interfaces
-            .get(interface_id)
-            .ok_or_else(|| GatewayError::NotFound(interface_id.to_string()))?;
-
-        if !iface.enabled {
-            return Err(GatewayError::Disabled(interface_id.to_string()));
+        let content_hash = hash_json_to_u64(payload);
+        let pkt = self.build_packet(interface_id, content_hash, role, max_impact)?;
+        if let Some(capture) = &self.capture {
+            capture.record(&pkt, Some(interface_id), Some(role), content_hash);
         }
+        Ok(pkt)
+    }
 
-        // RBAC impact gate check
-        if iface.impact > max_impact {
-            return Err(GatewayError::ImpactDenied {
-                impact: iface.impact,
-                max_allowed: max_impact,
-                role: role.to_string(),
-            });
-        }
+    /// Gate and build the packet for a payload hashing to `content_hash`.
+    #[cfg(feature = "ladybug")]
+    pub(crate) fn build_packet(
+        &self,
+        interface_id: &str,
+        content_hash: u64,
+        role: &str,
+        max_impact: ImpactLevel,
+    ) -> Result<CogPacket, GatewayError> {
+        let iface = self.validate_request(interface_id, role, max_impact)?;
 
         // Build CogPacket from interface definition
-        let content_hash = hash_json_to_u64(payload);
         let content = Container::random(content_hash);
 
         let source_addr = (iface.source_prefix as u16) << 8;
@@ -9,14 +9,28 @@
 //!
 //! Request body:  [`StepDelegationRequest`]
 //! Response body: [`StepDelegationResponse`]
+//!
+//! With batching enabled ([`LadybugRouter::with_batching`]), `lb.resonate`
+//! inputs holding an array of items are sent in batches whose size an
+//! [`AdaptiveBatcher`] tunes from the observed latency and errors: batches
+//! grow while they finish under the target latency and shrink when they
+//! take longer or fail. Failed batches are retried at the reduced size.
+//! The batcher is shared by clones of the router, so its feedback carries
+//! over between executions.
 
 use crate::types::{
     DataEnvelope, StepDelegationRequest, StepDelegationResponse, UnifiedStep,
 };
 use reqwest::Client;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tracing::{debug, error};
 
+/// Step types whose items may be split into batches.
+const BATCHED_STEP_TYPES: &[&str] = &["lb.resonate"];
+
 #[derive(Debug, Error)]
 pub enum LadybugRouterError {
     #[error("HTTP request to ladybug-rs failed: {0}")]
@@ -32,11 +46,149 @@ pub enum LadybugRouterError {
     UnknownStepType(String),
 }
 
+impl LadybugRouterError {
+    /// Whether sending the request again may succeed.
+    fn is_retryable(&self) -> bool {
+        match self {
+            LadybugRouterError::Http(_) => true,
+            LadybugRouterError::Status { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+/// Settings of adaptive batching.
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    /// Latency a batch should take.
+    pub target_latency: Duration,
+    /// Largest batch sent.
+    pub max_batch_size: usize,
+    /// Size of the first batch.
+    pub initial_batch_size: usize,
+    /// Times a failed batch is retried, at a reduced size, before the step
+    /// fails.
+    pub max_retries: u32,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            target_latency: Duration::from_millis(500),
+            max_batch_size: 256,
+            initial_batch_size: 16,
+            max_retries: 2,
+        }
+    }
+}
+
+impl BatchConfig {
+    /// Defaults overridden by `LADYBUG_BATCH_TARGET_LATENCY_MS` and
+    /// `LADYBUG_BATCH_MAX_SIZE`.
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        let mut config = Self::default();
+        if let Some(ms) = var("LADYBUG_BATCH_TARGET_LATENCY_MS") {
+            config.target_latency = Duration::from_millis(ms);
+        }
+        if let Some(size) = var("LADYBUG_BATCH_MAX_SIZE") {
+            config.max_batch_size = size.max(1) as usize;
+        }
+        config
+    }
+}
+
+/// Batch size controller driven by latency and error feedback.
+///
+/// After a successful batch the size is scaled by `target / latency`,
+/// limited to halving or doubling per batch; a failed batch halves it.
+/// The size stays between 1 and `max_batch_size`.
+#[derive(Debug)]
+pub struct AdaptiveBatcher {
+    config: BatchConfig,
+    state: Mutex<BatcherState>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BatcherState {
+    batch_size: usize,
+    /// Smoothed latency per item.
+    item_latency: Option<Duration>,
+    /// Smoothed share of failed batches.
+    error_rate: f64,
+}
+
+/// Weight of the newest observation in smoothed values.
+const SMOOTHING: f64 = 0.3;
+
+impl AdaptiveBatcher {
+    pub fn new(config: BatchConfig) -> Self {
+        let batch_size = config.initial_batch_size.clamp(1, config.max_batch_size.max(1));
+        Self {
+            config,
+            state: Mutex::new(BatcherState {
+                batch_size,
+                item_latency: None,
+                error_rate: 0.0,
+            }),
+        }
+    }
+
+    pub fn config(&self) -> &BatchConfig {
+        &self.config
+    }
+
+    /// Size of the next batch.
+    pub fn batch_size(&self) -> usize {
+        self.state().batch_size
+    }
+
+    /// Smoothed latency per item of successful batches.
+    pub fn item_latency(&self) -> Option<Duration> {
+        self.state().item_latency
+    }
+
+    /// Smoothed share of failed batches.
+    pub fn error_rate(&self) -> f64 {
+        self.state().error_rate
+    }
+
+    /// Record the outcome of a batch of `items` that took `latency`.
+    pub fn record(&self, items: usize, latency: Duration, success: bool) {
+        let max = self.config.max_batch_size.max(1);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let failed = if success { 0.0 } else { 1.0 };
+        state.error_rate += SMOOTHING * (failed - state.error_rate);
+        if !success {
+            state.batch_size = (state.batch_size / 2).max(1);
+            return;
+        }
+
+        let per_item = latency / items.max(1) as u32;
+        state.item_latency = Some(match state.item_latency {
+            Some(smoothed) => smoothed.mul_f64(1.0 - SMOOTHING) + per_item.mul_f64(SMOOTHING),
+            None => per_item,
+        });
+        // Only full batches say how large a batch may grow.
+        if items < state.batch_size && latency <= self.config.target_latency {
+            return;
+        }
+        let ratio = self.config.target_latency.as_secs_f64() / latency.as_secs_f64().max(1e-6);
+        let scaled = (state.batch_size as f64 * ratio.clamp(0.5, 2.0)).round() as usize;
+        state.batch_size = scaled.clamp(1, max);
+    }
+
+    fn state(&self) -> BatcherState {
+        *self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
 /// Routes `lb.*` steps to a ladybug-rs HTTP endpoint.
 #[derive(Clone)]
 pub struct LadybugRouter {
     client: Client,
     endpoint: String,
+    batcher: Option<Arc<AdaptiveBatcher>>,
 }
 
 impl LadybugRouter {
@@ -47,14 +199,32 @@ impl LadybugRouter {
         Self {
             client: Client::new(),
             endpoint: endpoint.into(),
+            batcher: None,
         }
     }
 
-    /// Create from the `LADYBUG_ENDPOINT` environment variable.
+    /// Send `lb.resonate` items in adaptively sized batches.
+    pub fn with_batching(mut self, config: BatchConfig) -> Self {
+        self.batcher = Some(Arc::new(AdaptiveBatcher::new(config)));
+        self
+    }
+
+    /// The batcher, when batching is enabled.
+    pub fn batcher(&self) -> Option<&AdaptiveBatcher> {
+        self.batcher.as_deref()
+    }
+
+    /// Create from the `LADYBUG_ENDPOINT` environment variable. Batching
+    /// is enabled when `LADYBUG_BATCHING` is `true` (see
+    /// [`BatchConfig::from_env`]).
     pub fn from_env() -> Result<Self, LadybugRouterError> {
         let endpoint =
             std::env::var("LADYBUG_ENDPOINT").map_err(|_| LadybugRouterError::NotConfigured)?;
-        Ok(Self::new(endpoint))
+        let router = Self::new(endpoint);
+        match std::env::var("LADYBUG_BATCHING").as_deref() {
+            Ok("true") | Ok("1") => Ok(router.with_batching(BatchConfig::from_env())),
+            _ => Ok(router),
+        }
     }
 
     /// Delegate a ladybug step to ladybug-rs and return the output envelope.
@@ -62,6 +232,9 @@ impl LadybugRouter {
     /// Routing:
     /// - `lb.resonate` → POST /api/v1/resonate
     /// - `lb.collapse` → POST /api/v1/collapse
+    ///
+    /// With batching enabled, the items of batched step types are sent in
+    /// batches and the outputs concatenated in order.
     pub async fn execute(
         &self,
         step: &UnifiedStep,
@@ -79,6 +252,76 @@ impl LadybugRouter {
             path
         );
 
+        match (&self.batcher, &input.data) {
+            (Some(batcher), Value::Array(items))
+                if items.len() > 1 && BATCHED_STEP_TYPES.contains(&step.step_type.as_str()) =>
+            {
+                self.execute_batched(batcher, &url, step, input, items).await
+            }
+            _ => self.send(&url, step, input).await,
+        }
+    }
+
+    /// Send `items` in batches sized by `batcher`, retrying failed batches.
+    async fn execute_batched(
+        &self,
+        batcher: &AdaptiveBatcher,
+        url: &str,
+        step: &UnifiedStep,
+        input: &DataEnvelope,
+        items: &[Value],
+    ) -> Result<StepDelegationResponse, LadybugRouterError> {
+        let mut outputs = Vec::with_capacity(items.len());
+        let mut last: Option<StepDelegationResponse> = None;
+        let mut offset = 0;
+        let mut retries = 0;
+
+        while offset < items.len() {
+            let size = batcher.batch_size().min(items.len() - offset);
+            let batch = DataEnvelope {
+                data: Value::Array(items[offset..offset + size].to_vec()),
+                metadata: input.metadata.clone(),
+            };
+            let started = Instant::now();
+            let result = self.send(url, step, &batch).await;
+            batcher.record(size, started.elapsed(), result.is_ok());
+            match result {
+                Ok(mut response) => {
+                    match std::mem::take(&mut response.output.data) {
+                        Value::Array(batch_outputs) => outputs.extend(batch_outputs),
+                        Value::Null => {}
+                        other => outputs.push(other),
+                    }
+                    last = Some(response);
+                    offset += size;
+                    retries = 0;
+                }
+                Err(e) if e.is_retryable() && retries < batcher.config().max_retries => {
+                    retries += 1;
+                    debug!(
+                        step_id = %step.step_id,
+                        offset,
+                        size,
+                        error = %e,
+                        "Retrying ladybug batch"
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut response = last.expect("at least one batch was sent");
+        response.output.data = Value::Array(outputs);
+        Ok(response)
+    }
+
+    /// POST one request to `url`.
+    async fn send(
+        &self,
+        url: &str,
+        step: &UnifiedStep,
+        input: &DataEnvelope,
+    ) -> Result<StepDelegationResponse, LadybugRouterError> {
         let request = StepDelegationRequest {
             step: step.clone(),
             input: input.clone(),
@@ -93,7 +336,7 @@ impl LadybugRouter {
 
         let resp = self
             .client
-            .post(&url)
+            .post(url)
             .json(&request)
             .send()
             .await?;
@@ -127,4 +370,41 @@ mod tests {
         let router = LadybugRouter::new("http://localhost:9090");
         assert_eq!(router.endpoint(), "http://localhost:9090");
     }
+
+    #[test]
+    fn test_batcher_follows_latency_and_errors() {
+        let batcher = AdaptiveBatcher::new(BatchConfig {
+            target_latency: Duration::from_millis(100),
+            max_batch_size: 64,
+            initial_batch_size: 8,
+            max_retries: 2,
+        });
+
+        // Fast batches grow, at most doubling each time, up to the maximum.
+        batcher.record(8, Duration::from_millis(10), true);
+        assert_eq!(batcher.batch_size(), 16);
+        for _ in 0..5 {
+            let size = batcher.batch_size();
+            batcher.record(size, Duration::from_millis(10), true);
+        }
+        assert_eq!(batcher.batch_size(), 64);
+
+        // Slow batches shrink toward the target latency.
+        batcher.record(64, Duration::from_millis(160), true);
+        assert_eq!(batcher.batch_size(), 40);
+
+        // A short final batch under target says nothing about growth.
+        batcher.record(3, Duration::from_millis(5), true);
+        assert_eq!(batcher.batch_size(), 40);
+
+        // Failures halve the size and raise the error rate.
+        batcher.record(40, Duration::from_millis(50), false);
+        assert_eq!(batcher.batch_size(), 20);
+        assert!(batcher.error_rate() > 0.0);
+        for _ in 0..10 {
+            batcher.record(1, Duration::from_millis(50), false);
+        }
+        assert_eq!(batcher.batch_size(), 1);
+        assert!(batcher.item_latency().is_some());
+    }
 }
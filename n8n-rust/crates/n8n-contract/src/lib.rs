@@ -14,6 +14,7 @@
 //! - [`ladybug_router`] — HTTP client that delegates `lb.*` steps to ladybug-rs
 //! - [`pg_store`] — (feature `postgres`) persistence of executions/steps
 //! - [`executors`] — `NodeExecutor` adapters so the n8n engine can route to crew/ladybug
+//! - `packet_capture` — (feature `ladybug`) CogPacket capture and replay for A2A debugging
 //!
 //! # Standalone vs Full Mode
 //!
@@ -31,6 +32,7 @@ pub mod ladybug_router;
 pub mod executors;
 pub mod interface_gateway;
 pub mod impact_gate;
+pub mod lint_rules;
 pub mod semantic_model;
 pub mod chat_session;
 pub mod thinking_mode;
@@ -48,6 +50,8 @@ pub mod bridge;
 pub mod wire_bridge;
 #[cfg(feature = "ladybug")]
 pub mod free_will;
+#[cfg(feature = "ladybug")]
+pub mod packet_capture;
 
 // JITSON — Cranelift JIT compilation of thinking styles and workflow hot paths.
 #[cfg(feature = "jitson")]
@@ -63,6 +67,7 @@ pub use ladybug_router::LadybugRouter;
 pub use executors::{CrewAgentExecutor, LadybugResonateExecutor, LadybugCollapseExecutor};
 pub use interface_gateway::{InterfaceGateway, InterfaceDefinition, InterfaceProtocol, ImpactLevel};
 pub use impact_gate::{ImpactGate, GateDecision, RoleDefinition};
+pub use lint_rules::{CredentialRoleRule, node_impact};
 pub use semantic_model::{
     SemanticModelRegistry, SemanticModel, SemanticEntity, SemanticField,
     AgentCapability,
@@ -76,6 +81,8 @@ pub use mcp_inbound::{McpToolRegistry, McpToolDefinition, McpToolCall, McpToolRe
 
 #[cfg(feature = "ladybug")]
 pub use free_will::{FreeWillPipeline, ModificationProposal, ModificationType, ModificationLimits};
+#[cfg(feature = "ladybug")]
+pub use packet_capture::{PacketCapture, CapturedPacket, ReplayOutcome, ReplayResult, replay};
 
 #[cfg(feature = "jitson")]
 pub use compiled_style::{CompiledStyle, CompiledStyleRegistry};
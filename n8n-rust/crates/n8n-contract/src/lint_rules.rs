@@ -0,0 +1,154 @@
+//! Lint rules that need the RBAC contract.
+//!
+//! These implement [`n8n_core::LintRule`] and are registered on top of the
+//! built-in rules with [`n8n_core::Linter::register`].
+
+use crate::impact_gate::ImpactGate;
+use crate::interface_gateway::ImpactLevel;
+use n8n_core::{Finding, LintRule, Severity};
+use n8n_workflow::{Node, NodeParameterValue, Workflow};
+use std::collections::HashMap;
+
+/// Classify the impact of running a node.
+///
+/// HTTP reads and triggers only observe; HTTP writes, crew delegation and
+/// anything else with a credential are treated as external effects.
+pub fn node_impact(node: &Node) -> ImpactLevel {
+    if node.is_trigger() {
+        return ImpactLevel::Observe;
+    }
+
+    match node.node_type.as_str() {
+        "n8n-nodes-base.httpRequest" => {
+            let method = match node.get_parameter("method") {
+                Some(NodeParameterValue::String(m)) => m.to_uppercase(),
+                _ => "GET".to_string(),
+            };
+            match method.as_str() {
+                "GET" | "HEAD" | "OPTIONS" => ImpactLevel::Observe,
+                _ => ImpactLevel::Moderate,
+            }
+        }
+        t if t.starts_with("lb.") => ImpactLevel::Internal,
+        t if t.starts_with("crew.") => ImpactLevel::Moderate,
+        _ if node.credentials.as_ref().is_some_and(|c| !c.is_empty()) => ImpactLevel::Moderate,
+        _ => ImpactLevel::Internal,
+    }
+}
+
+/// Flags credentials bound to a role that cannot authorize the node using
+/// them — typically an Observe-only role on a node that writes.
+pub struct CredentialRoleRule {
+    gate: ImpactGate,
+    /// Credential id or name → role id.
+    credential_roles: HashMap<String, String>,
+}
+
+impl CredentialRoleRule {
+    pub fn new(gate: ImpactGate, credential_roles: HashMap<String, String>) -> Self {
+        Self {
+            gate,
+            credential_roles,
+        }
+    }
+}
+
+impl LintRule for CredentialRoleRule {
+    fn id(&self) -> &str {
+        "credential-role-impact"
+    }
+
+    fn description(&self) -> &str {
+        "Credential bound to a role that cannot authorize the node's impact"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, workflow: &Workflow) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for node in workflow.nodes.iter().filter(|n| !n.disabled) {
+            let impact = node_impact(node);
+            let mut credentials: Vec<_> = node.credentials.iter().flatten().collect();
+            credentials.sort_by(|a, b| a.0.cmp(b.0));
+
+            for (credential_type, cred) in credentials {
+                let Some(role_id) = self
+                    .credential_roles
+                    .get(&cred.id)
+                    .or_else(|| self.credential_roles.get(&cred.name))
+                else {
+                    continue;
+                };
+                let Some(role) = self.gate.get_role(role_id) else {
+                    findings.push(Finding::at_node(
+                        node,
+                        format!("Credential '{}' is bound to unknown role '{}'", cred.name, role_id),
+                    ));
+                    continue;
+                };
+                if impact > role.max_impact {
+                    findings.push(
+                        Finding::at_node(
+                            node,
+                            format!(
+                                "Credential '{}' ({}) is bound to role '{}' (max {:?}) but the node's impact is {:?}",
+                                cred.name, credential_type, role.role_id, role.max_impact, impact
+                            ),
+                        )
+                        .with_parameter(format!("credentials.{}", credential_type)),
+                    );
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_core::Linter;
+    use n8n_workflow::NodeCredentialRef;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_observe_role_credential_on_write() {
+        let mut post = Node::new("Post", "n8n-nodes-base.httpRequest");
+        post.set_parameter("method", NodeParameterValue::String("POST".into()));
+        post.set_parameter("timeout", NodeParameterValue::Number(5000.0));
+        post.credentials = Some(HashMap::from([(
+            "httpHeaderAuth".to_string(),
+            NodeCredentialRef {
+                id: "7".into(),
+                name: "Read-only key".into(),
+            },
+        )]));
+
+        let mut get = post.clone();
+        get.name = "Get".into();
+        get.set_parameter("method", NodeParameterValue::String("GET".into()));
+
+        let mut wf = Workflow::new("Roles");
+        wf.add_node(post);
+        wf.add_node(get);
+
+        let mut linter = Linter::default();
+        linter.register(Arc::new(CredentialRoleRule::new(
+            ImpactGate::new(),
+            HashMap::from([("7".to_string(), "viewer".to_string())]),
+        )));
+
+        let report = linter.lint(&wf);
+        let findings: Vec<_> = report
+            .diagnostics
+            .iter()
+            .filter(|d| d.rule_id == "credential-role-impact")
+            .collect();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].node.as_deref(), Some("Post"));
+    }
+}
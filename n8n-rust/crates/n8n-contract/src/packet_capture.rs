@@ -0,0 +1,350 @@
+//! CogPacket capture and replay for A2A debugging.
+//!
+//! A [`PacketCapture`] attached to an [`InterfaceGateway`] (see
+//! [`InterfaceGateway::set_capture`]) records every packet the gateway
+//! routes: its header fields, the interface it came through and a hash of
+//! the JSON payload. Payloads themselves are never stored. Records are kept
+//! in a ring buffer of the most recent packets and, optionally, appended to
+//! a JSON-lines file.
+//!
+//! [`replay`] sends captured packets through a gateway's routing again and
+//! compares the headers it produces with the captured ones. A packet
+//! content container is seeded from the payload hash, so the hash is enough
+//! to rebuild it; differences therefore point at changed interface
+//! definitions or gates rather than at the payload.
+//!
+//! ```text
+//!   capture (ring buffer / .jsonl)  ──►  replay()  ──►  InterfaceGateway
+//!                                           │               │
+//!                                           ▼               ▼
+//!                                    ReplayOutcome  ◄──  CogPacket
+//! ```
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use ladybug_contract::wire::CogPacket;
+use serde::{Deserialize, Serialize};
+
+use crate::interface_gateway::{ImpactLevel, InterfaceGateway};
+
+/// Header fields of a CogPacket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PacketHeader {
+    pub opcode: u16,
+    pub source_addr: u16,
+    pub target_addr: u16,
+    pub flags: u32,
+    pub cycle: u64,
+    pub layer: u8,
+    pub rung: u8,
+    pub fan_out: u8,
+}
+
+impl PacketHeader {
+    pub fn of(packet: &CogPacket) -> Self {
+        Self {
+            opcode: packet.opcode(),
+            source_addr: packet.source_addr(),
+            target_addr: packet.target_addr(),
+            flags: u32::from(packet.flags()),
+            cycle: packet.cycle(),
+            layer: packet.layer(),
+            rung: packet.rung(),
+            fan_out: packet.fan_out(),
+        }
+    }
+
+    /// Names and values of the fields that differ from `other`.
+    pub fn diff(&self, other: &PacketHeader) -> Vec<HeaderDiff> {
+        let fields = [
+            ("opcode", u64::from(self.opcode), u64::from(other.opcode)),
+            ("source_addr", u64::from(self.source_addr), u64::from(other.source_addr)),
+            ("target_addr", u64::from(self.target_addr), u64::from(other.target_addr)),
+            ("flags", u64::from(self.flags), u64::from(other.flags)),
+            ("cycle", self.cycle, other.cycle),
+            ("layer", u64::from(self.layer), u64::from(other.layer)),
+            ("rung", u64::from(self.rung), u64::from(other.rung)),
+            ("fan_out", u64::from(self.fan_out), u64::from(other.fan_out)),
+        ];
+        fields
+            .into_iter()
+            .filter(|(_, a, b)| a != b)
+            .map(|(field, captured, replayed)| HeaderDiff {
+                field: field.to_string(),
+                captured,
+                replayed,
+            })
+            .collect()
+    }
+}
+
+/// A header field that differs between a captured and a replayed packet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeaderDiff {
+    pub field: String,
+    pub captured: u64,
+    pub replayed: u64,
+}
+
+/// One captured packet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturedPacket {
+    /// Position in the capture, starting at 0.
+    pub seq: u64,
+    /// Capture time, Unix milliseconds.
+    pub captured_at: i64,
+    /// Gateway interface the packet was routed through, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface_id: Option<String>,
+    /// Role the request was routed for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// Hash of the JSON payload the packet content was seeded from.
+    pub payload_hash: u64,
+    pub header: PacketHeader,
+}
+
+/// Ring buffer of captured packets, optionally mirrored to a file.
+#[derive(Debug)]
+pub struct PacketCapture {
+    capacity: usize,
+    buffer: Mutex<VecDeque<CapturedPacket>>,
+    file: Option<Mutex<LineWriter<File>>>,
+    next_seq: AtomicU64,
+}
+
+impl PacketCapture {
+    /// Capture keeping the last `capacity` packets in memory.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            buffer: Mutex::new(VecDeque::new()),
+            file: None,
+            next_seq: AtomicU64::new(0),
+        }
+    }
+
+    /// Also append every record to `path` as a line of JSON.
+    pub fn with_file(mut self, path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        self.file = Some(Mutex::new(LineWriter::new(file)));
+        Ok(self)
+    }
+
+    /// Record `packet`, built from a payload hashing to `payload_hash`.
+    pub fn record(
+        &self,
+        packet: &CogPacket,
+        interface_id: Option<&str>,
+        role: Option<&str>,
+        payload_hash: u64,
+    ) -> CapturedPacket {
+        let captured = CapturedPacket {
+            seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+            captured_at: chrono::Utc::now().timestamp_millis(),
+            interface_id: interface_id.map(str::to_string),
+            role: role.map(str::to_string),
+            payload_hash,
+            header: PacketHeader::of(packet),
+        };
+
+        if let Some(file) = &self.file {
+            let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+            let written = serde_json::to_string(&captured)
+                .map_err(std::io::Error::from)
+                .and_then(|line| writeln!(file, "{line}"));
+            if let Err(e) = written {
+                tracing::warn!(error = %e, "Failed to write packet capture");
+            }
+        }
+
+        let mut buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        if buffer.len() == self.capacity {
+            buffer.pop_front();
+        }
+        buffer.push_back(captured.clone());
+        captured
+    }
+
+    /// Captured packets still in the ring buffer, oldest first.
+    pub fn snapshot(&self) -> Vec<CapturedPacket> {
+        let buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        buffer.iter().cloned().collect()
+    }
+
+    /// Empty the ring buffer. The capture file is kept.
+    pub fn clear(&self) {
+        self.buffer.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Read a capture file written by [`PacketCapture::with_file`].
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Vec<CapturedPacket>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut packets = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            packets.push(serde_json::from_str(&line)?);
+        }
+        Ok(packets)
+    }
+}
+
+/// Result of replaying one captured packet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum ReplayResult {
+    /// The replayed header equals the captured one.
+    Matched,
+    /// The replayed header differs in these fields.
+    Diverged { fields: Vec<HeaderDiff> },
+    /// The gateway refused the packet.
+    Rejected { error: String },
+    /// The packet was not routed through a gateway interface.
+    Skipped,
+}
+
+/// Outcome of replaying one captured packet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayOutcome {
+    pub seq: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface_id: Option<String>,
+    #[serde(flatten)]
+    pub result: ReplayResult,
+}
+
+/// Route `packets` through `gateway` again with the impact ceiling
+/// `max_impact`, passing each rebuilt packet to `inject`, and report how
+/// the rebuilt headers compare with the captured ones.
+///
+/// Packets are routed under the role they were captured with. The cycle
+/// field, which the gateway does not set, is carried over from the capture.
+/// Replayed packets are not recorded by the gateway's own capture.
+pub fn replay<F>(
+    gateway: &InterfaceGateway,
+    packets: &[CapturedPacket],
+    max_impact: ImpactLevel,
+    mut inject: F,
+) -> Vec<ReplayOutcome>
+where
+    F: FnMut(&CapturedPacket, CogPacket),
+{
+    packets
+        .iter()
+        .map(|captured| {
+            let result = match &captured.interface_id {
+                None => ReplayResult::Skipped,
+                Some(interface_id) => {
+                    let role = captured.role.as_deref().unwrap_or_default();
+                    match gateway.build_packet(interface_id, captured.payload_hash, role, max_impact)
+                    {
+                        Ok(mut packet) => {
+                            packet.set_cycle(captured.header.cycle);
+                            packet.update_checksum();
+                            let fields = captured.header.diff(&PacketHeader::of(&packet));
+                            inject(captured, packet);
+                            if fields.is_empty() {
+                                ReplayResult::Matched
+                            } else {
+                                ReplayResult::Diverged { fields }
+                            }
+                        }
+                        Err(e) => ReplayResult::Rejected {
+                            error: e.to_string(),
+                        },
+                    }
+                }
+            };
+            ReplayOutcome {
+                seq: captured.seq,
+                interface_id: captured.interface_id.clone(),
+                result,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_capture_ring_buffer_and_file() {
+        let path = std::env::temp_dir().join(format!("cogpackets-{}.jsonl", uuid::Uuid::new_v4()));
+        let capture = Arc::new(PacketCapture::new(2).with_file(&path).unwrap());
+        let mut gw = InterfaceGateway::default();
+        gw.set_capture(capture.clone());
+
+        for limit in 0..3 {
+            gw.route_to_packet(
+                "rest.workflow.list",
+                &serde_json::json!({ "limit": limit }),
+                "viewer",
+                ImpactLevel::Observe,
+            )
+            .unwrap();
+        }
+
+        let buffered = capture.snapshot();
+        assert_eq!(buffered.iter().map(|p| p.seq).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(buffered[0].interface_id.as_deref(), Some("rest.workflow.list"));
+        assert_ne!(buffered[0].payload_hash, buffered[1].payload_hash);
+
+        let loaded = PacketCapture::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(&loaded[1..], &buffered[..]);
+    }
+
+    #[test]
+    fn test_replay_reports_changed_routing() {
+        let capture = Arc::new(PacketCapture::new(16));
+        let mut gw = InterfaceGateway::default();
+        gw.set_capture(capture.clone());
+        for id in ["a2a.lb.resonate", "a2a.crew.delegate"] {
+            gw.route_to_packet(id, &serde_json::json!({}), "agent_operator", ImpactLevel::Moderate)
+                .unwrap();
+        }
+        let mut packets = capture.snapshot();
+        packets.push(CapturedPacket {
+            seq: 99,
+            interface_id: None,
+            ..packets[0].clone()
+        });
+
+        // The crew interface now routes to another domain.
+        let mut changed = InterfaceGateway::default();
+        let mut crew = changed.get("a2a.crew.delegate").unwrap().clone();
+        crew.target_prefix = 0x0D;
+        changed.register(crew);
+
+        let mut injected = 0;
+        let outcomes = replay(&changed, &packets, ImpactLevel::Moderate, |_, _| injected += 1);
+        assert_eq!(injected, 2);
+        assert_eq!(outcomes[0].result, ReplayResult::Matched);
+        assert_eq!(
+            outcomes[1].result,
+            ReplayResult::Diverged {
+                fields: vec![HeaderDiff {
+                    field: "target_addr".into(),
+                    captured: 0x0C00,
+                    replayed: 0x0D00,
+                }],
+            }
+        );
+        assert_eq!(outcomes[2].result, ReplayResult::Skipped);
+
+        let outcomes = replay(&changed, &packets[..1], ImpactLevel::Observe, |_, _| {});
+        assert!(matches!(outcomes[0].result, ReplayResult::Rejected { .. }));
+        assert_eq!(capture.snapshot().len(), 2);
+    }
+}
@@ -0,0 +1,64 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use n8n_core::{decode_items, IngestParser, TransferCodec};
+use n8n_workflow::{DataObject, NodeExecutionData};
+
+/// A webhook-sized body: an array of order records.
+fn body(records: usize) -> Vec<u8> {
+    let records: Vec<_> = (0..records)
+        .map(|i| {
+            serde_json::json!({
+                "id": i,
+                "customer": {"name": format!("Customer {}", i), "email": "ada@example.com"},
+                "total": i as f64 * 1.25,
+                "paid": i % 2 == 0,
+                "lines": [{"sku": "A-1", "qty": 2}, {"sku": "B-7", "qty": 1}],
+                "note": "Leave at the front door, ring twice",
+            })
+        })
+        .collect();
+    serde_json::to_vec(&records).unwrap()
+}
+
+fn items(count: usize) -> Vec<NodeExecutionData> {
+    let records: Vec<DataObject> = serde_json::from_slice(&body(count)).unwrap();
+    records.into_iter().map(NodeExecutionData::new).collect()
+}
+
+fn bench_ingest(c: &mut Criterion) {
+    let body = body(1_000);
+    let mut group = c.benchmark_group("ingest_1k_records");
+    group.throughput(Throughput::Bytes(body.len() as u64));
+    group.bench_function("serde_json_value", |b| {
+        b.iter(|| black_box(serde_json::from_slice::<serde_json::Value>(&body).unwrap()))
+    });
+    for parser in [IngestParser::Json, IngestParser::SimdJson] {
+        if parser.is_available() {
+            group.bench_function(parser.as_str(), |b| {
+                b.iter(|| black_box(parser.parse(&body).unwrap()))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_transfer(c: &mut Criterion) {
+    let items = items(1_000);
+    let mut group = c.benchmark_group("transfer_1k_items");
+    group.throughput(Throughput::Elements(items.len() as u64));
+    for codec in [TransferCodec::Json, TransferCodec::Rkyv] {
+        if !codec.is_available() {
+            continue;
+        }
+        let encoded = codec.encode(&items).unwrap();
+        group.bench_function(format!("{}_encode", codec.as_str()), |b| {
+            b.iter(|| black_box(codec.encode(&items).unwrap()))
+        });
+        group.bench_function(format!("{}_decode", codec.as_str()), |b| {
+            b.iter(|| black_box(decode_items(&encoded).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_ingest, bench_transfer);
+criterion_main!(benches);
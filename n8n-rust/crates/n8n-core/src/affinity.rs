@@ -0,0 +1,171 @@
+//! Workflow affinity for queue mode.
+//!
+//! [`AffinityRouter`] maps workflow IDs to workers with a consistent hash
+//! ring, so executions of one workflow keep landing on the same worker and
+//! find its caches and static data warm. Each worker is placed on the ring
+//! `virtual_nodes` times; a workflow belongs to the first worker clockwise
+//! of its hash. When a worker joins or leaves only the workflows on its
+//! ring segments move, roughly `1 / workers` of them.
+//!
+//! Affinity is a preference: [`AffinityRouter::route_where`] walks on to
+//! the next workers on the ring when the preferred one cannot take the job,
+//! e.g. because it is at its concurrency limit.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Workflow affinity settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AffinityConfig {
+    pub enabled: bool,
+    /// Ring positions per worker; more spread workflows more evenly.
+    pub virtual_nodes: usize,
+}
+
+impl Default for AffinityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            virtual_nodes: 64,
+        }
+    }
+}
+
+/// Consistent hash ring over worker IDs.
+#[derive(Debug, Clone)]
+pub struct AffinityRouter {
+    virtual_nodes: usize,
+    ring: BTreeMap<u64, String>,
+    workers: BTreeSet<String>,
+}
+
+impl AffinityRouter {
+    pub fn new(virtual_nodes: usize) -> Self {
+        Self {
+            virtual_nodes: virtual_nodes.max(1),
+            ring: BTreeMap::new(),
+            workers: BTreeSet::new(),
+        }
+    }
+
+    pub fn from_config(config: &AffinityConfig) -> Self {
+        Self::new(config.virtual_nodes)
+    }
+
+    /// Add a worker. Returns `false` if it was already on the ring.
+    pub fn join(&mut self, worker: &str) -> bool {
+        if !self.workers.insert(worker.to_string()) {
+            return false;
+        }
+        for replica in 0..self.virtual_nodes {
+            self.ring.insert(hash(&format!("{}#{}", worker, replica)), worker.to_string());
+        }
+        true
+    }
+
+    /// Remove a worker. Returns `false` if it was not on the ring.
+    pub fn leave(&mut self, worker: &str) -> bool {
+        if !self.workers.remove(worker) {
+            return false;
+        }
+        self.ring.retain(|_, w| w != worker);
+        true
+    }
+
+    /// Make the ring match the live `workers`, e.g. after a heartbeat
+    /// sweep. Returns how many workers joined or left.
+    pub fn sync<'a>(&mut self, workers: impl IntoIterator<Item = &'a str>) -> usize {
+        let live: BTreeSet<String> = workers.into_iter().map(str::to_string).collect();
+        let gone: Vec<String> = self.workers.difference(&live).cloned().collect();
+        let mut changed = 0;
+        for worker in &gone {
+            changed += usize::from(self.leave(worker));
+        }
+        for worker in &live {
+            changed += usize::from(self.join(worker));
+        }
+        changed
+    }
+
+    pub fn workers(&self) -> impl Iterator<Item = &str> {
+        self.workers.iter().map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.workers.is_empty()
+    }
+
+    /// Preferred worker for `workflow_id`.
+    pub fn route(&self, workflow_id: &str) -> Option<&str> {
+        self.route_where(workflow_id, |_| true)
+    }
+
+    /// First worker on the ring from `workflow_id`'s position that
+    /// `accept`s the job, trying each worker once.
+    pub fn route_where(&self, workflow_id: &str, accept: impl Fn(&str) -> bool) -> Option<&str> {
+        let start = hash(workflow_id);
+        let mut tried = BTreeSet::new();
+        for worker in self.ring.range(start..).chain(self.ring.range(..start)).map(|(_, w)| w) {
+            if tried.len() == self.workers.len() {
+                break;
+            }
+            if tried.insert(worker.as_str()) && accept(worker) {
+                return Some(worker);
+            }
+        }
+        None
+    }
+}
+
+/// Stable ring position of `key`, the same on every process.
+fn hash(key: &str) -> u64 {
+    let digest = Sha256::digest(key.as_bytes());
+    u64::from_be_bytes(digest[..8].try_into().expect("digest has 32 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router(workers: &[&str]) -> AffinityRouter {
+        let mut router = AffinityRouter::new(64);
+        router.sync(workers.iter().copied());
+        router
+    }
+
+    #[test]
+    fn test_leave_only_moves_departed_workers_workflows() {
+        let before = router(&["w1", "w2", "w3", "w4"]);
+        let mut after = before.clone();
+        assert!(after.leave("w3"));
+
+        let workflows: Vec<String> = (0..400).map(|i| format!("wf-{}", i)).collect();
+        let mut moved = 0;
+        for id in &workflows {
+            let (old, new) = (before.route(id).unwrap(), after.route(id).unwrap());
+            if old != new {
+                assert_eq!(old, "w3");
+                moved += 1;
+            }
+        }
+        // About a quarter of the workflows lived on w3.
+        assert!(moved > 50 && moved < 150, "moved {}", moved);
+
+        // Joining again restores the original placement.
+        after.join("w3");
+        assert!(workflows.iter().all(|id| before.route(id) == after.route(id)));
+    }
+
+    #[test]
+    fn test_route_where_falls_back_to_next_worker() {
+        let router = router(&["w1", "w2", "w3"]);
+        let preferred = router.route("orders").unwrap().to_string();
+
+        let fallback = router.route_where("orders", |w| w != preferred).unwrap();
+        assert_ne!(fallback, preferred);
+        assert_eq!(router.route_where("orders", |_| false), None);
+        assert_eq!(AffinityRouter::new(8).route("orders"), None);
+    }
+}
@@ -0,0 +1,503 @@
+//! Binary data storage.
+//!
+//! Nodes that download files put the bytes on an item's `binary` map as
+//! base64, which makes every copy of the item, every saved execution and
+//! every event as large as the file. With a [`BinaryDataStore`] set on the
+//! engine's [`BinaryDataService`], inline binary data of each node's output
+//! is written to the store and replaced by a reference
+//! ([`BinaryData::reference`]); nodes that need the bytes read them back
+//! with [`BinaryDataService::bytes`].
+//!
+//! Stores are kept in memory ([`MemoryBinaryStore`]), on a filesystem
+//! ([`FileSystemBinaryStore`]) or in an S3-compatible bucket
+//! ([`S3BinaryStore`]). Keys are
+//! `workflows/{workflowId}/executions/{executionId}/binary_data/{uuid}`, the
+//! layout the garbage collector sweeps for orphans.
+//!
+//! With [`BinaryDataService::set_deduplicate`], payloads are stored by
+//! content instead, at `blobs/sha256/{digest}` ([`blob_key`]): identical
+//! files of any execution share one blob, which is written once. Blobs are
+//! shared, so they are never deleted through the service; the database
+//! counts their references and the garbage collector removes the ones no
+//! execution refers to any more.
+
+use crate::sigv4::{AwsCredentialsProvider, SigV4Signer};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use n8n_workflow::{BinaryData, NodeExecutionData};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Binary data storage errors.
+#[derive(Error, Debug)]
+pub enum BinaryDataError {
+    #[error("Binary data not found: {0}")]
+    NotFound(String),
+
+    #[error("Invalid binary data reference: {0}")]
+    InvalidReference(String),
+
+    #[error("Binary data is not valid base64: {0}")]
+    Decode(String),
+
+    #[error("Binary data storage failed: {0}")]
+    Storage(String),
+}
+
+/// Where binary data is kept, by key.
+#[async_trait]
+pub trait BinaryDataStore: Send + Sync {
+    /// Mode recorded in references, e.g. `filesystem`.
+    fn mode(&self) -> &'static str;
+
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), BinaryDataError>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, BinaryDataError>;
+
+    /// Remove `key`; removing a missing key is not an error.
+    async fn delete(&self, key: &str) -> Result<(), BinaryDataError>;
+
+    /// Whether `key` is stored. The default reads it.
+    async fn exists(&self, key: &str) -> Result<bool, BinaryDataError> {
+        match self.get(key).await {
+            Ok(_) => Ok(true),
+            Err(BinaryDataError::NotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Key of a new binary payload of an execution.
+pub fn binary_data_key(workflow_id: &str, execution_id: &str) -> String {
+    format!(
+        "workflows/{}/executions/{}/binary_data/{}",
+        workflow_id,
+        execution_id,
+        uuid::Uuid::new_v4()
+    )
+}
+
+/// Prefix of content-addressed keys.
+pub const BLOB_KEY_PREFIX: &str = "blobs/sha256/";
+
+/// Content-addressed key of `bytes`: `blobs/sha256/{digest}`.
+pub fn blob_key(bytes: &[u8]) -> String {
+    format!("{}{:x}", BLOB_KEY_PREFIX, Sha256::digest(bytes))
+}
+
+/// The engine's binary data store. Clones share it; none is set by
+/// default, which keeps binary data inline.
+#[derive(Clone, Default)]
+pub struct BinaryDataService {
+    store: Arc<RwLock<Option<Arc<dyn BinaryDataStore>>>>,
+    deduplicate: Arc<AtomicBool>,
+}
+
+impl BinaryDataService {
+    /// Store binary data in `store` from now on.
+    pub fn set_store(&self, store: Arc<dyn BinaryDataStore>) {
+        *self.store.write() = Some(store);
+    }
+
+    pub fn store(&self) -> Option<Arc<dyn BinaryDataStore>> {
+        self.store.read().clone()
+    }
+
+    /// Store payloads by content ([`blob_key`]) from now on, writing each
+    /// distinct payload once.
+    pub fn set_deduplicate(&self, deduplicate: bool) {
+        self.deduplicate.store(deduplicate, Ordering::Relaxed);
+    }
+
+    pub fn is_deduplicating(&self) -> bool {
+        self.deduplicate.load(Ordering::Relaxed)
+    }
+
+    /// Move inline binary data of `items` to the store, leaving
+    /// references. Returns how many payloads were moved; without a store
+    /// nothing is.
+    pub async fn offload(
+        &self,
+        workflow_id: &str,
+        execution_id: &str,
+        items: &mut [NodeExecutionData],
+    ) -> Result<usize, BinaryDataError> {
+        let Some(store) = self.store() else {
+            return Ok(0);
+        };
+        let deduplicate = self.is_deduplicating();
+        let mut moved = 0;
+        for binary in items.iter_mut().filter_map(|item| item.binary.as_mut()) {
+            for data in binary.values_mut().filter(|data| !data.is_stored()) {
+                let bytes = BASE64
+                    .decode(&data.data)
+                    .map_err(|e| BinaryDataError::Decode(e.to_string()))?;
+                if data.bytes.is_none() {
+                    data.bytes = Some(bytes.len() as u64);
+                }
+                let key = if deduplicate {
+                    blob_key(&bytes)
+                } else {
+                    binary_data_key(workflow_id, execution_id)
+                };
+                if !deduplicate || !store.exists(&key).await? {
+                    store.put(&key, bytes).await?;
+                }
+                data.id = Some(format!("{}:{}", store.mode(), key));
+                data.data = store.mode().to_string();
+                moved += 1;
+            }
+        }
+        Ok(moved)
+    }
+
+    /// The bytes of `data`, inline or stored.
+    pub async fn bytes(&self, data: &BinaryData) -> Result<Vec<u8>, BinaryDataError> {
+        let Some((mode, key)) = data.reference() else {
+            if let Some(id) = &data.id {
+                return Err(BinaryDataError::InvalidReference(id.clone()));
+            }
+            return BASE64
+                .decode(&data.data)
+                .map_err(|e| BinaryDataError::Decode(e.to_string()));
+        };
+        self.store_for(mode)?.get(key).await
+    }
+
+    /// Remove the stored bytes of `data`, if stored. Content-addressed
+    /// blobs may be shared and are left to the garbage collector.
+    pub async fn delete(&self, data: &BinaryData) -> Result<(), BinaryDataError> {
+        match data.reference() {
+            Some((_, key)) if key.starts_with(BLOB_KEY_PREFIX) => Ok(()),
+            Some((mode, key)) => self.store_for(mode)?.delete(key).await,
+            None => Ok(()),
+        }
+    }
+
+    fn store_for(&self, mode: &str) -> Result<Arc<dyn BinaryDataStore>, BinaryDataError> {
+        self.store().filter(|store| store.mode() == mode).ok_or_else(|| {
+            BinaryDataError::InvalidReference(format!("no '{}' store is configured", mode))
+        })
+    }
+}
+
+/// Binary data kept in process memory, lost on restart.
+#[derive(Default)]
+pub struct MemoryBinaryStore {
+    entries: RwLock<HashMap<String, Vec<u8>>>,
+}
+
+#[async_trait]
+impl BinaryDataStore for MemoryBinaryStore {
+    fn mode(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), BinaryDataError> {
+        self.entries.write().insert(key.to_string(), bytes);
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, BinaryDataError> {
+        self.entries
+            .read()
+            .get(key)
+            .cloned()
+            .ok_or_else(|| BinaryDataError::NotFound(key.to_string()))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BinaryDataError> {
+        self.entries.write().remove(key);
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, BinaryDataError> {
+        Ok(self.entries.read().contains_key(key))
+    }
+}
+
+/// Binary data in files under a root directory.
+pub struct FileSystemBinaryStore {
+    root: PathBuf,
+}
+
+impl FileSystemBinaryStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Path of `key`, which must stay below the root.
+    fn path(&self, key: &str) -> Result<PathBuf, BinaryDataError> {
+        let relative = Path::new(key);
+        let inside = relative.components().all(|c| matches!(c, Component::Normal(_)));
+        if key.is_empty() || !inside {
+            return Err(BinaryDataError::InvalidReference(key.to_string()));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+fn io_error(key: &str, e: std::io::Error) -> BinaryDataError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        BinaryDataError::NotFound(key.to_string())
+    } else {
+        BinaryDataError::Storage(format!("{}: {}", key, e))
+    }
+}
+
+#[async_trait]
+impl BinaryDataStore for FileSystemBinaryStore {
+    fn mode(&self) -> &'static str {
+        "filesystem"
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), BinaryDataError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| io_error(key, e))?;
+        }
+        tokio::fs::write(&path, bytes).await.map_err(|e| io_error(key, e))
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, BinaryDataError> {
+        tokio::fs::read(self.path(key)?).await.map_err(|e| io_error(key, e))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BinaryDataError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(key, e)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, BinaryDataError> {
+        tokio::fs::try_exists(self.path(key)?).await.map_err(|e| io_error(key, e))
+    }
+}
+
+/// Bucket of an [`S3BinaryStore`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3StorageConfig {
+    pub bucket: String,
+    pub region: String,
+    /// Endpoint of an S3-compatible store (e.g. `http://localhost:9000`),
+    /// addressed path-style; unset uses AWS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+/// Binary data as objects in an S3-compatible bucket, signed with SigV4.
+pub struct S3BinaryStore {
+    client: reqwest::Client,
+    config: S3StorageConfig,
+    signer: SigV4Signer,
+    credentials: Arc<AwsCredentialsProvider>,
+}
+
+impl S3BinaryStore {
+    pub fn new(config: S3StorageConfig, credentials: Arc<AwsCredentialsProvider>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            signer: SigV4Signer::new(&config.region, "s3"),
+            config,
+            credentials,
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        match &self.config.endpoint {
+            Some(endpoint) => {
+                let endpoint = endpoint.trim_end_matches('/');
+                let scheme = if endpoint.contains("://") { "" } else { "https://" };
+                format!("{}{}/{}/{}", scheme, endpoint, self.config.bucket, key)
+            }
+            None => format!(
+                "https://{}.s3.{}.amazonaws.com/{}",
+                self.config.bucket, self.config.region, key
+            ),
+        }
+    }
+
+    async fn send(
+        &self,
+        key: &str,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, BinaryDataError> {
+        let error = |e: String| BinaryDataError::Storage(format!("{}: {}", key, e));
+        let mut request = request.build().map_err(|e| error(e.to_string()))?;
+        let credentials = self.credentials.credentials().await.map_err(|e| error(e.to_string()))?;
+        self.signer
+            .sign(&mut request, &credentials)
+            .map_err(|e| error(e.to_string()))?;
+        let response = self.client.execute(request).await.map_err(|e| error(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(BinaryDataError::NotFound(key.to_string()));
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(error(format!("{} {}", status, body)));
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl BinaryDataStore for S3BinaryStore {
+    fn mode(&self) -> &'static str {
+        "s3"
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), BinaryDataError> {
+        self.send(key, self.client.put(self.url(key)).body(bytes)).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, BinaryDataError> {
+        let response = self.send(key, self.client.get(self.url(key))).await?;
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| BinaryDataError::Storage(format!("{}: {}", key, e)))?;
+        Ok(bytes.to_vec())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BinaryDataError> {
+        match self.send(key, self.client.delete(self.url(key))).await {
+            Err(BinaryDataError::NotFound(_)) | Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, BinaryDataError> {
+        match self.send(key, self.client.head(self.url(key))).await {
+            Ok(_) => Ok(true),
+            Err(BinaryDataError::NotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item_with_file(contents: &[u8]) -> NodeExecutionData {
+        NodeExecutionData::default().with_binary(
+            "data",
+            BinaryData {
+                data: BASE64.encode(contents),
+                mime_type: "text/plain".to_string(),
+                file_name: Some("hello.txt".to_string()),
+                file_extension: None,
+                file_size: None,
+                bytes: None,
+                id: None,
+                file_type: None,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_offload_leaves_references_and_reads_back() {
+        let service = BinaryDataService::default();
+        let mut items = vec![item_with_file(b"hello")];
+        assert_eq!(service.offload("wf", "ex", &mut items).await.unwrap(), 0);
+        assert!(!items[0].binary.as_ref().unwrap()["data"].is_stored());
+
+        service.set_store(Arc::new(MemoryBinaryStore::default()));
+        assert_eq!(service.offload("wf", "ex", &mut items).await.unwrap(), 1);
+        let data = items[0].binary.as_ref().unwrap()["data"].clone();
+        let (mode, key) = data.reference().unwrap();
+        assert_eq!(mode, "memory");
+        assert!(key.starts_with("workflows/wf/executions/ex/binary_data/"));
+        assert_eq!(data.data, "memory");
+        assert_eq!(data.bytes, Some(5));
+        assert_eq!(service.bytes(&data).await.unwrap(), b"hello");
+
+        // Already stored data is left alone
+        assert_eq!(service.offload("wf", "ex", &mut items).await.unwrap(), 0);
+        service.delete(&data).await.unwrap();
+        assert!(matches!(service.bytes(&data).await, Err(BinaryDataError::NotFound(_))));
+    }
+
+    /// Counts writes to a [`MemoryBinaryStore`].
+    #[derive(Default)]
+    struct CountingStore {
+        inner: MemoryBinaryStore,
+        puts: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl BinaryDataStore for CountingStore {
+        fn mode(&self) -> &'static str {
+            "memory"
+        }
+
+        async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), BinaryDataError> {
+            self.puts.fetch_add(1, Ordering::Relaxed);
+            self.inner.put(key, bytes).await
+        }
+
+        async fn get(&self, key: &str) -> Result<Vec<u8>, BinaryDataError> {
+            self.inner.get(key).await
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), BinaryDataError> {
+            self.inner.delete(key).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deduplicated_payloads_share_one_blob() {
+        let store = Arc::new(CountingStore::default());
+        let service = BinaryDataService::default();
+        service.set_store(store.clone());
+        service.set_deduplicate(true);
+
+        let mut first = vec![item_with_file(b"report"), item_with_file(b"other")];
+        let mut second = vec![item_with_file(b"report")];
+        assert_eq!(service.offload("wf-1", "ex-1", &mut first).await.unwrap(), 2);
+        assert_eq!(service.offload("wf-2", "ex-2", &mut second).await.unwrap(), 1);
+        assert_eq!(store.puts.load(Ordering::Relaxed), 2);
+
+        let a = first[0].binary.as_ref().unwrap()["data"].clone();
+        let b = second[0].binary.as_ref().unwrap()["data"].clone();
+        assert_eq!(a.id, b.id);
+        assert_eq!(a.reference().unwrap().1, blob_key(b"report"));
+        assert_eq!(service.bytes(&b).await.unwrap(), b"report");
+
+        // Shared blobs outlive deletes through the service
+        service.delete(&a).await.unwrap();
+        assert_eq!(service.bytes(&b).await.unwrap(), b"report");
+    }
+
+    #[tokio::test]
+    async fn test_filesystem_store_keeps_keys_below_root() {
+        let root = std::env::temp_dir().join(format!("n8n-binary-{}", uuid::Uuid::new_v4()));
+        let store = FileSystemBinaryStore::new(&root);
+        let key = binary_data_key("wf", "ex");
+
+        store.put(&key, b"bytes".to_vec()).await.unwrap();
+        assert_eq!(store.get(&key).await.unwrap(), b"bytes");
+        assert!(root.join(&key).is_file());
+        store.delete(&key).await.unwrap();
+        store.delete(&key).await.unwrap();
+        assert!(matches!(store.get(&key).await, Err(BinaryDataError::NotFound(_))));
+
+        for key in ["../escape", "/etc/passwd", "a/../../b", ""] {
+            assert!(matches!(
+                store.put(key, Vec::new()).await,
+                Err(BinaryDataError::InvalidReference(_))
+            ));
+        }
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,234 @@
+//! Workflow cloning with credential and variable remapping.
+//!
+//! [`clone_workflow`] deep-copies a workflow, typically a template, for a
+//! new owner. The copy gets new workflow, node and webhook IDs, is
+//! inactive, and keeps its nodes, connections, settings and pinned data;
+//! static data is not copied. Node names are unchanged, so connections and
+//! `$('Node')` references keep working.
+//!
+//! Credentials are remapped by the source credential's ID, or failing
+//! that its name. Variables are remapped by rewriting `$vars.NAME`
+//! references in node parameters. Credentials without a mapping keep
+//! pointing at the template's and are listed in the [`CloneReport`].
+
+use chrono::Utc;
+use n8n_workflow::{NodeCredentialRef, NodeParameterValue, Workflow};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// How to clone a workflow.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CloneOptions {
+    /// Name of the copy; defaults to the source name plus `name_suffix`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Appended to the source name when `name` is unset.
+    pub name_suffix: String,
+    /// Source credential ID (or name) to the credential the copy uses.
+    pub credentials: HashMap<String, NodeCredentialRef>,
+    /// Source variable name to the variable the copy uses.
+    pub variables: HashMap<String, String>,
+}
+
+impl Default for CloneOptions {
+    fn default() -> Self {
+        Self {
+            name: None,
+            name_suffix: " (copy)".to_string(),
+            credentials: HashMap::new(),
+            variables: HashMap::new(),
+        }
+    }
+}
+
+/// What [`clone_workflow`] remapped.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneReport {
+    /// Node credential references pointed at a new credential.
+    pub remapped_credentials: usize,
+    /// `$vars` references rewritten.
+    pub remapped_variables: usize,
+    /// Source credential IDs with no mapping, still used by the copy.
+    pub unmapped_credentials: Vec<String>,
+}
+
+/// Deep-copy `source` according to `options`.
+pub fn clone_workflow(source: &Workflow, options: &CloneOptions) -> (Workflow, CloneReport) {
+    let mut report = CloneReport::default();
+    let now = Utc::now();
+
+    let mut workflow = source.clone();
+    workflow.id = Uuid::new_v4().to_string();
+    workflow.name = options
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("{}{}", source.name, options.name_suffix));
+    workflow.active = false;
+    workflow.static_data = None;
+    workflow.version_id = None;
+    workflow.created_at = Some(now);
+    workflow.updated_at = Some(now);
+
+    for node in &mut workflow.nodes {
+        node.id = Uuid::new_v4().to_string();
+
+        // Webhook paths default to the webhook ID; a shared path would
+        // collide once both workflows are active.
+        if let Some(old_id) = node.webhook_id.replace(Uuid::new_v4().to_string()) {
+            if let Some(NodeParameterValue::String(path)) = node.parameters.get_mut("path") {
+                if *path == old_id {
+                    *path = node.webhook_id.clone().unwrap_or_default();
+                }
+            }
+        }
+
+        for credential in node.credentials.iter_mut().flat_map(|c| c.values_mut()) {
+            let target = options
+                .credentials
+                .get(&credential.id)
+                .or_else(|| options.credentials.get(&credential.name));
+            match target {
+                Some(target) => {
+                    *credential = target.clone();
+                    report.remapped_credentials += 1;
+                }
+                None if !report.unmapped_credentials.contains(&credential.id) => {
+                    report.unmapped_credentials.push(credential.id.clone());
+                }
+                None => {}
+            }
+        }
+
+        if !options.variables.is_empty() {
+            for value in node.parameters.values_mut() {
+                report.remapped_variables += remap_variables(value, &options.variables);
+            }
+        }
+    }
+
+    report.unmapped_credentials.sort();
+    (workflow, report)
+}
+
+/// Rewrite `$vars.NAME` references in `value`; returns how many changed.
+fn remap_variables(value: &mut NodeParameterValue, variables: &HashMap<String, String>) -> usize {
+    match value {
+        NodeParameterValue::String(s) | NodeParameterValue::Expression(s) => {
+            let (rewritten, count) = rewrite_vars(s, variables);
+            if count > 0 {
+                *s = rewritten;
+            }
+            count
+        }
+        NodeParameterValue::Array(items) => {
+            items.iter_mut().map(|v| remap_variables(v, variables)).sum()
+        }
+        NodeParameterValue::Object(map) => {
+            map.values_mut().map(|v| remap_variables(v, variables)).sum()
+        }
+        NodeParameterValue::Number(_) | NodeParameterValue::Boolean(_) => 0,
+    }
+}
+
+fn rewrite_vars(text: &str, variables: &HashMap<String, String>) -> (String, usize) {
+    const PREFIX: &str = "$vars.";
+    let mut out = String::with_capacity(text.len());
+    let mut count = 0;
+    let mut rest = text;
+
+    while let Some(pos) = rest.find(PREFIX) {
+        let (before, after) = rest.split_at(pos + PREFIX.len());
+        out.push_str(before);
+        let end = after
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+            .unwrap_or(after.len());
+        let (name, tail) = after.split_at(end);
+        match variables.get(name) {
+            Some(new_name) => {
+                out.push_str(new_name);
+                count += 1;
+            }
+            None => out.push_str(name),
+        }
+        rest = tail;
+    }
+    out.push_str(rest);
+    (out, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::Node;
+
+    #[test]
+    fn test_clone_remaps_credentials_and_variables() {
+        let mut node = Node::new("API", "n8n-nodes-base.httpRequest");
+        node.credentials = Some(HashMap::from([
+            (
+                "httpHeaderAuth".to_string(),
+                NodeCredentialRef { id: "tpl-cred".to_string(), name: "Template".to_string() },
+            ),
+            (
+                "oAuth2Api".to_string(),
+                NodeCredentialRef { id: "other".to_string(), name: "Other".to_string() },
+            ),
+        ]));
+        let url = "={{ $vars.BASE_URL }}/v1?key={{ $vars.BASE_URL_KEY }}";
+        node.parameters.insert("url".to_string(), NodeParameterValue::String(url.into()));
+        let mut source = Workflow::new("Customer template");
+        source.active = true;
+        source.nodes.push(node);
+
+        let options = CloneOptions {
+            credentials: HashMap::from([(
+                "tpl-cred".to_string(),
+                NodeCredentialRef { id: "acme-cred".to_string(), name: "Acme".to_string() },
+            )]),
+            variables: HashMap::from([("BASE_URL".to_string(), "ACME_URL".to_string())]),
+            ..Default::default()
+        };
+        let (copy, report) = clone_workflow(&source, &options);
+
+        assert_ne!(copy.id, source.id);
+        assert_ne!(copy.nodes[0].id, source.nodes[0].id);
+        assert_eq!(copy.name, "Customer template (copy)");
+        assert!(!copy.active);
+
+        let credentials = copy.nodes[0].credentials.as_ref().unwrap();
+        assert_eq!(credentials["httpHeaderAuth"].id, "acme-cred");
+        assert_eq!(report.remapped_credentials, 1);
+        assert_eq!(report.unmapped_credentials, vec!["other".to_string()]);
+
+        // `$vars.BASE_URL_KEY` is a different variable and stays.
+        let expected = "={{ $vars.ACME_URL }}/v1?key={{ $vars.BASE_URL_KEY }}";
+        assert!(matches!(
+            &copy.nodes[0].parameters["url"],
+            NodeParameterValue::String(s) if s == expected
+        ));
+        assert_eq!(report.remapped_variables, 1);
+    }
+
+    #[test]
+    fn test_clone_regenerates_webhook_path() {
+        let mut node = Node::new("Hook", "n8n-nodes-base.webhook");
+        node.webhook_id = Some("abc".to_string());
+        node.parameters.insert("path".to_string(), NodeParameterValue::String("abc".into()));
+        let mut source = Workflow::new("hooks");
+        source.nodes.push(node);
+
+        let options = CloneOptions { name: Some("Acme hooks".to_string()), ..Default::default() };
+        let (copy, _) = clone_workflow(&source, &options);
+
+        let new_id = copy.nodes[0].webhook_id.clone().unwrap();
+        assert_ne!(new_id, "abc");
+        assert!(matches!(
+            &copy.nodes[0].parameters["path"],
+            NodeParameterValue::String(p) if *p == new_id
+        ));
+        assert_eq!(copy.name, "Acme hooks");
+    }
+}
@@ -0,0 +1,238 @@
+//! Concurrency groups: executions sharing a key run one at a time.
+//!
+//! A workflow's `concurrencyGroup` setting holds a key, usually an
+//! expression such as `{{ $json.customerId }}` evaluated against the first
+//! trigger item. The engine takes the key's lock before running the
+//! workflow and holds it until the execution returns, so executions for one
+//! customer run in order while different customers run in parallel. Keys
+//! are scoped to the workflow; sub-executions are not grouped.
+//!
+//! Locks come from a [`ConcurrencyLocks`] backend: in-process by default,
+//! which only serializes executions on this instance, or one shared by all
+//! instances such as the Postgres advisory locks in `n8n-db`.
+
+use crate::error::ExecutionEngineError;
+use crate::expression::{resolve_parameter, ExpressionContext};
+use async_trait::async_trait;
+use n8n_workflow::{NodeExecutionData, Workflow};
+use parking_lot::{Mutex, RwLock};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::OwnedMutexGuard;
+
+/// Holds a group's lock; dropping it releases the lock.
+pub struct ConcurrencyGuard {
+    _held: Box<dyn Send>,
+}
+
+impl ConcurrencyGuard {
+    /// Guard that releases the lock when `held` is dropped.
+    pub fn new(held: impl Send + 'static) -> Self {
+        Self {
+            _held: Box::new(held),
+        }
+    }
+}
+
+/// Backend holding the locks of concurrency groups.
+#[async_trait]
+pub trait ConcurrencyLocks: Send + Sync {
+    /// Wait until `key` is free and take it.
+    async fn lock(&self, key: &str) -> Result<ConcurrencyGuard, String>;
+}
+
+/// In-process locks; waiters are served in arrival order.
+#[derive(Default)]
+pub struct LocalConcurrencyLocks {
+    keys: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
+}
+
+impl LocalConcurrencyLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of keys currently held or waited on.
+    pub fn active_keys(&self) -> usize {
+        self.keys.lock().len()
+    }
+}
+
+#[async_trait]
+impl ConcurrencyLocks for LocalConcurrencyLocks {
+    async fn lock(&self, key: &str) -> Result<ConcurrencyGuard, String> {
+        let mutex = self.keys.lock().entry(key.to_string()).or_default().clone();
+        let guard = mutex.lock_owned().await;
+        Ok(ConcurrencyGuard::new(LocalGuard {
+            key: key.to_string(),
+            keys: self.keys.clone(),
+            guard: Some(guard),
+        }))
+    }
+}
+
+/// Releases a local lock and forgets keys nobody holds or waits on.
+struct LocalGuard {
+    key: String,
+    keys: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl Drop for LocalGuard {
+    fn drop(&mut self) {
+        self.guard.take();
+        let mut keys = self.keys.lock();
+        // Waiters hold a clone of the mutex, so only the map's is left when
+        // nobody else wants the key.
+        if keys.get(&self.key).is_some_and(|m| Arc::strong_count(m) == 1) {
+            keys.remove(&self.key);
+        }
+    }
+}
+
+/// The engine's concurrency groups. Clones share the backend, which may be
+/// replaced once a shared one becomes available.
+#[derive(Clone)]
+pub struct ConcurrencyGroups {
+    backend: Arc<RwLock<Arc<dyn ConcurrencyLocks>>>,
+}
+
+impl Default for ConcurrencyGroups {
+    fn default() -> Self {
+        Self::new(Arc::new(LocalConcurrencyLocks::new()))
+    }
+}
+
+impl ConcurrencyGroups {
+    pub fn new(backend: Arc<dyn ConcurrencyLocks>) -> Self {
+        Self {
+            backend: Arc::new(RwLock::new(backend)),
+        }
+    }
+
+    /// Take locks from `backend` from now on. Locks already held stay with
+    /// the previous backend until released.
+    pub fn set_backend(&self, backend: Arc<dyn ConcurrencyLocks>) {
+        *self.backend.write() = backend;
+    }
+
+    /// Take the lock of the group `workflow`'s execution with `input`
+    /// belongs to, or `None` if it is not grouped.
+    pub async fn acquire(
+        &self,
+        workflow: &Workflow,
+        input: Option<&[NodeExecutionData]>,
+    ) -> Result<Option<ConcurrencyGuard>, ExecutionEngineError> {
+        let Some(settings) = &workflow.settings.concurrency_group else {
+            return Ok(None);
+        };
+        let Some(lock_key) = lock_key(workflow, input)? else {
+            return Ok(None);
+        };
+        let key = &lock_key[workflow.id.len() + 1..];
+
+        let backend = self.backend.read().clone();
+        tracing::debug!(group = %key, "Waiting for concurrency group");
+        let guard = match settings.max_wait_secs {
+            Some(secs) => tokio::time::timeout(Duration::from_secs(secs), backend.lock(&lock_key))
+                .await
+                .map_err(|_| {
+                    ExecutionEngineError::ConcurrencyGroup(format!(
+                        "Timed out after {} seconds waiting for group '{}'",
+                        secs, key
+                    ))
+                })?,
+            None => backend.lock(&lock_key).await,
+        };
+        guard.map(Some).map_err(ExecutionEngineError::ConcurrencyGroup)
+    }
+}
+
+/// Lock of the group `workflow`'s execution with `input` belongs to, scoped
+/// to the workflow, or `None` if it is not grouped.
+pub fn lock_key(
+    workflow: &Workflow,
+    input: Option<&[NodeExecutionData]>,
+) -> Result<Option<String>, ExecutionEngineError> {
+    let Some(settings) = &workflow.settings.concurrency_group else {
+        return Ok(None);
+    };
+    Ok(resolve_key(workflow, &settings.key, input)?
+        .map(|key| format!("{}:{}", workflow.id, key)))
+}
+
+/// Group key for an execution, evaluated against its first input item.
+/// Empty and null keys mean the execution is not grouped.
+pub fn resolve_key(
+    workflow: &Workflow,
+    key: &str,
+    input: Option<&[NodeExecutionData]>,
+) -> Result<Option<String>, ExecutionEngineError> {
+    let empty = NodeExecutionData::default();
+    let item = input.and_then(|items| items.first()).unwrap_or(&empty);
+    let mut context = ExpressionContext::minimal(item);
+    context.workflow_id = &workflow.id;
+    context.workflow_name = &workflow.name;
+
+    let value = resolve_parameter(&Value::String(key.to_string()), &context)
+        .map_err(|e| ExecutionEngineError::Expression(format!("Concurrency group key: {}", e)))?;
+    let key = match value {
+        Value::Null => return Ok(None),
+        Value::String(s) => s,
+        other => other.to_string(),
+    };
+    Ok((!key.is_empty()).then_some(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::ConcurrencyGroupSettings;
+
+    fn grouped(key: &str, max_wait_secs: Option<u64>) -> Workflow {
+        let mut workflow = Workflow::new("Orders");
+        workflow.settings.concurrency_group = Some(ConcurrencyGroupSettings {
+            key: key.to_string(),
+            max_wait_secs,
+        });
+        workflow
+    }
+
+    fn item(json: Value) -> Vec<NodeExecutionData> {
+        vec![NodeExecutionData::from_json_value(json).unwrap()]
+    }
+
+    #[test]
+    fn test_key_is_resolved_from_first_item() {
+        let workflow = grouped("{{ $json.customerId }}", None);
+        let resolve = |json| resolve_key(&workflow, "{{ $json.customerId }}", Some(&item(json)));
+
+        assert_eq!(resolve(serde_json::json!({"customerId": "c-1"})).unwrap(), Some("c-1".into()));
+        assert_eq!(resolve(serde_json::json!({"customerId": 7})).unwrap(), Some("7".into()));
+        assert_eq!(resolve(serde_json::json!({"customerId": ""})).unwrap(), None);
+        assert_eq!(resolve_key(&workflow, "global", None).unwrap(), Some("global".into()));
+    }
+
+    #[tokio::test]
+    async fn test_same_key_waits_while_other_keys_run() {
+        let locks = Arc::new(LocalConcurrencyLocks::new());
+        let groups = ConcurrencyGroups::new(locks.clone());
+        let workflow = grouped("{{ $json.customerId }}", Some(0));
+        let a = item(serde_json::json!({"customerId": "a"}));
+        let b = item(serde_json::json!({"customerId": "b"}));
+
+        let held = groups.acquire(&workflow, Some(&a)).await.unwrap();
+        assert!(held.is_some());
+        assert!(matches!(
+            groups.acquire(&workflow, Some(&a)).await,
+            Err(ExecutionEngineError::ConcurrencyGroup(_))
+        ));
+        assert!(groups.acquire(&workflow, Some(&b)).await.unwrap().is_some());
+
+        drop(held);
+        assert_eq!(locks.active_keys(), 0);
+        assert!(groups.acquire(&workflow, Some(&a)).await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,71 @@
+//! Correlation ids and tracing spans for execution logs.
+//!
+//! Every log line emitted while a workflow runs is inside an `execution`
+//! span (execution_id, workflow_id, correlation_id) and, while a node runs,
+//! a nested `node` span (node_name, node_type, run_index). With a JSON log
+//! formatter these fields appear on every line, so logs can be joined with
+//! execution records.
+//!
+//! The correlation id is carried in a task-local set by the transport
+//! (e.g. from an `X-Correlation-Id` header). Executions started outside a
+//! correlation scope use their execution id.
+
+use std::future::Future;
+use tracing::Span;
+
+tokio::task_local! {
+    static CORRELATION_ID: String;
+}
+
+/// Header carrying the correlation id on HTTP requests and responses.
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+/// Run `future` with the given correlation id in scope.
+pub async fn with_correlation_id<F: Future>(correlation_id: String, future: F) -> F::Output {
+    CORRELATION_ID.scope(correlation_id, future).await
+}
+
+/// Get the correlation id of the current task, if one is set.
+pub fn current_correlation_id() -> Option<String> {
+    CORRELATION_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Create the span wrapping a whole workflow execution.
+pub fn execution_span(execution_id: &str, workflow_id: &str, workflow_name: &str) -> Span {
+    let correlation_id = current_correlation_id().unwrap_or_else(|| execution_id.to_string());
+    tracing::info_span!(
+        "execution",
+        execution_id = %execution_id,
+        workflow_id = %workflow_id,
+        workflow_name = %workflow_name,
+        correlation_id = %correlation_id,
+    )
+}
+
+/// Create the span wrapping a single node run.
+pub fn node_span(node_name: &str, node_type: &str, run_index: usize) -> Span {
+    tracing::info_span!(
+        "node",
+        node_name = %node_name,
+        node_type = %node_type,
+        run_index = run_index,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_correlation_id_scope() {
+        assert_eq!(current_correlation_id(), None);
+
+        let inside = with_correlation_id("req-1".to_string(), async {
+            current_correlation_id()
+        })
+        .await;
+
+        assert_eq!(inside.as_deref(), Some("req-1"));
+        assert_eq!(current_correlation_id(), None);
+    }
+}
@@ -0,0 +1,355 @@
+//! Execution cost accounting.
+//!
+//! With [`CostConfig::enabled`], every finished task is priced and the
+//! result stored on the task's metadata under `cost`, so costs are saved
+//! with the execution (also when sampling drops node data). The run's
+//! result metadata carries the execution total.
+//!
+//! Prices come from the node type pricing table in the config:
+//!
+//! - `per_run`: charged once per node run, e.g. an HTTP call;
+//! - `per_item`: charged per output item;
+//! - `per_unit`: dotted output fields summed over items, each with a unit
+//!   price, e.g. `usage.total_tokens` for LLM nodes.
+//!
+//! Further [`CostHook`]s can be registered on the engine for costs the table
+//! cannot express. [`CostReport`] aggregates task costs by node type and
+//! unit for an execution, or across executions for a workflow.
+
+use n8n_workflow::{
+    connection::CONNECTION_MAIN, DataObject, GenericValue, Node, Run, TaskData, TaskMetadata,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Task metadata key of a task's cost.
+pub const COST_KEY: &str = "cost";
+/// Result metadata key of the execution's total cost.
+pub const COST_TOTAL_KEY: &str = "costTotal";
+/// Result metadata key of the currency costs are in.
+pub const COST_CURRENCY_KEY: &str = "costCurrency";
+
+/// Unit of [`NodeTypePricing::per_run`].
+pub const RUN_UNIT: &str = "run";
+/// Unit of [`NodeTypePricing::per_item`].
+pub const ITEM_UNIT: &str = "item";
+
+/// Cost accounting settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CostConfig {
+    pub enabled: bool,
+    /// Currency (or internal unit) all prices are in.
+    pub currency: String,
+    /// Prices by node type, e.g. `n8n-nodes-base.httpRequest`.
+    pub node_types: HashMap<String, NodeTypePricing>,
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            currency: "USD".to_string(),
+            node_types: HashMap::new(),
+        }
+    }
+}
+
+/// Prices of one node type.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NodeTypePricing {
+    pub per_run: f64,
+    pub per_item: f64,
+    /// Output field path → price per unit of its value.
+    pub per_unit: HashMap<String, f64>,
+}
+
+/// One priced quantity of a task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostLine {
+    pub unit: String,
+    pub quantity: f64,
+    pub cost: f64,
+}
+
+/// Prices node executions beyond the configured table.
+pub trait CostHook: Send + Sync {
+    fn price(&self, node: &Node, task: &TaskData) -> Vec<CostLine>;
+}
+
+impl CostHook for CostConfig {
+    fn price(&self, node: &Node, task: &TaskData) -> Vec<CostLine> {
+        let Some(pricing) = self.node_types.get(&node.node_type) else {
+            return Vec::new();
+        };
+        let items: Vec<&DataObject> = task
+            .data
+            .as_ref()
+            .and_then(|data| data.get(CONNECTION_MAIN))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|item| &item.json)
+            .collect();
+
+        let mut lines = Vec::new();
+        let mut push = |unit: &str, quantity: f64, price: f64| {
+            if quantity > 0.0 && price != 0.0 {
+                lines.push(CostLine {
+                    unit: unit.to_string(),
+                    quantity,
+                    cost: quantity * price,
+                });
+            }
+        };
+        push(RUN_UNIT, 1.0, pricing.per_run);
+        push(ITEM_UNIT, items.len() as f64, pricing.per_item);
+
+        let mut units: Vec<_> = pricing.per_unit.iter().collect();
+        units.sort_by(|a, b| a.0.cmp(b.0));
+        for (path, price) in units {
+            let quantity = items.iter().filter_map(|json| number_at(json, path)).sum();
+            push(path, quantity, *price);
+        }
+        lines
+    }
+}
+
+/// Prices tasks with the configured table and any extra hooks.
+#[derive(Clone, Default)]
+pub struct CostAccountant {
+    hooks: Vec<Arc<dyn CostHook>>,
+}
+
+impl CostAccountant {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, hook: Arc<dyn CostHook>) {
+        self.hooks.push(hook);
+    }
+
+    /// Price `task`, record its cost on the task and add it to the run's
+    /// total. Tasks without cost are left untouched.
+    pub fn account(&self, config: &CostConfig, node: &Node, run: &mut Run, task: &mut TaskData) {
+        let mut lines = config.price(node, task);
+        for hook in &self.hooks {
+            lines.extend(hook.price(node, task));
+        }
+        if lines.is_empty() {
+            return;
+        }
+
+        let total: f64 = lines.iter().map(|l| l.cost).sum();
+        let mut cost = DataObject::new();
+        cost.insert("nodeType".to_string(), GenericValue::String(node.node_type.clone()));
+        cost.insert("total".to_string(), GenericValue::Float(total));
+        cost.insert(
+            "lines".to_string(),
+            serde_json::to_value(&lines)
+                .and_then(serde_json::from_value)
+                .unwrap_or_default(),
+        );
+        task.metadata
+            .get_or_insert_with(TaskMetadata::default)
+            .custom
+            .insert(COST_KEY.to_string(), GenericValue::Object(cost));
+
+        let metadata = run.data.result_data.metadata.get_or_insert_with(HashMap::new);
+        let previous = metadata
+            .get(COST_TOTAL_KEY)
+            .and_then(|t| t.parse::<f64>().ok())
+            .unwrap_or(0.0);
+        metadata.insert(COST_TOTAL_KEY.to_string(), (previous + total).to_string());
+        metadata.insert(COST_CURRENCY_KEY.to_string(), config.currency.clone());
+    }
+}
+
+/// A priced task of a stored run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostRecord {
+    pub node: String,
+    pub node_type: String,
+    pub run_index: usize,
+    pub lines: Vec<CostLine>,
+}
+
+impl CostRecord {
+    pub fn total(&self) -> f64 {
+        self.lines.iter().map(|l| l.cost).sum()
+    }
+}
+
+/// Priced tasks of `run`, ordered by node and run index.
+pub fn cost_records(run: &Run) -> Vec<CostRecord> {
+    let mut records: Vec<CostRecord> = run
+        .data
+        .result_data
+        .run_data
+        .iter()
+        .flat_map(|(node, tasks)| {
+            tasks.iter().enumerate().filter_map(move |(run_index, task)| {
+                let cost = match task.metadata.as_ref()?.custom.get(COST_KEY)? {
+                    GenericValue::Object(cost) => cost,
+                    _ => return None,
+                };
+                let node_type = match cost.get("nodeType") {
+                    Some(GenericValue::String(t)) => t.clone(),
+                    _ => String::new(),
+                };
+                let lines = serde_json::to_value(cost.get("lines")?)
+                    .and_then(serde_json::from_value)
+                    .ok()?;
+                Some(CostRecord {
+                    node: node.clone(),
+                    node_type,
+                    run_index,
+                    lines,
+                })
+            })
+        })
+        .collect();
+    records.sort_by(|a, b| (&a.node, a.run_index).cmp(&(&b.node, b.run_index)));
+    records
+}
+
+/// Aggregated costs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostReport {
+    pub currency: String,
+    pub total: f64,
+    pub executions: usize,
+    pub by_node_type: BTreeMap<String, f64>,
+    pub by_unit: BTreeMap<String, UnitTotal>,
+}
+
+/// Quantity and cost of one unit.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UnitTotal {
+    pub quantity: f64,
+    pub cost: f64,
+}
+
+impl CostReport {
+    pub fn new(currency: impl Into<String>) -> Self {
+        Self {
+            currency: currency.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Add one execution's priced tasks.
+    pub fn add_execution(&mut self, records: &[CostRecord]) {
+        self.executions += 1;
+        for record in records {
+            *self.by_node_type.entry(record.node_type.clone()).or_default() += record.total();
+            for line in &record.lines {
+                let unit = self.by_unit.entry(line.unit.clone()).or_default();
+                unit.quantity += line.quantity;
+                unit.cost += line.cost;
+                self.total += line.cost;
+            }
+        }
+    }
+}
+
+/// Number at a dotted `path` in `json`.
+fn number_at(json: &DataObject, path: &str) -> Option<f64> {
+    let mut parts = path.split('.');
+    let mut value = json.get(parts.next()?)?;
+    for part in parts {
+        value = match value {
+            GenericValue::Object(map) => map.get(part)?,
+            _ => return None,
+        };
+    }
+    match value {
+        GenericValue::Integer(n) => Some(*n as f64),
+        GenericValue::Float(n) => Some(*n),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::{NodeExecutionData, WorkflowExecuteMode};
+
+    fn llm_task(tokens: &[i64]) -> TaskData {
+        let items = tokens
+            .iter()
+            .map(|t| {
+                NodeExecutionData::from_json_value(serde_json::json!({
+                    "usage": { "total_tokens": t }
+                }))
+                .unwrap()
+            })
+            .collect();
+        let mut task = TaskData::default();
+        task.data = Some(HashMap::from([(CONNECTION_MAIN.to_string(), vec![items])]));
+        task
+    }
+
+    fn config() -> CostConfig {
+        CostConfig {
+            enabled: true,
+            node_types: HashMap::from([(
+                "llm".to_string(),
+                NodeTypePricing {
+                    per_run: 0.01,
+                    per_unit: HashMap::from([("usage.total_tokens".to_string(), 0.001)]),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_table_prices_runs_and_units() {
+        let node = Node::new("Chat", "llm");
+        let lines = config().price(&node, &llm_task(&[100, 50]));
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].unit, RUN_UNIT);
+        assert_eq!(lines[1].unit, "usage.total_tokens");
+        assert_eq!(lines[1].quantity, 150.0);
+        assert!((lines[1].cost - 0.15).abs() < 1e-9);
+
+        assert!(config().price(&Node::new("Set", "set"), &llm_task(&[100])).is_empty());
+    }
+
+    #[test]
+    fn test_accounted_costs_aggregate() {
+        let config = config();
+        let accountant = CostAccountant::new();
+        let node = Node::new("Chat", "llm");
+        let mut run = Run::new(WorkflowExecuteMode::Manual);
+
+        for tokens in [10, 20] {
+            let mut task = llm_task(&[tokens]);
+            accountant.account(&config, &node, &mut run, &mut task);
+            run.data.result_data.run_data.entry("Chat".to_string()).or_default().push(task);
+        }
+
+        let records = cost_records(&run);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].run_index, 1);
+
+        let mut report = CostReport::new(&config.currency);
+        report.add_execution(&records);
+        assert!((report.total - 0.05).abs() < 1e-9);
+        assert_eq!(report.by_unit["usage.total_tokens"].quantity, 30.0);
+
+        let recorded: f64 = run.data.result_data.metadata.as_ref().unwrap()[COST_TOTAL_KEY]
+            .parse()
+            .unwrap();
+        assert!((recorded - report.total).abs() < 1e-9);
+    }
+}
@@ -2,7 +2,13 @@
 //!
 //! Provides AES-256-GCM encryption/decryption for credential data,
 //! matching n8n's implementation in @n8n/credentials package.
+//!
+//! Data encrypted by n8n itself (CryptoJS's AES-256-CBC, `Salted__` and an
+//! 8-byte salt in front, key and IV derived from the encryption key with
+//! OpenSSL's `EVP_BytesToKey` over MD5) is decrypted too, so credentials of
+//! an existing n8n database can be read and re-encrypted.
 
+use crate::masking::SecretMask;
 use aes_gcm::{
     aead::{Aead, KeyInit},
     Aes256Gcm, Nonce,
@@ -10,6 +16,7 @@ use aes_gcm::{
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use rand::RngCore;
 use sha2::{Digest, Sha256};
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Size of the AES-256-GCM nonce (IV) in bytes.
@@ -18,6 +25,15 @@ const NONCE_SIZE: usize = 12;
 /// Size of the AES-256 key in bytes.
 const KEY_SIZE: usize = 32;
 
+/// Prefix of data encrypted by n8n.
+const SALTED_PREFIX: &[u8] = b"Salted__";
+
+/// Size of the salt following [`SALTED_PREFIX`].
+const SALT_SIZE: usize = 8;
+
+/// Environment variable holding the encryption key, as in n8n.
+pub const ENCRYPTION_KEY_ENV: &str = "N8N_ENCRYPTION_KEY";
+
 /// Errors that can occur during credential operations.
 #[derive(Error, Debug)]
 pub enum CredentialError {
@@ -48,6 +64,10 @@ pub enum CredentialError {
 pub struct CredentialService {
     /// Derived encryption key from the master key.
     key: [u8; KEY_SIZE],
+    /// The master key, to decrypt data encrypted by n8n.
+    passphrase: Option<Arc<str>>,
+    /// Registry decrypted values are added to, so they can be masked.
+    mask: SecretMask,
 }
 
 impl CredentialService {
@@ -57,12 +77,36 @@ impl CredentialService {
     /// to produce the actual encryption key.
     pub fn new(encryption_key: &str) -> Self {
         let key = derive_key(encryption_key);
-        Self { key }
+        Self {
+            passphrase: Some(Arc::from(encryption_key)),
+            ..Self::from_key(key)
+        }
     }
 
-    /// Create a credential service from a pre-derived key (32 bytes).
+    /// Create a credential service from a pre-derived key (32 bytes). It
+    /// cannot decrypt data encrypted by n8n.
     pub fn from_key(key: [u8; KEY_SIZE]) -> Self {
-        Self { key }
+        Self {
+            key,
+            passphrase: None,
+            mask: SecretMask::global(),
+        }
+    }
+
+    /// Create a credential service with the key in [`ENCRYPTION_KEY_ENV`],
+    /// if it is set.
+    pub fn from_env() -> Option<Self> {
+        std::env::var(ENCRYPTION_KEY_ENV)
+            .ok()
+            .filter(|key| !key.is_empty())
+            .map(|key| Self::new(&key))
+    }
+
+    /// Register decrypted values in `mask` instead of the process-wide
+    /// registry.
+    pub fn with_mask(mut self, mask: SecretMask) -> Self {
+        self.mask = mask;
+        self
     }
 
     /// Encrypt credential data.
@@ -102,7 +146,9 @@ impl CredentialService {
     /// Takes a base64-encoded encrypted string and returns the decrypted JSON value.
     pub fn decrypt(&self, encrypted: &str) -> Result<serde_json::Value, CredentialError> {
         let plaintext = self.decrypt_string(encrypted)?;
-        Ok(serde_json::from_str(&plaintext)?)
+        let data = serde_json::from_str(&plaintext)?;
+        self.mask.register_json(&data);
+        Ok(data)
     }
 
     /// Decrypt a string directly.
@@ -113,6 +159,9 @@ impl CredentialService {
         // Decode base64
         let combined = BASE64.decode(encrypted)?;
 
+        if let Some(salted) = combined.strip_prefix(SALTED_PREFIX) {
+            return self.decrypt_n8n(salted);
+        }
         if combined.len() < NONCE_SIZE {
             return Err(CredentialError::InvalidFormat(
                 "Encrypted data too short".to_string(),
@@ -132,6 +181,27 @@ impl CredentialService {
             .map_err(|e| CredentialError::DecryptionError(e.to_string()))
     }
 
+    /// Decrypt data encrypted by n8n, following [`SALTED_PREFIX`].
+    fn decrypt_n8n(&self, salted: &[u8]) -> Result<String, CredentialError> {
+        use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+
+        let passphrase = self.passphrase.as_deref().ok_or_else(|| {
+            CredentialError::InvalidKey("n8n-encrypted data needs the encryption key".to_string())
+        })?;
+        if salted.len() < SALT_SIZE {
+            return Err(CredentialError::InvalidFormat("Salt missing".to_string()));
+        }
+        let (salt, ciphertext) = salted.split_at(SALT_SIZE);
+        let (key, iv) = evp_bytes_to_key(passphrase, salt);
+
+        let plaintext = cbc::Decryptor::<aes::Aes256>::new(&key.into(), &iv.into())
+            .decrypt_padded_vec_mut::<Pkcs7>(ciphertext)
+            .map_err(|e| CredentialError::DecryptionError(e.to_string()))?;
+
+        String::from_utf8(plaintext)
+            .map_err(|e| CredentialError::DecryptionError(e.to_string()))
+    }
+
     /// Re-encrypt credential data with a new key.
     ///
     /// This is useful for key rotation.
@@ -158,6 +228,25 @@ fn derive_key(key: &str) -> [u8; KEY_SIZE] {
     key_bytes
 }
 
+/// Key and IV of n8n's CryptoJS encryption: OpenSSL's `EVP_BytesToKey`
+/// with MD5 and one iteration.
+fn evp_bytes_to_key(passphrase: &str, salt: &[u8]) -> ([u8; KEY_SIZE], [u8; 16]) {
+    let mut derived = Vec::with_capacity(KEY_SIZE + 16);
+    let mut block: Vec<u8> = Vec::new();
+    while derived.len() < KEY_SIZE + 16 {
+        block.extend_from_slice(passphrase.as_bytes());
+        block.extend_from_slice(salt);
+        let digest = md5::compute(&block).0;
+        derived.extend_from_slice(&digest);
+        block = digest.to_vec();
+    }
+    let mut key = [0u8; KEY_SIZE];
+    let mut iv = [0u8; 16];
+    key.copy_from_slice(&derived[..KEY_SIZE]);
+    iv.copy_from_slice(&derived[KEY_SIZE..KEY_SIZE + 16]);
+    (key, iv)
+}
+
 /// Derive an encryption key using PBKDF2-HMAC-SHA256.
 ///
 /// This is more secure than simple hashing and matches n8n's
@@ -266,6 +355,19 @@ mod tests {
         assert_ne!(key1, key3);
     }
 
+    #[test]
+    fn test_decrypt_data_encrypted_by_n8n() {
+        // `openssl enc -aes-256-cbc -md md5 -salt`, as CryptoJS encrypts.
+        let encrypted = "U2FsdGVkX1+J1EiOgoEZVBgAUMXllizmniLpChioUDMzU9VUl0/pVszpRIi8vH8R";
+        let service = CredentialService::new("n8n-test-key");
+        let decrypted = service.decrypt(encrypted).unwrap();
+        assert_eq!(decrypted, serde_json::json!({"apiKey": "sk-legacy"}));
+
+        assert!(CredentialService::new("other-key").decrypt(encrypted).is_err());
+        let derived = CredentialService::from_key(derive_key("n8n-test-key"));
+        assert!(matches!(derived.decrypt_string(encrypted), Err(CredentialError::InvalidKey(_))));
+    }
+
     #[test]
     fn test_re_encrypt() {
         let old_service = CredentialService::new("old-key");
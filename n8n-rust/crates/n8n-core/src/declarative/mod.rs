@@ -0,0 +1,648 @@
+//! Nodes defined by n8n declarative descriptions.
+//!
+//! Many upstream nodes are pure REST descriptions: `requestDefaults` plus
+//! properties whose `routing` says how each parameter reaches the request.
+//! [`DeclarativeCatalog`] reads such descriptions from JSON (a single
+//! description, or an array like n8n's `/types/nodes.json` and
+//! `/types/credentials.json`) and registers a [`DeclarativeNode`] executor
+//! per node type, which builds and sends the requests from the routing.
+//! Programmatic nodes, whose descriptions have no routing, are skipped.
+//!
+//! Credentials are looked up through a [`CredentialResolver`]; the
+//! `generic` `authenticate` block of the credential type then adds its
+//! headers, query, body or basic auth to every request. Credential types
+//! extending `oAuth1Api` sign every request with OAuth1 instead.
+
+pub mod routing;
+
+use crate::error::ExecutionEngineError;
+use crate::executor::{NodeExecutor, NodeExecutorRegistry, NodeOutput};
+use crate::oauth1::{OAuth1Credentials, OAUTH1_CREDENTIAL_TYPE};
+use crate::runtime::RuntimeContext;
+use async_trait::async_trait;
+use n8n_workflow::{Node, NodeCredentialRef, NodeExecutionData, TaskDataConnections};
+use routing::{is_visible, parameters_with_defaults, post_receive, truthy, RequestPlan, Scope};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Package prefixed to description names without one.
+pub const DEFAULT_PACKAGE: &str = "n8n-nodes-base";
+
+/// Timeout of each request.
+const REQUEST_TIMEOUT_MS: u64 = 60_000;
+
+/// Pages fetched at most by one paginated request.
+const MAX_PAGES: usize = 1000;
+
+/// Errors of loading descriptions.
+#[derive(Error, Debug)]
+pub enum DeclarativeError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid description in {source_name}: {message}")]
+    Invalid { source_name: String, message: String },
+}
+
+/// Looks up the decrypted data of the credentials nodes reference.
+#[async_trait]
+pub trait CredentialResolver: Send + Sync {
+    /// Data of the credential `reference` of type `credential_type`.
+    async fn resolve(
+        &self,
+        credential_type: &str,
+        reference: &NodeCredentialRef,
+    ) -> Result<Value, String>;
+}
+
+/// Credential types and the resolver, shared by a catalog's nodes.
+#[derive(Default)]
+struct Shared {
+    credential_types: HashMap<String, Value>,
+    resolver: Option<Arc<dyn CredentialResolver>>,
+}
+
+/// Declarative node and credential descriptions, to be registered as
+/// executors.
+#[derive(Default)]
+pub struct DeclarativeCatalog {
+    nodes: HashMap<String, Value>,
+    shared: Shared,
+}
+
+impl DeclarativeCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve the credentials nodes reference with `resolver`. Without it,
+    /// nodes needing credentials fail.
+    pub fn with_credentials(mut self, resolver: Arc<dyn CredentialResolver>) -> Self {
+        self.shared.resolver = Some(resolver);
+        self
+    }
+
+    /// Load a `.json` file, or every `.json` file below a directory, and
+    /// return how many node and credential descriptions were added.
+    pub fn load_path(&mut self, path: &Path) -> Result<usize, DeclarativeError> {
+        let io_error = |source| DeclarativeError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        if !path.is_dir() {
+            let text = std::fs::read_to_string(path).map_err(io_error)?;
+            return self.load_str(&path.display().to_string(), &text);
+        }
+
+        let mut entries = std::fs::read_dir(path)
+            .map_err(io_error)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io_error)?;
+        entries.sort();
+        let mut loaded = 0;
+        for entry in entries {
+            if entry.is_dir() || entry.extension().is_some_and(|ext| ext == "json") {
+                loaded += self.load_path(&entry)?;
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Load descriptions from JSON text; `source_name` names it in errors.
+    pub fn load_str(&mut self, source_name: &str, text: &str) -> Result<usize, DeclarativeError> {
+        let invalid = |message: String| DeclarativeError::Invalid {
+            source_name: source_name.to_string(),
+            message,
+        };
+        let descriptions = match serde_json::from_str(text).map_err(|e| invalid(e.to_string()))? {
+            Value::Array(descriptions) => descriptions,
+            description => vec![description],
+        };
+
+        let mut loaded = 0;
+        for description in descriptions {
+            let Some(name) = description.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            let name = name.to_string();
+            let is_node = description.get("inputs").is_some()
+                || description.get("requestDefaults").is_some();
+            let is_credential =
+                description.get("authenticate").is_some() || description.get("extends").is_some();
+            if is_credential && !is_node {
+                self.shared.credential_types.insert(name, description);
+                loaded += 1;
+                continue;
+            }
+            if !is_declarative(&description) {
+                continue;
+            }
+            if !description.get("properties").is_some_and(Value::is_array) {
+                return Err(invalid(format!("Node '{}' has no properties", name)));
+            }
+
+            let node_type = if name.contains('.') {
+                name
+            } else {
+                format!("{}.{}", DEFAULT_PACKAGE, name)
+            };
+            // Versioned nodes are listed once per version; keep the newest.
+            let newer = self
+                .nodes
+                .get(&node_type)
+                .is_none_or(|known| version(&description) >= version(known));
+            if newer {
+                self.nodes.insert(node_type, description);
+            }
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    /// Node types of the loaded descriptions.
+    pub fn node_types(&self) -> impl Iterator<Item = &str> {
+        self.nodes.keys().map(String::as_str)
+    }
+
+    /// Register an executor per node type `registry` does not handle yet,
+    /// so built-in nodes keep precedence, and return how many were added.
+    pub fn register(self, registry: &mut NodeExecutorRegistry) -> usize {
+        let shared = Arc::new(self.shared);
+        let mut registered = 0;
+        for (node_type, description) in self.nodes {
+            if registry.get(&node_type).is_none() {
+                registry.register(Arc::new(DeclarativeNode {
+                    node_type,
+                    description,
+                    shared: shared.clone(),
+                }));
+                registered += 1;
+            }
+        }
+        registered
+    }
+}
+
+/// Whether a node description routes parameters into requests.
+fn is_declarative(description: &Value) -> bool {
+    fn has_routing(value: &Value) -> bool {
+        match value {
+            Value::Object(object) => {
+                object.contains_key("routing") || object.values().any(has_routing)
+            }
+            Value::Array(values) => values.iter().any(has_routing),
+            _ => false,
+        }
+    }
+    description.get("requestDefaults").is_some()
+        || description.get("properties").is_some_and(has_routing)
+}
+
+fn version(description: &Value) -> f64 {
+    match description.get("version") {
+        Some(Value::Array(versions)) => versions
+            .iter()
+            .filter_map(Value::as_f64)
+            .fold(1.0, f64::max),
+        Some(version) => version.as_f64().unwrap_or(1.0),
+        None => 1.0,
+    }
+}
+
+/// How a node's credential authenticates its requests.
+enum Authentication<'a> {
+    /// The credential type's `generic` `authenticate` block.
+    Generic(&'a Value),
+    OAuth1(OAuth1Credentials),
+}
+
+impl Shared {
+    /// Whether `credential_type` is, or extends, the OAuth1 credential.
+    fn is_oauth1(&self, credential_type: &str, depth: usize) -> bool {
+        if credential_type == OAUTH1_CREDENTIAL_TYPE {
+            return true;
+        }
+        let extends = self
+            .credential_types
+            .get(credential_type)
+            .and_then(|description| description.get("extends"))
+            .and_then(Value::as_array);
+        depth < 8
+            && extends
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .any(|parent| self.is_oauth1(parent, depth + 1))
+    }
+}
+
+/// Executor of one declarative node type.
+pub struct DeclarativeNode {
+    node_type: String,
+    description: Value,
+    shared: Arc<Shared>,
+}
+
+impl DeclarativeNode {
+    /// The description the node executes.
+    pub fn description(&self) -> &Value {
+        &self.description
+    }
+
+    fn properties(&self) -> &[Value] {
+        self.description["properties"].as_array().map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Data of the visible credential the node references, and how its
+    /// type authenticates.
+    async fn credentials(
+        &self,
+        node: &Node,
+        parameters: &Map<String, Value>,
+    ) -> Result<(Value, Option<Authentication<'_>>), String> {
+        let declared = self.description["credentials"].as_array().map(Vec::as_slice);
+        for credential in declared.unwrap_or_default() {
+            let Some(name) = credential.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            if !is_visible(credential, parameters, node.type_version) {
+                continue;
+            }
+            let reference = node.credentials.as_ref().and_then(|refs| refs.get(name));
+            let Some(reference) = reference else {
+                if credential.get("required") == Some(&Value::Bool(true)) {
+                    return Err(format!("Credentials of type '{}' are required", name));
+                }
+                continue;
+            };
+            let resolver = self.shared.resolver.as_ref().ok_or_else(|| {
+                format!("No credential resolver configured for '{}'", name)
+            })?;
+            let data = resolver.resolve(name, reference).await?;
+            let authentication = if self.shared.is_oauth1(name, 0) {
+                Some(Authentication::OAuth1(OAuth1Credentials::from_credential_data(&data)?))
+            } else {
+                self.shared
+                    .credential_types
+                    .get(name)
+                    .and_then(|credential_type| credential_type.get("authenticate"))
+                    .map(Authentication::Generic)
+            };
+            return Ok((data, authentication));
+        }
+        Ok((Value::Null, None))
+    }
+
+    /// The request `item` makes, from the defaults, the routing of the
+    /// visible properties and the credentials' authentication.
+    fn plan<'a>(
+        &self,
+        node: &Node,
+        parameters: &Map<String, Value>,
+        scope: &Scope<'a>,
+        authentication: Option<&Authentication<'_>>,
+    ) -> Result<RequestPlan<'a>, String> {
+        let mut plan = RequestPlan::default();
+        if let Some(defaults) = self.description.get("requestDefaults") {
+            plan.merge_request(defaults, scope)?;
+        }
+        for property in self.properties() {
+            let Some(name) = property.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            if let Some(value) = parameters.get(name) {
+                if is_visible(property, parameters, node.type_version) {
+                    plan.apply_property(property, value, scope)?;
+                }
+            }
+        }
+        match authentication {
+            Some(Authentication::Generic(authenticate)) => {
+                match authenticate.get("type").and_then(Value::as_str) {
+                    Some("generic") => {
+                        let properties = authenticate.get("properties").unwrap_or(&Value::Null);
+                        plan.merge_request(properties, scope)?;
+                    }
+                    other => {
+                        let other = other.unwrap_or("");
+                        return Err(format!("Unsupported authentication {:?}", other));
+                    }
+                }
+            }
+            Some(Authentication::OAuth1(credentials)) => plan.oauth1 = Some(credentials.clone()),
+            None => {}
+        }
+        Ok(plan)
+    }
+
+    /// Send `plan`'s request and return `{body, headers, statusCode}`.
+    async fn send(
+        node: &Node,
+        client: &reqwest::Client,
+        plan: &RequestPlan<'_>,
+        context: &RuntimeContext,
+    ) -> Result<Value, ExecutionEngineError> {
+        let node_error = |message: String| ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message,
+        };
+        let url = request_url(plan).map_err(node_error)?;
+        let method = plan.method.as_deref().unwrap_or("GET");
+        let method = reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|_| node_error(format!("Unsupported HTTP method: {}", method)))?;
+
+        let mut query = Vec::new();
+        for (key, value) in &plan.qs {
+            match value {
+                Value::Array(values) => query.extend(values.iter().map(|v| (key, plain(v)))),
+                Value::Null => {}
+                value => query.push((key, plain(value))),
+            }
+        }
+        let mut request = client.request(method.clone(), url).query(&query);
+        for (name, value) in &plan.headers {
+            request = request.header(name.as_str(), plain(value));
+        }
+        if let Some((username, password)) = &plan.auth {
+            request = request.basic_auth(username, Some(password));
+        }
+        let has_body = !matches!(method, reqwest::Method::GET | reqwest::Method::HEAD);
+        if has_body && !plan.body.is_empty() {
+            request = request.json(&plan.body);
+        }
+        let mut request = request
+            .build()
+            .map_err(|e| node_error(format!("Invalid request: {}", e)))?;
+        if let Some(oauth1) = &plan.oauth1 {
+            oauth1.sign(&mut request).map_err(node_error)?;
+        }
+        context
+            .egress()
+            .check(request.url())
+            .await
+            .map_err(|e| node_error(e.to_string()))?;
+
+        let cancel_token = context.cancellation_token();
+        let response = tokio::select! {
+            result = client.execute(request) => {
+                result.map_err(|e| node_error(format!("HTTP request failed: {}", e)))?
+            }
+            _ = cancel_token.cancelled() => {
+                return Err(ExecutionEngineError::Canceled);
+            }
+        };
+        let status = response.status();
+        let headers: Map<String, Value> = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((name.to_string(), Value::from(value.to_str().ok()?)))
+            })
+            .collect();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| node_error(format!("Failed to read response body: {}", e)))?;
+        if (status.is_client_error() || status.is_server_error()) && !plan.ignore_http_status_errors
+        {
+            return Err(node_error(format!(
+                "HTTP request returned status {}: {}",
+                status.as_u16(),
+                text
+            )));
+        }
+        let body = serde_json::from_str(&text).unwrap_or(Value::String(text));
+        Ok(json!({ "body": body, "headers": headers, "statusCode": status.as_u16() }))
+    }
+
+    /// Items of one page, after the `postReceive` actions.
+    fn page_items(
+        plan: &RequestPlan<'_>,
+        response: &Value,
+        root_property: Option<&str>,
+    ) -> Result<Vec<Value>, String> {
+        let body = if plan.return_full_response {
+            response.clone()
+        } else {
+            response["body"].clone()
+        };
+        let body = match root_property {
+            Some(property) => body.get(property).cloned().unwrap_or(Value::Null),
+            None => body,
+        };
+        let items = match body {
+            Value::Array(items) => items,
+            Value::Null if root_property.is_some() => Vec::new(),
+            body => vec![body],
+        };
+        post_receive(&plan.post_receive, items, response)
+    }
+
+    /// Send `plan`, following its pagination, and collect the items.
+    async fn fetch(
+        node: &Node,
+        client: &reqwest::Client,
+        mut plan: RequestPlan<'_>,
+        context: &RuntimeContext,
+    ) -> Result<Vec<Value>, ExecutionEngineError> {
+        let node_error = |message: String| ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message,
+        };
+        let Some((pagination, scope)) = plan.pagination.take() else {
+            let response = Self::send(node, client, &plan, context).await?;
+            return Self::page_items(&plan, &response, None).map_err(node_error);
+        };
+
+        let properties = pagination.get("properties").cloned().unwrap_or(Value::Null);
+        let mut items = Vec::new();
+        match pagination.get("type").and_then(Value::as_str) {
+            Some("offset") => {
+                let name = |key: &str, default: &str| {
+                    properties[key].as_str().unwrap_or(default).to_string()
+                };
+                let limit_name = name("limitParameter", "limit");
+                let offset_name = name("offsetParameter", "offset");
+                let page_size = scope.eval(&properties["pageSize"]).map_err(node_error)?;
+                let page_size = page_size.as_u64().filter(|size| *size > 0).unwrap_or(100);
+                let root_property = properties["rootProperty"].as_str();
+                let in_body = properties["type"].as_str() == Some("body");
+                for page in 0..MAX_PAGES {
+                    let target = if in_body { &mut plan.body } else { &mut plan.qs };
+                    target.insert(limit_name.clone(), Value::from(page_size));
+                    target.insert(offset_name.clone(), Value::from(page as u64 * page_size));
+                    let response = Self::send(node, client, &plan, context).await?;
+                    let page_items =
+                        Self::page_items(&plan, &response, root_property).map_err(node_error)?;
+                    let done = (page_items.len() as u64) < page_size;
+                    items.extend(page_items);
+                    if done {
+                        break;
+                    }
+                }
+            }
+            Some("generic") => {
+                for _ in 0..MAX_PAGES {
+                    let response = Self::send(node, client, &plan, context).await?;
+                    items.extend(Self::page_items(&plan, &response, None).map_err(node_error)?);
+                    let scope = scope.with("response", response);
+                    if !truthy(&scope.eval(&properties["continue"]).map_err(node_error)?) {
+                        break;
+                    }
+                    plan.merge_request(&properties["request"], &scope).map_err(node_error)?;
+                }
+            }
+            other => {
+                let message = format!("Unsupported pagination {:?}", other.unwrap_or(""));
+                return Err(node_error(message));
+            }
+        }
+        Ok(items)
+    }
+}
+
+/// `baseURL` joined with `url`, unless `url` is absolute.
+fn request_url(plan: &RequestPlan<'_>) -> Result<String, String> {
+    let url = plan.url.as_deref().unwrap_or("");
+    if url.starts_with("http://") || url.starts_with("https://") {
+        return Ok(url.to_string());
+    }
+    let base = plan.base_url.as_deref().ok_or("The request has no baseURL")?;
+    match (base.ends_with('/'), url.starts_with('/')) {
+        (true, true) => Ok(format!("{}{}", base, &url[1..])),
+        (false, false) if !url.is_empty() => Ok(format!("{}/{}", base, url)),
+        _ => Ok(format!("{}{}", base, url)),
+    }
+}
+
+/// A query or header value as text.
+fn plain(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[async_trait]
+impl NodeExecutor for DeclarativeNode {
+    fn node_type(&self) -> &str {
+        &self.node_type
+    }
+
+    async fn execute(
+        &self,
+        node: &Node,
+        input: &TaskDataConnections,
+        context: &RuntimeContext,
+    ) -> Result<NodeOutput, ExecutionEngineError> {
+        let node_error = |message: String| ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message,
+        };
+        let given = match serde_json::to_value(&node.parameters) {
+            Ok(Value::Object(given)) => given,
+            _ => Map::new(),
+        };
+        let parameters = parameters_with_defaults(self.properties(), given, node.type_version);
+        let (credentials, authentication) =
+            self.credentials(node, &parameters).await.map_err(node_error)?;
+
+        let client =
+            context.http_client(std::time::Duration::from_millis(REQUEST_TIMEOUT_MS))?;
+
+        let main_input = input.get("main").and_then(|v| v.first());
+        let items = main_input.cloned().unwrap_or_else(|| vec![NodeExecutionData::default()]);
+
+        let mut output = Vec::new();
+        for item in &items {
+            if context.is_canceled() {
+                return Err(ExecutionEngineError::Canceled);
+            }
+            let scope = Scope::new(item, Value::Object(parameters.clone()), credentials.clone());
+            let plan = self
+                .plan(node, &parameters, &scope, authentication.as_ref())
+                .map_err(node_error)?;
+            for value in Self::fetch(node, &client, plan, context).await? {
+                let value = match value {
+                    value @ Value::Object(_) => value,
+                    other => json!({ "data": other }),
+                };
+                let data = NodeExecutionData::from_json_value(value)
+                    .map_err(|e| node_error(e.to_string()))?;
+                output.push(data);
+            }
+        }
+        Ok(vec![output])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_loads_declarative_nodes_only() {
+        let descriptions = json!([
+            {
+                "name": "nasaPics",
+                "version": [1, 2],
+                "inputs": ["main"],
+                "requestDefaults": { "baseURL": "https://api.nasa.gov" },
+                "credentials": [{ "name": "nasaPicsApi", "required": true }],
+                "properties": []
+            },
+            { "name": "n8n-nodes-base.code", "inputs": ["main"], "properties": [] },
+            {
+                "name": "nasaPicsApi",
+                "properties": [{ "name": "apiKey", "type": "string" }],
+                "authenticate": {
+                    "type": "generic",
+                    "properties": { "qs": { "api_key": "={{$credentials.apiKey}}" } }
+                }
+            }
+        ]);
+        let mut catalog = DeclarativeCatalog::new();
+        assert_eq!(catalog.load_str("nodes.json", &descriptions.to_string()).unwrap(), 2);
+        let node_types: Vec<&str> = catalog.node_types().collect();
+        assert_eq!(node_types, vec!["n8n-nodes-base.nasaPics"]);
+
+        let mut registry = NodeExecutorRegistry::new();
+        assert_eq!(catalog.register(&mut registry), 1);
+        assert!(registry.get("n8n-nodes-base.nasaPics").is_some());
+    }
+
+    #[test]
+    fn test_plan_applies_authentication() {
+        let node_executor = DeclarativeNode {
+            node_type: "n8n-nodes-base.example".to_string(),
+            description: json!({
+                "requestDefaults": {
+                    "baseURL": "https://api.example.com/v1/",
+                    "url": "/items"
+                },
+                "properties": []
+            }),
+            shared: Arc::default(),
+        };
+        let node = Node::new("Example", "n8n-nodes-base.example");
+        let item = NodeExecutionData::default();
+        let scope = Scope::new(&item, json!({}), json!({ "token": "t0k" }));
+        let authenticate = json!({
+            "type": "generic",
+            "properties": { "headers": { "Authorization": "=Bearer {{$credentials.token}}" } }
+        });
+
+        let authentication = Authentication::Generic(&authenticate);
+        let plan = node_executor.plan(&node, &Map::new(), &scope, Some(&authentication)).unwrap();
+        assert_eq!(request_url(&plan).unwrap(), "https://api.example.com/v1/items");
+        assert_eq!(plan.headers["Authorization"], json!("Bearer t0k"));
+    }
+}
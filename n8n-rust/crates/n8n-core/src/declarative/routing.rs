@@ -0,0 +1,482 @@
+//! Turning a declarative description's properties into requests.
+//!
+//! Visible properties are walked in order; each contributes its `routing`
+//! (and that of the selected option or the collection entries that are
+//! set) to a [`RequestPlan`]: `request` fields are merged into the
+//! request, `send` puts the value into the query or body, `output` adds
+//! `postReceive` actions and `operations` pagination.
+
+use crate::expression::{parse_template, ExpressionContext, ExpressionEvaluator};
+use crate::oauth1::OAuth1Credentials;
+use n8n_workflow::NodeExecutionData;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Values expressions of a description can see: `$parameter`,
+/// `$credentials`, `$value`, `$response` and `$responseItem`, plus the
+/// current item as `$json`.
+#[derive(Debug, Clone)]
+pub struct Scope<'a> {
+    item: &'a NodeExecutionData,
+    locals: HashMap<String, Value>,
+}
+
+impl<'a> Scope<'a> {
+    pub fn new(item: &'a NodeExecutionData, parameters: Value, credentials: Value) -> Self {
+        let mut locals = HashMap::new();
+        locals.insert("parameter".to_string(), parameters);
+        locals.insert("credentials".to_string(), credentials);
+        locals.insert("value".to_string(), Value::Null);
+        Self { item, locals }
+    }
+
+    /// The scope with `name` bound, e.g. `value` or `response`.
+    pub fn with(&self, name: &str, value: Value) -> Self {
+        let mut scope = self.clone();
+        scope.locals.insert(name.to_string(), value);
+        scope
+    }
+
+    /// Evaluate `value`: strings starting with `=` are expressions, objects
+    /// and arrays are evaluated member by member.
+    pub fn eval(&self, value: &Value) -> Result<Value, String> {
+        match value {
+            Value::String(s) => match s.strip_prefix('=') {
+                Some(template) if template.contains("{{") => {
+                    let mut context = ExpressionContext::minimal(self.item);
+                    context.locals = Some(&self.locals);
+                    let expr = parse_template(template)
+                        .map_err(|e| format!("Invalid expression '{}': {}", s, e))?;
+                    ExpressionEvaluator::new()
+                        .evaluate(&expr, &context)
+                        .map_err(|e| format!("Expression '{}' failed: {}", s, e))
+                }
+                Some(text) => Ok(Value::String(text.to_string())),
+                None => Ok(value.clone()),
+            },
+            Value::Array(values) => values
+                .iter()
+                .map(|v| self.eval(v))
+                .collect::<Result<Vec<_>, String>>()
+                .map(Value::Array),
+            Value::Object(object) => object
+                .iter()
+                .map(|(key, v)| Ok((key.clone(), self.eval(v)?)))
+                .collect::<Result<Map<_, _>, String>>()
+                .map(Value::Object),
+            _ => Ok(value.clone()),
+        }
+    }
+
+    /// Evaluate `value` as a string, for URLs and property names.
+    pub fn eval_string(&self, value: &Value) -> Result<String, String> {
+        Ok(match self.eval(value)? {
+            Value::String(s) => s,
+            Value::Null => String::new(),
+            other => other.to_string(),
+        })
+    }
+}
+
+/// A `postReceive` action with the scope it was declared in.
+#[derive(Debug, Clone)]
+pub struct PostReceive<'a> {
+    pub action: Value,
+    pub scope: Scope<'a>,
+}
+
+/// Everything the properties contribute to one item's request.
+#[derive(Debug, Clone, Default)]
+pub struct RequestPlan<'a> {
+    pub method: Option<String>,
+    pub base_url: Option<String>,
+    pub url: Option<String>,
+    pub headers: Map<String, Value>,
+    pub qs: Map<String, Value>,
+    pub body: Map<String, Value>,
+    /// Basic auth user and password.
+    pub auth: Option<(String, String)>,
+    pub return_full_response: bool,
+    pub ignore_http_status_errors: bool,
+    /// Credentials every request is signed with.
+    pub oauth1: Option<OAuth1Credentials>,
+    pub post_receive: Vec<PostReceive<'a>>,
+    /// `operations.pagination` of the routing that asked to paginate.
+    pub pagination: Option<(Value, Scope<'a>)>,
+}
+
+impl<'a> RequestPlan<'a> {
+    /// Merge a `request` object (`requestDefaults` or a routing's).
+    pub fn merge_request(&mut self, request: &Value, scope: &Scope<'a>) -> Result<(), String> {
+        let Value::Object(request) = scope.eval(request)? else {
+            return Ok(());
+        };
+        for (key, value) in request {
+            match (key.as_str(), value) {
+                ("method", Value::String(method)) => self.method = Some(method.to_uppercase()),
+                ("baseURL", Value::String(url)) => self.base_url = Some(url),
+                ("url", Value::String(url)) => self.url = Some(url),
+                ("headers", Value::Object(headers)) => self.headers.extend(headers),
+                ("qs", Value::Object(qs)) => self.qs.extend(qs),
+                ("body", Value::Object(body)) => self.body.extend(body),
+                ("auth", Value::Object(auth)) => {
+                    let field = |key: &str| match auth.get(key) {
+                        Some(Value::String(s)) => s.clone(),
+                        _ => String::new(),
+                    };
+                    self.auth = Some((field("username"), field("password")));
+                }
+                ("returnFullResponse", Value::Bool(full)) => self.return_full_response = full,
+                ("ignoreHttpStatusErrors", Value::Bool(ignore)) => {
+                    self.ignore_http_status_errors = ignore
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply one property's `routing` with `$value` bound to `value`.
+    pub fn apply_routing(&mut self, routing: &Value, scope: &Scope<'a>) -> Result<(), String> {
+        if let Some(request) = routing.get("request") {
+            self.merge_request(request, scope)?;
+        }
+        if let Some(send) = routing.get("send") {
+            let value = match send.get("value") {
+                Some(value) => scope.eval(value)?,
+                None => scope.locals["value"].clone(),
+            };
+            if let Some(property) = send.get("property") {
+                let property = scope.eval_string(property)?;
+                let dotted = send.get("propertyInDotNotation") != Some(&Value::Bool(false));
+                let target = match send.get("type").and_then(Value::as_str) {
+                    Some("query") => &mut self.qs,
+                    _ => &mut self.body,
+                };
+                set_path(target, &property, value, dotted);
+            }
+            if let Some(paginate) = send.get("paginate") {
+                if truthy(&scope.eval(paginate)?) {
+                    if let Some(pagination) = routing.pointer("/operations/pagination") {
+                        self.pagination = Some((pagination.clone(), scope.clone()));
+                    }
+                }
+            }
+        }
+        if let Some(Value::Array(actions)) = routing.pointer("/output/postReceive") {
+            for action in actions.iter().filter(|a| a.is_object()) {
+                self.post_receive.push(PostReceive {
+                    action: action.clone(),
+                    scope: scope.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply the routing of `property` and of the options or entries its
+    /// `value` selects.
+    pub fn apply_property(
+        &mut self,
+        property: &Value,
+        value: &Value,
+        scope: &Scope<'a>,
+    ) -> Result<(), String> {
+        let bound = scope.with("value", value.clone());
+        if let Some(routing) = property.get("routing") {
+            self.apply_routing(routing, &bound)?;
+        }
+        let options = property.get("options").and_then(Value::as_array);
+        match property.get("type").and_then(Value::as_str) {
+            Some("options") | Some("multiOptions") => {
+                let selected: Vec<&Value> = match value {
+                    Value::Array(values) => values.iter().collect(),
+                    value => vec![value],
+                };
+                for option in options.into_iter().flatten() {
+                    let is_selected = option
+                        .get("value")
+                        .is_some_and(|v| selected.iter().any(|s| loose_eq(s, v)));
+                    if let (true, Some(routing)) = (is_selected, option.get("routing")) {
+                        self.apply_routing(routing, &bound)?;
+                    }
+                }
+            }
+            Some("collection") => {
+                for child in options.into_iter().flatten() {
+                    if let Some(child_value) = child_name(child).and_then(|n| value.get(n)) {
+                        self.apply_property(child, child_value, scope)?;
+                    }
+                }
+            }
+            Some("fixedCollection") => {
+                for group in options.into_iter().flatten() {
+                    let Some(entries) = child_name(group).and_then(|n| value.get(n)) else {
+                        continue;
+                    };
+                    let entries: Vec<&Value> = match entries {
+                        Value::Array(entries) => entries.iter().collect(),
+                        entry => vec![entry],
+                    };
+                    let children = group.get("values").and_then(Value::as_array);
+                    for entry in entries {
+                        for child in children.into_iter().flatten() {
+                            if let Some(v) = child_name(child).and_then(|n| entry.get(n)) {
+                                self.apply_property(child, v, scope)?;
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn child_name(property: &Value) -> Option<&str> {
+    property.get("name").and_then(Value::as_str)
+}
+
+/// Parameters of the node with the defaults of the visible properties
+/// filled in, in property order so later properties see earlier defaults.
+pub fn parameters_with_defaults(
+    properties: &[Value],
+    parameters: Map<String, Value>,
+    type_version: u32,
+) -> Map<String, Value> {
+    let mut parameters = parameters;
+    for property in properties {
+        let Some(name) = child_name(property) else {
+            continue;
+        };
+        if !parameters.contains_key(name) && is_visible(property, &parameters, type_version) {
+            if let Some(default) = property.get("default") {
+                parameters.insert(name.to_string(), default.clone());
+            }
+        }
+    }
+    parameters
+}
+
+/// Whether `displayOptions` show `property` for these parameters.
+pub fn is_visible(property: &Value, parameters: &Map<String, Value>, type_version: u32) -> bool {
+    let Some(options) = property.get("displayOptions") else {
+        return true;
+    };
+    let current = |name: &str| match name.trim_start_matches('/') {
+        "@version" => Some(Value::from(type_version)),
+        name => parameters.get(name).cloned(),
+    };
+    let matches = |name: &String, allowed: &Value| {
+        let allowed = allowed.as_array().map(Vec::as_slice).unwrap_or_default();
+        current(name).is_some_and(|value| allowed.iter().any(|a| loose_eq(a, &value)))
+    };
+    if let Some(Value::Object(show)) = options.get("show") {
+        if !show.iter().all(|(name, allowed)| matches(name, allowed)) {
+            return false;
+        }
+    }
+    if let Some(Value::Object(hide)) = options.get("hide") {
+        if hide.iter().any(|(name, allowed)| matches(name, allowed)) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Run the `postReceive` actions over the response's items.
+pub fn post_receive(
+    actions: &[PostReceive<'_>],
+    mut items: Vec<Value>,
+    response: &Value,
+) -> Result<Vec<Value>, String> {
+    for PostReceive { action, scope } in actions {
+        let scope = scope.with("response", response.clone());
+        let properties = action.get("properties").cloned().unwrap_or(Value::Null);
+        match action.get("type").and_then(Value::as_str) {
+            Some("rootProperty") => {
+                let property = scope.eval_string(&properties["property"])?;
+                items = items
+                    .into_iter()
+                    .flat_map(|item| match item.get(&property).cloned() {
+                        Some(Value::Array(values)) => values,
+                        Some(value) => vec![value],
+                        None => Vec::new(),
+                    })
+                    .collect();
+            }
+            Some("set") => {
+                items = match scope.eval(&properties["value"])? {
+                    Value::Array(values) => values,
+                    value => vec![value],
+                };
+            }
+            Some("limit") => {
+                let max = scope.eval(&properties["maxResults"])?;
+                let max = max.as_f64().or_else(|| max.as_str()?.parse().ok());
+                if let Some(max) = max {
+                    items.truncate(max.max(0.0) as usize);
+                }
+            }
+            Some("filter") => {
+                let mut kept = Vec::new();
+                for item in items {
+                    let pass = scope.with("responseItem", item.clone()).eval(&properties["pass"])?;
+                    if truthy(&pass) {
+                        kept.push(item);
+                    }
+                }
+                items = kept;
+            }
+            Some("setKeyValue") => {
+                items = items
+                    .into_iter()
+                    .map(|item| scope.with("responseItem", item).eval(&properties))
+                    .collect::<Result<_, _>>()?;
+            }
+            Some("sort") => {
+                let key = scope.eval_string(&properties["key"])?;
+                items.sort_by(|a, b| compare(&a[&key], &b[&key]));
+            }
+            other => {
+                return Err(format!("Unsupported postReceive type {:?}", other.unwrap_or("")));
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Set `path` in `target`, creating intermediate objects for dotted paths.
+fn set_path(target: &mut Map<String, Value>, path: &str, value: Value, dotted: bool) {
+    if !dotted {
+        target.insert(path.to_string(), value);
+        return;
+    }
+    let mut segments: Vec<&str> = path.split('.').collect();
+    let last = segments.pop().unwrap_or_default();
+    let mut current = target;
+    for segment in segments {
+        let entry = current
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !entry.is_object() {
+            *entry = Value::Object(Map::new());
+        }
+        current = entry.as_object_mut().expect("just made an object");
+    }
+    current.insert(last.to_string(), value);
+}
+
+/// JavaScript-style truthiness.
+pub fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0 && !n.is_nan()),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(_) | Value::Object(_) => true,
+    }
+}
+
+/// Equality with numbers compared by value, as option values often mix
+/// integers and floats.
+fn loose_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        _ => a == b,
+    }
+}
+
+fn compare(a: &Value, b: &Value) -> std::cmp::Ordering {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        _ => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_routing_builds_request() {
+        let properties = vec![
+            json!({ "name": "resource", "type": "options", "default": "user" }),
+            json!({
+                "name": "operation",
+                "type": "options",
+                "displayOptions": { "show": { "resource": ["user"] } },
+                "default": "get",
+                "options": [{
+                    "value": "get",
+                    "routing": {
+                        "request": { "method": "GET", "url": "=/users/{{$parameter.id}}" }
+                    }
+                }]
+            }),
+            json!({
+                "name": "options",
+                "type": "collection",
+                "default": {},
+                "options": [{
+                    "name": "fields",
+                    "type": "string",
+                    "routing": { "send": { "type": "query", "property": "fields" } }
+                }, {
+                    "name": "unset",
+                    "type": "string",
+                    "routing": { "send": { "type": "query", "property": "unset" } }
+                }]
+            }),
+        ];
+        let given = json!({ "id": 7, "options": { "fields": "name,email" } });
+        let parameters =
+            parameters_with_defaults(&properties, given.as_object().unwrap().clone(), 1);
+        assert_eq!(parameters["operation"], json!("get"));
+
+        let item = NodeExecutionData::default();
+        let scope = Scope::new(&item, Value::Object(parameters.clone()), Value::Null);
+        let mut plan = RequestPlan::default();
+        for property in &properties {
+            let name = property["name"].as_str().unwrap();
+            if let Some(value) = parameters.get(name) {
+                plan.apply_property(property, value, &scope).unwrap();
+            }
+        }
+        assert_eq!(plan.method.as_deref(), Some("GET"));
+        assert_eq!(plan.url.as_deref(), Some("/users/7"));
+        assert_eq!(Value::Object(plan.qs), json!({ "fields": "name,email" }));
+    }
+
+    #[test]
+    fn test_post_receive_actions() {
+        let item = NodeExecutionData::default();
+        let scope = Scope::new(&item, json!({ "limit": 2 }), Value::Null);
+        let action = |action: Value| PostReceive {
+            action,
+            scope: scope.clone(),
+        };
+        let actions = vec![
+            action(json!({ "type": "rootProperty", "properties": { "property": "data" } })),
+            action(json!({
+                "type": "filter",
+                "properties": { "pass": "={{$responseItem.active}}" }
+            })),
+            action(json!({
+                "type": "limit",
+                "properties": { "maxResults": "={{$parameter.limit}}" }
+            })),
+        ];
+        let body = json!({ "data": [
+            { "id": 1, "active": true },
+            { "id": 2, "active": false },
+            { "id": 3, "active": true },
+            { "id": 4, "active": true },
+        ] });
+        let items = post_receive(&actions, vec![body.clone()], &json!({ "body": body })).unwrap();
+        let ids: Vec<&Value> = items.iter().map(|item| &item["id"]).collect();
+        assert_eq!(ids, vec![&json!(1), &json!(3)]);
+    }
+}
@@ -0,0 +1,603 @@
+//! Signed webhook delivery.
+//!
+//! `n8n-nodes-base.webhookDelivery` POSTs each input item as JSON to a
+//! callback URL (`url`), usually one a customer registered. When the
+//! endpoint has a secret (`secret`, or the environment variable
+//! `secretEnv`), payloads are signed:
+//!
+//! - `X-N8n-Delivery`: delivery ID, the same for every attempt so receivers
+//!   can drop duplicates;
+//! - `X-N8n-Signature`: `t={unix time},v1={hex HMAC-SHA256 of "{t}.{body}"}`,
+//!   the scheme Stripe uses, so existing verifiers work.
+//!
+//! Network errors and `408`, `429` and `5xx` answers are retried up to
+//! `maxAttempts` (default 5) attempts, with exponential backoff from
+//! `initialDelayMs` (1000) to at most `maxDelayMs` (60000); each delay is
+//! jittered between half and all of its value. Other answers end the
+//! delivery.
+//!
+//! Deliveries and their attempts are recorded in the engine's
+//! [`WebhookDeliveries`], which redelivers them on request. The node
+//! outputs `{deliveryId, status, attempts, statusCode}` per item and fails
+//! when a delivery does, unless `failOnError` is `false`.
+
+use crate::egress::EgressPolicy;
+use crate::error::ExecutionEngineError;
+use crate::executor::{NodeExecutor, NodeOutput};
+use crate::runtime::RuntimeContext;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use n8n_workflow::{
+    object_to_json, DataObject, Node, NodeExecutionData, NodeParameterValue, TaskDataConnections,
+};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+/// Node type of the webhook delivery node.
+pub const WEBHOOK_DELIVERY_NODE_TYPE: &str = "n8n-nodes-base.webhookDelivery";
+
+/// Header carrying the delivery ID.
+pub const DELIVERY_ID_HEADER: &str = "X-N8n-Delivery";
+
+/// Header carrying the payload signature.
+pub const SIGNATURE_HEADER: &str = "X-N8n-Signature";
+
+/// Deliveries the in-memory store keeps before dropping the oldest.
+const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Timeout of manual redeliveries.
+const REDELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors of recorded deliveries.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryError {
+    #[error("Delivery {0} not found")]
+    NotFound(String),
+
+    #[error("Delivery store failed: {0}")]
+    Store(String),
+
+    #[error("Delivery refused: {0}")]
+    Refused(String),
+
+    #[error("Delivery canceled")]
+    Canceled,
+}
+
+/// Attempts and backoff of a delivery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay_ms: 1000,
+            max_delay_ms: 60_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait after `failures` failed attempts, with `jitter` in `[0, 1)`
+    /// picking between half and all of the backoff.
+    pub fn delay(&self, failures: u32, jitter: f64) -> Duration {
+        let doublings = failures.saturating_sub(1).min(32);
+        let backoff = self
+            .initial_delay_ms
+            .saturating_mul(1 << doublings)
+            .min(self.max_delay_ms);
+        let half = backoff / 2;
+        Duration::from_millis(half + ((backoff - half) as f64 * jitter.clamp(0.0, 1.0)) as u64)
+    }
+}
+
+/// State of a delivery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+/// One request of a delivery.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryAttempt {
+    /// 1-based, counting across redeliveries.
+    pub attempt: u32,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Requested through [`WebhookDeliveries::redeliver`].
+    #[serde(default)]
+    pub manual: bool,
+}
+
+/// A payload sent to a callback URL, with its attempts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delivery {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow_id: Option<String>,
+    pub node: String,
+    pub url: String,
+    pub payload: Value,
+    pub status: DeliveryStatus,
+    pub retry: RetryPolicy,
+    pub attempts: Vec<DeliveryAttempt>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Signing secret; empty sends unsigned payloads. Never serialized, so
+    /// stores persisting deliveries keep it themselves.
+    #[serde(skip_serializing, default)]
+    pub secret: String,
+}
+
+impl Delivery {
+    pub fn new(node: &str, url: &str, payload: Value, secret: &str, retry: RetryPolicy) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            workflow_id: None,
+            node: node.to_string(),
+            url: url.to_string(),
+            payload,
+            status: DeliveryStatus::Pending,
+            retry,
+            attempts: Vec::new(),
+            created_at: now,
+            updated_at: now,
+            secret: secret.to_string(),
+        }
+    }
+
+    pub fn with_workflow(mut self, workflow_id: Option<&str>) -> Self {
+        self.workflow_id = workflow_id.map(str::to_string);
+        self
+    }
+
+    /// Status code of the latest attempt.
+    pub fn last_status_code(&self) -> Option<u16> {
+        self.attempts.last().and_then(|a| a.status_code)
+    }
+}
+
+/// `X-N8n-Signature` value of `body` sent at `timestamp`.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    let signature: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("t={},v1={}", timestamp, signature)
+}
+
+/// Where deliveries are recorded.
+#[async_trait]
+pub trait DeliveryStore: Send + Sync {
+    /// Insert or replace `delivery`.
+    async fn save(&self, delivery: &Delivery) -> Result<(), String>;
+
+    async fn get(&self, id: &str) -> Result<Option<Delivery>, String>;
+
+    /// Latest deliveries first, of one workflow or all.
+    async fn list(&self, workflow_id: Option<&str>, limit: usize) -> Result<Vec<Delivery>, String>;
+}
+
+/// Deliveries kept in memory, the oldest dropped beyond a capacity.
+pub struct MemoryDeliveryStore {
+    deliveries: RwLock<HashMap<String, Delivery>>,
+    capacity: usize,
+}
+
+impl MemoryDeliveryStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            deliveries: RwLock::new(HashMap::new()),
+            capacity: capacity.max(1),
+        }
+    }
+}
+
+#[async_trait]
+impl DeliveryStore for MemoryDeliveryStore {
+    async fn save(&self, delivery: &Delivery) -> Result<(), String> {
+        let mut deliveries = self.deliveries.write();
+        deliveries.insert(delivery.id.clone(), delivery.clone());
+        if deliveries.len() > self.capacity {
+            let oldest = deliveries
+                .values()
+                .min_by_key(|d| d.created_at)
+                .map(|d| d.id.clone());
+            if let Some(id) = oldest {
+                deliveries.remove(&id);
+            }
+        }
+        Ok(())
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<Delivery>, String> {
+        Ok(self.deliveries.read().get(id).cloned())
+    }
+
+    async fn list(&self, workflow_id: Option<&str>, limit: usize) -> Result<Vec<Delivery>, String> {
+        let mut deliveries: Vec<Delivery> = self
+            .deliveries
+            .read()
+            .values()
+            .filter(|d| workflow_id.is_none() || d.workflow_id.as_deref() == workflow_id)
+            .cloned()
+            .collect();
+        deliveries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        deliveries.truncate(limit);
+        Ok(deliveries)
+    }
+}
+
+/// How an attempt ended.
+enum Outcome {
+    Delivered,
+    Retry,
+    GiveUp,
+}
+
+/// The engine's delivery service. Clones share the store, which starts in
+/// memory and may be replaced once a durable one is available.
+#[derive(Clone)]
+pub struct WebhookDeliveries {
+    store: Arc<RwLock<Arc<dyn DeliveryStore>>>,
+}
+
+impl Default for WebhookDeliveries {
+    fn default() -> Self {
+        Self {
+            store: Arc::new(RwLock::new(Arc::new(MemoryDeliveryStore::new(DEFAULT_CAPACITY)))),
+        }
+    }
+}
+
+impl WebhookDeliveries {
+    /// Record deliveries in `store` from now on.
+    pub fn set_store(&self, store: Arc<dyn DeliveryStore>) {
+        *self.store.write() = store;
+    }
+
+    fn store(&self) -> Arc<dyn DeliveryStore> {
+        self.store.read().clone()
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Delivery, DeliveryError> {
+        self.store()
+            .get(id)
+            .await
+            .map_err(DeliveryError::Store)?
+            .ok_or_else(|| DeliveryError::NotFound(id.to_string()))
+    }
+
+    pub async fn list(
+        &self,
+        workflow_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Delivery>, DeliveryError> {
+        self.store()
+            .list(workflow_id, limit)
+            .await
+            .map_err(DeliveryError::Store)
+    }
+
+    /// Send `delivery` until it succeeds, fails for good or runs out of
+    /// attempts, recording every attempt.
+    pub async fn deliver(
+        &self,
+        client: &reqwest::Client,
+        delivery: &mut Delivery,
+        cancel: &CancellationToken,
+    ) -> Result<(), DeliveryError> {
+        self.save(delivery).await;
+        for failures in 0..delivery.retry.max_attempts.max(1) {
+            if failures > 0 {
+                let wait = delivery.retry.delay(failures, rand::random());
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = cancel.cancelled() => return Err(DeliveryError::Canceled),
+                }
+            }
+            let outcome = tokio::select! {
+                outcome = Self::attempt(client, delivery, false) => outcome,
+                _ = cancel.cancelled() => return Err(DeliveryError::Canceled),
+            };
+            self.save(delivery).await;
+            if !matches!(outcome, Outcome::Retry) {
+                return Ok(());
+            }
+        }
+        delivery.status = DeliveryStatus::Failed;
+        self.save(delivery).await;
+        Ok(())
+    }
+
+    /// Send a recorded delivery once more, whatever its status, if
+    /// `egress` allows its URL.
+    pub async fn redeliver(
+        &self,
+        egress: &Arc<EgressPolicy>,
+        id: &str,
+    ) -> Result<Delivery, DeliveryError> {
+        let mut delivery = self.get(id).await?;
+        let url = reqwest::Url::parse(&delivery.url)
+            .map_err(|e| DeliveryError::Refused(format!("invalid URL '{}': {}", delivery.url, e)))?;
+        egress
+            .check(&url)
+            .await
+            .map_err(|e| DeliveryError::Refused(e.to_string()))?;
+        let client = egress
+            .client_builder()
+            .timeout(REDELIVERY_TIMEOUT)
+            .build()
+            .map_err(|e| DeliveryError::Refused(e.to_string()))?;
+
+        if let Outcome::Retry = Self::attempt(&client, &mut delivery, true).await {
+            delivery.status = DeliveryStatus::Failed;
+        }
+        self.store()
+            .save(&delivery)
+            .await
+            .map_err(DeliveryError::Store)?;
+        Ok(delivery)
+    }
+
+    /// Recording is best effort: a store failure must not resend payloads
+    /// the receiver already accepted.
+    async fn save(&self, delivery: &Delivery) {
+        if let Err(e) = self.store().save(delivery).await {
+            tracing::warn!(delivery = %delivery.id, error = %e, "Failed to record webhook delivery");
+        }
+    }
+
+    async fn attempt(client: &reqwest::Client, delivery: &mut Delivery, manual: bool) -> Outcome {
+        let body = serde_json::to_vec(&delivery.payload).unwrap_or_default();
+        let started_at = Utc::now();
+        let timer = Instant::now();
+        let attempt = delivery.attempts.len() as u32 + 1;
+
+        let mut request = client
+            .post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(DELIVERY_ID_HEADER, &delivery.id)
+            .header("X-N8n-Attempt", attempt);
+        if !delivery.secret.is_empty() {
+            let signature = sign(&delivery.secret, started_at.timestamp(), &body);
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+
+        let (status_code, error) = match request.body(body).send().await {
+            Ok(response) => {
+                let status = response.status();
+                (
+                    Some(status.as_u16()),
+                    (!status.is_success()).then(|| format!("HTTP {}", status)),
+                )
+            }
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let outcome = match (status_code, &error) {
+            (_, None) => Outcome::Delivered,
+            (None, _) => Outcome::Retry,
+            (Some(code), _) if code == 408 || code == 429 || code >= 500 => Outcome::Retry,
+            _ => Outcome::GiveUp,
+        };
+
+        delivery.attempts.push(DeliveryAttempt {
+            attempt,
+            started_at,
+            duration_ms: timer.elapsed().as_millis() as u64,
+            status_code,
+            error,
+            manual,
+        });
+        delivery.status = match outcome {
+            Outcome::Delivered => DeliveryStatus::Succeeded,
+            Outcome::Retry => DeliveryStatus::Pending,
+            Outcome::GiveUp => DeliveryStatus::Failed,
+        };
+        delivery.updated_at = Utc::now();
+        outcome
+    }
+}
+
+/// Webhook delivery node executor.
+pub struct WebhookDeliveryExecutor;
+
+impl WebhookDeliveryExecutor {
+    fn string_param(node: &Node, name: &str) -> Option<String> {
+        match node.parameters.get(name) {
+            Some(NodeParameterValue::String(s)) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    fn number_param(node: &Node, name: &str) -> Option<u64> {
+        match node.parameters.get(name) {
+            Some(NodeParameterValue::Number(n)) if *n >= 0.0 => Some(*n as u64),
+            _ => None,
+        }
+    }
+
+    fn retry_policy(node: &Node) -> RetryPolicy {
+        let default = RetryPolicy::default();
+        RetryPolicy {
+            max_attempts: Self::number_param(node, "maxAttempts")
+                .map_or(default.max_attempts, |n| n as u32),
+            initial_delay_ms: Self::number_param(node, "initialDelayMs")
+                .unwrap_or(default.initial_delay_ms),
+            max_delay_ms: Self::number_param(node, "maxDelayMs").unwrap_or(default.max_delay_ms),
+        }
+    }
+}
+
+#[async_trait]
+impl NodeExecutor for WebhookDeliveryExecutor {
+    fn node_type(&self) -> &str {
+        WEBHOOK_DELIVERY_NODE_TYPE
+    }
+
+    async fn execute(
+        &self,
+        node: &Node,
+        input: &TaskDataConnections,
+        context: &RuntimeContext,
+    ) -> Result<NodeOutput, ExecutionEngineError> {
+        let node_error = |message: String| ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message,
+        };
+
+        let url = Self::string_param(node, "url")
+            .ok_or_else(|| node_error("Parameter 'url' is required".to_string()))?;
+        let secret = match (Self::string_param(node, "secret"), Self::string_param(node, "secretEnv")) {
+            (Some(secret), _) => secret,
+            (None, Some(name)) => std::env::var(&name)
+                .map_err(|_| node_error(format!("Signing secret variable {} is not set", name)))?,
+            (None, None) => String::new(),
+        };
+        let retry = Self::retry_policy(node);
+        let fail_on_error = !matches!(
+            node.parameters.get("failOnError"),
+            Some(NodeParameterValue::Boolean(false))
+        );
+
+        let timeout_ms = Self::number_param(node, "timeout").unwrap_or(10000);
+        let client = context.http_client(Duration::from_millis(timeout_ms))?;
+        let endpoint = reqwest::Url::parse(&url)
+            .map_err(|e| node_error(format!("Invalid callback URL '{}': {}", url, e)))?;
+        context
+            .egress()
+            .check(&endpoint)
+            .await
+            .map_err(|e| node_error(e.to_string()))?;
+
+        let main_input = input.get("main").and_then(|v| v.first());
+        let items = main_input.cloned().unwrap_or_else(|| vec![NodeExecutionData::default()]);
+        let cancel_token = context.cancellation_token();
+        let mut output = Vec::new();
+
+        for item in &items {
+            let payload = Value::Object(object_to_json(&item.json));
+            let mut delivery = Delivery::new(&node.name, &url, payload, &secret, retry)
+                .with_workflow(context.workflow_id());
+            context
+                .deliveries()
+                .deliver(&client, &mut delivery, &cancel_token)
+                .await
+                .map_err(|_| ExecutionEngineError::Canceled)?;
+
+            if fail_on_error && delivery.status != DeliveryStatus::Succeeded {
+                let reason = delivery
+                    .attempts
+                    .last()
+                    .and_then(|a| a.error.clone())
+                    .unwrap_or_default();
+                return Err(node_error(format!(
+                    "Delivery {} to {} failed after {} attempts: {}",
+                    delivery.id,
+                    url,
+                    delivery.attempts.len(),
+                    reason
+                )));
+            }
+
+            let json: DataObject = serde_json::from_value(json!({
+                "deliveryId": delivery.id,
+                "status": delivery.status,
+                "attempts": delivery.attempts.len(),
+                "statusCode": delivery.last_status_code(),
+            }))
+            .map_err(|e| ExecutionEngineError::Internal(e.to_string()))?;
+            output.push(NodeExecutionData::new(json));
+        }
+
+        Ok(vec![output])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webhook_signature::{self, SignatureConfig, SignatureProvider, SignedRequest};
+
+    #[test]
+    fn test_backoff_is_exponential_capped_and_jittered() {
+        let retry = RetryPolicy {
+            max_attempts: 10,
+            initial_delay_ms: 1000,
+            max_delay_ms: 10_000,
+        };
+        assert_eq!(retry.delay(1, 0.999_999), Duration::from_millis(999));
+        assert_eq!(retry.delay(1, 0.0), Duration::from_millis(500));
+        assert_eq!(retry.delay(3, 0.0), Duration::from_millis(2000));
+        assert_eq!(retry.delay(8, 0.0), Duration::from_millis(5000));
+        assert_eq!(retry.delay(40, 0.999_999), Duration::from_millis(9999));
+    }
+
+    #[tokio::test]
+    async fn test_signature_and_store() {
+        // Receivers can check signatures with a Stripe-style verifier.
+        let now = Utc::now();
+        let body = br#"{"order":1}"#;
+        let headers = HashMap::from([(
+            "stripe-signature".to_string(),
+            sign("endpoint-secret", now.timestamp(), body),
+        )]);
+        let request = SignedRequest {
+            headers: &headers,
+            body,
+            url: "https://customer.example.com/hook",
+        };
+        let config = SignatureConfig::new(SignatureProvider::Stripe, "endpoint-secret");
+        assert!(webhook_signature::verify(&config, "endpoint-secret", &request, now).is_ok());
+
+        let deliveries = WebhookDeliveries::default();
+        deliveries.set_store(Arc::new(MemoryDeliveryStore::new(2)));
+        for n in 0..3 {
+            let mut delivery = Delivery::new("Notify", "https://x", json!({ "n": n }), "s", RetryPolicy::default())
+                .with_workflow(Some("wf"));
+            delivery.created_at += chrono::Duration::seconds(n);
+            deliveries.save(&delivery).await;
+        }
+
+        let listed = deliveries.list(Some("wf"), 10).await.unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].payload["n"], 2);
+        assert!(deliveries.list(Some("other"), 10).await.unwrap().is_empty());
+        assert_eq!(
+            deliveries.get("missing").await,
+            Err(DeliveryError::NotFound("missing".to_string()))
+        );
+        assert!(serde_json::to_value(&listed[0]).unwrap().get("secret").is_none());
+    }
+}
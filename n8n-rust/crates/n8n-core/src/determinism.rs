@@ -0,0 +1,140 @@
+//! Deterministic mode for test executions.
+//!
+//! With [`DeterministicConfig::enabled`], what an execution would take from
+//! the clock or a random source comes from a [`Determinism`] instead:
+//! `$now`, `$today`, `Date()` and `DateTime()` see a frozen instant,
+//! `randomItem()` and `shuffle()` draw from an RNG seeded with
+//! [`DeterministicConfig::seed`], and the engine draws execution IDs from
+//! a seeded RNG too. Each execution starts from the seed again, so running
+//! a workflow twice on the same input gives the same output, which
+//! golden-file tests compare against.
+
+use chrono::{DateTime, TimeZone, Utc};
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Deterministic mode settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeterministicConfig {
+    pub enabled: bool,
+    /// Seed of the random sources.
+    pub seed: u64,
+    /// Instant the clock is frozen at.
+    pub now: DateTime<Utc>,
+}
+
+impl Default for DeterministicConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            seed: 0,
+            now: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        }
+    }
+}
+
+struct Frozen {
+    now: DateTime<Utc>,
+    rng: Mutex<StdRng>,
+}
+
+/// Clock and random source of an execution: the real ones, or frozen and
+/// seeded in deterministic mode. Clones share the RNG.
+#[derive(Clone, Default)]
+pub struct Determinism {
+    frozen: Option<Arc<Frozen>>,
+}
+
+impl Determinism {
+    /// Frozen and seeded as `config` says, or the real clock and RNG when
+    /// it is disabled.
+    pub fn new(config: &DeterministicConfig) -> Self {
+        Self {
+            frozen: config.enabled.then(|| {
+                Arc::new(Frozen {
+                    now: config.now,
+                    rng: Mutex::new(StdRng::seed_from_u64(config.seed)),
+                })
+            }),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.frozen.is_some()
+    }
+
+    /// The current time.
+    pub fn now(&self) -> DateTime<Utc> {
+        match &self.frozen {
+            Some(frozen) => frozen.now,
+            None => Utc::now(),
+        }
+    }
+
+    /// A random index below `len`, which must not be 0.
+    pub fn index(&self, len: usize) -> usize {
+        match &self.frozen {
+            Some(frozen) => frozen.rng.lock().gen_range(0..len),
+            None => rand::thread_rng().gen_range(0..len),
+        }
+    }
+
+    /// Shuffle `values` in place.
+    pub fn shuffle<T>(&self, values: &mut [T]) {
+        match &self.frozen {
+            Some(frozen) => values.shuffle(&mut *frozen.rng.lock()),
+            None => values.shuffle(&mut rand::thread_rng()),
+        }
+    }
+
+    /// A random (version 4) UUID.
+    pub fn uuid(&self) -> Uuid {
+        match &self.frozen {
+            Some(frozen) => {
+                let mut bytes = [0u8; 16];
+                frozen.rng.lock().fill_bytes(&mut bytes);
+                uuid::Builder::from_random_bytes(bytes).into_uuid()
+            }
+            None => Uuid::new_v4(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Determinism {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Determinism")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_draws() {
+        let config = DeterministicConfig {
+            enabled: true,
+            seed: 42,
+            ..Default::default()
+        };
+        let draws = |d: Determinism| {
+            let mut values = [1, 2, 3, 4, 5, 6, 7, 8];
+            d.shuffle(&mut values);
+            (d.index(1000), values, d.uuid(), d.now())
+        };
+        assert_eq!(draws(Determinism::new(&config)), draws(Determinism::new(&config)));
+        assert_eq!(Determinism::new(&config).now(), config.now);
+
+        let other = DeterministicConfig { seed: 7, ..config.clone() };
+        assert_ne!(draws(Determinism::new(&config)), draws(Determinism::new(&other)));
+        assert!(!Determinism::new(&DeterministicConfig::default()).is_enabled());
+    }
+}
@@ -0,0 +1,315 @@
+//! Render workflows as Mermaid flowcharts or Graphviz DOT.
+//!
+//! Nodes are labelled with their name and type; disabled nodes (and edges
+//! touching them) are dashed. Edges are labelled with their output index
+//! when a node has several outputs, and with their connection type when it
+//! is not `main`. Back-edges that close a loop are annotated `loop`.
+
+use crate::engine::find_start_nodes;
+use n8n_workflow::{connection::CONNECTION_MAIN, Workflow};
+use std::collections::HashMap;
+
+/// Diagram output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramFormat {
+    Mermaid,
+    Dot,
+}
+
+impl DiagramFormat {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "mermaid" => Some(DiagramFormat::Mermaid),
+            "dot" | "graphviz" => Some(DiagramFormat::Dot),
+            _ => None,
+        }
+    }
+
+    /// MIME type of the rendered diagram.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            DiagramFormat::Mermaid => "text/vnd.mermaid; charset=utf-8",
+            DiagramFormat::Dot => "text/vnd.graphviz; charset=utf-8",
+        }
+    }
+}
+
+/// Render a workflow in the given format.
+pub fn render(workflow: &Workflow, format: DiagramFormat) -> String {
+    match format {
+        DiagramFormat::Mermaid => to_mermaid(workflow),
+        DiagramFormat::Dot => to_dot(workflow),
+    }
+}
+
+/// Render a workflow as a Mermaid flowchart.
+pub fn to_mermaid(workflow: &Workflow) -> String {
+    let mut out = String::from("flowchart LR\n");
+
+    for (i, node) in workflow.nodes.iter().enumerate() {
+        out.push_str(&format!(
+            "    n{}[\"{}<br/><small>{}</small>\"]\n",
+            i,
+            mermaid_escape(&node.name),
+            mermaid_escape(short_type(&node.node_type))
+        ));
+    }
+
+    for edge in edges(workflow) {
+        let arrow = if edge.dashed { "-.->" } else { "-->" };
+        match edge.label() {
+            Some(label) => out.push_str(&format!(
+                "    n{} {}|\"{}\"| n{}\n",
+                edge.source,
+                arrow,
+                mermaid_escape(&label),
+                edge.target
+            )),
+            None => out.push_str(&format!("    n{} {} n{}\n", edge.source, arrow, edge.target)),
+        }
+    }
+
+    let disabled: Vec<String> = workflow
+        .nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| n.disabled)
+        .map(|(i, _)| format!("n{}", i))
+        .collect();
+    if !disabled.is_empty() {
+        out.push_str("    classDef disabled stroke-dasharray: 5 5,opacity:0.6\n");
+        out.push_str(&format!("    class {} disabled\n", disabled.join(",")));
+    }
+
+    out
+}
+
+/// Render a workflow as a Graphviz DOT digraph.
+pub fn to_dot(workflow: &Workflow) -> String {
+    let mut out = format!("digraph \"{}\" {{\n", dot_escape(&workflow.name));
+    out.push_str("    rankdir=LR;\n");
+    out.push_str("    node [shape=box, style=rounded];\n");
+
+    for (i, node) in workflow.nodes.iter().enumerate() {
+        let style = if node.disabled { ", style=\"rounded,dashed\"" } else { "" };
+        out.push_str(&format!(
+            "    n{} [label=\"{}\\n{}\"{}];\n",
+            i,
+            dot_escape(&node.name),
+            dot_escape(short_type(&node.node_type)),
+            style
+        ));
+    }
+
+    for edge in edges(workflow) {
+        let mut attrs = Vec::new();
+        if let Some(label) = edge.label() {
+            attrs.push(format!("label=\"{}\"", dot_escape(&label)));
+        }
+        if edge.dashed {
+            attrs.push("style=dashed".to_string());
+        }
+        if edge.back {
+            attrs.push("constraint=false".to_string());
+        }
+
+        if attrs.is_empty() {
+            out.push_str(&format!("    n{} -> n{};\n", edge.source, edge.target));
+        } else {
+            out.push_str(&format!(
+                "    n{} -> n{} [{}];\n",
+                edge.source,
+                edge.target,
+                attrs.join(", ")
+            ));
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// A connection between two nodes, by index into `workflow.nodes`.
+struct Edge {
+    source: usize,
+    target: usize,
+    connection_type: String,
+    /// Output index, set when the source has more than one output.
+    output: Option<usize>,
+    dashed: bool,
+    back: bool,
+}
+
+impl Edge {
+    fn label(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.connection_type != CONNECTION_MAIN {
+            parts.push(self.connection_type.clone());
+        }
+        if let Some(output) = self.output {
+            parts.push(format!("output {}", output));
+        }
+        if self.back {
+            parts.push("loop".to_string());
+        }
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+}
+
+/// Collect edges in a stable order (node order, then connection type,
+/// output and position) and mark back-edges.
+fn edges(workflow: &Workflow) -> Vec<Edge> {
+    let index: HashMap<&str, usize> = workflow
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.name.as_str(), i))
+        .collect();
+
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); workflow.nodes.len()];
+    let mut edges = Vec::new();
+
+    for (source, node) in workflow.nodes.iter().enumerate() {
+        let Some(node_conns) = workflow.connections.get(&node.name) else {
+            continue;
+        };
+        let mut types: Vec<_> = node_conns.keys().collect();
+        types.sort();
+
+        for conn_type in types {
+            let outputs = &node_conns[conn_type];
+            let multiple = outputs.len() > 1;
+            for (output, conns) in outputs.iter().enumerate() {
+                for conn in conns {
+                    let Some(&target) = index.get(conn.node.as_str()) else {
+                        continue;
+                    };
+                    adjacency[source].push(target);
+                    edges.push(Edge {
+                        source,
+                        target,
+                        connection_type: conn_type.clone(),
+                        output: multiple.then_some(output),
+                        dashed: node.disabled || workflow.nodes[target].disabled,
+                        back: false,
+                    });
+                }
+            }
+        }
+    }
+
+    let back = back_edges(workflow, &index, &adjacency);
+    for edge in &mut edges {
+        edge.back = back.contains(&(edge.source, edge.target));
+    }
+    edges
+}
+
+/// Edges that point at a node still on the DFS stack, searching from the
+/// start nodes first so loops are cut where execution re-enters them.
+fn back_edges(
+    workflow: &Workflow,
+    index: &HashMap<&str, usize>,
+    adjacency: &[Vec<usize>],
+) -> Vec<(usize, usize)> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        New,
+        OnStack,
+        Done,
+    }
+
+    let mut roots: Vec<usize> = find_start_nodes(workflow)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|name| index.get(name.as_str()).copied())
+        .collect();
+    roots.extend(0..workflow.nodes.len());
+
+    let mut state = vec![State::New; workflow.nodes.len()];
+    let mut back = Vec::new();
+
+    for root in roots {
+        if state[root] != State::New {
+            continue;
+        }
+        // (node, next child position)
+        let mut stack = vec![(root, 0usize)];
+        state[root] = State::OnStack;
+
+        while let Some(top) = stack.last_mut() {
+            let node = top.0;
+            if let Some(&child) = adjacency[node].get(top.1) {
+                top.1 += 1;
+                match state[child] {
+                    State::New => {
+                        state[child] = State::OnStack;
+                        stack.push((child, 0));
+                    }
+                    State::OnStack => back.push((node, child)),
+                    State::Done => {}
+                }
+            } else {
+                state[node] = State::Done;
+                stack.pop();
+            }
+        }
+    }
+
+    back
+}
+
+/// `n8n-nodes-base.httpRequest` → `httpRequest`.
+fn short_type(node_type: &str) -> &str {
+    node_type.rsplit('.').next().unwrap_or(node_type)
+}
+
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::Node;
+
+    fn looping_workflow() -> Workflow {
+        let mut wf = Workflow::new("Loop");
+        wf.add_node(Node::new("Start", "n8n-nodes-base.manualTrigger"));
+        wf.add_node(Node::new("Check", "n8n-nodes-base.if"));
+        wf.add_node(Node::new("Retry", "n8n-nodes-base.wait"));
+        let mut archived = Node::new("Archive", "n8n-nodes-base.noOp");
+        archived.disabled = true;
+        wf.add_node(archived);
+
+        wf.connect("Start", "Check", 0, 0).unwrap();
+        wf.connect("Check", "Retry", 0, 0).unwrap();
+        wf.connect("Check", "Archive", 1, 0).unwrap();
+        wf.connect("Retry", "Check", 0, 0).unwrap();
+        wf
+    }
+
+    #[test]
+    fn test_mermaid_output() {
+        let out = to_mermaid(&looping_workflow());
+        assert!(out.starts_with("flowchart LR\n"));
+        assert!(out.contains("n0[\"Start<br/><small>manualTrigger</small>\"]"));
+        assert!(out.contains("n1 -->|\"output 0\"| n2"));
+        assert!(out.contains("n1 -.->|\"output 1\"| n3"));
+        assert!(out.contains("n2 -->|\"loop\"| n1"));
+        assert!(out.contains("class n3 disabled"));
+    }
+
+    #[test]
+    fn test_dot_output() {
+        let out = to_dot(&looping_workflow());
+        assert!(out.starts_with("digraph \"Loop\" {"));
+        assert!(out.contains("n3 [label=\"Archive\\nnoOp\", style=\"rounded,dashed\"];"));
+        assert!(out.contains("n2 -> n1 [label=\"loop\", constraint=false];"));
+        assert!(out.contains("n0 -> n1;"));
+    }
+}
@@ -0,0 +1,397 @@
+//! Markdown documentation for workflows.
+//!
+//! [`render`] describes a workflow for a catalog page: its description,
+//! triggers with their schedule or webhook route, every node with a
+//! summary of its parameters and the nodes feeding it, the credentials in
+//! use and the external endpoints called. Parameters named like secrets
+//! (see [`MaskingConfig`]) are masked; long values are shortened.
+
+use crate::explain::credential_uses;
+use crate::masking::{MaskingConfig, MASK};
+use n8n_workflow::{Node, NodeParameterValue, Workflow};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// MIME type of the rendered documentation.
+pub const CONTENT_TYPE: &str = "text/markdown; charset=utf-8";
+
+/// Parameter rows shown per node.
+const MAX_PARAMETERS: usize = 25;
+
+/// Longest parameter value shown in full.
+const MAX_VALUE_LEN: usize = 80;
+
+/// Node types whose `url` parameter is always sent with `POST`.
+const POST_ONLY_NODE_TYPES: &[&str] = &[crate::soap::SOAP_NODE_TYPE];
+
+const WEBHOOK_NODE_TYPE: &str = "n8n-nodes-base.webhook";
+const MANUAL_TRIGGER_NODE_TYPE: &str = "n8n-nodes-base.manualTrigger";
+
+/// Render `workflow` as Markdown.
+pub fn render(workflow: &Workflow) -> String {
+    let secrets = MaskingConfig::default().secret_parameters;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# {}\n", workflow.name);
+    match workflow.description.as_deref().map(str::trim) {
+        Some(description) if !description.is_empty() => {
+            let _ = writeln!(out, "{}\n", description);
+        }
+        _ => out.push_str("_No description._\n\n"),
+    }
+    out.push_str("| | |\n|---|---|\n");
+    let _ = writeln!(out, "| ID | `{}` |", workflow.id);
+    let _ = writeln!(out, "| Active | {} |", if workflow.active { "yes" } else { "no" });
+    let _ = writeln!(out, "| Nodes | {} |", workflow.nodes.len());
+    if let Some(timezone) = &workflow.settings.timezone {
+        let _ = writeln!(out, "| Timezone | {} |", cell(timezone));
+    }
+
+    out.push_str("\n## Triggers\n\n");
+    let triggers: Vec<&Node> = workflow.nodes.iter().filter(|n| n.is_trigger()).collect();
+    if triggers.is_empty() {
+        out.push_str("_None; the workflow only runs when called._\n");
+    }
+    for node in triggers {
+        let _ = write!(out, "- **{}** (`{}`): {}", node.name, node.node_type, describe_trigger(node));
+        out.push_str(if node.disabled { " _(disabled)_\n" } else { "\n" });
+    }
+
+    out.push_str("\n## Nodes\n");
+    let sources = sources(workflow);
+    for node in &workflow.nodes {
+        let _ = writeln!(out, "\n### {}\n", node.name);
+        let _ = write!(out, "`{}` v{}", node.node_type, node.type_version);
+        if node.disabled {
+            out.push_str(" · disabled");
+        }
+        out.push('\n');
+        if let Some(notes) = node.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+            let _ = writeln!(out, "\n{}", notes.trim());
+        }
+        if let Some(from) = sources.get(node.name.as_str()) {
+            let _ = writeln!(out, "\nReceives from: {}", from.join(", "));
+        }
+
+        let rows = parameter_rows(node, &secrets);
+        if !rows.is_empty() {
+            out.push_str("\n| Parameter | Value |\n|---|---|\n");
+            for (path, value) in rows.iter().take(MAX_PARAMETERS) {
+                let _ = writeln!(out, "| `{}` | {} |", path, value);
+            }
+            if rows.len() > MAX_PARAMETERS {
+                let _ = writeln!(out, "\n_{} more parameters._", rows.len() - MAX_PARAMETERS);
+            }
+        }
+    }
+
+    out.push_str("\n## Credentials\n\n");
+    let mut credentials: BTreeMap<(String, String, String), Vec<String>> = BTreeMap::new();
+    for usage in workflow.nodes.iter().flat_map(credential_uses) {
+        credentials
+            .entry((usage.name, usage.id, usage.credential_type))
+            .or_default()
+            .push(usage.node);
+    }
+    if credentials.is_empty() {
+        out.push_str("_None._\n");
+    } else {
+        out.push_str("| Credential | Type | Used by |\n|---|---|---|\n");
+        for ((name, id, credential_type), nodes) in &credentials {
+            let _ = writeln!(
+                out,
+                "| {} (`{}`) | `{}` | {} |",
+                cell(name),
+                id,
+                credential_type,
+                cell(&nodes.join(", "))
+            );
+        }
+    }
+
+    out.push_str("\n## External Endpoints\n\n");
+    let endpoints: Vec<(&Node, String, String)> = workflow
+        .nodes
+        .iter()
+        .filter_map(|node| {
+            let (method, url) = endpoint(node)?;
+            Some((node, method, url))
+        })
+        .collect();
+    if endpoints.is_empty() {
+        out.push_str("_None._\n");
+    } else {
+        out.push_str("| Node | Method | URL |\n|---|---|---|\n");
+        for (node, method, url) in endpoints {
+            let _ = writeln!(out, "| {} | `{}` | {} |", cell(&node.name), method, code(&url));
+        }
+    }
+
+    out
+}
+
+/// How a trigger node starts the workflow.
+fn describe_trigger(node: &Node) -> String {
+    match node.node_type.as_str() {
+        WEBHOOK_NODE_TYPE => {
+            let method = string_param(node, "httpMethod").unwrap_or("GET");
+            let path = string_param(node, "path").unwrap_or_default();
+            format!("`{} /webhook/{}`", method.to_uppercase(), path.trim_matches('/'))
+        }
+        MANUAL_TRIGGER_NODE_TYPE => "manual".to_string(),
+        _ => {
+            let schedule = describe_schedule(node);
+            if schedule.is_empty() {
+                "event".to_string()
+            } else {
+                schedule.join("; ")
+            }
+        }
+    }
+}
+
+/// Schedule rules of a schedule trigger: a top-level `cronExpression`, or
+/// the `rule.interval` entries of the n8n schedule trigger.
+fn describe_schedule(node: &Node) -> Vec<String> {
+    let mut rules = Vec::new();
+    if let Some(cron) = string_param(node, "cronExpression") {
+        rules.push(format!("cron `{}`", cron));
+    }
+
+    let Some(NodeParameterValue::Object(rule)) = node.parameters.get("rule") else {
+        return rules;
+    };
+    let Some(NodeParameterValue::Array(intervals)) = rule.get("interval") else {
+        return rules;
+    };
+    for interval in intervals {
+        let NodeParameterValue::Object(interval) = interval else {
+            continue;
+        };
+        let field = match interval.get("field") {
+            Some(NodeParameterValue::String(field)) => field.as_str(),
+            _ => "days",
+        };
+        let number = |key: &str| match interval.get(key) {
+            Some(NodeParameterValue::Number(n)) => Some(*n as i64),
+            _ => None,
+        };
+        if field == "cronExpression" {
+            if let Some(NodeParameterValue::String(cron)) = interval.get("expression") {
+                rules.push(format!("cron `{}`", cron));
+            }
+            continue;
+        }
+
+        let every = number(&format!("{}Interval", field)).unwrap_or(1);
+        let unit = field.trim_end_matches('s');
+        let mut rule = if every == 1 {
+            format!("every {}", unit)
+        } else {
+            format!("every {} {}s", every, unit)
+        };
+        if matches!(field, "days" | "weeks" | "months") {
+            let hour = number("triggerAtHour").unwrap_or(0);
+            let minute = number("triggerAtMinute").unwrap_or(0);
+            let _ = write!(rule, " at {:02}:{:02}", hour, minute);
+        }
+        rules.push(rule);
+    }
+    rules
+}
+
+/// Nodes connected into each node, in workflow order.
+fn sources(workflow: &Workflow) -> BTreeMap<&str, Vec<&str>> {
+    let mut sources: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for node in &workflow.nodes {
+        let Some(outputs) = workflow.connections.get(&node.name) else {
+            continue;
+        };
+        for connection in outputs.values().flatten().flatten() {
+            let from = sources.entry(connection.node.as_str()).or_default();
+            if !from.contains(&node.name.as_str()) {
+                from.push(node.name.as_str());
+            }
+        }
+    }
+    sources
+}
+
+/// Method and URL of a node calling an external endpoint.
+fn endpoint(node: &Node) -> Option<(String, String)> {
+    let url = string_param(node, "url")?;
+    let method = if POST_ONLY_NODE_TYPES.contains(&node.node_type.as_str()) {
+        "POST"
+    } else {
+        string_param(node, "method")
+            .or_else(|| string_param(node, "requestMethod"))
+            .unwrap_or("GET")
+    };
+    Some((method.to_uppercase(), url.trim_start_matches('=').to_string()))
+}
+
+/// Leaf parameters as (path, rendered value), sorted by path.
+fn parameter_rows(node: &Node, secrets: &[String]) -> Vec<(String, String)> {
+    let mut rows = Vec::new();
+    let mut keys: Vec<_> = node.parameters.keys().collect();
+    keys.sort();
+    for key in keys {
+        collect_rows(key, key, &node.parameters[key], secrets, &mut rows);
+    }
+    rows
+}
+
+fn collect_rows(
+    path: &str,
+    name: &str,
+    value: &NodeParameterValue,
+    secrets: &[String],
+    rows: &mut Vec<(String, String)>,
+) {
+    if secrets.iter().any(|s| s.eq_ignore_ascii_case(name)) {
+        rows.push((path.to_string(), MASK.to_string()));
+        return;
+    }
+    match value {
+        NodeParameterValue::String(s) | NodeParameterValue::Expression(s) => {
+            rows.push((path.to_string(), code(s)));
+        }
+        NodeParameterValue::Number(n) => rows.push((path.to_string(), n.to_string())),
+        NodeParameterValue::Boolean(b) => rows.push((path.to_string(), b.to_string())),
+        NodeParameterValue::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_rows(&format!("{}[{}]", path, i), name, item, secrets, rows);
+            }
+        }
+        NodeParameterValue::Object(map) => {
+            let mut keys: Vec<_> = map.keys().collect();
+            keys.sort();
+            for key in keys {
+                collect_rows(&format!("{}.{}", path, key), key, &map[key], secrets, rows);
+            }
+        }
+    }
+}
+
+fn string_param<'a>(node: &'a Node, name: &str) -> Option<&'a str> {
+    match node.parameters.get(name) {
+        Some(NodeParameterValue::String(s)) | Some(NodeParameterValue::Expression(s)) => {
+            Some(s.as_str())
+        }
+        _ => None,
+    }
+}
+
+/// A value as an inline code span, shortened and safe inside a table cell.
+fn code(value: &str) -> String {
+    let value: String = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    let value = if value.chars().count() > MAX_VALUE_LEN {
+        let short: String = value.chars().take(MAX_VALUE_LEN).collect();
+        format!("{}…", short)
+    } else {
+        value
+    };
+    if value.is_empty() {
+        "_empty_".to_string()
+    } else if value.contains('`') {
+        cell(&value)
+    } else {
+        format!("`{}`", value.replace('|', "\\|"))
+    }
+}
+
+/// Text safe inside a table cell.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::NodeCredentialRef;
+    use std::collections::HashMap;
+
+    fn object(entries: &[(&str, NodeParameterValue)]) -> NodeParameterValue {
+        NodeParameterValue::Object(
+            entries.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+        )
+    }
+
+    fn workflow() -> Workflow {
+        let mut wf = Workflow::new("Orders sync");
+        wf.description = Some("Copies new orders to the warehouse.".into());
+
+        let mut schedule = Node::new("Every morning", "n8n-nodes-base.scheduleTrigger");
+        schedule.set_parameter(
+            "rule",
+            object(&[(
+                "interval",
+                NodeParameterValue::Array(vec![
+                    object(&[
+                        ("field", NodeParameterValue::String("days".into())),
+                        ("triggerAtHour", NodeParameterValue::Number(7.0)),
+                    ]),
+                    object(&[
+                        ("field", NodeParameterValue::String("minutes".into())),
+                        ("minutesInterval", NodeParameterValue::Number(15.0)),
+                    ]),
+                ]),
+            )]),
+        );
+        let mut hook = Node::new("Hook", WEBHOOK_NODE_TYPE);
+        hook.set_parameter("httpMethod", NodeParameterValue::String("post".into()));
+        hook.set_parameter("path", NodeParameterValue::String("/orders/".into()));
+
+        let mut fetch = Node::new("Fetch", "n8n-nodes-base.httpRequest");
+        fetch.set_parameter("method", NodeParameterValue::String("POST".into()));
+        fetch.set_parameter(
+            "url",
+            NodeParameterValue::String("https://wms.example.com/{{ $json.id }}".into()),
+        );
+        fetch.set_parameter(
+            "options",
+            object(&[("apiKey", NodeParameterValue::String("hunter22".into()))]),
+        );
+        fetch.credentials = Some(HashMap::from([(
+            "httpHeaderAuth".to_string(),
+            NodeCredentialRef {
+                id: "7".into(),
+                name: "WMS key".into(),
+            },
+        )]));
+
+        wf.add_node(schedule);
+        wf.add_node(hook);
+        wf.add_node(fetch);
+        wf.connect("Every morning", "Fetch", 0, 0).unwrap();
+        wf.connect("Hook", "Fetch", 0, 0).unwrap();
+        wf
+    }
+
+    #[test]
+    fn test_render_describes_triggers_nodes_and_endpoints() {
+        let doc = render(&workflow());
+        assert!(doc.starts_with("# Orders sync\n\nCopies new orders to the warehouse.\n"));
+        assert!(doc.contains("- **Every morning** (`n8n-nodes-base.scheduleTrigger`): every day at 07:00; every 15 minutes\n"));
+        assert!(doc.contains("- **Hook** (`n8n-nodes-base.webhook`): `POST /webhook/orders`\n"));
+        assert!(doc.contains("Receives from: Every morning, Hook\n"));
+        assert!(doc.contains("| `options.apiKey` | *** |\n"));
+        assert!(!doc.contains("hunter22"));
+        assert!(doc.contains("| WMS key (`7`) | `httpHeaderAuth` | Fetch |\n"));
+        assert!(doc.contains("| Fetch | `POST` | `https://wms.example.com/{{ $json.id }}` |\n"));
+    }
+
+    #[test]
+    fn test_values_are_shortened_and_escaped() {
+        assert_eq!(code("a | b"), "`a \\| b`");
+        assert_eq!(code(""), "_empty_");
+        assert_eq!(code(&"x".repeat(100)).chars().count(), MAX_VALUE_LEN + 3);
+
+        let mut wf = Workflow::new("Bare");
+        wf.add_node(Node::new("Set", "n8n-nodes-base.set"));
+        let doc = render(&wf);
+        assert!(doc.contains("_No description._"));
+        assert!(doc.contains("## Credentials\n\n_None._\n"));
+        assert!(doc.contains("## External Endpoints\n\n_None._\n"));
+    }
+}
@@ -0,0 +1,427 @@
+//! Embedded mode: run the engine in-process without a server or database.
+//!
+//! [`N8nRuntime`] bundles an engine, in-memory workflow and execution
+//! storage, and an event bus. Host applications register their own node
+//! executors, load workflow JSON exported from n8n, and execute with their
+//! own serde types as input and output items.
+//!
+//! ```rust,no_run
+//! use n8n_core::N8nRuntime;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize)]
+//! struct Order { id: u64, total: f64 }
+//!
+//! #[derive(Deserialize)]
+//! struct Invoice { id: u64, amount: f64 }
+//!
+//! # async fn example(json: &str) -> Result<(), n8n_core::ExecutionEngineError> {
+//! let runtime = N8nRuntime::new();
+//! let workflow = runtime.load_workflow_json(json).await?;
+//!
+//! let mut events = runtime.subscribe();
+//! tokio::spawn(async move {
+//!     while let Ok(event) = events.recv().await {
+//!         println!("{}: {:?}", event.execution_id, event.event);
+//!     }
+//! });
+//!
+//! let invoices: Vec<Invoice> = runtime
+//!     .execute_typed(&workflow.id, &[Order { id: 1, total: 9.5 }])
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::engine::{ExecutionEvent, WorkflowEngine};
+use crate::error::ExecutionEngineError;
+use crate::executor::{NodeExecutor, NodeExecutorRegistry};
+use crate::migration::MigrationRegistry;
+use crate::runtime::RuntimeConfig;
+use crate::sub_execution::last_node_output;
+use crate::storage::{ExecutionStorage, MemoryExecutionStorage, MemoryWorkflowStorage, WorkflowStorage};
+use n8n_workflow::{
+    connection::CONNECTION_MAIN, object_to_json, NodeExecutionData, Run, Workflow,
+    WorkflowExecuteMode,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+
+/// An engine event tagged with the execution it belongs to.
+#[derive(Debug, Clone)]
+pub struct RuntimeEvent {
+    pub execution_id: String,
+    pub workflow_id: String,
+    pub event: ExecutionEvent,
+}
+
+/// A completed execution.
+#[derive(Debug, Clone)]
+pub struct Execution {
+    pub id: String,
+    pub run: Run,
+}
+
+impl Execution {
+    /// Items on the first main output of the last node that ran.
+    pub fn output(&self) -> Vec<NodeExecutionData> {
+        last_node_output(&self.run)
+    }
+
+    /// [`Self::output`] deserialized into `T`, one value per item.
+    pub fn output_as<T: DeserializeOwned>(&self) -> Result<Vec<T>, ExecutionEngineError> {
+        self.output()
+            .iter()
+            .map(|item| {
+                serde_json::from_value(Value::Object(object_to_json(&item.json)))
+                    .map_err(|e| ExecutionEngineError::Data(e.to_string()))
+            })
+            .collect()
+    }
+
+    /// The execution error, if the run failed.
+    pub fn error(&self) -> Option<ExecutionEngineError> {
+        let result = &self.run.data.result_data;
+        result.error.as_ref().map(|e| ExecutionEngineError::NodeExecution {
+            node: e
+                .context
+                .node_name
+                .clone()
+                .or_else(|| result.last_node_executed.clone())
+                .unwrap_or_default(),
+            message: e.message.clone(),
+        })
+    }
+}
+
+/// Builder for [`N8nRuntime`].
+pub struct N8nRuntimeBuilder {
+    executors: NodeExecutorRegistry,
+    config: RuntimeConfig,
+    migrations: MigrationRegistry,
+    event_capacity: usize,
+}
+
+impl N8nRuntimeBuilder {
+    /// Register a node executor, replacing any built-in for the same type.
+    pub fn executor(mut self, executor: Arc<dyn NodeExecutor>) -> Self {
+        self.executors.register(executor);
+        self
+    }
+
+    pub fn config(mut self, config: RuntimeConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Migrations applied to every loaded workflow.
+    pub fn migrations(mut self, migrations: MigrationRegistry) -> Self {
+        self.migrations = migrations;
+        self
+    }
+
+    /// Events buffered per subscriber before slow subscribers start
+    /// missing them.
+    pub fn event_capacity(mut self, capacity: usize) -> Self {
+        self.event_capacity = capacity.max(1);
+        self
+    }
+
+    pub fn build(self) -> N8nRuntime {
+        let (events, _) = broadcast::channel(self.event_capacity);
+        let workflows = Arc::new(MemoryWorkflowStorage::new());
+        let engine = WorkflowEngine::with_executors(self.executors, self.config)
+            .with_sub_workflows(workflows.clone());
+        N8nRuntime {
+            engine: Arc::new(engine),
+            workflows,
+            executions: Arc::new(MemoryExecutionStorage::new()),
+            migrations: Arc::new(self.migrations),
+            events,
+        }
+    }
+}
+
+/// In-process workflow runtime. Cheap to clone; clones share state.
+#[derive(Clone)]
+pub struct N8nRuntime {
+    engine: Arc<WorkflowEngine>,
+    workflows: Arc<MemoryWorkflowStorage>,
+    executions: Arc<MemoryExecutionStorage>,
+    migrations: Arc<MigrationRegistry>,
+    events: broadcast::Sender<RuntimeEvent>,
+}
+
+impl N8nRuntime {
+    /// Runtime with the built-in executors and default configuration.
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    pub fn builder() -> N8nRuntimeBuilder {
+        N8nRuntimeBuilder {
+            executors: NodeExecutorRegistry::new(),
+            config: RuntimeConfig::default(),
+            migrations: MigrationRegistry::default(),
+            event_capacity: 256,
+        }
+    }
+
+    pub fn engine(&self) -> &WorkflowEngine {
+        &self.engine
+    }
+
+    pub fn workflows(&self) -> &MemoryWorkflowStorage {
+        &self.workflows
+    }
+
+    pub fn executions(&self) -> &MemoryExecutionStorage {
+        &self.executions
+    }
+
+    /// Receive events from every execution started after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<RuntimeEvent> {
+        self.events.subscribe()
+    }
+
+    /// Parse workflow JSON (as exported from n8n), migrate outdated nodes,
+    /// validate and store it.
+    pub async fn load_workflow_json(&self, json: &str) -> Result<Workflow, ExecutionEngineError> {
+        let workflow: Workflow =
+            serde_json::from_str(json).map_err(|e| ExecutionEngineError::Data(e.to_string()))?;
+        self.add_workflow(workflow).await
+    }
+
+    /// Migrate, validate and store a workflow.
+    pub async fn add_workflow(&self, mut workflow: Workflow) -> Result<Workflow, ExecutionEngineError> {
+        let report = self.migrations.migrate_workflow(&mut workflow);
+        for failed in &report.failed {
+            tracing::warn!(
+                workflow = %workflow.name,
+                node = %failed.node,
+                error = %failed.message,
+                "Node migration failed"
+            );
+        }
+
+        workflow.validate()?;
+        self.workflows.save_workflow(&workflow).await?;
+        Ok(workflow)
+    }
+
+    /// Execute a stored workflow. A failed run is returned as `Ok`; check
+    /// [`Execution::error`].
+    pub async fn execute(
+        &self,
+        workflow_id: &str,
+        input: Option<Vec<NodeExecutionData>>,
+    ) -> Result<Execution, ExecutionEngineError> {
+        let workflow = self
+            .workflows
+            .get_workflow(workflow_id)
+            .await?
+            .ok_or_else(|| ExecutionEngineError::InvalidState(format!("Workflow not found: {}", workflow_id)))?;
+        self.execute_workflow(&workflow, input).await
+    }
+
+    /// Execute a workflow that is not stored in the runtime.
+    pub async fn execute_workflow(
+        &self,
+        workflow: &Workflow,
+        input: Option<Vec<NodeExecutionData>>,
+    ) -> Result<Execution, ExecutionEngineError> {
+        let (tx, mut rx) = mpsc::channel(100);
+        let execute = self
+            .engine
+            .execute_with_events(workflow, WorkflowExecuteMode::Internal, input, tx);
+
+        // The engine assigns the execution id; it arrives with `Started`.
+        let forward = async {
+            let mut execution_id = String::new();
+            while let Some(event) = rx.recv().await {
+                if let ExecutionEvent::Started { execution_id: id, .. } = &event {
+                    execution_id = id.clone();
+                }
+                // No subscribers is not an error.
+                let _ = self.events.send(RuntimeEvent {
+                    execution_id: execution_id.clone(),
+                    workflow_id: workflow.id.clone(),
+                    event,
+                });
+            }
+            execution_id
+        };
+
+        let (run, execution_id) = tokio::join!(execute, forward);
+        let run = run?;
+        let id = if execution_id.is_empty() {
+            uuid::Uuid::new_v4().to_string()
+        } else {
+            execution_id
+        };
+
+        let stored = self.engine.prepare_for_storage(workflow, &id, &run);
+        self.executions.save_execution(&id, &stored).await?;
+        Ok(Execution { id, run })
+    }
+
+    /// Execute with `T` values as input items and deserialize the output
+    /// items into `O`. A failed run is returned as an error.
+    pub async fn execute_typed<I: Serialize, O: DeserializeOwned>(
+        &self,
+        workflow_id: &str,
+        input: &[I],
+    ) -> Result<Vec<O>, ExecutionEngineError> {
+        let items = input
+            .iter()
+            .map(|value| {
+                serde_json::to_value(value)
+                    .and_then(NodeExecutionData::from_json_value)
+                    .map_err(|e| ExecutionEngineError::Data(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let execution = self
+            .execute(workflow_id, (!items.is_empty()).then_some(items))
+            .await?;
+        if let Some(error) = execution.error() {
+            return Err(error);
+        }
+        execution.output_as()
+    }
+}
+
+impl Default for N8nRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::RuntimeContext;
+    use async_trait::async_trait;
+    use n8n_workflow::{GenericValue, Node, TaskDataConnections};
+    use serde::Deserialize;
+
+    /// Doubles the `n` field of every item.
+    struct DoubleExecutor;
+
+    #[async_trait]
+    impl NodeExecutor for DoubleExecutor {
+        fn node_type(&self) -> &str {
+            "test.double"
+        }
+
+        async fn execute(
+            &self,
+            _node: &Node,
+            input: &TaskDataConnections,
+            _context: &RuntimeContext,
+        ) -> Result<crate::executor::NodeOutput, ExecutionEngineError> {
+            let items = input
+                .get(CONNECTION_MAIN)
+                .and_then(|v| v.first())
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|mut item| {
+                    if let Some(GenericValue::Integer(n)) = item.json.get("n").cloned() {
+                        item.json.insert("n".to_string(), GenericValue::Integer(n * 2));
+                    }
+                    item
+                })
+                .collect();
+            Ok(vec![items])
+        }
+    }
+
+    const WORKFLOW: &str = r#"{
+        "id": "wf-double",
+        "name": "Double",
+        "nodes": [
+            {"id": "1", "name": "Start", "type": "n8n-nodes-base.manualTrigger", "typeVersion": 1, "position": [0, 0], "parameters": {}},
+            {"id": "2", "name": "Double", "type": "test.double", "typeVersion": 1, "position": [200, 0], "parameters": {}}
+        ],
+        "connections": {
+            "Start": {"main": [[{"node": "Double", "type": "main", "index": 0}]]}
+        }
+    }"#;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Num {
+        n: i64,
+    }
+
+    #[tokio::test]
+    async fn test_execute_typed_with_custom_executor() {
+        let runtime = N8nRuntime::builder()
+            .executor(Arc::new(DoubleExecutor))
+            .build();
+        let workflow = runtime.load_workflow_json(WORKFLOW).await.unwrap();
+
+        let out: Vec<Num> = runtime
+            .execute_typed(&workflow.id, &[Num { n: 2 }, Num { n: 5 }])
+            .await
+            .unwrap();
+        assert_eq!(out, vec![Num { n: 4 }, Num { n: 10 }]);
+
+        let execution = runtime.execute(&workflow.id, None).await.unwrap();
+        assert!(runtime.executions().get_execution(&execution.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_receive_tagged_events() {
+        let runtime = N8nRuntime::builder()
+            .executor(Arc::new(DoubleExecutor))
+            .build();
+        let workflow = runtime.load_workflow_json(WORKFLOW).await.unwrap();
+        let mut events = runtime.subscribe();
+
+        let execution = runtime.execute(&workflow.id, None).await.unwrap();
+
+        let mut finished = false;
+        while let Ok(event) = events.try_recv() {
+            assert_eq!(event.execution_id, execution.id);
+            finished |= matches!(event.event, ExecutionEvent::Finished { .. });
+        }
+        assert!(finished);
+    }
+
+    fn caller(id: &str, target: &str) -> String {
+        format!(
+            r#"{{
+                "id": "{id}",
+                "name": "{id}",
+                "nodes": [
+                    {{"id": "1", "name": "Start", "type": "n8n-nodes-base.manualTrigger", "typeVersion": 1, "position": [0, 0], "parameters": {{}}}},
+                    {{"id": "2", "name": "Call", "type": "n8n-nodes-base.executeWorkflow", "typeVersion": 1, "position": [200, 0], "parameters": {{"workflowId": "{target}"}}}}
+                ],
+                "connections": {{
+                    "Start": {{"main": [[{{"node": "Call", "type": "main", "index": 0}}]]}}
+                }}
+            }}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_sub_workflow_output_and_cycle() {
+        let runtime = N8nRuntime::builder()
+            .executor(Arc::new(DoubleExecutor))
+            .build();
+        runtime.load_workflow_json(WORKFLOW).await.unwrap();
+        runtime.load_workflow_json(&caller("wf-parent", "wf-double")).await.unwrap();
+
+        let out: Vec<Num> = runtime.execute_typed("wf-parent", &[Num { n: 3 }]).await.unwrap();
+        assert_eq!(out, vec![Num { n: 6 }]);
+
+        runtime.load_workflow_json(&caller("wf-a", "wf-b")).await.unwrap();
+        runtime.load_workflow_json(&caller("wf-b", "wf-a")).await.unwrap();
+        let execution = runtime.execute("wf-a", None).await.unwrap();
+        let error = execution.error().expect("cycle should fail the run").to_string();
+        assert!(error.contains("wf-a -> wf-b -> wf-a"), "{}", error);
+    }
+}
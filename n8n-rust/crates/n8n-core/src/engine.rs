@@ -5,20 +5,58 @@
 //! - Wait nodes (pause execution until a condition is met)
 //! - Partial execution (test specific nodes)
 //! - Error handling with configurable retry logic
-
+//!
+//! Child nodes are queued per the workflow's [`ExecutionOrder`]:
+//! breadth-first in connection order for `v0`, depth-first by canvas
+//! position for `v1`.
+
+use crate::binary_data::BinaryDataService;
+use crate::concurrency::{self, ConcurrencyGroups};
+use crate::correlation;
+use crate::cost::{CostAccountant, CostHook};
+use crate::delivery::WebhookDeliveries;
+use crate::determinism::Determinism;
+use crate::egress::WorkflowProjects;
 use crate::error::ExecutionEngineError;
+use crate::execution_webhooks::{self, ExecutionNotice};
+use crate::explain::{self, ExecutionPlan};
 use crate::executor::{NodeExecutorRegistry, NodeOutput};
-use crate::expression::{self, ExpressionContext};
+use crate::expression::{ExpressionContext, ExpressionError, ExpressionEvaluator, ExpressionLimits};
+use crate::integrity::HashChain;
+use crate::journal::{has_side_effects, JournalOutcome, SideEffectJournal};
+use crate::masking::SecretMask;
+use crate::profiling::{CpuTimed, NodeProfile};
+use crate::processed_data::ProcessedData;
+use crate::progress::ExecutionProgress;
+use crate::resource_usage::ResourceUsage;
 use crate::runtime::{RuntimeConfig, RuntimeContext};
+use crate::statistics::WorkflowStatistics;
+use crate::storage::WorkflowStorage;
+use crate::sub_execution::{
+    last_node_output, ExecutionLineage, SubExecutionTracker, EXECUTE_WORKFLOW_NODE_TYPE,
+};
+use crate::supply::{self, NodeSupplies};
+use crate::throttle::Throttles;
+use crate::trigger_health::TriggerHealth;
+use crate::workflow_variables::WorkflowVariables;
 use n8n_workflow::{
-    connection::{graph, CONNECTION_MAIN},
-    ExecuteData, ExecutionStatus, Node, NodeExecutionData, NodeParameterValue, Run, TaskData,
-    TaskDataConnections, TaskDataConnectionsSource, Workflow, WorkflowExecuteMode,
+    connection::{graph, is_supply_connection, CONNECTION_MAIN},
+    ExecuteData, ExecutionOrder, ExecutionStatus, GenericValue, Node, NodeExecutionData,
+    NodeParameterValue, RelatedExecution, Run, TaskData, TaskDataConnections,
+    TaskDataConnectionsSource, TaskMetadata, Workflow, WorkflowExecuteMode,
 };
+use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
-use std::sync::Arc;
-use tokio::sync::mpsc;
-use tracing::{debug, error, info, warn};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, error, info, warn, Instrument};
+
+/// Task metadata key set on tasks whose output came from the workflow's
+/// pin data instead of running the node.
+pub const PINNED_KEY: &str = "pinned";
 
 /// Event emitted during workflow execution.
 #[derive(Debug, Clone)]
@@ -48,27 +86,228 @@ pub enum ExecutionEvent {
 pub struct WorkflowEngine {
     /// Node executor registry.
     executors: Arc<NodeExecutorRegistry>,
-    /// Runtime configuration.
-    config: RuntimeConfig,
+    /// Runtime configuration; replaced when the sender publishes an update.
+    config: watch::Receiver<RuntimeConfig>,
+    /// Workflows that `executeWorkflow` nodes may start.
+    sub_workflows: Option<Arc<dyn WorkflowStorage>>,
+    /// Sub-execution counts per running execution.
+    sub_executions: Arc<SubExecutionTracker>,
+    /// Prices tasks when cost accounting is enabled.
+    costs: CostAccountant,
+    /// Secrets masked out of task errors.
+    secrets: SecretMask,
+    /// Locks of the workflows' concurrency groups.
+    concurrency: ConcurrencyGroups,
+    /// Projects whose egress rules apply to the workflows.
+    projects: WorkflowProjects,
+    /// Variables of the workflows' projects.
+    variables: WorkflowVariables,
+    /// Deliveries of webhook delivery nodes.
+    deliveries: WebhookDeliveries,
+    /// Slots of throttle nodes.
+    throttles: Throttles,
+    /// Store binary data of node outputs is moved to.
+    binary_data: BinaryDataService,
+    /// Intents and completions of side-effecting node runs.
+    journal: SideEffectJournal,
+    /// Success, failure and data-loaded counts per workflow.
+    statistics: WorkflowStatistics,
+    /// Executions in flight.
+    progress: ExecutionProgress,
+    /// Values Remove Duplicates nodes saw in earlier executions.
+    processed_data: ProcessedData,
+    /// Consecutive failures and pauses of triggers.
+    trigger_health: TriggerHealth,
+    /// Source of execution IDs in deterministic mode.
+    execution_ids: OnceLock<Determinism>,
 }
 
 impl WorkflowEngine {
     /// Create a new workflow engine.
     pub fn new(config: RuntimeConfig) -> Self {
-        Self {
-            executors: Arc::new(NodeExecutorRegistry::new()),
-            config,
-        }
+        Self::with_executors(NodeExecutorRegistry::new(), config)
     }
 
     /// Create with custom executor registry.
     pub fn with_executors(executors: NodeExecutorRegistry, config: RuntimeConfig) -> Self {
         Self {
             executors: Arc::new(executors),
-            config,
+            config: watch::channel(config).1,
+            sub_workflows: None,
+            sub_executions: Arc::new(SubExecutionTracker::new()),
+            costs: CostAccountant::new(),
+            secrets: SecretMask::global(),
+            concurrency: ConcurrencyGroups::default(),
+            projects: WorkflowProjects::default(),
+            variables: WorkflowVariables::default(),
+            deliveries: WebhookDeliveries::default(),
+            throttles: Throttles::default(),
+            binary_data: BinaryDataService::default(),
+            journal: SideEffectJournal::default(),
+            statistics: WorkflowStatistics::default(),
+            progress: ExecutionProgress::default(),
+            processed_data: ProcessedData::default(),
+            trigger_health: TriggerHealth::default(),
+            execution_ids: OnceLock::new(),
         }
     }
 
+    /// Follow runtime configuration published on a watch channel.
+    ///
+    /// Each execution snapshots the latest value when it starts, so reloads
+    /// never change the settings of an execution that is already running.
+    pub fn with_config_updates(mut self, updates: watch::Receiver<RuntimeConfig>) -> Self {
+        self.config = updates;
+        self
+    }
+
+    /// Let `executeWorkflow` nodes run workflows from `workflows`, subject to
+    /// `RuntimeConfig::sub_executions`. Without this the node passes its
+    /// input through unchanged.
+    pub fn with_sub_workflows(mut self, workflows: Arc<dyn WorkflowStorage>) -> Self {
+        self.sub_workflows = Some(workflows);
+        self
+    }
+
+    /// Price node executions with `hook` in addition to
+    /// `RuntimeConfig::cost`'s pricing table.
+    pub fn with_cost_hook(mut self, hook: Arc<dyn CostHook>) -> Self {
+        self.costs.register(hook);
+        self
+    }
+
+    /// Mask secrets registered in `secrets` instead of the process-wide
+    /// registry.
+    pub fn with_secret_mask(mut self, secrets: SecretMask) -> Self {
+        self.secrets = secrets;
+        self
+    }
+
+    /// Registry of secrets masked out of task errors.
+    pub fn secret_mask(&self) -> &SecretMask {
+        &self.secrets
+    }
+
+    /// Concurrency groups executions wait on; swap in a shared backend
+    /// with [`ConcurrencyGroups::set_backend`].
+    pub fn concurrency_groups(&self) -> &ConcurrencyGroups {
+        &self.concurrency
+    }
+
+    /// Projects of the workflows, for `RuntimeConfig::egress`'s project
+    /// rules; set a lookup with [`WorkflowProjects::set_lookup`].
+    pub fn workflow_projects(&self) -> &WorkflowProjects {
+        &self.projects
+    }
+
+    /// Variables expressions read through `$vars`, those of the workflow's
+    /// project overriding global ones; set a source with
+    /// [`WorkflowVariables::set_source`].
+    pub fn workflow_variables(&self) -> &WorkflowVariables {
+        &self.variables
+    }
+
+    /// Deliveries recorded by webhook delivery nodes; swap in a durable
+    /// store with [`WebhookDeliveries::set_store`].
+    pub fn webhook_deliveries(&self) -> &WebhookDeliveries {
+        &self.deliveries
+    }
+
+    /// Slots of throttle nodes; defer items to a durable queue with
+    /// [`Throttles::set_queue`].
+    pub fn throttles(&self) -> &Throttles {
+        &self.throttles
+    }
+
+    /// Store of binary data moved out of node outputs; set one with
+    /// [`BinaryDataService::set_store`], or data stays inline.
+    pub fn binary_data(&self) -> &BinaryDataService {
+        &self.binary_data
+    }
+
+    /// Journal of side-effecting node runs; keep it durable with
+    /// [`SideEffectJournal::set_backend`].
+    pub fn journal(&self) -> &SideEffectJournal {
+        &self.journal
+    }
+
+    /// Execution counts per workflow, recorded after every execution;
+    /// keep them durable with [`WorkflowStatistics::set_backend`].
+    pub fn statistics(&self) -> &WorkflowStatistics {
+        &self.statistics
+    }
+
+    /// Executions started with [`Self::execute_with_events`] that have not
+    /// returned, with their queue position and current node.
+    pub fn progress(&self) -> &ExecutionProgress {
+        &self.progress
+    }
+
+    /// Values Remove Duplicates nodes deduplicate across executions; keep
+    /// them durable with [`ProcessedData::set_store`].
+    pub fn processed_data(&self) -> &ProcessedData {
+        &self.processed_data
+    }
+
+    /// Health of the webhook and timer triggers, which their callers record
+    /// and check; paused triggers must not start their workflow.
+    pub fn trigger_health(&self) -> &TriggerHealth {
+        &self.trigger_health
+    }
+
+    /// Runtime context of an execution of `workflow`, with its project's
+    /// egress rules and variables. Fails when the project or its variables
+    /// cannot be determined, rather than running with the global ones only.
+    async fn runtime_context(
+        &self,
+        workflow: &Workflow,
+        mode: WorkflowExecuteMode,
+        lineage: ExecutionLineage,
+    ) -> Result<RuntimeContext, ExecutionEngineError> {
+        let config = self.config();
+        let project = if config.egress.projects.is_empty() && !self.variables.has_source() {
+            None
+        } else {
+            self.projects.project_of(&workflow.id).await.map_err(|e| {
+                ExecutionEngineError::Internal(format!(
+                    "Failed to look up the project of workflow {}: {}",
+                    workflow.id, e
+                ))
+            })?
+        };
+        let variables = self.variables.resolve(project.as_deref()).await.map_err(|e| {
+            ExecutionEngineError::Internal(format!(
+                "Failed to read the variables of workflow {}: {}",
+                workflow.id, e
+            ))
+        })?;
+        Ok(RuntimeContext::new(mode, config)
+            .with_lineage(lineage)
+            .with_project(project.as_deref())
+            .with_variables(variables)
+            .with_workflow_id(&workflow.id)
+            .with_deliveries(self.deliveries.clone())
+            .with_throttles(self.throttles.clone())
+            .with_binary_data(self.binary_data.clone())
+            .with_processed_data(self.processed_data.clone()))
+    }
+
+    /// Get the current runtime configuration.
+    pub fn config(&self) -> RuntimeConfig {
+        self.config.borrow().clone()
+    }
+
+    /// ID of a new execution. In deterministic mode they are drawn from a
+    /// seeded RNG, so a new engine hands out the same IDs in the same order.
+    fn new_execution_id(&self) -> String {
+        let config = self.config.borrow();
+        if !config.deterministic.enabled {
+            return uuid::Uuid::new_v4().to_string();
+        }
+        let ids = self.execution_ids.get_or_init(|| Determinism::new(&config.deterministic));
+        ids.uuid().to_string()
+    }
+
     /// Execute a workflow and return the result.
     pub async fn execute(
         &self,
@@ -87,16 +326,84 @@ impl WorkflowEngine {
         mode: WorkflowExecuteMode,
         input_data: Option<Vec<NodeExecutionData>>,
         event_tx: mpsc::Sender<ExecutionEvent>,
+    ) -> Result<Run, ExecutionEngineError> {
+        let execution_id = self.new_execution_id();
+        self.execute_with_id(execution_id, workflow, mode, input_data, event_tx).await
+    }
+
+    /// [`Self::execute_with_events`] under `execution_id`, e.g. one the
+    /// caller already handed out.
+    pub async fn execute_with_id(
+        &self,
+        execution_id: String,
+        workflow: &Workflow,
+        mode: WorkflowExecuteMode,
+        input_data: Option<Vec<NodeExecutionData>>,
+        event_tx: mpsc::Sender<ExecutionEvent>,
+    ) -> Result<Run, ExecutionEngineError> {
+        let span = correlation::execution_span(&execution_id, &workflow.id, &workflow.name);
+        let lineage = ExecutionLineage::root(&execution_id, &workflow.id);
+        // Ungrouped executions never wait, so an invalid key only matters
+        // when acquiring.
+        let group = concurrency::lock_key(workflow, input_data.as_deref()).ok().flatten();
+        let _tracked = self.progress.track(&execution_id, &workflow.id, group);
+        let (event_tx, forwarding) = self.progress.forward(&execution_id, event_tx);
+        let _group = self
+            .concurrency
+            .acquire(workflow, input_data.as_deref())
+            .instrument(span.clone())
+            .await?;
+        let started = ExecutionNotice::started(&execution_id, workflow, mode);
+        execution_webhooks::notify(&self.config.borrow(), &self.deliveries, &started);
+        let execution =
+            self.run_with_events(workflow, mode, input_data, event_tx, execution_id, lineage);
+        let run = if !self.config.borrow().resource_usage.enabled {
+            execution.instrument(span).await
+        } else {
+            let (mut run, usage) = ResourceUsage::measure(execution.instrument(span)).await;
+            if let Ok(run) = &mut run {
+                usage.record(run);
+            }
+            run
+        };
+        // Deliver the last events before the execution stops being tracked.
+        let _ = forwarding.await;
+        self.statistics.record(workflow, mode, run.as_ref().ok()).await;
+        if let Some(finished) = started.finished(&run, &self.secrets) {
+            execution_webhooks::notify(&self.config.borrow(), &self.deliveries, &finished);
+        }
+        run
+    }
+
+    /// Execution loop for [`Self::execute_with_events`], run inside the
+    /// execution span.
+    async fn run_with_events(
+        &self,
+        workflow: &Workflow,
+        mode: WorkflowExecuteMode,
+        input_data: Option<Vec<NodeExecutionData>>,
+        event_tx: mpsc::Sender<ExecutionEvent>,
+        execution_id: String,
+        lineage: ExecutionLineage,
     ) -> Result<Run, ExecutionEngineError> {
         // Validate workflow
         workflow.validate()?;
 
-        // Create runtime context
-        let context = RuntimeContext::new(mode, self.config.clone());
+        // Sub-executions started by this execution count against its quota
+        // until it returns.
+        let _children = self.sub_executions.scope(&execution_id);
 
         // Initialize run
         let mut run = Run::new(mode);
-        let execution_id = uuid::Uuid::new_v4().to_string();
+        if let Some(parent) = lineage.parent() {
+            run.data.parent_execution = Some(RelatedExecution {
+                execution_id: parent.execution_id.clone(),
+                workflow_id: Some(parent.workflow_id.clone()),
+            });
+        }
+
+        // Create runtime context
+        let context = self.runtime_context(workflow, mode, lineage).await?;
 
         // Emit started event
         let _ = event_tx
@@ -115,6 +422,11 @@ impl WorkflowEngine {
         // Build connections by destination for parent lookups
         let _connections_by_dest = graph::map_connections_by_destination(&workflow.connections);
 
+        let integrity = &context.config.integrity;
+        let mut chain = integrity
+            .enabled
+            .then(|| HashChain::new(integrity, &execution_id, &workflow.id));
+
         // Execute nodes from stack
         while let Some(execute_data) = stack.pop_front() {
             // Check for cancellation
@@ -144,9 +456,13 @@ impl WorkflowEngine {
             debug!(node = %node_name, run_index, "Executing node");
 
             // Execute the node (resolving expressions in parameters)
-            let task_data = self
-                .execute_node(&execute_data, &context, &event_tx, &run, &execution_id, workflow)
+            let mut task_data = self
+                .execute_profiled(&execute_data, &context, &event_tx, &run, &execution_id, workflow)
+                .instrument(correlation::node_span(&node_name, &node.node_type, run_index))
                 .await;
+            self.finish_task(
+                &context, &mut chain, &mut run, &execute_data, run_index, &mut task_data,
+            );
 
             // Store result
             run.data
@@ -214,30 +530,7 @@ impl WorkflowEngine {
 
     /// Find start nodes in the workflow.
     fn find_start_nodes(&self, workflow: &Workflow) -> Result<Vec<String>, ExecutionEngineError> {
-        // First try to find trigger nodes
-        let triggers: Vec<_> = workflow
-            .get_trigger_nodes()
-            .into_iter()
-            .map(|n| n.name.clone())
-            .collect();
-
-        if !triggers.is_empty() {
-            return Ok(triggers);
-        }
-
-        // Fall back to nodes with no incoming connections
-        let start_nodes: Vec<_> = workflow
-            .get_start_nodes()
-            .into_iter()
-            .filter(|n| !n.disabled)
-            .map(|n| n.name.clone())
-            .collect();
-
-        if start_nodes.is_empty() {
-            return Err(ExecutionEngineError::NoStartNodes);
-        }
-
-        Ok(start_nodes)
+        find_start_nodes(workflow)
     }
 
     /// Initialize the execution stack.
@@ -273,8 +566,80 @@ impl WorkflowEngine {
         Ok(stack)
     }
 
+    /// Execute a single node, recording its profile on the task when the
+    /// workflow is profiled.
+    async fn execute_profiled(
+        &self,
+        execute_data: &ExecuteData,
+        context: &RuntimeContext,
+        event_tx: &mpsc::Sender<ExecutionEvent>,
+        run: &Run,
+        execution_id: &str,
+        workflow: &Workflow,
+    ) -> TaskData {
+        let profiled = workflow.settings.profile.unwrap_or(context.config.profiling.enabled);
+        let execute_data = &execute_once_input(execute_data);
+        let mut retries = 0;
+        let task = self.execute_node(
+            execute_data,
+            context,
+            event_tx,
+            run,
+            execution_id,
+            workflow,
+            &mut retries,
+        );
+        if !profiled {
+            let mut task = task.await;
+            always_output_data(&execute_data.node, &mut task);
+            self.store_binary_data(&execute_data.node, &mut task, &workflow.id, execution_id)
+                .await;
+            return task;
+        }
+
+        let started = Instant::now();
+        let (mut task, cpu) = CpuTimed::new(task).await;
+        always_output_data(&execute_data.node, &mut task);
+        self.store_binary_data(&execute_data.node, &mut task, &workflow.id, execution_id)
+            .await;
+        NodeProfile::measure(
+            &execute_data.node.node_type,
+            &execute_data.data,
+            &task,
+            started.elapsed(),
+            cpu,
+            retries,
+        )
+        .record(&mut task);
+        task
+    }
+
+    /// Move binary data of `task`'s output to the binary data store,
+    /// failing the task when it cannot be stored.
+    async fn store_binary_data(
+        &self,
+        node: &Node,
+        task: &mut TaskData,
+        workflow_id: &str,
+        execution_id: &str,
+    ) {
+        let Some(outputs) = task.data.as_mut() else {
+            return;
+        };
+        for items in outputs.values_mut().flatten() {
+            if let Err(e) = self.binary_data.offload(workflow_id, execution_id, items).await {
+                task.execution_status = ExecutionStatus::Error;
+                task.error =
+                    Some(n8n_workflow::ExecutionError::new(e.to_string()).with_node(&node.name));
+                return;
+            }
+        }
+    }
+
     /// Execute a single node, resolving any `{{ }}` expressions in its
-    /// parameters before invoking the executor.
+    /// parameters before invoking the executor. `retries` counts attempts
+    /// after the first.
+    #[allow(clippy::too_many_arguments)]
     async fn execute_node(
         &self,
         execute_data: &ExecuteData,
@@ -283,17 +648,108 @@ impl WorkflowEngine {
         run: &Run,
         execution_id: &str,
         workflow: &Workflow,
+        retries: &mut u32,
     ) -> TaskData {
         let mut task_data = TaskData::new();
 
-        // Resolve expressions in node parameters before execution.
-        let resolved_node = self.resolve_node_parameters(
-            &execute_data.node,
-            run,
-            execute_data,
-            execution_id,
-            workflow,
-        );
+        // In manual runs, pinned nodes (mocked ones included) output their
+        // pinned items instead of running.
+        if context.execution_context.source == WorkflowExecuteMode::Manual {
+            let node = &execute_data.node.name;
+            if let Some(items) = workflow.pin_data.as_ref().and_then(|pins| pins.get(node)) {
+                task_data.data = Some(self.format_output(vec![items.clone()]));
+                task_data.execution_status = ExecutionStatus::Success;
+                task_data
+                    .metadata
+                    .get_or_insert_with(TaskMetadata::default)
+                    .custom
+                    .insert(PINNED_KEY.to_string(), GenericValue::Bool(true));
+                task_data.finish();
+                return task_data;
+            }
+        }
+
+        // Resolve expressions in node parameters before execution, those
+        // of its sub-nodes included.
+        let limits = &context.config.expressions;
+        let determinism = context.determinism();
+        let variables = context.variables();
+        let resolved = self
+            .resolve_node_parameters(
+                &execute_data.node,
+                run,
+                execute_data,
+                execution_id,
+                workflow,
+                limits,
+                determinism,
+                variables,
+            )
+            .and_then(|node| {
+                let supplies = self.node_supplies(
+                    execute_data,
+                    run,
+                    execution_id,
+                    workflow,
+                    limits,
+                    determinism,
+                    variables,
+                )?;
+                Ok((node, supplies))
+            });
+        let (resolved_node, supplies) = match resolved {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                task_data.execution_status = ExecutionStatus::Error;
+                task_data.error = Some(
+                    n8n_workflow::ExecutionError::new(e.to_string())
+                        .with_node(&execute_data.node.name),
+                );
+                task_data.finish();
+                return task_data;
+            }
+        };
+        let masking = &context.config.masking;
+        if masking.enabled {
+            self.secrets.register_parameters(&resolved_node.parameters, &masking.secret_parameters);
+        }
+        let supplied_context;
+        let context = match supplies {
+            Some(supplies) => {
+                supplied_context = context.clone().with_supplies(supplies);
+                &supplied_context
+            }
+            None => context,
+        };
+
+        // Sub-workflows run on this engine so limits apply across the chain
+        if resolved_node.node_type == EXECUTE_WORKFLOW_NODE_TYPE {
+            if let Some(workflows) = &self.sub_workflows {
+                let result = self
+                    .execute_sub_workflow(
+                        workflows.as_ref(),
+                        &resolved_node,
+                        &execute_data.data,
+                        context,
+                    )
+                    .await;
+                match result {
+                    Ok(output) => {
+                        task_data.data = Some(self.format_output(output));
+                        task_data.execution_status = ExecutionStatus::Success;
+                    }
+                    Err(e) => {
+                        task_data.execution_status = ExecutionStatus::Error;
+                        task_data.error = Some(
+                            n8n_workflow::ExecutionError::new(e.to_string())
+                                .with_node(&resolved_node.name),
+                        );
+                    }
+                }
+                task_data.finish();
+                return task_data;
+            }
+        }
 
         // Get executor for this node type
         let executor = match self.executors.get(&resolved_node.node_type) {
@@ -322,8 +778,11 @@ impl WorkflowEngine {
         };
 
         let wait_between = resolved_node.wait_between_tries.unwrap_or(1000);
+        let journaled = context.config.journal.enabled
+            && has_side_effects(&resolved_node, &context.config.journal);
 
         for attempt in 0..max_tries {
+            *retries = attempt as u32;
             if attempt > 0 {
                 debug!(
                     node = %resolved_node.name,
@@ -333,10 +792,40 @@ impl WorkflowEngine {
                 tokio::time::sleep(tokio::time::Duration::from_millis(wait_between)).await;
             }
 
-            match executor
-                .execute(&resolved_node, &execute_data.data, context)
-                .await
-            {
+            let intent = if journaled {
+                match self
+                    .journal
+                    .intent(execution_id, &workflow.id, &resolved_node, attempt as u32)
+                    .await
+                {
+                    Ok(entry) => Some(entry),
+                    Err(e) => {
+                        task_data.execution_status = ExecutionStatus::Error;
+                        task_data.error = Some(
+                            n8n_workflow::ExecutionError::new(format!(
+                                "Failed to journal side effect: {}",
+                                e
+                            ))
+                            .with_node(&resolved_node.name),
+                        );
+                        break;
+                    }
+                }
+            } else {
+                None
+            };
+
+            let result = executor.execute(&resolved_node, &execute_data.data, context).await;
+            if let Some(entry) = &intent {
+                let outcome = if result.is_ok() {
+                    JournalOutcome::Succeeded
+                } else {
+                    JournalOutcome::Failed
+                };
+                self.journal.complete(entry, outcome).await;
+            }
+
+            match result {
                 Ok(output) => {
                     task_data.data = Some(self.format_output(output));
                     task_data.execution_status = ExecutionStatus::Success;
@@ -359,6 +848,61 @@ impl WorkflowEngine {
         task_data
     }
 
+    /// Run the workflow named by an `executeWorkflow` node with the node's
+    /// input, returning the sub-workflow's last node output.
+    async fn execute_sub_workflow(
+        &self,
+        workflows: &dyn WorkflowStorage,
+        node: &Node,
+        input: &TaskDataConnections,
+        context: &RuntimeContext,
+    ) -> Result<NodeOutput, ExecutionEngineError> {
+        let workflow_id =
+            sub_workflow_id(node).ok_or_else(|| ExecutionEngineError::NodeExecution {
+                node: node.name.clone(),
+                message: "No workflowId set".to_string(),
+            })?;
+
+        let execution_id = self.new_execution_id();
+        let lineage = self.sub_executions.admit(
+            &context.lineage,
+            &execution_id,
+            &workflow_id,
+            &context.config.sub_executions,
+        )?;
+
+        let workflow = workflows.get_workflow(&workflow_id).await?.ok_or_else(|| {
+            ExecutionEngineError::InvalidState(format!("Sub-workflow not found: {}", workflow_id))
+        })?;
+
+        let items = input.get(CONNECTION_MAIN).and_then(|v| v.first()).cloned();
+        let (tx, _rx) = mpsc::channel(100);
+        let span = correlation::execution_span(&execution_id, &workflow.id, &workflow.name);
+
+        // Boxed: the sub-execution recurses back into this function.
+        let sub_run: Pin<Box<dyn Future<Output = Result<Run, ExecutionEngineError>> + Send + '_>> =
+            Box::pin(
+                self.run_with_events(
+                    &workflow,
+                    WorkflowExecuteMode::Internal,
+                    items,
+                    tx,
+                    execution_id,
+                    lineage,
+                )
+                .instrument(span),
+            );
+        let run = sub_run.await?;
+
+        if let Some(error) = &run.data.result_data.error {
+            return Err(ExecutionEngineError::NodeExecution {
+                node: node.name.clone(),
+                message: format!("Sub-workflow '{}' failed: {}", workflow.name, error.message),
+            });
+        }
+        Ok(vec![last_node_output(&run)])
+    }
+
     /// Format node output into TaskDataConnections.
     fn format_output(&self, output: NodeOutput) -> TaskDataConnections {
         let mut result = TaskDataConnections::new();
@@ -375,9 +919,16 @@ impl WorkflowEngine {
         run_index: usize,
         stack: &mut VecDeque<ExecuteData>,
     ) -> Result<(), ExecutionEngineError> {
+        let mut queued = Vec::new();
+
         // Get connections from this node
         if let Some(node_conns) = workflow.connections.get(source_node) {
             for (conn_type, by_index) in node_conns {
+                // Sub-nodes are resolved by their root node, not queued
+                if is_supply_connection(conn_type) {
+                    continue;
+                }
+
                 // Get output data for this connection type
                 let outputs = output_data.get(conn_type);
 
@@ -422,7 +973,7 @@ impl WorkflowEngine {
                             previous_node_run: Some(run_index),
                         }];
 
-                        stack.push_back(ExecuteData {
+                        queued.push(ExecuteData {
                             node: target_node.clone(),
                             data: input,
                             source: Some(source),
@@ -433,6 +984,17 @@ impl WorkflowEngine {
             }
         }
 
+        match workflow.execution_order() {
+            ExecutionOrder::V0 => stack.extend(queued),
+            ExecutionOrder::V1 => {
+                // Top-most child first, ahead of everything already queued
+                queued.sort_by(|a, b| canvas_order(&a.node, &b.node));
+                for execute_data in queued.into_iter().rev() {
+                    stack.push_front(execute_data);
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -443,6 +1005,28 @@ impl WorkflowEngine {
         start_nodes: Vec<String>,
         destination_node: Option<String>,
         input_data: Option<Vec<NodeExecutionData>>,
+    ) -> Result<Run, ExecutionEngineError> {
+        let execution_id = self.new_execution_id();
+        let span = correlation::execution_span(&execution_id, &workflow.id, &workflow.name);
+        let _group = self
+            .concurrency
+            .acquire(workflow, input_data.as_deref())
+            .instrument(span.clone())
+            .await?;
+        self.run_partial(workflow, start_nodes, destination_node, input_data, execution_id)
+            .instrument(span)
+            .await
+    }
+
+    /// Execution loop for [`Self::execute_partial`], run inside the
+    /// execution span.
+    async fn run_partial(
+        &self,
+        workflow: &Workflow,
+        start_nodes: Vec<String>,
+        destination_node: Option<String>,
+        input_data: Option<Vec<NodeExecutionData>>,
+        execution_id: String,
     ) -> Result<Run, ExecutionEngineError> {
         // Validate start nodes exist
         for name in &start_nodes {
@@ -456,10 +1040,20 @@ impl WorkflowEngine {
 
         // Execute with the specific start nodes
         let (tx, _rx) = mpsc::channel(100);
-        let context = RuntimeContext::new(WorkflowExecuteMode::Manual, self.config.clone());
+        let _children = self.sub_executions.scope(&execution_id);
+        let context = self
+            .runtime_context(
+                workflow,
+                WorkflowExecuteMode::Manual,
+                ExecutionLineage::root(&execution_id, &workflow.id),
+            )
+            .await?;
 
         let mut run = Run::new(WorkflowExecuteMode::Manual);
-        let execution_id = uuid::Uuid::new_v4().to_string();
+        let integrity = &context.config.integrity;
+        let mut chain = integrity
+            .enabled
+            .then(|| HashChain::new(integrity, &execution_id, &workflow.id));
 
         // Initialize stack with specified start nodes
         let mut stack = self.initialize_stack(
@@ -494,12 +1088,26 @@ impl WorkflowEngine {
                 }
             }
 
+            let run_index = run
+                .data
+                .result_data
+                .run_data
+                .get(&node_name)
+                .map(|v| v.len())
+                .unwrap_or(0);
+            let node_span =
+                correlation::node_span(&node_name, &execute_data.node.node_type, run_index);
+
             // Stop at destination
             if destination_node.as_ref() == Some(&node_name) {
                 // Execute destination node
-                let task_data = self
-                    .execute_node(&execute_data, &context, &tx, &run, &execution_id, workflow)
+                let mut task_data = self
+                    .execute_profiled(&execute_data, &context, &tx, &run, &execution_id, workflow)
+                    .instrument(node_span)
                     .await;
+                self.finish_task(
+                    &context, &mut chain, &mut run, &execute_data, run_index, &mut task_data,
+                );
                 run.data
                     .result_data
                     .run_data
@@ -509,17 +1117,13 @@ impl WorkflowEngine {
                 break;
             }
 
-            let run_index = run
-                .data
-                .result_data
-                .run_data
-                .get(&node_name)
-                .map(|v| v.len())
-                .unwrap_or(0);
-
-            let task_data = self
-                .execute_node(&execute_data, &context, &tx, &run, &execution_id, workflow)
+            let mut task_data = self
+                .execute_profiled(&execute_data, &context, &tx, &run, &execution_id, workflow)
+                .instrument(node_span)
                 .await;
+            self.finish_task(
+                &context, &mut chain, &mut run, &execute_data, run_index, &mut task_data,
+            );
 
             run.data
                 .result_data
@@ -539,6 +1143,48 @@ impl WorkflowEngine {
         Ok(run)
     }
 
+    /// Price a finished task and link it into the hash chain before it is
+    /// stored in the run.
+    fn finish_task(
+        &self,
+        context: &RuntimeContext,
+        chain: &mut Option<HashChain>,
+        run: &mut Run,
+        execute_data: &ExecuteData,
+        run_index: usize,
+        task: &mut TaskData,
+    ) {
+        let node = &execute_data.node;
+        if context.config.masking.enabled {
+            if let Some(error) = task.error.as_mut() {
+                self.secrets.mask_error(error);
+            }
+        }
+        if context.config.cost.enabled {
+            self.costs.account(&context.config.cost, node, run, task);
+        }
+        if let Some(chain) = chain {
+            chain.append(run, &node.name, run_index, &execute_data.data, task);
+        }
+    }
+
+    /// Describe what executing the workflow would do, without running it.
+    pub fn explain(&self, workflow: &Workflow) -> Result<ExecutionPlan, ExecutionEngineError> {
+        explain::explain(workflow, &self.executors)
+    }
+
+    /// The run as it should be persisted, after applying the workflow's
+    /// sampling policy on top of the runtime default.
+    pub fn prepare_for_storage<'a>(
+        &self,
+        workflow: &Workflow,
+        execution_id: &str,
+        run: &'a Run,
+    ) -> Cow<'a, Run> {
+        let policy = self.config.borrow().sampling.for_workflow(&workflow.settings);
+        policy.apply(execution_id, run)
+    }
+
     // ========================================================================
     // Expression Resolution
     // ========================================================================
@@ -644,6 +1290,46 @@ impl WorkflowEngine {
         node_data
     }
 
+    /// Sub-nodes of `execute_data`'s node, directly or through other
+    /// sub-nodes, for it to resolve when it asks for them. Their parameters
+    /// are resolved now, against its input. `None` without sub-nodes.
+    #[allow(clippy::too_many_arguments)]
+    fn node_supplies(
+        &self,
+        execute_data: &ExecuteData,
+        run: &Run,
+        execution_id: &str,
+        workflow: &Workflow,
+        limits: &ExpressionLimits,
+        determinism: &Determinism,
+        variables: &HashMap<String, serde_json::Value>,
+    ) -> Result<Option<NodeSupplies>, ExpressionError> {
+        let sub_nodes = supply::sub_nodes_of(workflow, &execute_data.node.name);
+        if sub_nodes.is_empty() {
+            return Ok(None);
+        }
+        let mut resolved = workflow.clone();
+        for name in &sub_nodes {
+            if let Some(node) = resolved.get_node_mut(name) {
+                *node = self.resolve_node_parameters(
+                    node,
+                    run,
+                    execute_data,
+                    execution_id,
+                    workflow,
+                    limits,
+                    determinism,
+                    variables,
+                )?;
+            }
+        }
+        Ok(Some(NodeSupplies::new(
+            &execute_data.node.name,
+            Arc::new(resolved),
+            self.executors.clone(),
+        )))
+    }
+
     /// Resolve expressions in a node's parameters.
     ///
     /// For each item in the input data, this builds an `ExpressionContext` and
@@ -652,7 +1338,12 @@ impl WorkflowEngine {
     /// once per node execution, not per item).
     ///
     /// If resolution fails for any parameter, the original value is kept and a
-    /// warning is logged.
+    /// warning is logged. Values interpolated into strings are bounded by
+    /// `limits`; an oversized value under [`OversizeMode::Error`] fails the
+    /// node instead.
+    ///
+    /// [`OversizeMode::Error`]: crate::expression::OversizeMode::Error
+    #[allow(clippy::too_many_arguments)]
     fn resolve_node_parameters(
         &self,
         node: &Node,
@@ -660,10 +1351,13 @@ impl WorkflowEngine {
         execute_data: &ExecuteData,
         execution_id: &str,
         workflow: &Workflow,
-    ) -> Node {
+        limits: &ExpressionLimits,
+        determinism: &Determinism,
+        variables: &HashMap<String, serde_json::Value>,
+    ) -> Result<Node, ExpressionError> {
         // Fast path: skip if no parameters contain expressions.
         if !Self::params_contain_expression(&node.parameters) {
-            return node.clone();
+            return Ok(node.clone());
         }
 
         // Build node_data for expression context from existing run results.
@@ -690,7 +1384,6 @@ impl WorkflowEngine {
             .unwrap_or(0);
 
         // We need owned data for the statics used by ExpressionContext.
-        let empty_vars: HashMap<String, serde_json::Value> = HashMap::new();
         let empty_env: HashMap<String, String> = HashMap::new();
 
         let context = ExpressionContext {
@@ -698,15 +1391,19 @@ impl WorkflowEngine {
             item_index: 0,
             run_index,
             node_data: &node_data,
-            variables: &empty_vars,
+            run_data: Some(&run.data.result_data.run_data),
+            variables,
             env: &empty_env,
             execution_id,
             workflow_id: &workflow.id,
             workflow_name: &workflow.name,
             node_name: &node.name,
+            locals: None,
+            determinism,
         };
 
         // Resolve each parameter.
+        let evaluator = ExpressionEvaluator::with_limits(limits.clone());
         let mut resolved_node = node.clone();
         for (key, value) in &node.parameters {
             if !Self::value_contains_expression(value) {
@@ -714,12 +1411,13 @@ impl WorkflowEngine {
             }
 
             let json_value = Self::param_to_json(value);
-            match expression::resolve_parameter(&json_value, &context) {
+            match evaluator.resolve(&json_value, &context) {
                 Ok(resolved) => {
                     resolved_node
                         .parameters
                         .insert(key.clone(), Self::json_to_param(&resolved));
                 }
+                Err(e @ ExpressionError::ResultTooLarge { .. }) => return Err(e),
                 Err(e) => {
                     warn!(
                         node = %node.name,
@@ -732,7 +1430,7 @@ impl WorkflowEngine {
             }
         }
 
-        resolved_node
+        Ok(resolved_node)
     }
 }
 
@@ -741,3 +1439,85 @@ impl Default for WorkflowEngine {
         Self::new(RuntimeConfig::default())
     }
 }
+
+/// The input of a node set to `executeOnce`: the first item of each of its
+/// inputs. Other nodes keep their input.
+fn execute_once_input(execute_data: &ExecuteData) -> Cow<'_, ExecuteData> {
+    if !execute_data.node.execute_once {
+        return Cow::Borrowed(execute_data);
+    }
+    let mut once = execute_data.clone();
+    for items in once.data.values_mut().flatten() {
+        items.truncate(1);
+    }
+    Cow::Owned(once)
+}
+
+/// Give a successful node set to `alwaysOutputData` that output no items
+/// one empty item on its first output, so the nodes after it still run.
+fn always_output_data(node: &Node, task: &mut TaskData) {
+    if !node.always_output_data || task.execution_status != ExecutionStatus::Success {
+        return;
+    }
+    let outputs = task
+        .data
+        .get_or_insert_with(TaskDataConnections::new)
+        .entry(CONNECTION_MAIN.to_string())
+        .or_default();
+    if outputs.iter().all(|items| items.is_empty()) {
+        if outputs.is_empty() {
+            outputs.push(Vec::new());
+        }
+        outputs[0].push(NodeExecutionData::default());
+    }
+}
+
+/// `v1` order of sibling nodes: top to bottom, then left to right.
+fn canvas_order(a: &Node, b: &Node) -> std::cmp::Ordering {
+    let [ax, ay] = a.position;
+    let [bx, by] = b.position;
+    ay.total_cmp(&by).then(ax.total_cmp(&bx))
+}
+
+/// Find the nodes an execution starts from: trigger nodes, or else enabled
+/// nodes with no incoming connections.
+pub(crate) fn find_start_nodes(workflow: &Workflow) -> Result<Vec<String>, ExecutionEngineError> {
+    // First try to find trigger nodes
+    let triggers: Vec<_> = workflow
+        .get_trigger_nodes()
+        .into_iter()
+        .map(|n| n.name.clone())
+        .collect();
+
+    if !triggers.is_empty() {
+        return Ok(triggers);
+    }
+
+    // Fall back to nodes with no incoming connections
+    let start_nodes: Vec<_> = workflow
+        .get_start_nodes()
+        .into_iter()
+        .filter(|n| !n.disabled)
+        .map(|n| n.name.clone())
+        .collect();
+
+    if start_nodes.is_empty() {
+        return Err(ExecutionEngineError::NoStartNodes);
+    }
+
+    Ok(start_nodes)
+}
+
+/// `workflowId` of an `executeWorkflow` node, as a plain string or a
+/// resource locator (`{ "__rl": true, "value": "..." }`).
+fn sub_workflow_id(node: &Node) -> Option<String> {
+    match node.parameters.get("workflowId")? {
+        NodeParameterValue::String(id) if !id.is_empty() => Some(id.clone()),
+        NodeParameterValue::Number(n) => Some(n.to_string()),
+        NodeParameterValue::Object(locator) => match locator.get("value")? {
+            NodeParameterValue::String(id) if !id.is_empty() => Some(id.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -36,6 +36,25 @@ pub enum ExecutionEngineError {
     #[error("Expression evaluation error: {0}")]
     Expression(String),
 
+    #[error("Data conversion error: {0}")]
+    Data(String),
+
+    #[error("Sub-workflow cycle: {0}")]
+    SubExecutionCycle(String),
+
+    #[error("Sub-execution depth limit of {max} exceeded: {path}")]
+    SubExecutionDepth { max: usize, path: String },
+
+    #[error("Execution {execution_id} reached its limit of {max} sub-executions")]
+    SubExecutionQuota { execution_id: String, max: usize },
+
+    #[error("Concurrency group error: {0}")]
+    ConcurrencyGroup(String),
+
+    /// A save lost to a concurrent one; see [`crate::storage::WorkflowStorage`].
+    #[error("Version conflict: {0}")]
+    VersionConflict(String),
+
     #[error("Storage error: {0}")]
     Storage(String),
 
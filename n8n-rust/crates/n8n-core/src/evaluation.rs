@@ -0,0 +1,590 @@
+//! Evaluations: regression tests of a workflow against past executions.
+//!
+//! A [`TestSet`] holds [`TestCase`]s, each an input snapshot (the items its
+//! start node emitted) with the items the checked node is expected to
+//! output. [`TestCase::from_run`] takes both from a stored execution.
+//! [`evaluate`] replays every case through a workflow version, starting
+//! from the snapshot, and scores what the checked node (`outputNode`, or
+//! the last node executed) outputs:
+//!
+//! - `expected`: the share of expected items the output matches at the
+//!   same index, after dropping the top-level `ignoreFields` (timestamps,
+//!   IDs) from both;
+//! - each [`Scorer`]: an expression evaluated per output item, with `$json`
+//!   the item and `$expected` the expected item at its index. `true` and
+//!   `false` count 1 and 0, numbers are clamped to 0..=1; the score is the
+//!   mean over the items. This is how LLM outputs are checked, whose exact
+//!   wording changes from run to run:
+//!   `{{ $json.category == $expected.category }}`,
+//!   `{{ $json.answer.length < 400 }}`.
+//!
+//! A case passes when it runs without error and every score reaches its
+//! threshold (1 for `expected`). [`Evaluations`] keeps test sets and the
+//! reports of their runs.
+
+use crate::engine::WorkflowEngine;
+use crate::expression::{parse_template, ExpressionContext, ExpressionEvaluator};
+use crate::sub_execution::last_node_output;
+use chrono::{DateTime, Utc};
+use n8n_workflow::{
+    connection::CONNECTION_MAIN, object_to_json, NodeExecutionData, Run, TaskData, Workflow,
+};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use thiserror::Error;
+
+/// Reports kept per test set before dropping the oldest.
+const REPORTS_PER_SET: usize = 50;
+
+/// Name of the score comparing output with expected items.
+pub const EXPECTED_SCORE: &str = "expected";
+
+/// Errors of test sets.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EvaluationError {
+    #[error("{0} not found")]
+    NotFound(String),
+
+    #[error("Invalid test set: {0}")]
+    Invalid(String),
+}
+
+/// Expression scoring the output items of a case.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scorer {
+    pub name: String,
+    /// `{{ ... }}` expression returning a boolean or a number in 0..=1.
+    pub expression: String,
+    /// Score the case needs to pass.
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+}
+
+fn default_threshold() -> f64 {
+    1.0
+}
+
+/// One recorded input with its expected output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCase {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Execution the case was taken from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_id: Option<String>,
+    /// Items the start node is run with.
+    pub input: Vec<Value>,
+    /// Items the checked node should output; unchecked when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Vec<Value>>,
+}
+
+impl TestCase {
+    /// Take a case from a past execution: the first output of `start_node`
+    /// as input, and the output of `output_node` (or of the last node
+    /// executed) as expected items.
+    pub fn from_run(
+        execution_id: &str,
+        run: &Run,
+        start_node: &str,
+        output_node: Option<&str>,
+    ) -> Result<Self, EvaluationError> {
+        let input = first_output(run, start_node).ok_or_else(|| {
+            EvaluationError::Invalid(format!(
+                "Node '{}' did not run in execution {}",
+                start_node, execution_id
+            ))
+        })?;
+        let expected = match output_node {
+            Some(node) => last_output(run, node).unwrap_or_default(),
+            None => last_node_output(run),
+        };
+
+        Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: None,
+            execution_id: Some(execution_id.to_string()),
+            input: to_values(&input),
+            expected: Some(to_values(&expected)),
+        })
+    }
+}
+
+/// Cases a workflow is checked against.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestSet {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub workflow_id: String,
+    /// Node cases start from, usually the trigger.
+    pub start_node: String,
+    /// Node whose output is checked; the last node executed when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_node: Option<String>,
+    /// Top-level fields left out when comparing with expected items.
+    #[serde(default)]
+    pub ignore_fields: Vec<String>,
+    #[serde(default)]
+    pub scorers: Vec<Scorer>,
+    #[serde(default)]
+    pub cases: Vec<TestCase>,
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TestSet {
+    /// Check the set can be run.
+    pub fn validate(&self) -> Result<(), EvaluationError> {
+        let invalid = |message: String| Err(EvaluationError::Invalid(message));
+        if self.name.trim().is_empty() {
+            return invalid("name is required".to_string());
+        }
+        if self.start_node.trim().is_empty() {
+            return invalid("startNode is required".to_string());
+        }
+        for scorer in &self.scorers {
+            if scorer.name.is_empty() || scorer.name == EXPECTED_SCORE {
+                return invalid(format!("scorer name '{}' is not allowed", scorer.name));
+            }
+            if !(0.0..=1.0).contains(&scorer.threshold) {
+                return invalid(format!("threshold of scorer '{}' is not in 0..=1", scorer.name));
+            }
+            if let Err(e) = parse_template(expression_body(&scorer.expression)) {
+                return invalid(format!("scorer '{}': {}", scorer.name, e));
+            }
+        }
+        if let Some(case) = self.cases.iter().find(|case| case.input.is_empty()) {
+            return invalid(format!("case {} has no input items", case.id));
+        }
+        Ok(())
+    }
+}
+
+/// Score of one scorer for a case.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreResult {
+    pub name: String,
+    pub score: f64,
+    pub threshold: f64,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Outcome of one case.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaseResult {
+    pub case_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub passed: bool,
+    /// Mean of the scores; 0 when the case failed to run.
+    pub score: f64,
+    pub scores: Vec<ScoreResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    /// Items the checked node output.
+    pub output: Vec<Value>,
+}
+
+/// Outcome of running a test set against a workflow version.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluationReport {
+    pub id: String,
+    pub test_set_id: String,
+    pub workflow_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    /// Mean score of the cases.
+    pub score: f64,
+    pub cases: Vec<CaseResult>,
+}
+
+/// Run every case of `set` through `workflow` and score the outputs.
+pub async fn evaluate(engine: &WorkflowEngine, workflow: &Workflow, set: &TestSet) -> EvaluationReport {
+    let started_at = Utc::now();
+    let mut cases = Vec::with_capacity(set.cases.len());
+    for case in &set.cases {
+        cases.push(run_case(engine, workflow, set, case).await);
+    }
+
+    let passed = cases.iter().filter(|case| case.passed).count();
+    EvaluationReport {
+        id: uuid::Uuid::new_v4().to_string(),
+        test_set_id: set.id.clone(),
+        workflow_id: workflow.id.clone(),
+        version_id: workflow.version_id.clone(),
+        started_at,
+        finished_at: Utc::now(),
+        total: cases.len(),
+        passed,
+        failed: cases.len() - passed,
+        score: mean(cases.iter().map(|case| case.score)),
+        cases,
+    }
+}
+
+async fn run_case(
+    engine: &WorkflowEngine,
+    workflow: &Workflow,
+    set: &TestSet,
+    case: &TestCase,
+) -> CaseResult {
+    let started = Instant::now();
+    let failed = |error: String| CaseResult {
+        case_id: case.id.clone(),
+        name: case.name.clone(),
+        passed: false,
+        score: 0.0,
+        scores: Vec::new(),
+        error: Some(error),
+        duration_ms: started.elapsed().as_millis() as u64,
+        output: Vec::new(),
+    };
+
+    let input: Result<Vec<_>, _> =
+        case.input.iter().cloned().map(NodeExecutionData::from_json_value).collect();
+    let input = match input {
+        Ok(input) => input,
+        Err(e) => return failed(format!("Invalid input item: {}", e)),
+    };
+
+    let run = match engine
+        .execute_partial(workflow, vec![set.start_node.clone()], None, Some(input))
+        .await
+    {
+        Ok(run) => run,
+        Err(e) => return failed(e.to_string()),
+    };
+    if let Some(error) = &run.data.result_data.error {
+        return failed(error.message.clone());
+    }
+
+    let output = match &set.output_node {
+        Some(node) => match last_output(&run, node) {
+            Some(output) => output,
+            None => return failed(format!("Node '{}' did not run", node)),
+        },
+        None => last_node_output(&run),
+    };
+    let output = to_values(&output);
+    let scores = score_case(set, case, &output);
+
+    CaseResult {
+        case_id: case.id.clone(),
+        name: case.name.clone(),
+        passed: scores.iter().all(|score| score.passed),
+        score: if scores.is_empty() { 1.0 } else { mean(scores.iter().map(|s| s.score)) },
+        scores,
+        error: None,
+        duration_ms: started.elapsed().as_millis() as u64,
+        output,
+    }
+}
+
+/// Scores of the items a case output.
+pub fn score_case(set: &TestSet, case: &TestCase, output: &[Value]) -> Vec<ScoreResult> {
+    let mut scores = Vec::with_capacity(set.scorers.len() + 1);
+
+    if let Some(expected) = &case.expected {
+        let len = expected.len().max(output.len());
+        let matched = expected
+            .iter()
+            .zip(output)
+            .filter(|(expected, actual)| {
+                without(expected, &set.ignore_fields) == without(actual, &set.ignore_fields)
+            })
+            .count();
+        let score = if len == 0 { 1.0 } else { matched as f64 / len as f64 };
+        scores.push(ScoreResult {
+            name: EXPECTED_SCORE.to_string(),
+            score,
+            threshold: 1.0,
+            passed: score >= 1.0,
+            error: None,
+        });
+    }
+
+    for scorer in &set.scorers {
+        let result = if output.is_empty() {
+            Err("no output items".to_string())
+        } else {
+            output
+                .iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    let expected = case
+                        .expected
+                        .as_ref()
+                        .and_then(|expected| expected.get(index))
+                        .cloned()
+                        .unwrap_or(Value::Null);
+                    score_item(&scorer.expression, item, expected)
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(|item_scores| mean(item_scores.into_iter()))
+        };
+
+        scores.push(match result {
+            Ok(score) => ScoreResult {
+                name: scorer.name.clone(),
+                score,
+                threshold: scorer.threshold,
+                passed: score >= scorer.threshold,
+                error: None,
+            },
+            Err(error) => ScoreResult {
+                name: scorer.name.clone(),
+                score: 0.0,
+                threshold: scorer.threshold,
+                passed: false,
+                error: Some(error),
+            },
+        });
+    }
+
+    scores
+}
+
+fn score_item(expression: &str, item: &Value, expected: Value) -> Result<f64, String> {
+    let item = NodeExecutionData::from_json_value(item.clone()).map_err(|e| e.to_string())?;
+    let locals = HashMap::from([("expected".to_string(), expected)]);
+    let mut context = ExpressionContext::minimal(&item);
+    context.locals = Some(&locals);
+
+    let expr = parse_template(expression_body(expression)).map_err(|e| e.to_string())?;
+    match ExpressionEvaluator::new().evaluate(&expr, &context).map_err(|e| e.to_string())? {
+        Value::Bool(passed) => Ok(if passed { 1.0 } else { 0.0 }),
+        Value::Number(n) => Ok(n.as_f64().unwrap_or(0.0).clamp(0.0, 1.0)),
+        Value::Null => Ok(0.0),
+        other => Err(format!("expected a boolean or a number, got {}", other)),
+    }
+}
+
+/// Expressions may keep the `=` prefix of expression parameters.
+fn expression_body(expression: &str) -> &str {
+    expression.strip_prefix('=').unwrap_or(expression)
+}
+
+fn without(item: &Value, fields: &[String]) -> Value {
+    match item {
+        Value::Object(map) if !fields.is_empty() => Value::Object(
+            map.iter()
+                .filter(|(key, _)| !fields.contains(key))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        ),
+        _ => item.clone(),
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f64
+    }
+}
+
+fn first_output(run: &Run, node: &str) -> Option<Vec<NodeExecutionData>> {
+    main_output(run.data.result_data.run_data.get(node)?.first()?)
+}
+
+fn last_output(run: &Run, node: &str) -> Option<Vec<NodeExecutionData>> {
+    main_output(run.data.result_data.run_data.get(node)?.last()?)
+}
+
+fn main_output(task: &TaskData) -> Option<Vec<NodeExecutionData>> {
+    Some(task.data.as_ref()?.get(CONNECTION_MAIN)?.first().cloned().unwrap_or_default())
+}
+
+fn to_values(items: &[NodeExecutionData]) -> Vec<Value> {
+    items
+        .iter()
+        .map(|item| Value::Object(object_to_json(&item.json)))
+        .collect()
+}
+
+/// Test sets and the reports of their runs, kept in memory.
+#[derive(Clone, Default)]
+pub struct Evaluations {
+    sets: Arc<RwLock<HashMap<String, TestSet>>>,
+    reports: Arc<RwLock<HashMap<String, Vec<EvaluationReport>>>>,
+}
+
+impl Evaluations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create or replace a test set, giving it and its cases IDs.
+    pub fn save_set(&self, mut set: TestSet) -> Result<TestSet, EvaluationError> {
+        set.validate()?;
+        if set.id.is_empty() {
+            set.id = uuid::Uuid::new_v4().to_string();
+        }
+        for case in &mut set.cases {
+            if case.id.is_empty() {
+                case.id = uuid::Uuid::new_v4().to_string();
+            }
+        }
+        set.updated_at = Utc::now();
+        self.sets.write().insert(set.id.clone(), set.clone());
+        Ok(set)
+    }
+
+    pub fn get_set(&self, id: &str) -> Result<TestSet, EvaluationError> {
+        self.sets
+            .read()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| EvaluationError::NotFound(format!("Test set {}", id)))
+    }
+
+    /// Test sets of one workflow, or all, oldest first.
+    pub fn list_sets(&self, workflow_id: Option<&str>) -> Vec<TestSet> {
+        let mut sets: Vec<TestSet> = self
+            .sets
+            .read()
+            .values()
+            .filter(|set| workflow_id.is_none_or(|id| set.workflow_id == id))
+            .cloned()
+            .collect();
+        sets.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        sets
+    }
+
+    /// Delete a test set and its reports.
+    pub fn delete_set(&self, id: &str) -> bool {
+        self.reports.write().remove(id);
+        self.sets.write().remove(id).is_some()
+    }
+
+    /// Keep the report of a run.
+    pub fn record(&self, report: EvaluationReport) {
+        let mut reports = self.reports.write();
+        let reports = reports.entry(report.test_set_id.clone()).or_default();
+        reports.insert(0, report);
+        reports.truncate(REPORTS_PER_SET);
+    }
+
+    /// Reports of a test set, newest first.
+    pub fn reports(&self, test_set_id: &str) -> Vec<EvaluationReport> {
+        self.reports.read().get(test_set_id).cloned().unwrap_or_default()
+    }
+
+    pub fn report(&self, id: &str) -> Result<EvaluationReport, EvaluationError> {
+        self.reports
+            .read()
+            .values()
+            .flatten()
+            .find(|report| report.id == id)
+            .cloned()
+            .ok_or_else(|| EvaluationError::NotFound(format!("Report {}", id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::RuntimeConfig;
+    use n8n_workflow::{Node, WorkflowBuilder};
+    use serde_json::json;
+
+    fn test_set(cases: Vec<TestCase>, scorers: Vec<Scorer>) -> TestSet {
+        TestSet {
+            id: String::new(),
+            name: "Triage".to_string(),
+            workflow_id: "wf1".to_string(),
+            start_node: "Start".to_string(),
+            output_node: None,
+            ignore_fields: vec!["at".to_string()],
+            scorers,
+            cases,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_score_case_with_expected_items_and_expressions() {
+        let case = TestCase {
+            id: "c1".to_string(),
+            name: None,
+            execution_id: None,
+            input: vec![json!({ "text": "refund please" })],
+            expected: Some(vec![json!({ "category": "billing", "at": 1 })]),
+        };
+        let set = test_set(
+            vec![case.clone()],
+            vec![Scorer {
+                name: "category".to_string(),
+                expression: "={{ $json.category == $expected.category }}".to_string(),
+                threshold: 1.0,
+            }],
+        );
+
+        let scores = score_case(&set, &case, &[json!({ "category": "billing", "at": 2 })]);
+        assert_eq!(scores.len(), 2);
+        assert!(scores.iter().all(|score| score.passed && score.score == 1.0));
+
+        let scores = score_case(&set, &case, &[json!({ "category": "sales", "at": 1 })]);
+        assert!(scores.iter().all(|score| !score.passed && score.score == 0.0));
+
+        let scores = score_case(&set, &case, &[]);
+        assert_eq!(scores[1].error.as_deref(), Some("no output items"));
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_replays_case_from_execution() {
+        let workflow = WorkflowBuilder::new("Echo")
+            .node(Node::new("Start", "n8n-nodes-base.manualTrigger"))
+            .node(Node::new("Done", "n8n-nodes-base.noOp"))
+            .connect("Start", "Done", 0, 0)
+            .unwrap()
+            .build()
+            .unwrap();
+        let engine = WorkflowEngine::new(RuntimeConfig::default());
+        let item = NodeExecutionData::from_json_value(json!({ "id": 7 })).unwrap();
+        let run = engine
+            .execute_partial(&workflow, vec!["Start".to_string()], None, Some(vec![item]))
+            .await
+            .unwrap();
+
+        let case = TestCase::from_run("e1", &run, "Start", Some("Done")).unwrap();
+        assert_eq!(case.input, vec![json!({ "id": 7 })]);
+        assert_eq!(case.expected, Some(vec![json!({ "id": 7 })]));
+        assert!(TestCase::from_run("e1", &run, "Missing", None).is_err());
+
+        let evaluations = Evaluations::new();
+        let set = evaluations.save_set(test_set(vec![case], Vec::new())).unwrap();
+        let report = evaluate(&engine, &workflow, &set).await;
+        assert_eq!((report.total, report.passed, report.score), (1, 1, 1.0));
+
+        evaluations.record(report.clone());
+        assert_eq!(evaluations.reports(&set.id), vec![report]);
+    }
+}
@@ -0,0 +1,294 @@
+//! Execution webhooks: instance-level callbacks on execution lifecycle
+//! events.
+//!
+//! Every endpoint in [`ExecutionWebhooksConfig::endpoints`] is POSTed an
+//! [`ExecutionNotice`] when an execution starts and when it ends in
+//! success, failure or cancelation, so incident tooling can react without
+//! polling. An endpoint takes every event unless it lists the ones it wants
+//! in `events`. Sub-executions are not announced; their parent is.
+//!
+//! Notices go out like webhook delivery nodes' payloads: signed with the
+//! endpoint's `secret` (or the environment variable `secret_env`) in
+//! `X-N8n-Signature`, retried per `retry`, and recorded in the engine's
+//! [`WebhookDeliveries`] under the node name `execution.<event>`, where they
+//! can be listed and redelivered. They are sent in the background and
+//! never delay or fail the execution. Each one is delivered on its own, so
+//! a retried `started` may arrive after the end of its execution; `at`
+//! orders them.
+//!
+//! Requests go through the global egress rules of `RuntimeConfig::egress`.
+
+use crate::delivery::{Delivery, RetryPolicy, WebhookDeliveries};
+use crate::error::ExecutionEngineError;
+use crate::masking::SecretMask;
+use crate::runtime::RuntimeConfig;
+use chrono::{DateTime, Utc};
+use n8n_workflow::{ExecutionStatus, Run, Workflow, WorkflowExecuteMode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Lifecycle event of an execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LifecycleEvent {
+    Started,
+    Success,
+    Failure,
+    Canceled,
+}
+
+impl LifecycleEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Started => "started",
+            Self::Success => "success",
+            Self::Failure => "failure",
+            Self::Canceled => "canceled",
+        }
+    }
+
+    /// Event ending an execution with `result`; none while it waits.
+    pub fn finished(result: &Result<Run, ExecutionEngineError>) -> Option<Self> {
+        match result {
+            Err(ExecutionEngineError::Canceled) => Some(Self::Canceled),
+            Err(_) => Some(Self::Failure),
+            Ok(run) => match run.status {
+                ExecutionStatus::Success => Some(Self::Success),
+                ExecutionStatus::Canceled => Some(Self::Canceled),
+                ExecutionStatus::Error | ExecutionStatus::Crashed => Some(Self::Failure),
+                ExecutionStatus::New | ExecutionStatus::Running | ExecutionStatus::Waiting => {
+                    None
+                }
+            },
+        }
+    }
+}
+
+/// An endpoint notified of execution events.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExecutionWebhook {
+    pub url: String,
+    /// Events sent to the endpoint; all when empty.
+    pub events: Vec<LifecycleEvent>,
+    /// Signing secret; empty sends unsigned notices.
+    pub secret: String,
+    /// Environment variable holding the signing secret instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_env: Option<String>,
+    pub retry: RetryPolicy,
+}
+
+impl ExecutionWebhook {
+    /// Whether the endpoint takes `event`.
+    pub fn subscribes(&self, event: LifecycleEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+
+    /// The signing secret, `None` when `secret_env` is not set.
+    fn signing_secret(&self) -> Option<String> {
+        match &self.secret_env {
+            Some(name) if self.secret.is_empty() => std::env::var(name).ok(),
+            _ => Some(self.secret.clone()),
+        }
+    }
+}
+
+/// Execution webhook settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExecutionWebhooksConfig {
+    pub endpoints: Vec<ExecutionWebhook>,
+    /// Timeout of each request in milliseconds.
+    pub timeout_ms: u64,
+}
+
+impl Default for ExecutionWebhooksConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            timeout_ms: 10_000,
+        }
+    }
+}
+
+/// What an endpoint is told about an execution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionNotice {
+    pub event: LifecycleEvent,
+    pub execution_id: String,
+    pub workflow_id: String,
+    pub workflow_name: String,
+    pub mode: WorkflowExecuteMode,
+    pub status: ExecutionStatus,
+    pub started_at: DateTime<Utc>,
+    pub stopped_at: Option<DateTime<Utc>>,
+    /// Error ending a failed execution, with secrets masked.
+    pub error: Option<String>,
+}
+
+impl ExecutionNotice {
+    /// Notice of an execution of `workflow` starting now.
+    pub fn started(execution_id: &str, workflow: &Workflow, mode: WorkflowExecuteMode) -> Self {
+        Self {
+            event: LifecycleEvent::Started,
+            execution_id: execution_id.to_string(),
+            workflow_id: workflow.id.clone(),
+            workflow_name: workflow.name.clone(),
+            mode,
+            status: ExecutionStatus::Running,
+            started_at: Utc::now(),
+            stopped_at: None,
+            error: None,
+        }
+    }
+
+    /// Notice of the end of the execution `self` announced, with
+    /// `result`; `None` while it waits.
+    pub fn finished(
+        &self,
+        result: &Result<Run, ExecutionEngineError>,
+        secrets: &SecretMask,
+    ) -> Option<Self> {
+        let event = LifecycleEvent::finished(result)?;
+        let (status, stopped_at, error) = match result {
+            Ok(run) => (
+                run.status,
+                run.finished_at,
+                run.data.result_data.error.as_ref().map(|e| e.message.clone()),
+            ),
+            Err(ExecutionEngineError::Canceled) => (ExecutionStatus::Canceled, None, None),
+            Err(e) => (ExecutionStatus::Error, None, Some(e.to_string())),
+        };
+        Some(Self {
+            event,
+            status,
+            stopped_at: Some(stopped_at.unwrap_or_else(Utc::now)),
+            error: error.map(|e| secrets.mask(&e).into_owned()),
+            ..self.clone()
+        })
+    }
+
+    /// The JSON body POSTed to endpoints.
+    pub fn payload(&self) -> Value {
+        json!({
+            "event": format!("execution.{}", self.event.as_str()),
+            "executionId": self.execution_id,
+            "workflowId": self.workflow_id,
+            "workflowName": self.workflow_name,
+            "mode": self.mode,
+            "status": self.status,
+            "startedAt": self.started_at,
+            "stoppedAt": self.stopped_at,
+            "durationMs": self
+                .stopped_at
+                .map(|stopped| (stopped - self.started_at).num_milliseconds().max(0)),
+            "error": self.error,
+            "at": Utc::now(),
+        })
+    }
+}
+
+/// Send `notice` to the endpoints of `config` that take its event, in the
+/// background.
+pub(crate) fn notify(
+    config: &RuntimeConfig,
+    deliveries: &WebhookDeliveries,
+    notice: &ExecutionNotice,
+) {
+    let endpoints: Vec<&ExecutionWebhook> = config
+        .execution_webhooks
+        .endpoints
+        .iter()
+        .filter(|endpoint| endpoint.subscribes(notice.event))
+        .collect();
+    if endpoints.is_empty() {
+        return;
+    }
+
+    let client = match config.egress.policy(None) {
+        Ok(policy) => Arc::new(policy)
+            .client_builder()
+            .timeout(Duration::from_millis(config.execution_webhooks.timeout_ms))
+            .build(),
+        Err(e) => {
+            tracing::warn!(error = %e, "Execution webhooks not sent: invalid egress rules");
+            return;
+        }
+    };
+    let client = match client {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!(error = %e, "Execution webhooks not sent");
+            return;
+        }
+    };
+
+    let node = format!("execution.{}", notice.event.as_str());
+    let payload = notice.payload();
+    for endpoint in endpoints {
+        let Some(secret) = endpoint.signing_secret() else {
+            tracing::warn!(
+                url = %endpoint.url,
+                "Execution webhook not sent: signing secret variable is not set"
+            );
+            continue;
+        };
+        let mut delivery =
+            Delivery::new(&node, &endpoint.url, payload.clone(), &secret, endpoint.retry)
+                .with_workflow(Some(&notice.workflow_id));
+        let deliveries = deliveries.clone();
+        let client = client.clone();
+        tokio::spawn(async move {
+            let never = CancellationToken::new();
+            let _ = deliveries.deliver(&client, &mut delivery, &never).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notices_follow_the_execution() {
+        let workflow = Workflow::new("Invoices");
+        let started = ExecutionNotice::started("e1", &workflow, WorkflowExecuteMode::Trigger);
+        let payload = started.payload();
+        assert_eq!(payload["event"], "execution.started");
+        assert_eq!(payload["status"], "running");
+        assert_eq!(payload["mode"], "trigger");
+        assert!(payload["durationMs"].is_null());
+
+        let secrets = SecretMask::new(4);
+        secrets.register("hunter22");
+        let failed = started
+            .finished(
+                &Err(ExecutionEngineError::Internal("login hunter22 refused".to_string())),
+                &secrets,
+            )
+            .unwrap();
+        assert_eq!(failed.event, LifecycleEvent::Failure);
+        let payload = failed.payload();
+        assert_eq!(payload["event"], "execution.failure");
+        assert!(!payload["error"].as_str().unwrap().contains("hunter22"));
+        assert!(payload["durationMs"].as_i64().unwrap() >= 0);
+
+        let canceled = started.finished(&Err(ExecutionEngineError::Canceled), &secrets);
+        assert_eq!(canceled.unwrap().event, LifecycleEvent::Canceled);
+        let mut waiting = Run::new(WorkflowExecuteMode::Trigger);
+        waiting.status = ExecutionStatus::Waiting;
+        assert!(started.finished(&Ok(waiting), &secrets).is_none());
+
+        let endpoint = ExecutionWebhook {
+            events: vec![LifecycleEvent::Failure, LifecycleEvent::Canceled],
+            ..Default::default()
+        };
+        assert!(!endpoint.subscribes(LifecycleEvent::Started));
+        assert!(endpoint.subscribes(LifecycleEvent::Failure));
+        assert!(ExecutionWebhook::default().subscribes(LifecycleEvent::Success));
+    }
+}
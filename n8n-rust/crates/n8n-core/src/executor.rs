@@ -2,6 +2,9 @@
 
 use crate::error::ExecutionEngineError;
 use crate::runtime::RuntimeContext;
+use crate::serialization::IngestParser;
+use crate::supply::Supply;
+use crate::sigv4::{AwsCredentials, SigV4Signer};
 use async_trait::async_trait;
 use n8n_workflow::{DataObject, Node, NodeExecutionData, TaskDataConnections};
 use std::collections::HashMap;
@@ -23,6 +26,19 @@ pub trait NodeExecutor: Send + Sync {
         input: &TaskDataConnections,
         context: &RuntimeContext,
     ) -> Result<NodeOutput, ExecutionEngineError>;
+
+    /// What the node supplies, as a sub-node, to the root node it is
+    /// attached to; see [`crate::supply`]. Only sub-node types implement it.
+    async fn supply_data(
+        &self,
+        node: &Node,
+        _context: &RuntimeContext,
+    ) -> Result<Supply, ExecutionEngineError> {
+        Err(ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message: format!("Node type {} cannot be used as a sub-node", self.node_type()),
+        })
+    }
 }
 
 /// Registry of node executors.
@@ -39,6 +55,7 @@ impl NodeExecutorRegistry {
         // Register built-in executors
         registry.register(Arc::new(ManualTriggerExecutor));
         registry.register(Arc::new(ScheduleTriggerExecutor));
+        registry.register(Arc::new(TimerTriggerExecutor));
         registry.register(Arc::new(WebhookTriggerExecutor));
         registry.register(Arc::new(SetExecutor));
         registry.register(Arc::new(CodeExecutor));
@@ -46,6 +63,12 @@ impl NodeExecutorRegistry {
         registry.register(Arc::new(MergeExecutor));
         registry.register(Arc::new(NoOpExecutor));
         registry.register(Arc::new(HttpRequestExecutor));
+        registry.register(Arc::new(crate::soap::SoapExecutor));
+        registry.register(Arc::new(crate::rollup::RollupExecutor));
+        registry.register(Arc::new(crate::validation::ValidateExecutor));
+        registry.register(Arc::new(crate::jsonata::JsonataExecutor));
+        registry.register(Arc::new(crate::delivery::WebhookDeliveryExecutor));
+        registry.register(Arc::new(crate::throttle::ThrottleExecutor));
 
         // P0 Flow Control nodes
         registry.register(Arc::new(SwitchExecutor));
@@ -126,7 +149,7 @@ impl NodeExecutor for ScheduleTriggerExecutor {
         &self,
         node: &Node,
         _input: &TaskDataConnections,
-        _context: &RuntimeContext,
+        context: &RuntimeContext,
     ) -> Result<NodeOutput, ExecutionEngineError> {
         // Extract schedule info from node parameters
         let cron_expression = node
@@ -141,10 +164,11 @@ impl NodeExecutor for ScheduleTriggerExecutor {
             });
 
         // Create trigger output with schedule metadata
+        let now = context.determinism().now();
         let mut trigger_data = DataObject::new();
         trigger_data.insert(
             "timestamp".to_string(),
-            n8n_workflow::GenericValue::Integer(chrono::Utc::now().timestamp_millis()),
+            n8n_workflow::GenericValue::Integer(now.timestamp_millis()),
         );
         trigger_data.insert(
             "timezone".to_string(),
@@ -159,7 +183,6 @@ impl NodeExecutor for ScheduleTriggerExecutor {
         }
 
         // Add date/time components
-        let now = chrono::Utc::now();
         trigger_data.insert(
             "date".to_string(),
             n8n_workflow::GenericValue::String(now.format("%Y-%m-%d").to_string()),
@@ -185,6 +208,34 @@ impl NodeExecutor for ScheduleTriggerExecutor {
     }
 }
 
+/// Timer trigger node - starts the workflow when a durable timer fires.
+///
+/// The timer scheduler in `n8n-db` passes the fired timer (`timerId`,
+/// `scheduledFor`, `firedAt`, `payload`) as the input item.
+pub struct TimerTriggerExecutor;
+
+#[async_trait]
+impl NodeExecutor for TimerTriggerExecutor {
+    fn node_type(&self) -> &str {
+        "n8n-nodes-base.timerTrigger"
+    }
+
+    async fn execute(
+        &self,
+        _node: &Node,
+        input: &TaskDataConnections,
+        _context: &RuntimeContext,
+    ) -> Result<NodeOutput, ExecutionEngineError> {
+        let items = input
+            .get("main")
+            .and_then(|v| v.first())
+            .filter(|items| !items.is_empty())
+            .cloned()
+            .unwrap_or_else(|| vec![NodeExecutionData::default()]);
+        Ok(vec![items])
+    }
+}
+
 /// Webhook trigger node - triggers workflow when HTTP request is received.
 ///
 /// When executed within a workflow context, this provides the request data
@@ -460,42 +511,6 @@ impl HttpRequestExecutor {
             .unwrap_or(default)
     }
 
-    /// Convert a serde_json::Value into a GenericValue.
-    fn json_to_generic(value: serde_json::Value) -> n8n_workflow::GenericValue {
-        match value {
-            serde_json::Value::Null => n8n_workflow::GenericValue::Null,
-            serde_json::Value::Bool(b) => n8n_workflow::GenericValue::Bool(b),
-            serde_json::Value::Number(n) => {
-                if let Some(i) = n.as_i64() {
-                    n8n_workflow::GenericValue::Integer(i)
-                } else if let Some(f) = n.as_f64() {
-                    n8n_workflow::GenericValue::Float(f)
-                } else {
-                    n8n_workflow::GenericValue::Null
-                }
-            }
-            serde_json::Value::String(s) => n8n_workflow::GenericValue::String(s),
-            serde_json::Value::Array(arr) => {
-                n8n_workflow::GenericValue::Array(arr.into_iter().map(Self::json_to_generic).collect())
-            }
-            serde_json::Value::Object(obj) => {
-                let map: DataObject = obj
-                    .into_iter()
-                    .map(|(k, v)| (k, Self::json_to_generic(v)))
-                    .collect();
-                n8n_workflow::GenericValue::Object(map)
-            }
-        }
-    }
-
-    /// Build a reqwest::Client with the configured timeout.
-    fn build_client(timeout_ms: u64) -> Result<reqwest::Client, ExecutionEngineError> {
-        reqwest::Client::builder()
-            .timeout(std::time::Duration::from_millis(timeout_ms))
-            .build()
-            .map_err(|e| ExecutionEngineError::Internal(format!("Failed to build HTTP client: {}", e)))
-    }
-
     /// Build the request from node parameters.
     fn build_request(
         client: &reqwest::Client,
@@ -606,6 +621,43 @@ impl HttpRequestExecutor {
         }
     }
 
+    /// Signer and credentials of the `options.awsSigV4` option (`region`,
+    /// `service` and `credentials` of `auto`, `environment` or
+    /// `instanceMetadata`), if set.
+    async fn aws_signing(
+        node: &Node,
+    ) -> Result<Option<(SigV4Signer, AwsCredentials)>, ExecutionEngineError> {
+        use crate::sigv4::{AwsCredentialsProvider, CredentialSource};
+
+        let Some(n8n_workflow::NodeParameterValue::Object(options)) = node.parameters.get("options")
+        else {
+            return Ok(None);
+        };
+        let Some(n8n_workflow::NodeParameterValue::Object(aws)) = options.get("awsSigV4") else {
+            return Ok(None);
+        };
+        let node_error = |message: String| ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message,
+        };
+        let string = |key: &str| match aws.get(key) {
+            Some(n8n_workflow::NodeParameterValue::String(s)) if !s.is_empty() => Some(s.as_str()),
+            _ => None,
+        };
+        let region = string("region").ok_or_else(|| node_error("awsSigV4 needs a region".into()))?;
+        let service =
+            string("service").ok_or_else(|| node_error("awsSigV4 needs a service".into()))?;
+        let provider = |source| Arc::new(AwsCredentialsProvider::new(source));
+        let provider = match string("credentials").unwrap_or("auto") {
+            "auto" => AwsCredentialsProvider::shared_chain(),
+            "environment" => provider(CredentialSource::Environment),
+            "instanceMetadata" => provider(CredentialSource::InstanceMetadata),
+            other => return Err(node_error(format!("Unknown AWS credentials source '{}'", other))),
+        };
+        let credentials = provider.credentials().await.map_err(|e| node_error(e.to_string()))?;
+        Ok(Some((SigV4Signer::new(region, service), credentials)))
+    }
+
     /// Check whether the fullResponse option is enabled.
     fn is_full_response(node: &Node) -> bool {
         // Check in options.fullResponse or options.response.fullResponse
@@ -627,6 +679,7 @@ impl HttpRequestExecutor {
     async fn process_response(
         node: &Node,
         response: reqwest::Response,
+        parser: IngestParser,
     ) -> Result<DataObject, ExecutionEngineError> {
         let status_code = response.status().as_u16() as i64;
         let full_response = Self::is_full_response(node);
@@ -661,8 +714,8 @@ impl HttpRequestExecutor {
                         message: format!("Failed to read response body: {}", e),
                     }
                 })?;
-                match serde_json::from_str::<serde_json::Value>(&text) {
-                    Ok(json_val) => Self::json_to_generic(json_val),
+                match parser.parse(text.as_bytes()) {
+                    Ok(value) => value,
                     Err(_) => {
                         // If JSON parsing fails, return as string
                         n8n_workflow::GenericValue::String(text)
@@ -687,8 +740,8 @@ impl HttpRequestExecutor {
                     }
                 })?;
                 if content_type.contains("application/json") || content_type.contains("+json") {
-                    match serde_json::from_str::<serde_json::Value>(&text) {
-                        Ok(json_val) => Self::json_to_generic(json_val),
+                    match parser.parse(text.as_bytes()) {
+                        Ok(value) => value,
                         Err(_) => n8n_workflow::GenericValue::String(text),
                     }
                 } else {
@@ -753,7 +806,8 @@ impl NodeExecutor for HttpRequestExecutor {
         let timeout_ms = Self::get_number_param(node, "timeout", 10000.0) as u64;
 
         // Build a shared client for all items in this execution
-        let client = Self::build_client(timeout_ms)?;
+        let client = context.http_client(std::time::Duration::from_millis(timeout_ms))?;
+        let aws_signing = Self::aws_signing(node).await?;
 
         let mut output = Vec::new();
         let cancel_token = context.cancellation_token();
@@ -765,11 +819,30 @@ impl NodeExecutor for HttpRequestExecutor {
             }
 
             // Build the request
-            let request = Self::build_request(&client, node)?;
+            let mut request = Self::build_request(&client, node)?.build().map_err(|e| {
+                ExecutionEngineError::NodeExecution {
+                    node: node.name.clone(),
+                    message: format!("Invalid HTTP request: {}", e),
+                }
+            })?;
+            context.egress().check(request.url()).await.map_err(|e| {
+                ExecutionEngineError::NodeExecution {
+                    node: node.name.clone(),
+                    message: e.to_string(),
+                }
+            })?;
+            if let Some((signer, credentials)) = &aws_signing {
+                signer.sign(&mut request, credentials).map_err(|e| {
+                    ExecutionEngineError::NodeExecution {
+                        node: node.name.clone(),
+                        message: e.to_string(),
+                    }
+                })?;
+            }
 
             // Execute the request with cancellation support
             let response = tokio::select! {
-                result = request.send() => {
+                result = client.execute(request) => {
                     result.map_err(|e| {
                         if e.is_timeout() {
                             ExecutionEngineError::NodeExecution {
@@ -826,7 +899,8 @@ impl NodeExecutor for HttpRequestExecutor {
             }
 
             // Process the response
-            let result = Self::process_response(node, response).await?;
+            let parser = context.config.serialization.ingest;
+            let result = Self::process_response(node, response, parser).await?;
             output.push(NodeExecutionData::new(result));
         }
 
@@ -1091,8 +1165,46 @@ impl NodeExecutor for LimitExecutor {
 }
 
 /// RemoveDuplicates node - remove duplicate items.
+///
+/// By default removes items repeated within the input. With `operation`
+/// set to `removeItemsProcessedPreviously` it removes items whose value was
+/// seen by earlier executions, kept in the engine's
+/// [`ProcessedData`](crate::processed_data::ProcessedData) per node or, with
+/// `scope: workflow`, per workflow; `clearDeduplicationHistory` forgets
+/// them. `logic` picks how values compare:
+/// `removeItemsWithAlreadySeenKeyValues` (default),
+/// `removeItemsUpToStoredIncrementalKey` or `removeItemsUpToStoredDate`.
 pub struct RemoveDuplicatesExecutor;
 
+impl RemoveDuplicatesExecutor {
+    fn string_param(node: &Node, key: &str) -> Option<String> {
+        match node.parameters.get(key) {
+            Some(n8n_workflow::NodeParameterValue::String(s)) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    /// Value of `item` compared for duplicates: `field`, or the whole item.
+    fn dedupe_value(item: &NodeExecutionData, field: Option<&str>) -> String {
+        let value = match field {
+            Some(field) => item.json.get(field).map(serde_json::Value::from).unwrap_or_default(),
+            None => serde_json::Value::Object(n8n_workflow::object_to_json(&item.json)),
+        };
+        match value {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        }
+    }
+
+    /// Processed data context of `node`.
+    fn context(node: &Node) -> String {
+        match Self::string_param(node, "scope").as_deref() {
+            Some("workflow") => "workflow".to_string(),
+            _ => format!("node:{}", if node.id.is_empty() { &node.name } else { &node.id }),
+        }
+    }
+}
+
 #[async_trait]
 impl NodeExecutor for RemoveDuplicatesExecutor {
     fn node_type(&self) -> &str {
@@ -1103,22 +1215,71 @@ impl NodeExecutor for RemoveDuplicatesExecutor {
         &self,
         node: &Node,
         input: &TaskDataConnections,
-        _context: &RuntimeContext,
+        context: &RuntimeContext,
     ) -> Result<NodeOutput, ExecutionEngineError> {
+        use crate::processed_data::{ProcessedDataMode, DEFAULT_HISTORY_SIZE};
+
         let main_input = input.get("main").and_then(|v| v.first());
         let items = main_input.cloned().unwrap_or_default();
+        let node_error = |message: String| ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message,
+        };
 
         // Get field to check for duplicates
-        let compare_field = node
-            .parameters
-            .get("compareField")
-            .and_then(|v| {
-                if let n8n_workflow::NodeParameterValue::String(s) = v {
-                    Some(s.clone())
-                } else {
-                    None
+        let compare_field = Self::string_param(node, "compareField");
+        let operation = Self::string_param(node, "operation");
+        let workflow_id = context.workflow_id().unwrap_or_default();
+
+        match operation.as_deref() {
+            None | Some("removeItemsRepeatedWithinCurrentInput") => {}
+            Some("removeItemsProcessedPreviously") => {
+                let mode = match Self::string_param(node, "logic").as_deref() {
+                    None | Some("removeItemsWithAlreadySeenKeyValues") => {
+                        ProcessedDataMode::Entries
+                    }
+                    Some("removeItemsUpToStoredIncrementalKey") => {
+                        ProcessedDataMode::LatestIncrementalKey
+                    }
+                    Some("removeItemsUpToStoredDate") => ProcessedDataMode::LatestDate,
+                    Some(other) => return Err(node_error(format!("Unknown logic '{}'", other))),
+                };
+                let history_size = match node.parameters.get("historySize") {
+                    Some(n8n_workflow::NodeParameterValue::Number(n)) if *n >= 1.0 => *n as usize,
+                    _ => DEFAULT_HISTORY_SIZE,
+                };
+                if items.is_empty() {
+                    return Ok(vec![items]);
                 }
-            });
+                let values: Vec<String> = items
+                    .iter()
+                    .map(|item| Self::dedupe_value(item, compare_field.as_deref()))
+                    .collect();
+                let scope = Self::context(node);
+                let new = context
+                    .processed_data()
+                    .store()
+                    .check_and_record(workflow_id, &scope, mode, &values, history_size)
+                    .await
+                    .map_err(node_error)?;
+                let unique = items
+                    .into_iter()
+                    .zip(new)
+                    .filter_map(|(item, new)| new.then_some(item))
+                    .collect();
+                return Ok(vec![unique]);
+            }
+            Some("clearDeduplicationHistory") => {
+                context
+                    .processed_data()
+                    .store()
+                    .clear(workflow_id, &Self::context(node))
+                    .await
+                    .map_err(node_error)?;
+                return Ok(vec![items]);
+            }
+            Some(other) => return Err(node_error(format!("Unknown operation '{}'", other))),
+        }
 
         let mut seen = std::collections::HashSet::new();
         let mut unique = Vec::new();
@@ -1395,7 +1556,11 @@ impl NodeExecutor for StopAndErrorExecutor {
     }
 }
 
-/// ExecuteWorkflow node - execute another workflow (placeholder).
+/// ExecuteWorkflow node - execute another workflow.
+///
+/// Engines built with `WorkflowEngine::with_sub_workflows` run the
+/// sub-workflow themselves; this executor is the fallback without workflow
+/// storage and passes its input through.
 pub struct ExecuteWorkflowExecutor;
 
 #[async_trait]
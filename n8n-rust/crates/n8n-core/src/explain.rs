@@ -0,0 +1,408 @@
+//! Explain (dry-run) mode for workflows.
+//!
+//! [`explain`] walks a workflow the way the engine would run it, without
+//! executing any node, and returns an [`ExecutionPlan`]: the start nodes, the
+//! order nodes first run in, loops, which parameters hold expressions, the
+//! estimated number of external calls and the credentials that would be
+//! used. Intended for workflow reviews and CI checks.
+
+use crate::engine::find_start_nodes;
+use crate::error::ExecutionEngineError;
+use crate::executor::NodeExecutorRegistry;
+use crate::lint::for_each_expression;
+use n8n_workflow::{Node, Workflow};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Node types that call external systems even without credentials.
+pub const EXTERNAL_NODE_TYPES: &[&str] = &["n8n-nodes-base.httpRequest"];
+
+/// What an execution of a workflow would do.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPlan {
+    pub workflow_id: String,
+    pub workflow_name: String,
+    /// Nodes the execution starts from.
+    pub start_nodes: Vec<String>,
+    /// Reachable nodes in the order they first run.
+    pub order: Vec<String>,
+    /// Groups of nodes that form a loop, each in first-run order.
+    pub loops: Vec<Vec<String>>,
+    /// Nodes that never run (disabled, disconnected, or behind a disabled node).
+    pub skipped: Vec<String>,
+    /// Per-node detail, in `order`.
+    pub nodes: Vec<NodePlan>,
+    /// External calls for one pass through the workflow, counting one call
+    /// per node run. Nodes that call once per item, and loops, make more.
+    pub estimated_external_calls: usize,
+    /// Credentials used by reachable nodes.
+    pub credentials: Vec<CredentialUse>,
+    /// Problems that would surface at execution time.
+    pub warnings: Vec<String>,
+}
+
+/// Plan detail for one node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodePlan {
+    pub name: String,
+    pub node_type: String,
+    /// Whether an executor is registered for the node type.
+    pub has_executor: bool,
+    /// Whether the node is part of a loop.
+    pub in_loop: bool,
+    /// Whether the node calls an external system.
+    pub external: bool,
+    /// Parameter paths containing expressions (e.g. `values.email`).
+    pub expressions: Vec<String>,
+    /// Credentials the node would use.
+    pub credentials: Vec<CredentialUse>,
+}
+
+/// A credential reference used by a node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialUse {
+    pub node: String,
+    pub credential_type: String,
+    pub id: String,
+    pub name: String,
+}
+
+/// Build the execution plan for a workflow without running it.
+pub fn explain(
+    workflow: &Workflow,
+    executors: &NodeExecutorRegistry,
+) -> Result<ExecutionPlan, ExecutionEngineError> {
+    let start_nodes = find_start_nodes(workflow)?;
+    let edges = enabled_edges(workflow);
+
+    let mut tarjan = Tarjan::new(&edges);
+    for start in &start_nodes {
+        if !tarjan.index.contains_key(start.as_str()) {
+            tarjan.visit(start.as_str());
+        }
+    }
+
+    // Tarjan emits components in reverse topological order.
+    let components: Vec<Vec<String>> = tarjan.components.into_iter().rev().collect();
+    let loops: Vec<Vec<String>> = components
+        .iter()
+        .filter(|c| c.len() > 1 || edges.get(&c[0]).is_some_and(|t| t.contains(&c[0])))
+        .map(|c| component_order(c, &edges, &start_nodes))
+        .collect();
+    let order: Vec<String> = components
+        .iter()
+        .flat_map(|c| component_order(c, &edges, &start_nodes))
+        .collect();
+
+    let in_loop: HashSet<&str> = loops.iter().flatten().map(String::as_str).collect();
+    let reachable: HashSet<&str> = order.iter().map(String::as_str).collect();
+    let skipped: Vec<String> = workflow
+        .nodes
+        .iter()
+        .filter(|n| !reachable.contains(n.name.as_str()))
+        .map(|n| n.name.clone())
+        .collect();
+
+    let mut warnings = Vec::new();
+    let mut nodes = Vec::new();
+    for name in &order {
+        let Some(node) = workflow.get_node(name) else {
+            continue;
+        };
+
+        let has_executor = executors.get(&node.node_type).is_some();
+        if !has_executor && !node.is_trigger() {
+            warnings.push(format!(
+                "Node '{}' has no executor for type '{}' and would fail",
+                node.name, node.node_type
+            ));
+        }
+
+        let mut expressions = Vec::new();
+        let mut keys: Vec<_> = node.parameters.keys().collect();
+        keys.sort();
+        for key in keys {
+            for_each_expression(key, &node.parameters[key], &mut |path, _| {
+                expressions.push(path.to_string());
+            });
+        }
+
+        let credentials = credential_uses(node);
+        let external =
+            !credentials.is_empty() || EXTERNAL_NODE_TYPES.contains(&node.node_type.as_str());
+
+        nodes.push(NodePlan {
+            name: node.name.clone(),
+            node_type: node.node_type.clone(),
+            has_executor,
+            in_loop: in_loop.contains(name.as_str()),
+            external,
+            expressions,
+            credentials,
+        });
+    }
+
+    for members in &loops {
+        let external: Vec<&str> = nodes
+            .iter()
+            .filter(|n| n.external && members.contains(&n.name))
+            .map(|n| n.name.as_str())
+            .collect();
+        if !external.is_empty() {
+            warnings.push(format!(
+                "External calls in loop repeat per iteration: {}",
+                external.join(", ")
+            ));
+        }
+    }
+
+    Ok(ExecutionPlan {
+        workflow_id: workflow.id.clone(),
+        workflow_name: workflow.name.clone(),
+        start_nodes,
+        order,
+        loops,
+        skipped,
+        estimated_external_calls: nodes.iter().filter(|n| n.external).count(),
+        credentials: nodes.iter().flat_map(|n| n.credentials.clone()).collect(),
+        nodes,
+        warnings,
+    })
+}
+
+/// Outgoing connections per node, limited to enabled targets (the engine
+/// skips disabled nodes and does not run past them).
+fn enabled_edges(workflow: &Workflow) -> HashMap<String, Vec<String>> {
+    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+
+    for (source, node_conns) in &workflow.connections {
+        let mut types: Vec<_> = node_conns.keys().collect();
+        types.sort();
+
+        let targets = edges.entry(source.clone()).or_default();
+        for conn_type in types {
+            for conns in &node_conns[conn_type] {
+                for conn in conns {
+                    let enabled = workflow.get_node(&conn.node).is_some_and(|n| !n.disabled);
+                    if enabled && !targets.contains(&conn.node) {
+                        targets.push(conn.node.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    edges
+}
+
+/// Order the members of one component breadth-first from its entry points:
+/// start nodes and members with a predecessor outside the component.
+fn component_order(
+    component: &[String],
+    edges: &HashMap<String, Vec<String>>,
+    start_nodes: &[String],
+) -> Vec<String> {
+    if component.len() == 1 {
+        return component.to_vec();
+    }
+
+    let members: HashSet<&str> = component.iter().map(String::as_str).collect();
+    let mut entries: Vec<&str> = start_nodes
+        .iter()
+        .map(String::as_str)
+        .filter(|s| members.contains(s))
+        .collect();
+    let mut sources: Vec<&String> = edges.keys().collect();
+    sources.sort();
+    for source in sources {
+        if members.contains(source.as_str()) {
+            continue;
+        }
+        for target in &edges[source] {
+            if members.contains(target.as_str()) && !entries.contains(&target.as_str()) {
+                entries.push(target.as_str());
+            }
+        }
+    }
+
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut queue: VecDeque<&str> = VecDeque::new();
+    let mut order = Vec::new();
+    for entry in entries.into_iter().chain(component.iter().map(String::as_str)) {
+        if seen.insert(entry) {
+            queue.push_back(entry);
+        }
+        while let Some(node) = queue.pop_front() {
+            order.push(node.to_string());
+            for next in edges.get(node).into_iter().flatten() {
+                if members.contains(next.as_str()) && seen.insert(next.as_str()) {
+                    queue.push_back(next.as_str());
+                }
+            }
+        }
+    }
+
+    order
+}
+
+pub(crate) fn credential_uses(node: &Node) -> Vec<CredentialUse> {
+    let mut uses: Vec<CredentialUse> = node
+        .credentials
+        .iter()
+        .flatten()
+        .map(|(credential_type, cred)| CredentialUse {
+            node: node.name.clone(),
+            credential_type: credential_type.clone(),
+            id: cred.id.clone(),
+            name: cred.name.clone(),
+        })
+        .collect();
+    uses.sort_by(|a, b| a.credential_type.cmp(&b.credential_type));
+    uses
+}
+
+/// Tarjan's strongly connected components over the reachable graph.
+struct Tarjan<'a> {
+    edges: &'a HashMap<String, Vec<String>>,
+    index: HashMap<&'a str, usize>,
+    low: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: HashSet<&'a str>,
+    components: Vec<Vec<String>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn new(edges: &'a HashMap<String, Vec<String>>) -> Self {
+        Self {
+            edges,
+            index: HashMap::new(),
+            low: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            components: Vec::new(),
+        }
+    }
+
+    fn visit(&mut self, node: &'a str) {
+        let index = self.index.len();
+        self.index.insert(node, index);
+        self.low.insert(node, index);
+        self.stack.push(node);
+        self.on_stack.insert(node);
+
+        let edges = self.edges;
+        for next in edges.get(node).into_iter().flatten() {
+            let next = next.as_str();
+            let candidate = if !self.index.contains_key(next) {
+                self.visit(next);
+                self.low[next]
+            } else if self.on_stack.contains(next) {
+                self.index[next]
+            } else {
+                continue;
+            };
+            let low = self.low.get_mut(node).expect("visited node has a low-link");
+            *low = (*low).min(candidate);
+        }
+
+        if self.low[node] == self.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(member);
+                component.push(member.to_string());
+                if member == node {
+                    break;
+                }
+            }
+            component.reverse();
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::NodeCredentialRef;
+
+    fn workflow(nodes: Vec<Node>, connections: &[(&str, &str)]) -> Workflow {
+        let mut wf = Workflow::new("Explain");
+        for node in nodes {
+            wf.add_node(node);
+        }
+        for (source, target) in connections {
+            wf.connect(source, target, 0, 0).unwrap();
+        }
+        wf
+    }
+
+    #[test]
+    fn test_explain_linear_workflow() {
+        let mut http = Node::new("Fetch", "n8n-nodes-base.httpRequest");
+        http.set_parameter(
+            "url",
+            NodeParameterValue::String("https://api.example.com/{{ $json.id }}".into()),
+        );
+        http.credentials = Some(HashMap::from([(
+            "httpHeaderAuth".to_string(),
+            NodeCredentialRef {
+                id: "1".into(),
+                name: "API key".into(),
+            },
+        )]));
+
+        let wf = workflow(
+            vec![
+                Node::new("Start", "n8n-nodes-base.manualTrigger"),
+                http,
+                Node::new("Done", "n8n-nodes-base.noOp"),
+                Node::new("Orphan", "n8n-nodes-base.unknown"),
+            ],
+            &[("Start", "Fetch"), ("Fetch", "Done")],
+        );
+
+        let plan = explain(&wf, &NodeExecutorRegistry::new()).unwrap();
+        assert_eq!(plan.start_nodes, vec!["Start"]);
+        assert_eq!(plan.order, vec!["Start", "Fetch", "Done"]);
+        assert!(plan.loops.is_empty());
+        assert_eq!(plan.skipped, vec!["Orphan"]);
+        assert_eq!(plan.estimated_external_calls, 1);
+        assert_eq!(plan.nodes[1].expressions, vec!["url"]);
+        assert_eq!(plan.credentials.len(), 1);
+        assert_eq!(plan.credentials[0].credential_type, "httpHeaderAuth");
+        assert!(plan.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_explain_detects_loops_and_disabled_nodes() {
+        let mut disabled = Node::new("Disabled", "n8n-nodes-base.noOp");
+        disabled.disabled = true;
+
+        let wf = workflow(
+            vec![
+                Node::new("Start", "n8n-nodes-base.manualTrigger"),
+                Node::new("Batch", "n8n-nodes-base.splitInBatches"),
+                Node::new("Fetch", "n8n-nodes-base.httpRequest"),
+                disabled,
+                Node::new("After", "n8n-nodes-base.noOp"),
+            ],
+            &[
+                ("Start", "Batch"),
+                ("Batch", "Fetch"),
+                ("Fetch", "Batch"),
+                ("Batch", "Disabled"),
+                ("Disabled", "After"),
+            ],
+        );
+
+        let plan = explain(&wf, &NodeExecutorRegistry::new()).unwrap();
+        assert_eq!(plan.order, vec!["Start", "Batch", "Fetch"]);
+        assert_eq!(plan.loops, vec![vec!["Batch".to_string(), "Fetch".to_string()]]);
+        assert_eq!(plan.skipped, vec!["Disabled", "After"]);
+        assert!(plan.nodes.iter().all(|n| n.in_loop || n.name == "Start"));
+        assert_eq!(plan.warnings.len(), 1);
+    }
+}
@@ -1,14 +1,18 @@
 //! Expression evaluator for n8n expressions.
 
 use super::parser::{BinaryOperator, Expr, Literal, TemplatePart, UnaryOperator};
-use super::variables::resolve_variable;
+use super::stringify::ExpressionLimits;
+use super::variables::{resolve_variable, NodeReference};
 use super::{ExpressionContext, ExpressionError, ExpressionResult};
 use serde_json::Value;
+use std::collections::HashMap;
 
 /// Evaluator for n8n expressions.
 pub struct ExpressionEvaluator {
     /// Whether to use strict mode (throw on undefined).
     pub strict: bool,
+    /// Size limits of values interpolated into strings.
+    pub limits: ExpressionLimits,
 }
 
 impl Default for ExpressionEvaluator {
@@ -20,12 +24,49 @@ impl Default for ExpressionEvaluator {
 impl ExpressionEvaluator {
     /// Create a new evaluator.
     pub fn new() -> Self {
-        Self { strict: false }
+        Self {
+            strict: false,
+            limits: ExpressionLimits::default(),
+        }
     }
 
     /// Create a strict evaluator that throws on undefined.
     pub fn strict() -> Self {
-        Self { strict: true }
+        Self {
+            strict: true,
+            limits: ExpressionLimits::default(),
+        }
+    }
+
+    /// Create an evaluator bounding interpolated values by `limits`.
+    pub fn with_limits(limits: ExpressionLimits) -> Self {
+        Self {
+            strict: false,
+            limits,
+        }
+    }
+
+    /// Resolve expressions in a node parameter value with this evaluator.
+    pub fn resolve(&self, value: &Value, context: &ExpressionContext) -> ExpressionResult<Value> {
+        match value {
+            Value::String(s) if s.contains("{{") => {
+                let expr = super::parser::parse_template(s)?;
+                self.evaluate(&expr, context)
+            }
+            Value::Object(obj) => {
+                let mut result = serde_json::Map::new();
+                for (k, v) in obj {
+                    result.insert(k.clone(), self.resolve(v, context)?);
+                }
+                Ok(Value::Object(result))
+            }
+            Value::Array(arr) => {
+                let result: Result<Vec<_>, _> =
+                    arr.iter().map(|v| self.resolve(v, context)).collect();
+                Ok(Value::Array(result?))
+            }
+            _ => Ok(value.clone()),
+        }
     }
 
     /// Evaluate an expression AST.
@@ -59,6 +100,197 @@ impl ExpressionEvaluator {
             Expr::Array(elements) => self.eval_array(elements, context),
             Expr::Object(pairs) => self.eval_object(pairs, context),
             Expr::Template(parts) => self.eval_template(parts, context),
+            Expr::NodeReference(node) => {
+                let node = self.node_reference(node, context)?;
+                Ok(serde_json::json!({ "isExecuted": node.is_executed() }))
+            }
+            Expr::OptionalChain(chain) => {
+                Ok(self.eval_chain(chain, context)?.unwrap_or(Value::Null))
+            }
+            Expr::Optional(object) => self.evaluate(object, context),
+            Expr::Lambda { .. } => Err(ExpressionError::EvaluationError(
+                "Arrow functions can only be passed to array methods".to_string(),
+            )),
+        }
+    }
+
+    /// Call an arrow function with `args`; missing arguments are null.
+    fn apply(
+        &self,
+        params: &[String],
+        body: &Expr,
+        args: &[Value],
+        context: &ExpressionContext,
+    ) -> ExpressionResult<Value> {
+        let mut locals = context.locals.cloned().unwrap_or_default();
+        for (i, param) in params.iter().enumerate() {
+            locals.insert(param.clone(), args.get(i).cloned().unwrap_or(Value::Null));
+        }
+        let scoped = ExpressionContext {
+            locals: Some(&locals),
+            ..context.clone()
+        };
+        self.evaluate(body, &scoped)
+    }
+
+    /// Array methods taking an arrow function: `map`, `filter`, `find`,
+    /// `findIndex`, `some`, `every`, `flatMap`, `reduce` and `sort`.
+    /// Callbacks get `(item, index, array)`; `reduce` gets `(acc, item,
+    /// index, array)` and starts from its second argument or the first item.
+    fn call_array_callback(
+        &self,
+        arr: &[Value],
+        method: &str,
+        args: &[Expr],
+        context: &ExpressionContext,
+    ) -> ExpressionResult<Value> {
+        let Some(Expr::Lambda { params, body }) = args.first() else {
+            return Err(ExpressionError::InvalidArgument(format!(
+                "{}() expects an arrow function",
+                method
+            )));
+        };
+        let array = Value::Array(arr.to_vec());
+        let call = |item: &Value, i: usize| {
+            self.apply(params, body, &[item.clone(), Value::from(i), array.clone()], context)
+        };
+
+        match method {
+            "map" => Ok(Value::Array(
+                arr.iter().enumerate().map(|(i, v)| call(v, i)).collect::<Result<_, _>>()?,
+            )),
+            "filter" => {
+                let mut kept = Vec::new();
+                for (i, v) in arr.iter().enumerate() {
+                    if is_truthy(&call(v, i)?) {
+                        kept.push(v.clone());
+                    }
+                }
+                Ok(Value::Array(kept))
+            }
+            "find" | "findIndex" => {
+                for (i, v) in arr.iter().enumerate() {
+                    if is_truthy(&call(v, i)?) {
+                        return Ok(if method == "find" { v.clone() } else { Value::from(i) });
+                    }
+                }
+                Ok(if method == "find" { Value::Null } else { Value::from(-1) })
+            }
+            "some" | "every" => {
+                let want = method == "some";
+                for (i, v) in arr.iter().enumerate() {
+                    if is_truthy(&call(v, i)?) == want {
+                        return Ok(Value::Bool(want));
+                    }
+                }
+                Ok(Value::Bool(!want))
+            }
+            "flatMap" => {
+                let mut flat = Vec::new();
+                for (i, v) in arr.iter().enumerate() {
+                    match call(v, i)? {
+                        Value::Array(items) => flat.extend(items),
+                        other => flat.push(other),
+                    }
+                }
+                Ok(Value::Array(flat))
+            }
+            "reduce" => {
+                let (mut acc, start) = match args.get(1) {
+                    Some(init) => (self.evaluate(init, context)?, 0),
+                    None => match arr.first() {
+                        Some(first) => (first.clone(), 1),
+                        None => {
+                            return Err(ExpressionError::InvalidArgument(
+                                "reduce() of empty array with no initial value".to_string(),
+                            ))
+                        }
+                    },
+                };
+                for (i, v) in arr.iter().enumerate().skip(start) {
+                    let call_args = [acc, v.clone(), Value::from(i), array.clone()];
+                    acc = self.apply(params, body, &call_args, context)?;
+                }
+                Ok(acc)
+            }
+            "sort" => {
+                let mut sorted = arr.to_vec();
+                let mut error = None;
+                sorted.sort_by(|a, b| {
+                    match self.apply(params, body, &[a.clone(), b.clone()], context) {
+                        Ok(order) => value_to_number(&order)
+                            .partial_cmp(&0.0)
+                            .unwrap_or(std::cmp::Ordering::Equal),
+                        Err(e) => {
+                            error.get_or_insert(e);
+                            std::cmp::Ordering::Equal
+                        }
+                    }
+                });
+                match error {
+                    Some(e) => Err(e),
+                    None => Ok(Value::Array(sorted)),
+                }
+            }
+            _ => Err(ExpressionError::MethodNotFound(format!(
+                "{}() does not take an arrow function",
+                method
+            ))),
+        }
+    }
+
+    /// Evaluate a link of an optional chain; `None` once the object of a
+    /// `?.` was null, so the rest of the chain is skipped.
+    fn eval_chain(
+        &self,
+        expr: &Expr,
+        context: &ExpressionContext,
+    ) -> ExpressionResult<Option<Value>> {
+        match expr {
+            Expr::Optional(object) => {
+                Ok(self.eval_chain(object, context)?.filter(|v| !v.is_null()))
+            }
+            Expr::PropertyAccess { object, property }
+                if !matches!(**object, Expr::NodeReference(_)) =>
+            {
+                match self.eval_chain(object, context)? {
+                    Some(obj) => self.property_of(obj, property).map(Some),
+                    None => Ok(None),
+                }
+            }
+            Expr::IndexAccess { object, index } => match self.eval_chain(object, context)? {
+                Some(obj) => {
+                    let idx = self.evaluate(index, context)?;
+                    self.index_of(obj, idx).map(Some)
+                }
+                None => Ok(None),
+            },
+            Expr::MethodCall {
+                object,
+                method,
+                args,
+            } if !matches!(**object, Expr::NodeReference(_)) => {
+                match self.eval_chain(object, context)? {
+                    Some(obj) => self.call_method(&obj, method, args, context).map(Some),
+                    None => Ok(None),
+                }
+            }
+            _ => self.evaluate(expr, context).map(Some),
+        }
+    }
+
+    /// The `$("Node")` accessor for the node named by `node`.
+    fn node_reference<'c>(
+        &self,
+        node: &Expr,
+        context: &'c ExpressionContext<'c>,
+    ) -> ExpressionResult<NodeReference<'c>> {
+        match self.evaluate(node, context)? {
+            Value::String(name) => Ok(NodeReference::new(name, context)),
+            other => Err(ExpressionError::InvalidArgument(format!(
+                "$() expects a node name, got {}",
+                value_type_name(&other)
+            ))),
         }
     }
 
@@ -85,6 +317,10 @@ impl ExpressionEvaluator {
         name: &str,
         context: &ExpressionContext,
     ) -> ExpressionResult<Value> {
+        // Arrow function parameters shadow built-in variables.
+        if let Some(value) = context.locals.and_then(|locals| locals.get(name)) {
+            return Ok(value.clone());
+        }
         resolve_variable(name, context)
     }
 
@@ -94,8 +330,20 @@ impl ExpressionEvaluator {
         property: &str,
         context: &ExpressionContext,
     ) -> ExpressionResult<Value> {
+        if let Expr::NodeReference(node) = object {
+            let node = self.node_reference(node, context)?;
+            match property {
+                "item" => return node.item(),
+                "isExecuted" => return Ok(Value::Bool(node.is_executed())),
+                _ => {}
+            }
+        }
+
         let obj = self.evaluate(object, context)?;
+        self.property_of(obj, property)
+    }
 
+    fn property_of(&self, obj: Value, property: &str) -> ExpressionResult<Value> {
         match obj {
             Value::Object(map) => {
                 if let Some(value) = map.get(property) {
@@ -137,7 +385,10 @@ impl ExpressionEvaluator {
     ) -> ExpressionResult<Value> {
         let obj = self.evaluate(object, context)?;
         let idx = self.evaluate(index, context)?;
+        self.index_of(obj, idx)
+    }
 
+    fn index_of(&self, obj: Value, idx: Value) -> ExpressionResult<Value> {
         match (&obj, &idx) {
             (Value::Array(arr), Value::Number(n)) => {
                 let i = n.as_i64().unwrap_or(0) as usize;
@@ -179,13 +430,58 @@ impl ExpressionEvaluator {
         args: &[Expr],
         context: &ExpressionContext,
     ) -> ExpressionResult<Value> {
+        if let Expr::NodeReference(node) = object {
+            let node = self.node_reference(node, context)?;
+            let evaluated_args: Vec<Value> = args
+                .iter()
+                .map(|arg| self.evaluate(arg, context))
+                .collect::<Result<_, _>>()?;
+            let index =
+                |i: usize| evaluated_args.get(i).and_then(Value::as_u64).map(|n| n as usize);
+            match method {
+                "all" => return node.all(index(0), index(1)),
+                "first" => return node.first(index(0), index(1)),
+                "last" => return node.last(index(0), index(1)),
+                "itemMatching" => {
+                    let item = index(0).ok_or_else(|| {
+                        ExpressionError::InvalidArgument(
+                            "itemMatching() expects an item index".to_string(),
+                        )
+                    })?;
+                    return node.item_matching(item);
+                }
+                _ => {}
+            }
+        }
+
         let obj = self.evaluate(object, context)?;
+        self.call_method(&obj, method, args, context)
+    }
+
+    /// Call `method` on `obj`, passing arrow function arguments unevaluated.
+    fn call_method(
+        &self,
+        obj: &Value,
+        method: &str,
+        args: &[Expr],
+        context: &ExpressionContext,
+    ) -> ExpressionResult<Value> {
+        if args.iter().any(|arg| matches!(arg, Expr::Lambda { .. })) {
+            return match obj {
+                Value::Array(arr) => self.call_array_callback(arr, method, args, context),
+                Value::Null => Ok(Value::Null),
+                other => Err(ExpressionError::TypeError {
+                    expected: "array".to_string(),
+                    actual: value_type_name(other),
+                }),
+            };
+        }
+
         let evaluated_args: Vec<Value> = args
             .iter()
             .map(|arg| self.evaluate(arg, context))
             .collect::<Result<_, _>>()?;
-
-        super::extensions::call_method(&obj, method, &evaluated_args)
+        super::extensions::call_method(obj, method, &evaluated_args, context.determinism)
     }
 
     fn eval_function_call(
@@ -263,8 +559,8 @@ impl ExpressionEvaluator {
                 ))
             }
             (Value::String(l), Value::String(r)) => Ok(Value::String(format!("{}{}", l, r))),
-            (Value::String(l), r) => Ok(Value::String(format!("{}{}", l, value_to_string(r)))),
-            (l, Value::String(r)) => Ok(Value::String(format!("{}{}", value_to_string(l), r))),
+            (Value::String(l), r) => Ok(Value::String(format!("{}{}", l, self.limits.stringify(r)?))),
+            (l, Value::String(r)) => Ok(Value::String(format!("{}{}", self.limits.stringify(l)?, r))),
             (Value::Array(l), Value::Array(r)) => {
                 let mut result = l.clone();
                 result.extend(r.clone());
@@ -406,7 +702,7 @@ impl ExpressionEvaluator {
                 TemplatePart::String(s) => result.push_str(s),
                 TemplatePart::Expression(expr) => {
                     let value = self.evaluate(expr, context)?;
-                    result.push_str(&value_to_string(&value));
+                    result.push_str(&self.limits.stringify(&value)?);
                 }
             }
         }
@@ -457,18 +753,6 @@ fn value_type_name(value: &Value) -> String {
     }
 }
 
-/// Convert a value to a string.
-fn value_to_string(value: &Value) -> String {
-    match value {
-        Value::Null => "null".to_string(),
-        Value::Bool(b) => b.to_string(),
-        Value::Number(n) => n.to_string(),
-        Value::String(s) => s.clone(),
-        Value::Array(arr) => serde_json::to_string(arr).unwrap_or_default(),
-        Value::Object(obj) => serde_json::to_string(obj).unwrap_or_default(),
-    }
-}
-
 /// Convert a value to a number.
 fn value_to_number(value: &Value) -> f64 {
     match value {
@@ -491,29 +775,7 @@ pub fn resolve_parameter(
     value: &Value,
     context: &ExpressionContext,
 ) -> ExpressionResult<Value> {
-    let evaluator = ExpressionEvaluator::new();
-
-    match value {
-        Value::String(s) if s.contains("{{") => {
-            let expr = super::parser::parse_template(s)?;
-            evaluator.evaluate(&expr, context)
-        }
-        Value::Object(obj) => {
-            let mut result = serde_json::Map::new();
-            for (k, v) in obj {
-                result.insert(k.clone(), resolve_parameter(v, context)?);
-            }
-            Ok(Value::Object(result))
-        }
-        Value::Array(arr) => {
-            let result: Result<Vec<_>, _> = arr
-                .iter()
-                .map(|v| resolve_parameter(v, context))
-                .collect();
-            Ok(Value::Array(result?))
-        }
-        _ => Ok(value.clone()),
-    }
+    ExpressionEvaluator::new().resolve(value, context)
 }
 
 #[cfg(test)]
@@ -552,6 +814,123 @@ mod tests {
         assert_eq!(result, Value::String("ab".to_string()));
     }
 
+    #[test]
+    fn test_eval_node_reference() {
+        use n8n_workflow::GenericValue;
+
+        let item = |id: i64| {
+            let mut item = NodeExecutionData::default();
+            item.json.insert("id".to_string(), GenericValue::Integer(id));
+            item
+        };
+        let node_data = std::collections::HashMap::from([(
+            "Get Orders".to_string(),
+            vec![vec![item(1)], vec![item(2), item(3)]],
+        )]);
+        let current = NodeExecutionData::default();
+        let mut context = ExpressionContext::minimal(&current);
+        context.node_data = &node_data;
+        context.item_index = 1;
+
+        let evaluator = ExpressionEvaluator::new();
+        let eval = |source: &str| {
+            evaluator.evaluate(&super::super::parser::parse(source).unwrap(), &context)
+        };
+
+        assert_eq!(eval("$('Get Orders').item.json.id").unwrap(), Value::Number(3.into()));
+        assert_eq!(eval("$('Get Orders').first(0, 0).json.id").unwrap(), Value::Number(1.into()));
+        assert_eq!(eval("$('Get Orders').all().length()").unwrap(), Value::Number(2.into()));
+        assert_eq!(eval("$('Get Orders').all(1)").unwrap(), Value::Array(vec![]));
+        assert!(matches!(eval("$('Get Orders').all(0, 5)"), Err(ExpressionError::InvalidIndex(_))));
+        assert!(matches!(eval("$('Send Mail').item"), Err(ExpressionError::NodeNotExecuted(_))));
+        assert_eq!(eval("$('Send Mail').isExecuted").unwrap(), Value::Bool(false));
+    }
+
+    #[test]
+    fn test_eval_optional_chaining() {
+        use n8n_workflow::GenericValue;
+
+        let mut item = NodeExecutionData::default();
+        item.json.insert("user".to_string(), GenericValue::Null);
+        let context = ExpressionContext::minimal(&item);
+        let eval = |evaluator: &ExpressionEvaluator, source: &str| {
+            evaluator.evaluate(&super::super::parser::parse(source).unwrap(), &context)
+        };
+
+        // Strict mode fails on plain access of null but propagates null
+        // through the whole chain after `?.`.
+        let strict = ExpressionEvaluator::strict();
+        assert!(eval(&strict, "$json.user.email").is_err());
+        assert_eq!(eval(&strict, "$json.user?.email.domain").unwrap(), Value::Null);
+        assert_eq!(eval(&strict, "$json.user?.name.toUpperCase()").unwrap(), Value::Null);
+        assert_eq!(
+            eval(&strict, "$json.user?.email ?? 'none'").unwrap(),
+            Value::String("none".to_string())
+        );
+        assert_eq!(
+            eval(&strict, "$json.user?.email ? 'mail' : $json.user == null ? 'no user' : 'no mail'")
+                .unwrap(),
+            Value::String("no user".to_string())
+        );
+    }
+
+    #[test]
+    fn test_eval_arrow_functions() {
+        let item = NodeExecutionData::from_json_value(serde_json::json!({
+            "users": [
+                { "id": 1, "active": true, "age": 40 },
+                { "id": 2, "active": false, "age": 25 },
+                { "id": 3, "active": true, "age": 31 },
+            ]
+        }))
+        .unwrap();
+        let context = ExpressionContext::minimal(&item);
+        let evaluator = ExpressionEvaluator::new();
+        let eval = |source: &str| {
+            evaluator
+                .evaluate(&super::super::parser::parse(source).unwrap(), &context)
+                .unwrap()
+        };
+
+        assert_eq!(
+            eval("$json.users.filter(u => u.active).map(u => u.id)"),
+            serde_json::json!([1, 3])
+        );
+        assert_eq!(eval("$json.users.reduce((sum, u) => sum + u.age, 0)").as_f64(), Some(96.0));
+        assert_eq!(eval("$json.users.find(u => u.age < 30).id"), serde_json::json!(2));
+        assert_eq!(
+            eval("$json.users.sort((a, b) => a.age - b.age).map((u, i) => i + ':' + u.id)"),
+            serde_json::json!(["0:2", "1:3", "2:1"])
+        );
+        // Closures see the parameters of enclosing arrow functions.
+        assert_eq!(
+            eval("$json.users.map(u => [1, 2].map(n => n * u.id))").as_array().unwrap().len(),
+            3
+        );
+        assert!(evaluator
+            .evaluate(&super::super::parser::parse("(x => x)").unwrap(), &context)
+            .is_err());
+    }
+
+    #[test]
+    fn test_eval_template_literal() {
+        let item = NodeExecutionData::from_json_value(serde_json::json!({
+            "base": "https://api.example.com",
+            "id": 7,
+            "tags": ["a", "b"]
+        }))
+        .unwrap();
+        let context = ExpressionContext::minimal(&item);
+        let source =
+            "`${$json.base}/items/${$json.id}?tags=${$json.tags.map(t => `[${t}]`).join(',')}`";
+        let expr = super::super::parser::parse(source).unwrap();
+
+        assert_eq!(
+            ExpressionEvaluator::new().evaluate(&expr, &context).unwrap(),
+            Value::String("https://api.example.com/items/7?tags=[a],[b]".to_string())
+        );
+    }
+
     #[test]
     fn test_eval_conditional() {
         let evaluator = ExpressionEvaluator::new();
@@ -4,17 +4,21 @@
 //! that can be called on values in expressions.
 
 use super::{ExpressionContext, ExpressionError, ExpressionResult};
+use crate::determinism::Determinism;
+use n8n_workflow::object_to_json;
 use serde_json::Value;
 
-/// Call a method on a value.
+/// Call a method on a value. `randomItem()` and `shuffle()` draw from
+/// `determinism`.
 pub fn call_method(
     value: &Value,
     method: &str,
     args: &[Value],
+    determinism: &Determinism,
 ) -> ExpressionResult<Value> {
     match value {
         Value::String(s) => call_string_method(s, method, args),
-        Value::Array(arr) => call_array_method(arr, method, args),
+        Value::Array(arr) => call_array_method(arr, method, args, determinism),
         Value::Number(n) => call_number_method(n, method, args),
         Value::Object(obj) => call_object_method(obj, method, args),
         Value::Bool(b) => call_bool_method(*b, method, args),
@@ -29,7 +33,7 @@ pub fn call_method(
 pub fn call_function(
     name: &str,
     args: &[Value],
-    _context: &ExpressionContext,
+    context: &ExpressionContext,
 ) -> ExpressionResult<Value> {
     match name {
         // Type checking
@@ -53,8 +57,8 @@ pub fn call_function(
         )),
 
         // Date functions
-        "Date" => func_date(args),
-        "DateTime" => func_datetime(args),
+        "Date" => func_date(args, context.determinism),
+        "DateTime" => func_datetime(args, context.determinism),
 
         // Object functions
         "Object" => Err(ExpressionError::MethodNotFound(
@@ -64,6 +68,9 @@ pub fn call_function(
         // Array functions
         "Array" => func_array(args),
 
+        // JSONata over `$json` or the given data
+        "jsonata" => func_jsonata(args, context),
+
         _ => Err(ExpressionError::MethodNotFound(format!(
             "Unknown function: {}",
             name
@@ -358,7 +365,12 @@ fn hash_string(s: &str, algorithm: &str) -> ExpressionResult<Value> {
 // Array methods
 // =============================================================================
 
-fn call_array_method(arr: &[Value], method: &str, args: &[Value]) -> ExpressionResult<Value> {
+fn call_array_method(
+    arr: &[Value],
+    method: &str,
+    args: &[Value],
+    determinism: &Determinism,
+) -> ExpressionResult<Value> {
     match method {
         // Length
         "length" => Ok(Value::Number(arr.len().into())),
@@ -511,15 +523,12 @@ fn call_array_method(arr: &[Value], method: &str, args: &[Value]) -> ExpressionR
             if arr.is_empty() {
                 Ok(Value::Null)
             } else {
-                use rand::Rng;
-                let index = rand::thread_rng().gen_range(0..arr.len());
-                Ok(arr[index].clone())
+                Ok(arr[determinism.index(arr.len())].clone())
             }
         }
         "shuffle" => {
-            use rand::seq::SliceRandom;
             let mut result = arr.to_vec();
-            result.shuffle(&mut rand::thread_rng());
+            determinism.shuffle(&mut result);
             Ok(Value::Array(result))
         }
 
@@ -748,11 +757,11 @@ fn func_to_boolean(args: &[Value]) -> ExpressionResult<Value> {
     Ok(Value::Bool(boolean))
 }
 
-fn func_date(args: &[Value]) -> ExpressionResult<Value> {
-    use chrono::{NaiveDate, Utc};
+fn func_date(args: &[Value], determinism: &Determinism) -> ExpressionResult<Value> {
+    use chrono::NaiveDate;
 
     if args.is_empty() {
-        let now = Utc::now();
+        let now = determinism.now();
         return Ok(Value::String(now.format("%Y-%m-%d").to_string()));
     }
 
@@ -766,11 +775,11 @@ fn func_date(args: &[Value]) -> ExpressionResult<Value> {
     Ok(Value::Null)
 }
 
-fn func_datetime(args: &[Value]) -> ExpressionResult<Value> {
-    use chrono::{DateTime, Utc};
+fn func_datetime(args: &[Value], determinism: &Determinism) -> ExpressionResult<Value> {
+    use chrono::DateTime;
 
     if args.is_empty() {
-        let now = Utc::now();
+        let now = determinism.now();
         return Ok(Value::String(now.to_rfc3339()));
     }
 
@@ -788,6 +797,22 @@ fn func_array(args: &[Value]) -> ExpressionResult<Value> {
     Ok(Value::Array(args.to_vec()))
 }
 
+fn func_jsonata(args: &[Value], context: &ExpressionContext) -> ExpressionResult<Value> {
+    let expression = match args.first() {
+        Some(Value::String(expression)) => expression,
+        _ => {
+            return Err(ExpressionError::InvalidArgument(
+                "$jsonata() expects a JSONata expression string".to_string(),
+            ))
+        }
+    };
+    let data = match args.get(1) {
+        Some(data) => data.clone(),
+        None => Value::Object(object_to_json(&context.item.json)),
+    };
+    crate::jsonata::evaluate(expression, &data).map_err(ExpressionError::EvaluationError)
+}
+
 fn value_to_string(value: &Value) -> String {
     match value {
         Value::Null => "null".to_string(),
@@ -830,14 +855,16 @@ mod tests {
             Value::String("b".to_string()),
             Value::String("c".to_string()),
         ];
-        let result = call_array_method(&arr, "join", &[Value::String("-".to_string())]).unwrap();
+        let dash = [Value::String("-".to_string())];
+        let result = call_array_method(&arr, "join", &dash, &Determinism::default()).unwrap();
         assert_eq!(result, Value::String("a-b-c".to_string()));
     }
 
     #[test]
     fn test_array_first_last() {
         let arr = vec![Value::Number(1.into()), Value::Number(2.into()), Value::Number(3.into())];
-        assert_eq!(call_array_method(&arr, "first", &[]).unwrap(), Value::Number(1.into()));
-        assert_eq!(call_array_method(&arr, "last", &[]).unwrap(), Value::Number(3.into()));
+        let real = Determinism::default();
+        assert_eq!(call_array_method(&arr, "first", &[], &real).unwrap(), Value::Number(1.into()));
+        assert_eq!(call_array_method(&arr, "last", &[], &real).unwrap(), Value::Number(3.into()));
     }
 }
@@ -1,19 +1,23 @@
 //! Expression evaluation system for n8n.
 //!
 //! This module provides the expression parser and evaluator for n8n's
-//! expression syntax: `{{ $json.field }}`, `{{ $node.Name.json }}`, etc.
+//! expression syntax: `{{ $json.field }}`, `{{ $node.Name.json }}`,
+//! `{{ $("Name").item.json }}`, etc.
 
 pub mod parser;
 pub mod evaluator;
 pub mod extensions;
+pub mod stringify;
 pub mod variables;
 
 pub use evaluator::*;
 pub use parser::*;
 pub use extensions::*;
+pub use stringify::{ExpressionLimits, OversizeMode};
 pub use variables::*;
 
-use n8n_workflow::NodeExecutionData;
+use crate::determinism::Determinism;
+use n8n_workflow::{NodeExecutionData, TaskData};
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -28,6 +32,9 @@ pub struct ExpressionContext<'a> {
     pub run_index: usize,
     /// Access to other nodes' data.
     pub node_data: &'a HashMap<String, Vec<Vec<NodeExecutionData>>>,
+    /// Full run data, for `$("Node")` branch access; without it only the
+    /// first branch in `node_data` is reachable.
+    pub run_data: Option<&'a HashMap<String, Vec<TaskData>>>,
     /// Workflow variables.
     pub variables: &'a HashMap<String, Value>,
     /// Environment variables.
@@ -40,6 +47,11 @@ pub struct ExpressionContext<'a> {
     pub workflow_name: &'a str,
     /// Current node name.
     pub node_name: &'a str,
+    /// Arrow function parameters in scope.
+    pub locals: Option<&'a HashMap<String, Value>>,
+    /// Clock of `$now` and `Date()`, and source of `randomItem()` and
+    /// `shuffle()`.
+    pub determinism: &'a Determinism,
 }
 
 impl<'a> ExpressionContext<'a> {
@@ -50,18 +62,22 @@ impl<'a> ExpressionContext<'a> {
         static EMPTY_VARIABLES: std::sync::OnceLock<HashMap<String, Value>> =
             std::sync::OnceLock::new();
         static EMPTY_ENV: std::sync::OnceLock<HashMap<String, String>> = std::sync::OnceLock::new();
+        static REAL_CLOCK: std::sync::OnceLock<Determinism> = std::sync::OnceLock::new();
 
         Self {
             item,
             item_index: 0,
             run_index: 0,
             node_data: EMPTY_NODE_DATA.get_or_init(HashMap::new),
+            run_data: None,
             variables: EMPTY_VARIABLES.get_or_init(HashMap::new),
             env: EMPTY_ENV.get_or_init(HashMap::new),
             execution_id: "",
             workflow_id: "",
             workflow_name: "",
             node_name: "",
+            locals: None,
+            determinism: REAL_CLOCK.get_or_init(Determinism::default),
         }
     }
 }
@@ -96,6 +112,12 @@ pub enum ExpressionError {
     #[error("Node not found: {0}")]
     NodeNotFound(String),
 
+    #[error("Referenced node has not been executed: {0}")]
+    NodeNotExecuted(String),
+
     #[error("Evaluation error: {0}")]
     EvaluationError(String),
+
+    #[error("Expression result of {length} bytes exceeds the limit of {limit} bytes")]
+    ResultTooLarge { length: usize, limit: usize },
 }
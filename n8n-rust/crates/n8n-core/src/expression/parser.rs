@@ -3,8 +3,12 @@
 //! Parses expressions like:
 //! - `{{ $json.field }}`
 //! - `{{ $node["Name"].json.field }}`
+//! - `{{ $("Name").item.json.field }}`
 //! - `{{ $input.first().json }}`
 //! - `{{ $json.name.toUpperCase() }}`
+//! - `{{ $json.user?.email ?? "unknown" }}`
+//! - `{{ $json.items.map(item => item.id) }}`
+//! - `{{ `${$json.base}/users/${$json.id}` }}`
 
 use super::ExpressionError;
 
@@ -59,6 +63,18 @@ pub enum Expr {
     Object(Vec<(String, Expr)>),
     /// Template literal with embedded expressions.
     Template(Vec<TemplatePart>),
+    /// Node reference `$("Node Name")`.
+    NodeReference(Box<Expr>),
+    /// Object of a `?.` access: the rest of the chain is skipped when it is
+    /// null.
+    Optional(Box<Expr>),
+    /// Access chain containing `?.`, null when short-circuited.
+    OptionalChain(Box<Expr>),
+    /// Arrow function `(a, b) => body`, passed to array methods.
+    Lambda {
+        params: Vec<String>,
+        body: Box<Expr>,
+    },
 }
 
 /// Literal values.
@@ -116,9 +132,11 @@ enum Token {
     False,
     Number(f64),
     String(String),
+    Template(Vec<TemplatePart>), // `a ${b}`
     // Identifiers
     Ident(String),
     Variable(String), // $json, $input, etc.
+    Dollar,           // bare `$` of `$("Node")`
     // Operators
     Plus,
     Minus,
@@ -135,8 +153,10 @@ enum Token {
     Or,
     Not,
     NullishCoalesce, // ??
+    Arrow,           // =>
     // Punctuation
     Dot,
+    OptionalDot, // ?.
     Comma,
     Colon,
     Question,
@@ -151,6 +171,7 @@ enum Token {
 }
 
 /// Lexer for tokenizing expression strings.
+#[derive(Clone)]
 struct Lexer<'a> {
     input: &'a str,
     chars: std::iter::Peekable<std::str::CharIndices<'a>>,
@@ -196,6 +217,9 @@ impl<'a> Lexer<'a> {
                 self.chars.next();
                 Ok(Token::Percent)
             }
+            '.' if self.input[pos + 1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                self.read_number()
+            }
             '.' => {
                 self.chars.next();
                 Ok(Token::Dot)
@@ -236,15 +260,29 @@ impl<'a> Lexer<'a> {
             // Multi-character operators
             '?' => {
                 self.chars.next();
-                if self.chars.peek().map(|&(_, c)| c) == Some('?') {
-                    self.chars.next();
-                    Ok(Token::NullishCoalesce)
-                } else {
-                    Ok(Token::Question)
+                // `a ?.5 : b` is a conditional, not optional chaining.
+                let digit_follows = self
+                    .input
+                    .get(pos + 2..)
+                    .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+                match self.chars.peek().map(|&(_, c)| c) {
+                    Some('?') => {
+                        self.chars.next();
+                        Ok(Token::NullishCoalesce)
+                    }
+                    Some('.') if !digit_follows => {
+                        self.chars.next();
+                        Ok(Token::OptionalDot)
+                    }
+                    _ => Ok(Token::Question),
                 }
             }
             '=' => {
                 self.chars.next();
+                if self.chars.peek().map(|&(_, c)| c) == Some('>') {
+                    self.chars.next();
+                    return Ok(Token::Arrow);
+                }
                 if self.chars.peek().map(|&(_, c)| c) == Some('=') {
                     self.chars.next();
                     // Check for ===
@@ -314,6 +352,7 @@ impl<'a> Lexer<'a> {
 
             // String literals
             '"' | '\'' => self.read_string(),
+            '`' => self.read_template(),
 
             // Numbers
             '0'..='9' => self.read_number(),
@@ -379,6 +418,50 @@ impl<'a> Lexer<'a> {
         Ok(Token::String(s))
     }
 
+    /// Read a backtick template literal, parsing each `${...}` as an
+    /// expression.
+    fn read_template(&mut self) -> Result<Token, ExpressionError> {
+        let unterminated = || ExpressionError::ParseError("Unterminated template literal".into());
+        self.chars.next();
+        let mut parts = Vec::new();
+        let mut text = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some((_, '`')) => break,
+                Some((pos, '$')) if self.chars.peek().map(|&(_, c)| c) == Some('{') => {
+                    self.chars.next();
+                    let start = pos + 2;
+                    let end = start + closing_brace(&self.input[start..]).ok_or_else(unterminated)?;
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::String(std::mem::take(&mut text)));
+                    }
+                    let expr = parse(&self.input[start..end])?;
+                    parts.push(TemplatePart::Expression(Box::new(expr)));
+                    while self.chars.next().is_some_and(|(p, _)| p < end) {}
+                }
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, 'n')) => text.push('\n'),
+                    Some((_, 'r')) => text.push('\r'),
+                    Some((_, 't')) => text.push('\t'),
+                    Some((_, c @ ('\\' | '`' | '$'))) => text.push(c),
+                    Some((_, c)) => {
+                        text.push('\\');
+                        text.push(c);
+                    }
+                    None => return Err(unterminated()),
+                },
+                Some((_, ch)) => text.push(ch),
+                None => return Err(unterminated()),
+            }
+        }
+
+        if !text.is_empty() || parts.is_empty() {
+            parts.push(TemplatePart::String(text));
+        }
+        Ok(Token::Template(parts))
+    }
+
     fn read_number(&mut self) -> Result<Token, ExpressionError> {
         let start = self.current_pos;
         let mut end = start;
@@ -419,6 +502,9 @@ impl<'a> Lexer<'a> {
         }
 
         let var_name = &self.input[start..end];
+        if var_name.is_empty() && self.chars.peek().map(|&(_, c)| c) == Some('(') {
+            return Ok(Token::Dollar);
+        }
         if var_name.is_empty() {
             return Err(ExpressionError::ParseError(
                 "Expected variable name after '$'".to_string(),
@@ -670,37 +756,23 @@ impl<'a> Parser<'a> {
 
     fn parse_postfix(&mut self) -> Result<Expr, ExpressionError> {
         let mut expr = self.parse_primary()?;
+        let mut optional = false;
 
         loop {
             match &self.current {
-                Token::Dot => {
+                Token::OptionalDot => {
                     self.advance()?;
-                    match self.advance()? {
-                        Token::Ident(name) => {
-                            // Check if it's a method call
-                            if self.current == Token::LParen {
-                                self.advance()?;
-                                let args = self.parse_argument_list()?;
-                                self.expect(Token::RParen)?;
-                                expr = Expr::MethodCall {
-                                    object: Box::new(expr),
-                                    method: name,
-                                    args,
-                                };
-                            } else {
-                                expr = Expr::PropertyAccess {
-                                    object: Box::new(expr),
-                                    property: name,
-                                };
-                            }
-                        }
-                        other => {
-                            return Err(ExpressionError::ParseError(format!(
-                                "Expected property name after '.', got {:?}",
-                                other
-                            )))
-                        }
+                    expr = Expr::Optional(Box::new(expr));
+                    optional = true;
+                    // `a?.[i]` indexes; `a?.b` continues like `.`.
+                    if self.current == Token::LBracket {
+                        continue;
                     }
+                    expr = self.parse_member(expr)?;
+                }
+                Token::Dot => {
+                    self.advance()?;
+                    expr = self.parse_member(expr)?;
                 }
                 Token::LBracket => {
                     self.advance()?;
@@ -729,9 +801,40 @@ impl<'a> Parser<'a> {
             }
         }
 
+        if optional {
+            expr = Expr::OptionalChain(Box::new(expr));
+        }
         Ok(expr)
     }
 
+    /// Property access or method call on `expr` after a `.` or `?.`.
+    fn parse_member(&mut self, object: Expr) -> Result<Expr, ExpressionError> {
+        match self.advance()? {
+            Token::Ident(name) => {
+                // Check if it's a method call
+                if self.current == Token::LParen {
+                    self.advance()?;
+                    let args = self.parse_argument_list()?;
+                    self.expect(Token::RParen)?;
+                    Ok(Expr::MethodCall {
+                        object: Box::new(object),
+                        method: name,
+                        args,
+                    })
+                } else {
+                    Ok(Expr::PropertyAccess {
+                        object: Box::new(object),
+                        property: name,
+                    })
+                }
+            }
+            other => Err(ExpressionError::ParseError(format!(
+                "Expected property name after '.', got {:?}",
+                other
+            ))),
+        }
+    }
+
     fn parse_primary(&mut self) -> Result<Expr, ExpressionError> {
         match self.advance()? {
             Token::Null => Ok(Expr::Literal(Literal::Null)),
@@ -739,7 +842,18 @@ impl<'a> Parser<'a> {
             Token::False => Ok(Expr::Literal(Literal::Boolean(false))),
             Token::Number(n) => Ok(Expr::Literal(Literal::Number(n))),
             Token::String(s) => Ok(Expr::Literal(Literal::String(s))),
+            Token::Template(parts) => Ok(Expr::Template(parts)),
             Token::Variable(name) => Ok(Expr::Variable(name)),
+            Token::Dollar => {
+                self.expect(Token::LParen)?;
+                let node = self.parse_expression()?;
+                self.expect(Token::RParen)?;
+                Ok(Expr::NodeReference(Box::new(node)))
+            }
+            Token::Ident(name) if self.current == Token::Arrow => {
+                self.advance()?;
+                self.parse_lambda_body(vec![name])
+            }
             Token::Ident(name) => {
                 // Check if it's a function call
                 if self.current == Token::LParen {
@@ -753,6 +867,9 @@ impl<'a> Parser<'a> {
                 }
             }
             Token::LParen => {
+                if let Some(params) = self.arrow_params()? {
+                    return self.parse_lambda_body(params);
+                }
                 let expr = self.parse_expression()?;
                 self.expect(Token::RParen)?;
                 Ok(expr)
@@ -794,6 +911,51 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// After a `(`, the parameters of an arrow function if the parenthesis
+    /// opens one, consuming them and the `=>`. Otherwise nothing is consumed.
+    fn arrow_params(&mut self) -> Result<Option<Vec<String>>, ExpressionError> {
+        let mut lookahead = self.lexer.clone();
+        let mut token = self.current.clone();
+        let mut params = Vec::new();
+        let mut tokens = 0;
+
+        if token != Token::RParen {
+            loop {
+                match token {
+                    Token::Ident(name) => params.push(name),
+                    _ => return Ok(None),
+                }
+                token = lookahead.next_token()?;
+                tokens += 1;
+                match token {
+                    Token::Comma => {
+                        token = lookahead.next_token()?;
+                        tokens += 1;
+                    }
+                    Token::RParen => break,
+                    _ => return Ok(None),
+                }
+            }
+        }
+        if lookahead.next_token()? != Token::Arrow {
+            return Ok(None);
+        }
+
+        // Consume the parameters, `)` and `=>`.
+        for _ in 0..tokens + 2 {
+            self.advance()?;
+        }
+        Ok(Some(params))
+    }
+
+    fn parse_lambda_body(&mut self, params: Vec<String>) -> Result<Expr, ExpressionError> {
+        let body = self.parse_expression()?;
+        Ok(Expr::Lambda {
+            params,
+            body: Box::new(body),
+        })
+    }
+
     fn parse_object_pair(&mut self) -> Result<(String, Expr), ExpressionError> {
         let key = match self.advance()? {
             Token::Ident(name) | Token::String(name) => name,
@@ -838,6 +1000,45 @@ pub fn parse(input: &str) -> Result<Expr, ExpressionError> {
     Ok(expr)
 }
 
+/// Offset of the `}` closing a `${` whose contents start `input`, skipping
+/// braces inside nested objects, strings and template literals.
+fn closing_brace(input: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut chars = input.char_indices();
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            '"' | '\'' => loop {
+                match chars.next()? {
+                    (_, '\\') => {
+                        chars.next()?;
+                    }
+                    (_, c) if c == ch => break,
+                    _ => {}
+                }
+            },
+            '`' => loop {
+                match chars.next()? {
+                    (_, '\\') => {
+                        chars.next()?;
+                    }
+                    (_, '`') => break,
+                    (j, '$') if input[j + 1..].starts_with('{') => {
+                        chars.next();
+                        let end = j + 2 + closing_brace(&input[j + 2..])?;
+                        while chars.next()?.0 < end {}
+                    }
+                    _ => {}
+                }
+            },
+            _ => {}
+        }
+    }
+    None
+}
+
 /// Parse a template string with embedded expressions.
 /// Template format: "Hello {{ $json.name }}!"
 pub fn parse_template(input: &str) -> Result<Expr, ExpressionError> {
@@ -947,6 +1148,101 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_node_reference() {
+        let expr = parse("$(\"Get Orders\").all(0, 1)").unwrap();
+        assert_eq!(
+            expr,
+            Expr::MethodCall {
+                object: Box::new(Expr::NodeReference(Box::new(Expr::Literal(Literal::String(
+                    "Get Orders".to_string()
+                ))))),
+                method: "all".to_string(),
+                args: vec![
+                    Expr::Literal(Literal::Number(0.0)),
+                    Expr::Literal(Literal::Number(1.0)),
+                ],
+            }
+        );
+        assert!(parse("$.json").is_err());
+    }
+
+    #[test]
+    fn test_parse_optional_chaining() {
+        let json = || Box::new(Expr::Variable("json".to_string()));
+        let expr = parse("$json.user?.email ?? 'n/a'").unwrap();
+        assert_eq!(
+            expr,
+            Expr::BinaryOp {
+                left: Box::new(Expr::OptionalChain(Box::new(Expr::PropertyAccess {
+                    object: Box::new(Expr::Optional(Box::new(Expr::PropertyAccess {
+                        object: json(),
+                        property: "user".to_string(),
+                    }))),
+                    property: "email".to_string(),
+                }))),
+                op: BinaryOperator::NullishCoalesce,
+                right: Box::new(Expr::Literal(Literal::String("n/a".to_string()))),
+            }
+        );
+
+        // `??` binds tighter than `?:`, and `?.5` stays a conditional.
+        assert!(matches!(parse("$json.a ?? $json.b ? 1 : 2").unwrap(), Expr::Conditional { .. }));
+        assert!(matches!(parse("$json.a ?.5 : 1").unwrap(), Expr::Conditional { .. }));
+        assert!(matches!(parse("$json.items?.[0]?.id").unwrap(), Expr::OptionalChain(_)));
+    }
+
+    #[test]
+    fn test_parse_template_literal() {
+        let expr = parse("`${$json.base}/users/${$json.ids.map(id => `#${id}`)}`").unwrap();
+        let Expr::Template(parts) = expr else {
+            panic!("expected template, got {:?}", expr);
+        };
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[1], TemplatePart::String("/users/".to_string()));
+        assert!(matches!(
+            &parts[2],
+            TemplatePart::Expression(e) if matches!(**e, Expr::MethodCall { .. })
+        ));
+
+        // Braces inside strings and objects don't close the substitution.
+        let expr = parse("`a ${ {\"k\": \"}\"}.k } \\${b}`").unwrap();
+        assert!(matches!(&expr, Expr::Template(parts) if parts.len() == 3));
+        assert_eq!(parse("``").unwrap(), Expr::Template(vec![TemplatePart::String(String::new())]));
+        assert!(parse("`${a`").is_err());
+    }
+
+    #[test]
+    fn test_parse_arrow_functions() {
+        let ident = |name: &str| Expr::Variable(name.to_string());
+        let expr = parse("$json.items.map(item => item.id)").unwrap();
+        let Expr::MethodCall { args, .. } = expr else {
+            panic!("expected method call");
+        };
+        assert_eq!(
+            args,
+            vec![Expr::Lambda {
+                params: vec!["item".to_string()],
+                body: Box::new(Expr::PropertyAccess {
+                    object: Box::new(ident("item")),
+                    property: "id".to_string(),
+                }),
+            }]
+        );
+
+        let expr = parse("$json.n.reduce((sum, n) => sum + n, 0)").unwrap();
+        let Expr::MethodCall { args, .. } = expr else {
+            panic!("expected method call");
+        };
+        assert!(matches!(&args[0], Expr::Lambda { params, .. } if params.len() == 2));
+        assert_eq!(args[1], Expr::Literal(Literal::Number(0.0)));
+
+        let expr = parse("() => 1").unwrap();
+        assert!(matches!(expr, Expr::Lambda { params, .. } if params.is_empty()));
+        // A parenthesised expression is not a parameter list.
+        assert!(matches!(parse("(a) + 1").unwrap(), Expr::BinaryOp { .. }));
+    }
+
     #[test]
     fn test_parse_template() {
         let expr = parse_template("Hello {{ $json.name }}!").unwrap();
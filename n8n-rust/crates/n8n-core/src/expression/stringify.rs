@@ -0,0 +1,209 @@
+//! Size-bounded stringification of expression results.
+//!
+//! `"Order: {{ $json }}"` inlines the whole item; an expression reading a
+//! large API response this way puts megabytes into a parameter, and from
+//! there into logs, error messages and stored executions. Values
+//! interpolated into strings are limited to `max_string_length` bytes.
+//! Past the limit they are either truncated or fail the expression, per
+//! `on_oversize`.
+//!
+//! Truncation keeps the shape of the value: arrays keep their leading
+//! items and objects the keys that fit, large values among them truncated
+//! in turn, followed by a marker of what was cut (`"…(+9997 items)"`,
+//! `"…": "+40 keys"`), so the result is still valid JSON. Strings are cut
+//! at a character boundary.
+
+use super::{ExpressionError, ExpressionResult};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Room left for truncation markers.
+const MARKER_RESERVE: usize = 32;
+
+/// What happens to values over the limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OversizeMode {
+    /// Keep the leading part, marking what was cut.
+    #[default]
+    Truncate,
+    /// Fail the expression.
+    Error,
+}
+
+/// Limits on values interpolated into strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExpressionLimits {
+    /// Maximum bytes of one interpolated value; 0 disables the limit.
+    pub max_string_length: usize,
+    pub on_oversize: OversizeMode,
+}
+
+impl Default for ExpressionLimits {
+    fn default() -> Self {
+        Self {
+            max_string_length: 100_000,
+            on_oversize: OversizeMode::Truncate,
+        }
+    }
+}
+
+impl ExpressionLimits {
+    /// No limit, the behavior before limits existed.
+    pub fn unbounded() -> Self {
+        Self {
+            max_string_length: 0,
+            on_oversize: OversizeMode::Truncate,
+        }
+    }
+
+    /// String form of `value`, within the limit.
+    pub fn stringify(&self, value: &Value) -> ExpressionResult<String> {
+        let text = plain_string(value);
+        let limit = self.max_string_length;
+        if limit == 0 || text.len() <= limit {
+            return Ok(text);
+        }
+
+        match self.on_oversize {
+            OversizeMode::Error => Err(ExpressionError::ResultTooLarge {
+                length: text.len(),
+                limit,
+            }),
+            OversizeMode::Truncate => {
+                let budget = limit.saturating_sub(MARKER_RESERVE);
+                let truncated = plain_string(&truncate(value, budget));
+                Ok(cut(&truncated, limit).to_string())
+            }
+        }
+    }
+}
+
+fn plain_string(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.clone(),
+        Value::Array(_) | Value::Object(_) => serde_json::to_string(value).unwrap_or_default(),
+    }
+}
+
+/// `value` reduced to about `budget` serialized bytes.
+fn truncate(value: &Value, budget: usize) -> Value {
+    match value {
+        Value::String(s) if s.len() > budget => {
+            let kept = cut(s, budget.saturating_sub(MARKER_RESERVE));
+            let rest = s[kept.len()..].chars().count();
+            Value::String(format!("{}…(+{} chars)", kept, rest))
+        }
+        Value::Array(items) => {
+            let mut used = 2;
+            let mut kept = Vec::new();
+            for item in items {
+                let len = serialized_len(item) + 1;
+                if used + len <= budget {
+                    used += len;
+                    kept.push(item.clone());
+                    continue;
+                }
+                let room = budget.saturating_sub(used + MARKER_RESERVE);
+                if room > MARKER_RESERVE && is_container_or_string(item) {
+                    kept.push(truncate(item, room));
+                }
+                break;
+            }
+            if kept.len() < items.len() {
+                let cut_items = items.len() - kept.len();
+                kept.push(Value::String(format!("…(+{} items)", cut_items)));
+            }
+            Value::Array(kept)
+        }
+        Value::Object(map) => {
+            let mut used = 2;
+            let mut kept = Map::new();
+            for (key, item) in map {
+                let len = key.len() + 4 + serialized_len(item);
+                if used + len <= budget {
+                    used += len;
+                    kept.insert(key.clone(), item.clone());
+                    continue;
+                }
+                // Later keys may still fit, unlike later array items
+                let room = budget.saturating_sub(used + key.len() + 4 + MARKER_RESERVE);
+                if room > MARKER_RESERVE && is_container_or_string(item) {
+                    let item = truncate(item, room);
+                    used += key.len() + 4 + serialized_len(&item);
+                    kept.insert(key.clone(), item);
+                }
+            }
+            if kept.len() < map.len() {
+                let cut_keys = map.len() - kept.len();
+                kept.insert("…".to_string(), Value::String(format!("+{} keys", cut_keys)));
+            }
+            Value::Object(kept)
+        }
+        _ => value.clone(),
+    }
+}
+
+fn serialized_len(value: &Value) -> usize {
+    serde_json::to_vec(value).map(|bytes| bytes.len()).unwrap_or(0)
+}
+
+fn is_container_or_string(value: &Value) -> bool {
+    matches!(value, Value::String(_) | Value::Array(_) | Value::Object(_))
+}
+
+/// The longest prefix of `s` of at most `max` bytes.
+fn cut(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_truncation_keeps_valid_json_within_limit() {
+        let limits = ExpressionLimits {
+            max_string_length: 200,
+            on_oversize: OversizeMode::Truncate,
+        };
+        let rows: Vec<Value> = (0..1000).map(|i| json!({ "id": i, "name": "row" })).collect();
+        let value = json!({ "total": 1000, "rows": rows });
+
+        let text = limits.stringify(&value).unwrap();
+        assert!(text.len() <= 200, "{} bytes", text.len());
+        let parsed: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed["total"], 1000);
+        assert_eq!(parsed["rows"][0], json!({ "id": 0, "name": "row" }));
+        assert!(parsed["rows"].as_array().unwrap().last().unwrap().as_str().unwrap().contains("items)"));
+
+        let long = "é".repeat(300);
+        let text = limits.stringify(&Value::String(long)).unwrap();
+        assert!(text.len() <= 200 && text.ends_with("chars)"));
+
+        assert_eq!(limits.stringify(&json!({ "a": 1 })).unwrap(), r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn test_error_mode_rejects_oversized_values() {
+        let limits = ExpressionLimits {
+            max_string_length: 10,
+            on_oversize: OversizeMode::Error,
+        };
+        let err = limits.stringify(&json!("x".repeat(11))).unwrap_err();
+        assert!(matches!(err, ExpressionError::ResultTooLarge { length: 11, limit: 10 }));
+        assert_eq!(ExpressionLimits::unbounded().stringify(&json!("x".repeat(11))).unwrap().len(), 11);
+    }
+}
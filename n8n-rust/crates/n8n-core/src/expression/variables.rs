@@ -3,7 +3,10 @@
 //! Provides resolution for variables like $json, $input, $node, $execution, etc.
 
 use super::{ExpressionContext, ExpressionError, ExpressionResult};
-use chrono::{Datelike, Utc};
+use chrono::Datelike;
+use n8n_workflow::{
+    binaries_to_json, connection::CONNECTION_MAIN, item_to_json, object_to_json, NodeExecutionData,
+};
 use serde_json::Value;
 
 /// Resolve a variable by name.
@@ -30,9 +33,9 @@ pub fn resolve_variable(name: &str, context: &ExpressionContext) -> ExpressionRe
         "env" => resolve_env(context),
 
         // Date/time
-        "now" => Ok(Value::String(Utc::now().to_rfc3339())),
+        "now" => Ok(Value::String(context.determinism.now().to_rfc3339())),
         "today" => {
-            let now = Utc::now();
+            let now = context.determinism.now();
             Ok(Value::String(format!(
                 "{:04}-{:02}-{:02}",
                 now.year(),
@@ -53,35 +56,12 @@ pub fn resolve_variable(name: &str, context: &ExpressionContext) -> ExpressionRe
 
 /// Resolve $json - current item's JSON data.
 fn resolve_json(context: &ExpressionContext) -> ExpressionResult<Value> {
-    let json_map: serde_json::Map<String, Value> = context
-        .item
-        .json
-        .iter()
-        .map(|(k, v)| (k.clone(), data_value_to_json(v)))
-        .collect();
-    Ok(Value::Object(json_map))
+    Ok(Value::Object(object_to_json(&context.item.json)))
 }
 
 /// Resolve $binary - current item's binary data references.
 fn resolve_binary(context: &ExpressionContext) -> ExpressionResult<Value> {
-    match &context.item.binary {
-        Some(binary_map) => {
-            let result: serde_json::Map<String, Value> = binary_map
-                .iter()
-                .map(|(k, v)| {
-                    let bin_obj = serde_json::json!({
-                        "mimeType": v.mime_type,
-                        "fileName": v.file_name,
-                        "fileSize": v.file_size,
-                        "fileExtension": v.file_extension,
-                    });
-                    (k.clone(), bin_obj)
-                })
-                .collect();
-            Ok(Value::Object(result))
-        }
-        None => Ok(Value::Object(serde_json::Map::new())),
-    }
+    Ok(binaries_to_json(context.item.binary.as_ref()))
 }
 
 /// Resolve $input - input data reference.
@@ -114,14 +94,7 @@ fn resolve_node(context: &ExpressionContext) -> ExpressionResult<Value> {
                     Value::Array(
                         items
                             .iter()
-                            .map(|item| {
-                                let json_map: serde_json::Map<String, Value> = item
-                                    .json
-                                    .iter()
-                                    .map(|(k, v)| (k.clone(), data_value_to_json(v)))
-                                    .collect();
-                                Value::Object(json_map)
-                            })
+                            .map(|item| Value::Object(object_to_json(&item.json)))
                             .collect(),
                     )
                 })
@@ -180,25 +153,106 @@ fn resolve_env(context: &ExpressionContext) -> ExpressionResult<Value> {
     Ok(Value::Object(env))
 }
 
-/// Convert GenericValue to JSON Value.
-fn data_value_to_json(value: &n8n_workflow::GenericValue) -> Value {
-    match value {
-        n8n_workflow::GenericValue::Null => Value::Null,
-        n8n_workflow::GenericValue::Bool(b) => Value::Bool(*b),
-        n8n_workflow::GenericValue::Integer(i) => Value::Number((*i).into()),
-        n8n_workflow::GenericValue::Float(f) => {
-            Value::Number(serde_json::Number::from_f64(*f).unwrap_or_else(|| 0.into()))
+/// Accessor behind `$("Node")`.
+///
+/// Without a run index the node's latest run is used; without a branch
+/// index its first output. `item` matches items by index, so it is the
+/// referenced node's item at the current item index.
+pub struct NodeReference<'a> {
+    name: String,
+    context: &'a ExpressionContext<'a>,
+}
+
+impl<'a> NodeReference<'a> {
+    pub fn new(name: impl Into<String>, context: &'a ExpressionContext<'a>) -> Self {
+        Self {
+            name: name.into(),
+            context,
+        }
+    }
+
+    /// Whether the node has run in this execution.
+    pub fn is_executed(&self) -> bool {
+        match self.context.run_data {
+            Some(run_data) => run_data.get(&self.name).is_some_and(|runs| !runs.is_empty()),
+            None => self.context.node_data.get(&self.name).is_some_and(|runs| !runs.is_empty()),
         }
-        n8n_workflow::GenericValue::String(s) => Value::String(s.clone()),
-        n8n_workflow::GenericValue::Array(arr) => {
-            Value::Array(arr.iter().map(data_value_to_json).collect())
+    }
+
+    /// Items of `branch` in run `run`.
+    fn items(
+        &self,
+        branch: Option<usize>,
+        run: Option<usize>,
+    ) -> ExpressionResult<Vec<&'a NodeExecutionData>> {
+        let not_executed = || ExpressionError::NodeNotExecuted(self.name.clone());
+        let branch = branch.unwrap_or(0);
+
+        if let Some(run_data) = self.context.run_data {
+            let runs = run_data.get(&self.name).filter(|r| !r.is_empty()).ok_or_else(not_executed)?;
+            let task = &runs[self.run_index(run, runs.len())?];
+            return Ok(task
+                .data
+                .as_ref()
+                .and_then(|data| data.get(CONNECTION_MAIN))
+                .and_then(|outputs| outputs.get(branch))
+                .map(|items| items.iter().collect())
+                .unwrap_or_default());
         }
-        n8n_workflow::GenericValue::Object(obj) => {
-            let map: serde_json::Map<String, Value> =
-                obj.iter().map(|(k, v)| (k.clone(), data_value_to_json(v))).collect();
-            Value::Object(map)
+
+        let runs = self
+            .context
+            .node_data
+            .get(&self.name)
+            .filter(|r| !r.is_empty())
+            .ok_or_else(not_executed)?;
+        let items = &runs[self.run_index(run, runs.len())?];
+        Ok(if branch == 0 { items.iter().collect() } else { Vec::new() })
+    }
+
+    fn run_index(&self, run: Option<usize>, runs: usize) -> ExpressionResult<usize> {
+        match run {
+            None => Ok(runs - 1),
+            Some(run) if run < runs => Ok(run),
+            Some(run) => Err(ExpressionError::InvalidIndex(format!(
+                "Run {} of node '{}' does not exist ({} runs)",
+                run, self.name, runs
+            ))),
         }
     }
+
+    /// `.all(branchIndex?, runIndex?)`
+    pub fn all(&self, branch: Option<usize>, run: Option<usize>) -> ExpressionResult<Value> {
+        Ok(Value::Array(self.items(branch, run)?.into_iter().map(item_to_json).collect()))
+    }
+
+    /// `.first(branchIndex?, runIndex?)`
+    pub fn first(&self, branch: Option<usize>, run: Option<usize>) -> ExpressionResult<Value> {
+        Ok(self.items(branch, run)?.first().map(|i| item_to_json(i)).unwrap_or(Value::Null))
+    }
+
+    /// `.last(branchIndex?, runIndex?)`
+    pub fn last(&self, branch: Option<usize>, run: Option<usize>) -> ExpressionResult<Value> {
+        Ok(self.items(branch, run)?.last().map(|i| item_to_json(i)).unwrap_or(Value::Null))
+    }
+
+    /// `.item`, the item matching the current one.
+    pub fn item(&self) -> ExpressionResult<Value> {
+        self.item_matching(self.context.item_index)
+    }
+
+    /// `.itemMatching(itemIndex)`
+    pub fn item_matching(&self, index: usize) -> ExpressionResult<Value> {
+        self.items(None, None)?
+            .get(index)
+            .map(|i| item_to_json(i))
+            .ok_or_else(|| {
+                ExpressionError::InvalidIndex(format!(
+                    "Node '{}' has no item {}",
+                    self.name, index
+                ))
+            })
+    }
 }
 
 /// Node data accessor for $input.
@@ -0,0 +1,420 @@
+//! Drafting workflows from natural-language descriptions.
+//!
+//! A [`WorkflowGenerator`] asks an [`LlmProvider`] for a workflow that does
+//! what a description says. The prompt lists the built-in node types with
+//! their parameters, and the answer is held to them: nodes of other types
+//! are dropped, parameters a type does not declare are removed and
+//! connections to missing nodes go with them. What was changed, and what
+//! the [`Linter`] finds in the result, comes back as diagnostics next to
+//! the draft. Drafts are never saved or activated.
+//!
+//! [`OpenAiCompatibleProvider`] talks to any chat-completions API: OpenAI,
+//! Azure OpenAI, or a local server such as Ollama or vLLM.
+
+use crate::lint::{Diagnostic, Linter, Severity};
+use crate::node_types::builtin_node_type_descriptions;
+use async_trait::async_trait;
+use n8n_workflow::{Node, NodeTypeDescription, Workflow};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Instructions ahead of the node type list.
+const SYSTEM_PROMPT: &str = "You design n8n workflows. Answer with one JSON object and \
+nothing else, shaped {\"name\": string, \"nodes\": [{\"name\": string, \"type\": string, \
+\"parameters\": object}], \"connections\": {\"<source node>\": {\"main\": [[{\"node\": \
+\"<target node>\", \"type\": \"main\", \"index\": 0}]]}}}. Start with exactly one trigger \
+node, give every node a unique name, and use only the node types and parameters listed \
+below.";
+
+/// Workflow generation errors.
+#[derive(Error, Debug)]
+pub enum GenerationError {
+    #[error("Description must not be empty")]
+    EmptyDescription,
+
+    #[error("LLM provider failed: {0}")]
+    Provider(String),
+
+    #[error("LLM answer is not a workflow: {0}")]
+    InvalidDraft(String),
+}
+
+/// A large language model that answers prompts.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Answer `prompt` following the `system` instructions.
+    async fn complete(&self, system: &str, prompt: &str) -> Result<String, GenerationError>;
+}
+
+/// Chat-completions API of an [`OpenAiCompatibleProvider`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LlmConfig {
+    /// Serve workflow generation.
+    pub enabled: bool,
+    /// API base URL; requests go to `{base_url}/chat/completions`.
+    pub base_url: String,
+    /// Bearer token, if the API needs one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    pub model: String,
+    /// Seconds before a completion is abandoned.
+    pub timeout_secs: u64,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            base_url: "https://api.openai.com/v1".to_string(),
+            api_key: None,
+            model: "gpt-4o-mini".to_string(),
+            timeout_secs: 120,
+        }
+    }
+}
+
+/// An LLM behind an OpenAI-compatible chat-completions API.
+pub struct OpenAiCompatibleProvider {
+    client: reqwest::Client,
+    config: LlmConfig,
+}
+
+impl OpenAiCompatibleProvider {
+    pub fn new(config: LlmConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .unwrap_or_default();
+        Self { client, config }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiCompatibleProvider {
+    async fn complete(&self, system: &str, prompt: &str) -> Result<String, GenerationError> {
+        let error = |e: String| GenerationError::Provider(e);
+        let url = format!("{}/chat/completions", self.config.base_url.trim_end_matches('/'));
+        let mut request = self.client.post(url).json(&json!({
+            "model": self.config.model,
+            "temperature": 0,
+            "messages": [
+                { "role": "system", "content": system },
+                { "role": "user", "content": prompt },
+            ],
+        }));
+        if let Some(key) = &self.config.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request.send().await.map_err(|e| error(e.to_string()))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(error(format!("{} {}", status, body)));
+        }
+        let body: Value = response.json().await.map_err(|e| error(e.to_string()))?;
+        body.pointer("/choices/0/message/content")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| error("answer has no message content".to_string()))
+    }
+}
+
+/// A generated workflow with what was found wrong with it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowDraft {
+    pub workflow: Workflow,
+    /// Changes made to fit the node types, then lint findings; errors
+    /// first.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl WorkflowDraft {
+    /// Whether any diagnostic is an error.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|d| d.severity == Severity::Error)
+    }
+}
+
+/// Drafts workflows with an [`LlmProvider`], constrained to known node
+/// types.
+pub struct WorkflowGenerator {
+    provider: Arc<dyn LlmProvider>,
+    node_types: Vec<NodeTypeDescription>,
+    linter: Linter,
+}
+
+impl WorkflowGenerator {
+    /// A generator offering the built-in node types, linted with the
+    /// built-in rules.
+    pub fn new(provider: Arc<dyn LlmProvider>) -> Self {
+        Self {
+            provider,
+            node_types: builtin_node_type_descriptions(),
+            linter: Linter::default(),
+        }
+    }
+
+    /// Lint drafts with `linter`.
+    pub fn with_linter(mut self, linter: Linter) -> Self {
+        self.linter = linter;
+        self
+    }
+
+    /// Draft a workflow that does what `description` says.
+    pub async fn generate(&self, description: &str) -> Result<WorkflowDraft, GenerationError> {
+        let description = description.trim();
+        if description.is_empty() {
+            return Err(GenerationError::EmptyDescription);
+        }
+        let answer = self.provider.complete(&self.system_prompt(), description).await?;
+        self.draft(&answer)
+    }
+
+    /// The instructions, with the node types a draft may use.
+    pub fn system_prompt(&self) -> String {
+        let node_types: Vec<Value> = self
+            .node_types
+            .iter()
+            .map(|node_type| {
+                let parameters: Vec<Value> = node_type
+                    .properties
+                    .iter()
+                    .map(|p| {
+                        let options = p.options.as_ref().map(|options| {
+                            options.iter().map(|o| o.value.clone()).collect::<Vec<_>>()
+                        });
+                        json!({
+                            "name": p.name,
+                            "type": p.property_type,
+                            "required": p.required,
+                            "description": p.description,
+                            "options": options,
+                        })
+                    })
+                    .collect();
+                json!({
+                    "type": node_type.name,
+                    "description": node_type.description,
+                    "trigger": node_type.trigger,
+                    "parameters": parameters,
+                })
+            })
+            .collect();
+        let listed = serde_json::to_string_pretty(&node_types).unwrap_or_default();
+        format!("{}\n\nNode types:\n{}", SYSTEM_PROMPT, listed)
+    }
+
+    /// The draft in an LLM answer.
+    fn draft(&self, answer: &str) -> Result<WorkflowDraft, GenerationError> {
+        let mut value = extract_json(answer)?;
+        let object = value
+            .as_object_mut()
+            .ok_or_else(|| GenerationError::InvalidDraft("not a JSON object".to_string()))?;
+        object.insert("id".to_string(), uuid::Uuid::new_v4().to_string().into());
+        object.insert("active".to_string(), false.into());
+        object.entry("name").or_insert_with(|| "Generated workflow".into());
+        object.entry("connections").or_insert_with(|| json!({}));
+        if let Some(nodes) = object.get_mut("nodes").and_then(Value::as_array_mut) {
+            for (i, node) in nodes.iter_mut().filter_map(Value::as_object_mut).enumerate() {
+                node.entry("id").or_insert_with(|| uuid::Uuid::new_v4().to_string().into());
+                node.entry("typeVersion").or_insert_with(|| 1.into());
+                node.entry("position").or_insert_with(|| json!([250 * i, 300]));
+            }
+        }
+        let mut workflow: Workflow = serde_json::from_value(value)
+            .map_err(|e| GenerationError::InvalidDraft(e.to_string()))?;
+
+        let mut diagnostics = self.constrain(&mut workflow);
+        diagnostics.extend(self.linter.lint(&workflow).diagnostics);
+        diagnostics.sort_by(|a, b| b.severity.cmp(&a.severity));
+        Ok(WorkflowDraft { workflow, diagnostics })
+    }
+
+    /// Hold `workflow` to the node types, reporting every change.
+    fn constrain(&self, workflow: &mut Workflow) -> Vec<Diagnostic> {
+        let types: HashMap<&str, &NodeTypeDescription> =
+            self.node_types.iter().map(|t| (t.name.as_str(), t)).collect();
+        let mut diagnostics = Vec::new();
+        let mut removed = HashSet::new();
+
+        workflow.nodes.retain_mut(|node| {
+            let Some(node_type) = types.get(node.node_type.as_str()) else {
+                diagnostics.push(diagnostic(
+                    "unknown-node-type",
+                    Severity::Error,
+                    format!("Unknown node type '{}'; node removed", node.node_type),
+                    node,
+                    None,
+                ));
+                removed.insert(node.name.clone());
+                return false;
+            };
+            let declared = |name: &str| node_type.properties.iter().any(|p| p.name == name);
+            let mut unknown: Vec<String> =
+                node.parameters.keys().filter(|name| !declared(name)).cloned().collect();
+            unknown.sort();
+            for name in unknown {
+                node.parameters.remove(&name);
+                diagnostics.push(diagnostic(
+                    "unknown-parameter",
+                    Severity::Warning,
+                    format!("Not a parameter of {}; removed", node_type.name),
+                    node,
+                    Some(name),
+                ));
+            }
+            for property in &node_type.properties {
+                let missing = !node.parameters.contains_key(&property.name);
+                if property.required && property.default.is_none() && missing {
+                    diagnostics.push(diagnostic(
+                        "missing-parameter",
+                        Severity::Warning,
+                        "Required parameter is not set".to_string(),
+                        node,
+                        Some(property.name.clone()),
+                    ));
+                }
+            }
+            true
+        });
+
+        let present: HashSet<String> = workflow.nodes.iter().map(|n| n.name.clone()).collect();
+        let mut dangling = Vec::new();
+        workflow.connections.retain(|source, outputs| {
+            if !present.contains(source) {
+                if !removed.contains(source) {
+                    dangling.push(source.clone());
+                }
+                return false;
+            }
+            for targets in outputs.values_mut().flatten() {
+                targets.retain(|target| {
+                    let keep = present.contains(&target.node);
+                    if !keep && !removed.contains(&target.node) {
+                        dangling.push(target.node.clone());
+                    }
+                    keep
+                });
+            }
+            true
+        });
+        for name in dangling {
+            diagnostics.push(Diagnostic {
+                rule_id: "dangling-connection".to_string(),
+                severity: Severity::Warning,
+                message: format!("Connection to missing node '{}' removed", name),
+                node: None,
+                parameter: None,
+            });
+        }
+
+        let triggers = workflow
+            .nodes
+            .iter()
+            .filter(|node| types.get(node.node_type.as_str()).is_some_and(|t| t.trigger))
+            .count();
+        if triggers == 0 {
+            diagnostics.push(Diagnostic {
+                rule_id: "missing-trigger".to_string(),
+                severity: Severity::Warning,
+                message: "The draft has no trigger node".to_string(),
+                node: None,
+                parameter: None,
+            });
+        }
+        diagnostics
+    }
+}
+
+fn diagnostic(
+    rule_id: &str,
+    severity: Severity,
+    message: String,
+    node: &Node,
+    parameter: Option<String>,
+) -> Diagnostic {
+    Diagnostic {
+        rule_id: rule_id.to_string(),
+        severity,
+        message,
+        node: Some(node.name.clone()),
+        parameter,
+    }
+}
+
+/// The outermost JSON object in `answer`, which may wrap it in prose or a
+/// code fence.
+fn extract_json(answer: &str) -> Result<Value, GenerationError> {
+    match (answer.find('{'), answer.rfind('}')) {
+        (Some(start), Some(end)) if start < end => serde_json::from_str(&answer[start..=end])
+            .map_err(|e| GenerationError::InvalidDraft(e.to_string())),
+        _ => Err(GenerationError::InvalidDraft("no JSON object in the answer".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CannedProvider(&'static str);
+
+    #[async_trait]
+    impl LlmProvider for CannedProvider {
+        async fn complete(&self, system: &str, _prompt: &str) -> Result<String, GenerationError> {
+            assert!(system.contains("n8n-nodes-base.httpRequest"));
+            Ok(self.0.to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_draft_is_held_to_node_types() {
+        let answer = r#"Here it is:
+```json
+{
+  "name": "Fetch and post",
+  "nodes": [
+    { "name": "Start", "type": "n8n-nodes-base.manualTrigger", "parameters": {} },
+    { "name": "Fetch", "type": "n8n-nodes-base.httpRequest",
+      "parameters": { "url": "https://example.com", "colour": "blue" } },
+    { "name": "Post", "type": "n8n-nodes-base.slack", "parameters": {} }
+  ],
+  "connections": {
+    "Start": { "main": [[{ "node": "Fetch", "type": "main", "index": 0 }]] },
+    "Fetch": { "main": [[{ "node": "Post", "type": "main", "index": 0 }]] }
+  }
+}
+```"#;
+        let generator = WorkflowGenerator::new(Arc::new(CannedProvider(answer)));
+        let draft = generator.generate("fetch a page and post it").await.unwrap();
+
+        let names: Vec<&str> = draft.workflow.nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, ["Start", "Fetch"]);
+        assert!(!draft.workflow.nodes[1].parameters.contains_key("colour"));
+        assert!(draft.workflow.connections["Fetch"]["main"][0].is_empty());
+        assert!(draft.has_errors());
+        assert_eq!(draft.diagnostics[0].rule_id, "unknown-node-type");
+        let unknown = draft.diagnostics.iter().find(|d| d.rule_id == "unknown-parameter");
+        assert_eq!(unknown.and_then(|d| d.parameter.as_deref()), Some("colour"));
+        assert!(!draft.diagnostics.iter().any(|d| d.rule_id == "dangling-connection"));
+    }
+
+    #[tokio::test]
+    async fn test_answers_without_a_workflow_are_rejected() {
+        let generator = WorkflowGenerator::new(Arc::new(CannedProvider("I cannot help")));
+        assert!(matches!(
+            generator.generate("anything").await,
+            Err(GenerationError::InvalidDraft(_))
+        ));
+        assert!(matches!(
+            generator.generate("  ").await,
+            Err(GenerationError::EmptyDescription)
+        ));
+    }
+}
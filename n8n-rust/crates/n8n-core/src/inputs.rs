@@ -0,0 +1,204 @@
+//! Manual-run input parameters.
+//!
+//! A workflow's `inputs` setting declares the parameters an operator
+//! supplies when running it by hand. [`resolve_inputs`] checks supplied
+//! values against the declarations, fills in defaults and converts form
+//! strings (`"42"`, `"true"`) to the declared type; the result becomes the
+//! trigger item. [`input_schema`] describes the parameters as a JSON Schema
+//! that UIs can render as a form.
+
+use n8n_workflow::{WorkflowInput, WorkflowInputType};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+/// Why a supplied input was refused.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InputError {
+    pub name: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name, self.message)
+    }
+}
+
+/// Validate `values` against `inputs` and return the trigger item's JSON:
+/// every declared input that has a value or default. Undeclared values
+/// are refused.
+pub fn resolve_inputs(
+    inputs: &[WorkflowInput],
+    values: &Map<String, Value>,
+) -> Result<Map<String, Value>, Vec<InputError>> {
+    let mut errors = Vec::new();
+    let error = |name: &str, message: String| InputError {
+        name: name.to_string(),
+        message,
+    };
+
+    for name in values.keys() {
+        if !inputs.iter().any(|input| &input.name == name) {
+            errors.push(error(name, "Unknown input".to_string()));
+        }
+    }
+
+    let mut item = Map::new();
+    for input in inputs {
+        let value = match values.get(&input.name).filter(|v| !v.is_null()) {
+            Some(value) => value.clone(),
+            None => match &input.default {
+                Some(default) => default.clone(),
+                None if input.required => {
+                    errors.push(error(&input.name, "Required".to_string()));
+                    continue;
+                }
+                None => continue,
+            },
+        };
+        match coerce(input.input_type, value) {
+            Ok(value) if !input.options.is_empty() && !input.options.contains(&value) => {
+                errors.push(error(&input.name, format!("Must be one of {}", json!(input.options))));
+            }
+            Ok(value) => {
+                item.insert(input.name.clone(), value);
+            }
+            Err(message) => errors.push(error(&input.name, message)),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(item)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Convert `value` to `input_type`, accepting strings as submitted by forms
+/// and query strings.
+fn coerce(input_type: WorkflowInputType, value: Value) -> Result<Value, String> {
+    match (input_type, value) {
+        (WorkflowInputType::String, Value::String(s)) => Ok(Value::String(s)),
+        (WorkflowInputType::String, v @ (Value::Number(_) | Value::Bool(_))) => {
+            Ok(Value::String(v.to_string()))
+        }
+        (WorkflowInputType::Number, Value::Number(n)) => Ok(Value::Number(n)),
+        (WorkflowInputType::Number, Value::String(s)) => serde_json::from_str::<Value>(s.trim())
+            .ok()
+            .filter(Value::is_number)
+            .ok_or_else(|| format!("Expected a number, got {:?}", s)),
+        (WorkflowInputType::Boolean, Value::Bool(b)) => Ok(Value::Bool(b)),
+        (WorkflowInputType::Boolean, Value::String(s)) => match s.trim() {
+            "true" | "1" | "on" | "yes" => Ok(Value::Bool(true)),
+            "false" | "0" | "off" | "no" => Ok(Value::Bool(false)),
+            _ => Err(format!("Expected a boolean, got {:?}", s)),
+        },
+        (WorkflowInputType::Json, Value::String(s)) => {
+            // Forms submit JSON as text; plain strings stay strings.
+            Ok(serde_json::from_str(&s).unwrap_or(Value::String(s)))
+        }
+        (WorkflowInputType::Json, v) => Ok(v),
+        (expected, v) => Err(format!("Expected a {}, got {}", type_name(expected), v)),
+    }
+}
+
+fn type_name(input_type: WorkflowInputType) -> &'static str {
+    match input_type {
+        WorkflowInputType::String => "string",
+        WorkflowInputType::Number => "number",
+        WorkflowInputType::Boolean => "boolean",
+        WorkflowInputType::Json => "JSON value",
+    }
+}
+
+/// JSON Schema of the object [`resolve_inputs`] accepts.
+pub fn input_schema(inputs: &[WorkflowInput]) -> Value {
+    let properties: Map<String, Value> = inputs
+        .iter()
+        .map(|input| {
+            let mut schema = Map::new();
+            if input.input_type != WorkflowInputType::Json {
+                schema.insert("type".into(), json!(type_name(input.input_type)));
+            }
+            if let Some(description) = &input.description {
+                schema.insert("description".into(), json!(description));
+            }
+            if let Some(default) = &input.default {
+                schema.insert("default".into(), default.clone());
+            }
+            if !input.options.is_empty() {
+                schema.insert("enum".into(), json!(input.options));
+            }
+            (input.name.clone(), Value::Object(schema))
+        })
+        .collect();
+    let required: Vec<&str> = inputs
+        .iter()
+        .filter(|input| input.required && input.default.is_none())
+        .map(|input| input.name.as_str())
+        .collect();
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(name: &str, input_type: WorkflowInputType) -> WorkflowInput {
+        WorkflowInput {
+            name: name.to_string(),
+            input_type,
+            required: false,
+            default: None,
+            description: None,
+            options: vec![],
+        }
+    }
+
+    fn declared() -> Vec<WorkflowInput> {
+        vec![
+            WorkflowInput {
+                required: true,
+                ..input("customerId", WorkflowInputType::String)
+            },
+            WorkflowInput {
+                default: Some(json!(30)),
+                ..input("days", WorkflowInputType::Number)
+            },
+            input("dryRun", WorkflowInputType::Boolean),
+            WorkflowInput {
+                options: vec![json!("eu"), json!("us")],
+                ..input("region", WorkflowInputType::String)
+            },
+        ]
+    }
+
+    #[test]
+    fn test_resolve_applies_defaults_and_coerces_form_values() {
+        let values = json!({ "customerId": "c-1", "dryRun": "true", "region": "eu" });
+        let item = resolve_inputs(&declared(), values.as_object().unwrap()).unwrap();
+        assert_eq!(
+            Value::Object(item),
+            json!({ "customerId": "c-1", "days": 30, "dryRun": true, "region": "eu" })
+        );
+
+        let values = json!({ "days": "soon", "region": "apac", "extra": 1 });
+        let errors = resolve_inputs(&declared(), values.as_object().unwrap()).unwrap_err();
+        let names: Vec<&str> = errors.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["extra", "customerId", "days", "region"]);
+    }
+
+    #[test]
+    fn test_schema_lists_required_inputs_without_defaults() {
+        let schema = input_schema(&declared());
+        assert_eq!(schema["required"], json!(["customerId"]));
+        assert_eq!(schema["properties"]["days"], json!({ "type": "number", "default": 30 }));
+        assert_eq!(schema["properties"]["region"]["enum"], json!(["eu", "us"]));
+    }
+}
@@ -0,0 +1,421 @@
+//! Tamper-evident execution records.
+//!
+//! With [`IntegrityConfig::enabled`], the engine hashes every task's input
+//! and output and links the task into a chain:
+//!
+//! ```text
+//! hash[n] = H(hash[n-1], n, node, runIndex, status, inputHash, outputHash)
+//! hash[-1] = H("n8n-integrity-v1", executionId, workflowId)
+//! ```
+//!
+//! Each task stores its link under the `integrity` key of its metadata,
+//! and the run stores the chain head, length and the engine's execution ID
+//! in its result metadata.
+//! [`verify`] recomputes the chain from a stored run: editing, removing or
+//! reordering a task, or changing a retained output, breaks it. With a
+//! `secret`, links are HMAC-SHA256 so the chain cannot be recomputed by
+//! someone who can only edit the database.
+//!
+//! The execution ID is read from the run itself, so a valid chain shows the
+//! record is unchanged, not which ID it is stored under. Inputs are not
+//! stored with the run, so input hashes are only checked through the
+//! chain. Runs saved without node data (see
+//! [`sampling`](crate::sampling)) verify their chain but not their outputs.
+
+use crate::sampling::{DataRetention, DATA_RETENTION_KEY};
+use hmac::{Hmac, Mac};
+use n8n_workflow::{DataObject, GenericValue, Run, TaskData, TaskDataConnections, TaskMetadata};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Task metadata key holding a task's chain link.
+pub const INTEGRITY_KEY: &str = "integrity";
+/// Result metadata key holding the chain head.
+pub const INTEGRITY_HEAD_KEY: &str = "integrityHead";
+/// Result metadata key holding the number of links.
+pub const INTEGRITY_LENGTH_KEY: &str = "integrityLength";
+/// Result metadata key holding the link algorithm.
+pub const INTEGRITY_ALGORITHM_KEY: &str = "integrityAlgorithm";
+/// Result metadata key holding the execution ID the chain was started with.
+pub const INTEGRITY_EXECUTION_KEY: &str = "integrityExecutionId";
+
+const GENESIS: &str = "n8n-integrity-v1";
+
+/// Execution hash chain settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IntegrityConfig {
+    /// Hash every task of every execution.
+    pub enabled: bool,
+    /// Key for HMAC-SHA256 links; plain SHA-256 without one.
+    pub secret: Option<String>,
+}
+
+impl IntegrityConfig {
+    fn algorithm(&self) -> &'static str {
+        if self.secret.is_some() {
+            "hmac-sha256"
+        } else {
+            "sha256"
+        }
+    }
+}
+
+/// One link of an execution's hash chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainEntry {
+    pub sequence: u64,
+    pub node: String,
+    pub run_index: usize,
+    pub status: String,
+    pub input_hash: String,
+    pub output_hash: String,
+    pub hash: String,
+}
+
+impl ChainEntry {
+    fn to_metadata(&self) -> GenericValue {
+        let mut link = DataObject::new();
+        link.insert("sequence".to_string(), GenericValue::Integer(self.sequence as i64));
+        link.insert("inputHash".to_string(), GenericValue::String(self.input_hash.clone()));
+        link.insert("outputHash".to_string(), GenericValue::String(self.output_hash.clone()));
+        link.insert("hash".to_string(), GenericValue::String(self.hash.clone()));
+        GenericValue::Object(link)
+    }
+
+    fn from_task(node: &str, run_index: usize, task: &TaskData) -> Option<Self> {
+        let GenericValue::Object(link) = task.metadata.as_ref()?.custom.get(INTEGRITY_KEY)? else {
+            return None;
+        };
+        let string = |key: &str| match link.get(key) {
+            Some(GenericValue::String(s)) => Some(s.clone()),
+            _ => None,
+        };
+        let sequence = match link.get("sequence") {
+            Some(GenericValue::Integer(n)) if *n >= 0 => *n as u64,
+            _ => return None,
+        };
+        Some(Self {
+            sequence,
+            node: node.to_string(),
+            run_index,
+            status: task.execution_status.as_str().to_string(),
+            input_hash: string("inputHash")?,
+            output_hash: string("outputHash")?,
+            hash: string("hash")?,
+        })
+    }
+}
+
+/// Builds an execution's hash chain as tasks finish.
+pub struct HashChain {
+    config: IntegrityConfig,
+    execution_id: String,
+    head: String,
+    length: u64,
+}
+
+impl HashChain {
+    pub fn new(config: &IntegrityConfig, execution_id: &str, workflow_id: &str) -> Self {
+        Self {
+            head: genesis(config, execution_id, workflow_id),
+            config: config.clone(),
+            execution_id: execution_id.to_string(),
+            length: 0,
+        }
+    }
+
+    /// Link `task` into the chain, record the link on the task and the new
+    /// head on `run`.
+    pub fn append(
+        &mut self,
+        run: &mut Run,
+        node: &str,
+        run_index: usize,
+        input: &TaskDataConnections,
+        task: &mut TaskData,
+    ) {
+        let mut entry = ChainEntry {
+            sequence: self.length,
+            node: node.to_string(),
+            run_index,
+            status: task.execution_status.as_str().to_string(),
+            input_hash: content_hash(&serde_json::to_value(input).unwrap_or_default()),
+            output_hash: output_hash(task),
+            hash: String::new(),
+        };
+        entry.hash = link(&self.config, &self.head, &entry);
+
+        task.metadata
+            .get_or_insert_with(TaskMetadata::default)
+            .custom
+            .insert(INTEGRITY_KEY.to_string(), entry.to_metadata());
+
+        self.head = entry.hash;
+        self.length += 1;
+
+        let metadata = run.data.result_data.metadata.get_or_insert_with(Default::default);
+        metadata.insert(INTEGRITY_ALGORITHM_KEY.to_string(), self.config.algorithm().to_string());
+        metadata.insert(INTEGRITY_EXECUTION_KEY.to_string(), self.execution_id.clone());
+        metadata.insert(INTEGRITY_HEAD_KEY.to_string(), self.head.clone());
+        metadata.insert(INTEGRITY_LENGTH_KEY.to_string(), self.length.to_string());
+    }
+}
+
+/// Result of [`verify`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationReport {
+    /// The chain and every retained output check out.
+    pub valid: bool,
+    pub algorithm: Option<String>,
+    /// Execution ID the chain was started with.
+    pub execution_id: Option<String>,
+    pub length: u64,
+    pub head: Option<String>,
+    /// Tasks whose output was not retained and so could not be checked.
+    pub unverified_outputs: usize,
+    pub problems: Vec<String>,
+}
+
+/// The chain links of a stored run, in chain order.
+pub fn export_chain(run: &Run) -> Vec<ChainEntry> {
+    let mut entries: Vec<ChainEntry> = run
+        .data
+        .result_data
+        .run_data
+        .iter()
+        .flat_map(|(node, tasks)| {
+            tasks
+                .iter()
+                .enumerate()
+                .filter_map(move |(i, task)| ChainEntry::from_task(node, i, task))
+        })
+        .collect();
+    entries.sort_by_key(|e| e.sequence);
+    entries
+}
+
+/// Verify a stored run of `workflow_id` against its hash chain.
+pub fn verify(run: &Run, workflow_id: &str, config: &IntegrityConfig) -> VerificationReport {
+    let metadata = run.data.result_data.metadata.as_ref();
+    let recorded = |key: &str| metadata.and_then(|m| m.get(key)).cloned();
+
+    let mut report = VerificationReport {
+        valid: false,
+        algorithm: recorded(INTEGRITY_ALGORITHM_KEY),
+        execution_id: recorded(INTEGRITY_EXECUTION_KEY),
+        length: 0,
+        head: recorded(INTEGRITY_HEAD_KEY),
+        unverified_outputs: 0,
+        problems: Vec::new(),
+    };
+    let (Some(head), Some(execution_id)) = (report.head.clone(), report.execution_id.clone())
+    else {
+        report.problems.push("Execution has no hash chain".to_string());
+        return report;
+    };
+    if report.algorithm.as_deref() != Some(config.algorithm()) {
+        report.problems.push(format!(
+            "Chain uses {}, verifier is configured for {}",
+            report.algorithm.as_deref().unwrap_or("unknown"),
+            config.algorithm()
+        ));
+        return report;
+    }
+
+    let tasks: usize = run.data.result_data.run_data.values().map(Vec::len).sum();
+    let entries = export_chain(run);
+    if entries.len() != tasks {
+        report.problems.push(format!(
+            "{} of {} tasks have no chain link",
+            tasks - entries.len(),
+            tasks
+        ));
+    }
+    let data_retained =
+        recorded(DATA_RETENTION_KEY).as_deref() != Some(DataRetention::Metadata.as_str());
+
+    let mut previous = genesis(config, &execution_id, workflow_id);
+    for (expected, entry) in entries.iter().enumerate() {
+        if entry.sequence != expected as u64 {
+            report.problems.push(format!(
+                "Link {} missing before '{}' run {}",
+                expected, entry.node, entry.run_index
+            ));
+            break;
+        }
+        if link(config, &previous, entry) != entry.hash {
+            report.problems.push(format!(
+                "Link {} ('{}' run {}) does not match its content",
+                entry.sequence, entry.node, entry.run_index
+            ));
+        }
+
+        let task = &run.data.result_data.run_data[&entry.node][entry.run_index];
+        if !data_retained {
+            report.unverified_outputs += 1;
+        } else if output_hash(task) != entry.output_hash {
+            report.problems.push(format!(
+                "Output of '{}' run {} was modified",
+                entry.node, entry.run_index
+            ));
+        }
+        previous = entry.hash.clone();
+    }
+
+    report.length = entries.len() as u64;
+    if recorded(INTEGRITY_LENGTH_KEY) != Some(report.length.to_string()) {
+        report.problems.push("Chain length does not match the recorded length".to_string());
+    }
+    if previous != head {
+        report.problems.push("Chain head does not match the recorded head".to_string());
+    }
+    report.valid = report.problems.is_empty();
+    report
+}
+
+fn genesis(config: &IntegrityConfig, execution_id: &str, workflow_id: &str) -> String {
+    digest(config, &[GENESIS, execution_id, workflow_id])
+}
+
+fn link(config: &IntegrityConfig, previous: &str, entry: &ChainEntry) -> String {
+    digest(
+        config,
+        &[
+            previous,
+            &entry.sequence.to_string(),
+            &entry.node,
+            &entry.run_index.to_string(),
+            &entry.status,
+            &entry.input_hash,
+            &entry.output_hash,
+        ],
+    )
+}
+
+fn output_hash(task: &TaskData) -> String {
+    content_hash(&serde_json::json!({
+        "data": task.data,
+        "error": task.error.as_ref().map(|e| &e.message),
+    }))
+}
+
+/// Length-prefixed fields so `("ab", "c")` and `("a", "bc")` differ.
+fn digest(config: &IntegrityConfig, fields: &[&str]) -> String {
+    let mut message = Vec::new();
+    for field in fields {
+        message.extend_from_slice(&(field.len() as u64).to_be_bytes());
+        message.extend_from_slice(field.as_bytes());
+    }
+    match &config.secret {
+        Some(secret) => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+                .expect("HMAC accepts keys of any length");
+            mac.update(&message);
+            format!("{:x}", mac.finalize().into_bytes())
+        }
+        None => format!("{:x}", Sha256::digest(&message)),
+    }
+}
+
+fn content_hash(value: &Value) -> String {
+    let mut canonical = String::new();
+    write_canonical(value, &mut canonical);
+    format!("{:x}", Sha256::digest(canonical.as_bytes()))
+}
+
+/// JSON with object keys sorted, so hashes do not depend on map order.
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::{NodeExecutionData, WorkflowExecuteMode};
+    use std::collections::HashMap;
+
+    fn sealed_run(config: &IntegrityConfig) -> Run {
+        let mut run = Run::new(WorkflowExecuteMode::Manual);
+        let mut chain = HashChain::new(config, "exec-1", "wf-1");
+        for (node, value) in [("Start", 1), ("Double", 2)] {
+            let mut item = DataObject::new();
+            item.insert("n".to_string(), GenericValue::Integer(value));
+            let mut task = TaskData::new()
+                .with_output("main", vec![vec![NodeExecutionData::new(item)]]);
+            task.finish();
+            chain.append(&mut run, node, 0, &HashMap::new(), &mut task);
+            run.data.result_data.run_data.entry(node.to_string()).or_default().push(task);
+        }
+        run
+    }
+
+    #[test]
+    fn test_untampered_run_verifies() {
+        let config = IntegrityConfig {
+            enabled: true,
+            secret: Some("k".to_string()),
+        };
+        let run = sealed_run(&config);
+
+        let report = verify(&run, "wf-1", &config);
+        assert!(report.valid, "{:?}", report.problems);
+        assert_eq!(report.length, 2);
+        assert_eq!(report.execution_id.as_deref(), Some("exec-1"));
+        assert_eq!(export_chain(&run)[1].node, "Double");
+
+        // A different workflow or secret does not reproduce the chain.
+        assert!(!verify(&run, "wf-2", &config).valid);
+        let other = IntegrityConfig {
+            secret: Some("other".to_string()),
+            ..config
+        };
+        assert!(!verify(&run, "wf-1", &other).valid);
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let config = IntegrityConfig::default();
+        let mut run = sealed_run(&config);
+
+        let task = &mut run.data.result_data.run_data.get_mut("Double").unwrap()[0];
+        let items = &mut task.data.as_mut().unwrap().get_mut("main").unwrap()[0];
+        items[0].json.insert("n".to_string(), GenericValue::Integer(3));
+        let report = verify(&run, "wf-1", &config);
+        assert!(!report.valid);
+        assert!(report.problems[0].contains("Output of 'Double'"));
+
+        let mut run = sealed_run(&config);
+        run.data.result_data.run_data.remove("Start");
+        assert!(!verify(&run, "wf-1", &config).valid);
+    }
+}
@@ -0,0 +1,306 @@
+//! Write-ahead journal of side effects.
+//!
+//! With [`JournalConfig::enabled`], the engine writes an intent before each
+//! attempt of a node with side effects and marks it complete, succeeded or
+//! failed, once the attempt returns. A node that cannot record its intent
+//! fails without running, so no side effect goes unjournaled.
+//!
+//! An intent that is never completed means the process died while the
+//! side effect was in flight: the request may or may not have reached the
+//! other end. Such entries are "in doubt"; re-running their execution after
+//! a crash may repeat the side effect, and [`SideEffectJournal::in_doubt`]
+//! lists them for review.
+//!
+//! Nodes with side effects are HTTP requests with a method other than
+//! `GET`, `HEAD` and `OPTIONS`, the sending and publishing node types in
+//! [`SIDE_EFFECT_NODE_TYPES`], and the node types added in
+//! [`JournalConfig::node_types`].
+//!
+//! Entries are kept in memory by default; the Postgres backend in `n8n-db`
+//! keeps them across restarts, which is what makes them useful.
+
+use crate::delivery::WEBHOOK_DELIVERY_NODE_TYPE;
+use crate::soap::SOAP_NODE_TYPE;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use n8n_workflow::{Node, NodeParameterValue};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Node type of HTTP requests, journaled unless the method is safe.
+pub const HTTP_REQUEST_NODE_TYPE: &str = "n8n-nodes-base.httpRequest";
+
+/// Node types that always have side effects.
+pub const SIDE_EFFECT_NODE_TYPES: &[&str] = &[
+    "n8n-nodes-base.emailSend",
+    "n8n-nodes-base.slack",
+    "n8n-nodes-base.kafka",
+    "n8n-nodes-base.rabbitmq",
+    "n8n-nodes-base.amqp",
+    "n8n-nodes-base.mqtt",
+    "n8n-nodes-base.awsSns",
+    "n8n-nodes-base.awsSqs",
+    WEBHOOK_DELIVERY_NODE_TYPE,
+    SOAP_NODE_TYPE,
+];
+
+/// Side-effect journal settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JournalConfig {
+    /// Journal nodes with side effects.
+    pub enabled: bool,
+    /// Further node types to journal.
+    pub node_types: Vec<String>,
+}
+
+/// Whether `node` has side effects to journal.
+pub fn has_side_effects(node: &Node, config: &JournalConfig) -> bool {
+    if node.node_type == HTTP_REQUEST_NODE_TYPE {
+        let method = match node.parameters.get("method") {
+            Some(NodeParameterValue::String(method)) => method.to_uppercase(),
+            _ => "GET".to_string(),
+        };
+        return !matches!(method.as_str(), "GET" | "HEAD" | "OPTIONS");
+    }
+    SIDE_EFFECT_NODE_TYPES.contains(&node.node_type.as_str())
+        || config.node_types.iter().any(|t| *t == node.node_type)
+}
+
+/// How a journaled attempt ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalOutcome {
+    Succeeded,
+    Failed,
+}
+
+impl JournalOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl std::str::FromStr for JournalOutcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "succeeded" => Ok(Self::Succeeded),
+            "failed" => Ok(Self::Failed),
+            other => Err(format!("Unknown journal outcome '{}'", other)),
+        }
+    }
+}
+
+/// One attempt of a node with side effects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub id: String,
+    pub execution_id: String,
+    pub workflow_id: String,
+    pub node: String,
+    pub node_type: String,
+    /// Attempt of the node run, from 0.
+    pub attempt: u32,
+    pub intent_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<JournalOutcome>,
+}
+
+impl JournalEntry {
+    /// Whether the side effect may or may not have happened.
+    pub fn in_doubt(&self) -> bool {
+        self.completed_at.is_none()
+    }
+}
+
+/// Where journal entries are kept.
+#[async_trait]
+pub trait JournalBackend: Send + Sync {
+    /// Record an intent; it must be durable when this returns.
+    async fn record_intent(&self, entry: &JournalEntry) -> Result<(), String>;
+
+    /// Mark the intent `id` complete.
+    async fn record_completion(
+        &self,
+        id: &str,
+        outcome: JournalOutcome,
+        at: DateTime<Utc>,
+    ) -> Result<(), String>;
+
+    /// Entries of an execution, oldest first.
+    async fn entries(&self, execution_id: &str) -> Result<Vec<JournalEntry>, String>;
+
+    /// Uncompleted intents recorded before `before`, oldest first.
+    async fn in_doubt(&self, before: DateTime<Utc>) -> Result<Vec<JournalEntry>, String>;
+}
+
+/// In-process journal, lost on restart.
+#[derive(Default)]
+pub struct MemoryJournal {
+    entries: Mutex<HashMap<String, JournalEntry>>,
+}
+
+impl MemoryJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn sorted(&self, keep: impl Fn(&JournalEntry) -> bool) -> Vec<JournalEntry> {
+        let mut entries: Vec<_> =
+            self.entries.lock().values().filter(|e| keep(e)).cloned().collect();
+        entries.sort_by(|a, b| (a.intent_at, &a.id).cmp(&(b.intent_at, &b.id)));
+        entries
+    }
+}
+
+#[async_trait]
+impl JournalBackend for MemoryJournal {
+    async fn record_intent(&self, entry: &JournalEntry) -> Result<(), String> {
+        self.entries.lock().insert(entry.id.clone(), entry.clone());
+        Ok(())
+    }
+
+    async fn record_completion(
+        &self,
+        id: &str,
+        outcome: JournalOutcome,
+        at: DateTime<Utc>,
+    ) -> Result<(), String> {
+        let mut entries = self.entries.lock();
+        let entry = entries.get_mut(id).ok_or_else(|| format!("No journal entry {}", id))?;
+        entry.completed_at = Some(at);
+        entry.outcome = Some(outcome);
+        Ok(())
+    }
+
+    async fn entries(&self, execution_id: &str) -> Result<Vec<JournalEntry>, String> {
+        Ok(self.sorted(|e| e.execution_id == execution_id))
+    }
+
+    async fn in_doubt(&self, before: DateTime<Utc>) -> Result<Vec<JournalEntry>, String> {
+        Ok(self.sorted(|e| e.in_doubt() && e.intent_at < before))
+    }
+}
+
+/// The engine's side-effect journal. Clones share the backend, which may
+/// be replaced once a durable one becomes available.
+#[derive(Clone)]
+pub struct SideEffectJournal {
+    backend: Arc<RwLock<Arc<dyn JournalBackend>>>,
+}
+
+impl Default for SideEffectJournal {
+    fn default() -> Self {
+        Self::new(Arc::new(MemoryJournal::new()))
+    }
+}
+
+impl SideEffectJournal {
+    pub fn new(backend: Arc<dyn JournalBackend>) -> Self {
+        Self {
+            backend: Arc::new(RwLock::new(backend)),
+        }
+    }
+
+    /// Keep entries in `backend` from now on.
+    pub fn set_backend(&self, backend: Arc<dyn JournalBackend>) {
+        *self.backend.write() = backend;
+    }
+
+    fn backend(&self) -> Arc<dyn JournalBackend> {
+        self.backend.read().clone()
+    }
+
+    /// Record the intent to run `attempt` of `node`.
+    pub async fn intent(
+        &self,
+        execution_id: &str,
+        workflow_id: &str,
+        node: &Node,
+        attempt: u32,
+    ) -> Result<JournalEntry, String> {
+        let entry = JournalEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            execution_id: execution_id.to_string(),
+            workflow_id: workflow_id.to_string(),
+            node: node.name.clone(),
+            node_type: node.node_type.clone(),
+            attempt,
+            intent_at: Utc::now(),
+            completed_at: None,
+            outcome: None,
+        };
+        self.backend().record_intent(&entry).await?;
+        Ok(entry)
+    }
+
+    /// Mark `entry` complete. A failure is only logged: the entry stays in
+    /// doubt, which errs on the side of reporting.
+    pub async fn complete(&self, entry: &JournalEntry, outcome: JournalOutcome) {
+        if let Err(e) = self.backend().record_completion(&entry.id, outcome, Utc::now()).await {
+            tracing::warn!(
+                node = %entry.node,
+                error = %e,
+                "Failed to complete side-effect journal entry"
+            );
+        }
+    }
+
+    /// Entries of an execution, oldest first.
+    pub async fn entries(&self, execution_id: &str) -> Result<Vec<JournalEntry>, String> {
+        self.backend().entries(execution_id).await
+    }
+
+    /// Side effects in doubt since before `before`, oldest first.
+    pub async fn in_doubt(&self, before: DateTime<Utc>) -> Result<Vec<JournalEntry>, String> {
+        self.backend().in_doubt(before).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_side_effect_detection() {
+        let config = JournalConfig {
+            enabled: true,
+            node_types: vec!["acme.ledger".to_string()],
+        };
+        let mut http = Node::new("Fetch", HTTP_REQUEST_NODE_TYPE);
+        assert!(!has_side_effects(&http, &config));
+        http.set_parameter("method", NodeParameterValue::String("post".to_string()));
+        assert!(has_side_effects(&http, &config));
+
+        assert!(has_side_effects(&Node::new("Mail", "n8n-nodes-base.emailSend"), &config));
+        assert!(has_side_effects(&Node::new("Book", "acme.ledger"), &config));
+        assert!(!has_side_effects(&Node::new("Set", "n8n-nodes-base.set"), &config));
+    }
+
+    #[tokio::test]
+    async fn test_uncompleted_intents_are_in_doubt() {
+        let journal = SideEffectJournal::default();
+        let node = Node::new("Post", HTTP_REQUEST_NODE_TYPE);
+        let done = journal.intent("e1", "wf", &node, 0).await.unwrap();
+        journal.complete(&done, JournalOutcome::Failed).await;
+        let pending = journal.intent("e1", "wf", &node, 1).await.unwrap();
+
+        let entries = journal.entries("e1").await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].outcome, Some(JournalOutcome::Failed));
+
+        let later = Utc::now() + chrono::Duration::seconds(1);
+        assert_eq!(journal.in_doubt(later).await.unwrap(), vec![pending]);
+        assert!(journal.in_doubt(done.intent_at).await.unwrap().is_empty());
+    }
+}
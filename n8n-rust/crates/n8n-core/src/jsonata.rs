@@ -0,0 +1,222 @@
+//! JSONata transforms.
+//!
+//! Mappings written in [JSONata](https://jsonata.org) run natively, so
+//! workflows built on JSONata community nodes port without rewriting them
+//! as expressions. `$jsonata(expression, data)` evaluates one in an
+//! expression, over `$json` when `data` is left out, and the
+//! `n8n-nodes-base.jsonata` node transforms items with one:
+//!
+//! - `expression`: the JSONata expression
+//! - `mode`: `eachItem` (default) evaluates it over every item's JSON,
+//!   `allItems` once over the array of all items' JSON
+//! - `outputField`: field the result is written to; empty replaces the
+//!   item's JSON with the result, which must then be an object, or in
+//!   `allItems` mode an object or an array of objects, one item each
+//!
+//! A result that matches nothing (JSONata's `undefined`) is `null`.
+
+use crate::error::ExecutionEngineError;
+use crate::executor::{NodeExecutor, NodeOutput};
+use crate::runtime::RuntimeContext;
+use async_trait::async_trait;
+use bumpalo::Bump;
+use jsonata_rs::JsonAta;
+use n8n_workflow::{
+    object_from_json, object_to_json, DataObject, GenericValue, Node, NodeExecutionData,
+    NodeParameterValue, TaskDataConnections,
+};
+use serde_json::Value;
+
+/// Node type of the JSONata transform node.
+pub const JSONATA_NODE_TYPE: &str = "n8n-nodes-base.jsonata";
+
+/// Evaluate the JSONata `expression` over `data`.
+pub fn evaluate(expression: &str, data: &Value) -> Result<Value, String> {
+    let arena = Bump::new();
+    let jsonata = JsonAta::new(expression, &arena).map_err(|e| e.to_string())?;
+    evaluate_parsed(&jsonata, data)
+}
+
+/// Evaluate the parsed `jsonata` over `data`, so an expression evaluated
+/// over many inputs is parsed once.
+fn evaluate_parsed(jsonata: &JsonAta<'_>, data: &Value) -> Result<Value, String> {
+    let input = data.to_string();
+    let result = jsonata.evaluate(Some(&input), None).map_err(|e| e.to_string())?;
+    if result.is_undefined() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(&result.serialize(false)).map_err(|e| e.to_string())
+}
+
+/// Executes `n8n-nodes-base.jsonata` nodes.
+pub struct JsonataExecutor;
+
+#[async_trait]
+impl NodeExecutor for JsonataExecutor {
+    fn node_type(&self) -> &str {
+        JSONATA_NODE_TYPE
+    }
+
+    async fn execute(
+        &self,
+        node: &Node,
+        input: &TaskDataConnections,
+        _context: &RuntimeContext,
+    ) -> Result<NodeOutput, ExecutionEngineError> {
+        let node_error = |message: String| ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message,
+        };
+        let string = |key: &str| match node.parameters.get(key) {
+            Some(NodeParameterValue::String(value)) => value.trim(),
+            _ => "",
+        };
+        let expression = string("expression");
+        if expression.is_empty() {
+            return Err(node_error("No JSONata expression given".to_string()));
+        }
+        let output_field = string("outputField");
+
+        let items = input.get("main").and_then(|v| v.first()).cloned().unwrap_or_default();
+        let to_json = |item: &NodeExecutionData| Value::Object(object_to_json(&item.json));
+
+        match string("mode") {
+            "" | "eachItem" => {
+                let arena = Bump::new();
+                let jsonata = JsonAta::new(expression, &arena)
+                    .map_err(|e| node_error(e.to_string()))?;
+                let mut output = Vec::with_capacity(items.len());
+                for mut item in items {
+                    let result = evaluate_parsed(&jsonata, &to_json(&item)).map_err(node_error)?;
+                    if output_field.is_empty() {
+                        item.json = object(result).map_err(node_error)?;
+                    } else {
+                        item.json.insert(output_field.to_string(), GenericValue::from(result));
+                    }
+                    output.push(item);
+                }
+                Ok(vec![output])
+            }
+            "allItems" => {
+                let data = items.iter().map(to_json).collect();
+                let result = evaluate(expression, &Value::Array(data)).map_err(node_error)?;
+                let output = if !output_field.is_empty() {
+                    let mut json = DataObject::new();
+                    json.insert(output_field.to_string(), GenericValue::from(result));
+                    vec![NodeExecutionData::new(json)]
+                } else if let Value::Array(results) = result {
+                    results
+                        .into_iter()
+                        .map(|result| object(result).map(NodeExecutionData::new))
+                        .collect::<Result<_, _>>()
+                        .map_err(node_error)?
+                } else {
+                    vec![NodeExecutionData::new(object(result).map_err(node_error)?)]
+                };
+                Ok(vec![output])
+            }
+            other => Err(node_error(format!(
+                "Unknown mode '{}', expected 'eachItem' or 'allItems'",
+                other
+            ))),
+        }
+    }
+}
+
+fn object(result: Value) -> Result<DataObject, String> {
+    match result {
+        Value::Object(map) => Ok(object_from_json(map)),
+        other => Err(format!(
+            "The expression returned {}, not an object; set outputField to keep it",
+            other
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::{parse, ExpressionContext, ExpressionEvaluator};
+    use n8n_workflow::{connection::CONNECTION_MAIN, WorkflowExecuteMode};
+    use serde_json::json;
+
+    #[test]
+    fn test_evaluate_maps_and_aggregates() {
+        let order = json!({
+            "customer": { "first": "Ada", "last": "Lovelace" },
+            "lines": [
+                { "sku": "a", "price": 2.25, "qty": 2 },
+                { "sku": "b", "price": 10, "qty": 1 }
+            ]
+        });
+        assert_eq!(
+            evaluate(
+                r#"{ "name": customer.first & " " & customer.last,
+                     "total": $sum(lines.(price * qty)),
+                     "skus": lines.sku }"#,
+                &order
+            )
+            .unwrap(),
+            json!({ "name": "Ada Lovelace", "total": 14.5, "skus": ["a", "b"] })
+        );
+        assert_eq!(evaluate("missing.field", &order).unwrap(), Value::Null);
+        assert!(evaluate("lines[", &order).is_err());
+        assert!(object(json!(3)).is_err());
+
+        // `$jsonata()` reads `$json` unless given data.
+        let item = NodeExecutionData::new(object(order).unwrap());
+        let context = ExpressionContext::minimal(&item);
+        let sum = |source: &str| {
+            ExpressionEvaluator::new().evaluate(&parse(source).unwrap(), &context).unwrap()
+        };
+        assert_eq!(sum("$jsonata('$sum(lines.qty)')"), json!(3));
+        assert_eq!(sum("$jsonata('$sum($)', [1, 2])"), json!(3));
+    }
+
+    #[tokio::test]
+    async fn test_executor_modes() {
+        let context = RuntimeContext::new(WorkflowExecuteMode::Manual, Default::default());
+        let items: Vec<_> = [("a", 2), ("b", 3)]
+            .iter()
+            .map(|(sku, qty)| {
+                NodeExecutionData::from_json_value(json!({ "sku": sku, "qty": qty })).unwrap()
+            })
+            .collect();
+        let mut input = TaskDataConnections::new();
+        input.insert(CONNECTION_MAIN.to_string(), vec![items]);
+        let run = |parameters: &[(&str, &str)]| {
+            let mut node = Node::new("JSONata", JSONATA_NODE_TYPE);
+            for (key, value) in parameters {
+                node.set_parameter(*key, NodeParameterValue::String(value.to_string()));
+            }
+            let (input, context) = (&input, &context);
+            async move { JsonataExecutor.execute(&node, input, context).await }
+        };
+        let json = |output: &NodeOutput| -> Vec<Value> {
+            output[0].iter().map(|item| Value::Object(object_to_json(&item.json))).collect()
+        };
+
+        let each = run(&[("expression", r#"{ "id": sku & "-" & qty }"#)]).await.unwrap();
+        assert_eq!(json(&each), vec![json!({ "id": "a-2" }), json!({ "id": "b-3" })]);
+
+        let field = run(&[("expression", "qty * 10"), ("outputField", "total")]).await.unwrap();
+        assert_eq!(json(&field)[1], json!({ "sku": "b", "qty": 3, "total": 30 }));
+
+        let all = [("mode", "allItems"), ("expression", r#"$.{ "sku": $uppercase(sku) }"#)];
+        let all = run(&all).await.unwrap();
+        assert_eq!(json(&all), vec![json!({ "sku": "A" }), json!({ "sku": "B" })]);
+
+        let sum = [("mode", "allItems"), ("expression", "$sum(qty)"), ("outputField", "qty")];
+        assert_eq!(json(&run(&sum).await.unwrap()), vec![json!({ "qty": 5 })]);
+
+        // The expression is parsed once, before any item, so a syntax
+        // error fails the node.
+        let error = run(&[("expression", "qty[")]).await.unwrap_err();
+        assert!(matches!(
+            error,
+            ExecutionEngineError::NodeExecution { node, .. } if node == "JSONata"
+        ));
+        let error = run(&[("expression", "qty"), ("mode", "everyItem")]).await.unwrap_err();
+        assert!(error.to_string().contains("Unknown mode 'everyItem'"));
+    }
+}
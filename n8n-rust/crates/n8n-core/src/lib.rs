@@ -9,30 +9,182 @@
 //! - Partial execution (test specific nodes)
 //! - Error handling and retry logic
 
+pub mod affinity;
+pub mod binary_data;
 pub mod chess_workflow;
+pub mod cloning;
+pub mod concurrency;
+pub mod correlation;
+pub mod cost;
 pub mod credentials;
+pub mod declarative;
+pub mod delivery;
+pub mod determinism;
+pub mod diagram;
+pub mod docs;
+pub mod egress;
+pub mod embedded;
+pub mod evaluation;
 pub mod engine;
 pub mod error;
+pub mod execution_webhooks;
 pub mod executor;
+pub mod explain;
 pub mod expression;
+pub mod generation;
 pub mod hot_path;
+pub mod inputs;
+pub mod integrity;
+pub mod live;
+pub mod masking;
 pub mod node_types;
+pub mod oauth1;
+pub mod patch;
+pub mod processed_data;
+pub mod profiling;
+pub mod progress;
+pub mod resource_usage;
+pub mod run_data;
+pub mod run_window;
 pub mod runtime;
+pub mod statistics;
 pub mod storage;
+pub mod sub_execution;
+pub mod supply;
+pub mod throttle;
+pub mod trigger_health;
 pub mod jitson_hooks;
+pub mod jsonata;
+pub mod journal;
+pub mod lint;
+pub mod migration;
+pub mod sampling;
+pub mod schedule;
+pub mod schedule_trigger;
+pub mod schema_registry;
+pub mod serialization;
+pub mod sigv4;
+pub mod rollup;
+pub mod soap;
+pub mod validation;
+pub mod webhook_signature;
+pub mod workflow_variables;
 
-pub use credentials::{CredentialError, CredentialService, DecryptedCredentialData};
+pub use affinity::{AffinityConfig, AffinityRouter};
+pub use binary_data::{
+    binary_data_key, blob_key, BinaryDataError, BinaryDataService, BinaryDataStore,
+    FileSystemBinaryStore, MemoryBinaryStore, S3BinaryStore, S3StorageConfig, BLOB_KEY_PREFIX,
+};
+pub use cloning::{clone_workflow, CloneOptions, CloneReport};
+pub use concurrency::{
+    ConcurrencyGroups, ConcurrencyGuard, ConcurrencyLocks, LocalConcurrencyLocks,
+};
+pub use correlation::{current_correlation_id, with_correlation_id, CORRELATION_ID_HEADER};
+pub use cost::{
+    CostAccountant, CostConfig, CostHook, CostLine, CostRecord, CostReport, NodeTypePricing,
+};
+pub use credentials::{
+    CredentialError, CredentialService, DecryptedCredentialData, ENCRYPTION_KEY_ENV,
+};
+pub use declarative::{CredentialResolver, DeclarativeCatalog, DeclarativeError, DeclarativeNode};
+pub use delivery::{
+    Delivery, DeliveryError, DeliveryStatus, DeliveryStore, MemoryDeliveryStore, RetryPolicy,
+    WebhookDeliveries, WebhookDeliveryExecutor, WEBHOOK_DELIVERY_NODE_TYPE,
+};
+pub use determinism::{Determinism, DeterministicConfig};
+pub use diagram::DiagramFormat;
+pub use egress::{
+    EgressConfig, EgressError, EgressPolicy, EgressRules, HostProxy, ProjectLookup,
+    WorkflowProjects,
+};
+pub use embedded::{Execution, N8nRuntime, N8nRuntimeBuilder, RuntimeEvent};
+pub use evaluation::{
+    evaluate, CaseResult, EvaluationError, EvaluationReport, Evaluations, ScoreResult, Scorer,
+    TestCase, TestSet,
+};
 pub use engine::*;
 pub use error::*;
+pub use execution_webhooks::{
+    ExecutionNotice, ExecutionWebhook, ExecutionWebhooksConfig, LifecycleEvent,
+};
 pub use executor::*;
+pub use explain::{explain, CredentialUse, ExecutionPlan, NodePlan};
+pub use generation::{
+    GenerationError, LlmConfig, LlmProvider, OpenAiCompatibleProvider, WorkflowDraft,
+    WorkflowGenerator,
+};
 pub use hot_path::{CompiledWorkflow, CompiledWorkflowCache, CompiledNode, RouteEntry, CompileError};
 pub use expression::{
-    ExpressionContext, ExpressionError, ExpressionEvaluator, ExpressionResult,
-    parse, parse_template, resolve_parameter,
+    ExpressionContext, ExpressionError, ExpressionEvaluator, ExpressionLimits, ExpressionResult,
+    OversizeMode, parse, parse_template, resolve_parameter,
+};
+pub use inputs::{input_schema, resolve_inputs, InputError};
+pub use integrity::{ChainEntry, HashChain, IntegrityConfig, VerificationReport};
+pub use journal::{
+    has_side_effects, JournalBackend, JournalConfig, JournalEntry, JournalOutcome, MemoryJournal,
+    SideEffectJournal,
+};
+pub use jsonata::{JsonataExecutor, JSONATA_NODE_TYPE};
+pub use live::{LiveCounters, LiveCounts, LiveSnapshot};
+pub use masking::{MaskingConfig, SecretMask, MASK};
+pub use oauth1::{OAuth1Credentials, SignatureMethod, OAUTH1_CREDENTIAL_TYPE};
+pub use patch::{ConnectionPatch, PatchError, PatchOperation, WorkflowPatch};
+pub use lint::{
+    to_sarif, Diagnostic, Finding, LintReport, LintRule, Linter, RuleInfo, RuleLevel, Severity,
 };
+pub use migration::{
+    AppliedMigration, Deprecation, MigrationFailure, MigrationRegistry, MigrationReport,
+    NodeMigration,
+};
+pub use profiling::{
+    profile_report, FlameNode, NodeProfile, NodeRunProfile, ProfileReport, ProfilingConfig,
+};
+pub use processed_data::{
+    MemoryProcessedData, ProcessedData, ProcessedDataMode, ProcessedDataStore, ProcessedValue,
+};
+pub use progress::{CompletedNode, ExecutionProgress, InFlightExecution};
+pub use resource_usage::{ResourceUsage, ResourceUsageConfig};
+pub use run_data::{ItemPage, NodeRunSize, OutputRef, OutputSize, RunDataSummary};
+pub use run_window::WindowDecision;
 pub use runtime::*;
+pub use sampling::{DataRetention, SamplingPolicy, DATA_RETENTION_KEY};
+pub use schedule::{
+    Alignment, CronSchedule, PlannedRun, ScheduleError, ScheduleOptions, SchedulePreview,
+    SCHEDULE_TRIGGER_NODE_TYPE,
+};
+pub use schedule_trigger::{ScheduleHandler, ScheduleRunner, TickReport};
+pub use schema_registry::{
+    PayloadCodec, SchemaRegistryClient, SchemaRegistryError, SubjectNameStrategy,
+};
+pub use rollup::{RollupExecutor, ROLLUP_NODE_TYPE};
+pub use serialization::{
+    decode_items, IngestParser, SerializationConfig, SerializationError, TransferCodec,
+};
+pub use sigv4::{
+    AwsCredentials, AwsCredentialsProvider, CredentialSource, SigV4Error, SigV4Signer,
+};
+pub use soap::{SoapExecutor, SOAP_NODE_TYPE};
+pub use validation::{SchemaValidator, ValidateExecutor, Violation, VALIDATE_NODE_TYPE};
+pub use webhook_signature::{
+    SignatureConfig, SignatureError, SignatureProvider, SignatureVerification, SignedRequest,
+};
+pub use workflow_variables::{VariableSource, WorkflowVariables};
+pub use trigger_health::{TriggerEvent, TriggerHealth, TriggerHealthConfig, TriggerState};
+pub use statistics::{
+    MemoryStatistics, StatisticsBackend, StatisticsName, WorkflowStatistic, WorkflowStatistics,
+};
 pub use storage::{
     ExecutionStorage, WorkflowStorage, MemoryExecutionStorage, MemoryWorkflowStorage,
+    WorkflowVersion, MAX_MEMORY_VERSIONS,
+};
+pub use sub_execution::{
+    ExecutionLineage, LineageFrame, SubExecutionLimits, SubExecutionTracker,
+    EXECUTE_WORKFLOW_NODE_TYPE,
+};
+pub use supply::{NodeSupplies, SuppliedData, Supply};
+pub use throttle::{
+    release_targets, ThrottleExecutor, ThrottleQueue, Throttles, THROTTLED_ITEMS_KEY,
+    THROTTLE_NODE_TYPE,
 };
 pub use jitson_hooks::{
     CompiledParams, WorkflowLifecycle, ExecutionStats as JitsonExecutionStats,
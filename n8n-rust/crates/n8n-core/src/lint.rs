@@ -0,0 +1,601 @@
+//! Static workflow linting.
+//!
+//! A [`Linter`] runs a set of [`LintRule`]s over a workflow without
+//! executing it and returns a [`LintReport`] of diagnostics. Built-in rules:
+//!
+//! | Rule | Default | Checks |
+//! |------|---------|--------|
+//! | `http-timeout` | warning | HTTP Request nodes without an explicit `timeout` |
+//! | `deprecated-node-type` | warning | Node types deprecated in the [`MigrationRegistry`] |
+//! | `outdated-node-version` | info | Nodes below their latest `typeVersion` |
+//! | `expression-missing-node` | error | Expressions referencing nodes not in the workflow |
+//!
+//! Other crates add rules with [`Linter::register`]; each rule's level can be
+//! changed or turned off with [`Linter::configure`]. Reports render as JSON
+//! (serde) or SARIF 2.1.0 via [`to_sarif`] for CI code-scanning upload.
+
+use crate::migration::MigrationRegistry;
+use n8n_workflow::{Node, NodeParameterValue, Workflow};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Diagnostic severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// SARIF `level` for this severity.
+    pub fn sarif_level(self) -> &'static str {
+        match self {
+            Severity::Info => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// Configured level of a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+impl RuleLevel {
+    fn severity(self) -> Option<Severity> {
+        match self {
+            RuleLevel::Off => None,
+            RuleLevel::Info => Some(Severity::Info),
+            RuleLevel::Warning => Some(Severity::Warning),
+            RuleLevel::Error => Some(Severity::Error),
+        }
+    }
+}
+
+/// A problem found by a rule, before severity is applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub message: String,
+    pub node: Option<String>,
+    pub parameter: Option<String>,
+}
+
+impl Finding {
+    /// Create a finding attached to a node.
+    pub fn at_node(node: &Node, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            node: Some(node.name.clone()),
+            parameter: None,
+        }
+    }
+
+    /// Attach the parameter path the finding is about.
+    pub fn with_parameter(mut self, parameter: impl Into<String>) -> Self {
+        self.parameter = Some(parameter.into());
+        self
+    }
+}
+
+/// A lint rule.
+pub trait LintRule: Send + Sync {
+    /// Stable rule identifier (e.g. `http-timeout`).
+    fn id(&self) -> &str;
+
+    /// One-line description of what the rule checks.
+    fn description(&self) -> &str;
+
+    /// Severity used unless configured otherwise.
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    /// Check a workflow.
+    fn check(&self, workflow: &Workflow) -> Vec<Finding>;
+}
+
+/// A finding with its rule and effective severity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub rule_id: String,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<String>,
+}
+
+/// Lint result for one workflow.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintReport {
+    /// Where the workflow came from (file path or workflow id).
+    pub source: String,
+    pub workflow_id: String,
+    pub workflow_name: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl LintReport {
+    /// Whether any diagnostic is an error.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|d| d.severity == Severity::Error)
+    }
+}
+
+/// Description of a registered rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleInfo {
+    pub id: String,
+    pub description: String,
+    pub level: RuleLevel,
+}
+
+/// Runs lint rules over workflows.
+#[derive(Clone)]
+pub struct Linter {
+    rules: Vec<Arc<dyn LintRule>>,
+    levels: HashMap<String, RuleLevel>,
+}
+
+impl Linter {
+    /// Create a linter with no rules.
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            levels: HashMap::new(),
+        }
+    }
+
+    /// Create a linter with the built-in rules, checking node versions
+    /// against the given migrations.
+    pub fn with_migrations(migrations: Arc<MigrationRegistry>) -> Self {
+        let mut linter = Self::new();
+        linter.register(Arc::new(HttpTimeoutRule));
+        linter.register(Arc::new(DeprecatedNodeTypeRule::new(migrations.clone())));
+        linter.register(Arc::new(OutdatedNodeVersionRule::new(migrations)));
+        linter.register(Arc::new(ExpressionMissingNodeRule));
+        linter
+    }
+
+    /// Register a rule. A rule with the same id replaces the existing one.
+    pub fn register(&mut self, rule: Arc<dyn LintRule>) {
+        self.rules.retain(|r| r.id() != rule.id());
+        self.rules.push(rule);
+    }
+
+    /// Override the level of a rule.
+    pub fn configure(&mut self, rule_id: impl Into<String>, level: RuleLevel) {
+        self.levels.insert(rule_id.into(), level);
+    }
+
+    /// Registered rules with their effective levels.
+    pub fn rules(&self) -> Vec<RuleInfo> {
+        self.rules
+            .iter()
+            .map(|rule| RuleInfo {
+                id: rule.id().to_string(),
+                description: rule.description().to_string(),
+                level: self.level(rule.as_ref()),
+            })
+            .collect()
+    }
+
+    /// Lint a workflow.
+    pub fn lint(&self, workflow: &Workflow) -> LintReport {
+        let mut diagnostics = Vec::new();
+
+        for rule in &self.rules {
+            let Some(severity) = self.level(rule.as_ref()).severity() else {
+                continue;
+            };
+            for finding in rule.check(workflow) {
+                diagnostics.push(Diagnostic {
+                    rule_id: rule.id().to_string(),
+                    severity,
+                    message: finding.message,
+                    node: finding.node,
+                    parameter: finding.parameter,
+                });
+            }
+        }
+
+        diagnostics.sort_by(|a, b| b.severity.cmp(&a.severity));
+
+        LintReport {
+            source: workflow.id.clone(),
+            workflow_id: workflow.id.clone(),
+            workflow_name: workflow.name.clone(),
+            diagnostics,
+        }
+    }
+
+    fn level(&self, rule: &dyn LintRule) -> RuleLevel {
+        self.levels.get(rule.id()).copied().unwrap_or(match rule.default_severity() {
+            Severity::Info => RuleLevel::Info,
+            Severity::Warning => RuleLevel::Warning,
+            Severity::Error => RuleLevel::Error,
+        })
+    }
+}
+
+impl Default for Linter {
+    /// A linter with the built-in rules.
+    fn default() -> Self {
+        Self::with_migrations(Arc::new(MigrationRegistry::default()))
+    }
+}
+
+/// Render reports as a SARIF 2.1.0 log.
+pub fn to_sarif(linter: &Linter, reports: &[LintReport]) -> serde_json::Value {
+    let rules: Vec<serde_json::Value> = linter
+        .rules()
+        .into_iter()
+        .map(|rule| {
+            serde_json::json!({
+                "id": rule.id,
+                "shortDescription": { "text": rule.description },
+            })
+        })
+        .collect();
+
+    let results: Vec<serde_json::Value> = reports
+        .iter()
+        .flat_map(|report| {
+            report.diagnostics.iter().map(move |d| {
+                let mut location = serde_json::json!({
+                    "physicalLocation": { "artifactLocation": { "uri": report.source } },
+                });
+                if let Some(node) = &d.node {
+                    location["logicalLocations"] = serde_json::json!([{
+                        "name": node,
+                        "fullyQualifiedName": match &d.parameter {
+                            Some(p) => format!("{}.{}", node, p),
+                            None => node.clone(),
+                        },
+                        "kind": "member",
+                    }]);
+                }
+                serde_json::json!({
+                    "ruleId": d.rule_id,
+                    "level": d.severity.sarif_level(),
+                    "message": { "text": d.message },
+                    "locations": [location],
+                })
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "n8n-lint",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+// ============================================================================
+// Built-in Rules
+// ============================================================================
+
+/// HTTP Request nodes should set an explicit timeout.
+pub struct HttpTimeoutRule;
+
+impl LintRule for HttpTimeoutRule {
+    fn id(&self) -> &str {
+        "http-timeout"
+    }
+
+    fn description(&self) -> &str {
+        "HTTP Request node without an explicit timeout"
+    }
+
+    fn check(&self, workflow: &Workflow) -> Vec<Finding> {
+        workflow
+            .nodes
+            .iter()
+            .filter(|n| !n.disabled && n.node_type == "n8n-nodes-base.httpRequest")
+            .filter(|n| {
+                let in_options = matches!(
+                    n.get_parameter("options"),
+                    Some(NodeParameterValue::Object(o)) if o.contains_key("timeout")
+                );
+                n.get_parameter("timeout").is_none() && !in_options
+            })
+            .map(|n| Finding::at_node(n, "HTTP Request node has no timeout; set `timeout` (ms)"))
+            .collect()
+    }
+}
+
+/// Node types that are deprecated in favour of newer ones.
+pub struct DeprecatedNodeTypeRule {
+    migrations: Arc<MigrationRegistry>,
+}
+
+impl DeprecatedNodeTypeRule {
+    pub fn new(migrations: Arc<MigrationRegistry>) -> Self {
+        Self { migrations }
+    }
+}
+
+impl LintRule for DeprecatedNodeTypeRule {
+    fn id(&self) -> &str {
+        "deprecated-node-type"
+    }
+
+    fn description(&self) -> &str {
+        "Node uses a deprecated node type"
+    }
+
+    fn check(&self, workflow: &Workflow) -> Vec<Finding> {
+        workflow
+            .nodes
+            .iter()
+            .filter_map(|n| {
+                let deprecation = self.migrations.deprecation(&n.node_type)?;
+                Some(Finding::at_node(n, deprecation.message.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Nodes below the latest `typeVersion` of their type.
+pub struct OutdatedNodeVersionRule {
+    migrations: Arc<MigrationRegistry>,
+}
+
+impl OutdatedNodeVersionRule {
+    pub fn new(migrations: Arc<MigrationRegistry>) -> Self {
+        Self { migrations }
+    }
+}
+
+impl LintRule for OutdatedNodeVersionRule {
+    fn id(&self) -> &str {
+        "outdated-node-version"
+    }
+
+    fn description(&self) -> &str {
+        "Node is below the latest typeVersion and will be migrated on load"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, workflow: &Workflow) -> Vec<Finding> {
+        workflow
+            .nodes
+            .iter()
+            // Deprecated types are reported by `deprecated-node-type`.
+            .filter(|n| self.migrations.deprecation(&n.node_type).is_none())
+            .filter_map(|n| {
+                let latest = self.migrations.latest_version(&n.node_type)?;
+                if n.type_version >= latest {
+                    return None;
+                }
+                let steps: Vec<String> = self
+                    .migrations
+                    .pending(n)
+                    .iter()
+                    .map(|m| m.description().to_string())
+                    .collect();
+                let mut message = format!(
+                    "Node is at typeVersion {}; latest is {}",
+                    n.type_version, latest
+                );
+                if !steps.is_empty() {
+                    message.push_str(&format!(" ({})", steps.join("; ")));
+                }
+                Some(Finding::at_node(n, message))
+            })
+            .collect()
+    }
+}
+
+/// Expressions must only reference nodes that exist.
+pub struct ExpressionMissingNodeRule;
+
+impl LintRule for ExpressionMissingNodeRule {
+    fn id(&self) -> &str {
+        "expression-missing-node"
+    }
+
+    fn description(&self) -> &str {
+        "Expression references a node that does not exist"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, workflow: &Workflow) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for node in &workflow.nodes {
+            let mut keys: Vec<_> = node.parameters.keys().collect();
+            keys.sort();
+            for key in keys {
+                for_each_expression(key, &node.parameters[key], &mut |path, expr| {
+                    for name in referenced_nodes(expr) {
+                        if workflow.get_node(&name).is_none() {
+                            findings.push(
+                                Finding::at_node(
+                                    node,
+                                    format!("Expression references missing node '{}'", name),
+                                )
+                                .with_parameter(path),
+                            );
+                        }
+                    }
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+/// Call `f` with the path and text of every parameter value holding a
+/// `{{ }}` expression.
+pub fn for_each_expression(
+    path: &str,
+    value: &NodeParameterValue,
+    f: &mut dyn FnMut(&str, &str),
+) {
+    match value {
+        NodeParameterValue::String(s) | NodeParameterValue::Expression(s) => {
+            if s.contains("{{") {
+                f(path, s);
+            }
+        }
+        NodeParameterValue::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                for_each_expression(&format!("{}[{}]", path, i), item, f);
+            }
+        }
+        NodeParameterValue::Object(map) => {
+            let mut keys: Vec<_> = map.keys().collect();
+            keys.sort();
+            for key in keys {
+                for_each_expression(&format!("{}.{}", path, key), &map[key], f);
+            }
+        }
+        NodeParameterValue::Number(_) | NodeParameterValue::Boolean(_) => {}
+    }
+}
+
+/// Node names referenced as `$node["Name"]` or `$('Name')` in an expression.
+pub fn referenced_nodes(expr: &str) -> Vec<String> {
+    let mut names = Vec::new();
+
+    for prefix in ["$node[", "$("] {
+        let mut rest = expr;
+        while let Some(start) = rest.find(prefix) {
+            rest = &rest[start + prefix.len()..];
+            let Some(quote) = rest.chars().next().filter(|c| matches!(c, '"' | '\'' | '`')) else {
+                continue;
+            };
+            let Some(end) = rest[1..].find(quote) else {
+                break;
+            };
+            let name = &rest[1..1 + end];
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+            rest = &rest[1 + end..];
+        }
+    }
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workflow() -> Workflow {
+        let mut wf = Workflow::new("Lint");
+        wf.add_node(Node::new("Start", "n8n-nodes-base.manualTrigger"));
+
+        let mut http = Node::new("Fetch", "n8n-nodes-base.httpRequest");
+        http.set_parameter(
+            "url",
+            NodeParameterValue::String("={{ $node[\"Start\"].json.url }}".into()),
+        );
+        wf.add_node(http);
+
+        let mut set = Node::new("Legacy", "n8n-nodes-base.function");
+        set.set_parameter(
+            "value",
+            NodeParameterValue::String("{{ $('Missing').item.json.id }}".into()),
+        );
+        wf.add_node(set);
+        wf
+    }
+
+    #[test]
+    fn test_builtin_rules() {
+        let report = Linter::default().lint(&workflow());
+        let rules: Vec<&str> = report.diagnostics.iter().map(|d| d.rule_id.as_str()).collect();
+
+        assert_eq!(
+            rules,
+            vec![
+                "expression-missing-node",
+                "http-timeout",
+                "deprecated-node-type",
+                "outdated-node-version",
+            ]
+        );
+        assert!(report.has_errors());
+        assert_eq!(report.diagnostics[0].parameter.as_deref(), Some("value"));
+    }
+
+    #[test]
+    fn test_configure_and_custom_rules() {
+        struct NoNotes;
+        impl LintRule for NoNotes {
+            fn id(&self) -> &str {
+                "no-notes"
+            }
+            fn description(&self) -> &str {
+                "Nodes without notes"
+            }
+            fn default_severity(&self) -> Severity {
+                Severity::Info
+            }
+            fn check(&self, workflow: &Workflow) -> Vec<Finding> {
+                workflow
+                    .nodes
+                    .iter()
+                    .filter(|n| n.notes.is_none())
+                    .map(|n| Finding::at_node(n, "No notes"))
+                    .collect()
+            }
+        }
+
+        let mut linter = Linter::default();
+        linter.register(Arc::new(NoNotes));
+        linter.configure("expression-missing-node", RuleLevel::Off);
+        linter.configure("http-timeout", RuleLevel::Error);
+
+        let report = linter.lint(&workflow());
+        assert!(report.diagnostics.iter().all(|d| d.rule_id != "expression-missing-node"));
+        assert_eq!(report.diagnostics.iter().filter(|d| d.rule_id == "no-notes").count(), 3);
+        assert_eq!(report.diagnostics[0].rule_id, "http-timeout");
+
+        let sarif = to_sarif(&linter, &[report]);
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(sarif["runs"][0]["results"][0]["level"], "error");
+    }
+
+    #[test]
+    fn test_referenced_nodes() {
+        assert_eq!(
+            referenced_nodes("{{ $node[\"A\"].json.x + $('B').item.json.y + $node[\"A\"].json.z }}"),
+            vec!["A", "B"]
+        );
+        assert!(referenced_nodes("{{ $json.x }}").is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+//! Live execution counters.
+//!
+//! [`LiveCounters`] tracks how many executions are queued (`new`),
+//! running and waiting, per workflow and in total, so dashboards can poll
+//! them without `COUNT` queries. Storage backends call
+//! [`LiveCounters::observe`] whenever they save an execution; finished
+//! executions drop out. Counters only see changes made through this
+//! process, so [`LiveCounters::reconcile`] periodically replaces them with
+//! the unfinished executions read from storage.
+
+use n8n_workflow::ExecutionStatus;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Unfinished executions by state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LiveCounts {
+    pub queued: u64,
+    pub running: u64,
+    pub waiting: u64,
+}
+
+impl LiveCounts {
+    fn slot(&mut self, status: ExecutionStatus) -> Option<&mut u64> {
+        match status {
+            ExecutionStatus::New => Some(&mut self.queued),
+            ExecutionStatus::Running => Some(&mut self.running),
+            ExecutionStatus::Waiting => Some(&mut self.waiting),
+            _ => None,
+        }
+    }
+}
+
+/// Counts at one point in time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LiveSnapshot {
+    pub total: LiveCounts,
+    /// Workflows with at least one unfinished execution.
+    pub workflows: BTreeMap<String, LiveCounts>,
+}
+
+/// In-memory counters of unfinished executions.
+#[derive(Debug, Default)]
+pub struct LiveCounters {
+    /// Unfinished execution ID → (workflow ID, status).
+    executions: RwLock<HashMap<String, (String, ExecutionStatus)>>,
+}
+
+impl LiveCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the current status of an execution.
+    pub fn observe(&self, execution_id: &str, workflow_id: &str, status: ExecutionStatus) {
+        let mut executions = self.executions.write();
+        if is_live(status) {
+            executions.insert(execution_id.to_string(), (workflow_id.to_string(), status));
+        } else {
+            executions.remove(execution_id);
+        }
+    }
+
+    /// Forget a deleted execution.
+    pub fn remove(&self, execution_id: &str) {
+        self.executions.write().remove(execution_id);
+    }
+
+    /// Replace the counters with the unfinished executions in storage.
+    /// Returns how many executions were missing, stale or in another state.
+    pub fn reconcile<I>(&self, stored: I) -> usize
+    where
+        I: IntoIterator<Item = (String, String, ExecutionStatus)>,
+    {
+        let fresh: HashMap<String, (String, ExecutionStatus)> = stored
+            .into_iter()
+            .filter(|(_, _, status)| is_live(*status))
+            .map(|(id, workflow_id, status)| (id, (workflow_id, status)))
+            .collect();
+
+        let mut executions = self.executions.write();
+        let drift = fresh.iter().filter(|(id, entry)| executions.get(*id) != Some(*entry)).count()
+            + executions.keys().filter(|id| !fresh.contains_key(*id)).count();
+        *executions = fresh;
+        drift
+    }
+
+    pub fn snapshot(&self) -> LiveSnapshot {
+        let mut snapshot = LiveSnapshot::default();
+        for (workflow_id, status) in self.executions.read().values() {
+            let counts = snapshot.workflows.entry(workflow_id.clone()).or_default();
+            for count in [counts.slot(*status), snapshot.total.slot(*status)].into_iter().flatten() {
+                *count += 1;
+            }
+        }
+        snapshot
+    }
+}
+
+/// Whether executions in `status` are counted.
+fn is_live(status: ExecutionStatus) -> bool {
+    matches!(
+        status,
+        ExecutionStatus::New | ExecutionStatus::Running | ExecutionStatus::Waiting
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_follow_status_changes() {
+        let live = LiveCounters::new();
+        live.observe("e1", "wf-a", ExecutionStatus::New);
+        live.observe("e2", "wf-a", ExecutionStatus::Running);
+        live.observe("e3", "wf-b", ExecutionStatus::Waiting);
+        live.observe("e1", "wf-a", ExecutionStatus::Running);
+        live.observe("e2", "wf-a", ExecutionStatus::Success);
+
+        let snapshot = live.snapshot();
+        assert_eq!(snapshot.total, LiveCounts { queued: 0, running: 1, waiting: 1 });
+        assert_eq!(snapshot.workflows["wf-a"].running, 1);
+        assert_eq!(snapshot.workflows["wf-b"].waiting, 1);
+
+        live.remove("e3");
+        assert!(!live.snapshot().workflows.contains_key("wf-b"));
+    }
+
+    #[test]
+    fn test_reconcile_replaces_drifted_counts() {
+        let live = LiveCounters::new();
+        live.observe("e1", "wf-a", ExecutionStatus::Running);
+        live.observe("e2", "wf-a", ExecutionStatus::Running);
+
+        // e1 finished elsewhere, e2 is waiting and e3 was queued by another
+        // instance.
+        let drift = live.reconcile([
+            ("e2".to_string(), "wf-a".to_string(), ExecutionStatus::Waiting),
+            ("e3".to_string(), "wf-b".to_string(), ExecutionStatus::New),
+        ]);
+
+        assert_eq!(drift, 3);
+        assert_eq!(live.snapshot().total, LiveCounts { queued: 1, running: 0, waiting: 1 });
+        assert_eq!(live.reconcile(Vec::new()), 2);
+    }
+}
@@ -0,0 +1,233 @@
+//! Masking of secrets in errors, events and logs.
+//!
+//! [`SecretMask`] is a registry of secret values. Decrypted credentials are
+//! registered by [`CredentialService`](crate::CredentialService) and the
+//! values of designated secret parameters by the engine before a node runs.
+//! [`SecretMask::mask`] replaces every registered value, and the value of
+//! any `Authorization`-style header, with [`MASK`].
+//!
+//! The engine masks task errors before they are stored or sent as events;
+//! the server masks log lines. Values shorter than
+//! [`MaskingConfig::min_length`] are not registered, so short common strings
+//! do not blank out unrelated text.
+
+use n8n_workflow::{ExecutionError, NodeParameterValue};
+use parking_lot::RwLock;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, LazyLock, OnceLock};
+
+/// Replacement for masked values.
+pub const MASK: &str = "***";
+
+/// Credential-bearing HTTP headers, with an optional auth scheme.
+static AUTH_HEADER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r#"(?i)\b((?:proxy-)?authorization|x-api-key|api-key)"#,
+        r#"(["']?\s*[:=]\s*["']?)((?:bearer|basic|token)\s+)?[^\s"',;}]+"#,
+    ))
+    .expect("valid header pattern")
+});
+
+/// Secret masking settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaskingConfig {
+    pub enabled: bool,
+    /// Node parameter names (case-insensitive) whose values are secrets.
+    pub secret_parameters: Vec<String>,
+    /// Shortest value that is registered as a secret.
+    pub min_length: usize,
+}
+
+impl Default for MaskingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            secret_parameters: ["password", "apiKey", "accessToken", "clientSecret", "secret"]
+                .map(String::from)
+                .to_vec(),
+            min_length: 6,
+        }
+    }
+}
+
+/// Shared registry of secret values.
+#[derive(Debug, Clone)]
+pub struct SecretMask {
+    secrets: Arc<RwLock<BTreeSet<Secret>>>,
+    min_length: usize,
+}
+
+/// Secrets ordered longest first, so a secret containing another is
+/// replaced whole.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Secret(String);
+
+impl Ord for Secret {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.0.len().cmp(&self.0.len()).then_with(|| self.0.cmp(&other.0))
+    }
+}
+
+impl PartialOrd for Secret {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Default for SecretMask {
+    fn default() -> Self {
+        Self::new(MaskingConfig::default().min_length)
+    }
+}
+
+impl SecretMask {
+    pub fn new(min_length: usize) -> Self {
+        Self {
+            secrets: Arc::new(RwLock::new(BTreeSet::new())),
+            min_length,
+        }
+    }
+
+    /// The process-wide registry shared by the engine, credentials and logs.
+    pub fn global() -> Self {
+        static GLOBAL: OnceLock<SecretMask> = OnceLock::new();
+        GLOBAL.get_or_init(SecretMask::default).clone()
+    }
+
+    /// Register a secret value.
+    pub fn register(&self, secret: &str) {
+        let secret = secret.trim();
+        if secret.len() >= self.min_length && secret != MASK {
+            self.secrets.write().insert(Secret(secret.to_string()));
+        }
+    }
+
+    /// Register every string in decrypted credential data.
+    pub fn register_json(&self, value: &serde_json::Value) {
+        match value {
+            serde_json::Value::String(s) => self.register(s),
+            serde_json::Value::Array(items) => items.iter().for_each(|v| self.register_json(v)),
+            serde_json::Value::Object(map) => map.values().for_each(|v| self.register_json(v)),
+            _ => {}
+        }
+    }
+
+    /// Register the values of parameters named in `names`, at any depth.
+    pub fn register_parameters(
+        &self,
+        parameters: &HashMap<String, NodeParameterValue>,
+        names: &[String],
+    ) {
+        for (name, value) in parameters {
+            if names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                self.register_parameter(value);
+            } else if let NodeParameterValue::Object(nested) = value {
+                self.register_parameters(nested, names);
+            } else if let NodeParameterValue::Array(items) = value {
+                for item in items {
+                    if let NodeParameterValue::Object(nested) = item {
+                        self.register_parameters(nested, names);
+                    }
+                }
+            }
+        }
+    }
+
+    fn register_parameter(&self, value: &NodeParameterValue) {
+        match value {
+            NodeParameterValue::String(s) => self.register(s),
+            NodeParameterValue::Array(items) => {
+                items.iter().for_each(|v| self.register_parameter(v))
+            }
+            NodeParameterValue::Object(map) => {
+                map.values().for_each(|v| self.register_parameter(v))
+            }
+            _ => {}
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.secrets.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `text` with secrets and auth header values replaced by [`MASK`].
+    pub fn mask<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut masked = Cow::Borrowed(text);
+        for Secret(secret) in self.secrets.read().iter() {
+            if masked.contains(secret.as_str()) {
+                masked = Cow::Owned(masked.replace(secret.as_str(), MASK));
+            }
+        }
+        if AUTH_HEADER.is_match(&masked) {
+            let replaced = AUTH_HEADER.replace_all(&masked, format!("${{1}}${{2}}${{3}}{}", MASK));
+            masked = Cow::Owned(replaced.into_owned());
+        }
+        masked
+    }
+
+    /// Mask the message and context of an execution error in place.
+    pub fn mask_error(&self, error: &mut ExecutionError) {
+        let fields = [
+            Some(&mut error.message),
+            error.context.description.as_mut(),
+            error.context.cause.as_mut(),
+            error.stack.as_mut(),
+        ];
+        for field in fields.into_iter().flatten() {
+            if let Cow::Owned(masked) = self.mask(field) {
+                *field = masked;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_registered_secrets_and_auth_headers() {
+        let mask = SecretMask::new(6);
+        mask.register_json(&serde_json::json!({
+            "user": "bob",
+            "apiKey": "sk-live-123456",
+            "nested": { "token": "sk-live-123456-extra" }
+        }));
+        // "bob" is too short to register.
+        assert_eq!(mask.len(), 2);
+
+        assert_eq!(
+            mask.mask("key sk-live-123456-extra rejected for bob"),
+            "key *** rejected for bob"
+        );
+        assert_eq!(
+            mask.mask(r#"401 with headers {"Authorization": "Bearer abc.def", "X-Api-Key": "k"}"#),
+            r#"401 with headers {"Authorization": "Bearer ***", "X-Api-Key": "***"}"#
+        );
+        assert!(matches!(mask.mask("nothing secret"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_register_secret_parameters() {
+        let mask = SecretMask::new(6);
+        let mut auth = HashMap::new();
+        auth.insert("password".to_string(), NodeParameterValue::String("hunter22".into()));
+        let mut parameters = HashMap::new();
+        parameters.insert("url".to_string(), NodeParameterValue::String("https://api".into()));
+        parameters.insert("auth".to_string(), NodeParameterValue::Object(auth));
+
+        mask.register_parameters(&parameters, &MaskingConfig::default().secret_parameters);
+
+        let mut error = ExecutionError::new("login as admin:hunter22 failed at https://api");
+        mask.mask_error(&mut error);
+        assert_eq!(error.message, "login as admin:*** failed at https://api");
+    }
+}
@@ -0,0 +1,383 @@
+//! Node deprecation and `typeVersion` migration.
+//!
+//! n8n nodes carry a `typeVersion`; when a node type changes its parameter
+//! shape the version is bumped and older workflows are upgraded on load. A
+//! [`MigrationRegistry`] holds [`NodeMigration`]s keyed by node type and
+//! source version, and applies them in sequence until the node reaches the
+//! latest version. A migration may also replace the node type (e.g. the
+//! deprecated Function node becomes a Code node).
+//!
+//! Deprecated node types are recorded as [`Deprecation`]s. Both outdated
+//! versions and deprecated types are reported by the linter
+//! (`outdated-node-version`, `deprecated-node-type`).
+
+use crate::node_types::get_node_type_description;
+use n8n_workflow::{Node, NodeParameterValue, Workflow};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Upgrades a node from one `typeVersion` to the next.
+pub trait NodeMigration: Send + Sync {
+    /// Node type this migration applies to.
+    fn node_type(&self) -> &str;
+
+    /// Version the migration upgrades from.
+    fn from_version(&self) -> u32;
+
+    /// Version the node has afterwards.
+    fn to_version(&self) -> u32;
+
+    /// Node type afterwards, if the migration replaces the type.
+    fn target_type(&self) -> Option<&str> {
+        None
+    }
+
+    /// What the migration changes.
+    fn description(&self) -> &str;
+
+    /// Rewrite the node's parameters. The registry updates `node_type` and
+    /// `type_version` afterwards.
+    fn migrate(&self, node: &mut Node) -> Result<(), String>;
+}
+
+/// A deprecated node type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Deprecation {
+    pub node_type: String,
+    /// Node type to use instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+    pub message: String,
+}
+
+/// A migration applied to a node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedMigration {
+    pub node: String,
+    pub from_type: String,
+    pub from_version: u32,
+    pub to_type: String,
+    pub to_version: u32,
+    pub description: String,
+}
+
+/// A node whose migration failed; the node is left unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationFailure {
+    pub node: String,
+    pub node_type: String,
+    pub type_version: u32,
+    pub message: String,
+}
+
+/// Result of migrating a workflow.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub applied: Vec<AppliedMigration>,
+    pub failed: Vec<MigrationFailure>,
+}
+
+impl MigrationReport {
+    /// Whether nothing was changed or failed.
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.failed.is_empty()
+    }
+}
+
+/// Registry of node migrations and deprecations.
+#[derive(Clone)]
+pub struct MigrationRegistry {
+    migrations: HashMap<(String, u32), Arc<dyn NodeMigration>>,
+    deprecations: HashMap<String, Deprecation>,
+}
+
+impl MigrationRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self {
+            migrations: HashMap::new(),
+            deprecations: HashMap::new(),
+        }
+    }
+
+    /// Register a migration, replacing any for the same type and version.
+    pub fn register(&mut self, migration: Arc<dyn NodeMigration>) {
+        self.migrations.insert(
+            (migration.node_type().to_string(), migration.from_version()),
+            migration,
+        );
+    }
+
+    /// Mark a node type as deprecated.
+    pub fn deprecate(&mut self, deprecation: Deprecation) {
+        self.deprecations
+            .insert(deprecation.node_type.clone(), deprecation);
+    }
+
+    /// Deprecation for a node type, if deprecated.
+    pub fn deprecation(&self, node_type: &str) -> Option<&Deprecation> {
+        self.deprecations.get(node_type)
+    }
+
+    /// Latest known version of a node type, from its registered migrations
+    /// and built-in description.
+    pub fn latest_version(&self, node_type: &str) -> Option<u32> {
+        let described = get_node_type_description(node_type).map(|d| d.version.latest());
+        self.migrations
+            .values()
+            .filter(|m| m.target_type().unwrap_or(m.node_type()) == node_type)
+            .map(|m| m.to_version())
+            .chain(described)
+            .max()
+    }
+
+    /// Migrations that would be applied to a node, in order.
+    pub fn pending(&self, node: &Node) -> Vec<Arc<dyn NodeMigration>> {
+        let mut chain = Vec::new();
+        let mut key = (node.node_type.clone(), node.type_version);
+
+        while let Some(migration) = self.migrations.get(&key) {
+            // Guard against registrations that loop back on themselves.
+            if chain.len() > self.migrations.len() {
+                break;
+            }
+            chain.push(migration.clone());
+            key = (
+                migration
+                    .target_type()
+                    .unwrap_or(migration.node_type())
+                    .to_string(),
+                migration.to_version(),
+            );
+        }
+
+        chain
+    }
+
+    /// Upgrade a node to the latest version. On failure the node is left
+    /// unchanged.
+    pub fn migrate_node(&self, node: &mut Node) -> Result<Vec<AppliedMigration>, MigrationFailure> {
+        let mut upgraded = node.clone();
+        let mut applied = Vec::new();
+
+        for migration in self.pending(node) {
+            migration
+                .migrate(&mut upgraded)
+                .map_err(|message| MigrationFailure {
+                    node: node.name.clone(),
+                    node_type: upgraded.node_type.clone(),
+                    type_version: upgraded.type_version,
+                    message,
+                })?;
+
+            let from_type = std::mem::replace(
+                &mut upgraded.node_type,
+                migration
+                    .target_type()
+                    .unwrap_or(migration.node_type())
+                    .to_string(),
+            );
+            applied.push(AppliedMigration {
+                node: node.name.clone(),
+                from_type,
+                from_version: upgraded.type_version,
+                to_type: upgraded.node_type.clone(),
+                to_version: migration.to_version(),
+                description: migration.description().to_string(),
+            });
+            upgraded.type_version = migration.to_version();
+        }
+
+        *node = upgraded;
+        Ok(applied)
+    }
+
+    /// Upgrade every node of a workflow.
+    pub fn migrate_workflow(&self, workflow: &mut Workflow) -> MigrationReport {
+        let mut report = MigrationReport::default();
+
+        for node in &mut workflow.nodes {
+            match self.migrate_node(node) {
+                Ok(applied) => report.applied.extend(applied),
+                Err(failure) => report.failed.push(failure),
+            }
+        }
+
+        report
+    }
+}
+
+impl Default for MigrationRegistry {
+    /// A registry with the built-in migrations and deprecations.
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(Arc::new(FunctionToCode {
+            node_type: "n8n-nodes-base.function",
+            mode: "runOnceForAllItems",
+        }));
+        registry.register(Arc::new(FunctionToCode {
+            node_type: "n8n-nodes-base.functionItem",
+            mode: "runOnceForEachItem",
+        }));
+        registry.register(Arc::new(HttpRequestV1));
+
+        for (node_type, replacement) in [
+            ("n8n-nodes-base.function", "n8n-nodes-base.code"),
+            ("n8n-nodes-base.functionItem", "n8n-nodes-base.code"),
+            ("n8n-nodes-base.interval", "n8n-nodes-base.scheduleTrigger"),
+            ("n8n-nodes-base.cron", "n8n-nodes-base.scheduleTrigger"),
+        ] {
+            registry.deprecate(Deprecation {
+                node_type: node_type.to_string(),
+                replacement: Some(replacement.to_string()),
+                message: format!("Node type '{}' is deprecated; use '{}'", node_type, replacement),
+            });
+        }
+
+        registry
+    }
+}
+
+// ============================================================================
+// Built-in Migrations
+// ============================================================================
+
+/// Function / Function Item (v1) → Code (v1): `functionCode` → `jsCode`.
+struct FunctionToCode {
+    node_type: &'static str,
+    mode: &'static str,
+}
+
+impl NodeMigration for FunctionToCode {
+    fn node_type(&self) -> &str {
+        self.node_type
+    }
+
+    fn from_version(&self) -> u32 {
+        1
+    }
+
+    fn to_version(&self) -> u32 {
+        1
+    }
+
+    fn target_type(&self) -> Option<&str> {
+        Some("n8n-nodes-base.code")
+    }
+
+    fn description(&self) -> &str {
+        "Replace with a Code node (functionCode → jsCode)"
+    }
+
+    fn migrate(&self, node: &mut Node) -> Result<(), String> {
+        let code = node.parameters.remove("functionCode").unwrap_or_default();
+        node.parameters.insert("jsCode".into(), code);
+        node.parameters
+            .insert("mode".into(), NodeParameterValue::String(self.mode.into()));
+        Ok(())
+    }
+}
+
+/// HTTP Request v1 → v2: `requestMethod` → `method`, `options.timeout` →
+/// `timeout`.
+struct HttpRequestV1;
+
+impl NodeMigration for HttpRequestV1 {
+    fn node_type(&self) -> &str {
+        "n8n-nodes-base.httpRequest"
+    }
+
+    fn from_version(&self) -> u32 {
+        1
+    }
+
+    fn to_version(&self) -> u32 {
+        2
+    }
+
+    fn description(&self) -> &str {
+        "Rename requestMethod to method and move options.timeout to timeout"
+    }
+
+    fn migrate(&self, node: &mut Node) -> Result<(), String> {
+        if let Some(method) = node.parameters.remove("requestMethod") {
+            node.parameters.entry("method".into()).or_insert(method);
+        }
+
+        let timeout = match node.parameters.get_mut("options") {
+            Some(NodeParameterValue::Object(options)) => options.remove("timeout"),
+            Some(_) => return Err("options must be an object".into()),
+            None => None,
+        };
+        if let Some(timeout) = timeout {
+            node.parameters.entry("timeout".into()).or_insert(timeout);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_request_v1_migration() {
+        let mut node = Node::new("Fetch", "n8n-nodes-base.httpRequest");
+        node.set_parameter("requestMethod", NodeParameterValue::String("POST".into()));
+        node.set_parameter(
+            "options",
+            NodeParameterValue::Object(HashMap::from([(
+                "timeout".to_string(),
+                NodeParameterValue::Number(5000.0),
+            )])),
+        );
+
+        let registry = MigrationRegistry::default();
+        assert_eq!(registry.latest_version("n8n-nodes-base.httpRequest"), Some(2));
+
+        let applied = registry.migrate_node(&mut node).unwrap();
+        assert_eq!(applied.len(), 1);
+        assert_eq!(node.type_version, 2);
+        assert!(matches!(node.get_parameter("method"), Some(NodeParameterValue::String(m)) if m == "POST"));
+        assert!(node.get_parameter("timeout").is_some());
+        assert!(registry.pending(&node).is_empty());
+    }
+
+    #[test]
+    fn test_function_node_becomes_code() {
+        let mut wf = Workflow::new("Legacy");
+        let mut function = Node::new("Transform", "n8n-nodes-base.function");
+        function.set_parameter("functionCode", NodeParameterValue::String("return items;".into()));
+        wf.add_node(function);
+
+        let registry = MigrationRegistry::default();
+        assert!(registry.deprecation("n8n-nodes-base.function").is_some());
+
+        let report = registry.migrate_workflow(&mut wf);
+        assert_eq!(report.applied.len(), 1);
+        assert!(report.failed.is_empty());
+
+        let node = wf.get_node("Transform").unwrap();
+        assert_eq!(node.node_type, "n8n-nodes-base.code");
+        assert!(node.get_parameter("jsCode").is_some());
+        assert!(node.get_parameter("functionCode").is_none());
+    }
+
+    #[test]
+    fn test_failed_migration_leaves_node_unchanged() {
+        let mut node = Node::new("Fetch", "n8n-nodes-base.httpRequest");
+        node.set_parameter("requestMethod", NodeParameterValue::String("GET".into()));
+        node.set_parameter("options", NodeParameterValue::String("bad".into()));
+
+        let failure = MigrationRegistry::default().migrate_node(&mut node).unwrap_err();
+        assert_eq!(failure.type_version, 1);
+        assert_eq!(node.type_version, 1);
+        assert!(node.get_parameter("requestMethod").is_some());
+    }
+}
@@ -5,11 +5,34 @@ use n8n_workflow::{
     NodeTypeDescription, NodeVersion,
 };
 
+/// Names of the built-in node types.
+pub const BUILTIN_NODE_TYPES: &[&str] = &[
+    "n8n-nodes-base.manualTrigger",
+    "n8n-nodes-base.scheduleTrigger",
+    "n8n-nodes-base.timerTrigger",
+    "n8n-nodes-base.webhook",
+    "n8n-nodes-base.set",
+    "n8n-nodes-base.if",
+    "n8n-nodes-base.merge",
+    "n8n-nodes-base.code",
+    "n8n-nodes-base.httpRequest",
+    "n8n-nodes-base.noOp",
+];
+
+/// Descriptions of all built-in node types.
+pub fn builtin_node_type_descriptions() -> Vec<NodeTypeDescription> {
+    BUILTIN_NODE_TYPES
+        .iter()
+        .filter_map(|name| get_node_type_description(name))
+        .collect()
+}
+
 /// Get the description for a built-in node type.
 pub fn get_node_type_description(node_type: &str) -> Option<NodeTypeDescription> {
     match node_type {
         "n8n-nodes-base.manualTrigger" => Some(manual_trigger_description()),
         "n8n-nodes-base.scheduleTrigger" => Some(schedule_trigger_description()),
+        "n8n-nodes-base.timerTrigger" => Some(timer_trigger_description()),
         "n8n-nodes-base.webhook" => Some(webhook_trigger_description()),
         "n8n-nodes-base.set" => Some(set_description()),
         "n8n-nodes-base.if" => Some(if_description()),
@@ -83,6 +106,19 @@ fn schedule_trigger_description() -> NodeTypeDescription {
                 options: None,
                 placeholder: Some("0 0 * * *".to_string()),
             },
+            NodeProperty {
+                name: "options".to_string(),
+                display_name: "Options".to_string(),
+                property_type: NodePropertyType::Collection,
+                default: None,
+                description: Some(
+                    "`jitterSeconds`, `alignment` (`clock` or `sliding`) and `skipIfRunning`"
+                        .to_string(),
+                ),
+                required: false,
+                options: None,
+                placeholder: None,
+            },
         ],
         credentials: None,
         trigger: true,
@@ -90,6 +126,30 @@ fn schedule_trigger_description() -> NodeTypeDescription {
     }
 }
 
+fn timer_trigger_description() -> NodeTypeDescription {
+    NodeTypeDescription {
+        name: "n8n-nodes-base.timerTrigger".to_string(),
+        display_name: "Timer Trigger".to_string(),
+        group: vec!["trigger".to_string(), "schedule".to_string()],
+        description: "Starts the workflow when a scheduled timer fires".to_string(),
+        version: NodeVersion::Single(1),
+        icon: Some("fa:hourglass".to_string()),
+        inputs: vec![],
+        outputs: vec![NodeConnectionConfig {
+            connection_type: "main".to_string(),
+            display_name: None,
+            required: false,
+            max_connections: None,
+        }],
+        default_input_name: None,
+        default_output_name: None,
+        properties: vec![],
+        credentials: None,
+        trigger: true,
+        polling: false,
+    }
+}
+
 fn webhook_trigger_description() -> NodeTypeDescription {
     NodeTypeDescription {
         name: "n8n-nodes-base.webhook".to_string(),
@@ -329,7 +389,7 @@ fn http_request_description() -> NodeTypeDescription {
         display_name: "HTTP Request".to_string(),
         group: vec!["output".to_string()],
         description: "Make HTTP requests".to_string(),
-        version: NodeVersion::Single(1),
+        version: NodeVersion::Multiple(vec![1, 2]),
         icon: Some("fa:globe".to_string()),
         inputs: vec![NodeConnectionConfig {
             connection_type: "main".to_string(),
@@ -366,6 +426,26 @@ fn http_request_description() -> NodeTypeDescription {
                 options: None,
                 placeholder: None,
             },
+            NodeProperty {
+                name: "timeout".to_string(),
+                display_name: "Timeout".to_string(),
+                property_type: NodePropertyType::Number,
+                default: None,
+                description: Some("Milliseconds before the request fails".to_string()),
+                required: false,
+                options: None,
+                placeholder: None,
+            },
+            NodeProperty {
+                name: "options".to_string(),
+                display_name: "Options".to_string(),
+                property_type: NodePropertyType::Collection,
+                default: None,
+                description: Some("Request options, e.g. `neverError`".to_string()),
+                required: false,
+                options: None,
+                placeholder: None,
+            },
         ],
         credentials: Some(vec![NodeCredentialDescription {
             name: "httpBasicAuth".to_string(),
@@ -0,0 +1,280 @@
+//! Structural workflow patches.
+//!
+//! A [`WorkflowPatch`] edits a workflow through operations on its nodes and
+//! connections instead of replacing it, so a program changing one parameter
+//! neither sends the whole workflow nor overwrites edits it never saw.
+//! [`WorkflowPatch::apply`] applies the operations in order to a copy and
+//! fails on the first one that does not fit, leaving the workflow as it
+//! was: adding a node under a taken name, changing or removing a node that
+//! is not there, connecting missing nodes, adding a connection that exists
+//! or removing one that does not.
+//!
+//! Renaming a node through `updateNode` renames it in connections and
+//! pinned data too; `$('Node')` references in expressions are left as they
+//! are.
+
+use chrono::Utc;
+use n8n_workflow::{Node, Workflow, CONNECTION_MAIN};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// One change to a workflow.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum PatchOperation {
+    /// Add `node`; an empty `id` is replaced by a new one.
+    AddNode { node: Node },
+    /// Remove the node named `name` with its connections and pinned data.
+    RemoveNode { name: String },
+    /// Merge `changes` into the node named `name` as a JSON merge patch
+    /// (RFC 7396): fields set to `null` are removed, objects are merged,
+    /// anything else is replaced.
+    UpdateNode { name: String, changes: Value },
+    AddConnection(ConnectionPatch),
+    RemoveConnection(ConnectionPatch),
+}
+
+/// A connection added or removed by a patch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionPatch {
+    pub source: String,
+    pub target: String,
+    #[serde(default)]
+    pub source_output: usize,
+    #[serde(default)]
+    pub target_input: usize,
+    #[serde(rename = "type", default = "main_connection")]
+    pub connection_type: String,
+}
+
+fn main_connection() -> String {
+    CONNECTION_MAIN.to_string()
+}
+
+/// Operations applied to a workflow all together or not at all.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkflowPatch {
+    pub operations: Vec<PatchOperation>,
+}
+
+/// Why a patch does not apply.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Operation {index}: {message}")]
+pub struct PatchError {
+    /// Index of the failing operation.
+    pub index: usize,
+    pub message: String,
+}
+
+impl WorkflowPatch {
+    pub fn new(operations: Vec<PatchOperation>) -> Self {
+        Self { operations }
+    }
+
+    /// `workflow` with every operation applied.
+    pub fn apply(&self, workflow: &Workflow) -> Result<Workflow, PatchError> {
+        let mut patched = workflow.clone();
+        for (index, operation) in self.operations.iter().enumerate() {
+            apply_operation(&mut patched, operation)
+                .map_err(|message| PatchError { index, message })?;
+        }
+        patched.updated_at = Some(Utc::now());
+        Ok(patched)
+    }
+}
+
+fn apply_operation(workflow: &mut Workflow, operation: &PatchOperation) -> Result<(), String> {
+    match operation {
+        PatchOperation::AddNode { node } => {
+            check_name(workflow, &node.name)?;
+            let mut node = node.clone();
+            if node.id.is_empty() {
+                node.id = uuid::Uuid::new_v4().to_string();
+            }
+            workflow.nodes.push(node);
+        }
+        PatchOperation::RemoveNode { name } => {
+            let before = workflow.nodes.len();
+            workflow.nodes.retain(|node| node.name != *name);
+            if workflow.nodes.len() == before {
+                return Err(format!("Node '{}' not found", name));
+            }
+            workflow.connections.remove(name);
+            for conns in workflow.connections.values_mut().flat_map(|c| c.values_mut()).flatten()
+            {
+                conns.retain(|conn| conn.node != *name);
+            }
+            if let Some(pin_data) = workflow.pin_data.as_mut() {
+                pin_data.remove(name);
+            }
+        }
+        PatchOperation::UpdateNode { name, changes } => {
+            if !changes.is_object() {
+                return Err("changes must be an object".to_string());
+            }
+            let node =
+                workflow.get_node(name).ok_or_else(|| format!("Node '{}' not found", name))?;
+            let mut json = serde_json::to_value(node).map_err(|e| e.to_string())?;
+            merge_patch(&mut json, changes);
+            let updated: Node = serde_json::from_value(json)
+                .map_err(|e| format!("Node '{}' is invalid after the changes: {}", name, e))?;
+            if updated.name != *name {
+                check_name(workflow, &updated.name)?;
+                rename_references(workflow, name, &updated.name);
+            }
+            if let Some(node) = workflow.get_node_mut(name) {
+                *node = updated;
+            }
+        }
+        PatchOperation::AddConnection(patch) => {
+            if find_connection(workflow, patch).is_some() {
+                return Err(format!("{} is already connected", describe(patch)));
+            }
+            workflow
+                .connect_with_type(
+                    &patch.source,
+                    &patch.target,
+                    &patch.connection_type,
+                    patch.source_output,
+                    patch.target_input,
+                )
+                .map_err(|e| e.to_string())?;
+        }
+        PatchOperation::RemoveConnection(patch) => {
+            let position = find_connection(workflow, patch)
+                .ok_or_else(|| format!("{} is not connected", describe(patch)))?;
+            if let Some(conns) = workflow
+                .connections
+                .get_mut(&patch.source)
+                .and_then(|c| c.get_mut(&patch.connection_type))
+                .and_then(|by_output| by_output.get_mut(patch.source_output))
+            {
+                conns.remove(position);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_name(workflow: &Workflow, name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Node names cannot be empty".to_string());
+    }
+    if workflow.get_node(name).is_some() {
+        return Err(format!("A node named '{}' already exists", name));
+    }
+    Ok(())
+}
+
+fn rename_references(workflow: &mut Workflow, from: &str, to: &str) {
+    if let Some(conns) = workflow.connections.remove(from) {
+        workflow.connections.insert(to.to_string(), conns);
+    }
+    for conn in workflow.connections.values_mut().flat_map(|c| c.values_mut()).flatten().flatten()
+    {
+        if conn.node == from {
+            conn.node = to.to_string();
+        }
+    }
+    if let Some(pin_data) = workflow.pin_data.as_mut() {
+        if let Some(items) = pin_data.remove(from) {
+            pin_data.insert(to.to_string(), items);
+        }
+    }
+}
+
+/// Position of the connection among those of its source output.
+fn find_connection(workflow: &Workflow, patch: &ConnectionPatch) -> Option<usize> {
+    workflow
+        .connections
+        .get(&patch.source)?
+        .get(&patch.connection_type)?
+        .get(patch.source_output)?
+        .iter()
+        .position(|conn| {
+            conn.node == patch.target
+                && conn.connection_type == patch.connection_type
+                && conn.index == patch.target_input
+        })
+}
+
+fn describe(patch: &ConnectionPatch) -> String {
+    format!(
+        "'{}' output {} to '{}' input {} ({})",
+        patch.source, patch.source_output, patch.target, patch.target_input, patch.connection_type
+    )
+}
+
+/// Apply the JSON merge patch `patch` to `target`.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn operations(ops: Value) -> WorkflowPatch {
+        serde_json::from_value(json!({ "operations": ops })).unwrap()
+    }
+
+    #[test]
+    fn test_patch_applies_all_or_nothing() {
+        let mut workflow = Workflow::new("orders");
+        workflow.add_node(Node::new("Start", "n8n-nodes-base.manualTrigger"));
+        workflow.add_node(Node::new("Fetch", "n8n-nodes-base.httpRequest"));
+        workflow.connect("Start", "Fetch", 0, 0).unwrap();
+
+        let patch = operations(json!([
+            { "op": "addNode", "node": {
+                "id": "", "name": "Store", "type": "n8n-nodes-base.noOp",
+                "typeVersion": 1, "position": [400, 0]
+            } },
+            { "op": "addConnection", "source": "Fetch", "target": "Store" },
+            { "op": "updateNode", "name": "Fetch", "changes": {
+                "name": "Fetch Orders", "parameters": { "url": "https://shop.example/orders" }
+            } }
+        ]));
+        let patched = patch.apply(&workflow).unwrap();
+        assert!(!patched.get_node("Store").unwrap().id.is_empty());
+        let fetch = patched.get_node("Fetch Orders").unwrap();
+        assert!(fetch.parameters.contains_key("url"));
+        assert_eq!(patched.connections["Start"]["main"][0][0].node, "Fetch Orders");
+        assert_eq!(patched.connections["Fetch Orders"]["main"][0][0].node, "Store");
+
+        let patch = operations(json!([
+            { "op": "removeNode", "name": "Start" },
+            { "op": "removeConnection", "source": "Fetch", "target": "Store" }
+        ]));
+        let err = patch.apply(&patched).unwrap_err();
+        assert_eq!(err.index, 1);
+        // The failed patch changed nothing.
+        assert!(patched.get_node("Start").is_some());
+
+        let patch = operations(json!([{ "op": "removeNode", "name": "Start" }]));
+        let patched = patch.apply(&patched).unwrap();
+        assert!(!patched.connections.contains_key("Start"));
+        assert!(operations(json!([{ "op": "updateNode", "name": "Store", "changes": {
+            "name": "Fetch Orders"
+        } }]))
+        .apply(&patched)
+        .is_err());
+    }
+}
@@ -0,0 +1,279 @@
+//! Processed data: values seen by earlier executions, for deduplication.
+//!
+//! As n8n's Remove Duplicates node does with "remove items processed in
+//! previous executions", each node (or workflow, with `scope: workflow`)
+//! keeps one value under a context:
+//!
+//! - `entries`: hashes of the values seen, the latest `historySize`;
+//! - `latestIncrementalKey`: the highest number seen;
+//! - `latestDate`: the latest date seen.
+//!
+//! [`ProcessedDataStore::check_and_record`] compares a batch of values
+//! against the stored one and records them in one step, so two executions
+//! never both pass the same value. Values are kept in memory by default;
+//! the Postgres repository in `n8n-db` keeps them in `processed_data`.
+
+use async_trait::async_trait;
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use n8n_workflow::GenericValue;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+/// Entries kept per context unless the node sets `historySize`.
+pub const DEFAULT_HISTORY_SIZE: usize = 10_000;
+
+/// How values are compared with those processed before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessedDataMode {
+    /// Values not seen before pass.
+    Entries,
+    /// Numbers above the highest seen pass.
+    LatestIncrementalKey,
+    /// Dates after the latest seen pass.
+    LatestDate,
+}
+
+impl ProcessedDataMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Entries => "entries",
+            Self::LatestIncrementalKey => "latestIncrementalKey",
+            Self::LatestDate => "latestDate",
+        }
+    }
+}
+
+/// What is stored for a context, as n8n stores it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "data", rename_all = "camelCase")]
+pub enum ProcessedValue {
+    /// Hashes of the values seen, oldest first.
+    Entries(VecDeque<String>),
+    LatestIncrementalKey(f64),
+    /// RFC 3339.
+    LatestDate(String),
+}
+
+impl ProcessedValue {
+    pub fn mode(&self) -> ProcessedDataMode {
+        match self {
+            Self::Entries(_) => ProcessedDataMode::Entries,
+            Self::LatestIncrementalKey(_) => ProcessedDataMode::LatestIncrementalKey,
+            Self::LatestDate(_) => ProcessedDataMode::LatestDate,
+        }
+    }
+}
+
+/// Compare `values` with `stored` under `mode`: whether each is new, and
+/// the value to store afterwards. In `entries` mode, repeats within
+/// `values` are not new either.
+pub fn check(
+    stored: Option<&ProcessedValue>,
+    mode: ProcessedDataMode,
+    values: &[String],
+    history_size: usize,
+) -> Result<(Vec<bool>, ProcessedValue), String> {
+    if let Some(stored) = stored.filter(|stored| stored.mode() != mode) {
+        return Err(format!(
+            "Deduplication data was saved in '{}' mode, not '{}'; clear the history first",
+            stored.mode().as_str(),
+            mode.as_str()
+        ));
+    }
+
+    match mode {
+        ProcessedDataMode::Entries => {
+            let mut entries = match stored {
+                Some(ProcessedValue::Entries(entries)) => entries.clone(),
+                _ => VecDeque::new(),
+            };
+            let mut seen: HashSet<String> = entries.iter().cloned().collect();
+            let new = values
+                .iter()
+                .map(|value| {
+                    let hash = hash(value);
+                    let new = seen.insert(hash.clone());
+                    if new {
+                        entries.push_back(hash);
+                    }
+                    new
+                })
+                .collect();
+            while entries.len() > history_size {
+                entries.pop_front();
+            }
+            Ok((new, ProcessedValue::Entries(entries)))
+        }
+        ProcessedDataMode::LatestIncrementalKey => {
+            let latest = match stored {
+                Some(ProcessedValue::LatestIncrementalKey(latest)) => Some(*latest),
+                _ => None,
+            };
+            let numbers = values
+                .iter()
+                .map(|value| {
+                    value
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|n| n.is_finite())
+                        .ok_or_else(|| format!("'{}' is not a number", value))
+                })
+                .collect::<Result<Vec<f64>, String>>()?;
+            let new = numbers.iter().map(|n| latest.map_or(true, |l| *n > l)).collect();
+            let highest = numbers.into_iter().chain(latest).fold(f64::MIN, f64::max);
+            Ok((new, ProcessedValue::LatestIncrementalKey(highest)))
+        }
+        ProcessedDataMode::LatestDate => {
+            let latest = match stored {
+                Some(ProcessedValue::LatestDate(latest)) => parse_date(latest).ok(),
+                _ => None,
+            };
+            let dates = values
+                .iter()
+                .map(|value| parse_date(value))
+                .collect::<Result<Vec<_>, String>>()?;
+            let new = dates.iter().map(|d| latest.map_or(true, |l| *d > l)).collect();
+            let newest = dates.into_iter().chain(latest).max().unwrap_or_else(Utc::now);
+            Ok((new, ProcessedValue::LatestDate(newest.to_rfc3339())))
+        }
+    }
+}
+
+/// n8n's hash of a seen value: base64 MD5.
+fn hash(value: &str) -> String {
+    base64::engine::general_purpose::STANDARD.encode(md5::compute(value).0)
+}
+
+fn parse_date(value: &str) -> Result<DateTime<Utc>, String> {
+    GenericValue::String(value.to_string())
+        .as_datetime()
+        .or_else(|| {
+            let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+            Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+        })
+        .ok_or_else(|| format!("'{}' is not a date", value))
+}
+
+/// Where processed data is kept.
+#[async_trait]
+pub trait ProcessedDataStore: Send + Sync {
+    /// [`check`] `values` against the value of `context` in `workflow_id`
+    /// and store the result, atomically. Returns whether each value is new.
+    async fn check_and_record(
+        &self,
+        workflow_id: &str,
+        context: &str,
+        mode: ProcessedDataMode,
+        values: &[String],
+        history_size: usize,
+    ) -> Result<Vec<bool>, String>;
+
+    /// Forget the value of `context` in `workflow_id`.
+    async fn clear(&self, workflow_id: &str, context: &str) -> Result<(), String>;
+}
+
+/// In-process processed data, lost on restart.
+#[derive(Default)]
+pub struct MemoryProcessedData {
+    values: Mutex<HashMap<(String, String), ProcessedValue>>,
+}
+
+impl MemoryProcessedData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ProcessedDataStore for MemoryProcessedData {
+    async fn check_and_record(
+        &self,
+        workflow_id: &str,
+        context: &str,
+        mode: ProcessedDataMode,
+        values: &[String],
+        history_size: usize,
+    ) -> Result<Vec<bool>, String> {
+        let mut stored = self.values.lock();
+        let key = (workflow_id.to_string(), context.to_string());
+        let (new, value) = check(stored.get(&key), mode, values, history_size)?;
+        stored.insert(key, value);
+        Ok(new)
+    }
+
+    async fn clear(&self, workflow_id: &str, context: &str) -> Result<(), String> {
+        self.values.lock().remove(&(workflow_id.to_string(), context.to_string()));
+        Ok(())
+    }
+}
+
+/// The engine's processed data. Clones share the store, which may be
+/// replaced once a durable one becomes available.
+#[derive(Clone)]
+pub struct ProcessedData {
+    store: Arc<RwLock<Arc<dyn ProcessedDataStore>>>,
+}
+
+impl Default for ProcessedData {
+    fn default() -> Self {
+        Self::new(Arc::new(MemoryProcessedData::new()))
+    }
+}
+
+impl ProcessedData {
+    pub fn new(store: Arc<dyn ProcessedDataStore>) -> Self {
+        Self {
+            store: Arc::new(RwLock::new(store)),
+        }
+    }
+
+    /// Keep processed data in `store` from now on.
+    pub fn set_store(&self, store: Arc<dyn ProcessedDataStore>) {
+        *self.store.write() = store;
+    }
+
+    /// The current store.
+    pub fn store(&self) -> Arc<dyn ProcessedDataStore> {
+        self.store.read().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_values_pass_once_across_batches() {
+        let store = MemoryProcessedData::new();
+        let entries = ProcessedDataMode::Entries;
+        let first = store.check_and_record("wf", "node:n1", entries, &strings(&["a", "b", "a"]), 2);
+        assert_eq!(first.await.unwrap(), vec![true, true, false]);
+        let second = store.check_and_record("wf", "node:n1", entries, &strings(&["b", "c"]), 2);
+        assert_eq!(second.await.unwrap(), vec![false, true]);
+        // Only the latest two entries are kept, so `a` passes again.
+        let third = store.check_and_record("wf", "node:n1", entries, &strings(&["a"]), 2);
+        assert_eq!(third.await.unwrap(), vec![true]);
+
+        let latest = ProcessedDataMode::LatestIncrementalKey;
+        let ids = store.check_and_record("wf", "workflow", latest, &strings(&["3", "7"]), 0);
+        assert_eq!(ids.await.unwrap(), vec![true, true]);
+        let ids = store.check_and_record("wf", "workflow", latest, &strings(&["5", "8"]), 0);
+        assert_eq!(ids.await.unwrap(), vec![false, true]);
+
+        // Switching modes needs a cleared history.
+        let dates = ProcessedDataMode::LatestDate;
+        let today = strings(&["2026-10-15"]);
+        assert!(store.check_and_record("wf", "workflow", dates, &today, 0).await.is_err());
+        store.clear("wf", "workflow").await.unwrap();
+        let passed = store.check_and_record("wf", "workflow", dates, &today, 0).await.unwrap();
+        assert_eq!(passed, vec![true]);
+    }
+}
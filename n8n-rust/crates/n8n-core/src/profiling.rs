@@ -0,0 +1,330 @@
+//! Per-node execution profiling.
+//!
+//! With [`ProfilingConfig::enabled`] (or a workflow's `profile` setting),
+//! every finished task records a [`NodeProfile`] on its metadata under
+//! `profile`: wall time, CPU time, items and serialized bytes in and out,
+//! and retries. Profiles are saved with the execution like the rest of its
+//! task metadata.
+//!
+//! [`profile_report`] collects the profiles of a stored run in execution
+//! order with their totals; [`ProfileReport::flamegraph`] turns them into
+//! the nested `{name, value, children}` JSON flame graph viewers such as
+//! d3-flame-graph and speedscope import, with wall time in microseconds.
+//!
+//! CPU time is that of the threads polling the node, where the platform
+//! reports per-thread CPU time; work a node spawns onto other tasks is not
+//! included.
+
+use crate::run_data::output_size;
+use n8n_workflow::{GenericValue, Run, TaskData, TaskDataConnections, TaskMetadata};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Task metadata key of a task's profile.
+pub const PROFILE_KEY: &str = "profile";
+
+/// Profiling settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfilingConfig {
+    /// Profile every execution; workflows may override it.
+    pub enabled: bool,
+}
+
+/// Measurements of one node run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeProfile {
+    pub node_type: String,
+    pub wall_ms: f64,
+    /// `None` where per-thread CPU time is unavailable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_ms: Option<f64>,
+    pub items_in: usize,
+    pub items_out: usize,
+    /// Serialized JSON size of the input items.
+    pub bytes_in: usize,
+    /// Serialized JSON size of the output items.
+    pub bytes_out: usize,
+    /// Attempts after the first.
+    pub retries: u32,
+}
+
+impl NodeProfile {
+    /// Profile of `task`, run on `input`.
+    pub fn measure(
+        node_type: &str,
+        input: &TaskDataConnections,
+        task: &TaskData,
+        wall: Duration,
+        cpu: Option<Duration>,
+        retries: u32,
+    ) -> Self {
+        let mut profile = Self {
+            node_type: node_type.to_string(),
+            wall_ms: wall.as_secs_f64() * 1000.0,
+            cpu_ms: cpu.map(|cpu| cpu.as_secs_f64() * 1000.0),
+            retries,
+            ..Self::default()
+        };
+        for items in input.values().flatten() {
+            let size = output_size(items);
+            profile.items_in += size.items;
+            profile.bytes_in += size.bytes;
+        }
+        for items in task.data.iter().flat_map(|data| data.values()).flatten() {
+            let size = output_size(items);
+            profile.items_out += size.items;
+            profile.bytes_out += size.bytes;
+        }
+        profile
+    }
+
+    /// Store the profile on `task`'s metadata.
+    pub fn record(&self, task: &mut TaskData) {
+        let value = serde_json::to_value(self)
+            .and_then(serde_json::from_value)
+            .unwrap_or(GenericValue::Null);
+        task.metadata
+            .get_or_insert_with(TaskMetadata::default)
+            .custom
+            .insert(PROFILE_KEY.to_string(), value);
+    }
+
+    /// The profile recorded on `task`, if any.
+    pub fn of(task: &TaskData) -> Option<Self> {
+        let value = task.metadata.as_ref()?.custom.get(PROFILE_KEY)?;
+        serde_json::to_value(value).and_then(serde_json::from_value).ok()
+    }
+
+    fn add(&mut self, other: &NodeProfile) {
+        self.wall_ms += other.wall_ms;
+        if let Some(cpu) = other.cpu_ms {
+            self.cpu_ms = Some(self.cpu_ms.unwrap_or(0.0) + cpu);
+        }
+        self.items_in += other.items_in;
+        self.items_out += other.items_out;
+        self.bytes_in += other.bytes_in;
+        self.bytes_out += other.bytes_out;
+        self.retries += other.retries;
+    }
+}
+
+/// Profile of one run of a node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeRunProfile {
+    pub node: String,
+    pub run_index: usize,
+    /// Start of the run, Unix milliseconds.
+    pub start_time: i64,
+    #[serde(flatten)]
+    pub profile: NodeProfile,
+}
+
+/// Profiles of an execution's node runs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileReport {
+    /// Sums over all runs; `nodeType` is empty.
+    pub total: NodeProfile,
+    /// Runs in execution order.
+    pub runs: Vec<NodeRunProfile>,
+}
+
+/// A frame of a flame graph.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlameNode {
+    pub name: String,
+    /// Wall time in microseconds, children included.
+    pub value: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<FlameNode>,
+}
+
+impl ProfileReport {
+    /// Flame graph with a root frame named `root`, one frame per node (in
+    /// order of first run) and one per run of nodes that ran repeatedly.
+    pub fn flamegraph(&self, root: &str) -> FlameNode {
+        let micros = |ms: f64| (ms * 1000.0).round() as u64;
+        let mut nodes: Vec<FlameNode> = Vec::new();
+        for run in &self.runs {
+            let name = format!("{} ({})", run.node, run.profile.node_type);
+            let index = match nodes.iter().position(|n| n.name == name) {
+                Some(index) => index,
+                None => {
+                    nodes.push(FlameNode {
+                        name,
+                        value: 0,
+                        children: Vec::new(),
+                    });
+                    nodes.len() - 1
+                }
+            };
+            let node = &mut nodes[index];
+            node.value += micros(run.profile.wall_ms);
+            node.children.push(FlameNode {
+                name: format!("run {}", run.run_index),
+                value: micros(run.profile.wall_ms),
+                children: Vec::new(),
+            });
+        }
+        for node in &mut nodes {
+            if node.children.len() == 1 {
+                node.children.clear();
+            }
+        }
+        FlameNode {
+            name: root.to_string(),
+            value: nodes.iter().map(|n| n.value).sum(),
+            children: nodes,
+        }
+    }
+}
+
+/// Profiles recorded on `run`'s tasks.
+pub fn profile_report(run: &Run) -> ProfileReport {
+    let mut report = ProfileReport::default();
+    for (node, tasks) in &run.data.result_data.run_data {
+        for (run_index, task) in tasks.iter().enumerate() {
+            let Some(profile) = NodeProfile::of(task) else {
+                continue;
+            };
+            report.total.add(&profile);
+            report.runs.push(NodeRunProfile {
+                node: node.clone(),
+                run_index,
+                start_time: task.start_time,
+                profile,
+            });
+        }
+    }
+    report.runs.sort_by(|a, b| {
+        (a.start_time, &a.node, a.run_index).cmp(&(b.start_time, &b.node, b.run_index))
+    });
+    report
+}
+
+/// Future adding up the CPU time of the threads that poll it.
+pub(crate) struct CpuTimed<F> {
+    inner: Pin<Box<F>>,
+    cpu: Option<Duration>,
+}
+
+impl<F: Future> CpuTimed<F> {
+    pub(crate) fn new(inner: F) -> Self {
+        Self {
+            inner: Box::pin(inner),
+            cpu: None,
+        }
+    }
+}
+
+impl<F: Future> Future for CpuTimed<F> {
+    type Output = (F::Output, Option<Duration>);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let start = thread_cpu_time();
+        let poll = self.inner.as_mut().poll(cx);
+        if let (Some(start), Some(end)) = (start, thread_cpu_time()) {
+            self.cpu = Some(self.cpu.unwrap_or_default() + end.saturating_sub(start));
+        }
+        let cpu = self.cpu;
+        poll.map(|output| (output, cpu))
+    }
+}
+
+/// CPU time consumed by the calling thread.
+#[cfg(unix)]
+fn thread_cpu_time() -> Option<Duration> {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `time` is a valid timespec for the call to fill in.
+    let result = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) };
+    (result == 0).then(|| Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+}
+
+#[cfg(not(unix))]
+fn thread_cpu_time() -> Option<Duration> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::{connection::CONNECTION_MAIN, NodeExecutionData, WorkflowExecuteMode};
+    use std::collections::HashMap;
+
+    fn items(count: usize) -> Vec<NodeExecutionData> {
+        (0..count)
+            .map(|i| NodeExecutionData::from_json_value(serde_json::json!({ "i": i })).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_profiles_are_recorded_and_reported() {
+        let input = HashMap::from([(CONNECTION_MAIN.to_string(), vec![items(2)])]);
+        let mut run = Run::new(WorkflowExecuteMode::Manual);
+        for (start_time, node, outputs, retries) in [(10, "Fetch", 3, 2), (20, "Set", 1, 0)] {
+            let mut task =
+                TaskData::default().with_output(CONNECTION_MAIN, vec![items(outputs)]);
+            task.start_time = start_time;
+            let profile = NodeProfile::measure(
+                "test",
+                &input,
+                &task,
+                Duration::from_millis(5),
+                Some(Duration::from_millis(2)),
+                retries,
+            );
+            profile.record(&mut task);
+            run.data.result_data.run_data.entry(node.to_string()).or_default().push(task);
+        }
+        run.data.result_data.run_data.insert("Unprofiled".to_string(), vec![TaskData::default()]);
+
+        let report = profile_report(&run);
+        assert_eq!(report.runs.len(), 2);
+        assert_eq!(report.runs[0].node, "Fetch");
+        assert_eq!(report.runs[0].profile.items_in, 2);
+        assert_eq!(report.runs[0].profile.items_out, 3);
+        assert_eq!(report.runs[0].profile.bytes_out, 3 * report.runs[1].profile.bytes_out);
+        assert_eq!(report.total.items_out, 4);
+        assert_eq!(report.total.retries, 2);
+        assert_eq!(report.total.cpu_ms, Some(4.0));
+        assert!((report.total.wall_ms - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_flamegraph_groups_runs_by_node() {
+        let run = |node: &str, run_index, wall_ms| NodeRunProfile {
+            node: node.to_string(),
+            run_index,
+            start_time: 0,
+            profile: NodeProfile {
+                node_type: "t".to_string(),
+                wall_ms,
+                ..NodeProfile::default()
+            },
+        };
+        let report = ProfileReport {
+            runs: vec![run("Loop", 0, 1.0), run("Once", 0, 2.5), run("Loop", 1, 3.0)],
+            ..ProfileReport::default()
+        };
+
+        let graph = report.flamegraph("execution");
+        assert_eq!(graph.value, 6500);
+        assert_eq!(graph.children[0].name, "Loop (t)");
+        assert_eq!(graph.children[0].value, 4000);
+        assert_eq!(graph.children[0].children.len(), 2);
+        assert!(graph.children[1].children.is_empty());
+        assert_eq!(
+            serde_json::to_value(&graph.children[1]).unwrap(),
+            serde_json::json!({ "name": "Once (t)", "value": 2500 })
+        );
+    }
+}
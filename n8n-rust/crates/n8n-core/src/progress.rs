@@ -0,0 +1,305 @@
+//! Progress of in-flight executions.
+//!
+//! [`ExecutionProgress`] follows each execution the engine is running from
+//! the moment it queues for its concurrency group until it returns: the
+//! engine registers it with [`ExecutionProgress::track`] and passes its
+//! events through [`ExecutionProgress::forward`], which records the node
+//! that is running and the nodes that finished. Only executions of this
+//! process are visible.
+
+use chrono::{DateTime, Utc};
+use n8n_workflow::ExecutionStatus;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::engine::ExecutionEvent;
+
+/// A node run that finished.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletedNode {
+    pub node: String,
+    pub run_index: usize,
+    pub status: ExecutionStatus,
+    pub elapsed_ms: i64,
+    /// Items the run output.
+    pub items: usize,
+}
+
+/// What an in-flight execution is doing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InFlightExecution {
+    pub execution_id: String,
+    pub workflow_id: String,
+
+    /// `new` while waiting for its concurrency group, then `running`.
+    pub status: ExecutionStatus,
+
+    /// Place among the executions waiting for the same concurrency group,
+    /// 1 being next.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
+
+    pub queued_at: DateTime<Utc>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+
+    /// Since the execution queued.
+    pub elapsed_ms: i64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_node: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_node_elapsed_ms: Option<i64>,
+
+    /// Items output by the finished node runs.
+    pub items_processed: usize,
+
+    /// Finished node runs in the order they finished.
+    pub nodes: Vec<CompletedNode>,
+}
+
+struct Tracked {
+    workflow_id: String,
+    group: Option<String>,
+    /// Arrival order, for queue positions.
+    seq: u64,
+    queued_at: DateTime<Utc>,
+    started_at: Option<DateTime<Utc>>,
+    current: Option<(String, DateTime<Utc>)>,
+    nodes: Vec<CompletedNode>,
+}
+
+#[derive(Default)]
+struct Inner {
+    executions: HashMap<String, Tracked>,
+    next_seq: u64,
+}
+
+/// In-flight executions of an engine. Clones share the executions.
+#[derive(Clone, Default)]
+pub struct ExecutionProgress {
+    inner: Arc<RwLock<Inner>>,
+}
+
+/// Tracks an execution until dropped.
+pub struct ProgressGuard {
+    progress: ExecutionProgress,
+    execution_id: String,
+}
+
+impl Drop for ProgressGuard {
+    fn drop(&mut self) {
+        self.progress.inner.write().executions.remove(&self.execution_id);
+    }
+}
+
+impl ExecutionProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracking an execution of `workflow_id` that waits for the
+    /// concurrency group `group`, if any. It is forgotten when the guard
+    /// drops, including when the execution is aborted.
+    pub fn track(
+        &self,
+        execution_id: &str,
+        workflow_id: &str,
+        group: Option<String>,
+    ) -> ProgressGuard {
+        let mut inner = self.inner.write();
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+        inner.executions.insert(
+            execution_id.to_string(),
+            Tracked {
+                workflow_id: workflow_id.to_string(),
+                group,
+                seq,
+                queued_at: Utc::now(),
+                started_at: None,
+                current: None,
+                nodes: Vec::new(),
+            },
+        );
+        ProgressGuard {
+            progress: self.clone(),
+            execution_id: execution_id.to_string(),
+        }
+    }
+
+    /// Record `event` of a tracked execution.
+    pub fn observe(&self, execution_id: &str, event: &ExecutionEvent) {
+        let mut inner = self.inner.write();
+        let Some(tracked) = inner.executions.get_mut(execution_id) else {
+            return;
+        };
+        let now = Utc::now();
+        match event {
+            ExecutionEvent::Started { .. } => tracked.started_at = Some(now),
+            ExecutionEvent::NodeStarted { node_name, .. } => {
+                tracked.current = Some((node_name.clone(), now));
+            }
+            ExecutionEvent::NodeFinished {
+                node_name,
+                run_index,
+                task_data,
+            } => {
+                if tracked.current.as_ref().is_some_and(|(node, _)| node == node_name) {
+                    tracked.current = None;
+                }
+                tracked.nodes.push(CompletedNode {
+                    node: node_name.clone(),
+                    run_index: *run_index,
+                    status: task_data.execution_status,
+                    elapsed_ms: task_data.execution_time,
+                    items: task_data
+                        .data
+                        .as_ref()
+                        .map(|data| data.values().flatten().map(Vec::len).sum())
+                        .unwrap_or(0),
+                });
+            }
+            ExecutionEvent::Finished { .. } | ExecutionEvent::Error { .. } => {
+                tracked.current = None;
+            }
+        }
+    }
+
+    /// A sender whose events are recorded for `execution_id` and passed on
+    /// to `events`. The task ends once the sender and its clones drop.
+    pub fn forward(
+        &self,
+        execution_id: &str,
+        events: mpsc::Sender<ExecutionEvent>,
+    ) -> (mpsc::Sender<ExecutionEvent>, JoinHandle<()>) {
+        let (tx, mut rx) = mpsc::channel(events.max_capacity());
+        let progress = self.clone();
+        let execution_id = execution_id.to_string();
+        let task = tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                progress.observe(&execution_id, &event);
+                // The caller may not listen.
+                let _ = events.send(event).await;
+            }
+        });
+        (tx, task)
+    }
+
+    /// What the execution is doing, or `None` if it is not in flight here.
+    pub fn status(&self, execution_id: &str) -> Option<InFlightExecution> {
+        let inner = self.inner.read();
+        let tracked = inner.executions.get(execution_id)?;
+        let now = Utc::now();
+
+        let queue_position = match (&tracked.group, tracked.started_at) {
+            (Some(group), None) => Some(
+                inner
+                    .executions
+                    .values()
+                    .filter(|other| {
+                        other.started_at.is_none()
+                            && other.group.as_ref() == Some(group)
+                            && other.seq < tracked.seq
+                    })
+                    .count()
+                    + 1,
+            ),
+            _ => None,
+        };
+
+        Some(InFlightExecution {
+            execution_id: execution_id.to_string(),
+            workflow_id: tracked.workflow_id.clone(),
+            status: if tracked.started_at.is_some() {
+                ExecutionStatus::Running
+            } else {
+                ExecutionStatus::New
+            },
+            queue_position,
+            queued_at: tracked.queued_at,
+            started_at: tracked.started_at,
+            elapsed_ms: (now - tracked.queued_at).num_milliseconds(),
+            current_node: tracked.current.as_ref().map(|(node, _)| node.clone()),
+            current_node_elapsed_ms: tracked
+                .current
+                .as_ref()
+                .map(|(_, since)| (now - *since).num_milliseconds()),
+            items_processed: tracked.nodes.iter().map(|node| node.items).sum(),
+            nodes: tracked.nodes.clone(),
+        })
+    }
+
+    /// Number of executions in flight.
+    pub fn len(&self) -> usize {
+        self.inner.read().executions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::{NodeExecutionData, TaskData};
+
+    #[test]
+    fn test_progress_follows_events_and_queue() {
+        let progress = ExecutionProgress::new();
+        let first = progress.track("e1", "wf", Some("wf:acme".to_string()));
+        let second = progress.track("e2", "wf", Some("wf:acme".to_string()));
+        let third = progress.track("e3", "wf", Some("wf:acme".to_string()));
+
+        // e1 holds the group; e2 and e3 wait behind it.
+        progress.observe("e1", &ExecutionEvent::Started {
+            execution_id: "e1".to_string(),
+            workflow_id: "wf".to_string(),
+        });
+        assert_eq!(progress.status("e1").unwrap().queue_position, None);
+        assert_eq!(progress.status("e3").unwrap().queue_position, Some(2));
+        drop(second);
+        assert_eq!(progress.status("e3").unwrap().queue_position, Some(1));
+
+        let mut task_data = TaskData::new();
+        task_data.execution_status = ExecutionStatus::Success;
+        task_data.execution_time = 12;
+        task_data.data = Some(HashMap::from([(
+            "main".to_string(),
+            vec![vec![NodeExecutionData::default(); 3]],
+        )]));
+        progress.observe("e1", &ExecutionEvent::NodeStarted {
+            node_name: "Fetch".to_string(),
+            run_index: 0,
+        });
+        progress.observe("e1", &ExecutionEvent::NodeFinished {
+            node_name: "Fetch".to_string(),
+            run_index: 0,
+            task_data,
+        });
+        progress.observe("e1", &ExecutionEvent::NodeStarted {
+            node_name: "Transform".to_string(),
+            run_index: 0,
+        });
+
+        let status = progress.status("e1").unwrap();
+        assert_eq!(status.status, ExecutionStatus::Running);
+        assert_eq!(status.current_node.as_deref(), Some("Transform"));
+        assert_eq!(status.items_processed, 3);
+        assert_eq!(status.nodes[0].elapsed_ms, 12);
+
+        drop(first);
+        drop(third);
+        assert!(progress.status("e1").is_none());
+        assert!(progress.is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+//! Per-execution resource usage.
+//!
+//! With [`ResourceUsageConfig::enabled`], usually on queue workers, the
+//! engine measures every execution it runs and stores a [`ResourceUsage`]
+//! in the run's result metadata, so it is saved with the execution:
+//!
+//! - wall time;
+//! - CPU time of the threads polling the execution, where the platform
+//!   reports per-thread CPU time (work spawned onto other tasks is not
+//!   included);
+//! - how far the process's peak resident set size rose during the run.
+//!
+//! The peak RSS is that of the whole process: with several executions at
+//! once, a rise is charged to every execution running when it happened. An
+//! execution that stays below an earlier peak shows no rise at all, so the
+//! figure finds executions that grow a worker rather than measuring each
+//! one's memory.
+
+use crate::profiling::CpuTimed;
+use n8n_workflow::Run;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Instant;
+
+/// Result metadata key of the execution's wall time in milliseconds.
+pub const RESOURCE_WALL_MS_KEY: &str = "resourceWallMs";
+/// Result metadata key of the execution's CPU time in milliseconds.
+pub const RESOURCE_CPU_MS_KEY: &str = "resourceCpuMs";
+/// Result metadata key of the rise in peak RSS, in bytes.
+pub const RESOURCE_PEAK_RSS_DELTA_KEY: &str = "resourcePeakRssDelta";
+
+/// Resource usage settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceUsageConfig {
+    /// Measure every execution.
+    pub enabled: bool,
+}
+
+/// What one execution used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceUsage {
+    pub wall_ms: f64,
+    /// `None` where per-thread CPU time is unavailable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_ms: Option<f64>,
+    /// Rise of the process's peak RSS in bytes; `None` where the platform
+    /// does not report it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_rss_delta: Option<u64>,
+}
+
+impl ResourceUsage {
+    /// Run `future`, measuring what it uses.
+    pub async fn measure<F: Future>(future: F) -> (F::Output, Self) {
+        let rss_before = peak_rss();
+        let started = Instant::now();
+        let (output, cpu) = CpuTimed::new(future).await;
+        let usage = Self {
+            wall_ms: started.elapsed().as_secs_f64() * 1000.0,
+            cpu_ms: cpu.map(|cpu| cpu.as_secs_f64() * 1000.0),
+            peak_rss_delta: rss_before
+                .zip(peak_rss())
+                .map(|(before, after)| after.saturating_sub(before)),
+        };
+        (output, usage)
+    }
+
+    /// Store the usage in `run`'s result metadata.
+    pub fn record(&self, run: &mut Run) {
+        let metadata = run.data.result_data.metadata.get_or_insert_with(HashMap::new);
+        metadata.insert(RESOURCE_WALL_MS_KEY.to_string(), self.wall_ms.to_string());
+        if let Some(cpu_ms) = self.cpu_ms {
+            metadata.insert(RESOURCE_CPU_MS_KEY.to_string(), cpu_ms.to_string());
+        }
+        if let Some(delta) = self.peak_rss_delta {
+            metadata.insert(RESOURCE_PEAK_RSS_DELTA_KEY.to_string(), delta.to_string());
+        }
+    }
+
+    /// The usage recorded on `run`, if it was measured.
+    pub fn of(run: &Run) -> Option<Self> {
+        let metadata = run.data.result_data.metadata.as_ref()?;
+        let parse = |key: &str| metadata.get(key).and_then(|value| value.parse::<f64>().ok());
+        Some(Self {
+            wall_ms: parse(RESOURCE_WALL_MS_KEY)?,
+            cpu_ms: parse(RESOURCE_CPU_MS_KEY),
+            peak_rss_delta: metadata
+                .get(RESOURCE_PEAK_RSS_DELTA_KEY)
+                .and_then(|value| value.parse().ok()),
+        })
+    }
+}
+
+/// Peak resident set size of the process in bytes.
+#[cfg(unix)]
+fn peak_rss() -> Option<u64> {
+    // SAFETY: an all-zero rusage is valid for the call to fill in.
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    // SAFETY: `usage` is a valid rusage for the call to fill in.
+    let result = unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) };
+    // Kilobytes, except on macOS
+    let unit = if cfg!(target_os = "macos") { 1 } else { 1024 };
+    (result == 0).then(|| usage.ru_maxrss.max(0) as u64 * unit)
+}
+
+#[cfg(not(unix))]
+fn peak_rss() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::WorkflowExecuteMode;
+
+    #[tokio::test]
+    async fn test_usage_is_measured_and_recorded() {
+        let (sum, usage) = ResourceUsage::measure(async {
+            let buffer = vec![1u8; 4 * 1024 * 1024];
+            buffer.iter().map(|b| *b as u64).sum::<u64>()
+        })
+        .await;
+        assert_eq!(sum, 4 * 1024 * 1024);
+        if cfg!(target_os = "linux") {
+            assert!(usage.cpu_ms.is_some());
+            assert!(usage.peak_rss_delta.is_some());
+        }
+
+        let mut run = Run::new(WorkflowExecuteMode::Manual);
+        assert_eq!(ResourceUsage::of(&run), None);
+        usage.record(&mut run);
+        assert_eq!(ResourceUsage::of(&run), Some(usage));
+    }
+}
@@ -0,0 +1,479 @@
+//! Time-series rollups: items grouped into time buckets and aggregated.
+//!
+//! `n8n-nodes-base.timeSeriesRollup` buckets items by a timestamp field
+//! (epoch milliseconds or date strings) into minutes, hours or days of a
+//! timezone and outputs one item per non-empty bucket with its aggregates.
+//! The work is columnar: timestamps and aggregated fields become Arrow
+//! arrays, rows are sorted by bucket once, and each bucket's aggregates run
+//! as Arrow kernels over zero-copy slices.
+
+use crate::error::ExecutionEngineError;
+use crate::executor::{NodeExecutor, NodeOutput};
+use crate::runtime::RuntimeContext;
+use arrow::array::{Array, Float64Array, Int64Array, StringArray, UInt32Array};
+use arrow::compute::{self, SortOptions};
+use arrow::datatypes::{DataType, TimeUnit};
+use async_trait::async_trait;
+use chrono::{NaiveTime, Offset, TimeZone};
+use chrono_tz::Tz;
+use n8n_workflow::{
+    DataObject, GenericValue, Node, NodeExecutionData, NodeParameterValue, TaskDataConnections,
+};
+use std::collections::HashMap;
+
+/// Node type of the rollup node.
+pub const ROLLUP_NODE_TYPE: &str = "n8n-nodes-base.timeSeriesRollup";
+
+const MINUTE_MS: i64 = 60_000;
+const HOUR_MS: i64 = 60 * MINUTE_MS;
+const DAY_MS: i64 = 24 * HOUR_MS;
+
+/// Width of a bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketUnit {
+    Minute,
+    Hour,
+    Day,
+}
+
+/// Aggregate computed per bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RollupFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+    /// Linearly interpolated percentile, 0 to 100.
+    Percentile(f64),
+}
+
+/// One output field of a bucket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollupAggregate {
+    /// Field aggregated; a count without a field counts the bucket's items.
+    pub field: Option<String>,
+    pub function: RollupFunction,
+    pub output: String,
+}
+
+/// How items are bucketed and what is computed per bucket.
+#[derive(Debug, Clone)]
+pub struct RollupSpec {
+    pub timestamp_field: String,
+    pub unit: BucketUnit,
+    /// Units per bucket, counted from local midnight. Must divide a day for
+    /// minutes and hours; days always have one.
+    pub every: u32,
+    pub timezone: Tz,
+    pub aggregates: Vec<RollupAggregate>,
+}
+
+impl RollupSpec {
+    fn width_ms(&self) -> i64 {
+        let unit = match self.unit {
+            BucketUnit::Minute => MINUTE_MS,
+            BucketUnit::Hour => HOUR_MS,
+            BucketUnit::Day => DAY_MS,
+        };
+        unit * i64::from(self.every.max(1))
+    }
+
+    /// Start of the bucket holding `ms`, in epoch milliseconds.
+    fn bucket_start(&self, ms: i64) -> Option<i64> {
+        let instant = self.timezone.timestamp_millis_opt(ms).single()?;
+        let offset_ms = i64::from(instant.offset().fix().local_minus_utc()) * 1000;
+        match self.unit {
+            BucketUnit::Day => self.local_midnight(instant.date_naive()),
+            // Truncating with the instant's own offset keeps the repeated
+            // hour of a DST change in two buckets.
+            _ => {
+                let local = ms + offset_ms;
+                let day = local.div_euclid(DAY_MS) * DAY_MS;
+                let width = self.width_ms();
+                Some(day + (local - day) / width * width - offset_ms)
+            }
+        }
+    }
+
+    fn bucket_end(&self, start: i64) -> Option<i64> {
+        match self.unit {
+            BucketUnit::Day => {
+                let instant = self.timezone.timestamp_millis_opt(start).single()?;
+                self.local_midnight(instant.date_naive().succ_opt()?)
+            }
+            _ => Some(start + self.width_ms()),
+        }
+    }
+
+    fn local_midnight(&self, date: chrono::NaiveDate) -> Option<i64> {
+        let midnight = date.and_time(NaiveTime::MIN);
+        match self.timezone.from_local_datetime(&midnight).earliest() {
+            Some(start) => Some(start.timestamp_millis()),
+            // Midnight skipped by a DST change: the day starts an hour later.
+            None => self
+                .timezone
+                .from_local_datetime(&(midnight + chrono::Duration::hours(1)))
+                .earliest()
+                .map(|start| start.timestamp_millis()),
+        }
+    }
+
+    fn format(&self, ms: Option<i64>) -> GenericValue {
+        ms.and_then(|ms| self.timezone.timestamp_millis_opt(ms).single())
+            .map_or(GenericValue::Null, |at| GenericValue::String(at.to_rfc3339()))
+    }
+}
+
+/// One item per non-empty bucket, oldest first, with `bucketStart`,
+/// `bucketEnd` and the aggregates. Items without a readable timestamp are
+/// left out.
+pub fn rollup(
+    items: &[NodeExecutionData],
+    spec: &RollupSpec,
+) -> Result<Vec<NodeExecutionData>, String> {
+    let arrow_error = |e: arrow::error::ArrowError| e.to_string();
+
+    let timestamps = timestamp_column(items, &spec.timestamp_field)?;
+    let keys: Int64Array = timestamps
+        .iter()
+        .map(|ms| ms.and_then(|ms| spec.bucket_start(ms)))
+        .collect();
+    let order = compute::sort_to_indices(
+        &keys,
+        Some(SortOptions {
+            descending: false,
+            nulls_first: false,
+        }),
+        None,
+    )
+    .map_err(arrow_error)?;
+    let keys = take::<Int64Array>(&keys, &order)?;
+
+    let mut columns: HashMap<&str, Float64Array> = HashMap::new();
+    for field in spec.aggregates.iter().filter_map(|a| a.field.as_deref()) {
+        if !columns.contains_key(field) {
+            columns.insert(field, take(&numeric_column(items, field), &order)?);
+        }
+    }
+
+    let mut output = Vec::new();
+    let mut start = 0;
+    // Rows without a bucket sort last.
+    while start < keys.len() && keys.is_valid(start) {
+        let key = keys.value(start);
+        let end = (start..keys.len())
+            .find(|&row| !keys.is_valid(row) || keys.value(row) != key)
+            .unwrap_or(keys.len());
+
+        let mut json = DataObject::new();
+        json.insert("bucketStart".to_string(), spec.format(Some(key)));
+        json.insert("bucketEnd".to_string(), spec.format(spec.bucket_end(key)));
+        for aggregate in &spec.aggregates {
+            let value = match aggregate.field.as_deref().and_then(|f| columns.get(f)) {
+                Some(column) => {
+                    compute_aggregate(aggregate.function, &column.slice(start, end - start))
+                }
+                None => GenericValue::Integer((end - start) as i64),
+            };
+            json.insert(aggregate.output.clone(), value);
+        }
+        output.push(NodeExecutionData::new(json));
+        start = end;
+    }
+    Ok(output)
+}
+
+fn take<A: Array + Clone + 'static>(array: &dyn Array, order: &UInt32Array) -> Result<A, String> {
+    let taken = compute::take(array, order, None).map_err(|e| e.to_string())?;
+    taken
+        .as_any()
+        .downcast_ref::<A>()
+        .cloned()
+        .ok_or_else(|| "Unexpected array type".to_string())
+}
+
+/// Epoch milliseconds of each item. Numbers are taken as milliseconds;
+/// strings are parsed by Arrow's timestamp cast, as UTC unless they carry
+/// an offset.
+fn timestamp_column(items: &[NodeExecutionData], field: &str) -> Result<Int64Array, String> {
+    let numbers: Int64Array = items
+        .iter()
+        .map(|item| match item.json.get(field) {
+            Some(GenericValue::Integer(n)) => Some(*n),
+            Some(GenericValue::Float(f)) => Some(*f as i64),
+            _ => None,
+        })
+        .collect();
+    let strings: StringArray = items
+        .iter()
+        .map(|item| match item.json.get(field) {
+            Some(GenericValue::String(s)) => Some(s.as_str()),
+            _ => None,
+        })
+        .collect();
+    if strings.null_count() == strings.len() {
+        return Ok(numbers);
+    }
+
+    let parsed = compute::cast(&strings, &DataType::Timestamp(TimeUnit::Millisecond, None))
+        .and_then(|timestamps| compute::cast(&timestamps, &DataType::Int64))
+        .map_err(|e| e.to_string())?;
+    let parsed = parsed
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .ok_or_else(|| "Unexpected array type".to_string())?;
+    Ok(numbers.iter().zip(parsed.iter()).map(|(n, p)| n.or(p)).collect())
+}
+
+/// Values of `field` as floats; numeric strings are parsed, anything else
+/// is null.
+fn numeric_column(items: &[NodeExecutionData], field: &str) -> Float64Array {
+    items
+        .iter()
+        .map(|item| match item.json.get(field) {
+            Some(GenericValue::Integer(n)) => Some(*n as f64),
+            Some(GenericValue::Float(f)) => Some(*f),
+            Some(GenericValue::String(s)) => s.trim().parse().ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+fn compute_aggregate(function: RollupFunction, values: &Float64Array) -> GenericValue {
+    let count = values.len() - values.null_count();
+    let float = |value: Option<f64>| value.map_or(GenericValue::Null, GenericValue::Float);
+    match function {
+        RollupFunction::Count => GenericValue::Integer(count as i64),
+        RollupFunction::Sum => float(compute::sum(values)),
+        RollupFunction::Avg => float(compute::sum(values).map(|sum| sum / count as f64)),
+        RollupFunction::Min => float(compute::min(values)),
+        RollupFunction::Max => float(compute::max(values)),
+        RollupFunction::Percentile(p) => {
+            let mut sorted: Vec<f64> = values.iter().flatten().collect();
+            sorted.sort_unstable_by(f64::total_cmp);
+            float(percentile(&sorted, p))
+        }
+    }
+}
+
+fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    let last = sorted.len().checked_sub(1)?;
+    let rank = (p.clamp(0.0, 100.0) / 100.0) * last as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    Some(sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64))
+}
+
+/// Time-series rollup node - aggregates items per time bucket.
+pub struct RollupExecutor;
+
+impl RollupExecutor {
+    fn spec(node: &Node) -> Result<RollupSpec, String> {
+        let string = |key: &str| match node.parameters.get(key) {
+            Some(NodeParameterValue::String(s)) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        };
+
+        let timestamp_field = string("timestampField").ok_or("timestampField is required")?;
+        let unit = match string("interval").as_deref().unwrap_or("hour") {
+            "minute" => BucketUnit::Minute,
+            "hour" => BucketUnit::Hour,
+            "day" => BucketUnit::Day,
+            other => return Err(format!("Unknown interval '{}'", other)),
+        };
+        let every = match node.parameters.get("every") {
+            Some(NodeParameterValue::Number(n)) if *n >= 1.0 => *n as u32,
+            _ => 1,
+        };
+        let per_day = match unit {
+            BucketUnit::Minute => 1440,
+            BucketUnit::Hour => 24,
+            BucketUnit::Day => every,
+        };
+        if per_day % every != 0 {
+            return Err(format!("every must divide a day evenly, got {}", every));
+        }
+        let timezone = match string("timezone") {
+            Some(name) => name.parse().map_err(|_| format!("Unknown timezone '{}'", name))?,
+            None => Tz::UTC,
+        };
+
+        let mut aggregates = Vec::new();
+        if let Some(NodeParameterValue::Array(entries)) = node.parameters.get("aggregations") {
+            for entry in entries {
+                let NodeParameterValue::Object(entry) = entry else {
+                    continue;
+                };
+                aggregates.push(Self::aggregate(entry)?);
+            }
+        }
+        if aggregates.is_empty() {
+            aggregates.push(RollupAggregate {
+                field: None,
+                function: RollupFunction::Count,
+                output: "count".to_string(),
+            });
+        }
+
+        Ok(RollupSpec {
+            timestamp_field,
+            unit,
+            every: if unit == BucketUnit::Day { 1 } else { every },
+            timezone,
+            aggregates,
+        })
+    }
+
+    fn aggregate(entry: &HashMap<String, NodeParameterValue>) -> Result<RollupAggregate, String> {
+        let string = |key: &str| match entry.get(key) {
+            Some(NodeParameterValue::String(s)) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        };
+        let field = string("field");
+        let operation = string("operation").unwrap_or_else(|| "count".to_string());
+        let (function, name) = match operation.as_str() {
+            "count" => (RollupFunction::Count, "count".to_string()),
+            "sum" | "avg" | "min" | "max" => {
+                let function = match operation.as_str() {
+                    "sum" => RollupFunction::Sum,
+                    "avg" => RollupFunction::Avg,
+                    "min" => RollupFunction::Min,
+                    _ => RollupFunction::Max,
+                };
+                (function, operation.clone())
+            }
+            "percentile" => {
+                let p = match entry.get("percentile") {
+                    Some(NodeParameterValue::Number(p)) if (0.0..=100.0).contains(p) => *p,
+                    _ => return Err("percentile needs a percentile between 0 and 100".into()),
+                };
+                (RollupFunction::Percentile(p), format!("p{}", p))
+            }
+            other => return Err(format!("Unknown operation '{}'", other)),
+        };
+        if field.is_none() && function != RollupFunction::Count {
+            return Err(format!("{} needs a field", operation));
+        }
+
+        let output = string("outputField").unwrap_or_else(|| match &field {
+            Some(field) => format!("{}_{}", name, field),
+            None => name,
+        });
+        Ok(RollupAggregate {
+            field,
+            function,
+            output,
+        })
+    }
+}
+
+#[async_trait]
+impl NodeExecutor for RollupExecutor {
+    fn node_type(&self) -> &str {
+        ROLLUP_NODE_TYPE
+    }
+
+    async fn execute(
+        &self,
+        node: &Node,
+        input: &TaskDataConnections,
+        _context: &RuntimeContext,
+    ) -> Result<NodeOutput, ExecutionEngineError> {
+        let node_error = |message: String| ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message,
+        };
+        let spec = Self::spec(node).map_err(node_error)?;
+        let main_input = input.get("main").and_then(|v| v.first());
+        let items = main_input.cloned().unwrap_or_default();
+        Ok(vec![rollup(&items, &spec).map_err(node_error)?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn items(values: serde_json::Value) -> Vec<NodeExecutionData> {
+        let serde_json::Value::Array(values) = values else {
+            unreachable!()
+        };
+        values
+            .into_iter()
+            .map(|v| NodeExecutionData::from_json_value(v).unwrap())
+            .collect()
+    }
+
+    fn spec(unit: BucketUnit, timezone: Tz, aggregates: Vec<RollupAggregate>) -> RollupSpec {
+        RollupSpec {
+            timestamp_field: "at".to_string(),
+            unit,
+            every: 1,
+            timezone,
+            aggregates,
+        }
+    }
+
+    #[test]
+    fn test_hourly_rollup_with_aggregates() {
+        let aggregate = |function, output: &str| RollupAggregate {
+            field: Some("ms".to_string()),
+            function,
+            output: output.to_string(),
+        };
+        let input = items(json!([
+            { "at": "2026-03-01T10:59:00Z", "ms": 30 },
+            { "at": "2026-03-01T10:05:00Z", "ms": 10 },
+            { "at": "2026-03-01T09:15:00+01:00", "ms": 20.5 },
+            { "at": 1772361000000i64, "ms": 5 },
+            { "at": "not a date", "ms": 1 },
+        ]));
+        let spec = spec(
+            BucketUnit::Hour,
+            Tz::UTC,
+            vec![
+                RollupAggregate {
+                    field: None,
+                    function: RollupFunction::Count,
+                    output: "count".to_string(),
+                },
+                aggregate(RollupFunction::Avg, "avg"),
+                aggregate(RollupFunction::Max, "max"),
+                aggregate(RollupFunction::Percentile(50.0), "p50"),
+            ],
+        );
+
+        let buckets = rollup(&input, &spec).unwrap();
+        assert_eq!(buckets.len(), 2);
+        let first = &buckets[0].json;
+        assert_eq!(first["bucketStart"], GenericValue::from("2026-03-01T08:00:00+00:00"));
+        assert_eq!(first["count"], GenericValue::Integer(1));
+        let second = &buckets[1].json;
+        assert_eq!(second["bucketEnd"], GenericValue::from("2026-03-01T11:00:00+00:00"));
+        assert_eq!(second["count"], GenericValue::Integer(3));
+        assert_eq!(second["avg"], GenericValue::Float(15.0));
+        assert_eq!(second["max"], GenericValue::Float(30.0));
+        assert_eq!(second["p50"], GenericValue::Float(10.0));
+    }
+
+    #[test]
+    fn test_daily_buckets_follow_the_timezone() {
+        let input = items(json!([
+            // 23:30 on March 28 and 00:30 on March 29 in Berlin.
+            { "at": "2026-03-28T22:30:00Z" },
+            { "at": "2026-03-28T23:30:00Z" },
+        ]));
+        let count = RollupAggregate {
+            field: None,
+            function: RollupFunction::Count,
+            output: "count".to_string(),
+        };
+        let buckets = rollup(&input, &spec(BucketUnit::Day, chrono_tz::Europe::Berlin, vec![count]))
+            .unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[1].json["bucketStart"], GenericValue::from("2026-03-29T00:00:00+01:00"));
+        // DST starts that night, so the day is 23 hours long.
+        assert_eq!(buckets[1].json["bucketEnd"], GenericValue::from("2026-03-30T00:00:00+02:00"));
+    }
+}
@@ -0,0 +1,208 @@
+//! Size summaries and item pages of execution run data.
+//!
+//! Large executions carry hundreds of megabytes of items. Instead of
+//! transferring all of it, clients read a [`RunDataSummary`] (item counts
+//! and serialized sizes per node, run and output) and then fetch the items
+//! they show with [`page_items`], one offset/limit range of one output at a
+//! time.
+
+use n8n_workflow::{ExecutionStatus, NodeExecutionData, Run, TaskData};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Item count and size of one output of a node run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputSize {
+    pub items: usize,
+    /// Serialized JSON size of the items, binary data included.
+    pub bytes: usize,
+    /// Decoded size of the items' binary attachments.
+    pub binary_bytes: u64,
+}
+
+impl OutputSize {
+    fn add(&mut self, other: &OutputSize) {
+        self.items += other.items;
+        self.bytes += other.bytes;
+        self.binary_bytes += other.binary_bytes;
+    }
+}
+
+/// Sizes of one run of a node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeRunSize {
+    pub run_index: usize,
+    pub execution_status: ExecutionStatus,
+    /// Connection type → sizes per output index.
+    pub outputs: BTreeMap<String, Vec<OutputSize>>,
+}
+
+/// Sizes of an execution's run data.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunDataSummary {
+    pub total: OutputSize,
+    /// Node name → its runs.
+    pub nodes: BTreeMap<String, Vec<NodeRunSize>>,
+}
+
+/// Summarize the run data of `run`.
+pub fn summarize(run: &Run) -> RunDataSummary {
+    let mut summary = RunDataSummary::default();
+    for (node, tasks) in &run.data.result_data.run_data {
+        let runs = tasks
+            .iter()
+            .enumerate()
+            .map(|(run_index, task)| {
+                let outputs = task_outputs(task)
+                    .map(|(connection, outputs)| {
+                        let sizes: Vec<OutputSize> =
+                            outputs.iter().map(|o| output_size(o)).collect();
+                        sizes.iter().for_each(|size| summary.total.add(size));
+                        (connection.clone(), sizes)
+                    })
+                    .collect();
+                NodeRunSize {
+                    run_index,
+                    execution_status: task.execution_status,
+                    outputs,
+                }
+            })
+            .collect();
+        summary.nodes.insert(node.clone(), runs);
+    }
+    summary
+}
+
+fn task_outputs(
+    task: &TaskData,
+) -> impl Iterator<Item = (&String, &Vec<Vec<NodeExecutionData>>)> {
+    task.data.iter().flatten()
+}
+
+pub(crate) fn output_size(items: &[NodeExecutionData]) -> OutputSize {
+    let mut size = OutputSize {
+        items: items.len(),
+        ..Default::default()
+    };
+    for item in items {
+        size.bytes += serde_json::to_vec(item).map(|v| v.len()).unwrap_or(0);
+        size.binary_bytes += item
+            .binary
+            .iter()
+            .flatten()
+            .map(|(_, b)| b.bytes.unwrap_or(b.data.len() as u64 * 3 / 4))
+            .sum::<u64>();
+    }
+    size
+}
+
+/// Which output of which node run to page through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputRef<'a> {
+    pub node: &'a str,
+    pub run_index: usize,
+    pub connection_type: &'a str,
+    pub output_index: usize,
+}
+
+/// A range of the items of one output.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemPage {
+    pub node: String,
+    pub run_index: usize,
+    pub connection_type: String,
+    pub output_index: usize,
+    pub offset: usize,
+    /// Items in the output.
+    pub total: usize,
+    pub items: Vec<NodeExecutionData>,
+    /// Offset of the next page, if there are more items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
+}
+
+/// Items `offset..offset + limit` of an output, or `None` if the node has
+/// no such run. Outputs the run did not produce read as empty.
+pub fn page_items(
+    run: &Run,
+    output: &OutputRef<'_>,
+    offset: usize,
+    limit: usize,
+) -> Option<ItemPage> {
+    let task = run.data.result_data.run_data.get(output.node)?.get(output.run_index)?;
+    let items: &[NodeExecutionData] = task
+        .data
+        .as_ref()
+        .and_then(|data| data.get(output.connection_type))
+        .and_then(|outputs| outputs.get(output.output_index))
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let start = offset.min(items.len());
+    let end = start.saturating_add(limit).min(items.len());
+    Some(ItemPage {
+        node: output.node.to_string(),
+        run_index: output.run_index,
+        connection_type: output.connection_type.to_string(),
+        output_index: output.output_index,
+        offset: start,
+        total: items.len(),
+        items: items[start..end].to_vec(),
+        next_offset: (end < items.len()).then_some(end),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::WorkflowExecuteMode;
+    use std::collections::HashMap;
+
+    fn run_with_items(count: usize) -> Run {
+        let items = (0..count)
+            .map(|i| NodeExecutionData::from_json_value(serde_json::json!({ "i": i })).unwrap())
+            .collect();
+        let mut task = TaskData::default();
+        task.execution_status = ExecutionStatus::Success;
+        task.data = Some(HashMap::from([("main".to_string(), vec![items, vec![]])]));
+
+        let mut run = Run::new(WorkflowExecuteMode::Manual);
+        run.data.result_data.run_data.insert("Fetch".to_string(), vec![task]);
+        run
+    }
+
+    #[test]
+    fn test_summary_counts_items_per_output() {
+        let summary = summarize(&run_with_items(3));
+        let outputs = &summary.nodes["Fetch"][0].outputs["main"];
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].items, 3);
+        assert_eq!(outputs[1], OutputSize::default());
+        assert_eq!(summary.total.items, 3);
+        assert_eq!(summary.total.bytes, outputs[0].bytes);
+        assert!(outputs[0].bytes > 0);
+    }
+
+    #[test]
+    fn test_page_items() {
+        let run = run_with_items(5);
+        let output = OutputRef {
+            node: "Fetch",
+            run_index: 0,
+            connection_type: "main",
+            output_index: 0,
+        };
+
+        let page = page_items(&run, &output, 1, 2).unwrap();
+        assert_eq!((page.offset, page.total, page.next_offset), (1, 5, Some(3)));
+        assert_eq!(page.items[0].json["i"], n8n_workflow::GenericValue::Integer(1));
+
+        let last = page_items(&run, &output, 3, 10).unwrap();
+        assert_eq!((last.items.len(), last.next_offset), (2, None));
+        assert!(page_items(&run, &OutputRef { run_index: 1, ..output }, 0, 10).is_none());
+    }
+}
@@ -0,0 +1,239 @@
+//! Run windows: when triggers may start a workflow.
+//!
+//! A workflow's `runWindow` setting lists recurring windows (e.g. weekdays
+//! 08:00–20:00 in `Europe/Berlin`) and one-off blackouts (a deploy freeze).
+//! The timer scheduler and the webhook endpoint call [`decide`] before
+//! starting a workflow and apply the workflow's `outsideWindow` policy:
+//!
+//! - `queue`: hold the start until the window next opens;
+//! - `skip`: drop it silently;
+//! - `reject`: refuse it, telling the caller when to retry if known.
+//!
+//! Manual executions ignore run windows.
+
+use chrono::{DateTime, Datelike, Duration, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use n8n_workflow::{OutsideWindowPolicy, RunWindow, RunWindowSettings, Workflow};
+
+/// How far ahead [`next_open`] looks for the window to reopen.
+const LOOKAHEAD_DAYS: i64 = 400;
+
+/// What to do with a trigger start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowDecision {
+    /// Inside the window; run now.
+    Run,
+    /// Outside the window; run at `at`.
+    Queue { at: DateTime<Utc>, reason: String },
+    /// Outside the window; drop the start.
+    Skip { reason: String },
+    /// Outside the window; refuse the start. `retry_at` is when the window
+    /// reopens, if it does within the lookahead.
+    Reject {
+        reason: String,
+        retry_at: Option<DateTime<Utc>>,
+    },
+}
+
+impl WindowDecision {
+    pub fn is_run(&self) -> bool {
+        matches!(self, WindowDecision::Run)
+    }
+}
+
+/// Decide whether `workflow` may start at `now`. `default_timezone` is used
+/// when neither the run window nor the workflow sets one.
+pub fn decide(workflow: &Workflow, default_timezone: &str, now: DateTime<Utc>) -> WindowDecision {
+    let Some(settings) = &workflow.settings.run_window else {
+        return WindowDecision::Run;
+    };
+    let tz = timezone(settings, workflow, default_timezone);
+    let Some(reason) = closed_reason(settings, tz, now) else {
+        return WindowDecision::Run;
+    };
+
+    let opens_at = next_open(settings, tz, now);
+    match settings.outside_window {
+        OutsideWindowPolicy::Queue => match opens_at {
+            Some(at) => WindowDecision::Queue { at, reason },
+            // Never reopens: queueing would hold the start forever.
+            None => WindowDecision::Reject {
+                reason,
+                retry_at: None,
+            },
+        },
+        OutsideWindowPolicy::Skip => WindowDecision::Skip { reason },
+        OutsideWindowPolicy::Reject => WindowDecision::Reject {
+            reason,
+            retry_at: opens_at,
+        },
+    }
+}
+
+/// Why the workflow may not run at `at`, or `None` if it may.
+pub fn closed_reason(settings: &RunWindowSettings, tz: Tz, at: DateTime<Utc>) -> Option<String> {
+    if let Some(blackout) = settings.blackouts.iter().find(|b| b.start <= at && at < b.end) {
+        return Some(match &blackout.reason {
+            Some(reason) => format!("Blackout until {}: {}", blackout.end.to_rfc3339(), reason),
+            None => format!("Blackout until {}", blackout.end.to_rfc3339()),
+        });
+    }
+    if settings.windows.is_empty() {
+        return None;
+    }
+
+    let local = at.with_timezone(&tz);
+    let (day, time) = (local.weekday(), local.time());
+    if settings.windows.iter().any(|w| contains(w, day, time)) {
+        None
+    } else {
+        Some(format!("Outside run window ({} {})", day, time.format("%H:%M")))
+    }
+}
+
+/// The first instant at or after `now` when the workflow may run.
+pub fn next_open(settings: &RunWindowSettings, tz: Tz, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if closed_reason(settings, tz, now).is_none() {
+        return Some(now);
+    }
+
+    // The window can only reopen when a blackout ends or a window starts.
+    let mut candidates: Vec<DateTime<Utc>> = settings
+        .blackouts
+        .iter()
+        .map(|b| b.end)
+        .filter(|end| *end > now)
+        .collect();
+
+    let today = now.with_timezone(&tz).date_naive();
+    for offset in -1..=LOOKAHEAD_DAYS {
+        let date = today + Duration::days(offset);
+        for window in &settings.windows {
+            if !window.days.is_empty() && !window.days.contains(&date.weekday()) {
+                continue;
+            }
+            // Skipped when the opening time falls into a DST gap.
+            if let Some(start) = tz.from_local_datetime(&date.and_time(window.start)).earliest() {
+                let start = start.with_timezone(&Utc);
+                if start > now {
+                    candidates.push(start);
+                }
+            }
+        }
+    }
+
+    candidates.sort();
+    candidates
+        .into_iter()
+        .find(|at| closed_reason(settings, tz, *at).is_none())
+}
+
+/// When the run window of `workflow` next opens at or after `now`; `now`
+/// without a run window.
+pub fn reopens_at(
+    workflow: &Workflow,
+    default_timezone: &str,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let Some(settings) = &workflow.settings.run_window else {
+        return Some(now);
+    };
+    next_open(settings, timezone(settings, workflow, default_timezone), now)
+}
+
+fn contains(window: &RunWindow, day: Weekday, time: NaiveTime) -> bool {
+    let on = |d: Weekday| window.days.is_empty() || window.days.contains(&d);
+    if window.start < window.end {
+        on(day) && window.start <= time && time < window.end
+    } else if window.start > window.end {
+        // Spans midnight: the evening belongs to `day`, the early morning
+        // to the day before.
+        (on(day) && time >= window.start) || (on(day.pred()) && time < window.end)
+    } else {
+        on(day)
+    }
+}
+
+fn timezone(settings: &RunWindowSettings, workflow: &Workflow, default_timezone: &str) -> Tz {
+    let name = settings
+        .timezone
+        .as_deref()
+        .or(workflow.settings.timezone.as_deref())
+        .unwrap_or(default_timezone);
+    name.parse().unwrap_or_else(|_| {
+        tracing::warn!(workflow = %workflow.name, timezone = %name, "Unknown run window timezone, using UTC");
+        Tz::UTC
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::BlackoutPeriod;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn workflow(settings: RunWindowSettings) -> Workflow {
+        let mut workflow = Workflow::new("windowed");
+        workflow.settings.run_window = Some(settings);
+        workflow
+    }
+
+    #[test]
+    fn test_weekday_window_queues_until_monday() {
+        let wf = workflow(RunWindowSettings {
+            windows: vec![RunWindow {
+                days: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+                start: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+            }],
+            timezone: Some("Europe/Berlin".to_string()),
+            outside_window: OutsideWindowPolicy::Queue,
+            ..Default::default()
+        });
+
+        // Friday 14:00 in Berlin (UTC+2).
+        assert!(decide(&wf, "UTC", at("2026-10-16T12:00:00Z")).is_run());
+
+        // Friday 21:00 in Berlin: queued for Monday 08:00 local.
+        match decide(&wf, "UTC", at("2026-10-16T19:00:00Z")) {
+            WindowDecision::Queue { at: when, .. } => assert_eq!(when, at("2026-10-19T06:00:00Z")),
+            other => panic!("expected queue, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_blackout_and_overnight_window() {
+        let mut settings = RunWindowSettings {
+            blackouts: vec![BlackoutPeriod {
+                start: at("2026-10-16T00:00:00Z"),
+                end: at("2026-10-19T00:00:00Z"),
+                reason: Some("deploy freeze".to_string()),
+            }],
+            ..Default::default()
+        };
+        match decide(&workflow(settings.clone()), "UTC", at("2026-10-17T10:00:00Z")) {
+            WindowDecision::Reject { reason, retry_at } => {
+                assert!(reason.contains("deploy freeze"));
+                assert_eq!(retry_at, Some(at("2026-10-19T00:00:00Z")));
+            }
+            other => panic!("expected reject, got {:?}", other),
+        }
+
+        settings.blackouts.clear();
+        settings.windows.push(RunWindow {
+            days: vec![Weekday::Fri],
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(2, 0, 0).unwrap(),
+        });
+        settings.outside_window = OutsideWindowPolicy::Skip;
+        let wf = workflow(settings);
+        assert!(decide(&wf, "UTC", at("2026-10-17T01:00:00Z")).is_run());
+        assert!(matches!(
+            decide(&wf, "UTC", at("2026-10-17T03:00:00Z")),
+            WindowDecision::Skip { .. }
+        ));
+    }
+}
@@ -1,12 +1,35 @@
 //! Runtime context and configuration for workflow execution.
 
+use crate::binary_data::BinaryDataService;
+use crate::cost::CostConfig;
+use crate::delivery::WebhookDeliveries;
+use crate::determinism::{Determinism, DeterministicConfig};
+use crate::egress::{EgressConfig, EgressPolicy};
+use crate::error::ExecutionEngineError;
+use crate::execution_webhooks::ExecutionWebhooksConfig;
+use crate::expression::ExpressionLimits;
+use crate::integrity::IntegrityConfig;
+use crate::journal::JournalConfig;
+use crate::masking::MaskingConfig;
+use crate::processed_data::ProcessedData;
+use crate::profiling::ProfilingConfig;
+use crate::resource_usage::ResourceUsageConfig;
+use crate::sampling::SamplingPolicy;
+use crate::serialization::SerializationConfig;
+use crate::sub_execution::{ExecutionLineage, SubExecutionLimits};
+use crate::supply::{NodeSupplies, SuppliedData};
+use crate::throttle::Throttles;
+use crate::trigger_health::TriggerHealthConfig;
 use n8n_workflow::{ExecutionContext, WorkflowExecuteMode};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 /// Runtime configuration for the execution engine.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RuntimeConfig {
     /// Default execution timeout in seconds.
     pub default_timeout: u64,
@@ -18,6 +41,34 @@ pub struct RuntimeConfig {
     pub timezone: String,
     /// Binary data storage mode.
     pub binary_mode: BinaryStorageMode,
+    /// Which executions are saved with full data.
+    pub sampling: SamplingPolicy,
+    /// Depth and quota limits for `executeWorkflow` sub-executions.
+    pub sub_executions: SubExecutionLimits,
+    /// Per-task hash chain for tamper-evident execution records.
+    pub integrity: IntegrityConfig,
+    /// Per node-type pricing of executions.
+    pub cost: CostConfig,
+    /// Masking of secrets in errors, events and logs.
+    pub masking: MaskingConfig,
+    /// Proxies and allowed destinations of outbound HTTP requests.
+    pub egress: EgressConfig,
+    /// Per-node timing and size profiles of executions.
+    pub profiling: ProfilingConfig,
+    /// Per-execution CPU time and peak memory.
+    pub resource_usage: ResourceUsageConfig,
+    /// Write-ahead journal of side-effecting node runs.
+    pub journal: JournalConfig,
+    /// Size limits of expression results interpolated into strings.
+    pub expressions: ExpressionLimits,
+    /// Parsers and encodings of item data.
+    pub serialization: SerializationConfig,
+    /// Endpoints notified when executions start and end.
+    pub execution_webhooks: ExecutionWebhooksConfig,
+    /// Consecutive failures after which triggers are paused.
+    pub trigger_health: TriggerHealthConfig,
+    /// Frozen clock and seeded randomness, for reproducible test runs.
+    pub deterministic: DeterministicConfig,
 }
 
 impl Default for RuntimeConfig {
@@ -28,12 +79,27 @@ impl Default for RuntimeConfig {
             save_progress: true,
             timezone: "UTC".to_string(),
             binary_mode: BinaryStorageMode::Memory,
+            sampling: SamplingPolicy::default(),
+            sub_executions: SubExecutionLimits::default(),
+            integrity: IntegrityConfig::default(),
+            cost: CostConfig::default(),
+            masking: MaskingConfig::default(),
+            egress: EgressConfig::default(),
+            profiling: ProfilingConfig::default(),
+            resource_usage: ResourceUsageConfig::default(),
+            journal: JournalConfig::default(),
+            expressions: ExpressionLimits::default(),
+            serialization: SerializationConfig::default(),
+            execution_webhooks: ExecutionWebhooksConfig::default(),
+            trigger_health: TriggerHealthConfig::default(),
+            deterministic: DeterministicConfig::default(),
         }
     }
 }
 
 /// Binary data storage mode.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum BinaryStorageMode {
     Memory,
     FileSystem,
@@ -47,6 +113,26 @@ pub struct RuntimeContext {
     pub execution_context: ExecutionContext,
     /// Runtime configuration.
     pub config: RuntimeConfig,
+    /// Chain of executions that led to this one.
+    pub lineage: ExecutionLineage,
+    /// Egress policy of the workflow's project.
+    egress: Arc<EgressPolicy>,
+    /// Workflow being executed.
+    workflow_id: Option<String>,
+    /// Where webhook delivery nodes record their deliveries.
+    deliveries: WebhookDeliveries,
+    /// Slots of throttle nodes.
+    throttles: Throttles,
+    /// Store of binary data moved out of items.
+    binary_data: BinaryDataService,
+    /// Values deduplicated across executions.
+    processed_data: ProcessedData,
+    /// Clock and random source of expressions and nodes.
+    determinism: Determinism,
+    /// Variables expressions read through `$vars`.
+    variables: Arc<HashMap<String, serde_json::Value>>,
+    /// Sub-nodes of the running node.
+    supplies: Option<NodeSupplies>,
     /// Shared state storage.
     state: Arc<RwLock<HashMap<String, serde_json::Value>>>,
     /// Cancellation token.
@@ -56,14 +142,145 @@ pub struct RuntimeContext {
 impl RuntimeContext {
     /// Create a new runtime context.
     pub fn new(mode: WorkflowExecuteMode, config: RuntimeConfig) -> Self {
+        let egress = Self::compile_egress(&config.egress, None);
+        let determinism = Determinism::new(&config.deterministic);
         Self {
             execution_context: ExecutionContext::new(mode),
             config,
+            lineage: ExecutionLineage::default(),
+            egress,
+            workflow_id: None,
+            deliveries: WebhookDeliveries::default(),
+            throttles: Throttles::default(),
+            binary_data: BinaryDataService::default(),
+            processed_data: ProcessedData::default(),
+            determinism,
+            variables: Arc::default(),
+            supplies: None,
             state: Arc::new(RwLock::new(HashMap::new())),
             cancel_token: tokio_util::sync::CancellationToken::new(),
         }
     }
 
+    /// Set the execution lineage.
+    pub fn with_lineage(mut self, lineage: ExecutionLineage) -> Self {
+        self.execution_context.parent_execution_id =
+            lineage.parent().map(|p| p.execution_id.clone());
+        self.lineage = lineage;
+        self
+    }
+
+    /// Apply the egress rules of `project` on top of the global ones.
+    pub fn with_project(mut self, project: Option<&str>) -> Self {
+        self.egress = Self::compile_egress(&self.config.egress, project);
+        self
+    }
+
+    /// Set the workflow being executed.
+    pub fn with_workflow_id(mut self, workflow_id: &str) -> Self {
+        self.workflow_id = Some(workflow_id.to_string());
+        self
+    }
+
+    /// Record webhook deliveries in `deliveries`.
+    pub fn with_deliveries(mut self, deliveries: WebhookDeliveries) -> Self {
+        self.deliveries = deliveries;
+        self
+    }
+
+    /// Take throttle node slots from `throttles`.
+    pub fn with_throttles(mut self, throttles: Throttles) -> Self {
+        self.throttles = throttles;
+        self
+    }
+
+    /// Read stored binary data from `binary_data`.
+    pub fn with_binary_data(mut self, binary_data: BinaryDataService) -> Self {
+        self.binary_data = binary_data;
+        self
+    }
+
+    /// Deduplicate across executions with `processed_data`.
+    pub fn with_processed_data(mut self, processed_data: ProcessedData) -> Self {
+        self.processed_data = processed_data;
+        self
+    }
+
+    /// Resolve the running node's sub-nodes from `supplies`.
+    pub fn with_supplies(mut self, supplies: NodeSupplies) -> Self {
+        self.supplies = Some(supplies);
+        self
+    }
+
+    /// Set the variables of the workflow's project.
+    pub fn with_variables(mut self, variables: HashMap<String, serde_json::Value>) -> Self {
+        self.variables = Arc::new(variables);
+        self
+    }
+
+    /// Rules that fail to compile refuse every destination.
+    fn compile_egress(config: &EgressConfig, project: Option<&str>) -> Arc<EgressPolicy> {
+        Arc::new(config.policy(project).unwrap_or_else(EgressPolicy::refusing))
+    }
+
+    /// Egress policy outbound requests are checked against.
+    pub fn egress(&self) -> &Arc<EgressPolicy> {
+        &self.egress
+    }
+
+    pub fn workflow_id(&self) -> Option<&str> {
+        self.workflow_id.as_deref()
+    }
+
+    pub fn deliveries(&self) -> &WebhookDeliveries {
+        &self.deliveries
+    }
+
+    /// Clock and random source; frozen and seeded per execution under
+    /// `RuntimeConfig::deterministic`.
+    pub fn determinism(&self) -> &Determinism {
+        &self.determinism
+    }
+
+    /// Variables of the execution, the same for all of its nodes.
+    pub fn variables(&self) -> &HashMap<String, serde_json::Value> {
+        &self.variables
+    }
+
+    pub fn throttles(&self) -> &Throttles {
+        &self.throttles
+    }
+
+    pub fn binary_data(&self) -> &BinaryDataService {
+        &self.binary_data
+    }
+
+    pub fn processed_data(&self) -> &ProcessedData {
+        &self.processed_data
+    }
+
+    /// What the sub-nodes attached to the running node through supply
+    /// connection `connection_type` supply, resolved now; empty without
+    /// sub-nodes.
+    pub async fn input_connection_data(
+        &self,
+        connection_type: &str,
+    ) -> Result<Vec<SuppliedData>, ExecutionEngineError> {
+        match &self.supplies {
+            Some(supplies) => supplies.resolve(connection_type, self).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// HTTP client for node requests, enforcing the egress policy.
+    pub fn http_client(&self, timeout: Duration) -> Result<reqwest::Client, ExecutionEngineError> {
+        self.egress
+            .client_builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| ExecutionEngineError::Internal(format!("Failed to build HTTP client: {}", e)))
+    }
+
     /// Get a value from shared state.
     pub async fn get_state(&self, key: &str) -> Option<serde_json::Value> {
         self.state.read().await.get(key).cloned()
@@ -0,0 +1,227 @@
+//! Execution sampling and adaptive data saving.
+//!
+//! Saving the full data of every successful execution is wasteful for
+//! high-volume workflows. A [`SamplingPolicy`] decides, per execution,
+//! whether the run is stored with its full node data or only its metadata
+//! (status, timings, errors):
+//!
+//! - failures are always saved in full (unless disabled);
+//! - `success_percent` of successful executions are saved in full;
+//! - flagged workflows (`alwaysSaveData`) are always saved in full.
+//!
+//! The server-wide default lives in [`RuntimeConfig::sampling`]; workflows
+//! override it through their settings (`saveDataSuccessExecution`,
+//! `saveDataErrorExecution`, `saveDataSuccessSamplePercent`,
+//! `alwaysSaveData`).
+//!
+//! [`RuntimeConfig::sampling`]: crate::runtime::RuntimeConfig::sampling
+
+use n8n_workflow::{Run, SaveDataOption, WorkflowSettings};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Metadata key recording how much of a run was stored.
+pub const DATA_RETENTION_KEY: &str = "dataRetention";
+
+/// How much of a run is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataRetention {
+    /// Node input/output data is kept.
+    Full,
+    /// Only status, timings and errors are kept.
+    Metadata,
+}
+
+impl DataRetention {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataRetention::Full => "full",
+            DataRetention::Metadata => "metadata",
+        }
+    }
+}
+
+/// Policy deciding which executions keep their full data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SamplingPolicy {
+    /// Percentage (0–100) of successful executions saved with full data.
+    pub success_percent: u32,
+    /// Save full data for failed, crashed and canceled executions.
+    pub save_failures: bool,
+    /// Save full data for every execution.
+    pub always: bool,
+}
+
+impl Default for SamplingPolicy {
+    fn default() -> Self {
+        Self {
+            success_percent: 100,
+            save_failures: true,
+            always: false,
+        }
+    }
+}
+
+impl SamplingPolicy {
+    /// Resolve the policy for a workflow, applying its settings on top of
+    /// this (server-wide) policy.
+    pub fn for_workflow(&self, settings: &WorkflowSettings) -> SamplingPolicy {
+        let success_percent = match settings.save_data_success_execution {
+            Some(SaveDataOption::None) => 0,
+            _ => settings
+                .save_data_success_sample_percent
+                .unwrap_or(self.success_percent),
+        };
+        let save_failures = match settings.save_data_error_execution {
+            Some(SaveDataOption::All) => true,
+            Some(SaveDataOption::None) => false,
+            None => self.save_failures,
+        };
+
+        SamplingPolicy {
+            success_percent: success_percent.min(100),
+            save_failures,
+            always: self.always || settings.always_save_data.unwrap_or(false),
+        }
+    }
+
+    /// Decide how much of a finished run to store.
+    ///
+    /// Sampling is keyed on the execution id, so the decision is stable
+    /// when the same execution is saved more than once.
+    pub fn decide(&self, execution_id: &str, run: &Run) -> DataRetention {
+        let full = if self.always {
+            true
+        } else if run.has_error() {
+            self.save_failures
+        } else {
+            sample_bucket(execution_id) < self.success_percent
+        };
+
+        if full {
+            DataRetention::Full
+        } else {
+            DataRetention::Metadata
+        }
+    }
+
+    /// The run as it should be stored: borrowed when kept in full,
+    /// otherwise a copy with node data removed.
+    pub fn apply<'a>(&self, execution_id: &str, run: &'a Run) -> Cow<'a, Run> {
+        match self.decide(execution_id, run) {
+            DataRetention::Full => Cow::Borrowed(run),
+            DataRetention::Metadata => {
+                let mut stripped = run.clone();
+                strip_data(&mut stripped);
+                Cow::Owned(stripped)
+            }
+        }
+    }
+}
+
+/// Remove node input/output data from a run, keeping status, timings,
+/// errors and the execution order.
+pub fn strip_data(run: &mut Run) {
+    let result = &mut run.data.result_data;
+    for tasks in result.run_data.values_mut() {
+        for task in tasks {
+            task.data = None;
+            task.input_override = None;
+        }
+    }
+    result.pin_data = None;
+    result
+        .metadata
+        .get_or_insert_with(Default::default)
+        .insert(DATA_RETENTION_KEY.to_string(), DataRetention::Metadata.as_str().to_string());
+    run.data.execution_data = None;
+}
+
+/// Map an execution id onto 0..100.
+fn sample_bucket(execution_id: &str) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    execution_id.hash(&mut hasher);
+    (hasher.finish() % 100) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::{ExecutionStatus, TaskData, WorkflowExecuteMode};
+
+    fn run(status: ExecutionStatus) -> Run {
+        let mut run = Run::new(WorkflowExecuteMode::Trigger);
+        let mut task = TaskData::new();
+        task.data = Some(Default::default());
+        run.data.result_data.run_data.insert("Node".into(), vec![task]);
+        run.finish(status);
+        run
+    }
+
+    #[test]
+    fn test_failures_always_saved_when_successes_sampled_out() {
+        let policy = SamplingPolicy {
+            success_percent: 0,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.decide("a", &run(ExecutionStatus::Error)), DataRetention::Full);
+        let success = run(ExecutionStatus::Success);
+        assert_eq!(policy.decide("a", &success), DataRetention::Metadata);
+
+        let stored = policy.apply("a", &success);
+        assert!(stored.data.result_data.run_data["Node"][0].data.is_none());
+        assert_eq!(stored.status, ExecutionStatus::Success);
+        assert_eq!(
+            stored.data.result_data.metadata.as_ref().unwrap()[DATA_RETENTION_KEY],
+            "metadata"
+        );
+    }
+
+    #[test]
+    fn test_workflow_settings_override() {
+        let server = SamplingPolicy {
+            success_percent: 10,
+            ..Default::default()
+        };
+
+        let flagged = WorkflowSettings {
+            always_save_data: Some(true),
+            save_data_error_execution: Some(SaveDataOption::None),
+            ..Default::default()
+        };
+        let policy = server.for_workflow(&flagged);
+        assert_eq!(policy.decide("a", &run(ExecutionStatus::Error)), DataRetention::Full);
+        assert_eq!(policy.decide("b", &run(ExecutionStatus::Success)), DataRetention::Full);
+
+        let full_successes = WorkflowSettings {
+            save_data_success_sample_percent: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(server.for_workflow(&full_successes).success_percent, 100);
+
+        let no_successes = WorkflowSettings {
+            save_data_success_execution: Some(SaveDataOption::None),
+            save_data_success_sample_percent: Some(50),
+            ..Default::default()
+        };
+        assert_eq!(server.for_workflow(&no_successes).success_percent, 0);
+    }
+
+    #[test]
+    fn test_sampling_rate() {
+        let policy = SamplingPolicy {
+            success_percent: 25,
+            ..Default::default()
+        };
+        let success = run(ExecutionStatus::Success);
+        let full = (0..1000)
+            .filter(|i| policy.decide(&format!("exec-{}", i), &success) == DataRetention::Full)
+            .count();
+        assert!((150..350).contains(&full), "sampled {} of 1000", full);
+    }
+}
@@ -0,0 +1,767 @@
+//! Schedule previews: when schedule triggers plan to start a workflow.
+//!
+//! A `n8n-nodes-base.scheduleTrigger` node fires on its `cronExpression`
+//! parameter or, like n8n's node, on the rules of `rule.interval`, each of
+//! which is read as a cron schedule:
+//!
+//! | rule             | cron                             |
+//! |------------------|----------------------------------|
+//! | `seconds`        | `*/n * * * * *`                  |
+//! | `minutes`        | `0 */n * * * *`                  |
+//! | `hours`          | `0 m */n * * *`                  |
+//! | `days`           | `0 m h * * *`, every n days      |
+//! | `weeks`          | `0 m h * * d,...`, every n weeks |
+//! | `months`         | `0 m h dom */n *`                |
+//! | `cronExpression` | the expression                   |
+//!
+//! n8n picks a random second (and minute or hour when unset) to spread
+//! load; previews use 0. Day and week intervals count from the first
+//! planned run.
+//!
+//! [`preview`] merges the next runs of a workflow's enabled schedule
+//! triggers in the workflow timezone and applies its run window: a run in a
+//! closed window moves to the opening with `queue` and is dropped with
+//! `skip` and `reject`.
+//!
+//! A trigger's `options` ([`schedule_options`]) add a random delay of up
+//! to `jitterSeconds`, `sliding` alignment, which starts each run one
+//! interval after the previous one finished, and `skipIfRunning`. They are
+//! applied by [`crate::schedule_trigger`]; previews show the clock times
+//! without jitter.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use n8n_workflow::{Node, NodeParameterValue, Workflow};
+use serde::Serialize;
+
+use crate::run_window::{self, WindowDecision};
+
+/// Node type of the schedule trigger.
+pub const SCHEDULE_TRIGGER_NODE_TYPE: &str = "n8n-nodes-base.scheduleTrigger";
+
+/// How far ahead [`CronSchedule::next_after`] looks for a match.
+const LOOKAHEAD_DAYS: i64 = 366 * 4 + 1;
+
+/// Most runs a preview lists.
+pub const MAX_PREVIEW_RUNS: usize = 100;
+
+/// Candidate runs a preview looks at before giving up, e.g. when a run
+/// window never opens at the scheduled times.
+const MAX_CANDIDATES: usize = 10_000;
+
+const MONTH_NAMES: [&str; 12] =
+    ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+const DAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Schedule errors.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("Invalid cron expression '{expression}': {message}")]
+    InvalidCron { expression: String, message: String },
+
+    #[error("Invalid schedule rule: {0}")]
+    InvalidRule(String),
+}
+
+/// A parsed cron expression: five fields, or six with leading seconds.
+///
+/// Fields take `*`, `?`, values, `a-b` ranges, `/n` steps, comma lists and
+/// `JAN`/`MON` names; day of week 0 and 7 are Sunday. When both day of
+/// month and day of week are restricted, a day matching either fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = ScheduleError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let invalid = |message: String| ScheduleError::InvalidCron {
+            expression: expression.to_string(),
+            message,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let (seconds, rest) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            n => return Err(invalid(format!("expected 5 or 6 fields, got {}", n))),
+        };
+
+        let days_of_week = parse_field(rest[4], 0, 7, &DAY_NAMES, 0).map_err(invalid)?;
+        Ok(Self {
+            seconds: parse_field(seconds, 0, 59, &[], 0).map_err(invalid)?,
+            minutes: parse_field(rest[0], 0, 59, &[], 0).map_err(invalid)?,
+            hours: parse_field(rest[1], 0, 23, &[], 0).map_err(invalid)?,
+            days_of_month: parse_field(rest[2], 1, 31, &[], 0).map_err(invalid)?,
+            months: parse_field(rest[3], 1, 12, &MONTH_NAMES, 1).map_err(invalid)?,
+            // 7 is Sunday too.
+            days_of_week: (days_of_week | (days_of_week >> 7)) & 0x7f,
+            dom_restricted: !is_wildcard(rest[2]),
+            dow_restricted: !is_wildcard(rest[4]),
+        })
+    }
+}
+
+impl CronSchedule {
+    /// The first match after `after` in `tz`, if one falls within about
+    /// four years. Local times in a DST gap are skipped; repeated ones fire
+    /// once, at the earlier instant.
+    pub fn next_after(&self, tz: Tz, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let today = after.with_timezone(&tz).date_naive();
+        for offset in 0..=LOOKAHEAD_DAYS {
+            let date = today + Duration::days(offset);
+            if !self.matches_day(date) {
+                continue;
+            }
+            for hour in bits(self.hours) {
+                for minute in bits(self.minutes) {
+                    for second in bits(self.seconds) {
+                        let Some(local) = date.and_hms_opt(hour, minute, second) else {
+                            continue;
+                        };
+                        let Some(at) = tz.from_local_datetime(&local).earliest() else {
+                            continue;
+                        };
+                        let at = at.with_timezone(&Utc);
+                        if at > after {
+                            return Some(at);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.dom_restricted && self.dow_restricted {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+}
+
+fn is_wildcard(field: &str) -> bool {
+    field == "*" || field == "?"
+}
+
+/// Bit set of the values `field` selects; `names[i]` stands for `i + offset`.
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    offset: u32,
+) -> Result<u64, String> {
+    let value = |s: &str| -> Result<u32, String> {
+        let n = match names.iter().position(|name| name.eq_ignore_ascii_case(s)) {
+            Some(i) => i as u32 + offset,
+            None => s.parse().map_err(|_| format!("'{}' is not a value", s))?,
+        };
+        if n < min || n > max {
+            return Err(format!("{} is outside {}-{}", n, min, max));
+        }
+        Ok(n)
+    };
+
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, Some(step)),
+                _ => return Err(format!("'{}' is not a step", step)),
+            },
+            None => (part, None),
+        };
+        let (start, end) = if is_wildcard(range) {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            // `5/15` runs from 5 to the end.
+            (start, if step.is_some() { max } else { start })
+        };
+        if start > end {
+            return Err(format!("range '{}' is reversed", range));
+        }
+        for n in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            set |= 1 << n;
+        }
+    }
+    Ok(set)
+}
+
+fn bits(set: u64) -> impl Iterator<Item = u32> {
+    (0..64).filter(move |n| set & (1 << n) != 0)
+}
+
+/// Only every n-th day or week of a rule's matches fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recurrence {
+    Days(u32),
+    Weeks(u32),
+}
+
+/// One rule of a schedule trigger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleRule {
+    pub cron: CronSchedule,
+    pub recurrence: Option<Recurrence>,
+    /// Time between runs of `seconds`, `minutes` and `hours` rules.
+    pub period: Option<Duration>,
+}
+
+impl ScheduleRule {
+    fn cron(expression: &str) -> Result<Self, ScheduleError> {
+        Ok(Self {
+            cron: expression.parse()?,
+            recurrence: None,
+            period: None,
+        })
+    }
+
+    fn every(expression: &str, period: Duration) -> Result<Self, ScheduleError> {
+        Ok(Self {
+            period: Some(period),
+            ..Self::cron(expression)?
+        })
+    }
+
+    /// The first run after `after` in `tz`. `anchor` is the local date of
+    /// the rule's first run, which recurrences count from; it is set on the
+    /// first call.
+    pub fn next_after(
+        &self,
+        tz: Tz,
+        after: DateTime<Utc>,
+        anchor: &mut Option<NaiveDate>,
+    ) -> Option<DateTime<Utc>> {
+        let mut after = after;
+        loop {
+            let at = self.cron.next_after(tz, after)?;
+            let date = at.with_timezone(&tz).date_naive();
+            let anchor = *anchor.get_or_insert(date);
+            let fires = match self.recurrence {
+                None => true,
+                Some(Recurrence::Days(n)) => (date - anchor).num_days() % n as i64 == 0,
+                Some(Recurrence::Weeks(n)) => {
+                    let monday = |d: NaiveDate| {
+                        d - Duration::days(d.weekday().num_days_from_monday() as i64)
+                    };
+                    ((monday(date) - monday(anchor)).num_days() / 7) % n as i64 == 0
+                }
+            };
+            if fires {
+                return Some(at);
+            }
+            after = at;
+        }
+    }
+}
+
+/// Where a schedule trigger's runs fall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Alignment {
+    /// At the clock times the rules select.
+    #[default]
+    Clock,
+    /// One rule period after the previous run finished, so runs never
+    /// overlap and slow runs push the next ones back. Needs `seconds`,
+    /// `minutes` or `hours` rules.
+    Sliding,
+}
+
+/// `options` of a schedule trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleOptions {
+    /// Runs start up to this many seconds late, at random, so workflows on
+    /// the same schedule do not all start at once.
+    pub jitter_secs: u64,
+    pub alignment: Alignment,
+    /// Skip a run while the trigger's previous run is still executing.
+    pub skip_if_running: bool,
+}
+
+/// Longest jitter a schedule trigger may ask for.
+pub const MAX_JITTER_SECS: u64 = 24 * 60 * 60;
+
+/// The `options` of `node`, a schedule trigger: `jitterSeconds`,
+/// `alignment` (`clock` or `sliding`) and `skipIfRunning`.
+pub fn schedule_options(node: &Node) -> Result<ScheduleOptions, ScheduleError> {
+    let options = match node.parameters.get("options") {
+        Some(NodeParameterValue::Object(options)) => options,
+        None => return Ok(ScheduleOptions::default()),
+        Some(_) => return Err(ScheduleError::InvalidRule("options is not an object".to_string())),
+    };
+    let jitter_secs = match options.get("jitterSeconds") {
+        None => 0,
+        Some(NodeParameterValue::Number(n))
+            if *n >= 0.0 && n.fract() == 0.0 && *n as u64 <= MAX_JITTER_SECS =>
+        {
+            *n as u64
+        }
+        Some(_) => {
+            return Err(ScheduleError::InvalidRule(format!(
+                "jitterSeconds must be a whole number from 0 to {}",
+                MAX_JITTER_SECS
+            )))
+        }
+    };
+    let alignment = match options.get("alignment") {
+        None => Alignment::Clock,
+        Some(NodeParameterValue::String(s)) if s == "clock" => Alignment::Clock,
+        Some(NodeParameterValue::String(s)) if s == "sliding" => Alignment::Sliding,
+        Some(_) => {
+            return Err(ScheduleError::InvalidRule(
+                "alignment must be 'clock' or 'sliding'".to_string(),
+            ))
+        }
+    };
+    let skip_if_running = match options.get("skipIfRunning") {
+        None => false,
+        Some(NodeParameterValue::Boolean(skip)) => *skip,
+        Some(_) => {
+            return Err(ScheduleError::InvalidRule("skipIfRunning is not a boolean".to_string()))
+        }
+    };
+    if alignment == Alignment::Sliding
+        && schedule_rules(node)?.iter().any(|rule| rule.period.is_none())
+    {
+        return Err(ScheduleError::InvalidRule(
+            "sliding alignment needs seconds, minutes or hours rules".to_string(),
+        ));
+    }
+    Ok(ScheduleOptions {
+        jitter_secs,
+        alignment,
+        skip_if_running,
+    })
+}
+
+/// The rules `node`, a schedule trigger, fires on.
+pub fn schedule_rules(node: &Node) -> Result<Vec<ScheduleRule>, ScheduleError> {
+    match node.parameters.get("cronExpression") {
+        Some(NodeParameterValue::String(expression)) if !expression.trim().is_empty() => {
+            return Ok(vec![ScheduleRule::cron(expression)?]);
+        }
+        Some(NodeParameterValue::Expression(_)) => {
+            return Err(ScheduleError::InvalidRule(
+                "expressions in cronExpression are not previewed".to_string(),
+            ));
+        }
+        _ => {}
+    }
+
+    let intervals = match node.parameters.get("rule") {
+        Some(NodeParameterValue::Object(rule)) => match rule.get("interval") {
+            Some(NodeParameterValue::Array(intervals)) => intervals.clone(),
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+    if intervals.is_empty() {
+        // n8n's default rule: every day at midnight.
+        return Ok(vec![interval_rule(&Default::default())?]);
+    }
+
+    intervals
+        .iter()
+        .map(|interval| match interval {
+            NodeParameterValue::Object(interval) => interval_rule(interval),
+            _ => Err(ScheduleError::InvalidRule("interval is not an object".to_string())),
+        })
+        .collect()
+}
+
+fn interval_rule(
+    interval: &std::collections::HashMap<String, NodeParameterValue>,
+) -> Result<ScheduleRule, ScheduleError> {
+    let number = |key: &str, default: u32| -> Result<u32, ScheduleError> {
+        match interval.get(key) {
+            None => Ok(default),
+            Some(NodeParameterValue::Number(n)) if *n >= 0.0 && n.fract() == 0.0 => Ok(*n as u32),
+            Some(NodeParameterValue::String(s)) => s
+                .parse()
+                .map_err(|_| ScheduleError::InvalidRule(format!("{} is not a number", key))),
+            Some(_) => Err(ScheduleError::InvalidRule(format!("{} is not a number", key))),
+        }
+    };
+    let every = |key: &str| -> Result<u32, ScheduleError> {
+        match number(key, 1)? {
+            0 => Err(ScheduleError::InvalidRule(format!("{} must be at least 1", key))),
+            n => Ok(n),
+        }
+    };
+    let minute = number("triggerAtMinute", 0)?;
+    let hour = number("triggerAtHour", 0)?;
+
+    let field = match interval.get("field") {
+        Some(NodeParameterValue::String(field)) => field.as_str(),
+        None => "days",
+        Some(_) => return Err(ScheduleError::InvalidRule("field is not a string".to_string())),
+    };
+    let rule = match field {
+        "seconds" => {
+            let n = every("secondsInterval")?;
+            ScheduleRule::every(&format!("*/{} * * * * *", n), Duration::seconds(n.into()))?
+        }
+        "minutes" => {
+            let n = every("minutesInterval")?;
+            ScheduleRule::every(&format!("0 */{} * * * *", n), Duration::minutes(n.into()))?
+        }
+        "hours" => {
+            let n = every("hoursInterval")?;
+            ScheduleRule::every(&format!("0 {} */{} * * *", minute, n), Duration::hours(n.into()))?
+        }
+        "days" => ScheduleRule {
+            cron: format!("0 {} {} * * *", minute, hour).parse()?,
+            recurrence: Some(Recurrence::Days(every("daysInterval")?)),
+            period: None,
+        },
+        "weeks" => {
+            let days = match interval.get("triggerAtDay") {
+                Some(NodeParameterValue::Array(days)) if !days.is_empty() => days
+                    .iter()
+                    .map(|day| match day {
+                        NodeParameterValue::Number(n) => Ok(n.to_string()),
+                        NodeParameterValue::String(s) => Ok(s.clone()),
+                        _ => Err(ScheduleError::InvalidRule(
+                            "triggerAtDay is not a list of days".to_string(),
+                        )),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(","),
+                _ => "*".to_string(),
+            };
+            ScheduleRule {
+                cron: format!("0 {} {} * * {}", minute, hour, days).parse()?,
+                recurrence: Some(Recurrence::Weeks(every("weeksInterval")?)),
+                period: None,
+            }
+        }
+        "months" => ScheduleRule::cron(&format!(
+            "0 {} {} {} */{} *",
+            minute,
+            hour,
+            number("triggerAtDayOfMonth", 1)?,
+            every("monthsInterval")?
+        ))?,
+        "cronExpression" => match interval.get("expression") {
+            Some(NodeParameterValue::String(expression)) => ScheduleRule::cron(expression)?,
+            _ => {
+                return Err(ScheduleError::InvalidRule(
+                    "cronExpression rule has no expression".to_string(),
+                ))
+            }
+        },
+        other => return Err(ScheduleError::InvalidRule(format!("unknown field '{}'", other))),
+    };
+    Ok(rule)
+}
+
+/// A planned start of a workflow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedRun {
+    /// Schedule trigger that fires.
+    pub node: String,
+
+    pub at: DateTime<Utc>,
+
+    /// When the trigger fires, if the run window holds the run until `at`.
+    /// Runs held until the same opening are listed once.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deferred_from: Option<DateTime<Utc>>,
+}
+
+/// A schedule trigger that could not be previewed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidSchedule {
+    pub node: String,
+    pub message: String,
+}
+
+/// The next planned runs of a workflow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchedulePreview {
+    /// Timezone the schedules are read in.
+    pub timezone: String,
+
+    /// Earliest first.
+    pub next_runs: Vec<PlannedRun>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub invalid: Vec<InvalidSchedule>,
+}
+
+/// Whether `workflow` has an enabled schedule trigger.
+pub fn has_schedule(workflow: &Workflow) -> bool {
+    workflow
+        .nodes
+        .iter()
+        .any(|node| !node.disabled && node.node_type == SCHEDULE_TRIGGER_NODE_TYPE)
+}
+
+struct Cursor<'a> {
+    node: &'a str,
+    rule: ScheduleRule,
+    next: Option<DateTime<Utc>>,
+    /// Local date of the first run, which recurrences count from.
+    anchor: Option<NaiveDate>,
+}
+
+impl Cursor<'_> {
+    fn advance(&mut self, tz: Tz, after: DateTime<Utc>) {
+        self.next = self.rule.next_after(tz, after, &mut self.anchor);
+    }
+}
+
+/// Up to `count` runs of `workflow`'s schedule triggers after `from`, or
+/// `None` without an enabled schedule trigger. `default_timezone` is used
+/// when the workflow sets none.
+pub fn preview(
+    workflow: &Workflow,
+    default_timezone: &str,
+    from: DateTime<Utc>,
+    count: usize,
+) -> Option<SchedulePreview> {
+    if !has_schedule(workflow) {
+        return None;
+    }
+    let name = workflow.settings.timezone.as_deref().unwrap_or(default_timezone);
+    let tz: Tz = name.parse().unwrap_or_else(|_| {
+        tracing::warn!(workflow = %workflow.name, timezone = %name, "Unknown timezone, using UTC");
+        Tz::UTC
+    });
+
+    let mut cursors = Vec::new();
+    let mut invalid = Vec::new();
+    for node in &workflow.nodes {
+        if node.disabled || node.node_type != SCHEDULE_TRIGGER_NODE_TYPE {
+            continue;
+        }
+        match schedule_rules(node) {
+            Ok(rules) => cursors.extend(rules.into_iter().map(|rule| Cursor {
+                node: &node.name,
+                rule,
+                next: None,
+                anchor: None,
+            })),
+            Err(e) => invalid.push(InvalidSchedule {
+                node: node.name.clone(),
+                message: e.to_string(),
+            }),
+        }
+    }
+    for cursor in &mut cursors {
+        cursor.advance(tz, from);
+    }
+
+    let mut next_runs: Vec<PlannedRun> = Vec::new();
+    let count = count.min(MAX_PREVIEW_RUNS);
+    for _ in 0..MAX_CANDIDATES {
+        if next_runs.len() >= count {
+            break;
+        }
+        let Some(cursor) = cursors
+            .iter_mut()
+            .filter(|cursor| cursor.next.is_some())
+            .min_by_key(|cursor| cursor.next)
+        else {
+            break;
+        };
+        let Some(at) = cursor.next else { break };
+
+        match run_window::decide(workflow, default_timezone, at) {
+            WindowDecision::Run => {
+                next_runs.push(PlannedRun {
+                    node: cursor.node.to_string(),
+                    at,
+                    deferred_from: None,
+                });
+                cursor.advance(tz, at);
+            }
+            WindowDecision::Queue { at: opens, .. } => {
+                next_runs.push(PlannedRun {
+                    node: cursor.node.to_string(),
+                    at: opens,
+                    deferred_from: Some(at),
+                });
+                // Runs until the opening are held with this one.
+                cursor.advance(tz, opens);
+            }
+            WindowDecision::Skip { .. } | WindowDecision::Reject { .. } => {
+                match run_window::reopens_at(workflow, default_timezone, at) {
+                    // Include a run right at the opening.
+                    Some(opens) => cursor.advance(tz, opens - Duration::milliseconds(1)),
+                    None => cursor.next = None,
+                }
+            }
+        }
+    }
+    next_runs.sort_by_key(|run| run.at);
+
+    Some(SchedulePreview {
+        timezone: tz.name().to_string(),
+        next_runs,
+        invalid,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::{BlackoutPeriod, RunWindowSettings};
+    use std::collections::HashMap;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn interval(fields: &[(&str, NodeParameterValue)]) -> NodeParameterValue {
+        NodeParameterValue::Object(
+            fields.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+        )
+    }
+
+    fn trigger(name: &str, intervals: Vec<NodeParameterValue>) -> Node {
+        let mut node = Node::new(name, SCHEDULE_TRIGGER_NODE_TYPE);
+        let mut rule = HashMap::new();
+        rule.insert("interval".to_string(), NodeParameterValue::Array(intervals));
+        node.parameters.insert("rule".to_string(), NodeParameterValue::Object(rule));
+        node
+    }
+
+    #[test]
+    fn test_cron_fields_and_dst() {
+        let cron: CronSchedule = "30 9 * * MON-FRI".parse().unwrap();
+        // Saturday 2026-10-17 → Monday 09:30 Berlin (UTC+2).
+        let tz: Tz = "Europe/Berlin".parse().unwrap();
+        assert_eq!(
+            cron.next_after(tz, at("2026-10-17T12:00:00Z")),
+            Some(at("2026-10-19T07:30:00Z"))
+        );
+
+        // 02:30 does not exist in Berlin on 2027-03-28.
+        let cron: CronSchedule = "0 30 2 * * *".parse().unwrap();
+        assert_eq!(
+            cron.next_after(tz, at("2027-03-27T12:00:00Z")),
+            Some(at("2027-03-29T00:30:00Z"))
+        );
+
+        // Day of month or Sunday.
+        let cron: CronSchedule = "0 0 13 * 0".parse().unwrap();
+        assert_eq!(
+            cron.next_after(Tz::UTC, at("2026-10-15T00:00:00Z")),
+            Some(at("2026-10-18T00:00:00Z"))
+        );
+
+        assert!("* * *".parse::<CronSchedule>().is_err());
+        assert!("0 61 * * * *".parse::<CronSchedule>().is_err());
+        assert!("0 0 10-5 * *".parse::<CronSchedule>().is_err());
+    }
+
+    #[test]
+    fn test_preview_merges_triggers_and_applies_run_window() {
+        use NodeParameterValue::{Number, String as Str};
+
+        let mut workflow = Workflow::new("reports");
+        workflow.settings.timezone = Some("America/New_York".to_string());
+        workflow.nodes.push(trigger(
+            "Every 2 days",
+            vec![interval(&[
+                ("field", Str("days".to_string())),
+                ("daysInterval", Number(2.0)),
+                ("triggerAtHour", Number(6.0)),
+            ])],
+        ));
+        let mut cron = Node::new("Hourly", SCHEDULE_TRIGGER_NODE_TYPE);
+        cron.parameters.insert("cronExpression".to_string(), Str("0 0 * * * *".to_string()));
+        cron.disabled = true;
+        workflow.nodes.push(cron);
+
+        // Thursday 2026-10-15 12:00 New York (UTC-4).
+        let from = at("2026-10-15T16:00:00Z");
+        let planned = preview(&workflow, "UTC", from, 3).unwrap();
+        assert_eq!(planned.timezone, "America/New_York");
+        let times: Vec<_> = planned.next_runs.iter().map(|run| run.at).collect();
+        assert_eq!(
+            times,
+            vec![
+                at("2026-10-16T10:00:00Z"),
+                at("2026-10-18T10:00:00Z"),
+                at("2026-10-20T10:00:00Z"),
+            ]
+        );
+
+        // A blackout over the 18th drops that run with `reject`.
+        workflow.settings.run_window = Some(RunWindowSettings {
+            blackouts: vec![BlackoutPeriod {
+                start: at("2026-10-17T00:00:00Z"),
+                end: at("2026-10-19T00:00:00Z"),
+                reason: None,
+            }],
+            ..Default::default()
+        });
+        let planned = preview(&workflow, "UTC", from, 2).unwrap();
+        assert_eq!(planned.next_runs[1].at, at("2026-10-20T10:00:00Z"));
+
+        let fortnights = interval(&[("field", Str("fortnights".to_string()))]);
+        workflow.nodes[0] = trigger("Broken", vec![fortnights]);
+        let planned = preview(&workflow, "UTC", from, 2).unwrap();
+        assert!(planned.next_runs.is_empty());
+        assert_eq!(planned.invalid[0].node, "Broken");
+    }
+
+    #[test]
+    fn test_schedule_options() {
+        use NodeParameterValue::{Number, String as Str};
+
+        let days = interval(&[("field", Str("days".to_string()))]);
+        let minutes = interval(&[
+            ("field", Str("minutes".to_string())),
+            ("minutesInterval", Number(15.0)),
+        ]);
+        let mut node = trigger("Poll", vec![minutes]);
+        assert_eq!(schedule_options(&node).unwrap(), ScheduleOptions::default());
+        assert_eq!(schedule_rules(&node).unwrap()[0].period, Some(Duration::minutes(15)));
+
+        let options = interval(&[
+            ("jitterSeconds", Number(30.0)),
+            ("alignment", Str("sliding".to_string())),
+        ]);
+        node.parameters.insert("options".to_string(), options.clone());
+        let parsed = schedule_options(&node).unwrap();
+        assert_eq!(parsed.jitter_secs, 30);
+        assert_eq!(parsed.alignment, Alignment::Sliding);
+
+        // Days have no fixed period to slide by.
+        let mut node = trigger("Daily", vec![days]);
+        node.parameters.insert("options".to_string(), options);
+        assert!(schedule_options(&node).is_err());
+        let negative = interval(&[("jitterSeconds", Number(-1.0))]);
+        node.parameters.insert("options".to_string(), negative);
+        assert!(schedule_options(&node).is_err());
+    }
+}
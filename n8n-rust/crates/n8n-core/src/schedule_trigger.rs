@@ -0,0 +1,400 @@
+//! Firing schedule triggers.
+//!
+//! [`ScheduleRunner`] starts active workflows from their enabled schedule
+//! triggers at the times their rules select ([`crate::schedule`]), through
+//! a [`ScheduleHandler`]. Workflows are re-read every `refresh`, so
+//! activations, edits and deactivations take effect within it. Runs are
+//! subject to the workflow's run window: a run in a closed window waits for
+//! the opening with `queue` and is dropped with `skip` and `reject`. Runs
+//! missed meanwhile, e.g. while a queued run waited, are not caught up.
+//!
+//! The trigger's `options` ([`ScheduleOptions`]) shape the runs:
+//!
+//! - `jitterSeconds` delays every run by a random 0 to n seconds, so
+//!   workflows on the same schedule do not all start at once. Keep it
+//!   shorter than the interval, or runs are skipped;
+//! - `alignment: sliding` starts each run one rule period after the
+//!   previous one finished, instead of on the clock;
+//! - `skipIfRunning` drops a run while the trigger's previous run is still
+//!   executing.
+//!
+//! Plans are kept in memory: runs due while the process was down are not
+//! caught up. Each instance runs its own schedules unless given a leader
+//! flag ([`ScheduleRunner::with_leader`]), which limits them to the leader.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use n8n_workflow::{Node, Workflow};
+use parking_lot::Mutex;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::run_window::{self, WindowDecision};
+use crate::schedule::{
+    schedule_options, schedule_rules, Alignment, ScheduleOptions, ScheduleRule,
+    SCHEDULE_TRIGGER_NODE_TYPE,
+};
+use crate::storage::WorkflowStorage;
+
+/// Starts workflows from schedule triggers.
+#[async_trait]
+pub trait ScheduleHandler: Send + Sync {
+    /// Run `workflow` from the trigger `node` for the run planned at
+    /// `scheduled_for`, returning once the run finished.
+    async fn fire(
+        &self,
+        workflow: &Workflow,
+        node: &str,
+        scheduled_for: DateTime<Utc>,
+    ) -> Result<(), String>;
+}
+
+/// A schedule trigger: workflow id and node name.
+pub type TriggerKey = (String, String);
+
+/// What one [`ScheduleRunner::tick`] did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TickReport {
+    pub started: Vec<TriggerKey>,
+    /// Runs dropped by `skipIfRunning` or a closed run window.
+    pub skipped: Vec<TriggerKey>,
+}
+
+struct RuleCursor {
+    rule: ScheduleRule,
+    anchor: Option<NaiveDate>,
+    next: Option<DateTime<Utc>>,
+}
+
+struct Plan {
+    workflow: Arc<Workflow>,
+    /// Node and timezone the plan was made from.
+    fingerprint: String,
+    tz: Tz,
+    options: ScheduleOptions,
+    rules: Vec<RuleCursor>,
+    /// Next run as the rules place it.
+    scheduled: Option<DateTime<Utc>>,
+    /// When that run starts, jitter and run window included.
+    fire_at: Option<DateTime<Utc>>,
+}
+
+impl Plan {
+    /// Plan the first run after `after`, and every later one.
+    fn schedule_after(&mut self, after: DateTime<Utc>) {
+        self.scheduled = match self.options.alignment {
+            Alignment::Clock => {
+                for cursor in &mut self.rules {
+                    if !cursor.next.is_some_and(|next| next > after) {
+                        cursor.next = cursor.rule.next_after(self.tz, after, &mut cursor.anchor);
+                    }
+                }
+                self.rules.iter().filter_map(|cursor| cursor.next).min()
+            }
+            Alignment::Sliding => {
+                let period = self.rules.iter().filter_map(|cursor| cursor.rule.period).min();
+                period.map(|period| after + period)
+            }
+        };
+        let jitter = match self.options.jitter_secs {
+            0 => 0,
+            max => rand::thread_rng().gen_range(0..=max as i64),
+        };
+        self.fire_at = self.scheduled.map(|at| at + Duration::seconds(jitter));
+    }
+}
+
+/// Fires the schedule triggers of active workflows.
+pub struct ScheduleRunner {
+    workflows: Arc<dyn WorkflowStorage>,
+    handler: Arc<dyn ScheduleHandler>,
+    default_timezone: String,
+    refresh: std::time::Duration,
+    plans: Mutex<HashMap<TriggerKey, Plan>>,
+    /// Fingerprints of triggers whose schedule is invalid, reported once.
+    invalid: Mutex<HashMap<TriggerKey, String>>,
+    running: Mutex<HashSet<TriggerKey>>,
+    leader: Option<tokio::sync::watch::Receiver<bool>>,
+}
+
+impl ScheduleRunner {
+    /// Runner reading workflows from `workflows` every 30 seconds, in
+    /// `default_timezone` when a workflow sets none.
+    pub fn new(
+        workflows: Arc<dyn WorkflowStorage>,
+        handler: Arc<dyn ScheduleHandler>,
+        default_timezone: impl Into<String>,
+    ) -> Self {
+        Self {
+            workflows,
+            handler,
+            default_timezone: default_timezone.into(),
+            refresh: std::time::Duration::from_secs(30),
+            plans: Mutex::new(HashMap::new()),
+            invalid: Mutex::new(HashMap::new()),
+            running: Mutex::new(HashSet::new()),
+            leader: None,
+        }
+    }
+
+    /// Re-read workflows every `refresh`.
+    pub fn with_refresh(mut self, refresh: std::time::Duration) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Fire only while `leader` is true, so one of several instances
+    /// runs the schedules. Plans are dropped while following and made
+    /// afresh on taking the lead.
+    pub fn with_leader(mut self, leader: tokio::sync::watch::Receiver<bool>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Triggers with a run executing.
+    pub fn running(&self) -> usize {
+        self.running.lock().len()
+    }
+
+    /// When the next planned run starts.
+    pub fn next_fire_at(&self) -> Option<DateTime<Utc>> {
+        self.plans.lock().values().filter_map(|plan| plan.fire_at).min()
+    }
+
+    /// Run the scheduler until the task is aborted.
+    pub fn spawn(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let now = Utc::now();
+                if let Err(e) = self.tick(now).await {
+                    tracing::warn!(error = %e, "Reading workflows for schedule triggers failed");
+                }
+                let refresh = Duration::from_std(self.refresh).unwrap_or(Duration::seconds(30));
+                let wake = self.next_fire_at().map_or(now + refresh, |at| at.min(now + refresh));
+                let sleep = (wake - Utc::now()).to_std().unwrap_or_default();
+                tokio::time::sleep(sleep).await;
+            }
+        })
+    }
+
+    /// Re-read workflows and start the runs due at `now`.
+    pub async fn tick(self: &Arc<Self>, now: DateTime<Utc>) -> Result<TickReport, String> {
+        if self.leader.as_ref().is_some_and(|leader| !*leader.borrow()) {
+            self.plans.lock().clear();
+            return Ok(TickReport::default());
+        }
+        self.sync(now).await?;
+
+        let mut report = TickReport::default();
+        let mut plans = self.plans.lock();
+        for (key, plan) in plans.iter_mut() {
+            let (Some(fire_at), Some(scheduled)) = (plan.fire_at, plan.scheduled) else {
+                continue;
+            };
+            if fire_at > now {
+                continue;
+            }
+            match run_window::decide(&plan.workflow, &self.default_timezone, now) {
+                WindowDecision::Run => {}
+                WindowDecision::Queue { at, .. } => {
+                    plan.fire_at = Some(at);
+                    continue;
+                }
+                WindowDecision::Skip { .. } | WindowDecision::Reject { .. } => {
+                    report.skipped.push(key.clone());
+                    plan.schedule_after(now);
+                    continue;
+                }
+            }
+            if plan.options.skip_if_running && self.running.lock().contains(key) {
+                tracing::info!(
+                    workflow = %key.0,
+                    node = %key.1,
+                    "Skipping scheduled run, the previous one is still running"
+                );
+                report.skipped.push(key.clone());
+                plan.schedule_after(now);
+                continue;
+            }
+
+            self.running.lock().insert(key.clone());
+            report.started.push(key.clone());
+            match plan.options.alignment {
+                Alignment::Clock => plan.schedule_after(now.max(scheduled)),
+                // Planned once the run finished.
+                Alignment::Sliding => {
+                    plan.scheduled = None;
+                    plan.fire_at = None;
+                }
+            }
+            let (runner, key, workflow) = (self.clone(), key.clone(), plan.workflow.clone());
+            tokio::spawn(async move {
+                if let Err(e) = runner.handler.fire(&workflow, &key.1, scheduled).await {
+                    tracing::warn!(
+                        workflow = %key.0,
+                        node = %key.1,
+                        error = %e,
+                        "Scheduled run failed"
+                    );
+                }
+                runner.finished(&key, Utc::now());
+            });
+        }
+        Ok(report)
+    }
+
+    fn finished(&self, key: &TriggerKey, at: DateTime<Utc>) {
+        self.running.lock().remove(key);
+        if let Some(plan) = self.plans.lock().get_mut(key) {
+            if plan.options.alignment == Alignment::Sliding && plan.scheduled.is_none() {
+                plan.schedule_after(at);
+            }
+        }
+    }
+
+    /// Plan the triggers of active workflows, keeping the plans of
+    /// unchanged ones.
+    async fn sync(&self, now: DateTime<Utc>) -> Result<(), String> {
+        let workflows = self.workflows.list_workflows().await.map_err(|e| e.to_string())?;
+        let mut plans = self.plans.lock();
+        let mut invalid = self.invalid.lock();
+        let mut seen = HashSet::new();
+        for workflow in workflows.into_iter().filter(|w| w.active) {
+            let workflow = Arc::new(workflow);
+            for node in workflow.nodes.iter().filter(|node| is_schedule_trigger(node)) {
+                let key = (workflow.id.clone(), node.name.clone());
+                let name = workflow.settings.timezone.as_deref();
+                let name = name.unwrap_or(&self.default_timezone);
+                let node_json = serde_json::to_string(node).unwrap_or_default();
+                let fingerprint = format!("{}|{}", name, node_json);
+                seen.insert(key.clone());
+                if let Some(plan) = plans.get_mut(&key) {
+                    if plan.fingerprint == fingerprint {
+                        plan.workflow = workflow.clone();
+                        continue;
+                    }
+                }
+                plans.remove(&key);
+
+                let planned =
+                    schedule_rules(node).and_then(|rules| Ok((rules, schedule_options(node)?)));
+                let (rules, options) = match planned {
+                    Ok(planned) => planned,
+                    Err(e) => {
+                        if invalid.get(&key) != Some(&fingerprint) {
+                            tracing::warn!(
+                                workflow = %workflow.id,
+                                node = %node.name,
+                                error = %e,
+                                "Schedule trigger not started"
+                            );
+                            invalid.insert(key, fingerprint);
+                        }
+                        continue;
+                    }
+                };
+                invalid.remove(&key);
+                let mut plan = Plan {
+                    workflow: workflow.clone(),
+                    fingerprint,
+                    tz: name.parse().unwrap_or(Tz::UTC),
+                    options,
+                    rules: rules
+                        .into_iter()
+                        .map(|rule| RuleCursor {
+                            rule,
+                            anchor: None,
+                            next: None,
+                        })
+                        .collect(),
+                    scheduled: None,
+                    fire_at: None,
+                };
+                plan.schedule_after(now);
+                plans.insert(key, plan);
+            }
+        }
+        plans.retain(|key, _| seen.contains(key));
+        invalid.retain(|key, _| seen.contains(key));
+        Ok(())
+    }
+}
+
+fn is_schedule_trigger(node: &Node) -> bool {
+    !node.disabled && node.node_type == SCHEDULE_TRIGGER_NODE_TYPE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryWorkflowStorage;
+    use n8n_workflow::NodeParameterValue;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::Semaphore;
+
+    /// Counts runs, each of which waits for a permit.
+    struct HeldRuns {
+        fired: AtomicUsize,
+        permits: Semaphore,
+    }
+
+    #[async_trait]
+    impl ScheduleHandler for HeldRuns {
+        async fn fire(&self, _: &Workflow, _: &str, _: DateTime<Utc>) -> Result<(), String> {
+            self.fired.fetch_add(1, Ordering::SeqCst);
+            self.permits.acquire().await.unwrap().forget();
+            Ok(())
+        }
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_skip_if_running_drops_overlapping_runs() {
+        let mut trigger = Node::new("Every 10s", SCHEDULE_TRIGGER_NODE_TYPE);
+        trigger.parameters.insert(
+            "cronExpression".to_string(),
+            NodeParameterValue::String("*/10 * * * * *".to_string()),
+        );
+        let options = [("skipIfRunning".to_string(), NodeParameterValue::Boolean(true))];
+        trigger.parameters.insert(
+            "options".to_string(),
+            NodeParameterValue::Object(options.into_iter().collect()),
+        );
+        let mut workflow = Workflow::new("poll");
+        workflow.active = true;
+        workflow.nodes.push(trigger);
+        let workflows = Arc::new(MemoryWorkflowStorage::new());
+        workflows.save_workflow(&workflow).await.unwrap();
+
+        let handler = Arc::new(HeldRuns {
+            fired: AtomicUsize::new(0),
+            permits: Semaphore::new(0),
+        });
+        let runner = Arc::new(ScheduleRunner::new(workflows, handler.clone(), "UTC"));
+        let key = (workflow.id.clone(), "Every 10s".to_string());
+
+        let report = runner.tick(at("2026-10-15T12:00:01Z")).await.unwrap();
+        assert!(report.started.is_empty());
+        assert_eq!(runner.next_fire_at(), Some(at("2026-10-15T12:00:10Z")));
+
+        let report = runner.tick(at("2026-10-15T12:00:10Z")).await.unwrap();
+        assert_eq!(report.started, vec![key.clone()]);
+        // The first run is still held.
+        let report = runner.tick(at("2026-10-15T12:00:20Z")).await.unwrap();
+        assert_eq!(report.skipped, vec![key.clone()]);
+
+        handler.permits.add_permits(1);
+        while runner.running() > 0 {
+            tokio::task::yield_now().await;
+        }
+        let report = runner.tick(at("2026-10-15T12:00:30Z")).await.unwrap();
+        assert_eq!(report.started, vec![key]);
+        tokio::task::yield_now().await;
+        assert_eq!(handler.fired.load(Ordering::SeqCst), 2);
+    }
+}
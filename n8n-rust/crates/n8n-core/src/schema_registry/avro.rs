@@ -0,0 +1,515 @@
+//! Avro schemas and the Avro binary encoding, converted to and from JSON.
+//!
+//! Decoded values are plain JSON: unions decode to the value of the branch
+//! that was written, `bytes` and `fixed` to base64 strings and logical
+//! types to their underlying type. Encoding picks the first union branch
+//! the value fits and fills missing record fields from their defaults.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+/// An Avro type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    Array(Box<Schema>),
+    Map(Box<Schema>),
+    Union(Vec<Schema>),
+    Record { name: String, fields: Vec<Field> },
+    Enum { name: String, symbols: Vec<String> },
+    Fixed { name: String, size: usize },
+    /// Reference by full name to a named type defined elsewhere.
+    Named(String),
+}
+
+/// A field of a record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub schema: Schema,
+    pub default: Option<Value>,
+}
+
+/// A parsed schema and the named types it defines.
+#[derive(Debug, Clone)]
+pub struct AvroSchema {
+    pub root: Schema,
+    names: HashMap<String, Schema>,
+}
+
+impl AvroSchema {
+    /// Parse a schema in Avro's JSON notation.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let json: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let mut names = HashMap::new();
+        let root = parse_schema(&json, None, &mut names)?;
+        Ok(Self { root, names })
+    }
+
+    /// Full name of the root type, if it is a named type.
+    pub fn name(&self) -> Option<&str> {
+        match &self.root {
+            Schema::Record { name, .. }
+            | Schema::Enum { name, .. }
+            | Schema::Fixed { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Decode one datum of the root type.
+    pub fn decode(&self, bytes: &[u8]) -> Result<Value, String> {
+        let mut reader = Reader { bytes, pos: 0 };
+        let value = self.read(&self.root, &mut reader)?;
+        if reader.pos != bytes.len() {
+            return Err(format!("{} trailing bytes after Avro datum", bytes.len() - reader.pos));
+        }
+        Ok(value)
+    }
+
+    /// Encode `value` as a datum of the root type.
+    pub fn encode(&self, value: &Value) -> Result<Vec<u8>, String> {
+        let mut out = Vec::new();
+        self.write(&self.root, value, &mut out)?;
+        Ok(out)
+    }
+
+    fn resolve<'s>(&'s self, schema: &'s Schema) -> Result<&'s Schema, String> {
+        match schema {
+            Schema::Named(name) => {
+                self.names.get(name).ok_or_else(|| format!("Unknown Avro type {}", name))
+            }
+            schema => Ok(schema),
+        }
+    }
+
+    fn read(&self, schema: &Schema, reader: &mut Reader<'_>) -> Result<Value, String> {
+        Ok(match self.resolve(schema)? {
+            Schema::Null => Value::Null,
+            Schema::Boolean => Value::Bool(reader.take(1)?[0] != 0),
+            Schema::Int | Schema::Long => Value::from(reader.long()?),
+            Schema::Float => float(f32::from_le_bytes(reader.fixed()?) as f64),
+            Schema::Double => float(f64::from_le_bytes(reader.fixed()?)),
+            Schema::Bytes => {
+                let len = reader.len()?;
+                Value::String(BASE64.encode(reader.take(len)?))
+            }
+            Schema::String => {
+                let len = reader.len()?;
+                let bytes = reader.take(len)?.to_vec();
+                Value::String(String::from_utf8(bytes).map_err(|e| e.to_string())?)
+            }
+            Schema::Fixed { size, .. } => Value::String(BASE64.encode(reader.take(*size)?)),
+            Schema::Enum { name, symbols } => {
+                let index = reader.long()?;
+                let symbol = usize::try_from(index).ok().and_then(|i| symbols.get(i));
+                Value::String(
+                    symbol
+                        .ok_or_else(|| format!("Enum {} has no symbol {}", name, index))?
+                        .clone(),
+                )
+            }
+            Schema::Array(items) => {
+                let mut values = Vec::new();
+                while let Some(count) = reader.block()? {
+                    for _ in 0..count {
+                        values.push(self.read(items, reader)?);
+                    }
+                }
+                Value::Array(values)
+            }
+            Schema::Map(values) => {
+                let mut object = Map::new();
+                while let Some(count) = reader.block()? {
+                    for _ in 0..count {
+                        let Value::String(key) = self.read(&Schema::String, reader)? else {
+                            unreachable!("strings decode to strings");
+                        };
+                        object.insert(key, self.read(values, reader)?);
+                    }
+                }
+                Value::Object(object)
+            }
+            Schema::Union(branches) => {
+                let index = reader.long()?;
+                let branch = usize::try_from(index)
+                    .ok()
+                    .and_then(|i| branches.get(i))
+                    .ok_or_else(|| format!("Union has no branch {}", index))?;
+                self.read(branch, reader)?
+            }
+            Schema::Record { fields, .. } => {
+                let mut object = Map::new();
+                for field in fields {
+                    object.insert(field.name.clone(), self.read(&field.schema, reader)?);
+                }
+                Value::Object(object)
+            }
+            Schema::Named(name) => return Err(format!("Unresolved Avro type {}", name)),
+        })
+    }
+
+    fn write(&self, schema: &Schema, value: &Value, out: &mut Vec<u8>) -> Result<(), String> {
+        match (self.resolve(schema)?, value) {
+            (Schema::Null, Value::Null) => {}
+            (Schema::Boolean, Value::Bool(b)) => out.push(*b as u8),
+            (Schema::Int, Value::Number(n)) => {
+                let n = n
+                    .as_i64()
+                    .filter(|n| i32::try_from(*n).is_ok())
+                    .ok_or_else(|| format!("{} is not an int", n))?;
+                write_long(out, n);
+            }
+            (Schema::Long, Value::Number(n)) => {
+                write_long(out, n.as_i64().ok_or_else(|| format!("{} is not a long", n))?);
+            }
+            (Schema::Float, Value::Number(n)) => {
+                out.extend((n.as_f64().unwrap_or_default() as f32).to_le_bytes())
+            }
+            (Schema::Double, Value::Number(n)) => {
+                out.extend(n.as_f64().unwrap_or_default().to_le_bytes())
+            }
+            (Schema::String, Value::String(s)) => write_bytes(out, s.as_bytes()),
+            (Schema::Bytes, Value::String(s)) => {
+                write_bytes(out, &BASE64.decode(s).map_err(|e| format!("bytes: {}", e))?)
+            }
+            (Schema::Fixed { name, size }, Value::String(s)) => {
+                let bytes = BASE64.decode(s).map_err(|e| format!("{}: {}", name, e))?;
+                if bytes.len() != *size {
+                    return Err(format!("{} needs {} bytes, got {}", name, size, bytes.len()));
+                }
+                out.extend(bytes);
+            }
+            (Schema::Enum { name, symbols }, Value::String(s)) => {
+                let index = symbols
+                    .iter()
+                    .position(|symbol| symbol == s)
+                    .ok_or_else(|| format!("{} is not a symbol of {}", s, name))?;
+                write_long(out, index as i64);
+            }
+            (Schema::Array(items), Value::Array(values)) => {
+                if !values.is_empty() {
+                    write_long(out, values.len() as i64);
+                    for value in values {
+                        self.write(items, value, out)?;
+                    }
+                }
+                write_long(out, 0);
+            }
+            (Schema::Map(schema), Value::Object(object)) => {
+                if !object.is_empty() {
+                    write_long(out, object.len() as i64);
+                    for (key, value) in object {
+                        write_bytes(out, key.as_bytes());
+                        self.write(schema, value, out)?;
+                    }
+                }
+                write_long(out, 0);
+            }
+            (Schema::Union(branches), value) => {
+                let index = branches
+                    .iter()
+                    .position(|branch| self.fits(branch, value))
+                    .ok_or_else(|| format!("{} matches no branch of the union", value))?;
+                write_long(out, index as i64);
+                self.write(&branches[index], value, out)?;
+            }
+            (Schema::Record { fields, .. }, Value::Object(object)) => {
+                for field in fields {
+                    let value = object.get(&field.name).or(field.default.as_ref());
+                    self.write(&field.schema, value.unwrap_or(&Value::Null), out)
+                        .map_err(|e| format!("{}: {}", field.name, e))?;
+                }
+            }
+            (schema, value) => {
+                return Err(format!("Expected {}, got {}", kind(schema), value));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `value` can be written as `schema`, without checking nested
+    /// values.
+    fn fits(&self, schema: &Schema, value: &Value) -> bool {
+        match (self.resolve(schema), value) {
+            (Ok(Schema::Null), Value::Null) | (Ok(Schema::Boolean), Value::Bool(_)) => true,
+            (Ok(Schema::Int | Schema::Long), Value::Number(n)) => n.is_i64(),
+            (Ok(Schema::Float | Schema::Double), Value::Number(_)) => true,
+            (Ok(Schema::String | Schema::Bytes | Schema::Fixed { .. }), Value::String(_)) => true,
+            (Ok(Schema::Enum { symbols, .. }), Value::String(s)) => symbols.contains(s),
+            (Ok(Schema::Array(_)), Value::Array(_)) => true,
+            (Ok(Schema::Map(_) | Schema::Record { .. }), Value::Object(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+fn kind(schema: &Schema) -> String {
+    match schema {
+        Schema::Null => "null".to_string(),
+        Schema::Boolean => "boolean".to_string(),
+        Schema::Int => "int".to_string(),
+        Schema::Long => "long".to_string(),
+        Schema::Float => "float".to_string(),
+        Schema::Double => "double".to_string(),
+        Schema::Bytes => "bytes".to_string(),
+        Schema::String => "string".to_string(),
+        Schema::Array(_) => "array".to_string(),
+        Schema::Map(_) => "map".to_string(),
+        Schema::Union(_) => "union".to_string(),
+        Schema::Record { name, .. } | Schema::Enum { name, .. } | Schema::Fixed { name, .. } => {
+            name.clone()
+        }
+        Schema::Named(name) => name.clone(),
+    }
+}
+
+fn float(value: f64) -> Value {
+    Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+fn parse_schema(
+    json: &Value,
+    namespace: Option<&str>,
+    names: &mut HashMap<String, Schema>,
+) -> Result<Schema, String> {
+    let object = match json {
+        Value::String(name) => {
+            return Ok(primitive(name).unwrap_or_else(|| Schema::Named(full_name(name, namespace))));
+        }
+        Value::Array(branches) => {
+            return branches
+                .iter()
+                .map(|branch| parse_schema(branch, namespace, names))
+                .collect::<Result<_, _>>()
+                .map(Schema::Union);
+        }
+        Value::Object(object) => object,
+        other => return Err(format!("Invalid Avro schema {}", other)),
+    };
+
+    let type_name = match object.get("type") {
+        Some(Value::String(type_name)) => type_name.as_str(),
+        // `{"type": {...}}` wraps a complex type.
+        Some(inner) => return parse_schema(inner, namespace, names),
+        None => return Err("Avro schema object without \"type\"".to_string()),
+    };
+    let str_field = |key: &str| object.get(key).and_then(Value::as_str);
+    let named = |names: &HashMap<String, Schema>| -> Result<String, String> {
+        let name = str_field("name").ok_or_else(|| format!("{} without a name", type_name))?;
+        let name = full_name(name, str_field("namespace").or(namespace));
+        if names.contains_key(&name) {
+            return Err(format!("Avro type {} is defined twice", name));
+        }
+        Ok(name)
+    };
+
+    let schema = match type_name {
+        "record" | "error" => {
+            let name = named(names)?;
+            let inner = name.rsplit_once('.').map(|(namespace, _)| namespace);
+            let mut fields = Vec::new();
+            for field in object.get("fields").and_then(Value::as_array).into_iter().flatten() {
+                let field_name = field
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| format!("Field of {} without a name", name))?;
+                let schema = field
+                    .get("type")
+                    .ok_or_else(|| format!("Field {}.{} without a type", name, field_name))?;
+                fields.push(Field {
+                    name: field_name.to_string(),
+                    schema: parse_schema(schema, inner, names)?,
+                    default: field.get("default").cloned(),
+                });
+            }
+            Schema::Record { name, fields }
+        }
+        "enum" => Schema::Enum {
+            name: named(names)?,
+            symbols: object
+                .get("symbols")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|s| s.as_str().map(str::to_string))
+                .collect(),
+        },
+        "fixed" => Schema::Fixed {
+            name: named(names)?,
+            size: object
+                .get("size")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| "fixed without a size".to_string())? as usize,
+        },
+        "array" => {
+            let items = object.get("items").ok_or("array without \"items\"")?;
+            return Ok(Schema::Array(Box::new(parse_schema(items, namespace, names)?)));
+        }
+        "map" => {
+            let values = object.get("values").ok_or("map without \"values\"")?;
+            return Ok(Schema::Map(Box::new(parse_schema(values, namespace, names)?)));
+        }
+        // Primitives, possibly annotated with a logical type.
+        other => {
+            return primitive(other).ok_or_else(|| format!("Unknown Avro type {}", other));
+        }
+    };
+
+    let (Schema::Record { name, .. } | Schema::Enum { name, .. } | Schema::Fixed { name, .. }) =
+        &schema
+    else {
+        unreachable!("only named types get here");
+    };
+    names.insert(name.clone(), schema.clone());
+    Ok(schema)
+}
+
+fn primitive(name: &str) -> Option<Schema> {
+    Some(match name {
+        "null" => Schema::Null,
+        "boolean" => Schema::Boolean,
+        "int" => Schema::Int,
+        "long" => Schema::Long,
+        "float" => Schema::Float,
+        "double" => Schema::Double,
+        "bytes" => Schema::Bytes,
+        "string" => Schema::String,
+        _ => return None,
+    })
+}
+
+fn full_name(name: &str, namespace: Option<&str>) -> String {
+    match namespace {
+        Some(namespace) if !namespace.is_empty() && !name.contains('.') => {
+            format!("{}.{}", namespace, name)
+        }
+        _ => name.to_string(),
+    }
+}
+
+struct Reader<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+}
+
+impl<'b> Reader<'b> {
+    fn take(&mut self, n: usize) -> Result<&'b [u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| "Unexpected end of Avro data".to_string())?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn fixed<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().expect("length checked"))
+    }
+
+    /// A zigzag varint.
+    fn long(&mut self) -> Result<i64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok((value >> 1) as i64 ^ -((value & 1) as i64));
+            }
+        }
+        Err("Avro varint is too long".to_string())
+    }
+
+    fn len(&mut self) -> Result<usize, String> {
+        let len = self.long()?;
+        usize::try_from(len).map_err(|_| format!("Negative length {}", len))
+    }
+
+    /// Item count of the next array or map block, `None` at the end.
+    fn block(&mut self) -> Result<Option<u64>, String> {
+        let count = self.long()?;
+        if count < 0 {
+            // Negative counts are followed by the block's size in bytes.
+            self.long()?;
+        }
+        Ok((count != 0).then_some(count.unsigned_abs()))
+    }
+}
+
+fn write_long(out: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_long(out, bytes.len() as i64);
+    out.extend_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip_with_unions_and_defaults() {
+        let schema = AvroSchema::parse(
+            &json!({
+                "type": "record",
+                "name": "Order",
+                "namespace": "shop",
+                "fields": [
+                    { "name": "id", "type": "long" },
+                    { "name": "note", "type": ["null", "string"], "default": null },
+                    { "name": "status", "type": { "type": "enum", "name": "Status",
+                        "symbols": ["NEW", "PAID"] } },
+                    { "name": "lines", "type": { "type": "array", "items": {
+                        "type": "record", "name": "Line", "fields": [
+                            { "name": "sku", "type": "string" },
+                            { "name": "qty", "type": "int", "default": 1 }
+                        ] } } },
+                    { "name": "previous", "type": ["null", "Status"], "default": null }
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        assert_eq!(schema.name(), Some("shop.Order"));
+
+        let bytes = schema
+            .encode(&json!({
+                "id": -3,
+                "status": "PAID",
+                "lines": [{ "sku": "A" }, { "sku": "B", "qty": 2 }],
+                "previous": "NEW"
+            }))
+            .unwrap();
+        assert_eq!(&bytes[..2], &[0x05, 0x00]);
+        assert_eq!(
+            schema.decode(&bytes).unwrap(),
+            json!({
+                "id": -3,
+                "note": null,
+                "status": "PAID",
+                "lines": [{ "sku": "A", "qty": 1 }, { "sku": "B", "qty": 2 }],
+                "previous": "NEW"
+            })
+        );
+
+        let missing = schema.encode(&json!({ "id": 1, "status": "NEW" })).unwrap_err();
+        assert!(missing.starts_with("lines:"), "{}", missing);
+    }
+}
@@ -0,0 +1,437 @@
+//! Confluent Schema Registry aware payload encoding, for Kafka message
+//! keys and values.
+//!
+//! Registry-framed payloads start with a zero magic byte and the big-endian
+//! id of their schema; Protobuf payloads then list the indexes of their
+//! message type within the schema. [`PayloadCodec::decode`] fetches the
+//! schema by id and decodes Avro, Protobuf and JSON Schema payloads into
+//! JSON; [`PayloadCodec::encode`] encodes with the latest schema of the
+//! subject the [`SubjectNameStrategy`] derives. Schemas are cached: by id
+//! for good, since registered schemas never change, and a subject's latest
+//! schema for a configurable time.
+
+pub mod avro;
+pub mod protobuf;
+
+use n8n_workflow::NodeExecutionData;
+use parking_lot::RwLock;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+pub use avro::AvroSchema;
+pub use protobuf::ProtoSchema;
+
+/// First byte of a registry-framed payload.
+pub const MAGIC_BYTE: u8 = 0;
+
+/// Errors of schema lookups and payload encoding.
+#[derive(Error, Debug)]
+pub enum SchemaRegistryError {
+    #[error("Schema registry request failed: {0}")]
+    Http(String),
+
+    #[error("Schema registry answered {status}: {message}")]
+    Registry { status: u16, message: String },
+
+    #[error("Invalid schema {id}: {message}")]
+    Schema { id: u32, message: String },
+
+    #[error("Payload is not schema registry framed: {0}")]
+    Framing(String),
+
+    #[error("{0}")]
+    Subject(String),
+
+    #[error("Failed to decode payload with schema {id}: {message}")]
+    Decode { id: u32, message: String },
+
+    #[error("Failed to encode payload with schema {id}: {message}")]
+    Encode { id: u32, message: String },
+}
+
+/// How the subject of a topic's schema is named.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SubjectNameStrategy {
+    /// `<topic>-key` or `<topic>-value`.
+    #[default]
+    TopicName,
+    /// The record's full name.
+    RecordName,
+    /// `<topic>-<record full name>`.
+    TopicRecordName,
+}
+
+impl SubjectNameStrategy {
+    /// Parse the strategy's name, with or without Confluent's class name
+    /// suffix (`TopicNameStrategy`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim_end_matches("Strategy").to_ascii_lowercase().as_str() {
+            "topicname" | "topic" => Some(Self::TopicName),
+            "recordname" | "record" => Some(Self::RecordName),
+            "topicrecordname" | "topicrecord" => Some(Self::TopicRecordName),
+            _ => None,
+        }
+    }
+
+    /// Subject of the key (`is_key`) or value schema of `topic`. The record
+    /// strategies need the record name.
+    pub fn subject(
+        self,
+        topic: &str,
+        is_key: bool,
+        record: Option<&str>,
+    ) -> Result<String, SchemaRegistryError> {
+        let record = || {
+            record.ok_or_else(|| {
+                SchemaRegistryError::Subject(format!("{:?} needs a record name", self))
+            })
+        };
+        Ok(match self {
+            Self::TopicName => format!("{}-{}", topic, if is_key { "key" } else { "value" }),
+            Self::RecordName => record()?.to_string(),
+            Self::TopicRecordName => format!("{}-{}", topic, record()?),
+        })
+    }
+}
+
+/// A schema as registered.
+#[derive(Debug)]
+pub enum ParsedSchema {
+    Avro(AvroSchema),
+    Protobuf(ProtoSchema),
+    /// Payloads are JSON text; the schema is not enforced.
+    Json,
+}
+
+/// A registered schema and its id.
+#[derive(Debug)]
+pub struct RegisteredSchema {
+    pub id: u32,
+    pub schema: ParsedSchema,
+}
+
+impl RegisteredSchema {
+    /// Parse a schema of the registry's `schemaType` (`AVRO` if absent).
+    pub fn parse(
+        id: u32,
+        schema_type: Option<&str>,
+        text: &str,
+    ) -> Result<Self, SchemaRegistryError> {
+        let schema = match schema_type.unwrap_or("AVRO") {
+            "AVRO" => AvroSchema::parse(text).map(ParsedSchema::Avro),
+            "PROTOBUF" => ProtoSchema::parse(text).map(ParsedSchema::Protobuf),
+            "JSON" => Ok(ParsedSchema::Json),
+            other => Err(format!("Unsupported schema type {}", other)),
+        }
+        .map_err(|message| SchemaRegistryError::Schema { id, message })?;
+        Ok(Self { id, schema })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SchemaResponse {
+    #[serde(default)]
+    id: Option<u32>,
+    schema: String,
+    #[serde(default)]
+    schema_type: Option<String>,
+}
+
+/// Client of a schema registry's REST API with a schema cache.
+pub struct SchemaRegistryClient {
+    base_url: String,
+    http: reqwest::Client,
+    basic_auth: Option<(String, String)>,
+    latest_ttl: Duration,
+    by_id: RwLock<HashMap<u32, Arc<RegisteredSchema>>>,
+    latest: RwLock<HashMap<String, (u32, Instant)>>,
+}
+
+impl SchemaRegistryClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            basic_auth: None,
+            latest_ttl: Duration::from_secs(300),
+            by_id: RwLock::new(HashMap::new()),
+            latest: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Authenticate with an API key and secret or username and password.
+    pub fn with_basic_auth(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.basic_auth = Some((username.into(), password.into()));
+        self
+    }
+
+    /// How long a subject's latest schema is reused before asking again.
+    pub fn with_latest_ttl(mut self, ttl: Duration) -> Self {
+        self.latest_ttl = ttl;
+        self
+    }
+
+    /// Add a schema to the cache, as if it had been fetched.
+    pub fn insert(&self, schema: RegisteredSchema) -> Arc<RegisteredSchema> {
+        let schema = Arc::new(schema);
+        self.by_id.write().insert(schema.id, schema.clone());
+        schema
+    }
+
+    /// The schema registered with `id`.
+    pub async fn schema_by_id(
+        &self,
+        id: u32,
+    ) -> Result<Arc<RegisteredSchema>, SchemaRegistryError> {
+        let cached = self.by_id.read().get(&id).cloned();
+        if let Some(schema) = cached {
+            return Ok(schema);
+        }
+        let response = self.get(&format!("/schemas/ids/{}", id)).await?;
+        let schema_type = response.schema_type.as_deref();
+        Ok(self.insert(RegisteredSchema::parse(id, schema_type, &response.schema)?))
+    }
+
+    /// The latest schema registered under `subject`.
+    pub async fn latest_schema(
+        &self,
+        subject: &str,
+    ) -> Result<Arc<RegisteredSchema>, SchemaRegistryError> {
+        let cached = self
+            .latest
+            .read()
+            .get(subject)
+            .and_then(|(id, fetched)| (fetched.elapsed() < self.latest_ttl).then_some(*id));
+        if let Some(id) = cached {
+            return self.schema_by_id(id).await;
+        }
+
+        let path = format!("/subjects/{}/versions/latest", urlencoding::encode(subject));
+        let response = self.get(&path).await?;
+        let id = response.id.ok_or_else(|| {
+            SchemaRegistryError::Http(format!("Latest version of {} has no id", subject))
+        })?;
+        let cached = self.by_id.read().get(&id).cloned();
+        let schema = match cached {
+            Some(schema) => schema,
+            None => self.insert(RegisteredSchema::parse(
+                id,
+                response.schema_type.as_deref(),
+                &response.schema,
+            )?),
+        };
+        self.latest.write().insert(subject.to_string(), (id, Instant::now()));
+        Ok(schema)
+    }
+
+    async fn get(&self, path: &str) -> Result<SchemaResponse, SchemaRegistryError> {
+        let mut request = self
+            .http
+            .get(format!("{}{}", self.base_url, path))
+            .header("Accept", "application/vnd.schemaregistry.v1+json");
+        if let Some((username, password)) = &self.basic_auth {
+            request = request.basic_auth(username, Some(password));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| SchemaRegistryError::Http(e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(SchemaRegistryError::Registry {
+                status: status.as_u16(),
+                message: response.text().await.unwrap_or_default(),
+            });
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| SchemaRegistryError::Http(e.to_string()))
+    }
+}
+
+/// A decoded payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedPayload {
+    pub schema_id: u32,
+    pub value: Value,
+}
+
+impl DecodedPayload {
+    /// Item holding the payload: its fields if it is an object, otherwise
+    /// the value under `value`.
+    pub fn into_item(self) -> Result<NodeExecutionData, serde_json::Error> {
+        match self.value {
+            value @ Value::Object(_) => NodeExecutionData::from_json_value(value),
+            value => NodeExecutionData::from_json_value(serde_json::json!({ "value": value })),
+        }
+    }
+}
+
+/// Decodes and encodes registry-framed payloads.
+#[derive(Clone)]
+pub struct PayloadCodec {
+    registry: Arc<SchemaRegistryClient>,
+    strategy: SubjectNameStrategy,
+}
+
+impl PayloadCodec {
+    pub fn new(registry: Arc<SchemaRegistryClient>, strategy: SubjectNameStrategy) -> Self {
+        Self { registry, strategy }
+    }
+
+    /// Decode a framed payload with the schema it names.
+    pub async fn decode(&self, payload: &[u8]) -> Result<DecodedPayload, SchemaRegistryError> {
+        let (id, data) = split_frame(payload)?;
+        let schema = self.registry.schema_by_id(id).await?;
+        let error = |message: String| SchemaRegistryError::Decode { id, message };
+        let value = match &schema.schema {
+            ParsedSchema::Avro(avro) => avro.decode(data).map_err(error)?,
+            ParsedSchema::Protobuf(proto) => {
+                let (indexes, data) = read_message_indexes(data)?;
+                let message = proto.message_at(&indexes).map_err(error)?;
+                proto.decode(message, data).map_err(error)?
+            }
+            ParsedSchema::Json => {
+                serde_json::from_slice(data).map_err(|e| error(e.to_string()))?
+            }
+        };
+        Ok(DecodedPayload {
+            schema_id: id,
+            value,
+        })
+    }
+
+    /// Encode `value` as the key (`is_key`) or value of a message on
+    /// `topic`. `record` names the record for the record strategies and
+    /// picks the message of a Protobuf schema (the first if `None`).
+    pub async fn encode(
+        &self,
+        topic: &str,
+        is_key: bool,
+        record: Option<&str>,
+        value: &Value,
+    ) -> Result<Vec<u8>, SchemaRegistryError> {
+        let subject = self.strategy.subject(topic, is_key, record)?;
+        let schema = self.registry.latest_schema(&subject).await?;
+        let error = |message: String| SchemaRegistryError::Encode {
+            id: schema.id,
+            message,
+        };
+
+        let mut out = vec![MAGIC_BYTE];
+        out.extend(schema.id.to_be_bytes());
+        match &schema.schema {
+            ParsedSchema::Avro(avro) => out.extend(avro.encode(value).map_err(error)?),
+            ParsedSchema::Protobuf(proto) => {
+                let (message, indexes) = proto.find_message(record).map_err(error)?;
+                write_message_indexes(&mut out, &indexes);
+                out.extend(proto.encode(message, value).map_err(error)?);
+            }
+            ParsedSchema::Json => {
+                serde_json::to_writer(&mut out, value).map_err(|e| error(e.to_string()))?
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Schema id and data of a framed payload.
+pub fn split_frame(payload: &[u8]) -> Result<(u32, &[u8]), SchemaRegistryError> {
+    match payload {
+        [MAGIC_BYTE, a, b, c, d, data @ ..] => Ok((u32::from_be_bytes([*a, *b, *c, *d]), data)),
+        [magic, ..] if payload.len() >= 5 => {
+            Err(SchemaRegistryError::Framing(format!("unknown magic byte {}", magic)))
+        }
+        _ => Err(SchemaRegistryError::Framing(format!("only {} bytes", payload.len()))),
+    }
+}
+
+/// Protobuf message indexes: a count, then the indexes, all as zigzag
+/// varints. A count of zero stands for the first message.
+fn read_message_indexes(data: &[u8]) -> Result<(Vec<i64>, &[u8]), SchemaRegistryError> {
+    let mut pos = 0;
+    let mut next = || -> Result<i64, SchemaRegistryError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *data.get(pos).ok_or_else(|| {
+                SchemaRegistryError::Framing("truncated message indexes".to_string())
+            })?;
+            pos += 1;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok((value >> 1) as i64 ^ -((value & 1) as i64));
+            }
+        }
+        Err(SchemaRegistryError::Framing("invalid message indexes".to_string()))
+    };
+
+    let count = next()?;
+    if !(0..=64).contains(&count) {
+        return Err(SchemaRegistryError::Framing(format!("{} message indexes", count)));
+    }
+    let indexes = (0..count).map(|_| next()).collect::<Result<Vec<_>, _>>()?;
+    let indexes = if indexes.is_empty() { vec![0] } else { indexes };
+    Ok((indexes, &data[pos..]))
+}
+
+fn write_message_indexes(out: &mut Vec<u8>, indexes: &[i64]) {
+    // The first message is written as a bare zero count.
+    let indexes = if indexes == [0] { &[][..] } else { indexes };
+    let mut write = |n: i64| {
+        let mut n = ((n << 1) ^ (n >> 63)) as u64;
+        while n >= 0x80 {
+            out.push((n as u8) | 0x80);
+            n >>= 7;
+        }
+        out.push(n as u8);
+    };
+    write(indexes.len() as i64);
+    indexes.iter().for_each(|index| write(*index));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_subject_name_strategies() {
+        let topic = SubjectNameStrategy::parse("TopicNameStrategy").unwrap();
+        assert_eq!(topic.subject("orders", true, None).unwrap(), "orders-key");
+        let record = SubjectNameStrategy::TopicRecordName;
+        let subject = record.subject("orders", false, Some("shop.Order")).unwrap();
+        assert_eq!(subject, "orders-shop.Order");
+        assert!(SubjectNameStrategy::RecordName.subject("orders", false, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_decode_framed_protobuf_with_cached_schema() {
+        let registry = Arc::new(SchemaRegistryClient::new("http://registry.invalid"));
+        let proto = "syntax = \"proto3\"; message A { int32 x = 1; } message B { string y = 1; }";
+        registry.insert(RegisteredSchema::parse(7, Some("PROTOBUF"), proto).unwrap());
+        let codec = PayloadCodec::new(registry, SubjectNameStrategy::TopicName);
+
+        // Schema 7, message indexes [1], then B { y: "hi" }.
+        let payload = [0, 0, 0, 0, 7, 0x02, 0x02, 0x0a, 0x02, b'h', b'i'];
+        let decoded = codec.decode(&payload).await.unwrap();
+        assert_eq!(decoded, DecodedPayload { schema_id: 7, value: json!({ "y": "hi" }) });
+
+        let mut indexes = Vec::new();
+        write_message_indexes(&mut indexes, &[1]);
+        assert_eq!(indexes, [0x02, 0x02]);
+        assert!(matches!(
+            codec.decode(&[1, 0, 0, 0, 7]).await,
+            Err(SchemaRegistryError::Framing(_))
+        ));
+    }
+}
@@ -0,0 +1,971 @@
+//! Protobuf schemas, given as the `.proto` source the registry stores, and
+//! the Protobuf wire format converted to and from JSON.
+//!
+//! Fields keep their declared names, enums decode to their value names,
+//! `bytes` to base64 strings and maps to objects. Fields absent from the
+//! message are absent from the JSON. Imported types are not resolved and
+//! groups are not supported.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+/// Nesting depth beyond which messages are refused.
+const MAX_DEPTH: usize = 64;
+
+/// A scalar field type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scalar {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "double" => Scalar::Double,
+            "float" => Scalar::Float,
+            "int32" => Scalar::Int32,
+            "int64" => Scalar::Int64,
+            "uint32" => Scalar::Uint32,
+            "uint64" => Scalar::Uint64,
+            "sint32" => Scalar::Sint32,
+            "sint64" => Scalar::Sint64,
+            "fixed32" => Scalar::Fixed32,
+            "fixed64" => Scalar::Fixed64,
+            "sfixed32" => Scalar::Sfixed32,
+            "sfixed64" => Scalar::Sfixed64,
+            "bool" => Scalar::Bool,
+            "string" => Scalar::String,
+            "bytes" => Scalar::Bytes,
+            _ => return None,
+        })
+    }
+
+    fn wire_type(self) -> u8 {
+        match self {
+            Scalar::Double | Scalar::Fixed64 | Scalar::Sfixed64 => 1,
+            Scalar::Float | Scalar::Fixed32 | Scalar::Sfixed32 => 5,
+            Scalar::String | Scalar::Bytes => 2,
+            _ => 0,
+        }
+    }
+}
+
+/// Type of a message field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldType {
+    Scalar(Scalar),
+    /// Message by full name.
+    Message(String),
+    /// Enum by full name.
+    Enum(String),
+    Map(Scalar, Box<FieldType>),
+    /// Type name not resolved yet; only present while parsing.
+    Unresolved(String),
+}
+
+/// A field of a message.
+#[derive(Debug, Clone)]
+pub struct ProtoField {
+    pub name: String,
+    pub number: u32,
+    pub field_type: FieldType,
+    pub repeated: bool,
+}
+
+/// A message type.
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub full_name: String,
+    pub fields: Vec<ProtoField>,
+    /// Full names of the nested messages, in declaration order.
+    pub nested: Vec<String>,
+}
+
+/// The messages and enums of a `.proto` file.
+#[derive(Debug, Clone, Default)]
+pub struct ProtoSchema {
+    pub package: String,
+    messages: HashMap<String, Message>,
+    enums: HashMap<String, Vec<(String, i32)>>,
+    top_level: Vec<String>,
+}
+
+impl ProtoSchema {
+    /// Parse `.proto` source.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut parser = ProtoParser {
+            tokens: tokenize(source)?,
+            pos: 0,
+            schema: ProtoSchema::default(),
+        };
+        parser.file()?;
+        let mut schema = parser.schema;
+        schema.resolve_types()?;
+        Ok(schema)
+    }
+
+    /// Full name of the message the schema registry's message indexes
+    /// point at: an index into the top-level messages, then into the nested
+    /// messages of each. No indexes mean the first message.
+    pub fn message_at(&self, indexes: &[i64]) -> Result<&str, String> {
+        fn nth(names: &[String], index: i64) -> Option<&String> {
+            usize::try_from(index).ok().and_then(|i| names.get(i))
+        }
+
+        let missing = || format!("No message at indexes {:?}", indexes);
+        let (first, rest) = match indexes.split_first() {
+            Some((first, rest)) => (*first, rest),
+            None => (0, &[][..]),
+        };
+        let mut name = nth(&self.top_level, first).ok_or_else(missing)?;
+        for index in rest {
+            name = nth(&self.messages[name].nested, *index).ok_or_else(missing)?;
+        }
+        Ok(name.as_str())
+    }
+
+    /// Full name and message indexes of the message called `name` (full or
+    /// short name), or of the first message if `name` is `None`.
+    pub fn find_message(&self, name: Option<&str>) -> Result<(&str, Vec<i64>), String> {
+        fn search<'s>(
+            schema: &'s ProtoSchema,
+            names: &'s [String],
+            wanted: &str,
+            path: &mut Vec<i64>,
+        ) -> Option<&'s str> {
+            for (index, name) in names.iter().enumerate() {
+                path.push(index as i64);
+                let short = name.rsplit('.').next().unwrap_or(name);
+                if name == wanted || short == wanted {
+                    return Some(name.as_str());
+                }
+                if let Some(found) = search(schema, &schema.messages[name].nested, wanted, path) {
+                    return Some(found);
+                }
+                path.pop();
+            }
+            None
+        }
+
+        let Some(wanted) = name else {
+            let first = self.top_level.first().ok_or("Schema has no messages")?;
+            return Ok((first.as_str(), vec![0]));
+        };
+        let mut path = Vec::new();
+        let wanted = wanted.trim_start_matches('.');
+        search(self, &self.top_level, wanted, &mut path)
+            .map(|found| (found, path))
+            .ok_or_else(|| format!("Schema has no message {}", wanted))
+    }
+
+    /// Decode a serialized `message`.
+    pub fn decode(&self, message: &str, bytes: &[u8]) -> Result<Value, String> {
+        self.decode_message(message, bytes, 0)
+    }
+
+    /// Serialize `value` as `message`.
+    pub fn encode(&self, message: &str, value: &Value) -> Result<Vec<u8>, String> {
+        let mut out = Vec::new();
+        self.encode_message(message, value, &mut out, 0)?;
+        Ok(out)
+    }
+
+    fn resolve_types(&mut self) -> Result<(), String> {
+        let mut resolved = HashMap::new();
+        for (name, message) in &self.messages {
+            for (index, field) in message.fields.iter().enumerate() {
+                let field_type = self
+                    .resolve(name, &field.field_type)
+                    .map_err(|e| format!("{}.{}: {}", name, field.name, e))?;
+                resolved.insert((name.clone(), index), field_type);
+            }
+        }
+        for ((name, index), field_type) in resolved {
+            if let Some(message) = self.messages.get_mut(&name) {
+                message.fields[index].field_type = field_type;
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve(&self, scope: &str, field_type: &FieldType) -> Result<FieldType, String> {
+        let name = match field_type {
+            FieldType::Unresolved(name) => name,
+            FieldType::Map(key, value) => {
+                return Ok(FieldType::Map(*key, Box::new(self.resolve(scope, value)?)));
+            }
+            other => return Ok(other.clone()),
+        };
+        let found = |candidate: String| {
+            if self.messages.contains_key(&candidate) {
+                Some(FieldType::Message(candidate))
+            } else if self.enums.contains_key(&candidate) {
+                Some(FieldType::Enum(candidate))
+            } else {
+                None
+            }
+        };
+        if let Some(absolute) = name.strip_prefix('.') {
+            return found(absolute.to_string()).ok_or_else(|| format!("Unknown type {}", name));
+        }
+        // Innermost scope first, as protoc resolves names.
+        let mut scope = scope;
+        loop {
+            if let Some(field_type) = found(qualify(scope, name)) {
+                return Ok(field_type);
+            }
+            if scope.is_empty() {
+                return Err(format!("Unknown type {} (imports are not resolved)", name));
+            }
+            scope = scope.rsplit_once('.').map_or("", |(parent, _)| parent);
+        }
+    }
+
+    fn decode_message(&self, name: &str, bytes: &[u8], depth: usize) -> Result<Value, String> {
+        if depth > MAX_DEPTH {
+            return Err("Message nesting is too deep".to_string());
+        }
+        let message = self
+            .messages
+            .get(name)
+            .ok_or_else(|| format!("Unknown message {}", name))?;
+        let mut object = Map::new();
+        let mut wire = Wire { bytes, pos: 0 };
+
+        while !wire.done() {
+            let key = wire.varint()?;
+            let (number, wire_type) = ((key >> 3) as u32, (key & 7) as u8);
+            let Some(field) = message.fields.iter().find(|f| f.number == number) else {
+                wire.skip(wire_type)?;
+                continue;
+            };
+            let decode = |wire: &mut Wire<'_>| -> Result<Vec<Value>, String> {
+                match &field.field_type {
+                    // Repeated scalars other than strings and bytes may be packed.
+                    FieldType::Scalar(scalar)
+                        if wire_type == 2 && scalar.wire_type() != 2 && field.repeated =>
+                    {
+                        let mut packed = Wire {
+                            bytes: wire.length_delimited()?,
+                            pos: 0,
+                        };
+                        let mut values = Vec::new();
+                        while !packed.done() {
+                            values.push(read_scalar(*scalar, scalar.wire_type(), &mut packed)?);
+                        }
+                        Ok(values)
+                    }
+                    other => Ok(vec![self.read_value(other, wire_type, wire, depth)?]),
+                }
+            };
+
+            match &field.field_type {
+                FieldType::Map(key_type, value_type) => {
+                    let entry = wire.length_delimited()?;
+                    let (key, value) = self.decode_map_entry(*key_type, value_type, entry, depth)?;
+                    let map = object
+                        .entry(field.name.clone())
+                        .or_insert_with(|| Value::Object(Map::new()));
+                    if let Value::Object(map) = map {
+                        map.insert(key, value);
+                    }
+                }
+                _ if field.repeated => {
+                    let values = decode(&mut wire).map_err(|e| format!("{}: {}", field.name, e))?;
+                    let array = object
+                        .entry(field.name.clone())
+                        .or_insert_with(|| Value::Array(Vec::new()));
+                    if let Value::Array(array) = array {
+                        array.extend(values);
+                    }
+                }
+                _ => {
+                    // The last occurrence of a singular field wins.
+                    let value = decode(&mut wire)
+                        .map_err(|e| format!("{}: {}", field.name, e))?
+                        .pop()
+                        .unwrap_or(Value::Null);
+                    object.insert(field.name.clone(), value);
+                }
+            }
+        }
+        Ok(Value::Object(object))
+    }
+
+    fn read_value(
+        &self,
+        field_type: &FieldType,
+        wire_type: u8,
+        wire: &mut Wire<'_>,
+        depth: usize,
+    ) -> Result<Value, String> {
+        match field_type {
+            FieldType::Scalar(scalar) => read_scalar(*scalar, wire_type, wire),
+            FieldType::Enum(name) => {
+                expect_wire_type(wire_type, 0)?;
+                let number = wire.varint()? as i32;
+                let symbol = self.enums[name].iter().find(|(_, value)| *value == number);
+                Ok(symbol.map_or(Value::from(number), |(symbol, _)| Value::from(symbol.clone())))
+            }
+            FieldType::Message(name) => {
+                expect_wire_type(wire_type, 2)?;
+                let bytes = wire.length_delimited()?;
+                self.decode_message(name, bytes, depth + 1)
+            }
+            FieldType::Map(..) | FieldType::Unresolved(_) => {
+                Err("Unexpected field type".to_string())
+            }
+        }
+    }
+
+    fn decode_map_entry(
+        &self,
+        key_type: Scalar,
+        value_type: &FieldType,
+        bytes: &[u8],
+        depth: usize,
+    ) -> Result<(String, Value), String> {
+        let mut wire = Wire { bytes, pos: 0 };
+        let (mut key, mut value) = (None, None);
+        while !wire.done() {
+            let tag = wire.varint()?;
+            let wire_type = (tag & 7) as u8;
+            match tag >> 3 {
+                1 => key = Some(read_scalar(key_type, wire_type, &mut wire)?),
+                2 => value = Some(self.read_value(value_type, wire_type, &mut wire, depth)?),
+                _ => wire.skip(wire_type)?,
+            }
+        }
+        let key = match key {
+            Some(Value::String(key)) => key,
+            Some(other) => other.to_string(),
+            None if key_type == Scalar::String => String::new(),
+            None if key_type == Scalar::Bool => "false".to_string(),
+            None => "0".to_string(),
+        };
+        Ok((key, value.unwrap_or(Value::Null)))
+    }
+
+    fn encode_message(
+        &self,
+        name: &str,
+        value: &Value,
+        out: &mut Vec<u8>,
+        depth: usize,
+    ) -> Result<(), String> {
+        if depth > MAX_DEPTH {
+            return Err("Message nesting is too deep".to_string());
+        }
+        let message = self
+            .messages
+            .get(name)
+            .ok_or_else(|| format!("Unknown message {}", name))?;
+        let Value::Object(object) = value else {
+            return Err(format!("Expected an object for {}, got {}", name, value));
+        };
+        let known = |key: &&String| message.fields.iter().any(|f| &f.name == *key);
+        if let Some(unknown) = object.keys().find(|key| !known(key)) {
+            return Err(format!("{} has no field {}", name, unknown));
+        }
+
+        for field in &message.fields {
+            let Some(value) = object.get(&field.name).filter(|v| !v.is_null()) else {
+                continue;
+            };
+            self.encode_field(field, value, out, depth)
+                .map_err(|e| format!("{}: {}", field.name, e))?;
+        }
+        Ok(())
+    }
+
+    fn encode_field(
+        &self,
+        field: &ProtoField,
+        value: &Value,
+        out: &mut Vec<u8>,
+        depth: usize,
+    ) -> Result<(), String> {
+        match (&field.field_type, value) {
+            (FieldType::Map(key_type, value_type), Value::Object(entries)) => {
+                for (key, value) in entries {
+                    let mut entry = Vec::new();
+                    let key = map_key(*key_type, key)?;
+                    self.write_value(1, &FieldType::Scalar(*key_type), &key, &mut entry, depth)?;
+                    self.write_value(2, value_type, value, &mut entry, depth)?;
+                    write_tag(out, field.number, 2);
+                    write_length_delimited(out, &entry);
+                }
+                Ok(())
+            }
+            (FieldType::Scalar(scalar), Value::Array(values))
+                if field.repeated && scalar.wire_type() != 2 =>
+            {
+                let mut packed = Vec::new();
+                for value in values {
+                    write_scalar(*scalar, value, &mut packed)?;
+                }
+                write_tag(out, field.number, 2);
+                write_length_delimited(out, &packed);
+                Ok(())
+            }
+            (field_type, Value::Array(values)) if field.repeated => {
+                for value in values {
+                    self.write_value(field.number, field_type, value, out, depth)?;
+                }
+                Ok(())
+            }
+            (FieldType::Map(..), value) => Err(format!("Expected an object, got {}", value)),
+            (_, value) if field.repeated => Err(format!("Expected an array, got {}", value)),
+            (field_type, value) => self.write_value(field.number, field_type, value, out, depth),
+        }
+    }
+
+    fn write_value(
+        &self,
+        number: u32,
+        field_type: &FieldType,
+        value: &Value,
+        out: &mut Vec<u8>,
+        depth: usize,
+    ) -> Result<(), String> {
+        match field_type {
+            FieldType::Scalar(scalar) => {
+                write_tag(out, number, scalar.wire_type());
+                write_scalar(*scalar, value, out)
+            }
+            FieldType::Enum(name) => {
+                let number_value = match value {
+                    Value::String(symbol) => self.enums[name]
+                        .iter()
+                        .find(|(s, _)| s == symbol)
+                        .map(|(_, n)| *n)
+                        .ok_or_else(|| format!("{} is not a value of {}", symbol, name))?,
+                    other => int(other)? as i32,
+                };
+                write_tag(out, number, 0);
+                write_varint(out, number_value as i64 as u64);
+                Ok(())
+            }
+            FieldType::Message(name) => {
+                let mut inner = Vec::new();
+                self.encode_message(name, value, &mut inner, depth + 1)?;
+                write_tag(out, number, 2);
+                write_length_delimited(out, &inner);
+                Ok(())
+            }
+            FieldType::Map(..) | FieldType::Unresolved(_) => {
+                Err("Unexpected field type".to_string())
+            }
+        }
+    }
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", scope, name)
+    }
+}
+
+fn expect_wire_type(actual: u8, expected: u8) -> Result<(), String> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!("Wire type {} where {} was expected", actual, expected))
+    }
+}
+
+fn read_scalar(scalar: Scalar, wire_type: u8, wire: &mut Wire<'_>) -> Result<Value, String> {
+    expect_wire_type(wire_type, scalar.wire_type())?;
+    let float = |n: f64| Number::from_f64(n).map_or(Value::Null, Value::Number);
+    Ok(match scalar {
+        Scalar::Int32 => Value::from(wire.varint()? as i32),
+        Scalar::Int64 => Value::from(wire.varint()? as i64),
+        Scalar::Uint32 => Value::from(wire.varint()? as u32),
+        Scalar::Uint64 => Value::from(wire.varint()?),
+        Scalar::Sint32 | Scalar::Sint64 => {
+            let n = wire.varint()?;
+            Value::from((n >> 1) as i64 ^ -((n & 1) as i64))
+        }
+        Scalar::Bool => Value::Bool(wire.varint()? != 0),
+        Scalar::Fixed32 => Value::from(u32::from_le_bytes(wire.fixed()?)),
+        Scalar::Sfixed32 => Value::from(i32::from_le_bytes(wire.fixed()?)),
+        Scalar::Float => float(f32::from_le_bytes(wire.fixed()?) as f64),
+        Scalar::Fixed64 => Value::from(u64::from_le_bytes(wire.fixed()?)),
+        Scalar::Sfixed64 => Value::from(i64::from_le_bytes(wire.fixed()?)),
+        Scalar::Double => float(f64::from_le_bytes(wire.fixed()?)),
+        Scalar::String => {
+            let bytes = wire.length_delimited()?.to_vec();
+            Value::String(String::from_utf8(bytes).map_err(|e| e.to_string())?)
+        }
+        Scalar::Bytes => Value::String(BASE64.encode(wire.length_delimited()?)),
+    })
+}
+
+fn write_scalar(scalar: Scalar, value: &Value, out: &mut Vec<u8>) -> Result<(), String> {
+    let int32 = |value: &Value| -> Result<i32, String> {
+        let n = int(value)?;
+        i32::try_from(n).map_err(|_| format!("{} is out of range for a 32-bit integer", n))
+    };
+    let uint32 = |value: &Value| -> Result<u32, String> {
+        let n = uint(value)?;
+        u32::try_from(n).map_err(|_| format!("{} is out of range for a 32-bit integer", n))
+    };
+    let float = |value: &Value| {
+        value.as_f64().ok_or_else(|| format!("Expected a number, got {}", value))
+    };
+    match scalar {
+        // Negative int32 values are sign-extended to ten bytes, as protoc does.
+        Scalar::Int32 => write_varint(out, int32(value)? as i64 as u64),
+        Scalar::Int64 => write_varint(out, int(value)? as u64),
+        Scalar::Uint32 => write_varint(out, uint32(value)? as u64),
+        Scalar::Uint64 => write_varint(out, uint(value)?),
+        Scalar::Sint32 => write_varint(out, zigzag(int32(value)? as i64)),
+        Scalar::Sint64 => write_varint(out, zigzag(int(value)?)),
+        Scalar::Bool => {
+            let b = value.as_bool().ok_or_else(|| format!("Expected a boolean, got {}", value))?;
+            write_varint(out, b as u64);
+        }
+        Scalar::Fixed32 => out.extend(uint32(value)?.to_le_bytes()),
+        Scalar::Sfixed32 => out.extend(int32(value)?.to_le_bytes()),
+        Scalar::Float => out.extend((float(value)? as f32).to_le_bytes()),
+        Scalar::Fixed64 => out.extend(uint(value)?.to_le_bytes()),
+        Scalar::Sfixed64 => out.extend(int(value)?.to_le_bytes()),
+        Scalar::Double => out.extend(float(value)?.to_le_bytes()),
+        Scalar::String => {
+            let s = value.as_str().ok_or_else(|| format!("Expected a string, got {}", value))?;
+            write_length_delimited(out, s.as_bytes());
+        }
+        Scalar::Bytes => {
+            let s = value.as_str().ok_or_else(|| format!("Expected base64, got {}", value))?;
+            let bytes = BASE64.decode(s).map_err(|e| e.to_string())?;
+            write_length_delimited(out, &bytes);
+        }
+    }
+    Ok(())
+}
+
+/// Integers may be given as numbers or, as the JSON mapping writes 64-bit
+/// values, as strings.
+fn int(value: &Value) -> Result<i64, String> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("Expected an integer, got {}", value))
+}
+
+fn uint(value: &Value) -> Result<u64, String> {
+    match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("Expected an unsigned integer, got {}", value))
+}
+
+fn map_key(key_type: Scalar, key: &str) -> Result<Value, String> {
+    match key_type {
+        Scalar::String => Ok(Value::String(key.to_string())),
+        Scalar::Bool => key
+            .parse::<bool>()
+            .map(Value::Bool)
+            .map_err(|_| format!("Map key {} is not a boolean", key)),
+        _ => Ok(Value::String(key.to_string())),
+    }
+}
+
+fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn write_tag(out: &mut Vec<u8>, number: u32, wire_type: u8) {
+    write_varint(out, (u64::from(number) << 3) | u64::from(wire_type));
+}
+
+fn write_length_delimited(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+struct Wire<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+}
+
+impl<'b> Wire<'b> {
+    fn done(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'b [u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| "Unexpected end of Protobuf data".to_string())?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn fixed<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().expect("length checked"))
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Protobuf varint is too long".to_string())
+    }
+
+    fn length_delimited(&mut self) -> Result<&'b [u8], String> {
+        let len = self.varint()?;
+        self.take(usize::try_from(len).map_err(|_| "Length is too large".to_string())?)
+    }
+
+    fn skip(&mut self, wire_type: u8) -> Result<(), String> {
+        match wire_type {
+            0 => self.varint().map(drop),
+            1 => self.take(8).map(drop),
+            2 => self.length_delimited().map(drop),
+            5 => self.take(4).map(drop),
+            other => Err(format!("Unsupported wire type {}", other)),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<String>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' || c == '\'' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            if i >= chars.len() {
+                return Err("Unterminated string in .proto source".to_string());
+            }
+            i += 1;
+            tokens.push(chars[start..i].iter().collect());
+        } else if c.is_alphanumeric() || c == '_' || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '.')) {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else {
+            tokens.push(c.to_string());
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+struct ProtoParser {
+    tokens: Vec<String>,
+    pos: usize,
+    schema: ProtoSchema,
+}
+
+impl ProtoParser {
+    fn next(&mut self) -> Result<String, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| "Unexpected end of .proto source".to_string())?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        let token = self.next()?;
+        if token == expected {
+            Ok(())
+        } else {
+            Err(format!("Expected '{}', found '{}'", expected, token))
+        }
+    }
+
+    /// Skip to just past the next `;`, stepping over option values.
+    fn skip_statement(&mut self) -> Result<(), String> {
+        while self.next()? != ";" {}
+        Ok(())
+    }
+
+    /// Skip an optional `{ ... }` block and anything before it.
+    fn skip_block(&mut self) -> Result<(), String> {
+        loop {
+            match self.next()?.as_str() {
+                ";" => return Ok(()),
+                "{" => break,
+                _ => {}
+            }
+        }
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next()?.as_str() {
+                "{" => depth += 1,
+                "}" => depth -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn file(&mut self) -> Result<(), String> {
+        while let Some(token) = self.peek().map(str::to_string) {
+            match token.as_str() {
+                "package" => {
+                    self.pos += 1;
+                    self.schema.package = self.next()?;
+                    self.expect(";")?;
+                }
+                "syntax" | "edition" | "import" | "option" => self.skip_statement()?,
+                "message" => {
+                    self.pos += 1;
+                    let scope = self.schema.package.clone();
+                    let name = self.message(&scope)?;
+                    self.schema.top_level.push(name);
+                }
+                "enum" => {
+                    self.pos += 1;
+                    let scope = self.schema.package.clone();
+                    self.enumeration(&scope)?;
+                }
+                "service" | "extend" => self.skip_block()?,
+                ";" => self.pos += 1,
+                other => return Err(format!("Unexpected '{}' in .proto source", other)),
+            }
+        }
+        Ok(())
+    }
+
+    fn message(&mut self, scope: &str) -> Result<String, String> {
+        let full_name = qualify(scope, &self.next()?);
+        self.expect("{")?;
+        let mut message = Message {
+            full_name: full_name.clone(),
+            fields: Vec::new(),
+            nested: Vec::new(),
+        };
+        loop {
+            match self.next()?.as_str() {
+                "}" => break,
+                ";" => {}
+                "message" => message.nested.push(self.message(&full_name)?),
+                "enum" => self.enumeration(&full_name)?,
+                "option" | "reserved" | "extensions" => self.skip_statement()?,
+                "extend" => self.skip_block()?,
+                "oneof" => {
+                    self.next()?;
+                    self.expect("{")?;
+                    loop {
+                        match self.next()?.as_str() {
+                            "}" => break,
+                            "option" => self.skip_statement()?,
+                            field_type => {
+                                let field_type = field_type.to_string();
+                                message.fields.push(self.field(&field_type, false)?);
+                            }
+                        }
+                    }
+                }
+                "repeated" => {
+                    let field_type = self.next()?;
+                    message.fields.push(self.field(&field_type, true)?);
+                }
+                "optional" | "required" => {
+                    let field_type = self.next()?;
+                    message.fields.push(self.field(&field_type, false)?);
+                }
+                "map" if self.peek() == Some("<") => {
+                    self.expect("<")?;
+                    let key = self.next()?;
+                    let key = Scalar::parse(&key)
+                        .ok_or_else(|| format!("Invalid map key type {}", key))?;
+                    self.expect(",")?;
+                    let value = self.next()?;
+                    self.expect(">")?;
+                    let mut field = self.field(&value, false)?;
+                    field.field_type = FieldType::Map(key, Box::new(field.field_type));
+                    message.fields.push(field);
+                }
+                field_type => {
+                    let field_type = field_type.to_string();
+                    message.fields.push(self.field(&field_type, false)?);
+                }
+            }
+        }
+        self.schema.messages.insert(full_name.clone(), message);
+        Ok(full_name)
+    }
+
+    /// `name = number [options];` after the field's type.
+    fn field(&mut self, field_type: &str, repeated: bool) -> Result<ProtoField, String> {
+        if field_type == "group" {
+            return Err("Groups are not supported".to_string());
+        }
+        let name = self.next()?;
+        self.expect("=")?;
+        let number = self.next()?;
+        let number: u32 = number
+            .parse()
+            .map_err(|_| format!("Invalid field number {} for {}", number, name))?;
+        self.skip_statement()?;
+        Ok(ProtoField {
+            name,
+            number,
+            field_type: Scalar::parse(field_type)
+                .map(FieldType::Scalar)
+                .unwrap_or_else(|| FieldType::Unresolved(field_type.to_string())),
+            repeated,
+        })
+    }
+
+    fn enumeration(&mut self, scope: &str) -> Result<(), String> {
+        let full_name = qualify(scope, &self.next()?);
+        self.expect("{")?;
+        let mut values = Vec::new();
+        loop {
+            match self.next()?.as_str() {
+                "}" => break,
+                ";" => {}
+                "option" | "reserved" => self.skip_statement()?,
+                name => {
+                    let name = name.to_string();
+                    self.expect("=")?;
+                    let mut number = self.next()?;
+                    if number == "-" {
+                        number = format!("-{}", self.next()?);
+                    }
+                    let number = parse_int(&number)
+                        .ok_or_else(|| format!("Invalid value {} for {}", number, name))?;
+                    self.skip_statement()?;
+                    values.push((name, number));
+                }
+            }
+        }
+        self.schema.enums.insert(full_name, values);
+        Ok(())
+    }
+}
+
+fn parse_int(text: &str) -> Option<i32> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+    let n = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse::<i64>().ok()?,
+    };
+    i32::try_from(if negative { -n } else { n }).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ORDERS: &str = r#"
+        syntax = "proto3";
+        package shop.v1;
+
+        // An order.
+        message Order {
+          int64 id = 1;
+          Status status = 2;
+          repeated Line lines = 3;
+          map<string, int32> tags = 4;
+          repeated sint32 deltas = 5 [packed = true];
+          oneof contact { string email = 6; }
+
+          message Line {
+            string sku = 1;
+            uint32 qty = 2;
+          }
+        }
+
+        enum Status { NEW = 0; PAID = 1; }
+    "#;
+
+    #[test]
+    fn test_round_trip_through_wire_format() {
+        let schema = ProtoSchema::parse(ORDERS).unwrap();
+        let order = json!({
+            "id": 42,
+            "status": "PAID",
+            "lines": [{ "sku": "A", "qty": 2 }, { "sku": "B", "qty": 1 }],
+            "tags": { "prio": 1 },
+            "deltas": [-1, 5],
+            "email": "ada@example.com"
+        });
+        let bytes = schema.encode("shop.v1.Order", &order).unwrap();
+        assert_eq!(&bytes[..2], &[0x08, 42]);
+        assert_eq!(schema.decode("shop.v1.Order", &bytes).unwrap(), order);
+        assert!(schema.encode("shop.v1.Order", &json!({ "idd": 1 })).is_err());
+    }
+
+    #[test]
+    fn test_message_indexes() {
+        let schema = ProtoSchema::parse(ORDERS).unwrap();
+        assert_eq!(schema.message_at(&[]).unwrap(), "shop.v1.Order");
+        assert_eq!(schema.message_at(&[0, 0]).unwrap(), "shop.v1.Order.Line");
+        assert!(schema.message_at(&[1]).is_err());
+        assert_eq!(schema.find_message(Some("Line")).unwrap(), ("shop.v1.Order.Line", vec![0, 0]));
+        assert_eq!(schema.find_message(None).unwrap(), ("shop.v1.Order", vec![0]));
+    }
+}
@@ -0,0 +1,347 @@
+//! Serializers of item data.
+//!
+//! Two places move a lot of item data through serialization, and each has
+//! its own choice of format in [`SerializationConfig`]:
+//!
+//! - ingest, where request and response bodies become [`GenericValue`]s
+//!   (webhooks, HTTP Request responses). [`IngestParser::SimdJson`] parses
+//!   with simd-json, which is faster on large bodies; it needs the
+//!   `simd-json` feature.
+//! - transfer of items between processes. [`TransferCodec::Rkyv`] encodes
+//!   them with rkyv, which decodes without parsing; it needs the `rkyv`
+//!   feature. The codec is opt-in: no path of the server passes items
+//!   through it, it is for embedders and transports that move items
+//!   between processes themselves. [`decode_items`] validates rkyv input,
+//!   so it may come from untrusted peers.
+//!
+//! Both parsers build [`GenericValue`]s directly, without a
+//! `serde_json::Value` in between. Encoded items start with a tag byte, so
+//! [`decode_items`] reads items from processes configured either way.
+//!
+//! `benches/serialization_bench.rs` compares the formats; run it with
+//! `cargo bench -p n8n-core --features simd-json,rkyv --bench serialization_bench`.
+
+use n8n_workflow::{GenericValue, NodeExecutionData};
+use serde::{Deserialize, Serialize};
+
+/// Tag of items encoded as JSON.
+const JSON_TAG: u8 = b'j';
+/// Tag of items encoded with rkyv.
+const RKYV_TAG: u8 = b'r';
+
+/// Serialization errors.
+#[derive(Debug, thiserror::Error)]
+pub enum SerializationError {
+    #[error("Invalid JSON: {0}")]
+    Json(String),
+    #[error("Invalid encoded items: {0}")]
+    Encoding(String),
+    #[error("Serializer '{0}' is not compiled in")]
+    Unavailable(&'static str),
+}
+
+/// Serialization settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SerializationConfig {
+    /// Parser of incoming JSON bodies.
+    pub ingest: IngestParser,
+    /// Encoding of items passed between processes.
+    pub transfer: TransferCodec,
+}
+
+/// Parser of incoming JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IngestParser {
+    #[default]
+    Json,
+    SimdJson,
+}
+
+impl IngestParser {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::SimdJson => "simd-json",
+        }
+    }
+
+    /// Whether this build can parse with it.
+    pub fn is_available(&self) -> bool {
+        match self {
+            Self::Json => true,
+            Self::SimdJson => cfg!(feature = "simd-json"),
+        }
+    }
+
+    /// Parse a JSON document.
+    pub fn parse(&self, bytes: &[u8]) -> Result<GenericValue, SerializationError> {
+        match self {
+            Self::Json => {
+                serde_json::from_slice(bytes).map_err(|e| SerializationError::Json(e.to_string()))
+            }
+            #[cfg(feature = "simd-json")]
+            Self::SimdJson => {
+                // simd-json parses in place
+                let mut bytes = bytes.to_vec();
+                simd_json::serde::from_slice(&mut bytes)
+                    .map_err(|e| SerializationError::Json(e.to_string()))
+            }
+            #[cfg(not(feature = "simd-json"))]
+            Self::SimdJson => Err(SerializationError::Unavailable(self.as_str())),
+        }
+    }
+}
+
+/// Encoding of items passed between processes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransferCodec {
+    #[default]
+    Json,
+    Rkyv,
+}
+
+impl TransferCodec {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Rkyv => "rkyv",
+        }
+    }
+
+    /// Whether this build can encode with it.
+    pub fn is_available(&self) -> bool {
+        match self {
+            Self::Json => true,
+            Self::Rkyv => cfg!(feature = "rkyv"),
+        }
+    }
+
+    /// Encode `items`, tagged with the codec.
+    pub fn encode(&self, items: &[NodeExecutionData]) -> Result<Vec<u8>, SerializationError> {
+        match self {
+            Self::Json => {
+                let mut bytes = vec![JSON_TAG];
+                serde_json::to_writer(&mut bytes, items)
+                    .map_err(|e| SerializationError::Encoding(e.to_string()))?;
+                Ok(bytes)
+            }
+            #[cfg(feature = "rkyv")]
+            Self::Rkyv => wire::encode(items),
+            #[cfg(not(feature = "rkyv"))]
+            Self::Rkyv => Err(SerializationError::Unavailable(self.as_str())),
+        }
+    }
+}
+
+/// Decode items encoded by [`TransferCodec::encode`] with any codec.
+pub fn decode_items(bytes: &[u8]) -> Result<Vec<NodeExecutionData>, SerializationError> {
+    match bytes.split_first() {
+        Some((&JSON_TAG, body)) => {
+            serde_json::from_slice(body).map_err(|e| SerializationError::Encoding(e.to_string()))
+        }
+        #[cfg(feature = "rkyv")]
+        Some((&RKYV_TAG, body)) => wire::decode(body),
+        #[cfg(not(feature = "rkyv"))]
+        Some((&RKYV_TAG, _)) => Err(SerializationError::Unavailable(TransferCodec::Rkyv.as_str())),
+        Some((tag, _)) => Err(SerializationError::Encoding(format!("unknown tag {:#04x}", tag))),
+        None => Err(SerializationError::Encoding("empty input".to_string())),
+    }
+}
+
+/// rkyv mirror of items. `GenericValue` lives in `n8n-workflow`, which
+/// does not depend on rkyv, so items are copied into these types.
+#[cfg(feature = "rkyv")]
+mod wire {
+    use super::{SerializationError, RKYV_TAG};
+    use n8n_workflow::{DataObject, GenericValue, NodeExecutionData};
+    use rkyv::ser::{ScratchSpace, Serializer};
+    use rkyv::{Archive, Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    // The recursive fields omit their bounds, which the serializer and
+    // validator bounds below put back, as in rkyv's JSON example.
+    #[derive(Archive, Serialize, Deserialize)]
+    #[archive(bound(serialize = "__S: ScratchSpace + Serializer"), check_bytes)]
+    #[archive_attr(check_bytes(
+        bound = "__C: rkyv::validation::ArchiveContext, <__C as rkyv::Fallible>::Error: std::error::Error"
+    ))]
+    enum WireValue {
+        Null,
+        Bool(bool),
+        Integer(i64),
+        Float(f64),
+        String(String),
+        Array(
+            #[omit_bounds]
+            #[archive_attr(omit_bounds)]
+            Vec<WireValue>,
+        ),
+        Object(
+            #[omit_bounds]
+            #[archive_attr(omit_bounds)]
+            HashMap<String, WireValue>,
+        ),
+    }
+
+    impl From<&GenericValue> for WireValue {
+        fn from(value: &GenericValue) -> Self {
+            match value {
+                GenericValue::Null => Self::Null,
+                GenericValue::Bool(b) => Self::Bool(*b),
+                GenericValue::Integer(i) => Self::Integer(*i),
+                GenericValue::Float(f) => Self::Float(*f),
+                GenericValue::String(s) => Self::String(s.clone()),
+                GenericValue::Array(items) => Self::Array(items.iter().map(Self::from).collect()),
+                GenericValue::Object(map) => {
+                    Self::Object(map.iter().map(|(k, v)| (k.clone(), Self::from(v))).collect())
+                }
+            }
+        }
+    }
+
+    impl From<WireValue> for GenericValue {
+        fn from(value: WireValue) -> Self {
+            match value {
+                WireValue::Null => Self::Null,
+                WireValue::Bool(b) => Self::Bool(b),
+                WireValue::Integer(i) => Self::Integer(i),
+                WireValue::Float(f) => Self::Float(f),
+                WireValue::String(s) => Self::String(s),
+                WireValue::Array(items) => Self::Array(items.into_iter().map(Self::from).collect()),
+                WireValue::Object(map) => {
+                    Self::Object(map.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+                }
+            }
+        }
+    }
+
+    /// An item's JSON, and its remaining fields as JSON when it has any;
+    /// those are rare and small next to the JSON.
+    #[derive(Archive, Serialize, Deserialize)]
+    #[archive(check_bytes)]
+    struct WireItem {
+        json: HashMap<String, WireValue>,
+        rest: Option<String>,
+    }
+
+    pub(super) fn encode(items: &[NodeExecutionData]) -> Result<Vec<u8>, SerializationError> {
+        let items = items
+            .iter()
+            .map(|item| {
+                let rest = if item.binary.is_some()
+                    || item.error.is_some()
+                    || item.paired_item.is_some()
+                    || item.evaluation_data.is_some()
+                {
+                    let rest = NodeExecutionData {
+                        json: DataObject::new(),
+                        binary: item.binary.clone(),
+                        error: item.error.clone(),
+                        paired_item: item.paired_item.clone(),
+                        evaluation_data: item.evaluation_data.clone(),
+                    };
+                    let rest = serde_json::to_string(&rest)
+                        .map_err(|e| SerializationError::Encoding(e.to_string()))?;
+                    Some(rest)
+                } else {
+                    None
+                };
+                Ok(WireItem {
+                    json: item.json.iter().map(|(k, v)| (k.clone(), v.into())).collect(),
+                    rest,
+                })
+            })
+            .collect::<Result<Vec<_>, SerializationError>>()?;
+
+        let archived = rkyv::to_bytes::<_, 4096>(&items)
+            .map_err(|e| SerializationError::Encoding(e.to_string()))?;
+        let mut bytes = Vec::with_capacity(archived.len() + 1);
+        bytes.push(RKYV_TAG);
+        bytes.extend_from_slice(&archived);
+        Ok(bytes)
+    }
+
+    pub(super) fn decode(body: &[u8]) -> Result<Vec<NodeExecutionData>, SerializationError> {
+        // The tag byte leaves the archive unaligned
+        let mut aligned = rkyv::AlignedVec::with_capacity(body.len());
+        aligned.extend_from_slice(body);
+        // Validated, as the bytes may be truncated or come from anyone.
+        let archived = rkyv::check_archived_root::<Vec<WireItem>>(&aligned)
+            .map_err(|e| SerializationError::Encoding(e.to_string()))?;
+        let items: Vec<WireItem> =
+            archived.deserialize(&mut rkyv::Infallible).unwrap_or_else(|never| match never {});
+
+        items
+            .into_iter()
+            .map(|item| {
+                let mut data = match item.rest {
+                    Some(rest) => serde_json::from_str::<NodeExecutionData>(&rest)
+                        .map_err(|e| SerializationError::Encoding(e.to_string()))?,
+                    None => NodeExecutionData::default(),
+                };
+                data.json = item.json.into_iter().map(|(k, v)| (k, v.into())).collect();
+                Ok(data)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::DataObject;
+
+    fn sample_items() -> Vec<NodeExecutionData> {
+        let json: DataObject = serde_json::from_str(
+            r#"{"id": 7, "price": 9.5, "tags": ["a", null, true], "owner": {"name": "Ada"}}"#,
+        )
+        .unwrap();
+        vec![
+            NodeExecutionData::new(json.clone()),
+            NodeExecutionData::new(json).with_paired_item(0, None),
+        ]
+    }
+
+    #[test]
+    fn test_parsers_agree() {
+        let body = br#"{"id": 7, "price": 9.5, "tags": ["a", null, true], "big": 1e3}"#;
+        let parsed = IngestParser::Json.parse(body).unwrap();
+        match &parsed {
+            GenericValue::Object(object) => {
+                assert_eq!(object.get("id"), Some(&GenericValue::Integer(7)))
+            }
+            other => panic!("expected an object, got {:?}", other),
+        }
+        assert!(IngestParser::Json.parse(b"{not json").is_err());
+
+        if IngestParser::SimdJson.is_available() {
+            assert_eq!(IngestParser::SimdJson.parse(body).unwrap(), parsed);
+        } else {
+            assert!(matches!(
+                IngestParser::SimdJson.parse(body),
+                Err(SerializationError::Unavailable(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_items_round_trip_with_every_codec() {
+        let items = sample_items();
+        let expected = serde_json::to_value(&items).unwrap();
+        for codec in [TransferCodec::Json, TransferCodec::Rkyv] {
+            if !codec.is_available() {
+                continue;
+            }
+            let bytes = codec.encode(&items).unwrap();
+            let decoded = decode_items(&bytes).unwrap();
+            assert_eq!(serde_json::to_value(&decoded).unwrap(), expected, "{}", codec.as_str());
+            // Truncated input is refused, not read past its end.
+            assert!(decode_items(&bytes[..bytes.len() / 2]).is_err(), "{}", codec.as_str());
+        }
+        assert!(decode_items(b"").is_err());
+        assert!(decode_items(b"x[]").is_err());
+    }
+}
@@ -0,0 +1,421 @@
+//! AWS Signature Version 4 request signing.
+//!
+//! [`SigV4Signer`] signs a built `reqwest::Request` for one region and
+//! service, so AWS APIs can be called over plain HTTP without an SDK.
+//! Credentials come from an [`AwsCredentialsProvider`]: fixed keys (with a
+//! session token for temporary credentials), the `AWS_*` environment
+//! variables, or the EC2 instance metadata service (IMDSv2), whose role
+//! credentials are cached until shortly before they expire.
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use parking_lot::RwLock;
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, OnceLock};
+use thiserror::Error;
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const IMDS_ENDPOINT: &str = "http://169.254.169.254";
+const IMDS_TOKEN_TTL_SECONDS: &str = "21600";
+
+/// Cached credentials are refreshed this long before they expire.
+const REFRESH_MARGIN_SECONDS: i64 = 300;
+
+/// Errors of signing and of fetching credentials.
+#[derive(Error, Debug)]
+pub enum SigV4Error {
+    #[error("AWS credentials unavailable: {0}")]
+    Credentials(String),
+
+    #[error("Instance metadata request failed: {0}")]
+    InstanceMetadata(String),
+
+    #[error("Failed to sign request: {0}")]
+    Sign(String),
+}
+
+/// An access key, with a session token for temporary credentials.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+    /// When temporary credentials stop working.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl AwsCredentials {
+    pub fn new(access_key_id: impl Into<String>, secret_access_key: impl Into<String>) -> Self {
+        Self {
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None,
+            expires_at: None,
+        }
+    }
+
+    pub fn with_session_token(mut self, token: impl Into<String>) -> Self {
+        self.session_token = Some(token.into());
+        self
+    }
+
+    /// Read n8n's `aws` credential data: `accessKeyId`, `secretAccessKey`
+    /// and, with `temporaryCredentials`, `sessionToken`.
+    pub fn from_credential_data(data: &Value) -> Result<Self, SigV4Error> {
+        let string = |key: &str| data.get(key).and_then(Value::as_str).filter(|s| !s.is_empty());
+        let missing = |key: &str| SigV4Error::Credentials(format!("{} is required", key));
+        let mut credentials = Self::new(
+            string("accessKeyId").ok_or_else(|| missing("accessKeyId"))?,
+            string("secretAccessKey").ok_or_else(|| missing("secretAccessKey"))?,
+        );
+        if data.get("temporaryCredentials") == Some(&Value::Bool(true)) {
+            let token = string("sessionToken").ok_or_else(|| missing("sessionToken"))?;
+            credentials = credentials.with_session_token(token);
+        }
+        Ok(credentials)
+    }
+
+    /// `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`.
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let mut credentials =
+            Self::new(var("AWS_ACCESS_KEY_ID")?, var("AWS_SECRET_ACCESS_KEY")?);
+        credentials.session_token = var("AWS_SESSION_TOKEN");
+        Some(credentials)
+    }
+
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at
+            .is_none_or(|expires_at| now + Duration::seconds(REFRESH_MARGIN_SECONDS) < expires_at)
+    }
+}
+
+/// Where an [`AwsCredentialsProvider`] gets credentials.
+#[derive(Debug, Clone)]
+pub enum CredentialSource {
+    Static(AwsCredentials),
+    Environment,
+    /// Role credentials of the EC2 instance, via IMDSv2.
+    InstanceMetadata,
+    /// The environment if it has credentials, else instance metadata.
+    Chain,
+}
+
+/// Role credentials as the metadata service returns them.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct InstanceCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    token: String,
+    expiration: DateTime<Utc>,
+}
+
+/// Supplies credentials for signing, caching fetched ones.
+pub struct AwsCredentialsProvider {
+    source: CredentialSource,
+    metadata_endpoint: String,
+    cached: RwLock<Option<AwsCredentials>>,
+}
+
+impl AwsCredentialsProvider {
+    pub fn new(source: CredentialSource) -> Self {
+        Self {
+            source,
+            metadata_endpoint: IMDS_ENDPOINT.to_string(),
+            cached: RwLock::new(None),
+        }
+    }
+
+    /// Provider shared by the whole process for `Chain`, so instance
+    /// credentials are fetched once rather than per request.
+    pub fn shared_chain() -> Arc<Self> {
+        static CHAIN: OnceLock<Arc<AwsCredentialsProvider>> = OnceLock::new();
+        CHAIN
+            .get_or_init(|| Arc::new(Self::new(CredentialSource::Chain)))
+            .clone()
+    }
+
+    /// Talk to another metadata endpoint, e.g. a local mock.
+    pub fn with_metadata_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.metadata_endpoint = endpoint.into();
+        self
+    }
+
+    pub async fn credentials(&self) -> Result<AwsCredentials, SigV4Error> {
+        match &self.source {
+            CredentialSource::Static(credentials) => Ok(credentials.clone()),
+            CredentialSource::Environment => AwsCredentials::from_env().ok_or_else(|| {
+                SigV4Error::Credentials("AWS_ACCESS_KEY_ID is not set".to_string())
+            }),
+            CredentialSource::InstanceMetadata => self.instance_credentials().await,
+            CredentialSource::Chain => match AwsCredentials::from_env() {
+                Some(credentials) => Ok(credentials),
+                None => self.instance_credentials().await,
+            },
+        }
+    }
+
+    async fn instance_credentials(&self) -> Result<AwsCredentials, SigV4Error> {
+        let cached = self.cached.read().clone();
+        if let Some(cached) = cached.filter(|cached| cached.is_fresh(Utc::now())) {
+            return Ok(cached);
+        }
+        let credentials = self.fetch_instance_credentials().await?;
+        *self.cached.write() = Some(credentials.clone());
+        Ok(credentials)
+    }
+
+    async fn fetch_instance_credentials(&self) -> Result<AwsCredentials, SigV4Error> {
+        let error = |e: reqwest::Error| SigV4Error::InstanceMetadata(e.to_string());
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(2))
+            .build()
+            .map_err(error)?;
+        let base = self.metadata_endpoint.trim_end_matches('/');
+
+        let token = client
+            .put(format!("{}/latest/api/token", base))
+            .header("X-aws-ec2-metadata-token-ttl-seconds", IMDS_TOKEN_TTL_SECONDS)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(error)?
+            .text()
+            .await
+            .map_err(error)?;
+        let get = |path: String| {
+            client
+                .get(format!("{}/latest/meta-data/iam/security-credentials/{}", base, path))
+                .header("X-aws-ec2-metadata-token", token.as_str())
+                .send()
+        };
+
+        let roles = get(String::new())
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(error)?
+            .text()
+            .await
+            .map_err(error)?;
+        let role = roles.lines().next().map(str::trim).filter(|r| !r.is_empty());
+        let role = role.ok_or_else(|| {
+            SigV4Error::InstanceMetadata("The instance has no IAM role".to_string())
+        })?;
+        let fetched: InstanceCredentials = get(role.to_string())
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(error)?
+            .json()
+            .await
+            .map_err(error)?;
+        Ok(AwsCredentials {
+            access_key_id: fetched.access_key_id,
+            secret_access_key: fetched.secret_access_key,
+            session_token: Some(fetched.token),
+            expires_at: Some(fetched.expiration),
+        })
+    }
+}
+
+/// Signs requests for one region and service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigV4Signer {
+    pub region: String,
+    pub service: String,
+}
+
+impl SigV4Signer {
+    pub fn new(region: impl Into<String>, service: impl Into<String>) -> Self {
+        Self {
+            region: region.into(),
+            service: service.into(),
+        }
+    }
+
+    /// Add `Authorization`, `x-amz-date` and, with a session token,
+    /// `x-amz-security-token` to `request`.
+    pub fn sign(
+        &self,
+        request: &mut reqwest::Request,
+        credentials: &AwsCredentials,
+    ) -> Result<(), SigV4Error> {
+        self.sign_at(request, credentials, Utc::now())
+    }
+
+    /// Sign as of `now`.
+    pub fn sign_at(
+        &self,
+        request: &mut reqwest::Request,
+        credentials: &AwsCredentials,
+        now: DateTime<Utc>,
+    ) -> Result<(), SigV4Error> {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = &amz_date[..8];
+        let is_s3 = self.service == "s3";
+
+        let payload_hash = match request.body() {
+            None => format!("{:x}", Sha256::digest(b"")),
+            Some(body) => match body.as_bytes() {
+                Some(bytes) => format!("{:x}", Sha256::digest(bytes)),
+                None => "UNSIGNED-PAYLOAD".to_string(),
+            },
+        };
+        let mut set = |name: &'static str, value: &str| -> Result<(), SigV4Error> {
+            let value = reqwest::header::HeaderValue::from_str(value)
+                .map_err(|e| SigV4Error::Sign(e.to_string()))?;
+            request.headers_mut().insert(name, value);
+            Ok(())
+        };
+        set("x-amz-date", &amz_date)?;
+        if let Some(token) = &credentials.session_token {
+            set("x-amz-security-token", token)?;
+        }
+        if is_s3 {
+            set("x-amz-content-sha256", &payload_hash)?;
+        }
+
+        let url = request.url();
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(SigV4Error::Sign("The URL has no host".to_string())),
+        };
+        let mut headers: Vec<(String, String)> = vec![("host".to_string(), host)];
+        for (name, value) in request.headers() {
+            let name = name.as_str().to_ascii_lowercase();
+            if name == "content-type" || name.starts_with("x-amz-") {
+                let value = value.to_str().map_err(|e| SigV4Error::Sign(e.to_string()))?;
+                headers.push((name, value.split_whitespace().collect::<Vec<_>>().join(" ")));
+            }
+        }
+        headers.sort();
+        let signed_headers: Vec<&str> = headers.iter().map(|(name, _)| name.as_str()).collect();
+        let signed_headers = signed_headers.join(";");
+        let canonical_headers: String =
+            headers.iter().map(|(name, value)| format!("{}:{}\n", name, value)).collect();
+
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            request.method().as_str(),
+            canonical_uri(url.path(), is_s3),
+            canonical_query(url),
+            canonical_headers,
+            signed_headers,
+            payload_hash
+        );
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+        let string_to_sign = format!(
+            "{}\n{}\n{}\n{:x}",
+            ALGORITHM,
+            amz_date,
+            scope,
+            Sha256::digest(canonical_request.as_bytes())
+        );
+
+        let key = [date, &self.region, &self.service, "aws4_request"].iter().fold(
+            format!("AWS4{}", credentials.secret_access_key).into_bytes(),
+            |key, part| hmac(&key, part.as_bytes()),
+        );
+        let signature: String =
+            hmac(&key, string_to_sign.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+
+        let authorization = format!(
+            "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+            ALGORITHM, credentials.access_key_id, scope, signed_headers, signature
+        );
+        let authorization = reqwest::header::HeaderValue::from_str(&authorization)
+            .map_err(|e| SigV4Error::Sign(e.to_string()))?;
+        request.headers_mut().insert(reqwest::header::AUTHORIZATION, authorization);
+        Ok(())
+    }
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// The URL path with each segment encoded again; S3 signs it as is.
+fn canonical_uri(path: &str, is_s3: bool) -> String {
+    if path.is_empty() {
+        return "/".to_string();
+    }
+    if is_s3 {
+        return path.to_string();
+    }
+    path.split('/')
+        .map(|segment| urlencoding::encode(segment).into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Query parameters encoded strictly and sorted by name, then value.
+fn canonical_query(url: &reqwest::Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| (urlencoding::encode(&k).into_owned(), urlencoding::encode(&v).into_owned()))
+        .collect();
+    pairs.sort();
+    let pairs: Vec<String> = pairs.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    pairs.join("&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_signature_matches_aws_example() {
+        // The IAM ListUsers example of the AWS signing documentation.
+        let mut request = reqwest::Client::new()
+            .get("https://iam.amazonaws.com/?Action=ListUsers&Version=2010-05-08")
+            .header("Content-Type", "application/x-www-form-urlencoded; charset=utf-8")
+            .build()
+            .unwrap();
+        let credentials =
+            AwsCredentials::new("AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY");
+        let now = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+        SigV4Signer::new("us-east-1", "iam").sign_at(&mut request, &credentials, now).unwrap();
+
+        assert_eq!(request.headers()["x-amz-date"], "20150830T123600Z");
+        assert_eq!(
+            request.headers()[reqwest::header::AUTHORIZATION],
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+
+    #[test]
+    fn test_temporary_credentials_sign_the_token() {
+        let credentials = AwsCredentials::from_credential_data(&serde_json::json!({
+            "accessKeyId": "AKID",
+            "secretAccessKey": "secret",
+            "temporaryCredentials": true,
+            "sessionToken": "token/1"
+        }))
+        .unwrap();
+        let mut request = reqwest::Client::new()
+            .put("https://bucket.s3.eu-west-1.amazonaws.com/a%20b.txt")
+            .body("hello")
+            .build()
+            .unwrap();
+        SigV4Signer::new("eu-west-1", "s3").sign(&mut request, &credentials).unwrap();
+
+        let headers = request.headers();
+        assert_eq!(headers["x-amz-security-token"], "token/1");
+        assert_eq!(
+            headers["x-amz-content-sha256"],
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        let authorization = headers[reqwest::header::AUTHORIZATION].to_str().unwrap();
+        assert!(authorization.contains(
+            "SignedHeaders=host;x-amz-content-sha256;x-amz-date;x-amz-security-token"
+        ));
+    }
+}
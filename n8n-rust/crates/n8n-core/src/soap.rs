@@ -0,0 +1,699 @@
+//! SOAP node.
+//!
+//! `n8n-nodes-base.soap` calls a SOAP operation once per input item. The
+//! item's fields become the operation element's children (`fields` picks
+//! and orders them; otherwise they are sent in alphabetical order), keys
+//! starting with `@` become attributes and `#text` the element text. The
+//! `wsSecurity` parameter adds a WS-Security UsernameToken header with the
+//! password as text or digest.
+//!
+//! Responses are converted to JSON the same way in reverse: one key per
+//! element under the SOAP body, namespace prefixes dropped, repeated
+//! elements as arrays and text left as strings. SOAP faults fail the node.
+
+use crate::error::ExecutionEngineError;
+use crate::executor::{NodeExecutor, NodeOutput};
+use crate::runtime::RuntimeContext;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use n8n_workflow::{
+    object_to_json, DataObject, Node, NodeExecutionData, NodeParameterValue, TaskDataConnections,
+};
+use serde_json::{Map, Value};
+use sha1::{Digest, Sha1};
+
+/// Node type of the SOAP node.
+pub const SOAP_NODE_TYPE: &str = "n8n-nodes-base.soap";
+
+const WSSE_NS: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-secext-1.0.xsd";
+const WSU_NS: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-utility-1.0.xsd";
+const USERNAME_TOKEN_PROFILE: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-username-token-profile-1.0";
+const BASE64_BINARY: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-soap-message-security-1.0#Base64Binary";
+
+/// SOAP protocol version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoapVersion {
+    V11,
+    V12,
+}
+
+impl SoapVersion {
+    fn envelope_namespace(self) -> &'static str {
+        match self {
+            SoapVersion::V11 => "http://schemas.xmlsoap.org/soap/envelope/",
+            SoapVersion::V12 => "http://www.w3.org/2003/05/soap-envelope",
+        }
+    }
+}
+
+/// How a UsernameToken carries the password.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordType {
+    /// The password itself; only safe over TLS.
+    Text,
+    /// `Base64(SHA-1(nonce + created + password))`.
+    Digest,
+}
+
+/// WS-Security UsernameToken credentials.
+#[derive(Debug, Clone)]
+pub struct UsernameToken {
+    pub username: String,
+    pub password: String,
+    pub password_type: PasswordType,
+}
+
+impl UsernameToken {
+    /// `wsse:Security` header with a fresh nonce and creation time.
+    pub fn header(&self) -> String {
+        let nonce: [u8; 16] = rand::random();
+        let created = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        self.header_with(&nonce, &created)
+    }
+
+    fn header_with(&self, nonce: &[u8], created: &str) -> String {
+        let (kind, password) = match self.password_type {
+            PasswordType::Text => ("PasswordText", self.password.clone()),
+            PasswordType::Digest => {
+                let mut hasher = Sha1::new();
+                hasher.update(nonce);
+                hasher.update(created.as_bytes());
+                hasher.update(self.password.as_bytes());
+                ("PasswordDigest", BASE64.encode(hasher.finalize()))
+            }
+        };
+        format!(
+            concat!(
+                r#"<wsse:Security soap:mustUnderstand="1" xmlns:wsse="{}" xmlns:wsu="{}">"#,
+                "<wsse:UsernameToken><wsse:Username>{}</wsse:Username>",
+                r#"<wsse:Password Type="{}#{}">{}</wsse:Password>"#,
+                r#"<wsse:Nonce EncodingType="{}">{}</wsse:Nonce>"#,
+                "<wsu:Created>{}</wsu:Created></wsse:UsernameToken></wsse:Security>",
+            ),
+            WSSE_NS,
+            WSU_NS,
+            escape(&self.username),
+            USERNAME_TOKEN_PROFILE,
+            kind,
+            escape(&password),
+            BASE64_BINARY,
+            BASE64.encode(nonce),
+            created,
+        )
+    }
+}
+
+/// The operation a SOAP call invokes.
+#[derive(Debug, Clone)]
+pub struct SoapCall {
+    pub version: SoapVersion,
+    pub namespace: String,
+    pub operation: String,
+    /// Whether the operation's child elements are in its namespace
+    /// (`elementFormDefault="qualified"` in the WSDL).
+    pub qualified: bool,
+}
+
+/// Envelope calling `call` with `body` as the operation element's content
+/// and `header` (already serialized) as the SOAP header.
+pub fn build_envelope(call: &SoapCall, body: &[(String, Value)], header: Option<&str>) -> String {
+    let mut out = String::from(r#"<?xml version="1.0" encoding="utf-8"?>"#);
+    out.push_str(&format!(
+        r#"<soap:Envelope xmlns:soap="{}">"#,
+        call.version.envelope_namespace()
+    ));
+    if let Some(header) = header {
+        out.push_str(&format!("<soap:Header>{}</soap:Header>", header));
+    }
+
+    // Qualified children inherit a default namespace; unqualified ones need
+    // the operation alone to be prefixed.
+    let (operation, xmlns) = if call.qualified {
+        (call.operation.clone(), "xmlns")
+    } else {
+        (format!("tns:{}", call.operation), "xmlns:tns")
+    };
+    out.push_str(&format!(
+        r#"<soap:Body><{} {}="{}">"#,
+        operation,
+        xmlns,
+        escape(&call.namespace)
+    ));
+    for (name, value) in body {
+        write_element(&mut out, name, value);
+    }
+    out.push_str(&format!("</{}></soap:Body></soap:Envelope>", operation));
+    out
+}
+
+fn write_element(out: &mut String, name: &str, value: &Value) {
+    match value {
+        Value::Array(values) => values.iter().for_each(|v| write_element(out, name, v)),
+        Value::Object(fields) => {
+            out.push('<');
+            out.push_str(name);
+            for (key, v) in fields {
+                if let Some(attribute) = key.strip_prefix('@') {
+                    out.push_str(&format!(r#" {}="{}""#, attribute, escape(&text_of(v))));
+                }
+            }
+            out.push('>');
+            for (key, v) in fields {
+                if key == "#text" {
+                    out.push_str(&escape(&text_of(v)));
+                } else if !key.starts_with('@') {
+                    write_element(out, key, v);
+                }
+            }
+            out.push_str(&format!("</{}>", name));
+        }
+        Value::Null => out.push_str(&format!("<{}/>", name)),
+        scalar => out.push_str(&format!("<{0}>{1}</{0}>", name, escape(&text_of(scalar)))),
+    }
+}
+
+fn text_of(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Content of the SOAP body of `xml` as JSON, one key per body element
+/// (usually just `<operation>Response`). Faults and malformed responses are
+/// returned as errors.
+pub fn parse_response(xml: &str) -> Result<Value, String> {
+    let envelope = parse_xml(xml)?;
+    if envelope.name != "Envelope" {
+        return Err(format!("Expected a SOAP Envelope, found <{}>", envelope.name));
+    }
+    let body = envelope
+        .child("Body")
+        .ok_or_else(|| "SOAP Envelope has no Body".to_string())?;
+    if let Some(fault) = body.child("Fault") {
+        return Err(fault_message(fault));
+    }
+
+    let mut object = Map::new();
+    for child in &body.children {
+        insert_child(&mut object, &child.name, element_to_json(child));
+    }
+    Ok(Value::Object(object))
+}
+
+fn fault_message(fault: &XmlElement) -> String {
+    let text = |path: &[&str]| -> Option<String> {
+        let mut element = fault;
+        for name in path {
+            element = element.child(name)?;
+        }
+        Some(element.text.trim().to_string())
+    };
+    // SOAP 1.1 and 1.2 name the parts differently.
+    let code = text(&["faultcode"]).or_else(|| text(&["Code", "Value"]));
+    let reason = text(&["faultstring"]).or_else(|| text(&["Reason", "Text"]));
+    format!("SOAP fault {}: {}", code.unwrap_or_default(), reason.unwrap_or_default())
+}
+
+fn element_to_json(element: &XmlElement) -> Value {
+    if element.attributes.iter().any(|(name, value)| name == "nil" && value == "true") {
+        return Value::Null;
+    }
+    if element.attributes.is_empty() && element.children.is_empty() {
+        return Value::String(element.text.clone());
+    }
+
+    let mut object = Map::new();
+    for (name, value) in &element.attributes {
+        object.insert(format!("@{}", name), Value::String(value.clone()));
+    }
+    for child in &element.children {
+        insert_child(&mut object, &child.name, element_to_json(child));
+    }
+    let text = element.text.trim();
+    if !text.is_empty() {
+        object.insert("#text".to_string(), Value::String(text.to_string()));
+    }
+    Value::Object(object)
+}
+
+/// Insert `value` under `name`, turning repeated names into arrays.
+fn insert_child(object: &mut Map<String, Value>, name: &str, value: Value) {
+    match object.get_mut(name) {
+        Some(Value::Array(values)) => values.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+        None => {
+            object.insert(name.to_string(), value);
+        }
+    }
+}
+
+/// An XML element with namespace prefixes and declarations removed.
+#[derive(Debug, Default)]
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    text: String,
+}
+
+impl XmlElement {
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|child| child.name == name)
+    }
+}
+
+fn parse_xml(input: &str) -> Result<XmlElement, String> {
+    let mut parser = XmlParser {
+        input: input.trim_start_matches('\u{feff}'),
+        pos: 0,
+    };
+    parser.skip_misc()?;
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if parser.pos < parser.input.len() {
+        return Err("Unexpected content after the root element".to_string());
+    }
+    Ok(root)
+}
+
+/// Just enough of XML for SOAP responses: elements, attributes, text,
+/// CDATA and entity references. DTDs are skipped, not interpreted.
+struct XmlParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> XmlParser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        let matched = self.rest().starts_with(prefix);
+        if matched {
+            self.pos += prefix.len();
+        }
+        matched
+    }
+
+    /// Consume up to and including `end`, returning what came before it.
+    fn until(&mut self, end: &str) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let index = rest
+            .find(end)
+            .ok_or_else(|| format!("Expected {:?} after offset {}", end, self.pos))?;
+        self.pos += index + end.len();
+        Ok(&rest[..index])
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skip declarations, comments and processing instructions.
+    fn skip_misc(&mut self) -> Result<(), String> {
+        loop {
+            self.skip_whitespace();
+            if self.eat("<?") {
+                self.until("?>")?;
+            } else if self.eat("<!--") {
+                self.until("-->")?;
+            } else if self.eat("<!") {
+                self.until(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '='))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(format!("Expected a name at offset {}", self.pos));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    fn element(&mut self) -> Result<XmlElement, String> {
+        if !self.eat("<") {
+            return Err(format!("Expected an element at offset {}", self.pos));
+        }
+        let qname = self.name()?;
+        let mut element = XmlElement {
+            name: local_name(qname).to_string(),
+            ..Default::default()
+        };
+
+        loop {
+            self.skip_whitespace();
+            if self.eat("/>") {
+                return Ok(element);
+            }
+            if self.eat(">") {
+                break;
+            }
+            let attribute = self.name()?;
+            self.skip_whitespace();
+            if !self.eat("=") {
+                return Err(format!("Expected '=' after attribute {}", attribute));
+            }
+            self.skip_whitespace();
+            let quote = if self.eat("\"") {
+                "\""
+            } else if self.eat("'") {
+                "'"
+            } else {
+                return Err(format!("Expected a quoted value for attribute {}", attribute));
+            };
+            let value = decode_entities(self.until(quote)?)?;
+            if attribute != "xmlns" && !attribute.starts_with("xmlns:") {
+                element.attributes.push((local_name(attribute).to_string(), value));
+            }
+        }
+
+        loop {
+            let rest = self.rest();
+            let text = rest
+                .find('<')
+                .ok_or_else(|| format!("Element <{}> is not closed", qname))?;
+            element.text.push_str(&decode_entities(&rest[..text])?);
+            self.pos += text;
+
+            if self.eat("</") {
+                let end = self.name()?;
+                if end != qname {
+                    return Err(format!("Expected </{}>, found </{}>", qname, end));
+                }
+                self.skip_whitespace();
+                if !self.eat(">") {
+                    return Err(format!("Expected '>' after </{}", end));
+                }
+                return Ok(element);
+            } else if self.eat("<![CDATA[") {
+                element.text.push_str(self.until("]]>")?);
+            } else if self.eat("<!--") {
+                self.until("-->")?;
+            } else if self.eat("<?") {
+                self.until("?>")?;
+            } else {
+                element.children.push(self.element()?);
+            }
+        }
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit_once(':').map_or(name, |(_, local)| local)
+}
+
+fn decode_entities(text: &str) -> Result<String, String> {
+    if !text.contains('&') {
+        return Ok(text.to_string());
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .map(|i| start + i)
+            .ok_or_else(|| "Unterminated entity reference".to_string())?;
+        let entity = &rest[start + 1..end];
+        let decoded = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(decimal) = entity.strip_prefix('#') {
+                    decimal.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| format!("Unknown entity &{};", entity))?
+            }
+        };
+        out.push(decoded);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// SOAP node - calls a SOAP operation for each input item.
+pub struct SoapExecutor;
+
+impl SoapExecutor {
+    fn string_param(node: &Node, key: &str) -> Option<String> {
+        match node.parameters.get(key) {
+            Some(NodeParameterValue::String(s)) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    fn required_param(node: &Node, key: &str) -> Result<String, ExecutionEngineError> {
+        Self::string_param(node, key).ok_or_else(|| ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message: format!("Parameter '{}' is required for SOAP node", key),
+        })
+    }
+
+    fn username_token(node: &Node) -> Option<UsernameToken> {
+        let Some(NodeParameterValue::Object(security)) = node.parameters.get("wsSecurity") else {
+            return None;
+        };
+        let field = |key: &str| match security.get(key) {
+            Some(NodeParameterValue::String(s)) => s.clone(),
+            _ => String::new(),
+        };
+        let username = field("username");
+        if username.is_empty() {
+            return None;
+        }
+        Some(UsernameToken {
+            username,
+            password: field("password"),
+            password_type: match field("passwordType").as_str() {
+                "PasswordDigest" => PasswordType::Digest,
+                _ => PasswordType::Text,
+            },
+        })
+    }
+
+    /// Item fields sent as the operation's children.
+    fn item_body(item: &NodeExecutionData, fields: Option<&[String]>) -> Vec<(String, Value)> {
+        let json = object_to_json(&item.json);
+        match fields {
+            Some(fields) => fields
+                .iter()
+                .filter_map(|name| json.get(name).map(|v| (name.clone(), v.clone())))
+                .collect(),
+            None => json.into_iter().collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl NodeExecutor for SoapExecutor {
+    fn node_type(&self) -> &str {
+        SOAP_NODE_TYPE
+    }
+
+    async fn execute(
+        &self,
+        node: &Node,
+        input: &TaskDataConnections,
+        context: &RuntimeContext,
+    ) -> Result<NodeOutput, ExecutionEngineError> {
+        let node_error = |message: String| ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message,
+        };
+
+        let url = Self::required_param(node, "url")?;
+        let call = SoapCall {
+            version: match Self::string_param(node, "soapVersion").as_deref() {
+                Some("1.2") => SoapVersion::V12,
+                _ => SoapVersion::V11,
+            },
+            namespace: Self::required_param(node, "namespace")?,
+            operation: Self::required_param(node, "operation")?,
+            qualified: Self::string_param(node, "elementForm").as_deref() != Some("unqualified"),
+        };
+        let action = Self::string_param(node, "soapAction").unwrap_or_else(|| {
+            format!("{}/{}", call.namespace.trim_end_matches('/'), call.operation)
+        });
+        let content_type = match call.version {
+            SoapVersion::V11 => "text/xml; charset=utf-8".to_string(),
+            SoapVersion::V12 => {
+                format!("application/soap+xml; charset=utf-8; action=\"{}\"", action)
+            }
+        };
+        let fields: Option<Vec<String>> = Self::string_param(node, "fields").map(|fields| {
+            fields
+                .split(',')
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+                .collect()
+        });
+        let security = Self::username_token(node);
+
+        let timeout_ms = match node.parameters.get("timeout") {
+            Some(NodeParameterValue::Number(n)) => *n as u64,
+            _ => 10000,
+        };
+        let client = context.http_client(std::time::Duration::from_millis(timeout_ms))?;
+        let endpoint = reqwest::Url::parse(&url)
+            .map_err(|e| node_error(format!("Invalid endpoint URL '{}': {}", url, e)))?;
+        context
+            .egress()
+            .check(&endpoint)
+            .await
+            .map_err(|e| node_error(e.to_string()))?;
+
+        let main_input = input.get("main").and_then(|v| v.first());
+        let items = main_input.cloned().unwrap_or_else(|| vec![NodeExecutionData::default()]);
+        let cancel_token = context.cancellation_token();
+        let mut output = Vec::new();
+
+        for item in &items {
+            if context.is_canceled() {
+                return Err(ExecutionEngineError::Canceled);
+            }
+
+            let header = security.as_ref().map(UsernameToken::header);
+            let body = Self::item_body(item, fields.as_deref());
+            let envelope = build_envelope(&call, &body, header.as_deref());
+
+            let mut request = client
+                .post(&url)
+                .header("Content-Type", content_type.as_str())
+                .body(envelope);
+            if call.version == SoapVersion::V11 {
+                request = request.header("SOAPAction", format!("\"{}\"", action));
+            }
+
+            let response = tokio::select! {
+                result = request.send() => result.map_err(|e| {
+                    if e.is_timeout() {
+                        node_error(format!("SOAP request timed out after {}ms", timeout_ms))
+                    } else {
+                        node_error(format!("SOAP request failed: {}", e))
+                    }
+                })?,
+                _ = cancel_token.cancelled() => return Err(ExecutionEngineError::Canceled),
+            };
+            let status = response.status().as_u16();
+            let text = response
+                .text()
+                .await
+                .map_err(|e| node_error(format!("Failed to read response body: {}", e)))?;
+
+            // Faults usually arrive with status 500, so the body decides.
+            let json = parse_response(&text)
+                .map_err(|e| node_error(format!("{} (HTTP {})", e, status)))?;
+            let json: DataObject = serde_json::from_value(json)
+                .map_err(|e| ExecutionEngineError::Internal(e.to_string()))?;
+            output.push(NodeExecutionData::new(json));
+        }
+
+        Ok(vec![output])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_envelope_with_digest_username_token() {
+        let call = SoapCall {
+            version: SoapVersion::V11,
+            namespace: "urn:orders".to_string(),
+            operation: "CreateOrder".to_string(),
+            qualified: false,
+        };
+        let token = UsernameToken {
+            username: "api".to_string(),
+            password: "secret".to_string(),
+            password_type: PasswordType::Digest,
+        };
+        let header = token.header_with(b"0123456789abcdef", "2026-01-01T00:00:00.000Z");
+        let body = vec![
+            ("customer".to_string(), json!({ "@id": "c-1", "#text": "Ada & Co" })),
+            ("line".to_string(), json!([{ "sku": "A" }, { "sku": "B" }])),
+        ];
+        let envelope = build_envelope(&call, &body, Some(&header));
+
+        assert!(envelope.contains(r#"<tns:CreateOrder xmlns:tns="urn:orders">"#));
+        assert!(envelope.contains(r#"<customer id="c-1">Ada &amp; Co</customer>"#));
+        assert!(envelope.contains("<line><sku>A</sku></line><line><sku>B</sku></line>"));
+        assert!(envelope.contains("#PasswordDigest\">Jcd++c9gZ/c4zFSpJtkPJ3kxLec=</"));
+        assert!(envelope.contains(">MDEyMzQ1Njc4OWFiY2RlZg==</wsse:Nonce>"));
+    }
+
+    #[test]
+    fn test_parse_response_and_faults() {
+        let response = r#"<?xml version="1.0"?>
+            <s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">
+              <s:Body>
+                <m:GetOrderResponse xmlns:m="urn:orders">
+                  <m:status code="1">Shipped &lt;EU&gt;</m:status>
+                  <m:item>A</m:item>
+                  <m:item><![CDATA[B & C]]></m:item>
+                  <m:note xsi:nil="true" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"/>
+                </m:GetOrderResponse>
+              </s:Body>
+            </s:Envelope>"#;
+        assert_eq!(
+            parse_response(response).unwrap(),
+            json!({ "GetOrderResponse": {
+                "status": { "@code": "1", "#text": "Shipped <EU>" },
+                "item": ["A", "B & C"],
+                "note": null,
+            }})
+        );
+
+        let fault = r#"<soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope">
+            <soap:Body><soap:Fault>
+              <soap:Code><soap:Value>soap:Sender</soap:Value></soap:Code>
+              <soap:Reason><soap:Text xml:lang="en">Unknown order</soap:Text></soap:Reason>
+            </soap:Fault></soap:Body></soap:Envelope>"#;
+        assert_eq!(parse_response(fault).unwrap_err(), "SOAP fault soap:Sender: Unknown order");
+        assert!(parse_response("<a><b></a>").is_err());
+    }
+}
@@ -0,0 +1,292 @@
+//! Workflow statistics.
+//!
+//! As in n8n, the engine counts the executions of each workflow after every
+//! run, under one of these names:
+//!
+//! - `manual_success` and `manual_error` for manual executions;
+//! - `production_success` and `production_error` for all others.
+//!
+//! Executions that fail before or outside the run, such as canceled ones,
+//! count as errors. `data_loaded` is recorded once, the first time a
+//! production execution's trigger produced data. Each statistic also keeps
+//! the time of its latest event.
+//!
+//! Counts are kept in memory by default; the Postgres repository in
+//! `n8n-db` keeps them in `workflow_statistics`.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use n8n_workflow::{
+    connection::CONNECTION_MAIN, ExecutionStatus, Run, Workflow, WorkflowExecuteMode,
+};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// What a statistic counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatisticsName {
+    ProductionSuccess,
+    ProductionError,
+    ManualSuccess,
+    ManualError,
+    DataLoaded,
+}
+
+impl StatisticsName {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ProductionSuccess => "production_success",
+            Self::ProductionError => "production_error",
+            Self::ManualSuccess => "manual_success",
+            Self::ManualError => "manual_error",
+            Self::DataLoaded => "data_loaded",
+        }
+    }
+
+    /// The count an execution in `mode` adds to.
+    pub fn for_execution(mode: WorkflowExecuteMode, success: bool) -> Self {
+        match (mode == WorkflowExecuteMode::Manual, success) {
+            (true, true) => Self::ManualSuccess,
+            (true, false) => Self::ManualError,
+            (false, true) => Self::ProductionSuccess,
+            (false, false) => Self::ProductionError,
+        }
+    }
+}
+
+impl std::str::FromStr for StatisticsName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "production_success" => Ok(Self::ProductionSuccess),
+            "production_error" => Ok(Self::ProductionError),
+            "manual_success" => Ok(Self::ManualSuccess),
+            "manual_error" => Ok(Self::ManualError),
+            "data_loaded" => Ok(Self::DataLoaded),
+            other => Err(format!("Unknown workflow statistic '{}'", other)),
+        }
+    }
+}
+
+/// One statistic of a workflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowStatistic {
+    pub workflow_id: String,
+    pub name: StatisticsName,
+    pub count: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_event: Option<DateTime<Utc>>,
+}
+
+/// Where workflow statistics are kept. Both writes must be atomic, as
+/// several executions of a workflow may finish at once.
+#[async_trait]
+pub trait StatisticsBackend: Send + Sync {
+    /// Add one to `name` of `workflow_id`, returning the new count.
+    async fn increment(
+        &self,
+        workflow_id: &str,
+        name: StatisticsName,
+        at: DateTime<Utc>,
+    ) -> Result<u64, String>;
+
+    /// Record `name` of `workflow_id` with a count of one unless it is
+    /// already there. Returns whether it was recorded.
+    async fn insert_once(
+        &self,
+        workflow_id: &str,
+        name: StatisticsName,
+        at: DateTime<Utc>,
+    ) -> Result<bool, String>;
+
+    /// Statistics of `workflow_id`.
+    async fn for_workflow(&self, workflow_id: &str) -> Result<Vec<WorkflowStatistic>, String>;
+}
+
+/// In-process statistics, lost on restart.
+#[derive(Default)]
+pub struct MemoryStatistics {
+    counts: Mutex<HashMap<(String, StatisticsName), (u64, DateTime<Utc>)>>,
+}
+
+impl MemoryStatistics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl StatisticsBackend for MemoryStatistics {
+    async fn increment(
+        &self,
+        workflow_id: &str,
+        name: StatisticsName,
+        at: DateTime<Utc>,
+    ) -> Result<u64, String> {
+        let mut counts = self.counts.lock();
+        let entry = counts.entry((workflow_id.to_string(), name)).or_insert((0, at));
+        entry.0 += 1;
+        entry.1 = at;
+        Ok(entry.0)
+    }
+
+    async fn insert_once(
+        &self,
+        workflow_id: &str,
+        name: StatisticsName,
+        at: DateTime<Utc>,
+    ) -> Result<bool, String> {
+        let mut counts = self.counts.lock();
+        let key = (workflow_id.to_string(), name);
+        if counts.contains_key(&key) {
+            return Ok(false);
+        }
+        counts.insert(key, (1, at));
+        Ok(true)
+    }
+
+    async fn for_workflow(&self, workflow_id: &str) -> Result<Vec<WorkflowStatistic>, String> {
+        let mut statistics: Vec<_> = self
+            .counts
+            .lock()
+            .iter()
+            .filter(|((id, _), _)| id == workflow_id)
+            .map(|((id, name), (count, at))| WorkflowStatistic {
+                workflow_id: id.clone(),
+                name: *name,
+                count: *count,
+                latest_event: Some(*at),
+            })
+            .collect();
+        statistics.sort_by_key(|s| s.name.as_str());
+        Ok(statistics)
+    }
+}
+
+/// The engine's workflow statistics. Clones share the backend, which may
+/// be replaced once a durable one becomes available.
+#[derive(Clone)]
+pub struct WorkflowStatistics {
+    backend: Arc<RwLock<Arc<dyn StatisticsBackend>>>,
+}
+
+impl Default for WorkflowStatistics {
+    fn default() -> Self {
+        Self::new(Arc::new(MemoryStatistics::new()))
+    }
+}
+
+impl WorkflowStatistics {
+    pub fn new(backend: Arc<dyn StatisticsBackend>) -> Self {
+        Self {
+            backend: Arc::new(RwLock::new(backend)),
+        }
+    }
+
+    /// Keep statistics in `backend` from now on.
+    pub fn set_backend(&self, backend: Arc<dyn StatisticsBackend>) {
+        *self.backend.write() = backend;
+    }
+
+    fn backend(&self) -> Arc<dyn StatisticsBackend> {
+        self.backend.read().clone()
+    }
+
+    /// Count an execution of `workflow` in `mode`; `run` is `None` when it
+    /// failed without a run. Failures are only logged: statistics never
+    /// fail an execution.
+    pub async fn record(&self, workflow: &Workflow, mode: WorkflowExecuteMode, run: Option<&Run>) {
+        if workflow.id.is_empty() {
+            return;
+        }
+        let now = Utc::now();
+        let success = run.is_some_and(|run| run.status == ExecutionStatus::Success);
+        let name = StatisticsName::for_execution(mode, success);
+        let backend = self.backend();
+        if let Err(e) = backend.increment(&workflow.id, name, now).await {
+            tracing::warn!(workflow_id = %workflow.id, error = %e, "Failed to count execution");
+        }
+
+        let production = mode != WorkflowExecuteMode::Manual;
+        if production && run.is_some_and(|run| loaded_data(workflow, run)) {
+            let recorded = backend.insert_once(&workflow.id, StatisticsName::DataLoaded, now);
+            if let Err(e) = recorded.await {
+                tracing::warn!(
+                    workflow_id = %workflow.id,
+                    error = %e,
+                    "Failed to record loaded data"
+                );
+            }
+        }
+    }
+
+    /// Statistics of `workflow_id`.
+    pub async fn for_workflow(&self, workflow_id: &str) -> Result<Vec<WorkflowStatistic>, String> {
+        self.backend().for_workflow(workflow_id).await
+    }
+}
+
+/// Whether a start node of `workflow` output an item with data in `run`.
+fn loaded_data(workflow: &Workflow, run: &Run) -> bool {
+    let Ok(start_nodes) = crate::engine::find_start_nodes(workflow) else {
+        return false;
+    };
+    start_nodes
+        .iter()
+        .filter_map(|node| run.data.result_data.run_data.get(node))
+        .flatten()
+        .filter_map(|task| task.data.as_ref()?.get(CONNECTION_MAIN))
+        .flatten()
+        .flatten()
+        .any(|item| !item.json.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::{DataObject, GenericValue, NodeExecutionData, TaskData};
+
+    #[tokio::test]
+    async fn test_executions_are_counted_by_mode_and_status() {
+        let statistics = WorkflowStatistics::default();
+        let mut workflow = Workflow::new("Orders");
+        workflow.id = "wf".to_string();
+        workflow.add_node(n8n_workflow::Node::new("Hook", "n8n-nodes-base.webhook"));
+
+        let mut run = Run::new(WorkflowExecuteMode::Webhook);
+        let mut item = DataObject::new();
+        item.insert("order".to_string(), GenericValue::Integer(1));
+        let mut task = TaskData::new();
+        task.data = Some(HashMap::from([(
+            CONNECTION_MAIN.to_string(),
+            vec![vec![NodeExecutionData::new(item)]],
+        )]));
+        run.data.result_data.run_data.insert("Hook".to_string(), vec![task]);
+        run.finish(ExecutionStatus::Success);
+
+        statistics.record(&workflow, WorkflowExecuteMode::Webhook, Some(&run)).await;
+        statistics.record(&workflow, WorkflowExecuteMode::Webhook, Some(&run)).await;
+        statistics.record(&workflow, WorkflowExecuteMode::Manual, None).await;
+
+        let counts: HashMap<_, _> = statistics
+            .for_workflow("wf")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.name, s.count))
+            .collect();
+        assert_eq!(
+            counts,
+            HashMap::from([
+                (StatisticsName::ProductionSuccess, 2),
+                (StatisticsName::ManualError, 1),
+                (StatisticsName::DataLoaded, 1),
+            ])
+        );
+    }
+}
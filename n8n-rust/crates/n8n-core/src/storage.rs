@@ -1,26 +1,142 @@
 //! Storage backends for workflow data.
 
 use crate::error::ExecutionEngineError;
+use crate::patch::PatchOperation;
 use async_trait::async_trait;
-use n8n_workflow::{Run, Workflow};
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use n8n_workflow::{Node, Run, Workflow, WorkflowConnections};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Versions [`MemoryWorkflowStorage`] keeps per workflow.
+pub const MAX_MEMORY_VERSIONS: usize = 50;
+
+/// A saved version of a workflow.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowVersion {
+    pub version_id: String,
+    /// Version this one was saved over; absent for the first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_version_id: Option<String>,
+    pub name: String,
+    pub nodes: Vec<Node>,
+    pub connections: WorkflowConnections,
+    /// Operations of a patch; absent for full saves.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operations: Option<Vec<PatchOperation>>,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl WorkflowVersion {
+    /// `workflow` as saved over `previous_version_id`.
+    pub fn of(
+        workflow: &Workflow,
+        previous_version_id: Option<String>,
+        operations: Option<&[PatchOperation]>,
+    ) -> Self {
+        Self {
+            version_id: workflow.version_id.clone().unwrap_or_default(),
+            previous_version_id,
+            name: workflow.name.clone(),
+            nodes: workflow.nodes.clone(),
+            connections: workflow.connections.clone(),
+            operations: operations.map(<[PatchOperation]>::to_vec),
+            saved_at: Utc::now(),
+        }
+    }
+}
+
 /// Trait for workflow storage backends.
 #[async_trait]
 pub trait WorkflowStorage: Send + Sync {
     /// Get a workflow by ID.
     async fn get_workflow(&self, id: &str) -> Result<Option<Workflow>, ExecutionEngineError>;
 
-    /// Save a workflow.
+    /// Save a workflow under a new version ID.
+    ///
+    /// When `workflow.version_id` is set, the stored workflow must still be
+    /// at that version, or the save fails with
+    /// [`ExecutionEngineError::VersionConflict`].
     async fn save_workflow(&self, workflow: &Workflow) -> Result<(), ExecutionEngineError>;
 
+    /// Save a workflow like [`Self::save_workflow`], and record the saved
+    /// version in its history in the same write, with the `operations` of
+    /// the patch that produced it. Backends without history only save it.
+    async fn save_workflow_version(
+        &self,
+        workflow: &Workflow,
+        operations: Option<&[PatchOperation]>,
+    ) -> Result<(), ExecutionEngineError> {
+        let _ = operations;
+        self.save_workflow(workflow).await
+    }
+
+    /// Up to `limit` saved versions of a workflow, newest first.
+    async fn workflow_history(
+        &self,
+        id: &str,
+        limit: usize,
+    ) -> Result<Vec<WorkflowVersion>, ExecutionEngineError> {
+        let _ = (id, limit);
+        Ok(Vec::new())
+    }
+
     /// Delete a workflow.
     async fn delete_workflow(&self, id: &str) -> Result<bool, ExecutionEngineError>;
 
     /// List all workflows.
     async fn list_workflows(&self) -> Result<Vec<Workflow>, ExecutionEngineError>;
+
+    /// Workflows with words starting with every word of `query` in their
+    /// name, description or node types, best matches first. Scans
+    /// [`Self::list_workflows`]; backends with a search index override it.
+    async fn search_workflows(&self, query: &str) -> Result<Vec<Workflow>, ExecutionEngineError> {
+        let terms: Vec<String> = words(query).collect();
+        let mut matches: Vec<(u32, Workflow)> = self
+            .list_workflows()
+            .await?
+            .into_iter()
+            .filter_map(|workflow| Some((search_score(&workflow, &terms)?, workflow)))
+            .collect();
+        matches.sort_by(|a, b| b.0.cmp(&a.0));
+        Ok(matches.into_iter().map(|(_, workflow)| workflow).collect())
+    }
+}
+
+/// Lowercase words of `text`; dots and dashes split words, so
+/// `n8n-nodes-base.httpRequest` holds `httprequest`.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// How well `workflow` matches `terms`: each term scores by the best field
+/// holding a word it starts, the name scoring highest. `None` when a term
+/// matches nowhere or there are no terms.
+fn search_score(workflow: &Workflow, terms: &[String]) -> Option<u32> {
+    if terms.is_empty() {
+        return None;
+    }
+    let node_types: Vec<&str> = workflow.nodes.iter().map(|n| n.node_type.as_str()).collect();
+    let fields = [
+        (3, workflow.name.clone()),
+        (2, workflow.description.clone().unwrap_or_default()),
+        (1, node_types.join(" ")),
+    ];
+    let fields: Vec<(u32, Vec<String>)> =
+        fields.into_iter().map(|(weight, text)| (weight, words(&text).collect())).collect();
+    terms.iter().try_fold(0, |score, term| {
+        let best = fields
+            .iter()
+            .filter(|(_, words)| words.iter().any(|word| word.starts_with(term.as_str())))
+            .map(|(weight, _)| *weight)
+            .max()?;
+        Some(score + best)
+    })
 }
 
 /// Trait for execution storage backends.
@@ -42,15 +158,45 @@ pub trait ExecutionStorage: Send + Sync {
     ) -> Result<Vec<(String, Run)>, ExecutionEngineError>;
 }
 
-/// In-memory workflow storage (for testing and development).
+/// In-memory workflow storage (for testing and development). Keeps the
+/// last [`MAX_MEMORY_VERSIONS`] versions of each workflow.
 pub struct MemoryWorkflowStorage {
-    workflows: Arc<RwLock<HashMap<String, Workflow>>>,
+    workflows: Arc<RwLock<MemoryWorkflows>>,
+}
+
+#[derive(Default)]
+struct MemoryWorkflows {
+    current: HashMap<String, Workflow>,
+    history: HashMap<String, VecDeque<WorkflowVersion>>,
+}
+
+impl MemoryWorkflows {
+    /// Store `workflow`, returning it as stored and the version it replaced.
+    fn save(
+        &mut self,
+        workflow: &Workflow,
+    ) -> Result<(Workflow, Option<String>), ExecutionEngineError> {
+        let mut saved = workflow.clone();
+        let previous = self.current.get(&workflow.id).and_then(|w| w.version_id.clone());
+        match (self.current.get(&workflow.id), &workflow.version_id) {
+            (Some(stored), Some(expected)) if stored.version_id.as_ref() != Some(expected) => {
+                return Err(ExecutionEngineError::VersionConflict(format!(
+                    "workflow {} is no longer at version {}",
+                    workflow.id, expected
+                )));
+            }
+            (None, Some(_)) => {}
+            _ => saved.version_id = Some(uuid::Uuid::new_v4().to_string()),
+        }
+        self.current.insert(saved.id.clone(), saved.clone());
+        Ok((saved, previous))
+    }
 }
 
 impl MemoryWorkflowStorage {
     pub fn new() -> Self {
         Self {
-            workflows: Arc::new(RwLock::new(HashMap::new())),
+            workflows: Arc::new(RwLock::new(MemoryWorkflows::default())),
         }
     }
 }
@@ -64,23 +210,45 @@ impl Default for MemoryWorkflowStorage {
 #[async_trait]
 impl WorkflowStorage for MemoryWorkflowStorage {
     async fn get_workflow(&self, id: &str) -> Result<Option<Workflow>, ExecutionEngineError> {
-        Ok(self.workflows.read().await.get(id).cloned())
+        Ok(self.workflows.read().await.current.get(id).cloned())
     }
 
     async fn save_workflow(&self, workflow: &Workflow) -> Result<(), ExecutionEngineError> {
-        self.workflows
-            .write()
-            .await
-            .insert(workflow.id.clone(), workflow.clone());
+        self.workflows.write().await.save(workflow)?;
+        Ok(())
+    }
+
+    async fn save_workflow_version(
+        &self,
+        workflow: &Workflow,
+        operations: Option<&[PatchOperation]>,
+    ) -> Result<(), ExecutionEngineError> {
+        let mut workflows = self.workflows.write().await;
+        let (saved, previous) = workflows.save(workflow)?;
+        let versions = workflows.history.entry(saved.id.clone()).or_default();
+        versions.push_front(WorkflowVersion::of(&saved, previous, operations));
+        versions.truncate(MAX_MEMORY_VERSIONS);
         Ok(())
     }
 
+    async fn workflow_history(
+        &self,
+        id: &str,
+        limit: usize,
+    ) -> Result<Vec<WorkflowVersion>, ExecutionEngineError> {
+        let workflows = self.workflows.read().await;
+        let versions = workflows.history.get(id).into_iter().flatten();
+        Ok(versions.take(limit).cloned().collect())
+    }
+
     async fn delete_workflow(&self, id: &str) -> Result<bool, ExecutionEngineError> {
-        Ok(self.workflows.write().await.remove(id).is_some())
+        let mut workflows = self.workflows.write().await;
+        workflows.history.remove(id);
+        Ok(workflows.current.remove(id).is_some())
     }
 
     async fn list_workflows(&self) -> Result<Vec<Workflow>, ExecutionEngineError> {
-        Ok(self.workflows.read().await.values().cloned().collect())
+        Ok(self.workflows.read().await.current.values().cloned().collect())
     }
 }
 
@@ -135,3 +303,72 @@ impl ExecutionStorage for MemoryExecutionStorage {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::Node;
+
+    #[tokio::test]
+    async fn test_stale_workflow_save_conflicts() {
+        let storage = MemoryWorkflowStorage::new();
+        let mut workflow = Workflow::new("Orders");
+        storage.save_workflow(&workflow).await.unwrap();
+        let first = storage.get_workflow(&workflow.id).await.unwrap().unwrap();
+        assert!(first.version_id.is_some());
+
+        // Two editors start from the same version; the second save loses.
+        let mut second = first.clone();
+        workflow.name = "Orders v2".to_string();
+        workflow.version_id = first.version_id.clone();
+        storage.save_workflow(&workflow).await.unwrap();
+        second.name = "Orders (renamed)".to_string();
+        let result = storage.save_workflow(&second).await;
+        assert!(matches!(result, Err(ExecutionEngineError::VersionConflict(_))));
+
+        let stored = storage.get_workflow(&workflow.id).await.unwrap().unwrap();
+        assert_eq!(stored.name, "Orders v2");
+        assert_ne!(stored.version_id, first.version_id);
+    }
+
+    #[tokio::test]
+    async fn test_versions_are_recorded_with_their_save() {
+        let storage = MemoryWorkflowStorage::new();
+        let workflow = Workflow::new("Orders");
+        storage.save_workflow_version(&workflow, None).await.unwrap();
+        let first = storage.get_workflow(&workflow.id).await.unwrap().unwrap();
+
+        let mut stale = first.clone();
+        stale.version_id = Some("gone".to_string());
+        assert!(storage.save_workflow_version(&stale, Some(&[])).await.is_err());
+        storage.save_workflow_version(&first, Some(&[])).await.unwrap();
+
+        // The refused save left no version behind.
+        let history = storage.workflow_history(&workflow.id, 10).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].previous_version_id, first.version_id);
+        assert!(history[0].operations.is_some());
+        assert!(history[1].previous_version_id.is_none());
+        storage.delete_workflow(&workflow.id).await.unwrap();
+        assert!(storage.workflow_history(&workflow.id, 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_workflows() {
+        let storage = MemoryWorkflowStorage::new();
+        let mut sync = Workflow::new("Sync invoices");
+        sync.nodes.push(Node::new("Fetch", "n8n-nodes-base.httpRequest"));
+        let mut report = Workflow::new("Weekly report");
+        report.description = Some("Summarizes synced invoices".to_string());
+        storage.save_workflow(&sync).await.unwrap();
+        storage.save_workflow(&report).await.unwrap();
+
+        let names = |found: Vec<Workflow>| found.into_iter().map(|w| w.name).collect::<Vec<_>>();
+        let found = storage.search_workflows("invoice").await.unwrap();
+        assert_eq!(names(found), ["Sync invoices", "Weekly report"]);
+        let found = storage.search_workflows("HTTPREQ sync").await.unwrap();
+        assert_eq!(names(found), ["Sync invoices"]);
+        assert!(storage.search_workflows("slack").await.unwrap().is_empty());
+        assert!(storage.search_workflows(" ").await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,242 @@
+//! Limits on sub-executions started by `executeWorkflow` nodes.
+//!
+//! Every execution carries its [`ExecutionLineage`]: the chain of
+//! executions (and their workflows) that led to it. Before a sub-workflow
+//! starts, [`SubExecutionTracker::admit`] checks that
+//!
+//! - the new execution would not exceed `max_depth`,
+//! - its workflow is not already running higher up in the chain
+//!   (`A -> B -> A`), and
+//! - the parent has not already started `max_children` sub-executions.
+//!
+//! Violations fail the calling node with a [`ExecutionEngineError`] that
+//! names the chain, instead of recursing until the process runs out of
+//! memory.
+
+use crate::error::ExecutionEngineError;
+use dashmap::DashMap;
+use n8n_workflow::{connection::CONNECTION_MAIN, NodeExecutionData, Run};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Node type that starts sub-executions.
+pub const EXECUTE_WORKFLOW_NODE_TYPE: &str = "n8n-nodes-base.executeWorkflow";
+
+/// Sub-execution limits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubExecutionLimits {
+    /// Maximum nesting depth; a top-level execution has depth 0.
+    pub max_depth: usize,
+    /// Sub-executions a single execution may start (0 = unlimited).
+    pub max_children: usize,
+}
+
+impl Default for SubExecutionLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 10,
+            max_children: 100,
+        }
+    }
+}
+
+/// One execution in a lineage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineageFrame {
+    pub execution_id: String,
+    pub workflow_id: String,
+}
+
+/// The chain of executions from the top-level execution down to the
+/// current one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionLineage {
+    frames: Vec<LineageFrame>,
+}
+
+impl ExecutionLineage {
+    /// Lineage of a top-level execution.
+    pub fn root(execution_id: impl Into<String>, workflow_id: impl Into<String>) -> Self {
+        Self {
+            frames: vec![LineageFrame {
+                execution_id: execution_id.into(),
+                workflow_id: workflow_id.into(),
+            }],
+        }
+    }
+
+    /// Depth of the current execution (0 for top-level).
+    pub fn depth(&self) -> usize {
+        self.frames.len().saturating_sub(1)
+    }
+
+    /// The current execution.
+    pub fn current(&self) -> Option<&LineageFrame> {
+        self.frames.last()
+    }
+
+    /// The execution that started the current one.
+    pub fn parent(&self) -> Option<&LineageFrame> {
+        self.frames.len().checked_sub(2).map(|i| &self.frames[i])
+    }
+
+    pub fn frames(&self) -> &[LineageFrame] {
+        &self.frames
+    }
+
+    /// Lineage of a sub-execution started by the current execution.
+    pub fn child(&self, execution_id: impl Into<String>, workflow_id: impl Into<String>) -> Self {
+        let mut frames = self.frames.clone();
+        frames.push(LineageFrame {
+            execution_id: execution_id.into(),
+            workflow_id: workflow_id.into(),
+        });
+        Self { frames }
+    }
+
+    /// `wf-a -> wf-b -> wf-c`.
+    pub fn workflow_path(&self) -> String {
+        self.frames
+            .iter()
+            .map(|f| f.workflow_id.as_str())
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    }
+}
+
+/// Counts the sub-executions started by each running execution.
+#[derive(Debug, Default)]
+pub struct SubExecutionTracker {
+    children: DashMap<String, usize>,
+}
+
+impl SubExecutionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check the limits for starting `workflow_id` from the current
+    /// execution of `parent` and, if allowed, count it against the parent's
+    /// quota. Returns the sub-execution's lineage.
+    pub fn admit(
+        &self,
+        parent: &ExecutionLineage,
+        execution_id: &str,
+        workflow_id: &str,
+        limits: &SubExecutionLimits,
+    ) -> Result<ExecutionLineage, ExecutionEngineError> {
+        let lineage = parent.child(execution_id, workflow_id);
+
+        if parent.frames.iter().any(|f| f.workflow_id == workflow_id) {
+            return Err(ExecutionEngineError::SubExecutionCycle(lineage.workflow_path()));
+        }
+        if lineage.depth() > limits.max_depth {
+            return Err(ExecutionEngineError::SubExecutionDepth {
+                max: limits.max_depth,
+                path: lineage.workflow_path(),
+            });
+        }
+
+        if let Some(current) = parent.current() {
+            let mut count = self.children.entry(current.execution_id.clone()).or_insert(0);
+            if limits.max_children > 0 && *count >= limits.max_children {
+                return Err(ExecutionEngineError::SubExecutionQuota {
+                    execution_id: current.execution_id.clone(),
+                    max: limits.max_children,
+                });
+            }
+            *count += 1;
+        }
+
+        Ok(lineage)
+    }
+
+    /// Sub-executions started so far by `execution_id`.
+    pub fn children(&self, execution_id: &str) -> usize {
+        self.children.get(execution_id).map(|c| *c).unwrap_or(0)
+    }
+
+    /// Forget an execution's count once it has finished.
+    pub fn release(&self, execution_id: &str) {
+        self.children.remove(execution_id);
+    }
+
+    /// Release `execution_id` when the returned guard is dropped.
+    pub fn scope(self: &Arc<Self>, execution_id: &str) -> SubExecutionScope {
+        SubExecutionScope {
+            tracker: self.clone(),
+            execution_id: execution_id.to_string(),
+        }
+    }
+}
+
+/// Releases an execution's child count on drop.
+pub struct SubExecutionScope {
+    tracker: Arc<SubExecutionTracker>,
+    execution_id: String,
+}
+
+impl Drop for SubExecutionScope {
+    fn drop(&mut self) {
+        self.tracker.release(&self.execution_id);
+    }
+}
+
+/// Items on the first main output of the last node that ran.
+pub fn last_node_output(run: &Run) -> Vec<NodeExecutionData> {
+    let result = &run.data.result_data;
+    result
+        .last_node_executed
+        .as_ref()
+        .and_then(|node| result.run_data.get(node))
+        .and_then(|runs| runs.last())
+        .and_then(|task| task.data.as_ref())
+        .and_then(|data| data.get(CONNECTION_MAIN))
+        .and_then(|outputs| outputs.first())
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_and_depth() {
+        let tracker = SubExecutionTracker::new();
+        let limits = SubExecutionLimits { max_depth: 2, max_children: 0 };
+        let root = ExecutionLineage::root("e1", "A");
+
+        let b = tracker.admit(&root, "e2", "B", &limits).unwrap();
+        assert_eq!(b.depth(), 1);
+
+        let err = tracker.admit(&b, "e3", "A", &limits).unwrap_err();
+        assert!(
+            matches!(err, ExecutionEngineError::SubExecutionCycle(ref p) if p == "A -> B -> A")
+        );
+
+        let c = tracker.admit(&b, "e3", "C", &limits).unwrap();
+        let err = tracker.admit(&c, "e4", "D", &limits).unwrap_err();
+        assert!(matches!(err, ExecutionEngineError::SubExecutionDepth { max: 2, .. }));
+    }
+
+    #[test]
+    fn test_child_quota_is_per_parent() {
+        let tracker = Arc::new(SubExecutionTracker::new());
+        let limits = SubExecutionLimits { max_depth: 10, max_children: 2 };
+        let root = ExecutionLineage::root("e1", "A");
+
+        {
+            let _scope = tracker.scope("e1");
+            tracker.admit(&root, "c1", "B", &limits).unwrap();
+            tracker.admit(&root, "c2", "B", &limits).unwrap();
+            let err = tracker.admit(&root, "c3", "B", &limits).unwrap_err();
+            assert!(matches!(err, ExecutionEngineError::SubExecutionQuota { max: 2, .. }));
+
+            let other = ExecutionLineage::root("e2", "A");
+            tracker.admit(&other, "c4", "B", &limits).unwrap();
+        }
+        assert_eq!(tracker.children("e1"), 0);
+    }
+}
@@ -0,0 +1,132 @@
+//! Sub-nodes supplying root nodes.
+//!
+//! Through a supply connection (`ai_languageModel`, `ai_tool`, `ai_memory`
+//! and the other `ai_` types), a sub-node hands a root node something it
+//! uses while running, such as a language model, rather than items.
+//! Sub-nodes never run on their own. When a root node asks its
+//! [`RuntimeContext::input_connection_data`] for a connection type, the
+//! sub-nodes attached there are resolved on the spot with
+//! [`NodeExecutor::supply_data`]; a sub-node asks for its own sub-nodes the
+//! same way, like a tool that needs a model.
+//!
+//! What a sub-node supplies is a type-erased [`Supply`], which the root
+//! node downcasts to the interface it expects on that connection type.
+//! As in n8n, the parameters of sub-nodes are resolved against the root
+//! node's input.
+
+use crate::error::ExecutionEngineError;
+use crate::executor::{NodeExecutor, NodeExecutorRegistry};
+use crate::runtime::RuntimeContext;
+use n8n_workflow::connection::{graph, is_supply_connection};
+use n8n_workflow::Workflow;
+use std::any::Any;
+use std::sync::Arc;
+
+/// What a sub-node supplies, downcast by the root node.
+pub type Supply = Arc<dyn Any + Send + Sync>;
+
+/// The supply of one sub-node.
+#[derive(Clone)]
+pub struct SuppliedData {
+    /// Sub-node that supplied it.
+    pub node: String,
+    pub data: Supply,
+}
+
+impl SuppliedData {
+    /// The supply as a `T`, if it is one.
+    pub fn downcast<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.data.clone().downcast().ok()
+    }
+}
+
+/// Sub-nodes of a running node, resolved when it asks for them.
+#[derive(Clone)]
+pub struct NodeSupplies {
+    /// Node whose sub-nodes are resolved.
+    node: String,
+    /// The workflow, with the sub-nodes' parameters resolved.
+    workflow: Arc<Workflow>,
+    executors: Arc<NodeExecutorRegistry>,
+}
+
+impl NodeSupplies {
+    pub(crate) fn new(
+        node: &str,
+        workflow: Arc<Workflow>,
+        executors: Arc<NodeExecutorRegistry>,
+    ) -> Self {
+        Self {
+            node: node.to_string(),
+            workflow,
+            executors,
+        }
+    }
+
+    /// Supplies of the enabled sub-nodes attached through
+    /// `connection_type`, by input index.
+    pub async fn resolve(
+        &self,
+        connection_type: &str,
+        context: &RuntimeContext,
+    ) -> Result<Vec<SuppliedData>, ExecutionEngineError> {
+        if !is_supply_connection(connection_type) {
+            return Err(ExecutionEngineError::InvalidState(format!(
+                "'{}' is not a supply connection type",
+                connection_type
+            )));
+        }
+
+        let mut supplied = Vec::new();
+        for sub_node in self.workflow.supply_nodes(&self.node, connection_type) {
+            if sub_node.disabled {
+                continue;
+            }
+            let executor: Arc<dyn NodeExecutor> =
+                self.executors.get(&sub_node.node_type).ok_or_else(|| {
+                    ExecutionEngineError::NodeExecution {
+                        node: sub_node.name.clone(),
+                        message: format!(
+                            "No executor found for node type: {}",
+                            sub_node.node_type
+                        ),
+                    }
+                })?;
+            let sub_context = context.clone().with_supplies(Self {
+                node: sub_node.name.clone(),
+                ..self.clone()
+            });
+            let data = executor.supply_data(sub_node, &sub_context).await?;
+            supplied.push(SuppliedData {
+                node: sub_node.name.clone(),
+                data,
+            });
+        }
+        Ok(supplied)
+    }
+}
+
+/// Sub-nodes attached to `root`, directly or through other sub-nodes.
+pub(crate) fn sub_nodes_of(workflow: &Workflow, root: &str) -> Vec<String> {
+    let has_supplies = workflow
+        .connections
+        .values()
+        .any(|node_conns| node_conns.keys().any(|conn_type| is_supply_connection(conn_type)));
+    if !has_supplies {
+        return Vec::new();
+    }
+
+    let by_destination = graph::map_connections_by_destination(&workflow.connections);
+    let mut found: Vec<String> = Vec::new();
+    let mut pending = vec![root.to_string()];
+    while let Some(node) = pending.pop() {
+        for source in by_destination.get(&node).into_iter().flatten() {
+            let supplies = is_supply_connection(&source.connection_type);
+            if supplies && !found.contains(&source.source_node) {
+                found.push(source.source_node.clone());
+                pending.push(source.source_node.clone());
+            }
+        }
+    }
+    found
+}
@@ -0,0 +1,250 @@
+//! Item throttling.
+//!
+//! `n8n-nodes-base.throttle` lets at most `rate` items per `per` (`second`,
+//! `minute` or `hour`) through to the nodes after it, so a burst from a
+//! trigger does not overload the API they call. Every item takes the next
+//! free slot of its node, one `per / rate` apart; slots are shared by all
+//! executions of the workflow on this instance.
+//!
+//! With a [`ThrottleQueue`] set, usually durable timers, items whose slot
+//! has come pass at once and the rest are handed to the queue, grouped by
+//! second, which later runs the nodes after the throttle with them
+//! ([`release_targets`]). The execution itself does not wait. Without a
+//! queue the node waits for the last of its items' slots and passes them
+//! all, pacing the executions instead.
+
+use crate::error::ExecutionEngineError;
+use crate::executor::{NodeExecutor, NodeOutput};
+use crate::runtime::RuntimeContext;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use n8n_workflow::{
+    connection::CONNECTION_MAIN, Node, NodeExecutionData, NodeParameterValue, TaskDataConnections,
+    Workflow,
+};
+use parking_lot::{Mutex, RwLock};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Node type of the throttle node.
+pub const THROTTLE_NODE_TYPE: &str = "n8n-nodes-base.throttle";
+
+/// Timer payload key of the items a throttle node deferred.
+pub const THROTTLED_ITEMS_KEY: &str = "throttledItems";
+
+/// Where deferred items wait for their slot.
+#[async_trait]
+pub trait ThrottleQueue: Send + Sync {
+    /// Run the nodes after `node` of `workflow_id` with `items` at `at`.
+    async fn defer(
+        &self,
+        workflow_id: &str,
+        node: &str,
+        items: Vec<NodeExecutionData>,
+        at: DateTime<Utc>,
+    ) -> Result<(), String>;
+}
+
+/// The engine's throttle slots. Clones share them and the queue, which may
+/// be set once one becomes available.
+#[derive(Clone, Default)]
+pub struct Throttles {
+    /// Next free slot per workflow and node.
+    next_free: Arc<Mutex<HashMap<String, DateTime<Utc>>>>,
+    queue: Arc<RwLock<Option<Arc<dyn ThrottleQueue>>>>,
+}
+
+impl Throttles {
+    /// Defer items to `queue` from now on.
+    pub fn set_queue(&self, queue: Arc<dyn ThrottleQueue>) {
+        *self.queue.write() = Some(queue);
+    }
+
+    fn queue(&self) -> Option<Arc<dyn ThrottleQueue>> {
+        self.queue.read().clone()
+    }
+
+    /// Reserve `count` slots of `key`, `interval` apart, the first at `now`
+    /// or when the previous reservation ends.
+    pub fn reserve(
+        &self,
+        key: &str,
+        count: usize,
+        interval: chrono::Duration,
+        now: DateTime<Utc>,
+    ) -> Vec<DateTime<Utc>> {
+        let mut next_free = self.next_free.lock();
+        let next = next_free.entry(key.to_string()).or_insert(now);
+        let mut slot = (*next).max(now);
+        let mut slots = Vec::with_capacity(count);
+        for _ in 0..count {
+            slots.push(slot);
+            slot += interval;
+        }
+        *next = slot;
+        slots
+    }
+}
+
+/// Nodes the throttle node `node` releases items to: those on its first
+/// output, unless disabled.
+pub fn release_targets(workflow: &Workflow, node: &str) -> Vec<String> {
+    workflow
+        .connections
+        .get(node)
+        .and_then(|by_type| by_type.get(CONNECTION_MAIN))
+        .and_then(|outputs| outputs.first())
+        .into_iter()
+        .flatten()
+        .filter(|conn| workflow.get_node(&conn.node).is_some_and(|n| !n.disabled))
+        .map(|conn| conn.node.clone())
+        .collect()
+}
+
+/// Time between slots of a throttle node.
+fn interval(node: &Node) -> Result<chrono::Duration, String> {
+    let rate = match node.parameters.get("rate") {
+        Some(NodeParameterValue::Number(rate)) if *rate > 0.0 => *rate,
+        None => 1.0,
+        _ => return Err("rate must be a positive number".to_string()),
+    };
+    let per_ms = match node.parameters.get("per") {
+        Some(NodeParameterValue::String(per)) => match per.as_str() {
+            "second" => 1_000.0,
+            "minute" => 60_000.0,
+            "hour" => 3_600_000.0,
+            other => return Err(format!("Unknown period '{}'", other)),
+        },
+        _ => 1_000.0,
+    };
+    Ok(chrono::Duration::microseconds((per_ms * 1_000.0 / rate) as i64))
+}
+
+/// Throttle node - release items at a fixed rate.
+pub struct ThrottleExecutor;
+
+#[async_trait]
+impl NodeExecutor for ThrottleExecutor {
+    fn node_type(&self) -> &str {
+        THROTTLE_NODE_TYPE
+    }
+
+    async fn execute(
+        &self,
+        node: &Node,
+        input: &TaskDataConnections,
+        context: &RuntimeContext,
+    ) -> Result<NodeOutput, ExecutionEngineError> {
+        let node_error = |message: String| ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message,
+        };
+        let interval = interval(node).map_err(node_error)?;
+        let items = input
+            .get(CONNECTION_MAIN)
+            .and_then(|v| v.first())
+            .cloned()
+            .unwrap_or_default();
+        let workflow_id = context.workflow_id().unwrap_or_default();
+        let key = format!("{}:{}", workflow_id, node.name);
+        let now = Utc::now();
+        let slots = context.throttles().reserve(&key, items.len(), interval, now);
+
+        let queue = context.throttles().queue().filter(|_| !workflow_id.is_empty());
+        let Some(queue) = queue else {
+            if let Some(wait) = slots.last().and_then(|last| (*last - now).to_std().ok()) {
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = context.cancellation_token().cancelled() => {
+                        return Err(ExecutionEngineError::Canceled);
+                    }
+                }
+            }
+            return Ok(vec![items]);
+        };
+
+        let mut passed = Vec::new();
+        let mut deferred: BTreeMap<i64, (DateTime<Utc>, Vec<NodeExecutionData>)> = BTreeMap::new();
+        for (item, slot) in items.into_iter().zip(slots) {
+            if slot <= now {
+                passed.push(item);
+            } else {
+                deferred.entry(slot.timestamp()).or_insert((slot, Vec::new())).1.push(item);
+            }
+        }
+        for (at, items) in deferred.into_values() {
+            queue
+                .defer(workflow_id, &node.name, items, at)
+                .await
+                .map_err(|e| node_error(format!("Failed to defer items: {}", e)))?;
+        }
+        Ok(vec![passed])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::WorkflowExecuteMode;
+    use serde_json::json;
+
+    struct RecordingQueue(Mutex<Vec<(String, usize, DateTime<Utc>)>>);
+
+    #[async_trait]
+    impl ThrottleQueue for RecordingQueue {
+        async fn defer(
+            &self,
+            _workflow_id: &str,
+            node: &str,
+            items: Vec<NodeExecutionData>,
+            at: DateTime<Utc>,
+        ) -> Result<(), String> {
+            self.0.lock().push((node.to_string(), items.len(), at));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_reserve_spaces_slots_across_calls() {
+        let throttles = Throttles::default();
+        let now = Utc::now();
+        let second = chrono::Duration::seconds(1);
+
+        let first = throttles.reserve("wf:Throttle", 2, second, now);
+        assert_eq!(first, vec![now, now + second]);
+        let next = throttles.reserve("wf:Throttle", 1, second, now);
+        assert_eq!(next, vec![now + second * 2]);
+        assert_eq!(throttles.reserve("wf:Other", 1, second, now), vec![now]);
+
+        // A reservation long past does not allow a burst later
+        let later = now + chrono::Duration::hours(1);
+        assert_eq!(throttles.reserve("wf:Throttle", 1, second, later), vec![later]);
+    }
+
+    #[tokio::test]
+    async fn test_items_beyond_the_rate_are_deferred_by_second() {
+        let throttles = Throttles::default();
+        let queue = Arc::new(RecordingQueue(Mutex::new(Vec::new())));
+        throttles.set_queue(queue.clone());
+        let context = RuntimeContext::new(WorkflowExecuteMode::Trigger, Default::default())
+            .with_workflow_id("wf")
+            .with_throttles(throttles);
+
+        let mut node = Node::new("Throttle", THROTTLE_NODE_TYPE);
+        node.set_parameter("rate", NodeParameterValue::Number(2.0));
+        let items: Vec<_> = (0..5)
+            .map(|i| NodeExecutionData::from_json_value(json!({ "i": i })).unwrap())
+            .collect();
+        let mut input = TaskDataConnections::new();
+        input.insert(CONNECTION_MAIN.to_string(), vec![items]);
+
+        let output = ThrottleExecutor.execute(&node, &input, &context).await.unwrap();
+        assert_eq!(output[0].len(), 1);
+
+        let deferred = queue.0.lock().clone();
+        let counts: Vec<usize> = deferred.iter().map(|(_, count, _)| *count).collect();
+        assert_eq!(counts.iter().sum::<usize>(), 4);
+        assert!(deferred.iter().all(|(node, count, _)| node == "Throttle" && *count <= 2));
+        assert!(deferred.windows(2).all(|w| w[0].2 < w[1].2));
+    }
+}
@@ -0,0 +1,267 @@
+//! Trigger health: consecutive failures of webhook and timer triggers.
+//!
+//! Every run a trigger starts counts towards its health: a success resets
+//! its count of consecutive failures, a failure adds one, and canceled runs
+//! leave it as it was. Once the count reaches
+//! [`TriggerHealthConfig::max_consecutive_failures`] the trigger is paused,
+//! so a poison-pill request or a broken upstream does not keep failing the
+//! workflow: webhooks of a paused trigger are refused and its timers are
+//! dropped until it is resumed.
+//!
+//! Pauses and resumes are announced to subscribers of
+//! [`TriggerHealth::subscribe`]. Counts and pauses are kept in memory, per
+//! instance.
+
+use chrono::{DateTime, Utc};
+use n8n_workflow::Run;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use crate::error::ExecutionEngineError;
+use crate::execution_webhooks::LifecycleEvent;
+
+/// Trigger health settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TriggerHealthConfig {
+    /// Consecutive failures after which a trigger is paused (0 = never).
+    pub max_consecutive_failures: u32,
+}
+
+impl Default for TriggerHealthConfig {
+    fn default() -> Self {
+        Self {
+            max_consecutive_failures: 5,
+        }
+    }
+}
+
+/// Health of one trigger node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerState {
+    pub workflow_id: String,
+    pub node: String,
+    pub consecutive_failures: u32,
+
+    /// Error of the latest failure, if it was not followed by a success.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_failure_at: Option<DateTime<Utc>>,
+
+    /// When the trigger was paused; `None` while it runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paused_at: Option<DateTime<Utc>>,
+}
+
+impl TriggerState {
+    fn new(workflow_id: &str, node: &str) -> Self {
+        Self {
+            workflow_id: workflow_id.to_string(),
+            node: node.to_string(),
+            consecutive_failures: 0,
+            last_error: None,
+            last_failure_at: None,
+            paused_at: None,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+}
+
+/// A trigger paused or resumed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum TriggerEvent {
+    Paused(TriggerState),
+    #[serde(rename_all = "camelCase")]
+    Resumed { workflow_id: String, node: String },
+}
+
+/// Health of the triggers an engine runs. Clones share the triggers.
+#[derive(Clone)]
+pub struct TriggerHealth {
+    triggers: Arc<RwLock<HashMap<(String, String), TriggerState>>>,
+    events: broadcast::Sender<TriggerEvent>,
+}
+
+impl Default for TriggerHealth {
+    fn default() -> Self {
+        Self {
+            triggers: Arc::default(),
+            events: broadcast::channel(64).0,
+        }
+    }
+}
+
+impl TriggerHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pauses and resumes from now on. Events are dropped while nobody
+    /// subscribes.
+    pub fn subscribe(&self) -> broadcast::Receiver<TriggerEvent> {
+        self.events.subscribe()
+    }
+
+    /// Count the run `node` of `workflow_id` started, pausing the trigger
+    /// when it reaches `config`'s limit. Returns whether it was paused now.
+    pub fn record(
+        &self,
+        workflow_id: &str,
+        node: &str,
+        result: &Result<Run, ExecutionEngineError>,
+        config: &TriggerHealthConfig,
+    ) -> bool {
+        if workflow_id.is_empty() {
+            return false;
+        }
+        let error = match LifecycleEvent::finished(result) {
+            Some(LifecycleEvent::Success) => None,
+            Some(LifecycleEvent::Failure) => Some(match result {
+                Ok(run) => run
+                    .data
+                    .result_data
+                    .error
+                    .as_ref()
+                    .map(|e| e.message.clone())
+                    .unwrap_or_else(|| "Execution failed".to_string()),
+                Err(e) => e.to_string(),
+            }),
+            _ => return false,
+        };
+
+        let mut triggers = self.triggers.write();
+        let key = (workflow_id.to_string(), node.to_string());
+        let Some(error) = error else {
+            if let Some(state) = triggers.get_mut(&key) {
+                state.consecutive_failures = 0;
+                state.last_error = None;
+            }
+            return false;
+        };
+        let state = triggers
+            .entry(key)
+            .or_insert_with(|| TriggerState::new(workflow_id, node));
+        state.consecutive_failures += 1;
+        state.last_error = Some(error);
+        state.last_failure_at = Some(Utc::now());
+
+        let max = config.max_consecutive_failures;
+        if max == 0 || state.is_paused() || state.consecutive_failures < max {
+            return false;
+        }
+        state.paused_at = Some(Utc::now());
+        tracing::warn!(
+            workflow_id = %workflow_id,
+            node = %node,
+            failures = state.consecutive_failures,
+            "Trigger paused after consecutive failures"
+        );
+        let _ = self.events.send(TriggerEvent::Paused(state.clone()));
+        true
+    }
+
+    /// Whether `node` of `workflow_id` is paused.
+    pub fn is_paused(&self, workflow_id: &str, node: &str) -> bool {
+        self.triggers
+            .read()
+            .get(&(workflow_id.to_string(), node.to_string()))
+            .is_some_and(TriggerState::is_paused)
+    }
+
+    /// Resume a paused trigger with a fresh count. Returns whether it was
+    /// paused.
+    pub fn resume(&self, workflow_id: &str, node: &str) -> bool {
+        if !self.set_paused(workflow_id, node, false) {
+            return false;
+        }
+        let _ = self.events.send(TriggerEvent::Resumed {
+            workflow_id: workflow_id.to_string(),
+            node: node.to_string(),
+        });
+        true
+    }
+
+    /// Pause or resume a trigger without announcing it, as for changes
+    /// another instance announced. Returns whether its state changed.
+    pub fn set_paused(&self, workflow_id: &str, node: &str, paused: bool) -> bool {
+        let mut triggers = self.triggers.write();
+        let state = triggers
+            .entry((workflow_id.to_string(), node.to_string()))
+            .or_insert_with(|| TriggerState::new(workflow_id, node));
+        if state.is_paused() == paused {
+            return false;
+        }
+        if paused {
+            state.paused_at = Some(Utc::now());
+        } else {
+            state.paused_at = None;
+            state.consecutive_failures = 0;
+            state.last_error = None;
+        }
+        true
+    }
+
+    /// Triggers of `workflow_id` that failed since their last success or
+    /// are paused, by node name.
+    pub fn for_workflow(&self, workflow_id: &str) -> Vec<TriggerState> {
+        let mut states: Vec<TriggerState> = self
+            .triggers
+            .read()
+            .values()
+            .filter(|state| {
+                state.workflow_id == workflow_id
+                    && (state.consecutive_failures > 0 || state.is_paused())
+            })
+            .cloned()
+            .collect();
+        states.sort_by(|a, b| a.node.cmp(&b.node));
+        states
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::{ExecutionStatus, WorkflowExecuteMode};
+
+    #[test]
+    fn test_trigger_pauses_after_consecutive_failures() {
+        let health = TriggerHealth::new();
+        let mut events = health.subscribe();
+        let config = TriggerHealthConfig {
+            max_consecutive_failures: 2,
+        };
+        let mut success = Run::new(WorkflowExecuteMode::Webhook);
+        success.finish(ExecutionStatus::Success);
+        let failure = || Err(ExecutionEngineError::Internal("upstream down".to_string()));
+
+        assert!(!health.record("wf", "Hook", &failure(), &config));
+        assert!(!health.record("wf", "Hook", &Ok(success.clone()), &config));
+        assert!(!health.record("wf", "Hook", &failure(), &config));
+        assert!(!health.is_paused("wf", "Hook"));
+        assert!(health.record("wf", "Hook", &failure(), &config));
+        assert!(health.is_paused("wf", "Hook"));
+        assert!(matches!(events.try_recv(), Ok(TriggerEvent::Paused(state))
+            if state.consecutive_failures == 2));
+
+        let states = health.for_workflow("wf");
+        assert_eq!(states.len(), 1);
+        assert!(states[0].last_error.as_deref().unwrap().contains("upstream down"));
+
+        assert!(health.resume("wf", "Hook"));
+        assert!(!health.resume("wf", "Hook"));
+        assert!(!health.is_paused("wf", "Hook"));
+        assert!(health.for_workflow("wf").is_empty());
+        assert!(matches!(events.try_recv(), Ok(TriggerEvent::Resumed { .. })));
+    }
+}
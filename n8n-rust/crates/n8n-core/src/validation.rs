@@ -0,0 +1,629 @@
+//! Item validation against a JSON Schema or a field-type contract.
+//!
+//! `n8n-nodes-base.validate` checks each item's JSON and routes it to
+//! output 0 when it is valid and to output 1 otherwise, with the
+//! violations attached. A contract is a list of fields with a type and
+//! whether they are required; it is turned into a JSON Schema, so both
+//! modes report violations the same way.
+//!
+//! The validator covers the JSON Schema keywords that describe data:
+//! `type`, `enum`, `const`, the numeric, string, array and object bounds,
+//! `pattern`, `format` (`date-time`, `date`, `email`, `uuid`, `uri`),
+//! `properties`, `required`, `additionalProperties`, `patternProperties`,
+//! `items`, `allOf`, `anyOf`, `oneOf`, `not` and local `$ref`s into
+//! `$defs`/`definitions`. Unknown keywords are ignored.
+
+use crate::error::ExecutionEngineError;
+use crate::executor::{NodeExecutor, NodeOutput};
+use crate::runtime::RuntimeContext;
+use async_trait::async_trait;
+use n8n_workflow::{
+    object_to_json, GenericValue, Node, NodeExecutionData, NodeParameterValue,
+    TaskDataConnections,
+};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// Node type of the validation node.
+pub const VALIDATE_NODE_TYPE: &str = "n8n-nodes-base.validate";
+
+/// Nesting of `$ref`s followed before a schema is considered cyclic.
+const MAX_REF_DEPTH: usize = 32;
+
+/// One way an item breaks the schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    /// JSON Pointer to the offending value, `""` for the item itself.
+    pub path: String,
+    /// Schema keyword that failed.
+    pub keyword: String,
+    pub message: String,
+}
+
+/// A JSON Schema ready to validate values.
+#[derive(Debug, Clone)]
+pub struct SchemaValidator {
+    root: Value,
+    patterns: HashMap<String, Regex>,
+}
+
+impl SchemaValidator {
+    /// Compile `schema`, failing on invalid `pattern`s.
+    pub fn new(schema: Value) -> Result<Self, String> {
+        if !schema.is_object() && !schema.is_boolean() {
+            return Err("Schema must be an object or a boolean".to_string());
+        }
+        let mut patterns = HashMap::new();
+        collect_patterns(&schema, &mut patterns)?;
+        Ok(Self {
+            root: schema,
+            patterns,
+        })
+    }
+
+    /// Schema accepting objects with the contract's fields. Entries have a
+    /// `name`, a `type` (`string`, `number`, `integer`, `boolean`, `array`,
+    /// `object` or `any`), `required` and `nullable`.
+    pub fn from_contract(fields: &[Value]) -> Result<Self, String> {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for field in fields {
+            let name = field
+                .get("name")
+                .and_then(Value::as_str)
+                .filter(|name| !name.is_empty())
+                .ok_or("Every contract field needs a name")?;
+            let field_type = field.get("type").and_then(Value::as_str).unwrap_or("any");
+            let flag = |key: &str| field.get(key).and_then(Value::as_bool).unwrap_or(false);
+
+            let schema = match field_type {
+                "any" => json!({}),
+                "string" | "number" | "integer" | "boolean" | "array" | "object" => {
+                    if flag("nullable") {
+                        json!({ "type": [field_type, "null"] })
+                    } else {
+                        json!({ "type": field_type })
+                    }
+                }
+                other => return Err(format!("Unknown type '{}' for field '{}'", other, name)),
+            };
+            properties.insert(name.to_string(), schema);
+            if flag("required") {
+                required.push(Value::from(name));
+            }
+        }
+        Self::new(json!({ "type": "object", "properties": properties, "required": required }))
+    }
+
+    /// Every violation of `value`, empty when it is valid.
+    pub fn validate(&self, value: &Value) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        self.check(&self.root, value, "", 0, &mut violations);
+        violations
+    }
+
+    fn check(
+        &self,
+        schema: &Value,
+        value: &Value,
+        path: &str,
+        depth: usize,
+        out: &mut Vec<Violation>,
+    ) {
+        let violation = |keyword: &str, message: String| Violation {
+            path: path.to_string(),
+            keyword: keyword.to_string(),
+            message,
+        };
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                out.push(violation("false", "No value is allowed here".to_string()));
+                return;
+            }
+            Value::Object(schema) => schema,
+            _ => return,
+        };
+
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            match self.resolve(reference) {
+                Some(_) if depth >= MAX_REF_DEPTH => {
+                    out.push(violation("$ref", format!("'{}' nests too deeply", reference)));
+                }
+                Some(target) => self.check(target, value, path, depth + 1, out),
+                None => out.push(violation("$ref", format!("Cannot resolve '{}'", reference))),
+            }
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let types: Vec<&str> = match expected {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+                out.push(violation(
+                    "type",
+                    format!("Expected {}, got {}", types.join(" or "), type_name(value)),
+                ));
+                // The remaining keywords would only repeat the mismatch.
+                return;
+            }
+        }
+        if let Some(Value::Array(options)) = schema.get("enum") {
+            if !options.iter().any(|option| json_eq(option, value)) {
+                let options = Value::from(options.clone());
+                out.push(violation("enum", format!("Must be one of {}", options)));
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if !json_eq(constant, value) {
+                out.push(violation("const", format!("Must be {}", constant)));
+            }
+        }
+
+        match value {
+            Value::Number(number) => self.check_number(schema, number.as_f64(), &violation, out),
+            Value::String(s) => self.check_string(schema, s, &violation, out),
+            Value::Array(items) => self.check_array(schema, items, path, depth, &violation, out),
+            Value::Object(object) => {
+                self.check_object(schema, object, path, depth, &violation, out)
+            }
+            _ => {}
+        }
+
+        if let Some(Value::Array(all)) = schema.get("allOf") {
+            for sub in all {
+                self.check(sub, value, path, depth, out);
+            }
+        }
+        if let Some(Value::Array(any)) = schema.get("anyOf") {
+            if !any.iter().any(|sub| self.passes(sub, value, path, depth)) {
+                out.push(violation("anyOf", "Matches none of the allowed schemas".to_string()));
+            }
+        }
+        if let Some(Value::Array(one)) = schema.get("oneOf") {
+            let matched = one.iter().filter(|sub| self.passes(sub, value, path, depth)).count();
+            if matched != 1 {
+                out.push(violation(
+                    "oneOf",
+                    format!("Must match exactly one schema, matches {}", matched),
+                ));
+            }
+        }
+        if let Some(not) = schema.get("not") {
+            if self.passes(not, value, path, depth) {
+                out.push(violation("not", "Matches a disallowed schema".to_string()));
+            }
+        }
+    }
+
+    fn passes(&self, schema: &Value, value: &Value, path: &str, depth: usize) -> bool {
+        let mut violations = Vec::new();
+        self.check(schema, value, path, depth, &mut violations);
+        violations.is_empty()
+    }
+
+    fn check_number(
+        &self,
+        schema: &Map<String, Value>,
+        number: Option<f64>,
+        violation: &dyn Fn(&str, String) -> Violation,
+        out: &mut Vec<Violation>,
+    ) {
+        let Some(n) = number else { return };
+        let bound = |key: &str| schema.get(key).and_then(Value::as_f64);
+        if let Some(min) = bound("minimum").filter(|min| n < *min) {
+            out.push(violation("minimum", format!("Must be at least {}", min)));
+        }
+        if let Some(max) = bound("maximum").filter(|max| n > *max) {
+            out.push(violation("maximum", format!("Must be at most {}", max)));
+        }
+        if let Some(min) = bound("exclusiveMinimum").filter(|min| n <= *min) {
+            out.push(violation("exclusiveMinimum", format!("Must be greater than {}", min)));
+        }
+        if let Some(max) = bound("exclusiveMaximum").filter(|max| n >= *max) {
+            out.push(violation("exclusiveMaximum", format!("Must be less than {}", max)));
+        }
+        if let Some(step) = bound("multipleOf").filter(|step| *step > 0.0) {
+            let quotient = n / step;
+            if (quotient - quotient.round()).abs() > 1e-9 {
+                out.push(violation("multipleOf", format!("Must be a multiple of {}", step)));
+            }
+        }
+    }
+
+    fn check_string(
+        &self,
+        schema: &Map<String, Value>,
+        s: &str,
+        violation: &dyn Fn(&str, String) -> Violation,
+        out: &mut Vec<Violation>,
+    ) {
+        let length = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if length < min {
+                out.push(violation("minLength", format!("Must be at least {} characters", min)));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if length > max {
+                out.push(violation("maxLength", format!("Must be at most {} characters", max)));
+            }
+        }
+        if let Some(Value::String(pattern)) = schema.get("pattern") {
+            if self.patterns.get(pattern).is_some_and(|regex| !regex.is_match(s)) {
+                out.push(violation("pattern", format!("Must match '{}'", pattern)));
+            }
+        }
+        if let Some(Value::String(format)) = schema.get("format") {
+            if !matches_format(format, s) {
+                out.push(violation("format", format!("Must be a valid {}", format)));
+            }
+        }
+    }
+
+    fn check_array(
+        &self,
+        schema: &Map<String, Value>,
+        items: &[Value],
+        path: &str,
+        depth: usize,
+        violation: &dyn Fn(&str, String) -> Violation,
+        out: &mut Vec<Violation>,
+    ) {
+        let count = items.len() as u64;
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if count < min {
+                out.push(violation("minItems", format!("Must have at least {} items", min)));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if count > max {
+                out.push(violation("maxItems", format!("Must have at most {} items", max)));
+            }
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            let duplicate = items
+                .iter()
+                .enumerate()
+                .any(|(i, a)| items[..i].iter().any(|b| json_eq(a, b)));
+            if duplicate {
+                out.push(violation("uniqueItems", "Items must be unique".to_string()));
+            }
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                self.check(item_schema, item, &format!("{}/{}", path, i), depth, out);
+            }
+        }
+    }
+
+    fn check_object(
+        &self,
+        schema: &Map<String, Value>,
+        object: &Map<String, Value>,
+        path: &str,
+        depth: usize,
+        violation: &dyn Fn(&str, String) -> Violation,
+        out: &mut Vec<Violation>,
+    ) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    out.push(Violation {
+                        path: format!("{}/{}", path, escape_pointer(name)),
+                        keyword: "required".to_string(),
+                        message: "Required".to_string(),
+                    });
+                }
+            }
+        }
+        let count = object.len() as u64;
+        if let Some(min) = schema.get("minProperties").and_then(Value::as_u64) {
+            if count < min {
+                out.push(violation("minProperties", format!("Must have at least {} fields", min)));
+            }
+        }
+        if let Some(max) = schema.get("maxProperties").and_then(Value::as_u64) {
+            if count > max {
+                out.push(violation("maxProperties", format!("Must have at most {} fields", max)));
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let pattern_properties = schema.get("patternProperties").and_then(Value::as_object);
+        let additional = schema.get("additionalProperties");
+        // Sorted so violations come out in a stable order.
+        let mut names: Vec<&String> = object.keys().collect();
+        names.sort();
+        for name in names {
+            let value = &object[name];
+            let child = format!("{}/{}", path, escape_pointer(name));
+            let mut matched = false;
+            if let Some(property) = properties.and_then(|p| p.get(name)) {
+                matched = true;
+                self.check(property, value, &child, depth, out);
+            }
+            for (pattern, property) in pattern_properties.into_iter().flatten() {
+                if self.patterns.get(pattern).is_some_and(|regex| regex.is_match(name)) {
+                    matched = true;
+                    self.check(property, value, &child, depth, out);
+                }
+            }
+            match additional {
+                Some(Value::Bool(false)) if !matched => out.push(Violation {
+                    path: child,
+                    keyword: "additionalProperties".to_string(),
+                    message: "Field is not allowed".to_string(),
+                }),
+                Some(additional @ Value::Object(_)) if !matched => {
+                    self.check(additional, value, &child, depth, out)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Target of a local reference such as `#/$defs/address`.
+    fn resolve(&self, reference: &str) -> Option<&Value> {
+        let pointer = reference.strip_prefix('#')?;
+        self.root.pointer(pointer)
+    }
+}
+
+fn collect_patterns(schema: &Value, patterns: &mut HashMap<String, Regex>) -> Result<(), String> {
+    let compile = |pattern: &str, patterns: &mut HashMap<String, Regex>| {
+        if !patterns.contains_key(pattern) {
+            let regex = Regex::new(pattern)
+                .map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+            patterns.insert(pattern.to_string(), regex);
+        }
+        Ok::<_, String>(())
+    };
+    match schema {
+        Value::Object(object) => {
+            for (key, value) in object {
+                match (key.as_str(), value) {
+                    ("pattern", Value::String(pattern)) => compile(pattern, patterns)?,
+                    ("patternProperties", Value::Object(properties)) => {
+                        for (pattern, property) in properties {
+                            compile(pattern, patterns)?;
+                            collect_patterns(property, patterns)?;
+                        }
+                    }
+                    // `enum` and `const` hold data, not schemas.
+                    ("enum" | "const", _) => {}
+                    _ => collect_patterns(value, patterns)?,
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_patterns(value, patterns)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Equality with `1` and `1.0` equal, as JSON Schema compares numbers.
+fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(x, y)| json_eq(x, y))
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len() && x.iter().all(|(k, v)| y.get(k).is_some_and(|w| json_eq(v, w)))
+        }
+        _ => a == b,
+    }
+}
+
+fn matches_format(format: &str, s: &str) -> bool {
+    match format {
+        "date-time" => chrono::DateTime::parse_from_rfc3339(s).is_ok(),
+        "date" => chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok(),
+        "email" => s.split_once('@').is_some_and(|(local, domain)| {
+            !local.is_empty() && domain.contains('.') && !s.contains(char::is_whitespace)
+        }),
+        "uuid" => uuid::Uuid::parse_str(s).is_ok() && s.len() == 36,
+        "uri" => reqwest::Url::parse(s).is_ok(),
+        // Formats we do not know are annotations only.
+        _ => true,
+    }
+}
+
+fn escape_pointer(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+/// Validation node - routes valid items to output 0 and invalid ones, with
+/// their violations, to output 1.
+pub struct ValidateExecutor;
+
+impl ValidateExecutor {
+    fn validator(node: &Node) -> Result<SchemaValidator, String> {
+        let mode = match node.parameters.get("mode") {
+            Some(NodeParameterValue::String(mode)) => mode.as_str(),
+            _ => "jsonSchema",
+        };
+        match mode {
+            "jsonSchema" => {
+                let schema = match node.parameters.get("schema") {
+                    Some(NodeParameterValue::String(text)) => serde_json::from_str(text)
+                        .map_err(|e| format!("schema is not valid JSON: {}", e))?,
+                    Some(value @ NodeParameterValue::Object(_)) => {
+                        serde_json::to_value(value).map_err(|e| e.to_string())?
+                    }
+                    _ => return Err("schema is required".to_string()),
+                };
+                SchemaValidator::new(schema)
+            }
+            "contract" => {
+                let fields = match node.parameters.get("fields") {
+                    Some(fields @ NodeParameterValue::Array(_)) => {
+                        serde_json::to_value(fields).map_err(|e| e.to_string())?
+                    }
+                    _ => return Err("fields is required in contract mode".to_string()),
+                };
+                let fields = fields.as_array().cloned().unwrap_or_default();
+                SchemaValidator::from_contract(&fields)
+            }
+            other => Err(format!("Unknown mode '{}'", other)),
+        }
+    }
+}
+
+#[async_trait]
+impl NodeExecutor for ValidateExecutor {
+    fn node_type(&self) -> &str {
+        VALIDATE_NODE_TYPE
+    }
+
+    async fn execute(
+        &self,
+        node: &Node,
+        input: &TaskDataConnections,
+        _context: &RuntimeContext,
+    ) -> Result<NodeOutput, ExecutionEngineError> {
+        let node_error = |message: String| ExecutionEngineError::NodeExecution {
+            node: node.name.clone(),
+            message,
+        };
+        let validator = Self::validator(node).map_err(node_error)?;
+        let violations_field = match node.parameters.get("violationsField") {
+            Some(NodeParameterValue::String(field)) if !field.is_empty() => field.clone(),
+            _ => "validationErrors".to_string(),
+        };
+
+        let main_input = input.get("main").and_then(|v| v.first());
+        let items = main_input.cloned().unwrap_or_default();
+
+        let mut valid = Vec::new();
+        let mut invalid = Vec::new();
+        for mut item in items {
+            let json = Value::Object(object_to_json(&item.json));
+            let violations = validator.validate(&json);
+            if violations.is_empty() {
+                valid.push(item);
+                continue;
+            }
+            let violations = violations
+                .into_iter()
+                .map(|v| {
+                    let mut entry = n8n_workflow::DataObject::new();
+                    entry.insert("path".to_string(), GenericValue::String(v.path));
+                    entry.insert("keyword".to_string(), GenericValue::String(v.keyword));
+                    entry.insert("message".to_string(), GenericValue::String(v.message));
+                    GenericValue::Object(entry)
+                })
+                .collect();
+            item.json.insert(violations_field.clone(), GenericValue::Array(violations));
+            invalid.push(item);
+        }
+
+        // Output 0 = valid items, Output 1 = invalid items
+        Ok(vec![valid, invalid])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_violations_have_paths() {
+        let validator = SchemaValidator::new(json!({
+            "type": "object",
+            "required": ["id", "email"],
+            "additionalProperties": false,
+            "properties": {
+                "id": { "type": "integer", "minimum": 1 },
+                "email": { "type": "string", "format": "email" },
+                "address": { "$ref": "#/$defs/address" },
+                "tags": { "type": "array", "items": { "type": "string" }, "uniqueItems": true }
+            },
+            "$defs": {
+                "address": {
+                    "type": "object",
+                    "properties": { "zip": { "type": "string", "pattern": "^[0-9]{5}$" } }
+                }
+            }
+        }))
+        .unwrap();
+
+        let valid = json!({
+            "id": 3,
+            "email": "a@b.io",
+            "tags": ["x"],
+            "address": { "zip": "10115" }
+        });
+        assert!(validator.validate(&valid).is_empty());
+
+        let violations = validator.validate(&json!({
+            "id": 0,
+            "address": { "zip": "1011" },
+            "tags": ["x", 2, "x"],
+            "extra": true
+        }));
+        let found: Vec<(&str, &str)> = violations
+            .iter()
+            .map(|v| (v.path.as_str(), v.keyword.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("/email", "required"),
+                ("/address/zip", "pattern"),
+                ("/extra", "additionalProperties"),
+                ("/id", "minimum"),
+                ("/tags", "uniqueItems"),
+                ("/tags/1", "type"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_contract_mode() {
+        let validator = SchemaValidator::from_contract(&[
+            json!({ "name": "name", "type": "string", "required": true }),
+            json!({ "name": "age", "type": "integer", "nullable": true }),
+        ])
+        .unwrap();
+        assert!(validator.validate(&json!({ "name": "Ada", "age": null })).is_empty());
+        let violations = validator.validate(&json!({ "age": 3.5 }));
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[1].message, "Expected integer or null, got number");
+        assert!(SchemaValidator::from_contract(&[json!({ "name": "x", "type": "date" })]).is_err());
+    }
+}
@@ -0,0 +1,499 @@
+//! Signature verification of inbound webhooks.
+//!
+//! A webhook node verifies the requests it receives when its
+//! `options.signature` parameter names a provider:
+//!
+//! ```json
+//! { "options": { "signature": { "provider": "stripe", "secretEnv": "STRIPE_WEBHOOK_SECRET" } } }
+//! ```
+//!
+//! | Provider | Header | Scheme |
+//! |----------|--------|--------|
+//! | `stripe` | `Stripe-Signature` | HMAC-SHA256 of `{t}.{body}`, hex (`v1=`) |
+//! | `github` | `X-Hub-Signature-256` | HMAC-SHA256 of the body, hex (`sha256=`); legacy `X-Hub-Signature` (`sha1=`) |
+//! | `slack`  | `X-Slack-Signature` | HMAC-SHA256 of `v0:{timestamp}:{body}`, hex (`v0=`) |
+//! | `twilio` | `X-Twilio-Signature` | HMAC-SHA1 of the URL and sorted form fields, base64 |
+//!
+//! The secret is `secret` or the environment variable `secretEnv` (Twilio:
+//! the auth token). Stripe and Slack sign a timestamp, which must be within
+//! `toleranceSeconds` (default 300) of now. Twilio signs the public URL it
+//! called; set `url` when a proxy rewrites it. Twilio bodies other than
+//! forms are only accepted with their `bodySHA256` in the signed URL.
+//!
+//! Requests failing verification are rejected before the workflow runs.
+//! Verified ones carry a [`SignatureVerification`] in the item's
+//! `signature` field.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use n8n_workflow::{Node, NodeParameterValue};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Default maximum age of signed timestamps, in seconds.
+pub const DEFAULT_TOLERANCE_SECS: u64 = 300;
+
+/// Errors of signature configuration and verification.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    #[error("Invalid signature settings: {0}")]
+    InvalidConfig(String),
+
+    #[error("Missing {0} header")]
+    MissingHeader(&'static str),
+
+    #[error("Malformed {0} header")]
+    MalformedHeader(&'static str),
+
+    #[error("Signed timestamp is {0}s away from now")]
+    Expired(i64),
+
+    #[error("Signature does not match")]
+    Mismatch,
+}
+
+/// Webhook providers with a built-in signature scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureProvider {
+    Stripe,
+    #[serde(rename = "github")]
+    GitHub,
+    Slack,
+    Twilio,
+}
+
+impl SignatureProvider {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stripe => "stripe",
+            Self::GitHub => "github",
+            Self::Slack => "slack",
+            Self::Twilio => "twilio",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "stripe" => Some(Self::Stripe),
+            "github" => Some(Self::GitHub),
+            "slack" => Some(Self::Slack),
+            "twilio" => Some(Self::Twilio),
+            _ => None,
+        }
+    }
+}
+
+/// Signature settings of a webhook node (`options.signature`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureConfig {
+    pub provider: SignatureProvider,
+    /// Signing secret.
+    pub secret: Option<String>,
+    /// Environment variable holding the secret, read when `secret` is unset.
+    pub secret_env: Option<String>,
+    /// Maximum distance of a signed timestamp from now (Stripe, Slack).
+    pub tolerance_secs: u64,
+    /// Public URL the provider signs (Twilio); rebuilt from the request
+    /// when unset.
+    pub url: Option<String>,
+}
+
+impl SignatureConfig {
+    pub fn new(provider: SignatureProvider, secret: impl Into<String>) -> Self {
+        Self {
+            provider,
+            secret: Some(secret.into()),
+            secret_env: None,
+            tolerance_secs: DEFAULT_TOLERANCE_SECS,
+            url: None,
+        }
+    }
+
+    /// The settings of a webhook node, `None` when it does not verify
+    /// signatures.
+    pub fn from_node(node: &Node) -> Result<Option<Self>, SignatureError> {
+        let signature = match node.parameters.get("options") {
+            Some(NodeParameterValue::Object(options)) => match options.get("signature") {
+                Some(NodeParameterValue::Object(signature)) => signature,
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        let string = |name: &str| match signature.get(name) {
+            Some(NodeParameterValue::String(s)) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        };
+
+        let provider = match string("provider") {
+            Some(name) => SignatureProvider::from_str(&name).ok_or_else(|| {
+                SignatureError::InvalidConfig(format!("unknown provider '{}'", name))
+            })?,
+            None => return Ok(None),
+        };
+        let tolerance_secs = match signature.get("toleranceSeconds") {
+            Some(NodeParameterValue::Number(n)) if *n >= 0.0 => *n as u64,
+            Some(_) => {
+                return Err(SignatureError::InvalidConfig(
+                    "toleranceSeconds must be a non-negative number".to_string(),
+                ))
+            }
+            None => DEFAULT_TOLERANCE_SECS,
+        };
+
+        Ok(Some(Self {
+            provider,
+            secret: string("secret"),
+            secret_env: string("secretEnv"),
+            tolerance_secs,
+            url: string("url"),
+        }))
+    }
+
+    /// The signing secret, from `secret` or `secret_env`.
+    pub fn resolve_secret(&self) -> Result<String, SignatureError> {
+        if let Some(secret) = &self.secret {
+            return Ok(secret.clone());
+        }
+        let name = self.secret_env.as_deref().ok_or_else(|| {
+            SignatureError::InvalidConfig("secret or secretEnv is required".to_string())
+        })?;
+        std::env::var(name)
+            .ok()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| SignatureError::InvalidConfig(format!("{} is not set", name)))
+    }
+}
+
+/// The parts of an inbound request a signature covers.
+#[derive(Debug, Clone, Copy)]
+pub struct SignedRequest<'a> {
+    /// Header values by lower-case name.
+    pub headers: &'a HashMap<String, String>,
+    pub body: &'a [u8],
+    /// Full URL as the provider called it, with query string.
+    pub url: &'a str,
+}
+
+impl SignedRequest<'_> {
+    fn header(&self, name: &'static str) -> Result<&str, SignatureError> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(|s| s.trim())
+            .ok_or(SignatureError::MissingHeader(name))
+    }
+}
+
+/// Outcome of a successful verification, exposed to the workflow.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureVerification {
+    pub provider: SignatureProvider,
+    pub verified: bool,
+    pub algorithm: &'static str,
+    /// Signed timestamp (Stripe, Slack).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Verify `request` against the scheme of `config.provider`.
+pub fn verify(
+    config: &SignatureConfig,
+    secret: &str,
+    request: &SignedRequest<'_>,
+    now: DateTime<Utc>,
+) -> Result<SignatureVerification, SignatureError> {
+    let secret = secret.as_bytes();
+    let (algorithm, timestamp) = match config.provider {
+        SignatureProvider::Stripe => {
+            let header = request.header("Stripe-Signature")?;
+            let mut signed_at = None;
+            let mut signatures = Vec::new();
+            for part in header.split(',') {
+                match part.trim().split_once('=') {
+                    Some(("t", t)) => signed_at = Some(t),
+                    Some(("v1", signature)) => signatures.push(signature),
+                    _ => {}
+                }
+            }
+            let t = signed_at
+                .filter(|_| !signatures.is_empty())
+                .ok_or(SignatureError::MalformedHeader("Stripe-Signature"))?;
+            let timestamp = check_timestamp(t, "Stripe-Signature", config, now)?;
+
+            let mac = hmac_sha256(secret, &[t.as_bytes(), b".", request.body]);
+            if !signatures.iter().any(|s| matches_hex(mac.clone(), s)) {
+                return Err(SignatureError::Mismatch);
+            }
+            ("hmac-sha256", Some(timestamp))
+        }
+        SignatureProvider::GitHub => {
+            if let Ok(header) = request.header("X-Hub-Signature-256") {
+                let signature = header
+                    .strip_prefix("sha256=")
+                    .ok_or(SignatureError::MalformedHeader("X-Hub-Signature-256"))?;
+                if !matches_hex(hmac_sha256(secret, &[request.body]), signature) {
+                    return Err(SignatureError::Mismatch);
+                }
+                ("hmac-sha256", None)
+            } else {
+                let header = request
+                    .header("X-Hub-Signature")
+                    .map_err(|_| SignatureError::MissingHeader("X-Hub-Signature-256"))?;
+                let signature = header
+                    .strip_prefix("sha1=")
+                    .ok_or(SignatureError::MalformedHeader("X-Hub-Signature"))?;
+                let mut mac = Hmac::<Sha1>::new_from_slice(secret)
+                    .expect("HMAC accepts keys of any length");
+                mac.update(request.body);
+                if !matches_hex(mac, signature) {
+                    return Err(SignatureError::Mismatch);
+                }
+                ("hmac-sha1", None)
+            }
+        }
+        SignatureProvider::Slack => {
+            let t = request.header("X-Slack-Request-Timestamp")?;
+            let signature = request
+                .header("X-Slack-Signature")?
+                .strip_prefix("v0=")
+                .ok_or(SignatureError::MalformedHeader("X-Slack-Signature"))?;
+            let timestamp = check_timestamp(t, "X-Slack-Request-Timestamp", config, now)?;
+
+            let mac = hmac_sha256(secret, &[b"v0:", t.as_bytes(), b":", request.body]);
+            if !matches_hex(mac, signature) {
+                return Err(SignatureError::Mismatch);
+            }
+            ("hmac-sha256", Some(timestamp))
+        }
+        SignatureProvider::Twilio => {
+            let signature = BASE64
+                .decode(request.header("X-Twilio-Signature")?)
+                .map_err(|_| SignatureError::MalformedHeader("X-Twilio-Signature"))?;
+            let url = config.url.as_deref().unwrap_or(request.url);
+
+            let mut mac =
+                Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+            mac.update(url.as_bytes());
+            let form = request
+                .headers
+                .get("content-type")
+                .is_some_and(|t| t.starts_with("application/x-www-form-urlencoded"));
+            if form {
+                let mut fields = form_fields(request.body);
+                fields.sort();
+                for (name, value) in fields {
+                    mac.update(name.as_bytes());
+                    mac.update(value.as_bytes());
+                }
+            }
+            mac.verify_slice(&signature)
+                .map_err(|_| SignatureError::Mismatch)?;
+
+            // Other bodies are signed through a hash of the body in the URL;
+            // without one the signature would cover the URL alone.
+            if !form {
+                let expected =
+                    query_param(url, "bodySHA256").ok_or(SignatureError::Mismatch)?;
+                let actual = Sha256::digest(request.body);
+                if !decode_hex(&expected).is_some_and(|e| e == actual.as_slice()) {
+                    return Err(SignatureError::Mismatch);
+                }
+            }
+            ("hmac-sha1", None)
+        }
+    };
+
+    Ok(SignatureVerification {
+        provider: config.provider,
+        verified: true,
+        algorithm,
+        timestamp,
+    })
+}
+
+/// Parse a signed Unix timestamp and check it is within tolerance of `now`.
+fn check_timestamp(
+    t: &str,
+    header: &'static str,
+    config: &SignatureConfig,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>, SignatureError> {
+    let timestamp = t
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .ok_or(SignatureError::MalformedHeader(header))?;
+    let skew = (now - timestamp).num_seconds();
+    if skew.unsigned_abs() > config.tolerance_secs {
+        return Err(SignatureError::Expired(skew));
+    }
+    Ok(timestamp)
+}
+
+fn hmac_sha256(secret: &[u8], parts: &[&[u8]]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    for part in parts {
+        mac.update(part);
+    }
+    mac
+}
+
+/// Compare `mac` with a hex signature in constant time.
+fn matches_hex<M: Mac>(mac: M, signature: &str) -> bool {
+    decode_hex(signature).is_some_and(|bytes| mac.verify_slice(&bytes).is_ok())
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Decoded `name=value` pairs of a form-encoded body.
+fn form_fields(body: &[u8]) -> Vec<(String, String)> {
+    let decode = |s: &str| {
+        let s = s.replace('+', " ");
+        urlencoding::decode(&s)
+            .map(|d| d.into_owned())
+            .unwrap_or(s)
+    };
+    String::from_utf8_lossy(body)
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(name), decode(value))
+        })
+        .collect()
+}
+
+fn query_param(url: &str, name: &str) -> Option<String> {
+    let (_, query) = url.split_once('?')?;
+    form_fields(query.as_bytes())
+        .into_iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_stripe_and_github_signatures() {
+        let now = Utc::now();
+        let body = br#"{"id":"evt_1"}"#;
+        let t = now.timestamp().to_string();
+        let mac = hmac_sha256(b"whsec", &[t.as_bytes(), b".", body]);
+        let signature = hex(&mac.finalize().into_bytes());
+
+        let stripe = SignatureConfig::new(SignatureProvider::Stripe, "whsec");
+        let valid = headers(&[("stripe-signature", &format!("t={},v1=00,v1={}", t, signature))]);
+        let request = SignedRequest { headers: &valid, body, url: "/webhook/stripe" };
+        let verification = verify(&stripe, "whsec", &request, now).unwrap();
+        assert!(verification.verified);
+        assert_eq!(verification.timestamp.unwrap().timestamp(), now.timestamp());
+
+        let tampered = SignedRequest { body: br#"{"id":"evt_2"}"#, ..request };
+        assert_eq!(verify(&stripe, "whsec", &tampered, now), Err(SignatureError::Mismatch));
+
+        let github = SignatureConfig::new(SignatureProvider::GitHub, "gh");
+        let signature = hex(&hmac_sha256(b"gh", &[body]).finalize().into_bytes());
+        let valid = headers(&[("x-hub-signature-256", &format!("sha256={}", signature))]);
+        let request = SignedRequest { headers: &valid, body, url: "/webhook/gh" };
+        assert_eq!(verify(&github, "gh", &request, now).unwrap().algorithm, "hmac-sha256");
+        assert_eq!(verify(&github, "other", &request, now), Err(SignatureError::Mismatch));
+
+        let none = headers(&[]);
+        let request = SignedRequest { headers: &none, ..request };
+        assert_eq!(
+            verify(&github, "gh", &request, now),
+            Err(SignatureError::MissingHeader("X-Hub-Signature-256"))
+        );
+    }
+
+    #[test]
+    fn test_slack_timestamp_and_twilio_form() {
+        let now = Utc::now();
+        let slack = SignatureConfig::new(SignatureProvider::Slack, "slack");
+        let body = b"token=x&command=%2Fdeploy";
+        let stale = (now.timestamp() - 600).to_string();
+        let mac = hmac_sha256(b"slack", &[b"v0:", stale.as_bytes(), b":", body]);
+        let stale_headers = headers(&[
+            ("x-slack-request-timestamp", &stale),
+            ("x-slack-signature", &format!("v0={}", hex(&mac.finalize().into_bytes()))),
+        ]);
+        let request = SignedRequest { headers: &stale_headers, body, url: "/webhook/slack" };
+        assert!(matches!(verify(&slack, "slack", &request, now), Err(SignatureError::Expired(600))));
+
+        // Twilio signs the URL followed by the sorted form fields.
+        let url = "https://example.com/webhook/sms";
+        let body = b"To=%2B15550001111&Body=hello+world&From=%2B15552223333";
+        let mut mac = Hmac::<Sha1>::new_from_slice(b"token").unwrap();
+        mac.update(b"https://example.com/webhook/smsBodyhello worldFrom+15552223333To+15550001111");
+        let signature = BASE64.encode(mac.finalize().into_bytes());
+        let twilio_headers = headers(&[
+            ("content-type", "application/x-www-form-urlencoded"),
+            ("x-twilio-signature", &signature),
+        ]);
+        let request = SignedRequest { headers: &twilio_headers, body, url: "http://internal/webhook/sms" };
+
+        let mut twilio = SignatureConfig::new(SignatureProvider::Twilio, "token");
+        assert_eq!(verify(&twilio, "token", &request, now), Err(SignatureError::Mismatch));
+        twilio.url = Some(url.to_string());
+        assert_eq!(verify(&twilio, "token", &request, now).unwrap().algorithm, "hmac-sha1");
+    }
+
+    #[test]
+    fn test_twilio_json_body_needs_its_hash() {
+        let now = Utc::now();
+        let body = br#"{"MessageSid":"SM1"}"#;
+        let sign = |url: &str| {
+            let mut mac = Hmac::<Sha1>::new_from_slice(b"token").unwrap();
+            mac.update(url.as_bytes());
+            BASE64.encode(mac.finalize().into_bytes())
+        };
+        let verify_at = |url: &str, body: &[u8]| {
+            let signature = sign(url);
+            let json = headers(&[
+                ("content-type", "application/json"),
+                ("x-twilio-signature", &signature),
+            ]);
+            let request = SignedRequest { headers: &json, body, url };
+            let twilio = SignatureConfig::new(SignatureProvider::Twilio, "token");
+            verify(&twilio, "token", &request, now)
+        };
+
+        let hashed = format!(
+            "https://example.com/webhook/sms?bodySHA256={}",
+            hex(&Sha256::digest(body))
+        );
+        assert!(verify_at(&hashed, body).unwrap().verified);
+        assert_eq!(verify_at(&hashed, b"{}"), Err(SignatureError::Mismatch));
+        // Without the hash the signature covers no part of the body.
+        assert_eq!(
+            verify_at("https://example.com/webhook/sms", body),
+            Err(SignatureError::Mismatch)
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Variables of workflows, read in expressions through `$vars`.
+//!
+//! Variables are global or belong to a project. A workflow sees the global
+//! ones and those of its project, a project variable taking the place of a
+//! global one with the same key, so a project can override an instance
+//! default such as an API base URL. The engine reads them from a
+//! [`VariableSource`], such as the variables repository, once per
+//! execution, so every node of an execution sees the same values.
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Where variables come from.
+#[async_trait]
+pub trait VariableSource: Send + Sync {
+    /// Variables visible in `project_id`, or the global ones for `None`,
+    /// project variables overriding global ones of the same key.
+    async fn variables(
+        &self,
+        project_id: Option<&str>,
+    ) -> Result<HashMap<String, String>, String>;
+}
+
+/// The engine's source of variables. Clones share the source, which may be
+/// set once one becomes available; until then `$vars` is empty.
+#[derive(Clone, Default)]
+pub struct WorkflowVariables {
+    source: Arc<RwLock<Option<Arc<dyn VariableSource>>>>,
+}
+
+impl WorkflowVariables {
+    /// Read variables from `source` from now on.
+    pub fn set_source(&self, source: Arc<dyn VariableSource>) {
+        *self.source.write() = Some(source);
+    }
+
+    /// Whether a source is set.
+    pub fn has_source(&self) -> bool {
+        self.source.read().is_some()
+    }
+
+    /// Variables of workflows in `project_id`, as `$vars` sees them.
+    pub async fn resolve(
+        &self,
+        project_id: Option<&str>,
+    ) -> Result<HashMap<String, Value>, String> {
+        let source = self.source.read().clone();
+        let Some(source) = source else {
+            return Ok(HashMap::new());
+        };
+        let variables = source.variables(project_id).await?;
+        Ok(variables.into_iter().map(|(key, value)| (key, Value::String(value))).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Variables;
+
+    #[async_trait]
+    impl VariableSource for Variables {
+        async fn variables(
+            &self,
+            project_id: Option<&str>,
+        ) -> Result<HashMap<String, String>, String> {
+            let base_url = match project_id {
+                Some("sales") => "https://sales.example",
+                _ => "https://api.example",
+            };
+            Ok(HashMap::from([
+                ("region".to_string(), "eu".to_string()),
+                ("baseUrl".to_string(), base_url.to_string()),
+            ]))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_reads_the_source() {
+        let variables = WorkflowVariables::default();
+        assert!(variables.resolve(Some("sales")).await.unwrap().is_empty());
+
+        variables.set_source(Arc::new(Variables));
+        let sales = variables.resolve(Some("sales")).await.unwrap();
+        assert_eq!(sales["baseUrl"], "https://sales.example");
+        assert_eq!(sales["region"], "eu");
+        let global = variables.resolve(None).await.unwrap();
+        assert_eq!(global["baseUrl"], "https://api.example");
+    }
+}
@@ -5,11 +5,16 @@
 //! streaming.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use n8n_core::{ExecutionEvent, WorkflowEngine};
+use async_trait::async_trait;
+use n8n_core::{
+    ExecutionEngineError, ExecutionEvent, NodeExecutor, NodeExecutorRegistry, NodeOutput,
+    RuntimeConfig, RuntimeContext, Supply, WorkflowEngine,
+};
 use n8n_workflow::{
-    ExecutionStatus, GenericValue, Node, NodeExecutionData, NodeParameterValue,
-    Workflow, WorkflowExecuteMode,
+    connection::CONNECTION_AI_LANGUAGE_MODEL, ExecutionOrder, ExecutionStatus, GenericValue, Node,
+    NodeExecutionData, NodeParameterValue, TaskDataConnections, Workflow, WorkflowExecuteMode,
 };
 use tokio::sync::mpsc;
 
@@ -779,3 +784,209 @@ async fn test_event_streaming() {
         "Last event should be Finished"
     );
 }
+
+/// 11. Execution order.
+///     Trigger fans out to Bottom (connected first) and Top, which has a
+///     child. v0 runs breadth-first in connection order; v1 finishes the
+///     top branch before starting the bottom one.
+#[tokio::test]
+async fn test_execution_order_v0_and_v1() {
+    let engine = WorkflowEngine::default();
+
+    let mut top = noop_node("Top");
+    top.position = [200.0, 0.0];
+    let mut bottom = noop_node("Bottom");
+    bottom.position = [200.0, 300.0];
+    let mut workflow = make_workflow(
+        "execution_order",
+        vec![manual_trigger("Trigger"), bottom, top, noop_node("TopChild")],
+        &[
+            ("Trigger", "Bottom", 0, 0),
+            ("Trigger", "Top", 0, 0),
+            ("Top", "TopChild", 0, 0),
+        ],
+    );
+
+    let mut orders = Vec::new();
+    for order in [None, Some(ExecutionOrder::V1)] {
+        workflow.settings.execution_order = order;
+        let (tx, mut rx) = mpsc::channel::<ExecutionEvent>(100);
+        let run = engine
+            .execute_with_events(&workflow, WorkflowExecuteMode::Manual, None, tx)
+            .await
+            .expect("Execution should succeed");
+        assert_eq!(run.status, ExecutionStatus::Success);
+
+        let mut started = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let ExecutionEvent::NodeStarted { node_name, .. } = event {
+                started.push(node_name);
+            }
+        }
+        orders.push(started);
+    }
+
+    assert_eq!(orders[0], vec!["Trigger", "Bottom", "Top", "TopChild"]);
+    assert_eq!(orders[1], vec!["Trigger", "Top", "TopChild", "Bottom"]);
+}
+
+/// 12. executeOnce and alwaysOutputData.
+///     Trigger(3 items) -> Once(executeOnce) -> Limit(maxItems=0,
+///     alwaysOutputData) -> After.
+///     Once only sees the first item; Limit outputs nothing, so it emits one
+///     empty item and After still runs.
+#[tokio::test]
+async fn test_execute_once_and_always_output_data() {
+    let engine = WorkflowEngine::default();
+
+    let mut once = noop_node("Once");
+    once.execute_once = true;
+    let mut limit = limit_node("Limit", 0.0);
+    limit.always_output_data = true;
+    let workflow = make_workflow(
+        "node_flags",
+        vec![manual_trigger("Trigger"), once, limit, noop_node("After")],
+        &[
+            ("Trigger", "Once", 0, 0),
+            ("Once", "Limit", 0, 0),
+            ("Limit", "After", 0, 0),
+        ],
+    );
+
+    let input_items: Vec<NodeExecutionData> = ["a", "b", "c"]
+        .into_iter()
+        .map(|name| {
+            let mut data = HashMap::new();
+            data.insert("name".to_string(), GenericValue::String(name.to_string()));
+            NodeExecutionData::new(data)
+        })
+        .collect();
+
+    let run = engine
+        .execute(&workflow, WorkflowExecuteMode::Manual, Some(input_items))
+        .await
+        .expect("Execution should succeed");
+
+    assert_eq!(run.status, ExecutionStatus::Success);
+    assert_eq!(get_node_output_items(&run, "Trigger").len(), 3);
+
+    let once_items = get_node_output_items(&run, "Once");
+    assert_eq!(once_items.len(), 1);
+    assert!(matches!(
+        once_items[0].json.get("name"),
+        Some(GenericValue::String(name)) if name == "a"
+    ));
+
+    let limit_items = get_node_output_items(&run, "Limit");
+    assert_eq!(limit_items.len(), 1);
+    assert!(limit_items[0].json.is_empty());
+    assert_eq!(get_node_output_items(&run, "After").len(), 1);
+}
+
+/// Sub-node supplying the name of its `model` parameter as a language model.
+struct TestChatModel;
+
+#[async_trait]
+impl NodeExecutor for TestChatModel {
+    fn node_type(&self) -> &str {
+        "test.chatModel"
+    }
+
+    async fn execute(
+        &self,
+        node: &Node,
+        _input: &TaskDataConnections,
+        _context: &RuntimeContext,
+    ) -> Result<NodeOutput, ExecutionEngineError> {
+        panic!("sub-node {} must not be executed", node.name)
+    }
+
+    async fn supply_data(
+        &self,
+        node: &Node,
+        _context: &RuntimeContext,
+    ) -> Result<Supply, ExecutionEngineError> {
+        let model = match node.get_parameter("model") {
+            Some(NodeParameterValue::String(model)) => model.clone(),
+            _ => String::new(),
+        };
+        Ok(Arc::new(model))
+    }
+}
+
+/// Root node outputting the models supplied to it.
+struct TestAgent;
+
+#[async_trait]
+impl NodeExecutor for TestAgent {
+    fn node_type(&self) -> &str {
+        "test.agent"
+    }
+
+    async fn execute(
+        &self,
+        _node: &Node,
+        _input: &TaskDataConnections,
+        context: &RuntimeContext,
+    ) -> Result<NodeOutput, ExecutionEngineError> {
+        let items = context
+            .input_connection_data(CONNECTION_AI_LANGUAGE_MODEL)
+            .await?
+            .iter()
+            .map(|supplied| {
+                let model = supplied.downcast::<String>().expect("a model name");
+                let mut data = HashMap::new();
+                data.insert("model".to_string(), GenericValue::String(model.to_string()));
+                NodeExecutionData::new(data)
+            })
+            .collect();
+        Ok(vec![items])
+    }
+}
+
+/// 13. Sub-nodes.
+///     Trigger -> Agent, with Model attached to Agent as its language model.
+///     Model is no start node and never runs on its own: Agent resolves it
+///     when asking for its language model, with Model's expression resolved
+///     against Agent's input.
+#[tokio::test]
+async fn test_sub_nodes_are_resolved_by_their_root_node() {
+    let mut executors = NodeExecutorRegistry::new();
+    executors.register(Arc::new(TestChatModel));
+    executors.register(Arc::new(TestAgent));
+    let engine = WorkflowEngine::with_executors(executors, RuntimeConfig::default());
+
+    let mut model = Node::new("Model", "test.chatModel");
+    model.set_parameter(
+        "model",
+        NodeParameterValue::String("={{ $json.model }}".to_string()),
+    );
+    let mut workflow = make_workflow(
+        "sub_nodes",
+        vec![manual_trigger("Trigger"), Node::new("Agent", "test.agent"), model],
+        &[("Trigger", "Agent", 0, 0)],
+    );
+    workflow
+        .connect_with_type("Model", "Agent", CONNECTION_AI_LANGUAGE_MODEL, 0, 0)
+        .unwrap();
+
+    let mut data = HashMap::new();
+    data.insert("model".to_string(), GenericValue::String("gpt-4o".to_string()));
+    let run = engine
+        .execute(
+            &workflow,
+            WorkflowExecuteMode::Manual,
+            Some(vec![NodeExecutionData::new(data)]),
+        )
+        .await
+        .expect("Execution should succeed");
+
+    assert_eq!(run.status, ExecutionStatus::Success);
+    assert!(!run.data.result_data.run_data.contains_key("Model"));
+    let agent_items = get_node_output_items(&run, "Agent");
+    assert_eq!(agent_items.len(), 1);
+    assert!(matches!(
+        agent_items[0].json.get("model"),
+        Some(GenericValue::String(model)) if model == "gpt-4o"
+    ));
+}
@@ -0,0 +1,105 @@
+//! Importing workflows and tags one row at a time versus in bulk.
+//!
+//! Needs a migrated database in `DATABASE_URL`; without one it does
+//! nothing. Every iteration writes in a transaction it rolls back, so the
+//! database is left as it was.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use n8n_db::{
+    connect, DbContext, InsertTag, InsertWorkflow, TagEntity, TagRepository, WorkflowEntity,
+    WorkflowRepository,
+};
+use sqlx::PgPool;
+use tokio::runtime::Runtime;
+
+const ROWS: usize = 500;
+
+fn workflows() -> Vec<InsertWorkflow> {
+    (0..ROWS)
+        .map(|i| {
+            let mut workflow = WorkflowEntity::new(format!("Imported workflow {}", i));
+            workflow.connections = serde_json::json!({ "Webhook": { "main": [[]] } });
+            InsertWorkflow::from(&workflow)
+        })
+        .collect()
+}
+
+fn tags() -> Vec<InsertTag> {
+    (0..ROWS)
+        .map(|i| InsertTag::from(&TagEntity::new(format!("bench-{}", i))))
+        .collect()
+}
+
+fn setup(runtime: &Runtime) -> Option<PgPool> {
+    let Ok(url) = std::env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL is not set, skipping the import benchmark");
+        return None;
+    };
+    runtime.block_on(async {
+        let pool = connect(&url).await.expect("connect to DATABASE_URL");
+        DbContext::new(pool.clone()).migrate().await.expect("migrate");
+        Some(pool)
+    })
+}
+
+fn bench_import(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let Some(pool) = setup(&runtime) else {
+        return;
+    };
+    let workflows = workflows();
+    let tags = tags();
+
+    let mut group = c.benchmark_group("import_500_workflows");
+    group.throughput(Throughput::Elements(ROWS as u64));
+    group.sample_size(10);
+    group.bench_function("create", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let mut tx = pool.begin().await.unwrap();
+                for workflow in &workflows {
+                    WorkflowRepository::create_in(&mut *tx, workflow).await.unwrap();
+                }
+                tx.rollback().await.unwrap();
+            })
+        })
+    });
+    group.bench_function("insert_many", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let mut tx = pool.begin().await.unwrap();
+                WorkflowRepository::insert_many_in(&mut *tx, &workflows).await.unwrap();
+                tx.rollback().await.unwrap();
+            })
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("import_500_tags");
+    group.throughput(Throughput::Elements(ROWS as u64));
+    group.sample_size(10);
+    group.bench_function("create", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let mut tx = pool.begin().await.unwrap();
+                for tag in &tags {
+                    TagRepository::create_in(&mut *tx, tag).await.unwrap();
+                }
+                tx.rollback().await.unwrap();
+            })
+        })
+    });
+    group.bench_function("upsert_many", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let mut tx = pool.begin().await.unwrap();
+                TagRepository::upsert_many_in(&mut *tx, &tags).await.unwrap();
+                tx.rollback().await.unwrap();
+            })
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_import);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 002_timers
+--
+-- Durable timers for delays and timer-triggered workflows. Instances claim
+-- due timers with SELECT ... FOR UPDATE SKIP LOCKED and hold a lease
+-- (locked_by/locked_until) while firing, so each timer fires on one instance.
+
+-- =============================================================================
+-- TIMER_ENTITY
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS timer_entity (
+    id VARCHAR(36) PRIMARY KEY,
+    workflow_id VARCHAR(36) NOT NULL REFERENCES workflow_entity(id) ON DELETE CASCADE,
+    node VARCHAR(255) NOT NULL,
+    fire_at TIMESTAMPTZ NOT NULL,
+    payload JSONB,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    locked_by VARCHAR(255),
+    locked_until TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_timer_fire_at ON timer_entity(fire_at);
+CREATE INDEX idx_timer_workflow ON timer_entity(workflow_id);
@@ -0,0 +1,20 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 003_oauth_state
+--
+-- Pending OAuth authorizations. A row is written when an OAuth flow starts
+-- and removed by the callback; rows whose callback never arrives are purged
+-- by the garbage collector once expires_at has passed.
+
+-- =============================================================================
+-- OAUTH_STATE
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS oauth_state (
+    state VARCHAR(255) PRIMARY KEY,
+    credential_id VARCHAR(36) NOT NULL REFERENCES credentials_entity(id) ON DELETE CASCADE,
+    user_id UUID,
+    code_verifier TEXT,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_oauth_state_expires_at ON oauth_state(expires_at);
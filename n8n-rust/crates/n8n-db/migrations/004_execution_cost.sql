@@ -0,0 +1,24 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 004_execution_cost
+--
+-- Priced quantities of node runs, one row per cost line, written with the
+-- execution when cost accounting is enabled. Reports aggregate them per
+-- execution, workflow or project.
+
+-- =============================================================================
+-- EXECUTION_COST
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS execution_cost (
+    id SERIAL PRIMARY KEY,
+    execution_id VARCHAR(36) NOT NULL REFERENCES execution_entity(id) ON DELETE CASCADE,
+    node VARCHAR(128) NOT NULL,
+    node_type VARCHAR(128) NOT NULL,
+    run_index INTEGER NOT NULL,
+    unit VARCHAR(255) NOT NULL,
+    quantity DOUBLE PRECISION NOT NULL,
+    cost DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_execution_cost_execution_id ON execution_cost(execution_id);
+CREATE INDEX idx_execution_cost_created_at ON execution_cost(created_at);
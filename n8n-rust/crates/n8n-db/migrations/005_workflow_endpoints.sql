@@ -0,0 +1,32 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 005_workflow_endpoints
+--
+-- Workflows published as REST endpoints: a method and path (with `:name`
+-- parameters) mapped to a workflow node, with declared request and
+-- response schemas. Calls are authorized by API keys carrying the
+-- endpoint's scopes.
+
+-- =============================================================================
+-- WORKFLOW_ENDPOINT
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS workflow_endpoint (
+    id VARCHAR(36) PRIMARY KEY DEFAULT substring(md5(random()::text), 1, 21),
+    workflow_id VARCHAR(36) NOT NULL REFERENCES workflow_entity(id) ON DELETE CASCADE,
+    node VARCHAR(255) NOT NULL,
+    method VARCHAR(16) NOT NULL,
+    path VARCHAR(255) NOT NULL,
+    summary TEXT,
+    request_schema JSONB,
+    response_schema JSONB,
+    scopes TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_workflow_endpoint_workflow ON workflow_endpoint(workflow_id);
+CREATE UNIQUE INDEX idx_workflow_endpoint_route ON workflow_endpoint(method, path);
+
+-- =============================================================================
+-- API_KEY scopes
+-- =============================================================================
+ALTER TABLE api_key ADD COLUMN IF NOT EXISTS scopes TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,43 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 006_execution_annotations
+--
+-- QA annotations of executions: a vote (up or down), a note and tags from
+-- their own vocabulary, separate from workflow tags.
+--
+-- Reference: packages/@n8n/db/src/entities/execution-annotation.ee.ts
+--            packages/@n8n/db/src/entities/annotation-tag-entity.ee.ts
+
+-- =============================================================================
+-- ANNOTATION_TAG_ENTITY
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS annotation_tag_entity (
+    id VARCHAR(36) PRIMARY KEY,
+    name VARCHAR(24) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- =============================================================================
+-- EXECUTION_ANNOTATIONS
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS execution_annotations (
+    id SERIAL PRIMARY KEY,
+    execution_id VARCHAR(36) NOT NULL UNIQUE REFERENCES execution_entity(id) ON DELETE CASCADE,
+    vote VARCHAR(6) CHECK (vote IN ('up', 'down')),
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_execution_annotations_vote ON execution_annotations(vote);
+
+-- =============================================================================
+-- EXECUTION_ANNOTATION_TAGS (Junction table)
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS execution_annotation_tags (
+    annotation_id INTEGER NOT NULL REFERENCES execution_annotations(id) ON DELETE CASCADE,
+    tag_id VARCHAR(36) NOT NULL REFERENCES annotation_tag_entity(id) ON DELETE CASCADE,
+    PRIMARY KEY (annotation_id, tag_id)
+);
+
+CREATE INDEX idx_execution_annotation_tags_tag ON execution_annotation_tags(tag_id);
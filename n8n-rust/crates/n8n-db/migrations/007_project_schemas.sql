@@ -0,0 +1,15 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 007_project_schemas
+--
+-- Projects whose workflows and executions live in a schema of their own.
+-- Each such schema carries the full migration set; this registry, in the
+-- shared schema, tells requests and migration tooling where they are.
+
+-- =============================================================================
+-- PROJECT_SCHEMA
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS project_schema (
+    project_id VARCHAR(36) PRIMARY KEY REFERENCES project(id) ON DELETE RESTRICT,
+    schema_name VARCHAR(63) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,13 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 008_execution_pruning
+--
+-- Indexes for execution pruning: finding executions by age or rank among
+-- the newest, and soft-deleted executions due for hard deletion.
+
+-- =============================================================================
+-- EXECUTION_ENTITY
+-- =============================================================================
+CREATE INDEX IF NOT EXISTS idx_execution_created_at ON execution_entity(created_at DESC, id DESC)
+    WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_execution_deleted_at ON execution_entity(deleted_at)
+    WHERE deleted_at IS NOT NULL;
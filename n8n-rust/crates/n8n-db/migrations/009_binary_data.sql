@@ -0,0 +1,22 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 009_binary_data
+--
+-- References to binary payloads moved out of execution data into a
+-- binary data store. The bytes are in the store; rows go with their
+-- execution.
+
+-- =============================================================================
+-- BINARY_DATA
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS binary_data (
+    id VARCHAR(512) PRIMARY KEY,
+    mode VARCHAR(32) NOT NULL,
+    execution_id VARCHAR(36) NOT NULL REFERENCES execution_entity(id) ON DELETE CASCADE,
+    workflow_id VARCHAR(36),
+    mime_type VARCHAR(255) NOT NULL,
+    file_name VARCHAR(255),
+    file_size BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_binary_data_execution ON binary_data(execution_id);
@@ -0,0 +1,19 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 010_execution_resource_usage
+--
+-- Wall time, CPU time and peak RSS rise measured around each execution
+-- when resource usage capture is enabled, usually on queue workers.
+-- Insights aggregate them per workflow.
+
+-- =============================================================================
+-- EXECUTION_RESOURCE_USAGE
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS execution_resource_usage (
+    execution_id VARCHAR(36) PRIMARY KEY REFERENCES execution_entity(id) ON DELETE CASCADE,
+    wall_ms DOUBLE PRECISION NOT NULL,
+    cpu_ms DOUBLE PRECISION,
+    peak_rss_delta BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_execution_resource_usage_created_at ON execution_resource_usage(created_at);
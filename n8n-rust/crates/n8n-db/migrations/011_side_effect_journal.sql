@@ -0,0 +1,26 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 011_side_effect_journal
+--
+-- Write-ahead journal of side-effecting node runs: an intent is written
+-- before each attempt and completed after it. Intents never completed are
+-- side effects in doubt after a crash. No foreign key to execution_entity,
+-- as intents are written before the execution is saved.
+
+-- =============================================================================
+-- SIDE_EFFECT_JOURNAL
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS side_effect_journal (
+    id VARCHAR(36) PRIMARY KEY,
+    execution_id VARCHAR(36) NOT NULL,
+    workflow_id VARCHAR(36) NOT NULL,
+    node VARCHAR(128) NOT NULL,
+    node_type VARCHAR(128) NOT NULL,
+    attempt INTEGER NOT NULL DEFAULT 0,
+    intent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    outcome VARCHAR(16)
+);
+
+CREATE INDEX idx_side_effect_journal_execution ON side_effect_journal(execution_id);
+CREATE INDEX idx_side_effect_journal_in_doubt ON side_effect_journal(intent_at)
+    WHERE completed_at IS NULL;
@@ -0,0 +1,18 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 012_workflow_statistics
+--
+-- Execution counts per workflow, as in n8n: production and manual
+-- successes and errors, and whether the workflow's trigger has loaded
+-- data. No foreign key to workflow_entity, as workflows may also run from
+-- in-memory storage.
+
+-- =============================================================================
+-- WORKFLOW_STATISTICS
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS workflow_statistics (
+    workflow_id VARCHAR(36) NOT NULL,
+    name VARCHAR(128) NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    latest_event TIMESTAMPTZ,
+    PRIMARY KEY (workflow_id, name)
+);
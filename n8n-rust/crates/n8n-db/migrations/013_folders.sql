@@ -0,0 +1,19 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 013_folders
+--
+-- Tags of folders, as in n8n, and an index for listing the folders of a
+-- project level by level. The folder table itself is part of the initial
+-- schema.
+
+-- =============================================================================
+-- FOLDER_TAG_MAPPING (Junction table)
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS folder_tag_mapping (
+    folder_id VARCHAR(36) NOT NULL REFERENCES folder(id) ON DELETE CASCADE,
+    tag_id VARCHAR(36) NOT NULL REFERENCES tag_entity(id) ON DELETE CASCADE,
+    PRIMARY KEY (folder_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_folder_tag_tag ON folder_tag_mapping(tag_id);
+
+CREATE INDEX IF NOT EXISTS idx_folder_project_parent ON folder(project_id, parent_folder_id);
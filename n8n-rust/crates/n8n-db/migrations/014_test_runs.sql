@@ -0,0 +1,54 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 014_test_runs
+--
+-- Evaluation runs of workflows against test sets, with the outcome of
+-- each case. Runs aggregate the scores of their cases in metrics. No
+-- foreign key to workflow_entity, as workflows may also run from in-memory
+-- storage.
+--
+-- Reference: packages/@n8n/db/src/entities/test-run.ee.ts
+--            packages/@n8n/db/src/entities/test-case-execution.ee.ts
+
+-- =============================================================================
+-- TEST_RUN
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS test_run (
+    id VARCHAR(36) PRIMARY KEY,
+    workflow_id VARCHAR(36) NOT NULL,
+    test_set_id VARCHAR(36),
+    status VARCHAR(20) NOT NULL
+        CHECK (status IN ('new', 'running', 'completed', 'error', 'cancelled')),
+    error_code VARCHAR(255),
+    error_details JSONB,
+    run_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+    metrics JSONB,
+    passed_cases INTEGER NOT NULL DEFAULT 0,
+    failed_cases INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_test_run_workflow ON test_run(workflow_id, created_at DESC);
+
+-- =============================================================================
+-- TEST_CASE_EXECUTION
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS test_case_execution (
+    id VARCHAR(36) PRIMARY KEY,
+    test_run_id VARCHAR(36) NOT NULL REFERENCES test_run(id) ON DELETE CASCADE,
+    execution_id VARCHAR(36) REFERENCES execution_entity(id) ON DELETE SET NULL,
+    case_id VARCHAR(36),
+    status VARCHAR(20) NOT NULL
+        CHECK (status IN ('new', 'running', 'success', 'error', 'cancelled')),
+    run_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+    error_code VARCHAR(255),
+    error_details JSONB,
+    metrics JSONB,
+    outputs JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_test_case_execution_run ON test_case_execution(test_run_id);
@@ -0,0 +1,21 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 015_processed_data
+--
+-- Values Remove Duplicates nodes saw in earlier executions, one per
+-- workflow and context (`workflow`, or `node:<id>` per node). No foreign
+-- key to workflow_entity, as workflows may also run from in-memory storage.
+--
+-- Reference: packages/@n8n/db/src/entities/processed-data.ts
+
+-- =============================================================================
+-- PROCESSED_DATA
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS processed_data (
+    workflow_id VARCHAR(36) NOT NULL,
+    context VARCHAR(255) NOT NULL,
+    -- {"mode": "entries" | "latestIncrementalKey" | "latestDate", "data": ...}
+    value JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (workflow_id, context)
+);
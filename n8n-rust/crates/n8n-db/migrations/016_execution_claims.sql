@@ -0,0 +1,18 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 016_execution_claims
+--
+-- Workers without Redis take queued (`new`) executions straight from
+-- execution_entity: they claim one with SELECT ... FOR UPDATE SKIP LOCKED
+-- and hold a lease (claimed_by/lease_until) they renew with heartbeats
+-- while running it. A running execution whose lease expired belonged to a
+-- crashed worker and is claimable again.
+
+ALTER TABLE execution_entity ADD COLUMN IF NOT EXISTS claimed_by VARCHAR(255);
+ALTER TABLE execution_entity ADD COLUMN IF NOT EXISTS lease_until TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_execution_pending
+    ON execution_entity(created_at, id)
+    WHERE status = 'new' AND deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_execution_lease_until
+    ON execution_entity(lease_until)
+    WHERE status = 'running' AND lease_until IS NOT NULL;
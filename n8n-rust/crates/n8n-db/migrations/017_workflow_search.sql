@@ -0,0 +1,69 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 017_workflow_search
+--
+-- Full-text search over workflows. search_vector is generated from the
+-- name, tag names, description and node types, weighted in that order,
+-- and GIN-indexed. Generated columns cannot read other tables, so the tag
+-- names are copied into tag_names by triggers on workflow_tag_mapping and
+-- tag_entity. The 'simple' configuration keeps words unstemmed, as names
+-- and node types are rarely prose; dots and dashes in node types split
+-- words, so `httpRequest` finds `n8n-nodes-base.httpRequest`.
+
+ALTER TABLE workflow_entity ADD COLUMN IF NOT EXISTS tag_names TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE workflow_entity ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', name), 'A') ||
+        setweight(to_tsvector('simple', tag_names), 'B') ||
+        setweight(to_tsvector('simple', coalesce(description, '')), 'C') ||
+        setweight(to_tsvector('simple', translate(
+            jsonb_path_query_array(nodes, '$[*].type')::text, '.-"[],', '      '
+        )), 'D')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_workflow_search ON workflow_entity USING GIN (search_vector);
+
+-- =============================================================================
+-- TAG_NAMES maintenance
+-- =============================================================================
+CREATE OR REPLACE FUNCTION refresh_workflow_tag_names(target VARCHAR) RETURNS void AS $$
+    UPDATE workflow_entity
+    SET tag_names = coalesce((
+        SELECT string_agg(t.name, ' ' ORDER BY t.name)
+        FROM workflow_tag_mapping m JOIN tag_entity t ON t.id = m.tag_id
+        WHERE m.workflow_id = target
+    ), '')
+    WHERE id = target;
+$$ LANGUAGE sql;
+
+CREATE OR REPLACE FUNCTION workflow_tag_mapping_changed() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM refresh_workflow_tag_names(OLD.workflow_id);
+    ELSE
+        PERFORM refresh_workflow_tag_names(NEW.workflow_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION tag_entity_renamed() RETURNS trigger AS $$
+BEGIN
+    PERFORM refresh_workflow_tag_names(m.workflow_id)
+    FROM workflow_tag_mapping m WHERE m.tag_id = NEW.id;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS workflow_tag_mapping_search ON workflow_tag_mapping;
+CREATE TRIGGER workflow_tag_mapping_search
+    AFTER INSERT OR DELETE ON workflow_tag_mapping
+    FOR EACH ROW EXECUTE FUNCTION workflow_tag_mapping_changed();
+
+DROP TRIGGER IF EXISTS tag_entity_search ON tag_entity;
+CREATE TRIGGER tag_entity_search
+    AFTER UPDATE OF name ON tag_entity
+    FOR EACH ROW EXECUTE FUNCTION tag_entity_renamed();
+
+SELECT refresh_workflow_tag_names(workflow_id)
+FROM (SELECT DISTINCT workflow_id FROM workflow_tag_mapping) tagged;
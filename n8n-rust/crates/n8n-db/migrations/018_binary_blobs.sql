@@ -0,0 +1,62 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 018_binary_blobs
+--
+-- Content-addressed binary data. Deduplicated payloads are stored once at
+-- blobs/sha256/{digest} and referenced by every execution that produced
+-- them, so a reference id is no longer unique to one execution. binary_blob
+-- counts the references of each blob; triggers on binary_data keep the
+-- count, including deletes cascaded from execution_entity. Blobs whose
+-- count drops to 0 are removed by the garbage collector.
+
+ALTER TABLE binary_data DROP CONSTRAINT IF EXISTS binary_data_pkey;
+ALTER TABLE binary_data ADD PRIMARY KEY (id, execution_id);
+
+-- =============================================================================
+-- BINARY_BLOB
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS binary_blob (
+    id VARCHAR(512) PRIMARY KEY,
+    mode VARCHAR(32) NOT NULL,
+    file_size BIGINT,
+    ref_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    unreferenced_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_binary_blob_unreferenced
+    ON binary_blob(mode, unreferenced_at) WHERE ref_count = 0;
+
+-- =============================================================================
+-- REF_COUNT maintenance
+-- =============================================================================
+CREATE OR REPLACE FUNCTION binary_blob_referenced() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO binary_blob (id, mode, file_size, ref_count)
+    VALUES (NEW.id, NEW.mode, NEW.file_size, 1)
+    ON CONFLICT (id) DO UPDATE
+        SET ref_count = binary_blob.ref_count + 1, unreferenced_at = NULL;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION binary_blob_released() RETURNS trigger AS $$
+BEGIN
+    UPDATE binary_blob
+    SET ref_count = ref_count - 1,
+        unreferenced_at = CASE WHEN ref_count <= 1 THEN NOW() ELSE NULL END
+    WHERE id = OLD.id;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS binary_data_blob_referenced ON binary_data;
+CREATE TRIGGER binary_data_blob_referenced
+    AFTER INSERT ON binary_data
+    FOR EACH ROW WHEN (NEW.id LIKE '%:blobs/sha256/%')
+    EXECUTE FUNCTION binary_blob_referenced();
+
+DROP TRIGGER IF EXISTS binary_data_blob_released ON binary_data;
+CREATE TRIGGER binary_data_blob_released
+    AFTER DELETE ON binary_data
+    FOR EACH ROW WHEN (OLD.id LIKE '%:blobs/sha256/%')
+    EXECUTE FUNCTION binary_blob_released();
@@ -0,0 +1,14 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 019_workflow_archive
+--
+-- When a workflow was archived. Archived workflows are hidden from
+-- listings and can be restored until they are purged; deleted_at is what
+-- purging compares against. Workflows archived before this migration
+-- count as archived at their last update.
+
+ALTER TABLE workflow_entity ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+UPDATE workflow_entity SET deleted_at = updated_at WHERE is_archived AND deleted_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_workflow_deleted_at
+    ON workflow_entity(deleted_at) WHERE is_archived;
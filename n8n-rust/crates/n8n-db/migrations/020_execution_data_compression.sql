@@ -0,0 +1,10 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 020_execution_data_compression
+--
+-- Compressed execution data. Rows whose data_format is not 'json' keep the
+-- serialized run compressed in data_compressed, with data left empty.
+-- Existing rows are text and keep the default format.
+
+ALTER TABLE execution_data
+    ADD COLUMN IF NOT EXISTS data_format VARCHAR(8) NOT NULL DEFAULT 'json',
+    ADD COLUMN IF NOT EXISTS data_compressed BYTEA;
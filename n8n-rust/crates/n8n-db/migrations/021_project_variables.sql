@@ -0,0 +1,37 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 021_project_variables
+--
+-- Variable keys are unique per project, and among global variables. A
+-- project variable may share the key of a global one, which it overrides
+-- in the project's workflows. Duplicate keys saved before this migration
+-- are not resolved here, as no one of them is the right one to keep: the
+-- migration fails listing them, to be renamed or deleted by hand first.
+
+DO $$
+DECLARE
+    duplicates TEXT;
+BEGIN
+    SELECT string_agg(
+               format('%s (%s, %s variables)', key, coalesce(project_id, 'global'), count),
+               '; ' ORDER BY key, project_id
+           )
+    INTO duplicates
+    FROM (
+        SELECT key, project_id, count(*) AS count
+        FROM variables
+        GROUP BY key, project_id
+        HAVING count(*) > 1
+    ) AS duplicated;
+
+    IF duplicates IS NOT NULL THEN
+        RAISE EXCEPTION 'Duplicate variable keys: %', duplicates
+            USING HINT = 'Rename or delete the duplicates so each key is unique '
+                         'per project and among global variables, then migrate again.';
+    END IF;
+END $$;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_variables_key_project
+    ON variables(key, project_id) WHERE project_id IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_variables_key_global
+    ON variables(key) WHERE project_id IS NULL;
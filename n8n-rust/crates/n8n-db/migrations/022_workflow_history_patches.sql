@@ -0,0 +1,12 @@
+-- n8n-rust PostgreSQL Schema
+-- Migration: 022_workflow_history_patches
+--
+-- Which version a history entry was saved over, and the operations of the
+-- patch that saved it, if one did. Entries written before this migration
+-- have neither.
+
+ALTER TABLE workflow_history ADD COLUMN IF NOT EXISTS previous_version_id VARCHAR(36);
+ALTER TABLE workflow_history ADD COLUMN IF NOT EXISTS operations JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_workflow_history_workflow_created
+    ON workflow_history(workflow_id, created_at DESC);
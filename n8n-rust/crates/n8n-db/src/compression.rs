@@ -0,0 +1,122 @@
+//! Compression of stored execution data.
+//!
+//! `execution_data.data` holds the serialized run as JSON text, which for
+//! large executions runs to megabytes. With an algorithm configured,
+//! [`ExecutionRepository`](crate::ExecutionRepository) stores data of at
+//! least `threshold_bytes` compressed in `data_compressed` instead, leaving
+//! `data` empty, and records the algorithm in `data_format`. Reads
+//! decompress by the row's format, so changing the algorithm or turning
+//! compression off leaves earlier rows readable. Data that does not shrink
+//! is stored as text.
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+use crate::error::DbError;
+
+/// `data_format` of rows whose data is stored as text.
+pub const FORMAT_JSON: &str = "json";
+
+/// Algorithm execution data is compressed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    /// Store data as text.
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    /// `data_format` of rows compressed with this algorithm.
+    pub fn format(&self) -> &'static str {
+        match self {
+            Self::None => FORMAT_JSON,
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+}
+
+/// Execution data compression settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DataCompressionConfig {
+    pub algorithm: CompressionAlgorithm,
+    /// Serialized size from which data is compressed.
+    pub threshold_bytes: usize,
+}
+
+impl Default for DataCompressionConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: CompressionAlgorithm::None,
+            threshold_bytes: 64 * 1024,
+        }
+    }
+}
+
+impl DataCompressionConfig {
+    /// `data` compressed if it is large enough and shrinks, with the
+    /// `data_format` to record; `None` stores it as text.
+    pub fn compress(&self, data: &str) -> Result<Option<(&'static str, Vec<u8>)>, DbError> {
+        if data.len() < self.threshold_bytes {
+            return Ok(None);
+        }
+        let compressed = match self.algorithm {
+            CompressionAlgorithm::None => return Ok(None),
+            CompressionAlgorithm::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data.as_bytes())?;
+                encoder.finish()?
+            }
+            CompressionAlgorithm::Zstd => zstd::encode_all(data.as_bytes(), 0)?,
+        };
+        Ok((compressed.len() < data.len()).then(|| (self.algorithm.format(), compressed)))
+    }
+}
+
+/// Execution data stored in `format` as `compressed`.
+pub fn decompress(format: &str, compressed: &[u8]) -> Result<String, DbError> {
+    let bytes = match format {
+        "gzip" => {
+            let mut bytes = Vec::new();
+            flate2::read::GzDecoder::new(compressed).read_to_end(&mut bytes)?;
+            bytes
+        }
+        "zstd" => zstd::decode_all(compressed)?,
+        other => {
+            return Err(DbError::InvalidData(format!(
+                "Unknown execution data format {}",
+                other
+            )))
+        }
+    };
+    String::from_utf8(bytes).map_err(|e| DbError::InvalidData(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compress_roundtrip_above_threshold() {
+        let data = serde_json::json!({ "rows": vec!["the same row"; 2000] }).to_string();
+        for algorithm in [CompressionAlgorithm::Gzip, CompressionAlgorithm::Zstd] {
+            let config = DataCompressionConfig {
+                algorithm,
+                threshold_bytes: 1024,
+            };
+            let (format, compressed) = config.compress(&data).unwrap().unwrap();
+            assert_eq!(format, algorithm.format());
+            assert!(compressed.len() < data.len() / 10);
+            assert_eq!(decompress(format, &compressed).unwrap(), data);
+
+            assert!(config.compress("{}").unwrap().is_none());
+        }
+        assert!(DataCompressionConfig::default().compress(&data).unwrap().is_none());
+        assert!(decompress("brotli", b"").is_err());
+    }
+}
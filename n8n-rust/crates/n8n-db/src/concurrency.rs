@@ -0,0 +1,54 @@
+//! Concurrency group locks shared by every instance.
+//!
+//! A group's lock is a transaction-scoped Postgres advisory lock on a hash
+//! of the group key, so it is released when the holder finishes or its
+//! connection dies. Waiters poll with `pg_try_advisory_xact_lock` instead of
+//! blocking, so only executions that hold a lock tie up a pool connection;
+//! the price is that waiters are not served in arrival order.
+
+use async_trait::async_trait;
+use n8n_core::{ConcurrencyGuard, ConcurrencyLocks};
+use sqlx::PgPool;
+use std::time::Duration;
+
+/// [`ConcurrencyLocks`] backed by Postgres advisory locks.
+pub struct PgConcurrencyLocks {
+    pool: PgPool,
+    poll_interval: Duration,
+}
+
+impl PgConcurrencyLocks {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            poll_interval: Duration::from_millis(250),
+        }
+    }
+
+    /// How often a waiter retries a taken lock.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+}
+
+#[async_trait]
+impl ConcurrencyLocks for PgConcurrencyLocks {
+    async fn lock(&self, key: &str) -> Result<ConcurrencyGuard, String> {
+        loop {
+            let mut tx = self.pool.begin().await.map_err(|e| e.to_string())?;
+            let locked: bool =
+                sqlx::query_scalar("SELECT pg_try_advisory_xact_lock(hashtextextended($1, 0))")
+                    .bind(key)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            if locked {
+                // Held until the transaction is rolled back on drop.
+                return Ok(ConcurrencyGuard::new(tx));
+            }
+            let _ = tx.rollback().await;
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Execution annotation entities - matches n8n's ExecutionAnnotation and
+//! AnnotationTagEntity.
+//!
+//! Reference: packages/@n8n/db/src/entities/execution-annotation.ee.ts
+//!            packages/@n8n/db/src/entities/annotation-tag-entity.ee.ts
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::FromRow;
+
+use super::generate_nano_id;
+
+/// QA verdict on an execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnotationVote {
+    Up,
+    Down,
+}
+
+impl AnnotationVote {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "up" => Some(Self::Up),
+            "down" => Some(Self::Down),
+            _ => None,
+        }
+    }
+}
+
+/// AnnotationTagEntity - tags QA reviewers put on executions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationTagEntity {
+    /// Primary key - nano ID.
+    pub id: String,
+
+    /// Tag name (unique, 1-24 characters).
+    pub name: String,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AnnotationTagEntity {
+    /// Create a new annotation tag.
+    pub fn new(name: impl Into<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: generate_nano_id(),
+            name: name.into(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Check a tag name is usable.
+    pub fn validate_name(name: &str) -> Result<(), String> {
+        let len = name.trim().chars().count();
+        if len == 0 || len > 24 {
+            return Err("Annotation tag names have 1 to 24 characters".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// ExecutionAnnotation - vote, note and tags of one execution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionAnnotation {
+    pub id: i32,
+
+    /// Execution ID FK (unique).
+    pub execution_id: String,
+
+    /// `up` or `down`.
+    #[sqlx(default)]
+    pub vote: Option<String>,
+
+    #[sqlx(default)]
+    pub note: Option<String>,
+
+    /// Loaded separately from `execution_annotation_tags`.
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub tags: Vec<AnnotationTagEntity>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ExecutionAnnotation {
+    /// Get the parsed vote.
+    pub fn get_vote(&self) -> Option<AnnotationVote> {
+        self.vote.as_deref().and_then(AnnotationVote::from_str)
+    }
+}
+
+/// Partial update of an annotation: absent fields are kept, `null` clears
+/// the vote or note, `tags` replaces the tag IDs.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationUpdate {
+    #[serde(default, deserialize_with = "present")]
+    pub vote: Option<Option<AnnotationVote>>,
+    #[serde(default, deserialize_with = "present")]
+    pub note: Option<Option<String>>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl AnnotationUpdate {
+    /// Vote and note after applying the update to `current`.
+    pub fn apply(&self, current: Option<&ExecutionAnnotation>) -> (Option<String>, Option<String>) {
+        let vote = match self.vote {
+            Some(vote) => vote.map(|v| v.as_str().to_string()),
+            None => current.and_then(|a| a.vote.clone()),
+        };
+        let note = match &self.note {
+            Some(note) => note.clone().filter(|n| !n.trim().is_empty()),
+            None => current.and_then(|a| a.note.clone()),
+        };
+        (vote, note)
+    }
+}
+
+/// Distinguish an explicit `null` from an absent field.
+fn present<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Annotation counts of the executions in an insights window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationInsights {
+    /// Executions started in the window.
+    pub executions: i64,
+    /// Those with an annotation.
+    pub annotated: i64,
+    pub up_votes: i64,
+    pub down_votes: i64,
+    /// Down-voted executions that nevertheless succeeded, the runs QA
+    /// flags that status alone misses.
+    pub down_voted_successes: i64,
+    /// Annotated executions per tag, most used first.
+    pub tags: Vec<AnnotationTagCount>,
+}
+
+/// Executions carrying one annotation tag.
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationTagCount {
+    pub id: String,
+    pub name: String,
+    pub executions: i64,
+    pub up_votes: i64,
+    pub down_votes: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_distinguishes_null_from_absent() {
+        let current = ExecutionAnnotation {
+            id: 1,
+            execution_id: "e1".to_string(),
+            vote: Some("up".to_string()),
+            note: Some("looks right".to_string()),
+            tags: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let keep: AnnotationUpdate = serde_json::from_str(r#"{"tags": ["t1"]}"#).unwrap();
+        assert_eq!(keep.apply(Some(&current)), (Some("up".to_string()), Some("looks right".to_string())));
+        assert_eq!(keep.tags, Some(vec!["t1".to_string()]));
+
+        let clear: AnnotationUpdate = serde_json::from_str(r#"{"vote": null, "note": ""}"#).unwrap();
+        assert_eq!(clear.apply(Some(&current)), (None, None));
+
+        let down: AnnotationUpdate = serde_json::from_str(r#"{"vote": "down"}"#).unwrap();
+        assert_eq!(down.apply(None), (Some("down".to_string()), None));
+        assert!(serde_json::from_str::<AnnotationUpdate>(r#"{"vote": "meh"}"#).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+//! Binary data entity - where the stored binary payloads of executions are.
+//!
+//! n8n-rust specific; there is no TypeORM counterpart. The bytes live in a
+//! binary data store (filesystem or S3); a row records one reference so
+//! payloads can be found by execution and cleaned up with it.
+
+use chrono::{DateTime, Utc};
+use n8n_workflow::BinaryData;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// BinaryDataEntity - a stored binary payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct BinaryDataEntity {
+    /// The reference, `{mode}:{key}`; primary key with `execution_id`.
+    pub id: String,
+
+    /// Store the payload is in (`memory`, `filesystem`, `s3`).
+    pub mode: String,
+
+    /// Execution ID FK.
+    pub execution_id: String,
+
+    /// Workflow the execution ran.
+    #[sqlx(default)]
+    pub workflow_id: Option<String>,
+
+    pub mime_type: String,
+
+    #[sqlx(default)]
+    pub file_name: Option<String>,
+
+    /// Size in bytes.
+    #[sqlx(default)]
+    pub file_size: Option<i64>,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl BinaryDataEntity {
+    /// Row for `data` of `execution_id`, if it is stored rather than
+    /// inline. The workflow is read from the key
+    /// (`workflows/{workflowId}/...`).
+    pub fn from_reference(data: &BinaryData, execution_id: &str) -> Option<Self> {
+        let (mode, key) = data.reference()?;
+        let workflow_id = key
+            .strip_prefix("workflows/")
+            .and_then(|rest| rest.split_once('/'))
+            .map(|(workflow_id, _)| workflow_id.to_string());
+        Some(Self {
+            id: data.id.clone()?,
+            mode: mode.to_string(),
+            execution_id: execution_id.to_string(),
+            workflow_id,
+            mime_type: data.mime_type.clone(),
+            file_name: data.file_name.clone(),
+            file_size: data.bytes.map(|bytes| bytes as i64),
+            created_at: Utc::now(),
+        })
+    }
+}
@@ -11,7 +11,7 @@ use super::generate_nano_id;
 /// CredentialsEntity - encrypted credential storage.
 ///
 /// Note: The `data` field contains encrypted credential data.
-/// Encryption/decryption is handled by the credentials service.
+/// `CredentialsRepository` decrypts it when given an encryption service.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct CredentialsEntity {
     /// Primary key - nano ID.
@@ -137,3 +137,87 @@ pub struct CredentialFilters {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
+
+/// Version of the [`CredentialBundle`] format written by this version.
+pub const CREDENTIAL_BUNDLE_VERSION: u32 = 1;
+
+/// Credentials exported for another instance. The `data` of each is
+/// encrypted with a transfer key rather than either instance's key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialBundle {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub credentials: Vec<ExportedCredential>,
+}
+
+/// One credential of a [`CredentialBundle`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedCredential {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    /// Encrypted with the transfer key.
+    pub data: String,
+    #[serde(default)]
+    pub is_managed: bool,
+    #[serde(default)]
+    pub is_global: bool,
+}
+
+/// IDs given to imported credentials.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportIds {
+    /// Keep the exported IDs, so workflows promoted along keep working;
+    /// credentials with the same ID are overwritten.
+    #[default]
+    Preserve,
+    /// Give every credential a new ID.
+    Remap,
+}
+
+/// How to import a [`CredentialBundle`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOptions {
+    #[serde(default)]
+    pub ids: ImportIds,
+    /// Project to own the imported credentials.
+    pub project_id: Option<String>,
+    /// Report without changing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// What importing a credential did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportAction {
+    Created,
+    /// A credential with the same ID was overwritten.
+    Updated,
+}
+
+/// An imported credential, under its ID in the bundle and here.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedCredential {
+    pub source_id: String,
+    pub id: String,
+    pub name: String,
+    pub action: ImportAction,
+}
+
+/// Outcome of an import, or of a dry run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    /// Whether the credentials were written.
+    pub applied: bool,
+    pub dry_run: bool,
+    pub ids: ImportIds,
+    pub credentials: Vec<ImportedCredential>,
+}
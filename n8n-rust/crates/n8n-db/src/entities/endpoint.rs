@@ -0,0 +1,82 @@
+//! Workflow endpoint entity - a workflow published as a REST endpoint.
+//!
+//! n8n-rust specific; there is no TypeORM counterpart.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::generate_nano_id;
+
+/// EndpointEntity - method and path routed to a workflow node.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EndpointEntity {
+    /// Primary key - nano ID.
+    pub id: String,
+
+    /// Workflow ID FK.
+    pub workflow_id: String,
+
+    /// Node the request item is passed to.
+    pub node: String,
+
+    /// HTTP method (GET, POST, etc.).
+    pub method: String,
+
+    /// Path below `/endpoint/`, with `:name` parameters.
+    pub path: String,
+
+    /// One-line description for the OpenAPI document.
+    #[sqlx(default)]
+    pub summary: Option<String>,
+
+    /// JSON schema of the request body.
+    #[sqlx(default)]
+    pub request_schema: Option<serde_json::Value>,
+
+    /// JSON schema of the response body.
+    #[sqlx(default)]
+    pub response_schema: Option<serde_json::Value>,
+
+    /// API key scopes a caller needs; empty for anyone with a key.
+    pub scopes: Vec<String>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Insert parameters for creating or replacing an endpoint.
+#[derive(Debug, Clone)]
+pub struct InsertEndpoint {
+    pub id: String,
+    pub workflow_id: String,
+    pub node: String,
+    pub method: String,
+    pub path: String,
+    pub summary: Option<String>,
+    pub request_schema: Option<serde_json::Value>,
+    pub response_schema: Option<serde_json::Value>,
+    pub scopes: Vec<String>,
+}
+
+impl InsertEndpoint {
+    /// Route `method` `path` to `node` of `workflow_id`.
+    pub fn new(
+        workflow_id: impl Into<String>,
+        node: impl Into<String>,
+        method: impl Into<String>,
+        path: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: generate_nano_id(),
+            workflow_id: workflow_id.into(),
+            node: node.into(),
+            method: method.into(),
+            path: path.into(),
+            summary: None,
+            request_schema: None,
+            response_schema: None,
+            scopes: Vec::new(),
+        }
+    }
+}
@@ -9,7 +9,8 @@ use sqlx::FromRow;
 
 use n8n_workflow::{ExecutionStatus, WorkflowExecuteMode};
 
-use super::generate_nano_id;
+use super::{generate_nano_id, AnnotationVote};
+use crate::error::DbError;
 
 /// ExecutionEntity - workflow execution record.
 ///
@@ -61,6 +62,14 @@ pub struct ExecutionEntity {
 
     /// Where execution data is stored: 'db', 'fs', or 's3'.
     pub stored_at: String,
+
+    /// Worker that claimed the execution from the queue.
+    #[sqlx(default)]
+    pub claimed_by: Option<String>,
+
+    /// Claim expiry; after this another worker may claim the execution.
+    #[sqlx(default)]
+    pub lease_until: Option<DateTime<Utc>>,
 }
 
 impl ExecutionEntity {
@@ -81,6 +90,8 @@ impl ExecutionEntity {
             retry_success_id: None,
             wait_till: None,
             stored_at: "db".to_string(),
+            claimed_by: None,
+            lease_until: None,
         }
     }
 
@@ -189,6 +200,77 @@ impl ExecutionMetadata {
     }
 }
 
+/// Resource usage of a workflow's measured executions, for finding
+/// workflows that strain workers.
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowResourceUsage {
+    pub workflow_id: String,
+    pub executions: i64,
+    pub wall_ms_avg: f64,
+    pub cpu_ms_total: Option<f64>,
+    pub cpu_ms_avg: Option<f64>,
+    pub cpu_ms_max: Option<f64>,
+    /// Rises of the worker's peak RSS, in bytes.
+    pub peak_rss_delta_avg: Option<f64>,
+    pub peak_rss_delta_max: Option<i64>,
+}
+
+/// Position after an execution in the newest-first execution order, for
+/// keyset pagination. Rendered as an opaque string for APIs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: String,
+}
+
+impl ExecutionCursor {
+    /// Cursor of the page after `execution`.
+    pub fn after(execution: &ExecutionEntity) -> Self {
+        Self {
+            created_at: execution.created_at,
+            id: execution.id.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for ExecutionCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.created_at.timestamp_micros(), self.id)
+    }
+}
+
+impl std::str::FromStr for ExecutionCursor {
+    type Err = DbError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DbError::InvalidData(format!("Invalid cursor '{}'", s));
+        let (micros, id) = s.split_once(':').ok_or_else(invalid)?;
+        let created_at = micros
+            .parse()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        if id.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            created_at,
+            id: id.to_string(),
+        })
+    }
+}
+
+/// A page of executions and the cursor of the next one.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPage {
+    pub executions: Vec<ExecutionEntity>,
+    /// Unset on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
 /// Query filters for executions.
 #[derive(Debug, Clone, Default)]
 pub struct ExecutionFilters {
@@ -196,6 +278,12 @@ pub struct ExecutionFilters {
     pub status: Option<Vec<ExecutionStatus>>,
     pub mode: Option<Vec<WorkflowExecuteMode>>,
     pub finished: Option<bool>,
+    /// Executions of workflows carrying any of these tags.
+    pub tag_ids: Option<Vec<String>>,
+    /// Executions annotated with this vote.
+    pub vote: Option<AnnotationVote>,
+    /// Executions annotated with any of these annotation tags.
+    pub annotation_tag_ids: Option<Vec<String>>,
     pub started_after: Option<DateTime<Utc>>,
     pub started_before: Option<DateTime<Utc>>,
     pub include_deleted: bool,
@@ -265,3 +353,24 @@ pub struct ExecutionWithData {
     pub data: Option<ExecutionData>,
     pub metadata: Vec<ExecutionMetadata>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trips_and_rejects_garbage() {
+        let execution = ExecutionEntity::new("wf-1", WorkflowExecuteMode::Manual);
+        let cursor = ExecutionCursor::after(&execution);
+        let parsed: ExecutionCursor = cursor.to_string().parse().unwrap();
+        assert_eq!(parsed.id, execution.id);
+        assert_eq!(
+            parsed.created_at.timestamp_micros(),
+            execution.created_at.timestamp_micros()
+        );
+
+        for garbage in ["", "abc", "12:", "x:id"] {
+            assert!(garbage.parse::<ExecutionCursor>().is_err());
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Folder entity - matches n8n's Folder.
+//!
+//! Reference: packages/@n8n/db/src/entities/folder.ts
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::generate_nano_id;
+
+/// Folder - organizes a project's workflows; folders nest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct Folder {
+    /// Primary key - nano ID.
+    pub id: String,
+
+    pub name: String,
+
+    /// Folder it is in; `None` at the top of its project.
+    #[sqlx(default)]
+    pub parent_folder_id: Option<String>,
+
+    /// Project the folder belongs to.
+    #[sqlx(default)]
+    pub project_id: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Folder {
+    /// Create a folder at the top of a project.
+    pub fn new(name: impl Into<String>, project_id: impl Into<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: generate_nano_id(),
+            name: name.into(),
+            parent_folder_id: None,
+            project_id: Some(project_id.into()),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Put the folder in `parent`.
+    pub fn in_folder(mut self, parent: impl Into<String>) -> Self {
+        self.parent_folder_id = Some(parent.into());
+        self
+    }
+}
+
+/// Insert parameters for creating a folder.
+#[derive(Debug, Clone)]
+pub struct InsertFolder {
+    pub id: String,
+    pub name: String,
+    pub parent_folder_id: Option<String>,
+    pub project_id: Option<String>,
+}
+
+impl From<&Folder> for InsertFolder {
+    fn from(f: &Folder) -> Self {
+        Self {
+            id: f.id.clone(),
+            name: f.name.clone(),
+            parent_folder_id: f.parent_folder_id.clone(),
+            project_id: f.project_id.clone(),
+        }
+    }
+}
+
+/// FolderTagMapping - junction table for folder-tag relationships.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FolderTagMapping {
+    pub folder_id: String,
+    pub tag_id: String,
+}
+
+/// A folder in a listed tree.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FolderTreeEntry {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub folder: Folder,
+
+    /// Levels below the top of the listing, 0 at the top.
+    pub depth: i32,
+
+    /// Names of the folders from the top of the listing to this one.
+    pub path: Vec<String>,
+}
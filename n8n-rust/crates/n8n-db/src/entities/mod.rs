@@ -3,25 +3,41 @@
 //! These structs map directly to the PostgreSQL tables and maintain
 //! compatibility with the original n8n database schema.
 
+pub mod annotation;
+pub mod binary_data;
 pub mod credentials;
+pub mod endpoint;
 pub mod execution;
+pub mod folder;
+pub mod processed_data;
 pub mod project;
 pub mod settings;
 pub mod tag;
+pub mod test_run;
+pub mod timer;
 pub mod user;
 pub mod variables;
 pub mod webhook;
 pub mod workflow;
+pub mod workflow_statistics;
 
+pub use annotation::*;
+pub use binary_data::*;
 pub use credentials::*;
+pub use endpoint::*;
 pub use execution::*;
+pub use folder::*;
+pub use processed_data::*;
 pub use project::*;
 pub use settings::*;
 pub use tag::*;
+pub use test_run::*;
+pub use timer::*;
 pub use user::*;
 pub use variables::*;
 pub use webhook::*;
 pub use workflow::*;
+pub use workflow_statistics::*;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -0,0 +1,34 @@
+//! Processed data entity - matches n8n's ProcessedData.
+//!
+//! Reference: packages/@n8n/db/src/entities/processed-data.ts
+
+use chrono::{DateTime, Utc};
+use n8n_core::ProcessedValue;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+/// ProcessedDataEntity - values a Remove Duplicates node saw before.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessedDataEntity {
+    /// Workflow ID, part of the primary key.
+    pub workflow_id: String,
+
+    /// `workflow`, or `node:<id>`; part of the primary key.
+    pub context: String,
+
+    /// `{mode, data}`; see [`ProcessedValue`].
+    #[sqlx(json)]
+    pub value: Value,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ProcessedDataEntity {
+    /// Get the parsed value.
+    pub fn get_value(&self) -> Option<ProcessedValue> {
+        serde_json::from_value(self.value.clone()).ok()
+    }
+}
@@ -0,0 +1,349 @@
+//! Test run entities - matches n8n's TestRun and TestCaseExecution.
+//!
+//! A test run is one evaluation of a workflow against a test set; each of
+//! its cases is a test case execution. Runs aggregate the scores of their
+//! cases into `metrics`.
+//!
+//! Reference: packages/@n8n/db/src/entities/test-run.ee.ts
+//!            packages/@n8n/db/src/entities/test-case-execution.ee.ts
+
+use chrono::{DateTime, Utc};
+use n8n_core::{CaseResult, EvaluationReport};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::FromRow;
+
+/// State of a test run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestRunStatus {
+    New,
+    Running,
+    Completed,
+    Error,
+    Cancelled,
+}
+
+impl TestRunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::New => "new",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Error => "error",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "new" => Some(Self::New),
+            "running" => Some(Self::Running),
+            "completed" => Some(Self::Completed),
+            "error" => Some(Self::Error),
+            "cancelled" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of a test case execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestCaseStatus {
+    New,
+    Running,
+    Success,
+    Error,
+    Cancelled,
+}
+
+impl TestCaseStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::New => "new",
+            Self::Running => "running",
+            Self::Success => "success",
+            Self::Error => "error",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "new" => Some(Self::New),
+            "running" => Some(Self::Running),
+            "success" => Some(Self::Success),
+            "error" => Some(Self::Error),
+            "cancelled" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// Error codes of failed test cases.
+pub mod test_case_error_codes {
+    /// The workflow failed to run the case.
+    pub const EXECUTION_FAILED: &str = "EXECUTION_FAILED";
+    /// A score stayed below its threshold.
+    pub const THRESHOLD_NOT_MET: &str = "THRESHOLD_NOT_MET";
+}
+
+/// TestRun - one evaluation of a workflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TestRun {
+    /// Primary key - the report ID.
+    pub id: String,
+
+    pub workflow_id: String,
+
+    /// Test set evaluated.
+    #[sqlx(default)]
+    pub test_set_id: Option<String>,
+
+    /// `new`, `running`, `completed`, `error` or `cancelled`.
+    pub status: String,
+
+    #[sqlx(default)]
+    pub error_code: Option<String>,
+
+    #[sqlx(json)]
+    #[sqlx(default)]
+    pub error_details: Option<Value>,
+
+    #[sqlx(default)]
+    pub run_at: Option<DateTime<Utc>>,
+
+    #[sqlx(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+
+    /// Mean of each score over the cases, and `score`, the mean of all.
+    #[sqlx(json)]
+    #[sqlx(default)]
+    pub metrics: Option<Value>,
+
+    pub passed_cases: i32,
+    pub failed_cases: i32,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TestRun {
+    /// Get the parsed status.
+    pub fn get_status(&self) -> Option<TestRunStatus> {
+        TestRunStatus::from_str(&self.status)
+    }
+
+    /// The completed run of `report`.
+    pub fn from_report(report: &EvaluationReport) -> Self {
+        let now = Utc::now();
+        Self {
+            id: report.id.clone(),
+            workflow_id: report.workflow_id.clone(),
+            test_set_id: Some(report.test_set_id.clone()),
+            status: TestRunStatus::Completed.as_str().to_string(),
+            error_code: None,
+            error_details: None,
+            run_at: Some(report.started_at),
+            completed_at: Some(report.finished_at),
+            metrics: Some(Value::Object(aggregate_metrics(report))),
+            passed_cases: report.passed as i32,
+            failed_cases: report.failed as i32,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// TestCaseExecution - one case of a test run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCaseExecution {
+    /// Primary key - nano ID.
+    pub id: String,
+
+    pub test_run_id: String,
+
+    /// Saved execution of the case, if it was saved.
+    #[sqlx(default)]
+    pub execution_id: Option<String>,
+
+    /// Case of the test set.
+    #[sqlx(default)]
+    pub case_id: Option<String>,
+
+    /// `new`, `running`, `success`, `error` or `cancelled`.
+    pub status: String,
+
+    #[sqlx(default)]
+    pub run_at: Option<DateTime<Utc>>,
+
+    #[sqlx(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+
+    /// One of [`test_case_error_codes`].
+    #[sqlx(default)]
+    pub error_code: Option<String>,
+
+    #[sqlx(json)]
+    #[sqlx(default)]
+    pub error_details: Option<Value>,
+
+    /// Score by name.
+    #[sqlx(json)]
+    #[sqlx(default)]
+    pub metrics: Option<Value>,
+
+    /// Items the checked node output.
+    #[sqlx(json)]
+    #[sqlx(default)]
+    pub outputs: Option<Value>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TestCaseExecution {
+    /// Get the parsed status.
+    pub fn get_status(&self) -> Option<TestCaseStatus> {
+        TestCaseStatus::from_str(&self.status)
+    }
+
+    /// The execution of `case` in the run `test_run_id`.
+    pub fn from_case(test_run_id: &str, case: &CaseResult, run_at: DateTime<Utc>) -> Self {
+        let now = Utc::now();
+        let (status, error_code, error_details) = match &case.error {
+            _ if case.passed => (TestCaseStatus::Success, None, None),
+            Some(error) => (
+                TestCaseStatus::Error,
+                Some(test_case_error_codes::EXECUTION_FAILED),
+                Some(serde_json::json!({ "message": error })),
+            ),
+            None => {
+                let failed: Vec<&str> = case
+                    .scores
+                    .iter()
+                    .filter(|score| !score.passed)
+                    .map(|score| score.name.as_str())
+                    .collect();
+                (
+                    TestCaseStatus::Error,
+                    Some(test_case_error_codes::THRESHOLD_NOT_MET),
+                    Some(serde_json::json!({ "scores": failed })),
+                )
+            }
+        };
+        let metrics: Map<String, Value> = case
+            .scores
+            .iter()
+            .map(|score| (score.name.clone(), score.score.into()))
+            .collect();
+
+        Self {
+            id: super::generate_nano_id(),
+            test_run_id: test_run_id.to_string(),
+            execution_id: None,
+            case_id: Some(case.case_id.clone()),
+            status: status.as_str().to_string(),
+            run_at: Some(run_at),
+            completed_at: Some(run_at + chrono::Duration::milliseconds(case.duration_ms as i64)),
+            error_code: error_code.map(str::to_string),
+            error_details,
+            metrics: Some(Value::Object(metrics)),
+            outputs: Some(Value::Array(case.output.clone())),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Mean of each score over the cases of `report` that have it, with
+/// `score` the mean case score.
+pub fn aggregate_metrics(report: &EvaluationReport) -> Map<String, Value> {
+    let mut sums: Vec<(&str, f64, u32)> = Vec::new();
+    for score in report.cases.iter().flat_map(|case| &case.scores) {
+        match sums.iter_mut().find(|(name, _, _)| *name == score.name) {
+            Some((_, sum, count)) => {
+                *sum += score.score;
+                *count += 1;
+            }
+            None => sums.push((&score.name, score.score, 1)),
+        }
+    }
+
+    let mut metrics: Map<String, Value> = sums
+        .into_iter()
+        .map(|(name, sum, count)| (name.to_string(), (sum / count as f64).into()))
+        .collect();
+    metrics.insert("score".to_string(), report.score.into());
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_core::ScoreResult;
+
+    fn score(name: &str, score: f64, passed: bool) -> ScoreResult {
+        ScoreResult {
+            name: name.to_string(),
+            score,
+            threshold: 1.0,
+            passed,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_runs_aggregate_their_cases() {
+        let case = |id: &str, scores: Vec<ScoreResult>, error: Option<&str>| CaseResult {
+            case_id: id.to_string(),
+            name: None,
+            passed: error.is_none() && scores.iter().all(|s| s.passed),
+            score: 0.0,
+            scores,
+            error: error.map(str::to_string),
+            duration_ms: 120,
+            output: Vec::new(),
+        };
+        let now = Utc::now();
+        let report = EvaluationReport {
+            id: "r1".to_string(),
+            test_set_id: "s1".to_string(),
+            workflow_id: "w1".to_string(),
+            version_id: None,
+            started_at: now,
+            finished_at: now,
+            total: 3,
+            passed: 1,
+            failed: 2,
+            score: 0.5,
+            cases: vec![
+                case("c1", vec![score("expected", 1.0, true), score("tone", 1.0, true)], None),
+                case("c2", vec![score("expected", 0.5, false)], None),
+                case("c3", Vec::new(), Some("Node 'Classify' failed")),
+            ],
+        };
+
+        let run = TestRun::from_report(&report);
+        assert_eq!(run.get_status(), Some(TestRunStatus::Completed));
+        assert_eq!((run.passed_cases, run.failed_cases), (1, 2));
+        let metrics = run.metrics.unwrap();
+        assert_eq!(metrics["expected"], 0.75);
+        assert_eq!(metrics["tone"], 1.0);
+        assert_eq!(metrics["score"], 0.5);
+
+        let executions: Vec<TestCaseExecution> = report
+            .cases
+            .iter()
+            .map(|case| TestCaseExecution::from_case(&run.id, case, now))
+            .collect();
+        assert_eq!(executions[0].get_status(), Some(TestCaseStatus::Success));
+        assert_eq!(executions[1].error_code.as_deref(), Some("THRESHOLD_NOT_MET"));
+        assert_eq!(executions[1].error_details.as_ref().unwrap()["scores"][0], "expected");
+        assert_eq!(executions[2].error_code.as_deref(), Some("EXECUTION_FAILED"));
+        assert_eq!(executions[2].get_status(), Some(TestCaseStatus::Error));
+    }
+}
@@ -0,0 +1,75 @@
+//! Timer entity - durable timers fired by [`TimerScheduler`](crate::scheduler::TimerScheduler).
+//!
+//! n8n-rust specific; there is no TypeORM counterpart.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::generate_nano_id;
+
+/// TimerEntity - a pending timer.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TimerEntity {
+    /// Primary key - nano ID.
+    pub id: String,
+
+    /// Workflow ID FK.
+    pub workflow_id: String,
+
+    /// Node that receives the timer when it fires.
+    pub node: String,
+
+    /// When the timer is due.
+    pub fire_at: DateTime<Utc>,
+
+    /// Data passed to the node when the timer fires.
+    #[sqlx(default)]
+    pub payload: Option<serde_json::Value>,
+
+    /// Failed firing attempts so far.
+    pub attempts: i32,
+
+    /// Instance currently firing the timer.
+    #[sqlx(default)]
+    pub locked_by: Option<String>,
+
+    /// Lease expiry; after this another instance may claim the timer.
+    #[sqlx(default)]
+    pub locked_until: Option<DateTime<Utc>>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Insert parameters for creating a timer.
+#[derive(Debug, Clone)]
+pub struct InsertTimer {
+    pub id: String,
+    pub workflow_id: String,
+    pub node: String,
+    pub fire_at: DateTime<Utc>,
+    pub payload: Option<serde_json::Value>,
+}
+
+impl InsertTimer {
+    /// Create a timer for `node` in `workflow_id`, due at `fire_at`.
+    pub fn new(
+        workflow_id: impl Into<String>,
+        node: impl Into<String>,
+        fire_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: generate_nano_id(),
+            workflow_id: workflow_id.into(),
+            node: node.into(),
+            fire_at,
+            payload: None,
+        }
+    }
+
+    pub fn with_payload(mut self, payload: serde_json::Value) -> Self {
+        self.payload = Some(payload);
+        self
+    }
+}
@@ -2,11 +2,15 @@
 //!
 //! Reference: packages/@n8n/db/src/entities/user.ts
 
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use n8n_workflow::ExecutionStatus;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use super::annotation::AnnotationVote;
+use super::execution::ExecutionFilters;
+
 /// User entity.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
@@ -121,10 +125,86 @@ pub struct UserSettings {
     pub user_activated_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub npm_rc: Option<String>,
+    /// Saved filters of the execution list.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub execution_views: Vec<ExecutionView>,
+    /// ID of the view the execution list opens with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_execution_view: Option<String>,
     #[serde(flatten)]
     pub extra: serde_json::Value,
 }
 
+impl UserSettings {
+    /// The saved view with `id`.
+    pub fn execution_view(&self, id: &str) -> Option<&ExecutionView> {
+        self.execution_views.iter().find(|v| v.id == id)
+    }
+}
+
+/// A saved filter of the execution list, stored in [`UserSettings`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionView {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Any of these statuses; empty for all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub status: Vec<ExecutionStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow_id: Option<String>,
+    /// Tag IDs; executions of workflows carrying any of them match.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Only executions annotated with this vote.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vote: Option<AnnotationVote>,
+    /// Annotation tag IDs; executions annotated with any of them match.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotation_tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_after: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_before: Option<DateTime<Utc>>,
+    /// Only executions started in the last this many hours, counted from
+    /// when the view is applied. Combines with `startedAfter`, the later
+    /// bound winning.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_hours: Option<u32>,
+}
+
+impl ExecutionView {
+    /// Check the view is usable.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("View name must not be empty".to_string());
+        }
+        if let (Some(after), Some(before)) = (self.started_after, self.started_before) {
+            if after > before {
+                return Err("startedAfter is later than startedBefore".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Execution filters selecting the view at `now`.
+    pub fn filters(&self, now: DateTime<Utc>) -> ExecutionFilters {
+        let since = self.last_hours.map(|h| now - Duration::hours(i64::from(h)));
+        ExecutionFilters {
+            workflow_id: self.workflow_id.clone(),
+            status: (!self.status.is_empty()).then(|| self.status.clone()),
+            tag_ids: (!self.tags.is_empty()).then(|| self.tags.clone()),
+            vote: self.vote,
+            annotation_tag_ids: (!self.annotation_tags.is_empty())
+                .then(|| self.annotation_tags.clone()),
+            started_after: self.started_after.max(since),
+            started_before: self.started_before,
+            ..Default::default()
+        }
+    }
+}
+
 /// Role entity.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Role {
@@ -168,3 +248,44 @@ pub struct ApiKey {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execution_view_filters() {
+        let now = Utc::now();
+        let view = ExecutionView {
+            id: "v1".into(),
+            name: "Failed today".into(),
+            status: vec![ExecutionStatus::Error, ExecutionStatus::Crashed],
+            tags: vec!["billing".into()],
+            started_after: Some(now - Duration::hours(48)),
+            last_hours: Some(24),
+            ..Default::default()
+        };
+
+        let filters = view.filters(now);
+        assert_eq!(filters.status.as_deref().map(|s| s.len()), Some(2));
+        assert_eq!(filters.tag_ids, Some(vec!["billing".to_string()]));
+        assert_eq!(filters.started_after, Some(now - Duration::hours(24)));
+        assert!(filters.workflow_id.is_none() && filters.started_before.is_none());
+
+        let unnamed = ExecutionView { name: " ".into(), ..view };
+        assert!(unnamed.validate().is_err());
+    }
+
+    #[test]
+    fn test_user_settings_keep_views_and_unknown_keys() {
+        let json = serde_json::json!({
+            "isOnboarded": true,
+            "executionViews": [{ "id": "v1", "name": "Errors", "status": ["error"] }],
+            "defaultExecutionView": "v1",
+            "theme": "dark",
+        });
+        let settings: UserSettings = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(settings.execution_view("v1").unwrap().status, vec![ExecutionStatus::Error]);
+        assert_eq!(serde_json::to_value(&settings).unwrap(), json);
+    }
+}
@@ -31,6 +31,10 @@ pub struct WorkflowEntity {
     /// Soft-delete flag.
     pub is_archived: bool,
 
+    /// When the workflow was archived.
+    #[sqlx(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+
     /// Workflow nodes as JSON.
     #[sqlx(json)]
     pub nodes: Vec<Node>,
@@ -89,6 +93,7 @@ impl WorkflowEntity {
             description: None,
             active: false,
             is_archived: false,
+            deleted_at: None,
             nodes: Vec::new(),
             connections: serde_json::json!({}),
             settings: None,
@@ -164,6 +169,14 @@ pub struct WorkflowHistory {
     /// Whether this was auto-saved.
     pub autosaved: bool,
 
+    /// Version this one was saved over.
+    #[sqlx(default)]
+    pub previous_version_id: Option<String>,
+
+    /// Operations of the patch that saved this version.
+    #[sqlx(default)]
+    pub operations: Option<serde_json::Value>,
+
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -184,6 +197,8 @@ impl WorkflowHistory {
             name: Some(workflow.name.clone()),
             description: workflow.description.clone(),
             autosaved: false,
+            previous_version_id: None,
+            operations: None,
             created_at: now,
             updated_at: now,
         }
@@ -0,0 +1,33 @@
+//! Workflow statistics entity - matches n8n's WorkflowStatistics.
+//!
+//! Reference: packages/@n8n/db/src/entities/workflow-statistics.ts
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// WorkflowStatisticsEntity - one count of a workflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowStatisticsEntity {
+    /// Workflow ID, part of the primary key.
+    pub workflow_id: String,
+
+    /// What is counted, part of the primary key; see [`statistics_names`].
+    pub name: String,
+
+    pub count: i32,
+
+    /// When the count last changed.
+    #[sqlx(default)]
+    pub latest_event: Option<DateTime<Utc>>,
+}
+
+/// Statistic names as stored in `workflow_statistics.name`.
+pub mod statistics_names {
+    pub const PRODUCTION_SUCCESS: &str = "production_success";
+    pub const PRODUCTION_ERROR: &str = "production_error";
+    pub const MANUAL_SUCCESS: &str = "manual_success";
+    pub const MANUAL_ERROR: &str = "manual_error";
+    pub const DATA_LOADED: &str = "data_loaded";
+}
@@ -33,6 +33,10 @@ pub enum DbError {
     #[error("Migration error: {0}")]
     MigrationError(String),
 
+    /// The row changed since the caller read it (optimistic locking).
+    #[error("Version conflict: expected version {expected}, found {current}")]
+    VersionConflict { expected: String, current: String },
+
     /// Transaction error.
     #[error("Transaction error: {0}")]
     TransactionError(String),
@@ -44,6 +48,10 @@ pub enum DbError {
     /// SQLx error.
     #[error("Database error: {0}")]
     SqlxError(#[from] sqlx::Error),
+
+    /// Filesystem error.
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
 }
 
 impl DbError {
@@ -56,6 +64,11 @@ impl DbError {
     pub fn is_duplicate(&self) -> bool {
         matches!(self, Self::DuplicateKey(_))
     }
+
+    /// Check if this is a version conflict error.
+    pub fn is_version_conflict(&self) -> bool {
+        matches!(self, Self::VersionConflict { .. })
+    }
 }
 
 /// Result type for database operations.
@@ -0,0 +1,200 @@
+//! Entity changes shared between instances through Postgres
+//! `LISTEN`/`NOTIFY`.
+//!
+//! Instances keep some rows in memory, like published endpoints. When one
+//! instance changes such a row it publishes an [`EntityChange`] on the
+//! bus's channel, and the other instances, subscribed to it, drop or
+//! reload what they hold. A change published in a transaction is only
+//! delivered once it commits, and not at all if it rolls back.
+//!
+//! Notifications are not stored: ones sent while a subscriber's connection
+//! is down are lost. The subscription then yields an error before it
+//! reconnects, after which subscribers should reload everything they hold.
+
+use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::error::DbError;
+
+/// Channel changes are published on by default.
+pub const DEFAULT_CHANNEL: &str = "n8n_entity_changes";
+
+/// Kind of entity that changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangedEntity {
+    Workflow,
+    Webhook,
+    Endpoint,
+    Credentials,
+    Variable,
+    Setting,
+    /// A trigger node, as `<workflow id>:<node name>`.
+    Trigger,
+    /// Published by a newer version.
+    #[serde(other)]
+    Unknown,
+}
+
+/// What happened to the entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeOperation {
+    Created,
+    Updated,
+    Deleted,
+    /// A trigger stopped starting its workflow.
+    Paused,
+    Resumed,
+    #[serde(other)]
+    Unknown,
+}
+
+/// A change of one entity, as published on the bus.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityChange {
+    pub entity: ChangedEntity,
+    pub operation: ChangeOperation,
+    /// ID of the entity.
+    pub id: String,
+    /// Bus that published it, one per instance.
+    pub origin: String,
+    pub at: DateTime<Utc>,
+}
+
+/// Entity changes over `LISTEN`/`NOTIFY` on one channel.
+#[derive(Clone)]
+pub struct PgEventBus {
+    pool: PgPool,
+    channel: String,
+    origin: String,
+}
+
+impl PgEventBus {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            channel: DEFAULT_CHANNEL.to_string(),
+            origin: Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Publish and listen on `channel` instead of [`DEFAULT_CHANNEL`].
+    pub fn with_channel(mut self, channel: impl Into<String>) -> Self {
+        self.channel = channel.into();
+        self
+    }
+
+    /// Identifies the changes this bus publishes.
+    pub fn origin(&self) -> &str {
+        &self.origin
+    }
+
+    /// Publish a change to every subscriber.
+    pub async fn publish(
+        &self,
+        entity: ChangedEntity,
+        operation: ChangeOperation,
+        id: &str,
+    ) -> Result<(), DbError> {
+        self.publish_in(&self.pool, entity, operation, id).await
+    }
+
+    /// [`Self::publish`] on `executor`; in a transaction the change is
+    /// delivered when it commits.
+    pub async fn publish_in<'e>(
+        &self,
+        executor: impl PgExecutor<'e>,
+        entity: ChangedEntity,
+        operation: ChangeOperation,
+        id: &str,
+    ) -> Result<(), DbError> {
+        let change = EntityChange {
+            entity,
+            operation,
+            id: id.to_string(),
+            origin: self.origin.clone(),
+            at: Utc::now(),
+        };
+        sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(&self.channel)
+            .bind(serde_json::to_string(&change)?)
+            .execute(executor)
+            .await?;
+        Ok(())
+    }
+
+    /// Changes published by other instances, from now on. Each subscription
+    /// holds a connection of its own, outside the pool's limit.
+    pub async fn subscribe(
+        &self,
+    ) -> Result<impl Stream<Item = Result<EntityChange, DbError>> + Send + 'static, DbError> {
+        let mut listener = PgListener::connect_with(&self.pool).await?;
+        listener.listen(&self.channel).await?;
+        let origin = self.origin.clone();
+
+        Ok(stream::unfold(listener, move |mut listener| {
+            let origin = origin.clone();
+            async move {
+                loop {
+                    let notification = match listener.try_recv().await {
+                        Ok(Some(notification)) => notification,
+                        // Reconnected on the next receive
+                        Ok(None) => {
+                            let lost = DbError::ConnectionError(
+                                "Event bus connection lost; changes may have been missed"
+                                    .to_string(),
+                            );
+                            return Some((Err(lost), listener));
+                        }
+                        Err(e) => return Some((Err(e.into()), listener)),
+                    };
+                    match parse_change(notification.payload()) {
+                        Ok(change) if change.origin == origin => continue,
+                        Ok(change) => return Some((Ok(change), listener)),
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Ignoring malformed entity change");
+                        }
+                    }
+                }
+            }
+        }))
+    }
+}
+
+fn parse_change(payload: &str) -> Result<EntityChange, DbError> {
+    Ok(serde_json::from_str(payload)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes_from_newer_versions_still_parse() {
+        let change = parse_change(
+            r#"{"entity":"folder","operation":"archived","id":"f1","origin":"o",
+                "at":"2026-03-01T08:00:00Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(change.entity, ChangedEntity::Unknown);
+        assert_eq!(change.operation, ChangeOperation::Unknown);
+        assert_eq!(change.id, "f1");
+
+        let endpoint = EntityChange {
+            entity: ChangedEntity::Endpoint,
+            operation: ChangeOperation::Deleted,
+            id: "e1".to_string(),
+            origin: "o".to_string(),
+            at: Utc::now(),
+        };
+        let payload = serde_json::to_string(&endpoint).unwrap();
+        assert!(payload.contains(r#""entity":"endpoint""#));
+        assert_eq!(parse_change(&payload).unwrap(), endpoint);
+    }
+}
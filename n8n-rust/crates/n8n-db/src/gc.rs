@@ -0,0 +1,556 @@
+//! Garbage collection of stale and orphaned resources.
+//!
+//! [`GarbageCollector`] sweeps, every `interval_secs`:
+//!
+//! - webhook rows whose workflow was deleted or deactivated,
+//! - OAuth states whose `expires_at` has passed,
+//! - binary data of executions that no longer exist, stored under
+//!   `{binary_data_dir}/workflows/{workflowId}/executions/{executionId}`,
+//! - content-addressed blobs no execution refers to any more, in the store
+//!   set with [`GarbageCollector::with_binary_data`], and blob files under
+//!   `{binary_data_dir}/blobs/sha256` that no saved execution ever referred to,
+//! - `shared_workflow` rows whose workflow or project is gone.
+//!
+//! Foreign keys already cascade most deletes; the row checks cover
+//! databases imported without them. Binary data younger than
+//! `binary_grace_secs`, and blobs unreferenced for less than that, are
+//! kept, since running executions are only saved when they finish.
+//!
+//! A dry run counts what would be removed without removing anything.
+//! Totals across sweeps are kept in [`GcMetrics`].
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use crate::error::DbError;
+use crate::repositories::{BinaryDataRepository, BlobStats};
+use n8n_core::{BinaryDataService, BLOB_KEY_PREFIX};
+
+/// Webhooks whose workflow is missing or inactive.
+const STALE_WEBHOOKS: &str = "webhook_entity w WHERE NOT EXISTS \
+     (SELECT 1 FROM workflow_entity wf WHERE wf.id = w.workflow_id AND wf.active)";
+
+/// OAuth states past their expiry.
+const EXPIRED_OAUTH_STATES: &str = "oauth_state WHERE expires_at < NOW()";
+
+/// Sharing rows whose workflow or project is missing.
+const DANGLING_SHARED_WORKFLOWS: &str = "shared_workflow s WHERE \
+     NOT EXISTS (SELECT 1 FROM workflow_entity wf WHERE wf.id = s.workflow_id) \
+     OR NOT EXISTS (SELECT 1 FROM project p WHERE p.id = s.project_id)";
+
+/// Configuration for [`GarbageCollector`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GcConfig {
+    /// Run the collector when a database is configured.
+    pub enabled: bool,
+    /// Seconds between sweeps.
+    pub interval_secs: u64,
+    /// Count garbage without removing it.
+    pub dry_run: bool,
+    /// Root of filesystem binary data. Unset skips the binary data sweep.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary_data_dir: Option<PathBuf>,
+    /// Minimum age before an execution's binary data counts as orphaned.
+    pub binary_grace_secs: u64,
+}
+
+impl Default for GcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 3600,
+            dry_run: false,
+            binary_data_dir: None,
+            binary_grace_secs: 3600,
+        }
+    }
+}
+
+/// What one sweep removed, or would have removed in a dry run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcReport {
+    pub dry_run: bool,
+    pub stale_webhooks: u64,
+    pub expired_oauth_states: u64,
+    /// Execution directories of binary data.
+    pub orphaned_binary_data: u64,
+    pub orphaned_binary_bytes: u64,
+    /// Content-addressed blobs.
+    pub unreferenced_blobs: u64,
+    pub unreferenced_blob_bytes: u64,
+    pub dangling_shared_workflows: u64,
+}
+
+impl GcReport {
+    /// Items removed across all resource kinds.
+    pub fn total(&self) -> u64 {
+        self.stale_webhooks
+            + self.expired_oauth_states
+            + self.orphaned_binary_data
+            + self.unreferenced_blobs
+            + self.dangling_shared_workflows
+    }
+}
+
+/// Collector counters since startup. Dry runs count as sweeps but add
+/// nothing to the removal totals.
+#[derive(Debug, Default)]
+pub struct GcMetrics {
+    sweeps: AtomicU64,
+    failures: AtomicU64,
+    stale_webhooks: AtomicU64,
+    expired_oauth_states: AtomicU64,
+    orphaned_binary_data: AtomicU64,
+    orphaned_binary_bytes: AtomicU64,
+    unreferenced_blobs: AtomicU64,
+    unreferenced_blob_bytes: AtomicU64,
+    dangling_shared_workflows: AtomicU64,
+    last_sweep: Mutex<Option<(DateTime<Utc>, GcReport)>>,
+}
+
+/// Point-in-time copy of [`GcMetrics`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcMetricsSnapshot {
+    pub sweeps: u64,
+    pub failures: u64,
+    pub stale_webhooks: u64,
+    pub expired_oauth_states: u64,
+    pub orphaned_binary_data: u64,
+    pub orphaned_binary_bytes: u64,
+    pub unreferenced_blobs: u64,
+    pub unreferenced_blob_bytes: u64,
+    pub dangling_shared_workflows: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_sweep_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_report: Option<GcReport>,
+}
+
+impl GcMetrics {
+    fn record(&self, report: &GcReport) {
+        self.sweeps.fetch_add(1, Ordering::Relaxed);
+        if !report.dry_run {
+            let add = |counter: &AtomicU64, n: u64| counter.fetch_add(n, Ordering::Relaxed);
+            add(&self.stale_webhooks, report.stale_webhooks);
+            add(&self.expired_oauth_states, report.expired_oauth_states);
+            add(&self.orphaned_binary_data, report.orphaned_binary_data);
+            add(&self.orphaned_binary_bytes, report.orphaned_binary_bytes);
+            add(&self.unreferenced_blobs, report.unreferenced_blobs);
+            add(&self.unreferenced_blob_bytes, report.unreferenced_blob_bytes);
+            add(&self.dangling_shared_workflows, report.dangling_shared_workflows);
+        }
+        *self.last_sweep.lock().expect("gc metrics poisoned") = Some((Utc::now(), report.clone()));
+    }
+
+    fn record_failure(&self) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> GcMetricsSnapshot {
+        let last = self.last_sweep.lock().expect("gc metrics poisoned").clone();
+        let get = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        GcMetricsSnapshot {
+            sweeps: get(&self.sweeps),
+            failures: get(&self.failures),
+            stale_webhooks: get(&self.stale_webhooks),
+            expired_oauth_states: get(&self.expired_oauth_states),
+            orphaned_binary_data: get(&self.orphaned_binary_data),
+            orphaned_binary_bytes: get(&self.orphaned_binary_bytes),
+            unreferenced_blobs: get(&self.unreferenced_blobs),
+            unreferenced_blob_bytes: get(&self.unreferenced_blob_bytes),
+            dangling_shared_workflows: get(&self.dangling_shared_workflows),
+            last_sweep_at: last.as_ref().map(|(at, _)| *at),
+            last_report: last.map(|(_, report)| report),
+        }
+    }
+}
+
+/// An execution's binary data directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BinaryDataEntry {
+    execution_id: String,
+    path: PathBuf,
+    bytes: u64,
+}
+
+/// A content-addressed blob file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BlobFile {
+    key: String,
+    path: PathBuf,
+    bytes: u64,
+}
+
+/// Removes stale and orphaned resources.
+pub struct GarbageCollector {
+    pool: PgPool,
+    config: GcConfig,
+    metrics: GcMetrics,
+    binary_data: Option<BinaryDataService>,
+}
+
+impl GarbageCollector {
+    pub fn new(pool: PgPool, config: GcConfig) -> Self {
+        Self {
+            pool,
+            config,
+            metrics: GcMetrics::default(),
+            binary_data: None,
+        }
+    }
+
+    /// Sweep unreferenced content-addressed blobs from `binary_data`'s
+    /// store.
+    pub fn with_binary_data(mut self, binary_data: BinaryDataService) -> Self {
+        self.binary_data = Some(binary_data);
+        self
+    }
+
+    pub fn config(&self) -> &GcConfig {
+        &self.config
+    }
+
+    pub fn metrics(&self) -> &GcMetrics {
+        &self.metrics
+    }
+
+    /// Totals of the content-addressed blobs, with what deduplication
+    /// saved.
+    pub async fn blob_stats(&self) -> Result<BlobStats, DbError> {
+        BinaryDataRepository::new(self.pool.clone()).blob_stats().await
+    }
+
+    /// Sweep every `interval_secs` until the task is aborted.
+    pub fn spawn(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut tick =
+                tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+            loop {
+                tick.tick().await;
+                let _ = self.run_once(self.config.dry_run).await;
+            }
+        })
+    }
+
+    /// Run one sweep.
+    pub async fn run_once(&self, dry_run: bool) -> Result<GcReport, DbError> {
+        match self.sweep(dry_run).await {
+            Ok(report) => {
+                self.metrics.record(&report);
+                tracing::info!(
+                    dry_run,
+                    stale_webhooks = report.stale_webhooks,
+                    expired_oauth_states = report.expired_oauth_states,
+                    orphaned_binary_data = report.orphaned_binary_data,
+                    orphaned_binary_bytes = report.orphaned_binary_bytes,
+                    unreferenced_blobs = report.unreferenced_blobs,
+                    unreferenced_blob_bytes = report.unreferenced_blob_bytes,
+                    dangling_shared_workflows = report.dangling_shared_workflows,
+                    "Garbage collection finished"
+                );
+                Ok(report)
+            }
+            Err(e) => {
+                self.metrics.record_failure();
+                tracing::warn!(error = %e, "Garbage collection failed");
+                Err(e)
+            }
+        }
+    }
+
+    async fn sweep(&self, dry_run: bool) -> Result<GcReport, DbError> {
+        let mut report = GcReport {
+            dry_run,
+            stale_webhooks: self.sweep_rows(STALE_WEBHOOKS, dry_run).await?,
+            expired_oauth_states: self.sweep_rows(EXPIRED_OAUTH_STATES, dry_run).await?,
+            dangling_shared_workflows: self.sweep_rows(DANGLING_SHARED_WORKFLOWS, dry_run).await?,
+            ..Default::default()
+        };
+
+        if let Some(dir) = &self.config.binary_data_dir {
+            for entry in self.orphaned_binary_data(dir).await? {
+                if !dry_run {
+                    tokio::fs::remove_dir_all(&entry.path).await?;
+                }
+                report.orphaned_binary_data += 1;
+                report.orphaned_binary_bytes += entry.bytes;
+            }
+            for blob in self.untracked_blob_files(dir).await? {
+                if !dry_run {
+                    tokio::fs::remove_file(&blob.path).await?;
+                }
+                report.unreferenced_blobs += 1;
+                report.unreferenced_blob_bytes += blob.bytes;
+            }
+        }
+
+        self.sweep_unreferenced_blobs(dry_run, &mut report).await?;
+        Ok(report)
+    }
+
+    /// Delete, or in a dry run count, blobs of the binary data store whose
+    /// references have all been gone for `binary_grace_secs`. The row is
+    /// claimed before the bytes are deleted, so a blob referenced again in
+    /// the meantime is kept.
+    async fn sweep_unreferenced_blobs(
+        &self,
+        dry_run: bool,
+        report: &mut GcReport,
+    ) -> Result<(), DbError> {
+        let Some(store) = self.binary_data.as_ref().and_then(|b| b.store()) else {
+            return Ok(());
+        };
+        let blobs: Vec<(String, Option<i64>)> = sqlx::query_as(
+            "SELECT id, file_size FROM binary_blob WHERE mode = $1 AND ref_count = 0 \
+             AND unreferenced_at < NOW() - make_interval(secs => $2)",
+        )
+        .bind(store.mode())
+        .bind(self.config.binary_grace_secs as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let prefix = format!("{}:", store.mode());
+        for (id, size) in blobs {
+            let Some(key) = id.strip_prefix(&prefix) else {
+                continue;
+            };
+            if !dry_run {
+                let claimed = sqlx::query("DELETE FROM binary_blob WHERE id = $1 AND ref_count = 0")
+                    .bind(&id)
+                    .execute(&self.pool)
+                    .await?
+                    .rows_affected();
+                if claimed == 0 {
+                    continue;
+                }
+                store
+                    .delete(key)
+                    .await
+                    .map_err(|e| DbError::InvalidData(e.to_string()))?;
+            }
+            report.unreferenced_blobs += 1;
+            report.unreferenced_blob_bytes += size.unwrap_or(0).max(0) as u64;
+        }
+        Ok(())
+    }
+
+    /// Blob files under `dir` past the grace period that have no
+    /// `binary_blob` row: written by executions that were never saved.
+    async fn untracked_blob_files(&self, dir: &Path) -> Result<Vec<BlobFile>, DbError> {
+        let root = dir.to_path_buf();
+        let grace = Duration::from_secs(self.config.binary_grace_secs);
+        let files =
+            tokio::task::spawn_blocking(move || blob_files(&root, SystemTime::now(), grace))
+                .await
+                .map_err(|e| DbError::InvalidData(e.to_string()))??;
+        if files.is_empty() {
+            return Ok(files);
+        }
+
+        let ids: Vec<String> = files.iter().map(|f| format!("filesystem:{}", f.key)).collect();
+        let tracked: HashSet<String> =
+            sqlx::query_scalar("SELECT id FROM binary_blob WHERE id = ANY($1)")
+                .bind(&ids)
+                .fetch_all(&self.pool)
+                .await?
+                .into_iter()
+                .collect();
+
+        Ok(files
+            .into_iter()
+            .filter(|f| !tracked.contains(&format!("filesystem:{}", f.key)))
+            .collect())
+    }
+
+    /// Delete, or in a dry run count, the rows matched by `target`.
+    async fn sweep_rows(&self, target: &str, dry_run: bool) -> Result<u64, DbError> {
+        if dry_run {
+            let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", target))
+                .fetch_one(&self.pool)
+                .await?;
+            Ok(count as u64)
+        } else {
+            let result = sqlx::query(&format!("DELETE FROM {}", target))
+                .execute(&self.pool)
+                .await?;
+            Ok(result.rows_affected())
+        }
+    }
+
+    /// Binary data directories past the grace period whose execution is
+    /// missing or soft-deleted.
+    async fn orphaned_binary_data(&self, dir: &Path) -> Result<Vec<BinaryDataEntry>, DbError> {
+        let root = dir.to_path_buf();
+        let grace = Duration::from_secs(self.config.binary_grace_secs);
+        let entries = tokio::task::spawn_blocking(move || {
+            binary_data_entries(&root, SystemTime::now(), grace)
+        })
+        .await
+        .map_err(|e| DbError::InvalidData(e.to_string()))??;
+        if entries.is_empty() {
+            return Ok(entries);
+        }
+
+        let ids: Vec<&str> = entries.iter().map(|e| e.execution_id.as_str()).collect();
+        let live: HashSet<String> = sqlx::query_scalar(
+            "SELECT id FROM execution_entity WHERE id = ANY($1) AND deleted_at IS NULL",
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .collect();
+
+        Ok(entries
+            .into_iter()
+            .filter(|e| !live.contains(&e.execution_id))
+            .collect())
+    }
+}
+
+/// Execution directories under `root` last modified before `now - grace`.
+fn binary_data_entries(
+    root: &Path,
+    now: SystemTime,
+    grace: Duration,
+) -> std::io::Result<Vec<BinaryDataEntry>> {
+    let mut entries = Vec::new();
+    let workflows = root.join("workflows");
+    if !workflows.is_dir() {
+        return Ok(entries);
+    }
+
+    for workflow in std::fs::read_dir(workflows)? {
+        let executions = workflow?.path().join("executions");
+        if !executions.is_dir() {
+            continue;
+        }
+        for execution in std::fs::read_dir(executions)? {
+            let execution = execution?;
+            let path = execution.path();
+            let modified = execution.metadata()?.modified()?;
+            let old_enough = now.duration_since(modified).is_ok_and(|age| age >= grace);
+            if !path.is_dir() || !old_enough {
+                continue;
+            }
+            entries.push(BinaryDataEntry {
+                execution_id: execution.file_name().to_string_lossy().into_owned(),
+                bytes: dir_size(&path)?,
+                path,
+            });
+        }
+    }
+
+    entries.sort_by(|a, b| a.execution_id.cmp(&b.execution_id));
+    Ok(entries)
+}
+
+/// Blob files under `root` last modified before `now - grace`.
+fn blob_files(root: &Path, now: SystemTime, grace: Duration) -> std::io::Result<Vec<BlobFile>> {
+    let mut files = Vec::new();
+    let blobs = root.join(BLOB_KEY_PREFIX);
+    if !blobs.is_dir() {
+        return Ok(files);
+    }
+
+    for entry in std::fs::read_dir(blobs)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let old_enough = now
+            .duration_since(metadata.modified()?)
+            .is_ok_and(|age| age >= grace);
+        if !metadata.is_file() || !old_enough {
+            continue;
+        }
+        files.push(BlobFile {
+            key: format!("{}{}", BLOB_KEY_PREFIX, entry.file_name().to_string_lossy()),
+            path: entry.path(),
+            bytes: metadata.len(),
+        });
+    }
+
+    files.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(files)
+}
+
+fn dir_size(path: &Path) -> std::io::Result<u64> {
+    let mut total = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        total += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_data_entries_respect_grace() {
+        let root = std::env::temp_dir().join(format!("n8n-gc-{}", uuid::Uuid::new_v4()));
+        let execution = root.join("workflows/wf-1/executions/exec-1");
+        std::fs::create_dir_all(execution.join("binary_data")).unwrap();
+        std::fs::write(execution.join("binary_data/file"), b"12345").unwrap();
+        std::fs::write(root.join("workflows/wf-1/stray"), b"x").unwrap();
+        std::fs::create_dir_all(root.join(BLOB_KEY_PREFIX)).unwrap();
+        std::fs::write(root.join(BLOB_KEY_PREFIX).join("abc"), b"1234").unwrap();
+
+        let now = SystemTime::now();
+        assert!(binary_data_entries(&root, now, Duration::from_secs(3600))
+            .unwrap()
+            .is_empty());
+
+        let later = now + Duration::from_secs(7200);
+        let entries = binary_data_entries(&root, later, Duration::from_secs(3600)).unwrap();
+        assert_eq!(
+            entries,
+            vec![BinaryDataEntry {
+                execution_id: "exec-1".to_string(),
+                path: execution,
+                bytes: 5,
+            }]
+        );
+        let blobs = blob_files(&root, later, Duration::from_secs(3600)).unwrap();
+        assert_eq!(blobs.len(), 1);
+        assert_eq!((blobs[0].key.as_str(), blobs[0].bytes), ("blobs/sha256/abc", 4));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_dry_run_metrics_count_sweeps_only() {
+        let metrics = GcMetrics::default();
+        let report = GcReport {
+            stale_webhooks: 3,
+            orphaned_binary_data: 1,
+            orphaned_binary_bytes: 10,
+            ..Default::default()
+        };
+
+        metrics.record(&GcReport { dry_run: true, ..report.clone() });
+        metrics.record(&report);
+        metrics.record_failure();
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.sweeps, 2);
+        assert_eq!(snapshot.failures, 1);
+        assert_eq!(snapshot.stale_webhooks, 3);
+        assert_eq!(snapshot.orphaned_binary_bytes, 10);
+        assert_eq!(snapshot.last_report.map(|r| r.total()), Some(4));
+    }
+}
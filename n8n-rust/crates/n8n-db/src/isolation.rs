@@ -0,0 +1,346 @@
+//! Per-project schema isolation.
+//!
+//! Projects of compliance-separated customers can keep their workflows and
+//! executions in a Postgres schema of their own instead of the shared
+//! `public` one. [`ProjectSchemas::isolate`] creates the schema
+//! (`project_<projectId>` by default), runs the full migration set in it,
+//! copies the project's row in so sharing rows keep their foreign key, and
+//! records the schema in `project_schema`. Users, memberships, credentials
+//! and everything else read from the shared schema stay there.
+//!
+//! [`ProjectSchemas::context`] routes a request: the [`DbContext`] of the
+//! project's schema when it is isolated, the shared one otherwise. Schema
+//! connections set `search_path` to the project schema followed by
+//! `public`, where the `uuid-ossp` functions live; every table exists in
+//! the project schema, so no query falls through to shared rows.
+//!
+//! [`ProjectSchemas::migrate_all`] migrates the shared schema, then every
+//! project schema, continuing past failures; [`ProjectSchemas::status`]
+//! reports the migrations each schema still lacks.
+//!
+//! Only projects without workflows can be isolated; existing rows are
+//! moved by exporting and re-importing them. Isolation is permanent, so
+//! routes are cached once found.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::FromRow;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::error::DbError;
+use crate::repositories::DbContext;
+use crate::DbConfig;
+
+/// Schema holding the shared tables.
+pub const SHARED_SCHEMA: &str = "public";
+
+/// Longest Postgres identifier.
+const MAX_IDENTIFIER_LEN: usize = 63;
+
+/// Configuration of per-project schemas.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchemaIsolationConfig {
+    /// Route isolated projects to their schemas.
+    pub enabled: bool,
+    /// Prefix of project schema names.
+    pub schema_prefix: String,
+    /// Pool size of each project schema.
+    pub max_connections_per_schema: u32,
+}
+
+impl Default for SchemaIsolationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schema_prefix: "project_".to_string(),
+            max_connections_per_schema: 2,
+        }
+    }
+}
+
+/// A project kept in a schema of its own.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSchema {
+    pub project_id: String,
+    pub schema_name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Migration state of one schema.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaStatus {
+    pub schema: String,
+    /// Unset for the shared schema.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    /// Versions not yet applied.
+    pub pending: Vec<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Name of the schema of `project_id`.
+///
+/// Nano IDs are case-sensitive, so names keep their case and are always
+/// quoted; characters outside the nano ID alphabet are refused rather than
+/// replaced, which could make two projects share a schema.
+pub fn schema_name(prefix: &str, project_id: &str) -> Result<String, DbError> {
+    let valid = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    if project_id.is_empty() || !project_id.chars().all(valid) || !prefix.chars().all(valid) {
+        return Err(DbError::InvalidData(format!(
+            "Project ID {:?} with prefix {:?} cannot name a schema",
+            project_id, prefix
+        )));
+    }
+    let name = format!("{}{}", prefix, project_id);
+    if name.len() > MAX_IDENTIFIER_LEN || name.starts_with("pg_") || name == SHARED_SCHEMA {
+        return Err(DbError::InvalidData(format!("{} is not a usable schema name", name)));
+    }
+    Ok(name)
+}
+
+/// Quoted identifier of a name from [`schema_name`].
+fn quoted(schema: &str) -> String {
+    format!("\"{}\"", schema)
+}
+
+/// Routes projects to their schemas.
+#[derive(Clone)]
+pub struct ProjectSchemas {
+    shared: DbContext,
+    database_url: String,
+    config: SchemaIsolationConfig,
+    /// Contexts of isolated projects, by project ID.
+    contexts: Arc<RwLock<HashMap<String, DbContext>>>,
+}
+
+impl ProjectSchemas {
+    /// Route with `db.schema_isolation`, opening schema connections to
+    /// `db.database_url`.
+    pub fn new(shared: DbContext, db: &DbConfig) -> Self {
+        Self {
+            shared,
+            database_url: db.database_url.clone(),
+            config: db.schema_isolation.clone(),
+            contexts: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Context of the shared schema.
+    pub fn shared(&self) -> &DbContext {
+        &self.shared
+    }
+
+    /// Isolated projects, oldest first.
+    pub async fn list(&self) -> Result<Vec<ProjectSchema>, DbError> {
+        let schemas = sqlx::query_as::<_, ProjectSchema>(
+            "SELECT project_id, schema_name, created_at FROM project_schema ORDER BY created_at",
+        )
+        .fetch_all(&self.shared.pool)
+        .await?;
+        Ok(schemas)
+    }
+
+    /// Schema of `project_id`, if it is isolated.
+    pub async fn find(&self, project_id: &str) -> Result<Option<ProjectSchema>, DbError> {
+        let schema = sqlx::query_as::<_, ProjectSchema>(
+            "SELECT project_id, schema_name, created_at FROM project_schema WHERE project_id = $1",
+        )
+        .bind(project_id)
+        .fetch_optional(&self.shared.pool)
+        .await?;
+        Ok(schema)
+    }
+
+    /// Context serving a request of `project_id`: its schema's when it is
+    /// isolated and isolation is enabled, the shared one otherwise.
+    pub async fn context(&self, project_id: Option<&str>) -> Result<DbContext, DbError> {
+        let Some(project_id) = project_id.filter(|_| self.config.enabled) else {
+            return Ok(self.shared.clone());
+        };
+        if let Some(context) = self.contexts.read().await.get(project_id) {
+            return Ok(context.clone());
+        }
+        // Not cached as shared: another instance may isolate the project
+        match self.find(project_id).await? {
+            Some(schema) => self.open(project_id, &schema.schema_name).await,
+            None => Ok(self.shared.clone()),
+        }
+    }
+
+    /// Move `project_id`, which must not own workflows yet, into a schema of
+    /// its own. Isolating an isolated project returns its schema.
+    pub async fn isolate(&self, project_id: &str) -> Result<ProjectSchema, DbError> {
+        if let Some(schema) = self.find(project_id).await? {
+            return Ok(schema);
+        }
+        sqlx::query_scalar::<_, String>("SELECT id FROM project WHERE id = $1")
+            .bind(project_id)
+            .fetch_optional(&self.shared.pool)
+            .await?
+            .ok_or(DbError::NotFound)?;
+        let workflows: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM shared_workflow WHERE project_id = $1")
+                .bind(project_id)
+                .fetch_one(&self.shared.pool)
+                .await?;
+        if workflows > 0 {
+            return Err(DbError::InvalidData(format!(
+                "Project {} already owns {} workflows; export them before isolating it",
+                project_id, workflows
+            )));
+        }
+
+        let name = schema_name(&self.config.schema_prefix, project_id)?;
+        sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS {}", quoted(&name)))
+            .execute(&self.shared.pool)
+            .await?;
+        let context = self.open(project_id, &name).await?;
+        context
+            .migrate()
+            .await
+            .map_err(|e| DbError::MigrationError(format!("{}: {}", name, e)))?;
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO project (id, name, type, icon, description, created_at, updated_at)
+            SELECT id, name, type, icon, description, created_at, updated_at
+            FROM {}.project WHERE id = $1
+            ON CONFLICT (id) DO NOTHING
+            "#,
+            SHARED_SCHEMA
+        ))
+        .bind(project_id)
+        .execute(&context.pool)
+        .await?;
+
+        let schema = sqlx::query_as::<_, ProjectSchema>(
+            r#"
+            INSERT INTO project_schema (project_id, schema_name)
+            VALUES ($1, $2)
+            RETURNING project_id, schema_name, created_at
+            "#,
+        )
+        .bind(project_id)
+        .bind(&name)
+        .fetch_one(&self.shared.pool)
+        .await?;
+        info!(project_id, schema = %name, "Project isolated in its own schema");
+        Ok(schema)
+    }
+
+    /// Run migrations in the shared schema, then in every project schema.
+    /// A project schema failing to migrate is reported and the rest still
+    /// run; the shared schema failing stops here.
+    pub async fn migrate_all(&self) -> Result<Vec<SchemaStatus>, DbError> {
+        self.shared
+            .migrate()
+            .await
+            .map_err(|e| DbError::MigrationError(format!("{}: {}", SHARED_SCHEMA, e)))?;
+        let mut statuses = vec![SchemaStatus {
+            schema: SHARED_SCHEMA.to_string(),
+            project_id: None,
+            pending: Vec::new(),
+            error: None,
+        }];
+
+        for schema in self.list().await? {
+            let context = self.open(&schema.project_id, &schema.schema_name).await?;
+            let error = context.migrate().await.err().map(|e| e.to_string());
+            if let Some(error) = &error {
+                warn!(schema = %schema.schema_name, "Project schema migration failed: {}", error);
+            }
+            let pending = context.pending_migrations().await.unwrap_or_default();
+            statuses.push(SchemaStatus {
+                schema: schema.schema_name,
+                project_id: Some(schema.project_id),
+                pending,
+                error,
+            });
+        }
+        Ok(statuses)
+    }
+
+    /// Pending migrations of the shared and every project schema.
+    pub async fn status(&self) -> Result<Vec<SchemaStatus>, DbError> {
+        let mut statuses = vec![SchemaStatus {
+            schema: SHARED_SCHEMA.to_string(),
+            project_id: None,
+            pending: self.shared.pending_migrations().await?,
+            error: None,
+        }];
+
+        for schema in self.list().await? {
+            let context = self.open(&schema.project_id, &schema.schema_name).await?;
+            let (pending, error) = match context.pending_migrations().await {
+                Ok(pending) => (pending, None),
+                Err(e) => (Vec::new(), Some(e.to_string())),
+            };
+            statuses.push(SchemaStatus {
+                schema: schema.schema_name,
+                project_id: Some(schema.project_id),
+                pending,
+                error,
+            });
+        }
+        Ok(statuses)
+    }
+
+    /// Context of `schema`, connecting lazily on first use.
+    async fn open(&self, project_id: &str, schema: &str) -> Result<DbContext, DbError> {
+        let mut contexts = self.contexts.write().await;
+        if let Some(context) = contexts.get(project_id) {
+            return Ok(context.clone());
+        }
+        let options = PgConnectOptions::from_str(&self.database_url)?.options([(
+            "search_path",
+            format!("{},{}", quoted(schema), SHARED_SCHEMA),
+        )]);
+        let pool = PgPoolOptions::new()
+            .max_connections(self.config.max_connections_per_schema.max(1))
+            .min_connections(0)
+            .acquire_timeout(Duration::from_secs(30))
+            .idle_timeout(Duration::from_secs(600))
+            .connect_lazy_with(options);
+        let context = DbContext::new(pool);
+        contexts.insert(project_id.to_string(), context.clone());
+        Ok(context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_name_keeps_case_and_refuses_unsafe_ids() {
+        assert_eq!(schema_name("project_", "aB3-x_Z").unwrap(), "project_aB3-x_Z");
+        assert_ne!(
+            schema_name("project_", "abc").unwrap(),
+            schema_name("project_", "ABC").unwrap()
+        );
+
+        assert!(schema_name("project_", "").is_err());
+        assert!(schema_name("project_", "x\"; DROP SCHEMA public; --").is_err());
+        assert!(schema_name("project_", &"a".repeat(60)).is_err());
+        assert!(schema_name("pg_", "abc").is_err());
+        assert!(schema_name("", "public").is_err());
+    }
+
+    #[test]
+    fn test_schema_isolation_config_defaults() {
+        let config: SchemaIsolationConfig =
+            serde_json::from_value(serde_json::json!({ "enabled": true })).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.schema_prefix, "project_");
+        assert_eq!(config.max_connections_per_schema, 2);
+    }
+}
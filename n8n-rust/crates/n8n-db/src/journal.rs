@@ -0,0 +1,126 @@
+//! Side-effect journal kept in Postgres.
+//!
+//! Entries live in `side_effect_journal` (migration
+//! `011_side_effect_journal`), so intents survive the crash they are meant
+//! to expose. Rows are not tied to `execution_entity`: intents are written
+//! while the execution runs, before it is saved.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use n8n_core::{JournalBackend, JournalEntry, JournalOutcome};
+use sqlx::{FromRow, PgPool};
+
+const JOURNAL_COLUMNS: &str =
+    "id, execution_id, workflow_id, node, node_type, attempt, intent_at, completed_at, outcome";
+
+#[derive(FromRow)]
+struct JournalRow {
+    id: String,
+    execution_id: String,
+    workflow_id: String,
+    node: String,
+    node_type: String,
+    attempt: i32,
+    intent_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+    outcome: Option<String>,
+}
+
+impl From<JournalRow> for JournalEntry {
+    fn from(row: JournalRow) -> Self {
+        Self {
+            id: row.id,
+            execution_id: row.execution_id,
+            workflow_id: row.workflow_id,
+            node: row.node,
+            node_type: row.node_type,
+            attempt: row.attempt.max(0) as u32,
+            intent_at: row.intent_at,
+            completed_at: row.completed_at,
+            outcome: row.outcome.and_then(|outcome| outcome.parse().ok()),
+        }
+    }
+}
+
+/// [`JournalBackend`] backed by Postgres.
+pub struct PgSideEffectJournal {
+    pool: PgPool,
+}
+
+impl PgSideEffectJournal {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl JournalBackend for PgSideEffectJournal {
+    async fn record_intent(&self, entry: &JournalEntry) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            INSERT INTO side_effect_journal
+                (id, execution_id, workflow_id, node, node_type, attempt, intent_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(&entry.id)
+        .bind(&entry.execution_id)
+        .bind(&entry.workflow_id)
+        .bind(&entry.node)
+        .bind(&entry.node_type)
+        .bind(entry.attempt as i32)
+        .bind(entry.intent_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    async fn record_completion(
+        &self,
+        id: &str,
+        outcome: JournalOutcome,
+        at: DateTime<Utc>,
+    ) -> Result<(), String> {
+        let updated = sqlx::query(
+            "UPDATE side_effect_journal SET completed_at = $2, outcome = $3 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(at)
+        .bind(outcome.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected();
+        if updated == 0 {
+            return Err(format!("No journal entry {}", id));
+        }
+        Ok(())
+    }
+
+    async fn entries(&self, execution_id: &str) -> Result<Vec<JournalEntry>, String> {
+        let rows = sqlx::query_as::<_, JournalRow>(&format!(
+            "SELECT {} FROM side_effect_journal WHERE execution_id = $1 ORDER BY intent_at, id",
+            JOURNAL_COLUMNS
+        ))
+        .bind(execution_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(rows.into_iter().map(JournalEntry::from).collect())
+    }
+
+    async fn in_doubt(&self, before: DateTime<Utc>) -> Result<Vec<JournalEntry>, String> {
+        let rows = sqlx::query_as::<_, JournalRow>(&format!(
+            "SELECT {} FROM side_effect_journal
+             WHERE completed_at IS NULL AND intent_at < $1
+             ORDER BY intent_at, id",
+            JOURNAL_COLUMNS
+        ))
+        .bind(before)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(rows.into_iter().map(JournalEntry::from).collect())
+    }
+}
@@ -0,0 +1,184 @@
+//! Leader election between instances.
+//!
+//! Some work must run on one instance of a deployment only, like pruning
+//! executions or firing schedule triggers. [`LeaderElection`] elects that
+//! instance with a session-level Postgres advisory lock on a hash of its
+//! key, taken on a connection of its own: the instance holding the lock
+//! leads until it resigns or its connection dies, which releases the lock
+//! for the others, which try it every `renew_interval`.
+//!
+//! The leader renews by checking its connection on the same interval; a
+//! check that fails or does not answer within the interval loses the lead.
+//! Leadership is published on a [`watch`] channel, and loss callbacks run
+//! when it is lost. Postgres releases the lock of a dead session only once
+//! it notices, and a leader cut off from the database leads until its next
+//! check, so work guarded by an election must tolerate a short gap or
+//! overlap.
+
+use sqlx::{PgConnection, PgPool};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::error::DbError;
+
+/// Key instances elect their leader under by default.
+pub const DEFAULT_LEADER_KEY: &str = "n8n_leader";
+
+type LossCallback = Box<dyn Fn() + Send + Sync>;
+
+/// Elects one leader among the instances sharing a database and key.
+pub struct LeaderElection {
+    pool: PgPool,
+    key: String,
+    renew_interval: Duration,
+    leader: watch::Sender<bool>,
+    on_loss: Mutex<Vec<LossCallback>>,
+    /// Connection holding the lock while leading.
+    connection: tokio::sync::Mutex<Option<PgConnection>>,
+}
+
+impl LeaderElection {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            key: DEFAULT_LEADER_KEY.to_string(),
+            renew_interval: Duration::from_secs(10),
+            leader: watch::channel(false).0,
+            on_loss: Mutex::new(Vec::new()),
+            connection: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Elect under `key`, for work led separately from the default.
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = key.into();
+        self
+    }
+
+    /// How often the leader checks its connection and followers try the
+    /// lock.
+    pub fn with_renew_interval(mut self, renew_interval: Duration) -> Self {
+        self.renew_interval = renew_interval;
+        self
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Whether this instance leads.
+    pub fn is_leader(&self) -> bool {
+        *self.leader.borrow()
+    }
+
+    /// Leadership of this instance, updated as it is won and lost.
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.leader.subscribe()
+    }
+
+    /// Call `callback` whenever this instance loses the lead.
+    pub fn on_loss(&self, callback: impl Fn() + Send + Sync + 'static) {
+        self.on_loss.lock().expect("leader callbacks poisoned").push(Box::new(callback));
+    }
+
+    /// Renew the lead, or try to take it; returns whether this instance
+    /// leads afterwards.
+    pub async fn renew(&self) -> Result<bool, DbError> {
+        let mut connection = self.connection.lock().await;
+        if let Some(held) = connection.as_mut() {
+            let check = sqlx::query("SELECT 1").execute(&mut *held);
+            match tokio::time::timeout(self.renew_interval, check).await {
+                Ok(Ok(_)) => return Ok(true),
+                Ok(Err(e)) => {
+                    tracing::warn!(key = %self.key, error = %e, "Leader connection failed")
+                }
+                Err(_) => tracing::warn!(key = %self.key, "Leader connection timed out"),
+            }
+            // Dropping the connection ends the session and its lock.
+            *connection = None;
+            self.lost();
+            return Ok(false);
+        }
+
+        let mut candidate = self.pool.acquire().await?;
+        let locked: bool =
+            sqlx::query_scalar("SELECT pg_try_advisory_lock(hashtextextended($1, 0))")
+                .bind(&self.key)
+                .fetch_one(&mut *candidate)
+                .await?;
+        if locked {
+            // Out of the pool, so it is not recycled while holding the lock.
+            *connection = Some(candidate.detach());
+            self.leader.send_replace(true);
+            tracing::info!(key = %self.key, "Elected leader");
+        }
+        Ok(locked)
+    }
+
+    /// Give up the lead, e.g. on shutdown, without running loss callbacks.
+    /// Abort the task from [`Self::spawn`] first, or it runs again.
+    pub async fn resign(&self) -> Result<(), DbError> {
+        let Some(mut held) = self.connection.lock().await.take() else {
+            return Ok(());
+        };
+        self.leader.send_replace(false);
+        sqlx::query("SELECT pg_advisory_unlock(hashtextextended($1, 0))")
+            .bind(&self.key)
+            .execute(&mut held)
+            .await?;
+        sqlx::Connection::close(held).await?;
+        tracing::info!(key = %self.key, "Resigned leadership");
+        Ok(())
+    }
+
+    /// Renew every `renew_interval` until the task is aborted.
+    pub fn spawn(self: std::sync::Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(self.renew_interval);
+            loop {
+                tick.tick().await;
+                if let Err(e) = self.renew().await {
+                    tracing::warn!(key = %self.key, error = %e, "Leader election failed");
+                }
+            }
+        })
+    }
+
+    fn lost(&self) {
+        if !self.leader.send_replace(false) {
+            return;
+        }
+        tracing::warn!(key = %self.key, "Lost leadership");
+        for callback in self.on_loss.lock().expect("leader callbacks poisoned").iter() {
+            callback();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_loss_notifies_once() {
+        let pool = PgPool::connect_lazy("postgres://localhost/n8n").unwrap();
+        let election = LeaderElection::new(pool);
+        let losses = Arc::new(AtomicUsize::new(0));
+        let counted = losses.clone();
+        election.on_loss(move || {
+            counted.fetch_add(1, Ordering::SeqCst);
+        });
+        let mut leader = election.subscribe();
+
+        election.leader.send_replace(true);
+        assert!(election.is_leader());
+        election.lost();
+        election.lost();
+        assert_eq!(losses.load(Ordering::SeqCst), 1);
+        assert!(leader.has_changed().unwrap());
+        assert!(!*leader.borrow_and_update());
+    }
+}
@@ -38,10 +38,22 @@
 //! This crate maintains compatibility with the original n8n PostgreSQL schema.
 //! All entity definitions match the TypeORM entities in `packages/@n8n/db/src/entities/`.
 
+pub mod compression;
+pub mod concurrency;
 pub mod entities;
 pub mod error;
+pub mod events;
+pub mod gc;
+pub mod isolation;
+pub mod journal;
+pub mod leader;
+pub mod query;
+pub mod raw;
 pub mod repositories;
+pub mod scheduler;
 pub mod storage;
+pub mod tiered;
+pub mod transfer;
 
 // Re-export entity types explicitly to avoid ambiguous glob re-exports
 // (entities and repositories have submodules with the same names).
@@ -50,37 +62,83 @@ pub use entities::{
     // Workflow entities
     WorkflowEntity, WorkflowMeta, WorkflowHistory, SharedWorkflow,
     WorkflowSharingRole, WorkflowTagMapping, InsertWorkflow, UpdateWorkflow,
+    // Folder entities
+    Folder, FolderTagMapping, FolderTreeEntry, InsertFolder,
     // Execution entities
     ExecutionEntity, ExecutionData, ExecutionMetadata, ExecutionFilters,
-    ExecutionWithData, InsertExecution, UpdateExecution,
+    ExecutionWithData, InsertExecution, UpdateExecution, WorkflowResourceUsage, ExecutionCursor,
+    ExecutionPage,
+    // Annotation entities
+    ExecutionAnnotation, AnnotationTagEntity, AnnotationVote, AnnotationUpdate,
+    AnnotationInsights, AnnotationTagCount,
+    // Test run entities
+    TestRun, TestRunStatus, TestCaseExecution, TestCaseStatus,
+    // Binary data entities
+    BinaryDataEntity,
     // Credentials entities
     CredentialsEntity, SharedCredentials, CredentialSharingRole,
-    InsertCredentials, UpdateCredentials, CredentialFilters,
+    InsertCredentials, UpdateCredentials, CredentialFilters, CredentialBundle, ExportedCredential,
+    ImportIds, ImportOptions, ImportAction, ImportedCredential, ImportReport,
+    CREDENTIAL_BUNDLE_VERSION,
     // User entities
-    User, UserSettings, Role, AuthIdentity, ApiKey,
+    User, UserSettings, ExecutionView, Role, AuthIdentity, ApiKey,
     // Project entities
     Project, ProjectIcon, ProjectRelation,
     // Tag entities
     TagEntity, InsertTag,
     // Webhook entities
     WebhookEntity, InsertWebhook,
+    // Endpoint entities
+    EndpointEntity, InsertEndpoint,
+    // Timer entities
+    TimerEntity, InsertTimer,
     // Settings entities
     Setting,
     // Variables entities
     Variable, InsertVariable,
+    // Workflow statistics entities
+    WorkflowStatisticsEntity,
+    // Processed data entities
+    ProcessedDataEntity,
 };
 
+pub use compression::{CompressionAlgorithm, DataCompressionConfig};
+pub use concurrency::PgConcurrencyLocks;
 pub use error::*;
+pub use events::{ChangeOperation, ChangedEntity, EntityChange, PgEventBus};
+
+pub use gc::{GarbageCollector, GcConfig, GcMetrics, GcMetricsSnapshot, GcReport};
+pub use isolation::{
+    schema_name, ProjectSchema, ProjectSchemas, SchemaIsolationConfig, SchemaStatus, SHARED_SCHEMA,
+};
+pub use journal::PgSideEffectJournal;
+pub use leader::{LeaderElection, DEFAULT_LEADER_KEY};
+pub use query::{
+    ExecutionOrder, ExecutionQuery, QueryParam, SortDirection, DEFAULT_QUERY_LIMIT,
+};
+pub use raw::{named_to_positional, RawQuery};
 
 // Re-export storage bridge types.
 pub use storage::{SqlxExecutionStorage, SqlxWorkflowStorage};
+pub use tiered::{TieredExecutionStorage, TieredStorageConfig};
+pub use transfer::{
+    credential_issues, CredentialIssue, CredentialProblem, ExecutionHistory, TransferReport,
+    TransferRequest, WorkflowTransfers,
+};
+pub use scheduler::{
+    TimerHandler, TimerOutcome, TimerQueue, TimerScheduler, TimerSchedulerConfig,
+    WorkflowTimerHandler, SPILLED_REQUEST_KEY,
+};
 
 // Re-export repository types explicitly.
 pub use repositories::{
     DbContext,
     WorkflowRepository, ExecutionRepository, CredentialsRepository,
     TagRepository, UserRepository, ProjectRepository, SettingsRepository,
-    VariablesRepository, WebhookRepository,
+    VariablesRepository, WebhookRepository, TimerRepository, EndpointRepository, CostScope,
+    AnnotationRepository, BinaryDataRepository, BlobStats, PruningConfig, PruningReport,
+    PruningService, WorkflowStatisticsRepository, FolderRepository, MAX_FOLDER_DEPTH,
+    TestRunRepository, ProcessedDataRepository,
 };
 
 use sqlx::postgres::{PgPool, PgPoolOptions};
@@ -119,7 +177,8 @@ pub async fn connect_with_options(
 }
 
 /// Database configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct DbConfig {
     /// PostgreSQL connection URL.
     pub database_url: String,
@@ -131,6 +190,14 @@ pub struct DbConfig {
     pub acquire_timeout_secs: u64,
     /// Idle connection timeout in seconds.
     pub idle_timeout_secs: u64,
+    /// Per-project schemas; see [`isolation`].
+    pub schema_isolation: SchemaIsolationConfig,
+    /// Key credential data is encrypted with, as n8n's
+    /// `N8N_ENCRYPTION_KEY`; unset stores it as given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encryption_key: Option<String>,
+    /// Compression of large execution data; see [`compression`].
+    pub compression: DataCompressionConfig,
 }
 
 impl Default for DbConfig {
@@ -141,6 +208,9 @@ impl Default for DbConfig {
             min_connections: 1,
             acquire_timeout_secs: 30,
             idle_timeout_secs: 600,
+            schema_isolation: SchemaIsolationConfig::default(),
+            encryption_key: None,
+            compression: DataCompressionConfig::default(),
         }
     }
 }
@@ -168,6 +238,24 @@ impl DbConfig {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(600),
+            schema_isolation: SchemaIsolationConfig {
+                enabled: std::env::var("DB_SCHEMA_ISOLATION")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(false),
+                ..Default::default()
+            },
+            encryption_key: std::env::var(n8n_core::ENCRYPTION_KEY_ENV).ok(),
+            compression: DataCompressionConfig {
+                algorithm: std::env::var("N8N_EXECUTION_DATA_COMPRESSION")
+                    .ok()
+                    .and_then(|s| serde_json::from_value(s.into()).ok())
+                    .unwrap_or_default(),
+                threshold_bytes: std::env::var("N8N_EXECUTION_DATA_COMPRESSION_THRESHOLD")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(DataCompressionConfig::default().threshold_bytes),
+            },
         }
     }
 
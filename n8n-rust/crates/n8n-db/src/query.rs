@@ -0,0 +1,425 @@
+//! Composable execution queries.
+//!
+//! [`ExecutionFilters`] covers the fixed filters of the execution list,
+//! which lists through the [`ExecutionQuery`] they convert into.
+//! [`ExecutionQuery`] builds any combination of them, plus execution
+//! metadata matches and ordering, into one parameterized statement run by
+//! [`ExecutionRepository::query`](crate::ExecutionRepository::query):
+//!
+//! ```rust,no_run
+//! # async fn failures(db: n8n_db::DbContext) -> Result<(), n8n_db::DbError> {
+//! use n8n_db::{ExecutionOrder, ExecutionQuery, SortDirection};
+//! use n8n_workflow::ExecutionStatus;
+//!
+//! let query = ExecutionQuery::new()
+//!     .statuses([ExecutionStatus::Error, ExecutionStatus::Crashed])
+//!     .workflow_ids(["wf1", "wf2"])
+//!     .metadata("customerId", "c-42")
+//!     .annotation_tags(["needs-review"])
+//!     .order_by(ExecutionOrder::StoppedAt, SortDirection::Desc)
+//!     .limit(50);
+//! let executions = db.executions.query(&query).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Every value is bound as a parameter; the statement text only ever holds
+//! the builder's own SQL and placeholders. Metadata matches are one JSONB
+//! containment test against the execution's `execution_metadata` rows
+//! folded into an object. Conditions combine with `AND`; the values of one
+//! set condition with `OR`. Without an ordering executions come newest
+//! first; `id` breaks ties so pages are stable.
+
+use chrono::{DateTime, Utc};
+use n8n_workflow::{ExecutionStatus, WorkflowExecuteMode};
+use serde_json::{Map, Value};
+
+use crate::entities::{AnnotationVote, ExecutionCursor, ExecutionFilters};
+
+/// Rows a query returns unless it sets a limit.
+pub const DEFAULT_QUERY_LIMIT: i64 = 100;
+
+/// A parameter of a built query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryParam {
+    Bool(bool),
+    Int(i64),
+    Text(String),
+    TextArray(Vec<String>),
+    Timestamp(DateTime<Utc>),
+    Jsonb(Value),
+}
+
+/// Column executions can be ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionOrder {
+    CreatedAt,
+    StartedAt,
+    StoppedAt,
+    Status,
+    WorkflowId,
+}
+
+impl ExecutionOrder {
+    fn column(&self) -> &'static str {
+        match self {
+            Self::CreatedAt => "created_at",
+            Self::StartedAt => "started_at",
+            Self::StoppedAt => "stopped_at",
+            Self::Status => "status",
+            Self::WorkflowId => "workflow_id",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    fn sql(&self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC NULLS LAST",
+        }
+    }
+}
+
+/// Builder of an execution query.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionQuery {
+    statuses: Option<Vec<ExecutionStatus>>,
+    modes: Option<Vec<WorkflowExecuteMode>>,
+    workflow_ids: Option<Vec<String>>,
+    finished: Option<bool>,
+    workflow_tag_ids: Option<Vec<String>>,
+    metadata: Map<String, Value>,
+    vote: Option<AnnotationVote>,
+    annotation_tag_ids: Option<Vec<String>>,
+    started_after: Option<DateTime<Utc>>,
+    started_before: Option<DateTime<Utc>>,
+    stopped_after: Option<DateTime<Utc>>,
+    stopped_before: Option<DateTime<Utc>>,
+    include_deleted: bool,
+    cursor: Option<ExecutionCursor>,
+    order: Vec<(ExecutionOrder, SortDirection)>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+impl ExecutionQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Executions with any of these statuses.
+    pub fn statuses(mut self, statuses: impl IntoIterator<Item = ExecutionStatus>) -> Self {
+        self.statuses = Some(statuses.into_iter().collect());
+        self
+    }
+
+    /// Executions started in any of these modes.
+    pub fn modes(mut self, modes: impl IntoIterator<Item = WorkflowExecuteMode>) -> Self {
+        self.modes = Some(modes.into_iter().collect());
+        self
+    }
+
+    /// Executions of any of these workflows.
+    pub fn workflow_ids<S: Into<String>>(mut self, ids: impl IntoIterator<Item = S>) -> Self {
+        self.workflow_ids = Some(ids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn finished(mut self, finished: bool) -> Self {
+        self.finished = Some(finished);
+        self
+    }
+
+    /// Executions of workflows carrying any of these tags.
+    pub fn workflow_tags<S: Into<String>>(mut self, tag_ids: impl IntoIterator<Item = S>) -> Self {
+        self.workflow_tag_ids = Some(tag_ids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Executions whose metadata has `key` set to `value`; repeat for
+    /// several keys.
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), Value::String(value.into()));
+        self
+    }
+
+    /// Executions annotated with this vote.
+    pub fn vote(mut self, vote: AnnotationVote) -> Self {
+        self.vote = Some(vote);
+        self
+    }
+
+    /// Executions annotated with any of these annotation tags.
+    pub fn annotation_tags<S: Into<String>>(
+        mut self,
+        tag_ids: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.annotation_tag_ids = Some(tag_ids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Executions started in `[after, before]`; either end may be open.
+    pub fn started_between(
+        mut self,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.started_after = after;
+        self.started_before = before;
+        self
+    }
+
+    /// Executions stopped in `[after, before]`; either end may be open.
+    pub fn stopped_between(
+        mut self,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.stopped_after = after;
+        self.stopped_before = before;
+        self
+    }
+
+    /// Include soft-deleted executions.
+    pub fn include_deleted(mut self, include: bool) -> Self {
+        self.include_deleted = include;
+        self
+    }
+
+    /// Executions after `cursor` in the newest-first order, for keyset
+    /// pagination; only meaningful without another ordering.
+    pub fn after_cursor(mut self, cursor: ExecutionCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Order by `order`, after any ordering added before.
+    pub fn order_by(mut self, order: ExecutionOrder, direction: SortDirection) -> Self {
+        self.order.push((order, direction));
+        self
+    }
+
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: i64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// The statement and its parameters, `$1` first.
+    pub fn build(&self) -> (String, Vec<QueryParam>) {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+        if !self.include_deleted {
+            conditions.push("deleted_at IS NULL".to_string());
+        }
+        // `$?` in a condition is the placeholder of its parameter.
+        let mut push = |condition: &str, param: QueryParam| {
+            params.push(param);
+            conditions.push(condition.replace("$?", &format!("${}", params.len())));
+        };
+
+        if let Some(statuses) = &self.statuses {
+            let statuses = statuses.iter().map(|s| s.as_str().to_string()).collect();
+            push("status = ANY($?)", QueryParam::TextArray(statuses));
+        }
+        if let Some(modes) = &self.modes {
+            let modes = modes.iter().map(|m| m.as_str().to_string()).collect();
+            push("mode = ANY($?)", QueryParam::TextArray(modes));
+        }
+        if let Some(ids) = &self.workflow_ids {
+            push("workflow_id = ANY($?)", QueryParam::TextArray(ids.clone()));
+        }
+        if let Some(finished) = self.finished {
+            push("finished = $?", QueryParam::Bool(finished));
+        }
+        if let Some(tag_ids) = &self.workflow_tag_ids {
+            push(
+                "EXISTS (SELECT 1 FROM workflow_tag_mapping t \
+                 WHERE t.workflow_id = execution_entity.workflow_id AND t.tag_id = ANY($?))",
+                QueryParam::TextArray(tag_ids.clone()),
+            );
+        }
+        if !self.metadata.is_empty() {
+            push(
+                "(SELECT COALESCE(jsonb_object_agg(m.key, m.value), '{}'::jsonb) \
+                 FROM execution_metadata m WHERE m.execution_id = execution_entity.id) @> $?",
+                QueryParam::Jsonb(Value::Object(self.metadata.clone())),
+            );
+        }
+        if let Some(vote) = self.vote {
+            push(
+                "EXISTS (SELECT 1 FROM execution_annotations a \
+                 WHERE a.execution_id = execution_entity.id AND a.vote = $?)",
+                QueryParam::Text(vote.as_str().to_string()),
+            );
+        }
+        if let Some(tag_ids) = &self.annotation_tag_ids {
+            push(
+                "EXISTS (SELECT 1 FROM execution_annotations a \
+                 JOIN execution_annotation_tags t ON t.annotation_id = a.id \
+                 WHERE a.execution_id = execution_entity.id AND t.tag_id = ANY($?))",
+                QueryParam::TextArray(tag_ids.clone()),
+            );
+        }
+        let ranges = [
+            ("started_at >= $?", self.started_after),
+            ("started_at <= $?", self.started_before),
+            ("stopped_at >= $?", self.stopped_after),
+            ("stopped_at <= $?", self.stopped_before),
+        ];
+        for (condition, at) in ranges {
+            if let Some(at) = at {
+                push(condition, QueryParam::Timestamp(at));
+            }
+        }
+        if let Some(cursor) = &self.cursor {
+            params.push(QueryParam::Timestamp(cursor.created_at));
+            params.push(QueryParam::Text(cursor.id.clone()));
+            conditions.push(format!(
+                "(created_at, id) < (${}, ${})",
+                params.len() - 1,
+                params.len()
+            ));
+        }
+
+        let mut order: Vec<String> = self
+            .order
+            .iter()
+            .map(|(order, direction)| format!("{} {}", order.column(), direction.sql()))
+            .collect();
+        if order.is_empty() {
+            order.push("created_at DESC".to_string());
+        }
+        order.push("id DESC".to_string());
+
+        if conditions.is_empty() {
+            conditions.push("TRUE".to_string());
+        }
+        params.push(QueryParam::Int(self.limit.unwrap_or(DEFAULT_QUERY_LIMIT).max(0)));
+        params.push(QueryParam::Int(self.offset.unwrap_or(0).max(0)));
+        let sql = format!(
+            "SELECT id, finished, mode, status, created_at, started_at, stopped_at, \
+             deleted_at, workflow_id, retry_of, retry_success_id, wait_till, stored_at \
+             FROM execution_entity WHERE {} ORDER BY {} LIMIT ${} OFFSET ${}",
+            conditions.join(" AND "),
+            order.join(", "),
+            params.len() - 1,
+            params.len()
+        );
+        (sql, params)
+    }
+}
+
+impl From<&ExecutionFilters> for ExecutionQuery {
+    fn from(filters: &ExecutionFilters) -> Self {
+        Self {
+            statuses: filters.status.clone(),
+            modes: filters.mode.clone(),
+            workflow_ids: filters.workflow_id.clone().map(|id| vec![id]),
+            finished: filters.finished,
+            workflow_tag_ids: filters.tag_ids.clone(),
+            vote: filters.vote,
+            annotation_tag_ids: filters.annotation_tag_ids.clone(),
+            started_after: filters.started_after,
+            started_before: filters.started_before,
+            include_deleted: filters.include_deleted,
+            limit: filters.limit,
+            offset: filters.offset,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_numbers_parameters_in_order() {
+        let after = Utc::now();
+        let (sql, params) = ExecutionQuery::new()
+            .statuses([ExecutionStatus::Error])
+            .workflow_ids(["wf1", "wf2"])
+            .metadata("customerId", "c-42")
+            .annotation_tags(["review"])
+            .started_between(Some(after), None)
+            .order_by(ExecutionOrder::StoppedAt, SortDirection::Desc)
+            .limit(10)
+            .build();
+
+        assert!(sql.contains("deleted_at IS NULL AND status = ANY($1) AND workflow_id = ANY($2)"));
+        assert!(sql.contains("@> $3"));
+        assert!(sql.contains("t.tag_id = ANY($4)"));
+        assert!(sql.contains("started_at >= $5"));
+        assert!(sql.ends_with("ORDER BY stopped_at DESC NULLS LAST, id DESC LIMIT $6 OFFSET $7"));
+        assert_eq!(
+            params,
+            vec![
+                QueryParam::TextArray(vec!["error".to_string()]),
+                QueryParam::TextArray(vec!["wf1".to_string(), "wf2".to_string()]),
+                QueryParam::Jsonb(serde_json::json!({ "customerId": "c-42" })),
+                QueryParam::TextArray(vec!["review".to_string()]),
+                QueryParam::Timestamp(after),
+                QueryParam::Int(10),
+                QueryParam::Int(0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_values_never_reach_the_statement() {
+        let hostile = "x'); DROP TABLE execution_entity; --";
+        let query = ExecutionQuery::new()
+            .workflow_ids([hostile])
+            .workflow_tags([hostile])
+            .metadata(hostile, hostile)
+            .annotation_tags([hostile]);
+        let (sql, params) = query.build();
+        let (plain, _) = ExecutionQuery::new()
+            .workflow_ids(["a"])
+            .workflow_tags(["a"])
+            .metadata("a", "a")
+            .annotation_tags(["a"])
+            .build();
+
+        assert_eq!(sql, plain);
+        assert!(!sql.contains("DROP") && !sql.contains(hostile));
+        let mut metadata = Map::new();
+        metadata.insert(hostile.to_string(), hostile.into());
+        assert_eq!(params[2], QueryParam::Jsonb(Value::Object(metadata)));
+    }
+
+    #[test]
+    fn test_filters_page_after_a_cursor() {
+        let filters = ExecutionFilters {
+            mode: Some(vec![WorkflowExecuteMode::Webhook]),
+            ..ExecutionFilters::for_workflow("wf1")
+        };
+        let cursor = ExecutionCursor {
+            created_at: Utc::now(),
+            id: "e9".to_string(),
+        };
+        let (sql, params) = ExecutionQuery::from(&filters)
+            .after_cursor(cursor.clone())
+            .limit(21)
+            .build();
+
+        assert!(sql.contains("mode = ANY($1) AND workflow_id = ANY($2)"));
+        assert!(sql.contains("(created_at, id) < ($3, $4)"));
+        assert!(sql.ends_with("ORDER BY created_at DESC, id DESC LIMIT $5 OFFSET $6"));
+        assert_eq!(params[2], QueryParam::Timestamp(cursor.created_at));
+        assert_eq!(params[3], QueryParam::Text(cursor.id));
+        assert_eq!(params[4], QueryParam::Int(21));
+    }
+}
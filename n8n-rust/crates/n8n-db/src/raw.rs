@@ -0,0 +1,376 @@
+//! Raw SQL with named parameters, for reports the repositories do not
+//! cover.
+//!
+//! ```rust,no_run
+//! # async fn report(db: n8n_db::DbContext) -> Result<(), n8n_db::DbError> {
+//! let rows = db
+//!     .raw("SELECT status, COUNT(*) AS runs FROM execution_entity \
+//!           WHERE workflow_id = :workflow AND started_at >= :since::timestamptz \
+//!           GROUP BY status")
+//!     .bind("workflow", "wf1")
+//!     .bind("since", "2026-01-01T00:00:00Z")
+//!     .fetch_all()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! `:name` placeholders become positional parameters; names repeat freely,
+//! and `::` casts, quoted strings, quoted identifiers, dollar quotes and
+//! comments are left alone. Every placeholder must be bound and every bound
+//! value used. Values are JSON: `null`, booleans, integers (`bigint`),
+//! floats and strings (`text`) bind as such, so timestamps and the like
+//! need a cast (`:since::timestamptz`); arrays of strings or integers bind
+//! as `text[]` or `bigint[]` for `= ANY(:ids)`, other arrays and objects
+//! as `jsonb`.
+//!
+//! Queries are read-only by default: they run in a `READ ONLY`
+//! transaction that is rolled back, under a 30 second statement timeout.
+//! `read_only(false)` allows writes and commits. Only one statement runs
+//! per query. This keeps mistakes out of the database, not hostile
+//! operators: connect with a read-only role for that.
+//!
+//! [`RawQuery::fetch_all`] maps rows to JSON objects by column type. Types
+//! without a JSON mapping (`numeric`, `interval`, ...) need a cast in the
+//! query, such as `AVG(x)::float8`. [`RawQuery::fetch_all_as`] maps rows
+//! with `sqlx::FromRow` instead.
+
+use serde_json::{Map, Value};
+use sqlx::postgres::{PgArguments, PgRow};
+use sqlx::query::Query;
+use sqlx::types::Json;
+use sqlx::{Column, FromRow, PgPool, Postgres, Row, TypeInfo};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::error::DbError;
+
+/// Statement timeout of raw queries.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A raw SQL query with named parameters.
+#[derive(Debug, Clone)]
+pub struct RawQuery {
+    pool: PgPool,
+    sql: String,
+    params: BTreeMap<String, Value>,
+    read_only: bool,
+    timeout: Duration,
+}
+
+impl RawQuery {
+    pub fn new(pool: PgPool, sql: impl Into<String>) -> Self {
+        Self {
+            pool,
+            sql: sql.into(),
+            params: BTreeMap::new(),
+            read_only: true,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Bind `:name`.
+    pub fn bind(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.params.insert(name.into(), value.into());
+        self
+    }
+
+    /// Bind every entry of `params`.
+    pub fn bind_all(mut self, params: Map<String, Value>) -> Self {
+        self.params.extend(params);
+        self
+    }
+
+    /// Allow writes (`false`) or keep the query read-only (the default).
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Statement timeout; zero disables it.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Rows as JSON objects keyed by column name.
+    pub async fn fetch_all(&self) -> Result<Vec<Map<String, Value>>, DbError> {
+        let (sql, names) = named_to_positional(&self.sql)?;
+        let values = self.ordered_values(&names)?;
+
+        let mut tx = self.begin().await?;
+        let rows = bind_values(sqlx::query(&sql), &values).fetch_all(&mut *tx).await?;
+        self.finish(tx).await?;
+
+        rows.iter().map(row_to_json).collect()
+    }
+
+    /// Rows mapped with `FromRow`.
+    pub async fn fetch_all_as<T>(&self) -> Result<Vec<T>, DbError>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let (sql, names) = named_to_positional(&self.sql)?;
+        let values = self.ordered_values(&names)?;
+
+        let mut tx = self.begin().await?;
+        let rows = bind_values(sqlx::query(&sql), &values).fetch_all(&mut *tx).await?;
+        self.finish(tx).await?;
+
+        rows.iter().map(|row| T::from_row(row).map_err(DbError::from)).collect()
+    }
+
+    /// First row mapped with `FromRow`, if any.
+    pub async fn fetch_optional_as<T>(&self) -> Result<Option<T>, DbError>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let (sql, names) = named_to_positional(&self.sql)?;
+        let values = self.ordered_values(&names)?;
+
+        let mut tx = self.begin().await?;
+        let row = bind_values(sqlx::query(&sql), &values).fetch_optional(&mut *tx).await?;
+        self.finish(tx).await?;
+
+        row.map(|row| T::from_row(&row).map_err(DbError::from)).transpose()
+    }
+
+    /// Run a statement, returning the rows it affected. Needs
+    /// `read_only(false)` to change anything.
+    pub async fn execute(&self) -> Result<u64, DbError> {
+        let (sql, names) = named_to_positional(&self.sql)?;
+        let values = self.ordered_values(&names)?;
+
+        let mut tx = self.begin().await?;
+        let result = bind_values(sqlx::query(&sql), &values).execute(&mut *tx).await?;
+        self.finish(tx).await?;
+
+        Ok(result.rows_affected())
+    }
+
+    fn ordered_values(&self, names: &[String]) -> Result<Vec<Value>, DbError> {
+        if let Some(unused) = self.params.keys().find(|name| !names.contains(name)) {
+            return Err(DbError::InvalidData(format!(
+                "Parameter :{} is not used by the query",
+                unused
+            )));
+        }
+        names
+            .iter()
+            .map(|name| {
+                self.params.get(name).cloned().ok_or_else(|| {
+                    DbError::InvalidData(format!("Parameter :{} is not bound", name))
+                })
+            })
+            .collect()
+    }
+
+    async fn begin(&self) -> Result<sqlx::Transaction<'static, Postgres>, DbError> {
+        let mut tx = self.pool.begin().await?;
+        if self.read_only {
+            sqlx::query("SET TRANSACTION READ ONLY").execute(&mut *tx).await?;
+        }
+        if !self.timeout.is_zero() {
+            sqlx::query(&format!(
+                "SET LOCAL statement_timeout = {}",
+                self.timeout.as_millis()
+            ))
+            .execute(&mut *tx)
+            .await?;
+        }
+        Ok(tx)
+    }
+
+    async fn finish(&self, tx: sqlx::Transaction<'static, Postgres>) -> Result<(), DbError> {
+        if self.read_only {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Rewrite `:name` placeholders as `$1`, `$2`, ..., returning the SQL and
+/// the parameter name of each position.
+pub fn named_to_positional(sql: &str) -> Result<(String, Vec<String>), DbError> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut out = String::with_capacity(sql.len());
+    let mut names: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            // Quoted strings and identifiers; doubled quotes stay inside
+            '\'' | '"' => {
+                let mut end = i + 1;
+                loop {
+                    match chars.get(end) {
+                        None => {
+                            return Err(DbError::InvalidData(
+                                "Unterminated quote in query".to_string(),
+                            ))
+                        }
+                        Some(&q) if q == c && chars.get(end + 1) == Some(&c) => end += 2,
+                        Some(&q) if q == c => break,
+                        Some(_) => end += 1,
+                    }
+                }
+                out.extend(&chars[i..=end]);
+                i = end + 1;
+            }
+            '-' if next == Some('-') => {
+                let end = (i..chars.len()).find(|&j| chars[j] == '\n').unwrap_or(chars.len());
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            '/' if next == Some('*') => {
+                let end = (i + 2..chars.len().saturating_sub(1))
+                    .find(|&j| chars[j] == '*' && chars[j + 1] == '/')
+                    .map(|j| j + 2)
+                    .ok_or_else(|| DbError::InvalidData("Unterminated comment in query".to_string()))?;
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            // $tag$ ... $tag$ strings
+            '$' if !next.is_some_and(|n| n.is_ascii_digit()) => {
+                let tag_end = (i + 1..chars.len())
+                    .take_while(|&j| chars[j] == '$' || chars[j].is_alphanumeric() || chars[j] == '_')
+                    .find(|&j| chars[j] == '$');
+                let Some(tag_end) = tag_end else {
+                    out.push(c);
+                    i += 1;
+                    continue;
+                };
+                let tag: String = chars[i..=tag_end].iter().collect();
+                let rest: String = chars[tag_end + 1..].iter().collect();
+                let close = rest
+                    .find(&tag)
+                    .ok_or_else(|| DbError::InvalidData("Unterminated dollar quote in query".to_string()))?;
+                let body_len = rest[..close].chars().count();
+                let end = tag_end + 1 + body_len + tag.chars().count();
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            ':' if next == Some(':') => {
+                out.push_str("::");
+                i += 2;
+            }
+            ':' if next.is_some_and(|n| n.is_ascii_alphabetic() || n == '_') => {
+                let end = (i + 1..chars.len())
+                    .find(|&j| !(chars[j].is_ascii_alphanumeric() || chars[j] == '_'))
+                    .unwrap_or(chars.len());
+                let name: String = chars[i + 1..end].iter().collect();
+                let position = match names.iter().position(|n| *n == name) {
+                    Some(position) => position,
+                    None => {
+                        names.push(name);
+                        names.len() - 1
+                    }
+                };
+                out.push_str(&format!("${}", position + 1));
+                i = end;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    Ok((out, names))
+}
+
+fn bind_values<'q>(
+    mut query: Query<'q, Postgres, PgArguments>,
+    values: &[Value],
+) -> Query<'q, Postgres, PgArguments> {
+    for value in values {
+        query = match value {
+            Value::Null => query.bind(None::<String>),
+            Value::Bool(b) => query.bind(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => query.bind(i),
+                None => query.bind(n.as_f64()),
+            },
+            Value::String(s) => query.bind(s.clone()),
+            Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_string) => {
+                let strings: Vec<String> =
+                    items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect();
+                query.bind(strings)
+            }
+            Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_i64) => {
+                let ints: Vec<i64> = items.iter().filter_map(Value::as_i64).collect();
+                query.bind(ints)
+            }
+            other => query.bind(Json(other.clone())),
+        };
+    }
+    query
+}
+
+fn row_to_json(row: &PgRow) -> Result<Map<String, Value>, DbError> {
+    let mut object = Map::new();
+    for column in row.columns() {
+        let i = column.ordinal();
+        let value = match column.type_info().name() {
+            "BOOL" => json_of(row.try_get::<Option<bool>, _>(i)?),
+            "INT2" => json_of(row.try_get::<Option<i16>, _>(i)?),
+            "INT4" => json_of(row.try_get::<Option<i32>, _>(i)?),
+            "INT8" => json_of(row.try_get::<Option<i64>, _>(i)?),
+            "FLOAT4" => json_of(row.try_get::<Option<f32>, _>(i)?),
+            "FLOAT8" => json_of(row.try_get::<Option<f64>, _>(i)?),
+            "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" => json_of(row.try_get::<Option<String>, _>(i)?),
+            "UUID" => json_of(row.try_get::<Option<uuid::Uuid>, _>(i)?.map(|u| u.to_string())),
+            "TIMESTAMPTZ" => json_of(row.try_get::<Option<chrono::DateTime<chrono::Utc>>, _>(i)?),
+            "TIMESTAMP" => json_of(row.try_get::<Option<chrono::NaiveDateTime>, _>(i)?),
+            "DATE" => json_of(row.try_get::<Option<chrono::NaiveDate>, _>(i)?),
+            "JSON" | "JSONB" => row.try_get::<Option<Value>, _>(i)?.unwrap_or(Value::Null),
+            "TEXT[]" | "VARCHAR[]" => json_of(row.try_get::<Option<Vec<String>>, _>(i)?),
+            "INT4[]" => json_of(row.try_get::<Option<Vec<i32>>, _>(i)?),
+            "INT8[]" => json_of(row.try_get::<Option<Vec<i64>>, _>(i)?),
+            other => {
+                return Err(DbError::InvalidData(format!(
+                    "Column {} has type {} without a JSON mapping; cast it, e.g. {}::text",
+                    column.name(),
+                    other,
+                    column.name()
+                )))
+            }
+        };
+        object.insert(column.name().to_string(), value);
+    }
+    Ok(object)
+}
+
+fn json_of<T: serde::Serialize>(value: Option<T>) -> Value {
+    value
+        .and_then(|v| serde_json::to_value(v).ok())
+        .unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_parameters_become_positional() {
+        let (sql, names) = named_to_positional(
+            "SELECT id::text, ':skip' AS \"a:b\" -- :comment\n\
+             FROM execution_entity WHERE workflow_id = :wf AND status = ANY(:statuses) \
+             AND $$:dollar$$ <> 'it''s :x' AND workflow_id <> :wf /* :block */",
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            "SELECT id::text, ':skip' AS \"a:b\" -- :comment\n\
+             FROM execution_entity WHERE workflow_id = $1 AND status = ANY($2) \
+             AND $$:dollar$$ <> 'it''s :x' AND workflow_id <> $1 /* :block */"
+        );
+        assert_eq!(names, vec!["wf".to_string(), "statuses".to_string()]);
+
+        assert!(named_to_positional("SELECT 'open").is_err());
+    }
+}
@@ -0,0 +1,277 @@
+//! Annotation repository - execution annotations and annotation tags.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::entities::{
+    AnnotationInsights, AnnotationTagCount, AnnotationTagEntity, AnnotationUpdate,
+    ExecutionAnnotation,
+};
+use crate::error::DbError;
+
+/// Repository for execution annotation operations.
+#[derive(Clone)]
+pub struct AnnotationRepository {
+    pool: PgPool,
+}
+
+impl AnnotationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get the annotation of an execution, with its tags.
+    pub async fn find_by_execution(
+        &self,
+        execution_id: &str,
+    ) -> Result<Option<ExecutionAnnotation>, DbError> {
+        let annotation = sqlx::query_as::<_, ExecutionAnnotation>(
+            r#"
+            SELECT id, execution_id, vote, note, created_at, updated_at
+            FROM execution_annotations
+            WHERE execution_id = $1
+            "#,
+        )
+        .bind(execution_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match annotation {
+            Some(mut annotation) => {
+                annotation.tags = self.tags_of(annotation.id).await?;
+                Ok(Some(annotation))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Apply `update` to the annotation of an execution, creating it on
+    /// first use.
+    pub async fn annotate(
+        &self,
+        execution_id: &str,
+        update: &AnnotationUpdate,
+    ) -> Result<ExecutionAnnotation, DbError> {
+        let mut tx = self.pool.begin().await?;
+
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM execution_entity WHERE id = $1 AND deleted_at IS NULL)",
+        )
+        .bind(execution_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if !exists {
+            return Err(DbError::NotFound);
+        }
+
+        let current = sqlx::query_as::<_, ExecutionAnnotation>(
+            r#"
+            SELECT id, execution_id, vote, note, created_at, updated_at
+            FROM execution_annotations
+            WHERE execution_id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(execution_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let (vote, note) = update.apply(current.as_ref());
+
+        let mut annotation = sqlx::query_as::<_, ExecutionAnnotation>(
+            r#"
+            INSERT INTO execution_annotations (execution_id, vote, note)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (execution_id) DO UPDATE SET
+                vote = EXCLUDED.vote,
+                note = EXCLUDED.note,
+                updated_at = NOW()
+            RETURNING id, execution_id, vote, note, created_at, updated_at
+            "#,
+        )
+        .bind(execution_id)
+        .bind(&vote)
+        .bind(&note)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if let Some(tag_ids) = &update.tags {
+            let known = sqlx::query_scalar::<_, String>(
+                "SELECT id FROM annotation_tag_entity WHERE id = ANY($1)",
+            )
+            .bind(tag_ids)
+            .fetch_all(&mut *tx)
+            .await?;
+            if let Some(unknown) = tag_ids.iter().find(|id| !known.contains(id)) {
+                return Err(DbError::InvalidData(format!(
+                    "Unknown annotation tag {}",
+                    unknown
+                )));
+            }
+
+            sqlx::query("DELETE FROM execution_annotation_tags WHERE annotation_id = $1")
+                .bind(annotation.id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                r#"
+                INSERT INTO execution_annotation_tags (annotation_id, tag_id)
+                SELECT $1, UNNEST($2::varchar[])
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(annotation.id)
+            .bind(tag_ids)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        annotation.tags = self.tags_of(annotation.id).await?;
+        Ok(annotation)
+    }
+
+    /// Remove the annotation of an execution.
+    pub async fn delete(&self, execution_id: &str) -> Result<bool, DbError> {
+        let result = sqlx::query("DELETE FROM execution_annotations WHERE execution_id = $1")
+            .bind(execution_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn tags_of(&self, annotation_id: i32) -> Result<Vec<AnnotationTagEntity>, DbError> {
+        let tags = sqlx::query_as::<_, AnnotationTagEntity>(
+            r#"
+            SELECT t.id, t.name, t.created_at, t.updated_at
+            FROM annotation_tag_entity t
+            JOIN execution_annotation_tags m ON m.tag_id = t.id
+            WHERE m.annotation_id = $1
+            ORDER BY t.name ASC
+            "#,
+        )
+        .bind(annotation_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    // =========================================================================
+    // Annotation Tags
+    // =========================================================================
+
+    /// List all annotation tags.
+    pub async fn find_all_tags(&self) -> Result<Vec<AnnotationTagEntity>, DbError> {
+        let tags = sqlx::query_as::<_, AnnotationTagEntity>(
+            "SELECT id, name, created_at, updated_at FROM annotation_tag_entity ORDER BY name ASC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Create an annotation tag.
+    pub async fn create_tag(&self, name: &str) -> Result<AnnotationTagEntity, DbError> {
+        AnnotationTagEntity::validate_name(name).map_err(DbError::InvalidData)?;
+        let tag = AnnotationTagEntity::new(name.trim());
+
+        let created = sqlx::query_as::<_, AnnotationTagEntity>(
+            r#"
+            INSERT INTO annotation_tag_entity (id, name)
+            VALUES ($1, $2)
+            RETURNING id, name, created_at, updated_at
+            "#,
+        )
+        .bind(&tag.id)
+        .bind(&tag.name)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                DbError::DuplicateKey(format!("Annotation tag '{}' already exists", tag.name))
+            }
+            e => e.into(),
+        })?;
+
+        Ok(created)
+    }
+
+    /// Delete an annotation tag, removing it from every annotation.
+    pub async fn delete_tag(&self, id: &str) -> Result<bool, DbError> {
+        let result = sqlx::query("DELETE FROM annotation_tag_entity WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // =========================================================================
+    // Insights
+    // =========================================================================
+
+    /// Annotation counts of executions started since `since`, of one
+    /// workflow or all.
+    pub async fn insights(
+        &self,
+        workflow_id: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<AnnotationInsights, DbError> {
+        let scope = r#"
+            e.deleted_at IS NULL
+            AND ($1::varchar IS NULL OR e.workflow_id = $1)
+            AND ($2::timestamptz IS NULL OR e.started_at >= $2)
+        "#;
+
+        let (executions, annotated, up_votes, down_votes, down_voted_successes) =
+            sqlx::query_as::<_, (i64, i64, i64, i64, i64)>(&format!(
+                r#"
+                SELECT COUNT(*),
+                       COUNT(a.id),
+                       COUNT(*) FILTER (WHERE a.vote = 'up'),
+                       COUNT(*) FILTER (WHERE a.vote = 'down'),
+                       COUNT(*) FILTER (WHERE a.vote = 'down' AND e.status = 'success')
+                FROM execution_entity e
+                LEFT JOIN execution_annotations a ON a.execution_id = e.id
+                WHERE {}
+                "#,
+                scope
+            ))
+            .bind(workflow_id)
+            .bind(since)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let tags = sqlx::query_as::<_, AnnotationTagCount>(&format!(
+            r#"
+            SELECT t.id, t.name,
+                   COUNT(*) AS executions,
+                   COUNT(*) FILTER (WHERE a.vote = 'up') AS up_votes,
+                   COUNT(*) FILTER (WHERE a.vote = 'down') AS down_votes
+            FROM execution_entity e
+            JOIN execution_annotations a ON a.execution_id = e.id
+            JOIN execution_annotation_tags m ON m.annotation_id = a.id
+            JOIN annotation_tag_entity t ON t.id = m.tag_id
+            WHERE {}
+            GROUP BY t.id, t.name
+            ORDER BY executions DESC, t.name ASC
+            "#,
+            scope
+        ))
+        .bind(workflow_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(AnnotationInsights {
+            executions,
+            annotated,
+            up_votes,
+            down_votes,
+            down_voted_successes,
+            tags,
+        })
+    }
+}
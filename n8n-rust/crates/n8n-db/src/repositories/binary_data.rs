@@ -0,0 +1,139 @@
+//! Binary data repository - references to stored binary payloads.
+
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+
+use crate::entities::BinaryDataEntity;
+use crate::error::DbError;
+
+const BINARY_DATA_COLUMNS: &str =
+    "id, mode, execution_id, workflow_id, mime_type, file_name, file_size, created_at";
+
+/// Content-addressed blobs and what deduplication saved. A blob with `n`
+/// references would have been stored `n` times without it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobStats {
+    /// Blobs in the store, referenced or awaiting collection.
+    pub blobs: i64,
+    /// Blobs no execution refers to.
+    pub unreferenced_blobs: i64,
+    /// References of all blobs.
+    pub references: i64,
+    /// Bytes the blobs take.
+    pub stored_bytes: i64,
+    /// Bytes the references would take if each had its own copy.
+    pub referenced_bytes: i64,
+    /// `referenced_bytes` not written thanks to deduplication.
+    pub saved_bytes: i64,
+}
+
+/// Repository for binary data references.
+#[derive(Clone)]
+pub struct BinaryDataRepository {
+    pool: PgPool,
+}
+
+impl BinaryDataRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a reference by ID (`{mode}:{key}`). A content-addressed blob
+    /// may be referenced by several executions; the first is returned.
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<BinaryDataEntity>, DbError> {
+        let entry = sqlx::query_as::<_, BinaryDataEntity>(&format!(
+            "SELECT {} FROM binary_data WHERE id = $1 ORDER BY created_at LIMIT 1",
+            BINARY_DATA_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// References of an execution's payloads.
+    pub async fn find_by_execution(
+        &self,
+        execution_id: &str,
+    ) -> Result<Vec<BinaryDataEntity>, DbError> {
+        let entries = sqlx::query_as::<_, BinaryDataEntity>(&format!(
+            "SELECT {} FROM binary_data WHERE execution_id = $1 ORDER BY created_at, id",
+            BINARY_DATA_COLUMNS
+        ))
+        .bind(execution_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Record references; ones already recorded for the same execution are
+    /// kept as they are. Each new reference to a content-addressed blob
+    /// adds to its reference count.
+    pub async fn record(&self, entries: &[BinaryDataEntity]) -> Result<(), DbError> {
+        let mut tx = self.pool.begin().await?;
+        for entry in entries {
+            sqlx::query(
+                r#"
+                INSERT INTO binary_data
+                    (id, mode, execution_id, workflow_id, mime_type, file_name, file_size, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (id, execution_id) DO NOTHING
+                "#,
+            )
+            .bind(&entry.id)
+            .bind(&entry.mode)
+            .bind(&entry.execution_id)
+            .bind(&entry.workflow_id)
+            .bind(&entry.mime_type)
+            .bind(&entry.file_name)
+            .bind(entry.file_size)
+            .bind(entry.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Remove the references of an execution, returning them so their
+    /// payloads can be deleted from the store. Content-addressed blobs are
+    /// only released; the garbage collector deletes them once unreferenced.
+    pub async fn delete_by_execution(
+        &self,
+        execution_id: &str,
+    ) -> Result<Vec<BinaryDataEntity>, DbError> {
+        let entries = sqlx::query_as::<_, BinaryDataEntity>(&format!(
+            "DELETE FROM binary_data WHERE execution_id = $1 RETURNING {}",
+            BINARY_DATA_COLUMNS
+        ))
+        .bind(execution_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Totals of the content-addressed blobs.
+    pub async fn blob_stats(&self) -> Result<BlobStats, DbError> {
+        let stats = sqlx::query_as::<_, BlobStats>(
+            r#"
+            SELECT
+                COUNT(*) AS blobs,
+                COUNT(*) FILTER (WHERE ref_count = 0) AS unreferenced_blobs,
+                COALESCE(SUM(ref_count), 0)::BIGINT AS "references",
+                COALESCE(SUM(file_size), 0)::BIGINT AS stored_bytes,
+                COALESCE(SUM(file_size * ref_count), 0)::BIGINT AS referenced_bytes,
+                COALESCE(SUM(file_size * GREATEST(ref_count - 1, 0)), 0)::BIGINT AS saved_bytes
+            FROM binary_blob
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+}
@@ -1,10 +1,22 @@
 //! Credentials repository - CRUD operations for credentials.
+//!
+//! With an encryption service, `data` is encrypted when written and
+//! decrypted when read, so callers only see plaintext credential data.
+//!
+//! [`CredentialsRepository::export`] and [`CredentialsRepository::import`]
+//! move credentials between instances, e.g. from staging to production:
+//! the bundle in between is encrypted with a transfer key both sides are
+//! given, and the importing instance re-encrypts with its own key.
 
-use sqlx::PgPool;
+use chrono::Utc;
+use n8n_core::{CredentialError, CredentialService};
+use sqlx::{PgConnection, PgPool};
 
 use crate::entities::{
-    CredentialFilters, CredentialSharingRole, CredentialsEntity, InsertCredentials,
-    SharedCredentials, UpdateCredentials,
+    generate_nano_id, CredentialBundle, CredentialFilters, CredentialSharingRole,
+    CredentialsEntity, ExportedCredential, ImportAction, ImportIds, ImportOptions,
+    ImportReport, ImportedCredential, InsertCredentials, SharedCredentials, UpdateCredentials,
+    CREDENTIAL_BUNDLE_VERSION,
 };
 use crate::error::DbError;
 
@@ -12,11 +24,40 @@ use crate::error::DbError;
 #[derive(Clone)]
 pub struct CredentialsRepository {
     pool: PgPool,
+    /// Encrypts `data` on insert and update and decrypts it on fetch.
+    encryption: Option<CredentialService>,
 }
 
 impl CredentialsRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            encryption: None,
+        }
+    }
+
+    /// Encrypt credential data with `encryption`; without it `data` is
+    /// stored as given.
+    pub fn with_encryption(mut self, encryption: CredentialService) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    fn encrypt(&self, data: &str) -> Result<String, DbError> {
+        match &self.encryption {
+            Some(encryption) => encryption.encrypt_string(data).map_err(encryption_failed),
+            None => Ok(data.to_string()),
+        }
+    }
+
+    fn decrypt(&self, mut creds: CredentialsEntity) -> Result<CredentialsEntity, DbError> {
+        if let Some(encryption) = &self.encryption {
+            // Registers the values for secret masking.
+            let data =
+                encryption.decrypt(&creds.data).map_err(|e| decryption_failed(&creds.id, e))?;
+            creds.data = data.to_string();
+        }
+        Ok(creds)
     }
 
     /// Get credentials by ID.
@@ -33,7 +74,7 @@ impl CredentialsRepository {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(creds)
+        creds.map(|creds| self.decrypt(creds)).transpose()
     }
 
     /// List credentials with filters.
@@ -58,7 +99,7 @@ impl CredentialsRepository {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(creds)
+        creds.into_iter().map(|creds| self.decrypt(creds)).collect()
     }
 
     /// List credentials by type.
@@ -76,7 +117,7 @@ impl CredentialsRepository {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(creds)
+        creds.into_iter().map(|creds| self.decrypt(creds)).collect()
     }
 
     /// Create new credentials.
@@ -92,11 +133,60 @@ impl CredentialsRepository {
         .bind(&creds.id)
         .bind(&creds.name)
         .bind(&creds.credential_type)
-        .bind(&creds.data)
+        .bind(self.encrypt(&creds.data)?)
         .fetch_one(&self.pool)
         .await?;
 
-        Ok(created)
+        self.decrypt(created)
+    }
+
+    /// Insert `creds` in one statement. Returns how many were inserted.
+    pub async fn insert_many(&self, creds: &[InsertCredentials]) -> Result<u64, DbError> {
+        self.bulk_write(creds, "").await
+    }
+
+    /// Insert `creds`, replacing the name, type and data of those whose ID
+    /// exists, in one statement. Returns how many were inserted or replaced.
+    pub async fn upsert_many(&self, creds: &[InsertCredentials]) -> Result<u64, DbError> {
+        let on_conflict = r#"
+            ON CONFLICT (id) DO UPDATE
+            SET name = EXCLUDED.name, type = EXCLUDED.type, data = EXCLUDED.data,
+                updated_at = NOW()
+        "#;
+        self.bulk_write(creds, on_conflict).await
+    }
+
+    /// Write `creds` with one `INSERT ... SELECT FROM UNNEST`. All data is
+    /// encrypted before anything is written.
+    async fn bulk_write(
+        &self,
+        creds: &[InsertCredentials],
+        on_conflict: &str,
+    ) -> Result<u64, DbError> {
+        if creds.is_empty() {
+            return Ok(0);
+        }
+        let data = creds
+            .iter()
+            .map(|c| self.encrypt(&c.data))
+            .collect::<Result<Vec<_>, DbError>>()?;
+        let sql = format!(
+            r#"
+            INSERT INTO credentials_entity (id, name, type, data)
+            SELECT * FROM UNNEST($1::varchar[], $2::varchar[], $3::varchar[], $4::text[])
+            {}
+            "#,
+            on_conflict
+        );
+        let result = sqlx::query(&sql)
+            .bind(creds.iter().map(|c| c.id.as_str()).collect::<Vec<_>>())
+            .bind(creds.iter().map(|c| c.name.as_str()).collect::<Vec<_>>())
+            .bind(creds.iter().map(|c| c.credential_type.as_str()).collect::<Vec<_>>())
+            .bind(data)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
     }
 
     /// Update credentials.
@@ -116,13 +206,13 @@ impl CredentialsRepository {
         )
         .bind(id)
         .bind(&update.name)
-        .bind(&update.data)
+        .bind(update.data.as_deref().map(|data| self.encrypt(data)).transpose()?)
         .bind(update.is_managed)
         .bind(update.is_global)
         .fetch_one(&self.pool)
         .await?;
 
-        Ok(updated)
+        self.decrypt(updated)
     }
 
     /// Delete credentials.
@@ -135,6 +225,202 @@ impl CredentialsRepository {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Re-encrypt the data of every credential from `from` to `to`, in one
+    /// transaction. Credentials `to` already decrypts are left as they are,
+    /// so an interrupted rotation can be run again. Returns how many were
+    /// re-encrypted.
+    pub async fn rotate_key(
+        &self,
+        from: &CredentialService,
+        to: &CredentialService,
+    ) -> Result<u64, DbError> {
+        let mut tx = self.pool.begin().await?;
+        let rotated = Self::rotate_key_in(&mut tx, from, to).await?;
+        tx.commit().await?;
+        Ok(rotated)
+    }
+
+    /// [`Self::rotate_key`] on `conn`, which should be in a transaction.
+    pub async fn rotate_key_in(
+        conn: &mut PgConnection,
+        from: &CredentialService,
+        to: &CredentialService,
+    ) -> Result<u64, DbError> {
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, data FROM credentials_entity ORDER BY id FOR UPDATE")
+                .fetch_all(&mut *conn)
+                .await?;
+
+        let mut rotated = 0;
+        for (id, data) in rows {
+            if to.decrypt_string(&data).is_ok() {
+                continue;
+            }
+            let plaintext = from.decrypt_string(&data).map_err(|e| decryption_failed(&id, e))?;
+            let encrypted = to.encrypt_string(&plaintext).map_err(encryption_failed)?;
+            sqlx::query("UPDATE credentials_entity SET data = $2 WHERE id = $1")
+                .bind(&id)
+                .bind(encrypted)
+                .execute(&mut *conn)
+                .await?;
+            rotated += 1;
+        }
+
+        Ok(rotated)
+    }
+
+    // =========================================================================
+    // Export and import
+    // =========================================================================
+
+    /// Export the credentials `ids`, or all when empty, with their data
+    /// encrypted with `transfer` instead of this instance's key.
+    pub async fn export(
+        &self,
+        ids: &[String],
+        transfer: &CredentialService,
+    ) -> Result<CredentialBundle, DbError> {
+        let rows = sqlx::query_as::<_, CredentialsEntity>(
+            r#"
+            SELECT id, name, type as credential_type, data, is_managed, is_global,
+                   is_resolvable, resolvable_allow_fallback, resolver_id, created_at, updated_at
+            FROM credentials_entity
+            WHERE cardinality($1::text[]) = 0 OR id = ANY($1)
+            ORDER BY id
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let missing: Vec<&str> = ids
+            .iter()
+            .filter(|id| !rows.iter().any(|row| &row.id == *id))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(DbError::InvalidData(format!(
+                "Unknown credentials: {}",
+                missing.join(", ")
+            )));
+        }
+
+        let credentials = rows
+            .into_iter()
+            .map(|row| {
+                let row = self.decrypt(row)?;
+                Ok(ExportedCredential {
+                    data: transfer.encrypt_string(&row.data).map_err(encryption_failed)?,
+                    id: row.id,
+                    name: row.name,
+                    credential_type: row.credential_type,
+                    is_managed: row.is_managed,
+                    is_global: row.is_global,
+                })
+            })
+            .collect::<Result<_, DbError>>()?;
+
+        Ok(CredentialBundle {
+            version: CREDENTIAL_BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            credentials,
+        })
+    }
+
+    /// Import `bundle`, decrypting with `transfer` and storing with this
+    /// instance's key, in one transaction. Every credential is decrypted
+    /// before anything is written, so a wrong transfer key changes nothing.
+    pub async fn import(
+        &self,
+        bundle: &CredentialBundle,
+        transfer: &CredentialService,
+        options: &ImportOptions,
+    ) -> Result<ImportReport, DbError> {
+        if bundle.version != CREDENTIAL_BUNDLE_VERSION {
+            return Err(DbError::InvalidData(format!(
+                "Unsupported credential bundle version {}",
+                bundle.version
+            )));
+        }
+        let data = bundle
+            .credentials
+            .iter()
+            .map(|credential| {
+                let plaintext = transfer
+                    .decrypt_string(&credential.data)
+                    .map_err(|e| decryption_failed(&credential.id, e))?;
+                self.encrypt(&plaintext)
+            })
+            .collect::<Result<Vec<_>, DbError>>()?;
+
+        let mut tx = self.pool.begin().await?;
+        let mut imported = Vec::with_capacity(data.len());
+        for (credential, data) in bundle.credentials.iter().zip(data) {
+            let id = match options.ids {
+                ImportIds::Preserve => credential.id.clone(),
+                ImportIds::Remap => generate_nano_id(),
+            };
+            let created: bool = sqlx::query_scalar(
+                r#"
+                INSERT INTO credentials_entity (id, name, type, data, is_managed, is_global)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (id) DO UPDATE
+                SET name = EXCLUDED.name, type = EXCLUDED.type, data = EXCLUDED.data,
+                    is_managed = EXCLUDED.is_managed, is_global = EXCLUDED.is_global,
+                    updated_at = NOW()
+                RETURNING (xmax = 0)
+                "#,
+            )
+            .bind(&id)
+            .bind(&credential.name)
+            .bind(&credential.credential_type)
+            .bind(data)
+            .bind(credential.is_managed)
+            .bind(credential.is_global)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            if let Some(project_id) = &options.project_id {
+                sqlx::query(
+                    r#"
+                    INSERT INTO shared_credentials (credentials_id, project_id, role)
+                    VALUES ($1, $2, 'credential:owner')
+                    ON CONFLICT (credentials_id, project_id) DO NOTHING
+                    "#,
+                )
+                .bind(&id)
+                .bind(project_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            imported.push(ImportedCredential {
+                source_id: credential.id.clone(),
+                id,
+                name: credential.name.clone(),
+                action: if created {
+                    ImportAction::Created
+                } else {
+                    ImportAction::Updated
+                },
+            });
+        }
+
+        // A dry run makes the same changes, to report them, and undoes them.
+        if options.dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+
+        Ok(ImportReport {
+            applied: !options.dry_run,
+            dry_run: options.dry_run,
+            ids: options.ids,
+            credentials: imported,
+        })
+    }
+
     // =========================================================================
     // Sharing
     // =========================================================================
@@ -197,3 +483,11 @@ impl CredentialsRepository {
         Ok(shared)
     }
 }
+
+fn encryption_failed(e: CredentialError) -> DbError {
+    DbError::InvalidData(format!("Failed to encrypt credentials: {}", e))
+}
+
+fn decryption_failed(id: &str, e: CredentialError) -> DbError {
+    DbError::InvalidData(format!("Credentials {} could not be decrypted: {}", id, e))
+}
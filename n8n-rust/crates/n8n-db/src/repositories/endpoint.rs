@@ -0,0 +1,102 @@
+//! Workflow endpoint repository - published REST endpoints and the API key
+//! scopes that authorize calling them.
+
+use sqlx::PgPool;
+
+use crate::entities::{EndpointEntity, InsertEndpoint};
+use crate::error::DbError;
+
+const ENDPOINT_COLUMNS: &str = "id, workflow_id, node, method, path, summary, request_schema, response_schema, scopes, created_at, updated_at";
+
+/// Repository for workflow endpoint operations.
+#[derive(Clone)]
+pub struct EndpointRepository {
+    pool: PgPool,
+}
+
+impl EndpointRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List all endpoints, ordered by path.
+    pub async fn find_all(&self) -> Result<Vec<EndpointEntity>, DbError> {
+        let endpoints = sqlx::query_as::<_, EndpointEntity>(&format!(
+            "SELECT {} FROM workflow_endpoint ORDER BY path, method",
+            ENDPOINT_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(endpoints)
+    }
+
+    /// Find endpoints for a workflow.
+    pub async fn find_by_workflow(&self, workflow_id: &str) -> Result<Vec<EndpointEntity>, DbError> {
+        let endpoints = sqlx::query_as::<_, EndpointEntity>(&format!(
+            "SELECT {} FROM workflow_endpoint WHERE workflow_id = $1 ORDER BY path, method",
+            ENDPOINT_COLUMNS
+        ))
+        .bind(workflow_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(endpoints)
+    }
+
+    /// Create an endpoint, replacing the one on the same method and path.
+    pub async fn upsert(&self, endpoint: &InsertEndpoint) -> Result<EndpointEntity, DbError> {
+        let saved = sqlx::query_as::<_, EndpointEntity>(&format!(
+            r#"
+            INSERT INTO workflow_endpoint
+                (id, workflow_id, node, method, path, summary, request_schema, response_schema, scopes)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (method, path) DO UPDATE SET
+                workflow_id = EXCLUDED.workflow_id,
+                node = EXCLUDED.node,
+                summary = EXCLUDED.summary,
+                request_schema = EXCLUDED.request_schema,
+                response_schema = EXCLUDED.response_schema,
+                scopes = EXCLUDED.scopes,
+                updated_at = NOW()
+            RETURNING {}
+            "#,
+            ENDPOINT_COLUMNS
+        ))
+        .bind(&endpoint.id)
+        .bind(&endpoint.workflow_id)
+        .bind(&endpoint.node)
+        .bind(&endpoint.method)
+        .bind(&endpoint.path)
+        .bind(&endpoint.summary)
+        .bind(&endpoint.request_schema)
+        .bind(&endpoint.response_schema)
+        .bind(&endpoint.scopes)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(saved)
+    }
+
+    /// Delete an endpoint.
+    pub async fn delete(&self, id: &str) -> Result<bool, DbError> {
+        let result = sqlx::query("DELETE FROM workflow_endpoint WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Scopes of an API key, or `None` if the key does not exist.
+    pub async fn api_key_scopes(&self, api_key: &str) -> Result<Option<Vec<String>>, DbError> {
+        let scopes = sqlx::query_scalar::<_, Vec<String>>(
+            "SELECT scopes FROM api_key WHERE api_key = $1",
+        )
+        .bind(api_key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(scopes)
+    }
+}
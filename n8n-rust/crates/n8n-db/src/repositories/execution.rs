@@ -1,24 +1,83 @@
 //! Execution repository - CRUD operations for executions.
 
-use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use chrono::{DateTime, Duration, Utc};
+use futures::{Stream, TryStreamExt};
+use sqlx::{FromRow, PgPool};
 
+use crate::compression::{decompress, DataCompressionConfig, FORMAT_JSON};
 use crate::entities::{
-    ExecutionData, ExecutionEntity, ExecutionFilters, ExecutionMetadata,
-    ExecutionWithData, InsertExecution, UpdateExecution,
+    ExecutionCursor, ExecutionData, ExecutionEntity, ExecutionFilters, ExecutionMetadata,
+    ExecutionPage, ExecutionWithData, InsertExecution, UpdateExecution, WorkflowResourceUsage,
 };
 use crate::error::DbError;
+use crate::query::{ExecutionQuery, QueryParam};
+use n8n_core::{CostRecord, CostReport, ResourceUsage};
 use n8n_workflow::ExecutionStatus;
 
+/// What a cost summary aggregates over.
+#[derive(Debug, Clone)]
+pub enum CostScope {
+    Execution(String),
+    Workflow(String),
+    Project(String),
+}
+
+/// An `execution_data` row as stored, before decompression.
+#[derive(FromRow)]
+struct StoredExecutionData {
+    execution_id: String,
+    data: String,
+    data_format: String,
+    data_compressed: Option<Vec<u8>>,
+    #[sqlx(json)]
+    workflow_data: serde_json::Value,
+    workflow_version_id: Option<String>,
+}
+
+impl TryFrom<StoredExecutionData> for ExecutionData {
+    type Error = DbError;
+
+    fn try_from(row: StoredExecutionData) -> Result<Self, DbError> {
+        let data = match (row.data_format.as_str(), row.data_compressed) {
+            (FORMAT_JSON, _) => row.data,
+            (format, Some(compressed)) => decompress(format, &compressed)?,
+            (format, None) => {
+                return Err(DbError::InvalidData(format!(
+                    "Execution data of {} is {} without compressed data",
+                    row.execution_id, format
+                )))
+            }
+        };
+        Ok(ExecutionData {
+            execution_id: row.execution_id,
+            data,
+            workflow_data: row.workflow_data,
+            workflow_version_id: row.workflow_version_id,
+        })
+    }
+}
+
 /// Repository for execution operations.
 #[derive(Clone)]
 pub struct ExecutionRepository {
     pool: PgPool,
+    /// Compresses large execution data on save; see [`crate::compression`].
+    compression: DataCompressionConfig,
 }
 
 impl ExecutionRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            compression: DataCompressionConfig::default(),
+        }
+    }
+
+    /// Compress execution data as `compression` says; without it data is
+    /// stored as text.
+    pub fn with_compression(mut self, compression: DataCompressionConfig) -> Self {
+        self.compression = compression;
+        self
     }
 
     /// Get an execution by ID.
@@ -58,53 +117,77 @@ impl ExecutionRepository {
 
     /// List executions with filters.
     pub async fn find_all(&self, filters: &ExecutionFilters) -> Result<Vec<ExecutionEntity>, DbError> {
-        let mut conditions = vec!["1=1".to_string()];
-        let mut param_idx = 1;
+        self.query(&ExecutionQuery::from(filters)).await
+    }
 
-        if !filters.include_deleted {
-            conditions.push("deleted_at IS NULL".to_string());
+    /// Executions matching `query`, in one parameterized statement.
+    pub async fn query(&self, query: &ExecutionQuery) -> Result<Vec<ExecutionEntity>, DbError> {
+        let (sql, params) = query.build();
+        let mut query = sqlx::query_as::<_, ExecutionEntity>(&sql);
+        for param in params {
+            query = match param {
+                QueryParam::Bool(value) => query.bind(value),
+                QueryParam::Int(value) => query.bind(value),
+                QueryParam::Text(value) => query.bind(value),
+                QueryParam::TextArray(values) => query.bind(values),
+                QueryParam::Timestamp(at) => query.bind(at),
+                QueryParam::Jsonb(value) => query.bind(value),
+            };
         }
+        Ok(query.fetch_all(&self.pool).await?)
+    }
 
-        if filters.workflow_id.is_some() {
-            conditions.push(format!("workflow_id = ${}", param_idx));
-            param_idx += 1;
+    /// One page of executions matching `filters`, newest first, starting
+    /// after `cursor` (from the first page without one). Unlike offsets,
+    /// cursors cost the same on every page and do not skip or repeat rows
+    /// when executions are added meanwhile. `filters.limit` and
+    /// `filters.offset` are ignored.
+    pub async fn find_page(
+        &self,
+        filters: &ExecutionFilters,
+        cursor: Option<&ExecutionCursor>,
+        limit: i64,
+    ) -> Result<ExecutionPage, DbError> {
+        let limit = limit.max(1);
+        let mut query = ExecutionQuery::from(filters).offset(0);
+        if let Some(cursor) = cursor {
+            query = query.after_cursor(cursor.clone());
         }
+        // One more than asked tells whether another page follows
+        let mut executions = self.query(&query.limit(limit + 1)).await?;
 
-        if filters.finished.is_some() {
-            conditions.push(format!("finished = ${}", param_idx));
-            param_idx += 1;
-        }
+        let next_cursor = if executions.len() as i64 > limit {
+            executions.truncate(limit as usize);
+            executions.last().map(ExecutionCursor::after)
+        } else {
+            None
+        };
+        Ok(ExecutionPage {
+            executions,
+            next_cursor,
+        })
+    }
 
-        let query = format!(
+    /// Every execution not deleted, of `workflow_id` or of all workflows,
+    /// newest first, read row by row from one query so memory stays flat
+    /// however many there are. The stream holds a pooled connection until
+    /// it is dropped.
+    pub fn stream(
+        &self,
+        workflow_id: Option<&str>,
+    ) -> impl Stream<Item = Result<ExecutionEntity, DbError>> + Send + '_ {
+        sqlx::query_as::<_, ExecutionEntity>(
             r#"
             SELECT id, finished, mode, status, created_at, started_at, stopped_at,
                    deleted_at, workflow_id, retry_of, retry_success_id, wait_till, stored_at
             FROM execution_entity
-            WHERE {}
-            ORDER BY created_at DESC
-            LIMIT ${}
-            OFFSET ${}
+            WHERE deleted_at IS NULL AND ($1::varchar IS NULL OR workflow_id = $1)
+            ORDER BY created_at DESC, id DESC
             "#,
-            conditions.join(" AND "),
-            param_idx,
-            param_idx + 1
-        );
-
-        let mut query = sqlx::query_as::<_, ExecutionEntity>(&query);
-
-        if let Some(ref workflow_id) = filters.workflow_id {
-            query = query.bind(workflow_id);
-        }
-        if let Some(finished) = filters.finished {
-            query = query.bind(finished);
-        }
-
-        query = query
-            .bind(filters.limit.unwrap_or(100))
-            .bind(filters.offset.unwrap_or(0));
-
-        let executions = query.fetch_all(&self.pool).await?;
-        Ok(executions)
+        )
+        .bind(workflow_id.map(str::to_string))
+        .fetch(&self.pool)
+        .map_err(DbError::from)
     }
 
     /// List executions for a workflow.
@@ -325,15 +408,100 @@ impl ExecutionRepository {
         Ok(result.rows_affected())
     }
 
+    // =========================================================================
+    // Queue claims
+    // =========================================================================
+
+    /// Claim the oldest queued execution for `worker_id`, holding it for
+    /// `lease`, and mark it running. Rows locked by a concurrent claim are
+    /// skipped, so workers never claim the same execution, and running
+    /// executions whose lease expired (their worker crashed) are claimable
+    /// again. `None` when nothing is queued.
+    pub async fn claim_next_pending(
+        &self,
+        worker_id: &str,
+        lease: Duration,
+    ) -> Result<Option<ExecutionEntity>, DbError> {
+        let claimed = sqlx::query_as::<_, ExecutionEntity>(
+            r#"
+            UPDATE execution_entity
+            SET status = 'running', started_at = NOW(),
+                claimed_by = $1, lease_until = NOW() + $2
+            WHERE id = (
+                SELECT id FROM execution_entity
+                WHERE deleted_at IS NULL
+                  AND (status = 'new' OR (status = 'running' AND lease_until < NOW()))
+                ORDER BY created_at, id
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, finished, mode, status, created_at, started_at, stopped_at,
+                      deleted_at, workflow_id, retry_of, retry_success_id, wait_till, stored_at,
+                      claimed_by, lease_until
+            "#,
+        )
+        .bind(worker_id)
+        .bind(lease)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(claimed)
+    }
+
+    /// Renew `worker_id`'s claim on a running execution for another
+    /// `lease`. Returns `false` when the claim was lost, e.g. the lease
+    /// expired and another worker took the execution; the worker should
+    /// then stop running it.
+    pub async fn heartbeat(
+        &self,
+        id: &str,
+        worker_id: &str,
+        lease: Duration,
+    ) -> Result<bool, DbError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE execution_entity
+            SET lease_until = NOW() + $3
+            WHERE id = $1 AND claimed_by = $2 AND status = 'running'
+            "#,
+        )
+        .bind(id)
+        .bind(worker_id)
+        .bind(lease)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Put an execution `worker_id` claimed but will not run, e.g. when
+    /// shutting down, back in the queue.
+    pub async fn release_claim(&self, id: &str, worker_id: &str) -> Result<bool, DbError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE execution_entity
+            SET status = 'new', started_at = NULL, claimed_by = NULL, lease_until = NULL
+            WHERE id = $1 AND claimed_by = $2 AND status = 'running'
+            "#,
+        )
+        .bind(id)
+        .bind(worker_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     // =========================================================================
     // Execution Data
     // =========================================================================
 
-    /// Get execution data.
+    /// Get execution data, decompressed.
     pub async fn get_data(&self, execution_id: &str) -> Result<Option<ExecutionData>, DbError> {
-        let data = sqlx::query_as::<_, ExecutionData>(
+        let row = sqlx::query_as::<_, StoredExecutionData>(
             r#"
-            SELECT execution_id, data, workflow_data, workflow_version_id
+            SELECT execution_id, data, data_format, data_compressed, workflow_data,
+                   workflow_version_id
             FROM execution_data
             WHERE execution_id = $1
             "#,
@@ -342,23 +510,33 @@ impl ExecutionRepository {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(data)
+        row.map(ExecutionData::try_from).transpose()
     }
 
-    /// Save execution data.
+    /// Save execution data, compressed if it is large enough.
     pub async fn save_data(&self, data: &ExecutionData) -> Result<(), DbError> {
+        let (text, format, compressed) = match self.compression.compress(&data.data)? {
+            Some((format, compressed)) => ("", format, Some(compressed)),
+            None => (data.data.as_str(), FORMAT_JSON, None),
+        };
         sqlx::query(
             r#"
-            INSERT INTO execution_data (execution_id, data, workflow_data, workflow_version_id)
-            VALUES ($1, $2, $3, $4)
+            INSERT INTO execution_data
+                (execution_id, data, data_format, data_compressed, workflow_data,
+                 workflow_version_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
             ON CONFLICT (execution_id) DO UPDATE SET
                 data = EXCLUDED.data,
+                data_format = EXCLUDED.data_format,
+                data_compressed = EXCLUDED.data_compressed,
                 workflow_data = EXCLUDED.workflow_data,
                 workflow_version_id = EXCLUDED.workflow_version_id
             "#,
         )
         .bind(&data.execution_id)
-        .bind(&data.data)
+        .bind(text)
+        .bind(format)
+        .bind(compressed)
         .bind(&data.workflow_data)
         .bind(&data.workflow_version_id)
         .execute(&self.pool)
@@ -401,6 +579,180 @@ impl ExecutionRepository {
         Ok(metadata)
     }
 
+    // =========================================================================
+    // Execution Cost
+    // =========================================================================
+
+    /// Replace the cost lines of an execution.
+    pub async fn save_costs(&self, execution_id: &str, records: &[CostRecord]) -> Result<(), DbError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM execution_cost WHERE execution_id = $1")
+            .bind(execution_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for record in records {
+            for line in &record.lines {
+                sqlx::query(
+                    r#"
+                    INSERT INTO execution_cost
+                        (execution_id, node, node_type, run_index, unit, quantity, cost)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
+                    "#,
+                )
+                .bind(execution_id)
+                .bind(&record.node)
+                .bind(&record.node_type)
+                .bind(record.run_index as i32)
+                .bind(&line.unit)
+                .bind(line.quantity)
+                .bind(line.cost)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Aggregate stored costs of `scope`, optionally only those since `since`.
+    pub async fn cost_summary(
+        &self,
+        scope: &CostScope,
+        since: Option<DateTime<Utc>>,
+        currency: &str,
+    ) -> Result<CostReport, DbError> {
+        let (filter, id) = match scope {
+            CostScope::Execution(id) => ("c.execution_id = $1", id),
+            CostScope::Workflow(id) => ("e.workflow_id = $1", id),
+            CostScope::Project(id) => (
+                "e.workflow_id IN (SELECT workflow_id FROM shared_workflow WHERE project_id = $1)",
+                id,
+            ),
+        };
+        let from = format!(
+            r#"
+            FROM execution_cost c
+            JOIN execution_entity e ON e.id = c.execution_id
+            WHERE {} AND ($2::timestamptz IS NULL OR c.created_at >= $2)
+            "#,
+            filter
+        );
+
+        let rows = sqlx::query_as::<_, (String, String, f64, f64)>(&format!(
+            "SELECT c.node_type, c.unit, SUM(c.quantity), SUM(c.cost) {}
+             GROUP BY c.node_type, c.unit",
+            from
+        ))
+        .bind(id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let (executions,) = sqlx::query_as::<_, (i64,)>(&format!(
+            "SELECT COUNT(DISTINCT c.execution_id) {}",
+            from
+        ))
+        .bind(id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let mut report = CostReport::new(currency);
+        report.executions = executions as usize;
+        for (node_type, unit, quantity, cost) in rows {
+            *report.by_node_type.entry(node_type).or_default() += cost;
+            let total = report.by_unit.entry(unit).or_default();
+            total.quantity += quantity;
+            total.cost += cost;
+            report.total += cost;
+        }
+        Ok(report)
+    }
+
+    // =========================================================================
+    // Execution Resource Usage
+    // =========================================================================
+
+    /// Store the measured resource usage of an execution.
+    pub async fn save_resource_usage(
+        &self,
+        execution_id: &str,
+        usage: &ResourceUsage,
+    ) -> Result<(), DbError> {
+        sqlx::query(
+            r#"
+            INSERT INTO execution_resource_usage (execution_id, wall_ms, cpu_ms, peak_rss_delta)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (execution_id) DO UPDATE
+            SET wall_ms = $2, cpu_ms = $3, peak_rss_delta = $4
+            "#,
+        )
+        .bind(execution_id)
+        .bind(usage.wall_ms)
+        .bind(usage.cpu_ms)
+        .bind(usage.peak_rss_delta.map(|delta| delta as i64))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Resource usage per workflow, optionally of one workflow and only
+    /// since `since`, heaviest CPU users first.
+    pub async fn resource_usage_by_workflow(
+        &self,
+        workflow_id: Option<&str>,
+        since: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<WorkflowResourceUsage>, DbError> {
+        let usage = sqlx::query_as::<_, WorkflowResourceUsage>(
+            r#"
+            SELECT e.workflow_id,
+                   COUNT(*) AS executions,
+                   AVG(r.wall_ms) AS wall_ms_avg,
+                   SUM(r.cpu_ms) AS cpu_ms_total,
+                   AVG(r.cpu_ms) AS cpu_ms_avg,
+                   MAX(r.cpu_ms) AS cpu_ms_max,
+                   AVG(r.peak_rss_delta)::float8 AS peak_rss_delta_avg,
+                   MAX(r.peak_rss_delta) AS peak_rss_delta_max
+            FROM execution_resource_usage r
+            JOIN execution_entity e ON e.id = r.execution_id
+            WHERE e.workflow_id IS NOT NULL
+              AND ($1::varchar IS NULL OR e.workflow_id = $1)
+              AND ($2::timestamptz IS NULL OR r.created_at >= $2)
+            GROUP BY e.workflow_id
+            ORDER BY cpu_ms_total DESC NULLS LAST, wall_ms_avg DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(workflow_id)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(usage)
+    }
+
+    /// Unfinished executions as (id, workflow ID, status), for reconciling
+    /// live counters.
+    pub async fn find_live(&self) -> Result<Vec<(String, String, String)>, DbError> {
+        let executions = sqlx::query_as::<_, (String, String, String)>(
+            r#"
+            SELECT id, workflow_id, status
+            FROM execution_entity
+            WHERE status IN ('new', 'running', 'waiting') AND deleted_at IS NULL
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(executions)
+    }
+
     /// Count executions by status.
     pub async fn count_by_status(&self) -> Result<Vec<(String, i64)>, DbError> {
         let counts = sqlx::query_as::<_, (String, i64)>(
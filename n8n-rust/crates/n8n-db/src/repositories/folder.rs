@@ -0,0 +1,406 @@
+//! Folder repository - folder trees and the workflows in them.
+//!
+//! Trees are walked with recursive CTEs, capped at [`MAX_FOLDER_DEPTH`]
+//! levels so a cycle left by concurrent moves cannot make them run forever.
+
+use sqlx::{PgConnection, PgExecutor, PgPool};
+
+use crate::entities::{
+    Folder, FolderTagMapping, FolderTreeEntry, InsertFolder, WorkflowSharingRole,
+};
+use crate::error::DbError;
+
+/// Deepest nesting the tree queries follow.
+pub const MAX_FOLDER_DEPTH: i32 = 64;
+
+/// Repository for folder operations.
+#[derive(Clone)]
+pub struct FolderRepository {
+    pool: PgPool,
+}
+
+impl FolderRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a folder by ID.
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<Folder>, DbError> {
+        let folder = sqlx::query_as::<_, Folder>(
+            r#"
+            SELECT id, name, parent_folder_id, project_id, created_at, updated_at
+            FROM folder WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(folder)
+    }
+
+    /// Folders directly in `parent`, or at the top of the project.
+    pub async fn find_children(
+        &self,
+        project_id: &str,
+        parent: Option<&str>,
+    ) -> Result<Vec<Folder>, DbError> {
+        let folders = sqlx::query_as::<_, Folder>(
+            r#"
+            SELECT id, name, parent_folder_id, project_id, created_at, updated_at
+            FROM folder
+            WHERE project_id = $1 AND parent_folder_id IS NOT DISTINCT FROM $2
+            ORDER BY name ASC
+            "#,
+        )
+        .bind(project_id)
+        .bind(parent)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(folders)
+    }
+
+    /// Every folder below `root`, or the whole project's tree, depth-first
+    /// with siblings by name.
+    pub async fn tree(
+        &self,
+        project_id: &str,
+        root: Option<&str>,
+    ) -> Result<Vec<FolderTreeEntry>, DbError> {
+        let entries = sqlx::query_as::<_, FolderTreeEntry>(
+            r#"
+            WITH RECURSIVE tree AS (
+                SELECT id, name, parent_folder_id, project_id, created_at, updated_at,
+                       0 AS depth, ARRAY[name]::VARCHAR[] AS path
+                FROM folder
+                WHERE project_id = $1 AND parent_folder_id IS NOT DISTINCT FROM $2
+                UNION ALL
+                SELECT f.id, f.name, f.parent_folder_id, f.project_id, f.created_at,
+                       f.updated_at, t.depth + 1, t.path || f.name
+                FROM folder f
+                JOIN tree t ON f.parent_folder_id = t.id
+                WHERE t.depth < $3
+            )
+            SELECT id, name, parent_folder_id, project_id, created_at, updated_at, depth, path
+            FROM tree
+            ORDER BY path
+            "#,
+        )
+        .bind(project_id)
+        .bind(root)
+        .bind(MAX_FOLDER_DEPTH)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// The folders from the top of the project down to `id`, for
+    /// breadcrumbs; empty for an unknown folder.
+    pub async fn ancestors(&self, id: &str) -> Result<Vec<Folder>, DbError> {
+        let folders = sqlx::query_as::<_, Folder>(
+            r#"
+            WITH RECURSIVE ancestors AS (
+                SELECT id, name, parent_folder_id, project_id, created_at, updated_at,
+                       0 AS depth
+                FROM folder
+                WHERE id = $1
+                UNION ALL
+                SELECT f.id, f.name, f.parent_folder_id, f.project_id, f.created_at,
+                       f.updated_at, a.depth + 1
+                FROM folder f
+                JOIN ancestors a ON f.id = a.parent_folder_id
+                WHERE a.depth < $2
+            )
+            SELECT id, name, parent_folder_id, project_id, created_at, updated_at
+            FROM ancestors
+            ORDER BY depth DESC
+            "#,
+        )
+        .bind(id)
+        .bind(MAX_FOLDER_DEPTH)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(folders)
+    }
+
+    /// IDs of the unarchived workflows in `id` and its subfolders.
+    pub async fn workflow_ids_in_tree(&self, id: &str) -> Result<Vec<String>, DbError> {
+        let ids = sqlx::query_scalar::<_, String>(
+            r#"
+            WITH RECURSIVE subtree AS (
+                SELECT id, 0 AS depth FROM folder WHERE id = $1
+                UNION ALL
+                SELECT f.id, s.depth + 1
+                FROM folder f
+                JOIN subtree s ON f.parent_folder_id = s.id
+                WHERE s.depth < $2
+            )
+            SELECT w.id FROM workflow_entity w
+            JOIN subtree s ON w.parent_folder_id = s.id
+            WHERE w.is_archived = false
+            ORDER BY w.name ASC
+            "#,
+        )
+        .bind(id)
+        .bind(MAX_FOLDER_DEPTH)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Create a new folder.
+    pub async fn create(&self, folder: &InsertFolder) -> Result<Folder, DbError> {
+        Self::create_in(&self.pool, folder).await
+    }
+
+    /// [`Self::create`] on `executor`.
+    pub async fn create_in<'e>(
+        executor: impl PgExecutor<'e>,
+        folder: &InsertFolder,
+    ) -> Result<Folder, DbError> {
+        let created = sqlx::query_as::<_, Folder>(
+            r#"
+            INSERT INTO folder (id, name, parent_folder_id, project_id)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, name, parent_folder_id, project_id, created_at, updated_at
+            "#,
+        )
+        .bind(&folder.id)
+        .bind(&folder.name)
+        .bind(&folder.parent_folder_id)
+        .bind(&folder.project_id)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(created)
+    }
+
+    /// Rename a folder.
+    pub async fn rename(&self, id: &str, name: &str) -> Result<Folder, DbError> {
+        sqlx::query_as::<_, Folder>(
+            r#"
+            UPDATE folder SET name = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, parent_folder_id, project_id, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(DbError::NotFound)
+    }
+
+    /// Move a folder with its contents into `parent`, or to the top of its
+    /// project. The parent must be in the same project and not the folder
+    /// itself or one of its subfolders.
+    pub async fn move_folder(&self, id: &str, parent: Option<&str>) -> Result<Folder, DbError> {
+        let moved = sqlx::query_as::<_, Folder>(
+            r#"
+            WITH RECURSIVE subtree AS (
+                SELECT id, 0 AS depth FROM folder WHERE id = $1
+                UNION ALL
+                SELECT f.id, s.depth + 1
+                FROM folder f
+                JOIN subtree s ON f.parent_folder_id = s.id
+                WHERE s.depth < $3
+            )
+            UPDATE folder SET parent_folder_id = $2, updated_at = NOW()
+            WHERE id = $1
+              AND ($2::VARCHAR IS NULL OR (
+                  $2 NOT IN (SELECT id FROM subtree)
+                  AND EXISTS (
+                      SELECT 1 FROM folder p
+                      WHERE p.id = $2 AND p.project_id IS NOT DISTINCT FROM folder.project_id
+                  )
+              ))
+            RETURNING id, name, parent_folder_id, project_id, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(parent)
+        .bind(MAX_FOLDER_DEPTH)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match moved {
+            Some(folder) => Ok(folder),
+            None if self.find_by_id(id).await?.is_none() => Err(DbError::NotFound),
+            None => Err(DbError::InvalidData(format!(
+                "Folder {} cannot move into {}: not a folder of its project outside it",
+                id,
+                parent.unwrap_or_default()
+            ))),
+        }
+    }
+
+    /// Move workflows into `folder_id`, or to the top of their project.
+    /// Only workflows owned by the folder's project move. Returns how many
+    /// moved.
+    pub async fn move_workflows(
+        &self,
+        workflow_ids: &[String],
+        folder_id: Option<&str>,
+    ) -> Result<u64, DbError> {
+        Self::move_workflows_in(&self.pool, workflow_ids, folder_id).await
+    }
+
+    /// [`Self::move_workflows`] on `executor`.
+    pub async fn move_workflows_in<'e>(
+        executor: impl PgExecutor<'e>,
+        workflow_ids: &[String],
+        folder_id: Option<&str>,
+    ) -> Result<u64, DbError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE workflow_entity w SET parent_folder_id = $2
+            WHERE w.id = ANY($1)
+              AND ($2::VARCHAR IS NULL OR EXISTS (
+                  SELECT 1 FROM folder f
+                  JOIN shared_workflow sw ON sw.project_id = f.project_id
+                  WHERE f.id = $2 AND sw.workflow_id = w.id AND sw.role = $3
+              ))
+            "#,
+        )
+        .bind(workflow_ids)
+        .bind(folder_id)
+        .bind(WorkflowSharingRole::Owner.to_string())
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete a folder and its subfolders. With `transfer_to`, the folder's
+    /// workflows and subfolders move there first and are kept; otherwise
+    /// the workflows of the whole tree are archived at the top of their
+    /// project, as the foreign keys would delete them with their folder.
+    pub async fn delete(&self, id: &str, transfer_to: Option<&str>) -> Result<bool, DbError> {
+        let mut tx = self.pool.begin().await?;
+        let deleted = Self::delete_in(&mut tx, id, transfer_to).await?;
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    /// [`Self::delete`] on `conn`, which should be in a transaction.
+    pub async fn delete_in(
+        conn: &mut PgConnection,
+        id: &str,
+        transfer_to: Option<&str>,
+    ) -> Result<bool, DbError> {
+        match transfer_to {
+            Some(target) => {
+                let target_inside: bool = sqlx::query_scalar(
+                    r#"
+                    WITH RECURSIVE subtree AS (
+                        SELECT id, 0 AS depth FROM folder WHERE id = $1
+                        UNION ALL
+                        SELECT f.id, s.depth + 1
+                        FROM folder f
+                        JOIN subtree s ON f.parent_folder_id = s.id
+                        WHERE s.depth < $3
+                    )
+                    SELECT EXISTS (SELECT 1 FROM subtree WHERE id = $2)
+                    "#,
+                )
+                .bind(id)
+                .bind(target)
+                .bind(MAX_FOLDER_DEPTH)
+                .fetch_one(&mut *conn)
+                .await?;
+                if target_inside {
+                    return Err(DbError::InvalidData(format!(
+                        "Folder {} cannot transfer its contents into itself or a subfolder",
+                        id
+                    )));
+                }
+
+                sqlx::query(
+                    "UPDATE workflow_entity SET parent_folder_id = $2 WHERE parent_folder_id = $1",
+                )
+                .bind(id)
+                .bind(target)
+                .execute(&mut *conn)
+                .await?;
+                sqlx::query(
+                    r#"
+                    UPDATE folder SET parent_folder_id = $2, updated_at = NOW()
+                    WHERE parent_folder_id = $1
+                    "#,
+                )
+                .bind(id)
+                .bind(target)
+                .execute(&mut *conn)
+                .await?;
+            }
+            None => {
+                sqlx::query(
+                    r#"
+                    WITH RECURSIVE subtree AS (
+                        SELECT id, 0 AS depth FROM folder WHERE id = $1
+                        UNION ALL
+                        SELECT f.id, s.depth + 1
+                        FROM folder f
+                        JOIN subtree s ON f.parent_folder_id = s.id
+                        WHERE s.depth < $2
+                    )
+                    UPDATE workflow_entity
+                    SET parent_folder_id = NULL, is_archived = true, active = false,
+                        deleted_at = COALESCE(deleted_at, NOW())
+                    WHERE parent_folder_id IN (SELECT id FROM subtree)
+                    "#,
+                )
+                .bind(id)
+                .bind(MAX_FOLDER_DEPTH)
+                .execute(&mut *conn)
+                .await?;
+            }
+        }
+
+        let result = sqlx::query("DELETE FROM folder WHERE id = $1")
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get tags for a folder.
+    pub async fn get_tags(&self, folder_id: &str) -> Result<Vec<FolderTagMapping>, DbError> {
+        let tags = sqlx::query_as::<_, FolderTagMapping>(
+            "SELECT folder_id, tag_id FROM folder_tag_mapping WHERE folder_id = $1",
+        )
+        .bind(folder_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Set tags for a folder (replaces existing).
+    pub async fn set_tags(&self, folder_id: &str, tag_ids: &[String]) -> Result<(), DbError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM folder_tag_mapping WHERE folder_id = $1")
+            .bind(folder_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO folder_tag_mapping (folder_id, tag_id)
+            SELECT $1, tag_id FROM UNNEST($2::VARCHAR[]) AS tag_id
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(folder_id)
+        .bind(tag_ids)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
@@ -3,27 +3,51 @@
 //! These repositories provide async CRUD operations with compile-time
 //! checked SQL queries via sqlx.
 
+pub mod annotation;
+pub mod binary_data;
 pub mod credentials;
+pub mod endpoint;
 pub mod execution;
+pub mod folder;
+pub mod processed_data;
 pub mod project;
+pub mod pruning;
 pub mod settings;
 pub mod tag;
+pub mod test_run;
+pub mod timer;
 pub mod user;
 pub mod variables;
 pub mod webhook;
 pub mod workflow;
+pub mod workflow_statistics;
 
+pub use annotation::*;
+pub use binary_data::*;
 pub use credentials::*;
+pub use endpoint::*;
 pub use execution::*;
+pub use folder::*;
+pub use processed_data::*;
 pub use project::*;
+pub use pruning::*;
 pub use settings::*;
 pub use tag::*;
+pub use test_run::*;
+pub use timer::*;
 pub use user::*;
 pub use variables::*;
 pub use webhook::*;
 pub use workflow::*;
+pub use workflow_statistics::*;
 
-use sqlx::PgPool;
+use futures::future::BoxFuture;
+use n8n_core::CredentialService;
+use sqlx::{PgConnection, PgPool};
+
+use crate::compression::DataCompressionConfig;
+use crate::error::DbError;
+use crate::raw::RawQuery;
 
 /// Database context containing all repositories.
 #[derive(Clone)]
@@ -31,13 +55,21 @@ pub struct DbContext {
     pub pool: PgPool,
     pub workflows: WorkflowRepository,
     pub executions: ExecutionRepository,
+    pub annotations: AnnotationRepository,
+    pub binary_data: BinaryDataRepository,
     pub credentials: CredentialsRepository,
+    pub endpoints: EndpointRepository,
+    pub folders: FolderRepository,
     pub tags: TagRepository,
+    pub test_runs: TestRunRepository,
     pub users: UserRepository,
     pub projects: ProjectRepository,
     pub settings: SettingsRepository,
     pub variables: VariablesRepository,
     pub webhooks: WebhookRepository,
+    pub timers: TimerRepository,
+    pub statistics: WorkflowStatisticsRepository,
+    pub processed_data: ProcessedDataRepository,
 }
 
 impl DbContext {
@@ -46,19 +78,115 @@ impl DbContext {
         Self {
             workflows: WorkflowRepository::new(pool.clone()),
             executions: ExecutionRepository::new(pool.clone()),
+            annotations: AnnotationRepository::new(pool.clone()),
+            binary_data: BinaryDataRepository::new(pool.clone()),
             credentials: CredentialsRepository::new(pool.clone()),
+            endpoints: EndpointRepository::new(pool.clone()),
+            folders: FolderRepository::new(pool.clone()),
             tags: TagRepository::new(pool.clone()),
+            test_runs: TestRunRepository::new(pool.clone()),
             users: UserRepository::new(pool.clone()),
             projects: ProjectRepository::new(pool.clone()),
             settings: SettingsRepository::new(pool.clone()),
             variables: VariablesRepository::new(pool.clone()),
             webhooks: WebhookRepository::new(pool.clone()),
+            timers: TimerRepository::new(pool.clone()),
+            statistics: WorkflowStatisticsRepository::new(pool.clone()),
+            processed_data: ProcessedDataRepository::new(pool.clone()),
             pool,
         }
     }
 
+    /// Encrypt and decrypt credential data with `encryption`.
+    pub fn with_encryption(mut self, encryption: CredentialService) -> Self {
+        self.credentials = self.credentials.with_encryption(encryption);
+        self
+    }
+
+    /// Compress execution data as `compression` says.
+    pub fn with_compression(mut self, compression: DataCompressionConfig) -> Self {
+        self.executions = self.executions.with_compression(compression);
+        self
+    }
+
     /// Run database migrations.
     pub async fn migrate(&self) -> Result<(), sqlx::migrate::MigrateError> {
         sqlx::migrate!("./migrations").run(&self.pool).await
     }
+
+    /// Raw SQL with `:name` parameters, read-only unless allowed otherwise;
+    /// see [`crate::raw`].
+    pub fn raw(&self, sql: impl Into<String>) -> RawQuery {
+        RawQuery::new(self.pool.clone(), sql)
+    }
+
+    /// Run `work` as one unit of work: in a transaction, committed when it
+    /// returns `Ok` and rolled back when it fails. Repository methods
+    /// ending in `_in` run on the connection it is given:
+    ///
+    /// ```rust,no_run
+    /// # use n8n_db::{DbContext, DbError, InsertWorkflow, WorkflowRepository, WorkflowSharingRole};
+    /// # async fn create(
+    /// #     db: &DbContext,
+    /// #     workflow: InsertWorkflow,
+    /// #     project_id: String,
+    /// #     tag_ids: Vec<String>,
+    /// # ) -> Result<(), DbError> {
+    /// let created = db
+    ///     .transaction(|conn| {
+    ///         Box::pin(async move {
+    ///             let created = WorkflowRepository::create_in(&mut *conn, &workflow).await?;
+    ///             let owner = WorkflowSharingRole::Owner;
+    ///             WorkflowRepository::share_in(&mut *conn, &created.id, &project_id, owner)
+    ///                 .await?;
+    ///             WorkflowRepository::set_tags_in(conn, &created.id, &tag_ids).await?;
+    ///             Ok(created)
+    ///         })
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Only owned data can be moved into `work`.
+    pub async fn transaction<T, F>(&self, work: F) -> Result<T, DbError>
+    where
+        T: Send,
+        F: for<'c> FnOnce(&'c mut PgConnection) -> BoxFuture<'c, Result<T, DbError>> + Send,
+    {
+        let mut tx = self.pool.begin().await?;
+        match work(&mut *tx).await {
+            Ok(value) => {
+                tx.commit().await?;
+                Ok(value)
+            }
+            Err(e) => {
+                if let Err(rollback) = tx.rollback().await {
+                    tracing::warn!(error = %rollback, "Failed to roll back transaction");
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Check database connectivity with a trivial round-trip.
+    pub async fn ping(&self) -> Result<(), DbError> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// List embedded migrations that have not been successfully applied.
+    pub async fn pending_migrations(&self) -> Result<Vec<i64>, DbError> {
+        let applied: Vec<i64> = sqlx::query_scalar(
+            "SELECT version FROM _sqlx_migrations WHERE success = true",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sqlx::migrate!("./migrations")
+            .iter()
+            .map(|m| m.version)
+            .filter(|v| !applied.contains(v))
+            .collect())
+    }
 }
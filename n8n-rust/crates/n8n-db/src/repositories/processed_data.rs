@@ -0,0 +1,157 @@
+//! Processed data repository - values deduplicated across executions.
+
+use async_trait::async_trait;
+use n8n_core::{processed_data, ProcessedDataMode, ProcessedDataStore, ProcessedValue};
+use sqlx::{PgConnection, PgPool};
+
+use crate::entities::ProcessedDataEntity;
+use crate::error::DbError;
+
+/// Repository for processed data.
+#[derive(Clone)]
+pub struct ProcessedDataRepository {
+    pool: PgPool,
+}
+
+impl ProcessedDataRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Value of a context.
+    pub async fn find(
+        &self,
+        workflow_id: &str,
+        context: &str,
+    ) -> Result<Option<ProcessedDataEntity>, DbError> {
+        let row = sqlx::query_as::<_, ProcessedDataEntity>(
+            r#"
+            SELECT workflow_id, context, value, created_at, updated_at FROM processed_data
+            WHERE workflow_id = $1 AND context = $2
+            "#,
+        )
+        .bind(workflow_id)
+        .bind(context)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row)
+    }
+
+    /// Compare `values` with the value of a context and store the result,
+    /// in one transaction. Returns whether each value is new.
+    pub async fn check_and_record(
+        &self,
+        workflow_id: &str,
+        context: &str,
+        mode: ProcessedDataMode,
+        values: &[String],
+        history_size: usize,
+    ) -> Result<Vec<bool>, DbError> {
+        let mut tx = self.pool.begin().await?;
+        let new =
+            Self::check_and_record_in(&mut tx, workflow_id, context, mode, values, history_size)
+                .await?;
+        tx.commit().await?;
+        Ok(new)
+    }
+
+    /// [`Self::check_and_record`] on `conn`, which should be in a
+    /// transaction. Executions checking the same context wait for each
+    /// other until it ends.
+    pub async fn check_and_record_in(
+        conn: &mut PgConnection,
+        workflow_id: &str,
+        context: &str,
+        mode: ProcessedDataMode,
+        values: &[String],
+        history_size: usize,
+    ) -> Result<Vec<bool>, DbError> {
+        // Also serializes the first batch of a context, which has no row
+        // to lock yet.
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+            .bind(format!("processed_data:{}:{}", workflow_id, context))
+            .execute(&mut *conn)
+            .await?;
+
+        let stored: Option<serde_json::Value> = sqlx::query_scalar(
+            "SELECT value FROM processed_data WHERE workflow_id = $1 AND context = $2",
+        )
+        .bind(workflow_id)
+        .bind(context)
+        .fetch_optional(&mut *conn)
+        .await?;
+        let stored: Option<ProcessedValue> = stored.map(serde_json::from_value).transpose()?;
+
+        let (new, value) = processed_data::check(stored.as_ref(), mode, values, history_size)
+            .map_err(DbError::InvalidData)?;
+        sqlx::query(
+            r#"
+            INSERT INTO processed_data (workflow_id, context, value)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (workflow_id, context) DO UPDATE
+            SET value = EXCLUDED.value, updated_at = NOW()
+            "#,
+        )
+        .bind(workflow_id)
+        .bind(context)
+        .bind(serde_json::to_value(&value)?)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(new)
+    }
+
+    /// Forget the value of a context.
+    pub async fn clear(&self, workflow_id: &str, context: &str) -> Result<bool, DbError> {
+        let result =
+            sqlx::query("DELETE FROM processed_data WHERE workflow_id = $1 AND context = $2")
+                .bind(workflow_id)
+                .bind(context)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove the processed data of a deleted workflow.
+    pub async fn delete_by_workflow(&self, workflow_id: &str) -> Result<u64, DbError> {
+        let result = sqlx::query("DELETE FROM processed_data WHERE workflow_id = $1")
+            .bind(workflow_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// The engine's processed data, kept in `processed_data`.
+#[async_trait]
+impl ProcessedDataStore for ProcessedDataRepository {
+    async fn check_and_record(
+        &self,
+        workflow_id: &str,
+        context: &str,
+        mode: ProcessedDataMode,
+        values: &[String],
+        history_size: usize,
+    ) -> Result<Vec<bool>, String> {
+        ProcessedDataRepository::check_and_record(
+            self,
+            workflow_id,
+            context,
+            mode,
+            values,
+            history_size,
+        )
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    async fn clear(&self, workflow_id: &str, context: &str) -> Result<(), String> {
+        ProcessedDataRepository::clear(self, workflow_id, context)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
@@ -1,6 +1,6 @@
 //! Project repository - CRUD operations for projects.
 
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 use uuid::Uuid;
 
 use crate::entities::{Project, ProjectRelation};
@@ -52,6 +52,14 @@ impl ProjectRepository {
 
     /// Create a new project.
     pub async fn create(&self, project: &Project) -> Result<Project, DbError> {
+        Self::create_in(&self.pool, project).await
+    }
+
+    /// [`Self::create`] on `executor`.
+    pub async fn create_in<'e>(
+        executor: impl PgExecutor<'e>,
+        project: &Project,
+    ) -> Result<Project, DbError> {
         let icon_json = project.icon.as_ref()
             .map(|i| serde_json::to_value(i))
             .transpose()?;
@@ -69,7 +77,7 @@ impl ProjectRepository {
         .bind(&icon_json)
         .bind(&project.description)
         .bind(project.creator_id)
-        .fetch_one(&self.pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(created)
@@ -87,6 +95,16 @@ impl ProjectRepository {
 
     /// Add a user to a project.
     pub async fn add_member(&self, project_id: &str, user_id: Uuid, role: &str) -> Result<ProjectRelation, DbError> {
+        Self::add_member_in(&self.pool, project_id, user_id, role).await
+    }
+
+    /// [`Self::add_member`] on `executor`.
+    pub async fn add_member_in<'e>(
+        executor: impl PgExecutor<'e>,
+        project_id: &str,
+        user_id: Uuid,
+        role: &str,
+    ) -> Result<ProjectRelation, DbError> {
         let relation = sqlx::query_as::<_, ProjectRelation>(
             r#"
             INSERT INTO project_relation (project_id, user_id, role)
@@ -98,7 +116,7 @@ impl ProjectRepository {
         .bind(project_id)
         .bind(user_id)
         .bind(role)
-        .fetch_one(&self.pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(relation)
@@ -0,0 +1,202 @@
+//! Execution pruning - keeps the execution table from growing unbounded.
+//!
+//! Pruning runs in two steps, as in n8n. Finished executions older than
+//! `max_age_hours`, or beyond the newest `max_count`, are soft-deleted
+//! (`deleted_at` is set) and disappear from listings. Soft-deleted
+//! executions are hard-deleted once `hard_delete_buffer_hours` have
+//! passed, together with their data and metadata through the foreign
+//! keys; their binary data is left for the garbage collector.
+//!
+//! Executions that are new, running or waiting, and executions with an
+//! annotation, are never pruned. Both steps work in batches of
+//! `batch_size` rows so no statement holds locks on a large part of the
+//! table. With several instances, give the service a
+//! [`LeaderElection`](crate::LeaderElection) so only the leader prunes.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+use crate::error::DbError;
+
+/// Configuration for [`PruningService`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PruningConfig {
+    /// Prune executions when a database is configured.
+    pub enabled: bool,
+    /// Soft-delete finished executions older than this; 0 keeps any age.
+    pub max_age_hours: u64,
+    /// Soft-delete finished executions beyond the newest this many; 0 keeps
+    /// any number.
+    pub max_count: u64,
+    /// Hours between soft and hard deletion.
+    pub hard_delete_buffer_hours: u64,
+    /// Rows per statement.
+    pub batch_size: u32,
+    /// Seconds between runs.
+    pub interval_secs: u64,
+}
+
+impl Default for PruningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_age_hours: 336,
+            max_count: 10_000,
+            hard_delete_buffer_hours: 1,
+            batch_size: 100,
+            interval_secs: 900,
+        }
+    }
+}
+
+/// What one run deleted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruningReport {
+    pub soft_deleted: u64,
+    pub hard_deleted: u64,
+}
+
+/// Soft- and hard-deletes old executions.
+#[derive(Clone)]
+pub struct PruningService {
+    pool: PgPool,
+    config: PruningConfig,
+    leader: Option<watch::Receiver<bool>>,
+}
+
+impl PruningService {
+    pub fn new(pool: PgPool, config: PruningConfig) -> Self {
+        Self {
+            pool,
+            config,
+            leader: None,
+        }
+    }
+
+    /// Prune only while `leader` is true, e.g.
+    /// [`LeaderElection::subscribe`](crate::LeaderElection::subscribe).
+    pub fn with_leader(mut self, leader: watch::Receiver<bool>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    pub fn config(&self) -> &PruningConfig {
+        &self.config
+    }
+
+    /// Prune every `interval_secs` until the task is aborted.
+    pub fn spawn(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(std::time::Duration::from_secs(
+                self.config.interval_secs.max(1),
+            ));
+            loop {
+                tick.tick().await;
+                if self.leader.as_ref().is_some_and(|leader| !*leader.borrow()) {
+                    continue;
+                }
+                match self.run_once().await {
+                    Ok(report) => tracing::info!(
+                        soft_deleted = report.soft_deleted,
+                        hard_deleted = report.hard_deleted,
+                        "Execution pruning finished"
+                    ),
+                    Err(e) => tracing::warn!(error = %e, "Execution pruning failed"),
+                }
+            }
+        })
+    }
+
+    /// Run both steps once.
+    pub async fn run_once(&self) -> Result<PruningReport, DbError> {
+        let now = Utc::now();
+        Ok(PruningReport {
+            soft_deleted: self.soft_delete(now).await?,
+            hard_deleted: self.hard_delete(now).await?,
+        })
+    }
+
+    /// Soft-delete executions past the age or count limit.
+    pub async fn soft_delete(&self, now: DateTime<Utc>) -> Result<u64, DbError> {
+        let older_than = (self.config.max_age_hours > 0)
+            .then(|| now - Duration::hours(self.config.max_age_hours as i64));
+        let keep = self.config.max_count as i64;
+        if older_than.is_none() && keep == 0 {
+            return Ok(0);
+        }
+
+        let batch = self.config.batch_size.max(1) as i64;
+        let mut total = 0;
+        loop {
+            let deleted = sqlx::query(
+                r#"
+                UPDATE execution_entity SET deleted_at = $4
+                WHERE id IN (
+                    SELECT e.id FROM execution_entity e
+                    WHERE e.deleted_at IS NULL
+                      AND e.status NOT IN ('new', 'running', 'waiting')
+                      AND NOT EXISTS (
+                          SELECT 1 FROM execution_annotations a WHERE a.execution_id = e.id
+                      )
+                      AND (
+                          e.created_at < $1
+                          OR ($2 > 0 AND e.id IN (
+                              SELECT id FROM execution_entity
+                              WHERE deleted_at IS NULL
+                              ORDER BY created_at DESC, id DESC
+                              OFFSET $2
+                          ))
+                      )
+                    LIMIT $3
+                    FOR UPDATE SKIP LOCKED
+                )
+                "#,
+            )
+            .bind(older_than)
+            .bind(keep)
+            .bind(batch)
+            .bind(now)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+            total += deleted;
+            if deleted < batch as u64 {
+                return Ok(total);
+            }
+        }
+    }
+
+    /// Hard-delete executions soft-deleted before the buffer.
+    pub async fn hard_delete(&self, now: DateTime<Utc>) -> Result<u64, DbError> {
+        let before = now - Duration::hours(self.config.hard_delete_buffer_hours as i64);
+        let batch = self.config.batch_size.max(1) as i64;
+        let mut total = 0;
+        loop {
+            let deleted = sqlx::query(
+                r#"
+                DELETE FROM execution_entity
+                WHERE id IN (
+                    SELECT id FROM execution_entity
+                    WHERE deleted_at < $1
+                    LIMIT $2
+                    FOR UPDATE SKIP LOCKED
+                )
+                "#,
+            )
+            .bind(before)
+            .bind(batch)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+            total += deleted;
+            if deleted < batch as u64 {
+                return Ok(total);
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! Tag repository - CRUD operations for tags.
 
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 
 use crate::entities::{InsertTag, TagEntity};
 use crate::error::DbError;
@@ -53,6 +53,14 @@ impl TagRepository {
 
     /// Create a new tag.
     pub async fn create(&self, tag: &InsertTag) -> Result<TagEntity, DbError> {
+        Self::create_in(&self.pool, tag).await
+    }
+
+    /// [`Self::create`] on `executor`.
+    pub async fn create_in<'e>(
+        executor: impl PgExecutor<'e>,
+        tag: &InsertTag,
+    ) -> Result<TagEntity, DbError> {
         let created = sqlx::query_as::<_, TagEntity>(
             r#"
             INSERT INTO tag_entity (id, name)
@@ -62,12 +70,66 @@ impl TagRepository {
         )
         .bind(&tag.id)
         .bind(&tag.name)
-        .fetch_one(&self.pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(created)
     }
 
+    /// Insert `tags` in one statement. Returns how many were inserted.
+    pub async fn insert_many(&self, tags: &[InsertTag]) -> Result<u64, DbError> {
+        Self::insert_many_in(&self.pool, tags).await
+    }
+
+    /// [`Self::insert_many`] on `executor`.
+    pub async fn insert_many_in<'e>(
+        executor: impl PgExecutor<'e>,
+        tags: &[InsertTag],
+    ) -> Result<u64, DbError> {
+        Self::bulk_write(executor, tags, "").await
+    }
+
+    /// Insert `tags`, renaming those whose ID exists, in one statement.
+    /// Returns how many were inserted or renamed.
+    pub async fn upsert_many(&self, tags: &[InsertTag]) -> Result<u64, DbError> {
+        Self::upsert_many_in(&self.pool, tags).await
+    }
+
+    /// [`Self::upsert_many`] on `executor`.
+    pub async fn upsert_many_in<'e>(
+        executor: impl PgExecutor<'e>,
+        tags: &[InsertTag],
+    ) -> Result<u64, DbError> {
+        let on_conflict =
+            "ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name, updated_at = NOW()";
+        Self::bulk_write(executor, tags, on_conflict).await
+    }
+
+    async fn bulk_write<'e>(
+        executor: impl PgExecutor<'e>,
+        tags: &[InsertTag],
+        on_conflict: &str,
+    ) -> Result<u64, DbError> {
+        if tags.is_empty() {
+            return Ok(0);
+        }
+        let sql = format!(
+            r#"
+            INSERT INTO tag_entity (id, name)
+            SELECT * FROM UNNEST($1::varchar[], $2::varchar[])
+            {}
+            "#,
+            on_conflict
+        );
+        let result = sqlx::query(&sql)
+            .bind(tags.iter().map(|t| t.id.as_str()).collect::<Vec<_>>())
+            .bind(tags.iter().map(|t| t.name.as_str()).collect::<Vec<_>>())
+            .execute(executor)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Update a tag.
     pub async fn update(&self, id: &str, name: &str) -> Result<TagEntity, DbError> {
         let updated = sqlx::query_as::<_, TagEntity>(
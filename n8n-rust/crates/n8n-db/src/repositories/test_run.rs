@@ -0,0 +1,223 @@
+//! Test run repository - evaluation runs and their test case executions.
+
+use n8n_core::EvaluationReport;
+use serde_json::Value;
+use sqlx::{PgConnection, PgPool};
+
+use crate::entities::{TestCaseExecution, TestRun, TestRunStatus};
+use crate::error::DbError;
+
+const TEST_RUN_COLUMNS: &str = "id, workflow_id, test_set_id, status, error_code, error_details, \
+    run_at, completed_at, metrics, passed_cases, failed_cases, created_at, updated_at";
+
+/// Repository for test run operations.
+#[derive(Clone)]
+pub struct TestRunRepository {
+    pool: PgPool,
+}
+
+impl TestRunRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a test run by ID.
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<TestRun>, DbError> {
+        let run = sqlx::query_as::<_, TestRun>(&format!(
+            "SELECT {} FROM test_run WHERE id = $1",
+            TEST_RUN_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(run)
+    }
+
+    /// Latest test runs of a workflow first.
+    pub async fn find_by_workflow(
+        &self,
+        workflow_id: &str,
+        limit: i64,
+    ) -> Result<Vec<TestRun>, DbError> {
+        let runs = sqlx::query_as::<_, TestRun>(&format!(
+            "SELECT {} FROM test_run WHERE workflow_id = $1 ORDER BY created_at DESC LIMIT $2",
+            TEST_RUN_COLUMNS
+        ))
+        .bind(workflow_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(runs)
+    }
+
+    /// Case executions of a test run, in the order they ran.
+    pub async fn find_cases(&self, test_run_id: &str) -> Result<Vec<TestCaseExecution>, DbError> {
+        let cases = sqlx::query_as::<_, TestCaseExecution>(
+            r#"
+            SELECT id, test_run_id, execution_id, case_id, status, run_at, completed_at,
+                   error_code, error_details, metrics, outputs, created_at, updated_at
+            FROM test_case_execution
+            WHERE test_run_id = $1
+            ORDER BY run_at ASC, id ASC
+            "#,
+        )
+        .bind(test_run_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(cases)
+    }
+
+    /// Record a run that is starting.
+    pub async fn start(
+        &self,
+        id: &str,
+        workflow_id: &str,
+        test_set_id: Option<&str>,
+    ) -> Result<TestRun, DbError> {
+        let run = sqlx::query_as::<_, TestRun>(&format!(
+            r#"
+            INSERT INTO test_run (id, workflow_id, test_set_id, status, run_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            RETURNING {}
+            "#,
+            TEST_RUN_COLUMNS
+        ))
+        .bind(id)
+        .bind(workflow_id)
+        .bind(test_set_id)
+        .bind(TestRunStatus::Running.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(run)
+    }
+
+    /// Complete a run with `report`, started or not, recording its cases in
+    /// one transaction.
+    pub async fn record(&self, report: &EvaluationReport) -> Result<TestRun, DbError> {
+        let mut tx = self.pool.begin().await?;
+        let run = Self::record_in(&mut tx, report).await?;
+        tx.commit().await?;
+        Ok(run)
+    }
+
+    /// [`Self::record`] on `conn`, which should be in a transaction.
+    pub async fn record_in(
+        conn: &mut PgConnection,
+        report: &EvaluationReport,
+    ) -> Result<TestRun, DbError> {
+        let run = TestRun::from_report(report);
+        let saved = sqlx::query_as::<_, TestRun>(&format!(
+            r#"
+            INSERT INTO test_run (id, workflow_id, test_set_id, status, run_at, completed_at,
+                                  metrics, passed_cases, failed_cases)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (id) DO UPDATE SET
+                status = EXCLUDED.status,
+                completed_at = EXCLUDED.completed_at,
+                metrics = EXCLUDED.metrics,
+                passed_cases = EXCLUDED.passed_cases,
+                failed_cases = EXCLUDED.failed_cases,
+                updated_at = NOW()
+            RETURNING {}
+            "#,
+            TEST_RUN_COLUMNS
+        ))
+        .bind(&run.id)
+        .bind(&run.workflow_id)
+        .bind(&run.test_set_id)
+        .bind(&run.status)
+        .bind(run.run_at)
+        .bind(run.completed_at)
+        .bind(&run.metrics)
+        .bind(run.passed_cases)
+        .bind(run.failed_cases)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        sqlx::query("DELETE FROM test_case_execution WHERE test_run_id = $1")
+            .bind(&run.id)
+            .execute(&mut *conn)
+            .await?;
+        for case in &report.cases {
+            let execution = TestCaseExecution::from_case(&run.id, case, report.started_at);
+            sqlx::query(
+                r#"
+                INSERT INTO test_case_execution
+                    (id, test_run_id, execution_id, case_id, status, run_at, completed_at,
+                     error_code, error_details, metrics, outputs)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                "#,
+            )
+            .bind(&execution.id)
+            .bind(&execution.test_run_id)
+            .bind(&execution.execution_id)
+            .bind(&execution.case_id)
+            .bind(&execution.status)
+            .bind(execution.run_at)
+            .bind(execution.completed_at)
+            .bind(&execution.error_code)
+            .bind(&execution.error_details)
+            .bind(&execution.metrics)
+            .bind(&execution.outputs)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(saved)
+    }
+
+    /// End a run that could not be evaluated.
+    pub async fn fail(
+        &self,
+        id: &str,
+        error_code: &str,
+        error_details: Option<&Value>,
+    ) -> Result<bool, DbError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE test_run
+            SET status = $2, error_code = $3, error_details = $4, completed_at = NOW(),
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(TestRunStatus::Error.as_str())
+        .bind(error_code)
+        .bind(error_details)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Cancel a run that has not completed.
+    pub async fn cancel(&self, id: &str) -> Result<bool, DbError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE test_run SET status = $2, completed_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status IN ('new', 'running')
+            "#,
+        )
+        .bind(id)
+        .bind(TestRunStatus::Cancelled.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a test run and its case executions.
+    pub async fn delete(&self, id: &str) -> Result<bool, DbError> {
+        let result = sqlx::query("DELETE FROM test_run WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
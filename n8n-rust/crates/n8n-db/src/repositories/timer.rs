@@ -0,0 +1,205 @@
+//! Timer repository - durable timer storage and claiming.
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+
+use crate::entities::{InsertTimer, TimerEntity};
+use crate::error::DbError;
+
+const TIMER_COLUMNS: &str = "id, workflow_id, node, fire_at, payload, attempts, locked_by, locked_until, created_at, updated_at";
+
+/// Repository for timer operations.
+#[derive(Clone)]
+pub struct TimerRepository {
+    pool: PgPool,
+}
+
+impl TimerRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a timer by ID.
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<TimerEntity>, DbError> {
+        let timer = sqlx::query_as::<_, TimerEntity>(&format!(
+            "SELECT {} FROM timer_entity WHERE id = $1",
+            TIMER_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(timer)
+    }
+
+    /// Find timers for a workflow, soonest first.
+    pub async fn find_by_workflow(&self, workflow_id: &str) -> Result<Vec<TimerEntity>, DbError> {
+        let timers = sqlx::query_as::<_, TimerEntity>(&format!(
+            "SELECT {} FROM timer_entity WHERE workflow_id = $1 ORDER BY fire_at",
+            TIMER_COLUMNS
+        ))
+        .bind(workflow_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(timers)
+    }
+
+    /// Create a timer.
+    pub async fn create(&self, timer: &InsertTimer) -> Result<TimerEntity, DbError> {
+        let created = sqlx::query_as::<_, TimerEntity>(&format!(
+            r#"
+            INSERT INTO timer_entity (id, workflow_id, node, fire_at, payload)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            TIMER_COLUMNS
+        ))
+        .bind(&timer.id)
+        .bind(&timer.workflow_id)
+        .bind(&timer.node)
+        .bind(timer.fire_at)
+        .bind(&timer.payload)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(created)
+    }
+
+    /// Unclaimed timers due before `until`, soonest first. Used to fill the
+    /// scheduler's in-memory wakeup queue.
+    pub async fn find_upcoming(
+        &self,
+        until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<(String, DateTime<Utc>)>, DbError> {
+        let rows: Vec<(String, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT id, fire_at FROM timer_entity
+            WHERE fire_at <= $1 AND (locked_until IS NULL OR locked_until < NOW())
+            ORDER BY fire_at
+            LIMIT $2
+            "#,
+        )
+        .bind(until)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Claim up to `limit` due timers for `instance_id`, holding each for
+    /// `lease`. Rows locked by a concurrent claim are skipped, and timers
+    /// whose lease expired (the claiming instance died) are claimable again.
+    pub async fn claim_due(
+        &self,
+        instance_id: &str,
+        now: DateTime<Utc>,
+        lease: Duration,
+        limit: i64,
+    ) -> Result<Vec<TimerEntity>, DbError> {
+        let timers = sqlx::query_as::<_, TimerEntity>(&format!(
+            r#"
+            UPDATE timer_entity
+            SET locked_by = $1, locked_until = $3, updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM timer_entity
+                WHERE fire_at <= $2 AND (locked_until IS NULL OR locked_until < $2)
+                ORDER BY fire_at
+                LIMIT $4
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            TIMER_COLUMNS
+        ))
+        .bind(instance_id)
+        .bind(now)
+        .bind(now + lease)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(timers)
+    }
+
+    /// Remove a fired timer. Only the instance holding the lease may do so.
+    pub async fn complete(&self, id: &str, instance_id: &str) -> Result<bool, DbError> {
+        let result = sqlx::query("DELETE FROM timer_entity WHERE id = $1 AND locked_by = $2")
+            .bind(id)
+            .bind(instance_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Release a timer that failed to fire and reschedule it.
+    pub async fn release(
+        &self,
+        id: &str,
+        instance_id: &str,
+        retry_at: DateTime<Utc>,
+    ) -> Result<bool, DbError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE timer_entity
+            SET fire_at = $3, attempts = attempts + 1,
+                locked_by = NULL, locked_until = NULL, updated_at = NOW()
+            WHERE id = $1 AND locked_by = $2
+            "#,
+        )
+        .bind(id)
+        .bind(instance_id)
+        .bind(retry_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Move a claimed timer to `fire_at` and release it, without counting
+    /// an attempt.
+    pub async fn reschedule(
+        &self,
+        id: &str,
+        instance_id: &str,
+        fire_at: DateTime<Utc>,
+    ) -> Result<bool, DbError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE timer_entity
+            SET fire_at = $3, locked_by = NULL, locked_until = NULL, updated_at = NOW()
+            WHERE id = $1 AND locked_by = $2
+            "#,
+        )
+        .bind(id)
+        .bind(instance_id)
+        .bind(fire_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Cancel a timer.
+    pub async fn delete(&self, id: &str) -> Result<bool, DbError> {
+        let result = sqlx::query("DELETE FROM timer_entity WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Cancel all timers for a workflow.
+    pub async fn delete_by_workflow(&self, workflow_id: &str) -> Result<u64, DbError> {
+        let result = sqlx::query("DELETE FROM timer_entity WHERE workflow_id = $1")
+            .bind(workflow_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
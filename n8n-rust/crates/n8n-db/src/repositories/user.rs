@@ -3,7 +3,7 @@
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::entities::{Role, User};
+use crate::entities::{Role, User, UserSettings};
 use crate::error::DbError;
 
 /// Repository for user operations.
@@ -93,6 +93,37 @@ impl UserRepository {
         Ok(created)
     }
 
+    /// Change a user's settings with `change`, atomically. Fails with
+    /// [`DbError::NotFound`] for an unknown user; an error from `change`
+    /// leaves the settings untouched.
+    pub async fn modify_settings<T>(
+        &self,
+        id: Uuid,
+        change: impl FnOnce(&mut UserSettings) -> Result<T, DbError>,
+    ) -> Result<T, DbError> {
+        let mut tx = self.pool.begin().await?;
+
+        let settings = sqlx::query_scalar::<_, Option<sqlx::types::Json<UserSettings>>>(
+            r#"SELECT settings FROM "user" WHERE id = $1 FOR UPDATE"#,
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(DbError::NotFound)?;
+        let mut settings = settings.map(|s| s.0).unwrap_or_default();
+
+        let result = change(&mut settings)?;
+
+        sqlx::query(r#"UPDATE "user" SET settings = $2, updated_at = NOW() WHERE id = $1"#)
+            .bind(id)
+            .bind(sqlx::types::Json(&settings))
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(result)
+    }
+
     /// Delete a user.
     pub async fn delete(&self, id: Uuid) -> Result<bool, DbError> {
         let result = sqlx::query(r#"DELETE FROM "user" WHERE id = $1"#)
@@ -1,6 +1,9 @@
 //! Variables repository - CRUD operations for variables.
 
+use async_trait::async_trait;
+use n8n_core::VariableSource;
 use sqlx::PgPool;
+use std::collections::HashMap;
 
 use crate::entities::{InsertVariable, Variable};
 use crate::error::DbError;
@@ -73,7 +76,26 @@ impl VariablesRepository {
         Ok(vars)
     }
 
-    /// Create a new variable.
+    /// Variables visible in `project_id`, or the global ones for `None`:
+    /// a project variable replaces the global variable of the same key.
+    pub async fn resolve(&self, project_id: Option<&str>) -> Result<Vec<Variable>, DbError> {
+        let vars = sqlx::query_as::<_, Variable>(
+            r#"
+            SELECT DISTINCT ON (key) id, key, type as variable_type, value, project_id
+            FROM variables
+            WHERE project_id IS NULL OR project_id = $1
+            ORDER BY key, project_id NULLS LAST
+            "#,
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(vars)
+    }
+
+    /// Create a new variable. Fails with [`DbError::DuplicateKey`] when its
+    /// project, or the global scope, has a variable with the same key.
     pub async fn create(&self, var: &InsertVariable) -> Result<Variable, DbError> {
         let created = sqlx::query_as::<_, Variable>(
             r#"
@@ -88,11 +110,61 @@ impl VariablesRepository {
         .bind(&var.value)
         .bind(&var.project_id)
         .fetch_one(&self.pool)
-        .await?;
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                let scope = match &var.project_id {
+                    Some(project_id) => format!("project {}", project_id),
+                    None => "the global scope".to_string(),
+                };
+                DbError::DuplicateKey(format!("Variable '{}' already exists in {}", var.key, scope))
+            }
+            e => e.into(),
+        })?;
 
         Ok(created)
     }
 
+    /// Insert `vars` in one statement. Returns how many were inserted.
+    pub async fn insert_many(&self, vars: &[InsertVariable]) -> Result<u64, DbError> {
+        self.bulk_write(vars, "").await
+    }
+
+    /// Insert `vars`, replacing those whose ID exists, in one statement.
+    /// Returns how many were inserted or replaced.
+    pub async fn upsert_many(&self, vars: &[InsertVariable]) -> Result<u64, DbError> {
+        let on_conflict = r#"
+            ON CONFLICT (id) DO UPDATE
+            SET key = EXCLUDED.key, type = EXCLUDED.type, value = EXCLUDED.value,
+                project_id = EXCLUDED.project_id
+        "#;
+        self.bulk_write(vars, on_conflict).await
+    }
+
+    async fn bulk_write(&self, vars: &[InsertVariable], on_conflict: &str) -> Result<u64, DbError> {
+        if vars.is_empty() {
+            return Ok(0);
+        }
+        let sql = format!(
+            r#"
+            INSERT INTO variables (id, key, type, value, project_id)
+            SELECT * FROM UNNEST($1::varchar[], $2::text[], $3::text[], $4::text[], $5::varchar[])
+            {}
+            "#,
+            on_conflict
+        );
+        let result = sqlx::query(&sql)
+            .bind(vars.iter().map(|v| v.id.as_str()).collect::<Vec<_>>())
+            .bind(vars.iter().map(|v| v.key.as_str()).collect::<Vec<_>>())
+            .bind(vars.iter().map(|v| v.variable_type.as_str()).collect::<Vec<_>>())
+            .bind(vars.iter().map(|v| v.value.as_str()).collect::<Vec<_>>())
+            .bind(vars.iter().map(|v| v.project_id.as_deref()).collect::<Vec<_>>())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Update a variable.
     pub async fn update(&self, id: &str, value: &str) -> Result<Variable, DbError> {
         let updated = sqlx::query_as::<_, Variable>(
@@ -120,3 +192,15 @@ impl VariablesRepository {
         Ok(result.rows_affected() > 0)
     }
 }
+
+/// Workflows see the global variables and their project's.
+#[async_trait]
+impl VariableSource for VariablesRepository {
+    async fn variables(
+        &self,
+        project_id: Option<&str>,
+    ) -> Result<HashMap<String, String>, String> {
+        let vars = self.resolve(project_id).await.map_err(|e| e.to_string())?;
+        Ok(vars.into_iter().map(|v| (v.key, v.value)).collect())
+    }
+}
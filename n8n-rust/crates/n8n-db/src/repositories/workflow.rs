@@ -1,10 +1,13 @@
 //! Workflow repository - CRUD operations for workflows.
 
-use sqlx::PgPool;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use n8n_core::ProjectLookup;
+use sqlx::{PgConnection, PgExecutor, PgPool};
 
 use crate::entities::{
-    InsertWorkflow, SharedWorkflow, UpdateWorkflow, WorkflowEntity, WorkflowHistory,
-    WorkflowSharingRole, WorkflowTagMapping,
+    generate_version_id, InsertWorkflow, SharedWorkflow, UpdateWorkflow, WorkflowEntity,
+    WorkflowHistory, WorkflowSharingRole, WorkflowTagMapping,
 };
 use crate::error::DbError;
 
@@ -23,7 +26,7 @@ impl WorkflowRepository {
     pub async fn find_by_id(&self, id: &str) -> Result<Option<WorkflowEntity>, DbError> {
         let workflow = sqlx::query_as::<_, WorkflowEntity>(
             r#"
-            SELECT id, name, description, active, is_archived, nodes, connections,
+            SELECT id, name, description, active, is_archived, deleted_at, nodes, connections,
                    settings, static_data, meta, pin_data, version_id, active_version_id,
                    version_counter, trigger_count, parent_folder_id, created_at, updated_at
             FROM workflow_entity
@@ -41,7 +44,7 @@ impl WorkflowRepository {
     pub async fn find_by_name(&self, name: &str) -> Result<Option<WorkflowEntity>, DbError> {
         let workflow = sqlx::query_as::<_, WorkflowEntity>(
             r#"
-            SELECT id, name, description, active, is_archived, nodes, connections,
+            SELECT id, name, description, active, is_archived, deleted_at, nodes, connections,
                    settings, static_data, meta, pin_data, version_id, active_version_id,
                    version_counter, trigger_count, parent_folder_id, created_at, updated_at
             FROM workflow_entity
@@ -55,11 +58,11 @@ impl WorkflowRepository {
         Ok(workflow)
     }
 
-    /// List all workflows.
+    /// List all workflows, archived ones only with `include_archived`.
     pub async fn find_all(&self, include_archived: bool) -> Result<Vec<WorkflowEntity>, DbError> {
         let query = if include_archived {
             r#"
-            SELECT id, name, description, active, is_archived, nodes, connections,
+            SELECT id, name, description, active, is_archived, deleted_at, nodes, connections,
                    settings, static_data, meta, pin_data, version_id, active_version_id,
                    version_counter, trigger_count, parent_folder_id, created_at, updated_at
             FROM workflow_entity
@@ -67,7 +70,7 @@ impl WorkflowRepository {
             "#
         } else {
             r#"
-            SELECT id, name, description, active, is_archived, nodes, connections,
+            SELECT id, name, description, active, is_archived, deleted_at, nodes, connections,
                    settings, static_data, meta, pin_data, version_id, active_version_id,
                    version_counter, trigger_count, parent_folder_id, created_at, updated_at
             FROM workflow_entity
@@ -83,11 +86,40 @@ impl WorkflowRepository {
         Ok(workflows)
     }
 
+    /// Unarchived workflows whose name, tag names, description or node
+    /// types contain words starting with every word of `query`, best
+    /// matches first. Matches in the name rank highest, then tags,
+    /// description and node types. Uses the GIN index on `search_vector`.
+    pub async fn search(&self, query: &str) -> Result<Vec<WorkflowEntity>, DbError> {
+        // Each word of the query, split as the indexed text is, becomes a
+        // quoted prefix term, so user input cannot inject tsquery syntax.
+        let workflows = sqlx::query_as::<_, WorkflowEntity>(
+            r#"
+            WITH terms AS (
+                SELECT to_tsquery('simple', string_agg(quote_literal(word) || ':*', ' & ')) AS q
+                FROM unnest(tsvector_to_array(to_tsvector('simple', translate($1, '.-', '  '))))
+                    AS word
+            )
+            SELECT id, name, description, active, is_archived, deleted_at, nodes, connections,
+                   settings, static_data, meta, pin_data, version_id, active_version_id,
+                   version_counter, trigger_count, parent_folder_id, created_at, updated_at
+            FROM workflow_entity, terms
+            WHERE is_archived = false AND search_vector @@ terms.q
+            ORDER BY ts_rank(search_vector, terms.q) DESC, updated_at DESC
+            "#,
+        )
+        .bind(query)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(workflows)
+    }
+
     /// List active workflows.
     pub async fn find_active(&self) -> Result<Vec<WorkflowEntity>, DbError> {
         let workflows = sqlx::query_as::<_, WorkflowEntity>(
             r#"
-            SELECT id, name, description, active, is_archived, nodes, connections,
+            SELECT id, name, description, active, is_archived, deleted_at, nodes, connections,
                    settings, static_data, meta, pin_data, version_id, active_version_id,
                    version_counter, trigger_count, parent_folder_id, created_at, updated_at
             FROM workflow_entity
@@ -105,7 +137,7 @@ impl WorkflowRepository {
     pub async fn find_by_folder(&self, folder_id: &str) -> Result<Vec<WorkflowEntity>, DbError> {
         let workflows = sqlx::query_as::<_, WorkflowEntity>(
             r#"
-            SELECT id, name, description, active, is_archived, nodes, connections,
+            SELECT id, name, description, active, is_archived, deleted_at, nodes, connections,
                    settings, static_data, meta, pin_data, version_id, active_version_id,
                    version_counter, trigger_count, parent_folder_id, created_at, updated_at
             FROM workflow_entity
@@ -122,6 +154,14 @@ impl WorkflowRepository {
 
     /// Create a new workflow.
     pub async fn create(&self, workflow: &InsertWorkflow) -> Result<WorkflowEntity, DbError> {
+        Self::create_in(&self.pool, workflow).await
+    }
+
+    /// [`Self::create`] on `executor`.
+    pub async fn create_in<'e>(
+        executor: impl PgExecutor<'e>,
+        workflow: &InsertWorkflow,
+    ) -> Result<WorkflowEntity, DbError> {
         let created = sqlx::query_as::<_, WorkflowEntity>(
             r#"
             INSERT INTO workflow_entity (
@@ -129,7 +169,7 @@ impl WorkflowRepository {
                 static_data, meta, pin_data, version_id, parent_folder_id
             )
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-            RETURNING id, name, description, active, is_archived, nodes, connections,
+            RETURNING id, name, description, active, is_archived, deleted_at, nodes, connections,
                       settings, static_data, meta, pin_data, version_id, active_version_id,
                       version_counter, trigger_count, parent_folder_id, created_at, updated_at
             "#,
@@ -145,17 +185,123 @@ impl WorkflowRepository {
         .bind(&workflow.pin_data)
         .bind(&workflow.version_id)
         .bind(&workflow.parent_folder_id)
-        .fetch_one(&self.pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(created)
     }
 
-    /// Update a workflow.
-    pub async fn update(&self, id: &str, update: &UpdateWorkflow) -> Result<WorkflowEntity, DbError> {
+    /// Insert `workflows` in one statement. Returns how many were inserted.
+    pub async fn insert_many(&self, workflows: &[InsertWorkflow]) -> Result<u64, DbError> {
+        Self::insert_many_in(&self.pool, workflows).await
+    }
+
+    /// [`Self::insert_many`] on `executor`.
+    pub async fn insert_many_in<'e>(
+        executor: impl PgExecutor<'e>,
+        workflows: &[InsertWorkflow],
+    ) -> Result<u64, DbError> {
+        Self::bulk_write(executor, workflows, "").await
+    }
+
+    /// Insert `workflows`, replacing those whose ID exists, in one
+    /// statement. Returns how many were inserted or replaced.
+    pub async fn upsert_many(&self, workflows: &[InsertWorkflow]) -> Result<u64, DbError> {
+        Self::upsert_many_in(&self.pool, workflows).await
+    }
+
+    /// [`Self::upsert_many`] on `executor`.
+    pub async fn upsert_many_in<'e>(
+        executor: impl PgExecutor<'e>,
+        workflows: &[InsertWorkflow],
+    ) -> Result<u64, DbError> {
+        Self::bulk_write(
+            executor,
+            workflows,
+            r#"
+            ON CONFLICT (id) DO UPDATE
+            SET name = EXCLUDED.name, description = EXCLUDED.description,
+                nodes = EXCLUDED.nodes, connections = EXCLUDED.connections,
+                settings = EXCLUDED.settings, static_data = EXCLUDED.static_data,
+                meta = EXCLUDED.meta, pin_data = EXCLUDED.pin_data,
+                version_id = EXCLUDED.version_id, parent_folder_id = EXCLUDED.parent_folder_id,
+                updated_at = NOW()
+            "#,
+        )
+        .await
+    }
+
+    /// Write `workflows` with one `INSERT ... SELECT FROM UNNEST`, binding a
+    /// column array per field, so the statement has the same 11 parameters
+    /// however many rows it writes.
+    async fn bulk_write<'e>(
+        executor: impl PgExecutor<'e>,
+        workflows: &[InsertWorkflow],
+        on_conflict: &str,
+    ) -> Result<u64, DbError> {
+        if workflows.is_empty() {
+            return Ok(0);
+        }
+        let json = |field: fn(&InsertWorkflow) -> Option<&serde_json::Value>| {
+            workflows.iter().map(|w| field(w).cloned()).collect::<Vec<_>>()
+        };
+        let sql = format!(
+            r#"
+            INSERT INTO workflow_entity (
+                id, name, description, nodes, connections, settings,
+                static_data, meta, pin_data, version_id, parent_folder_id
+            )
+            SELECT * FROM UNNEST(
+                $1::varchar[], $2::varchar[], $3::text[], $4::jsonb[], $5::jsonb[], $6::jsonb[],
+                $7::jsonb[], $8::jsonb[], $9::jsonb[], $10::varchar[], $11::varchar[]
+            )
+            {}
+            "#,
+            on_conflict
+        );
+        let result = sqlx::query(&sql)
+            .bind(workflows.iter().map(|w| w.id.as_str()).collect::<Vec<_>>())
+            .bind(workflows.iter().map(|w| w.name.as_str()).collect::<Vec<_>>())
+            .bind(workflows.iter().map(|w| w.description.as_deref()).collect::<Vec<_>>())
+            .bind(workflows.iter().map(|w| w.nodes.clone()).collect::<Vec<_>>())
+            .bind(workflows.iter().map(|w| w.connections.clone()).collect::<Vec<_>>())
+            .bind(json(|w| w.settings.as_ref()))
+            .bind(json(|w| w.static_data.as_ref()))
+            .bind(json(|w| w.meta.as_ref()))
+            .bind(json(|w| w.pin_data.as_ref()))
+            .bind(workflows.iter().map(|w| w.version_id.as_str()).collect::<Vec<_>>())
+            .bind(workflows.iter().map(|w| w.parent_folder_id.as_deref()).collect::<Vec<_>>())
+            .execute(executor)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Update a workflow if it is still at `expected_version_id`, giving it
+    /// a new version ID (`update.version_id`, or a generated one).
+    ///
+    /// Fails with [`DbError::VersionConflict`] when another update came
+    /// first, so concurrent editors cannot overwrite each other.
+    pub async fn update(
+        &self,
+        id: &str,
+        expected_version_id: &str,
+        update: &UpdateWorkflow,
+    ) -> Result<WorkflowEntity, DbError> {
+        let mut conn = self.pool.acquire().await?;
+        Self::update_in(&mut conn, id, expected_version_id, update).await
+    }
+
+    /// [`Self::update`] on `conn`.
+    pub async fn update_in(
+        conn: &mut PgConnection,
+        id: &str,
+        expected_version_id: &str,
+        update: &UpdateWorkflow,
+    ) -> Result<WorkflowEntity, DbError> {
         // Build dynamic update query
-        let mut set_clauses = Vec::new();
-        let mut param_idx = 2; // $1 is id
+        let mut set_clauses = vec!["version_id = $3".to_string()];
+        let mut param_idx = 4; // $1 is id, $2 the expected version, $3 the new one
 
         if update.name.is_some() {
             set_clauses.push(format!("name = ${}", param_idx));
@@ -186,23 +332,23 @@ impl WorkflowRepository {
         set_clauses.push("version_counter = version_counter + 1".to_string());
         set_clauses.push("updated_at = NOW()".to_string());
 
-        if set_clauses.is_empty() {
-            return self.find_by_id(id).await?.ok_or(DbError::NotFound);
-        }
-
         let query = format!(
             r#"
             UPDATE workflow_entity
             SET {}
-            WHERE id = $1
-            RETURNING id, name, description, active, is_archived, nodes, connections,
+            WHERE id = $1 AND version_id = $2
+            RETURNING id, name, description, active, is_archived, deleted_at, nodes, connections,
                       settings, static_data, meta, pin_data, version_id, active_version_id,
                       version_counter, trigger_count, parent_folder_id, created_at, updated_at
             "#,
             set_clauses.join(", ")
         );
 
-        let mut query = sqlx::query_as::<_, WorkflowEntity>(&query).bind(id);
+        let version_id = update.version_id.clone().unwrap_or_else(generate_version_id);
+        let mut query = sqlx::query_as::<_, WorkflowEntity>(&query)
+            .bind(id)
+            .bind(expected_version_id)
+            .bind(version_id);
 
         if let Some(ref name) = update.name {
             query = query.bind(name);
@@ -223,16 +369,77 @@ impl WorkflowRepository {
             query = query.bind(conns);
         }
 
-        let updated = query.fetch_one(&self.pool).await?;
+        if let Some(updated) = query.fetch_optional(&mut *conn).await? {
+            return Ok(updated);
+        }
+        let current: Option<String> = sqlx::query_scalar(
+            "SELECT version_id FROM workflow_entity WHERE id = $1 AND is_archived = false",
+        )
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+        match current {
+            Some(current) => Err(DbError::VersionConflict {
+                expected: expected_version_id.to_string(),
+                current,
+            }),
+            None => Err(DbError::NotFound),
+        }
+    }
+
+    /// [`Self::create`], recording the new workflow as the first version in
+    /// its history in the same transaction.
+    pub async fn create_with_history(
+        &self,
+        workflow: &InsertWorkflow,
+    ) -> Result<WorkflowEntity, DbError> {
+        let mut tx = self.pool.begin().await?;
+        let created = Self::create_in(&mut *tx, workflow).await?;
+        Self::create_history_in(&mut *tx, &Self::version_of(&created, None, None)).await?;
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    /// [`Self::update`], recording the updated workflow in its history in
+    /// the same transaction, as saved over `expected_version_id` by the
+    /// patch `operations`, if a patch saved it. A refused update records
+    /// nothing.
+    pub async fn update_with_history(
+        &self,
+        id: &str,
+        expected_version_id: &str,
+        update: &UpdateWorkflow,
+        operations: Option<serde_json::Value>,
+    ) -> Result<WorkflowEntity, DbError> {
+        let mut tx = self.pool.begin().await?;
+        let updated = Self::update_in(&mut tx, id, expected_version_id, update).await?;
+        let version = Self::version_of(&updated, Some(expected_version_id), operations);
+        Self::create_history_in(&mut *tx, &version).await?;
+        tx.commit().await?;
         Ok(updated)
     }
 
-    /// Archive a workflow (soft delete).
+    /// History entry of `workflow` at its current version.
+    fn version_of(
+        workflow: &WorkflowEntity,
+        previous_version_id: Option<&str>,
+        operations: Option<serde_json::Value>,
+    ) -> WorkflowHistory {
+        let mut version = WorkflowHistory::from_workflow(workflow, &[]);
+        version.version_id = workflow.version_id.clone();
+        version.previous_version_id = previous_version_id.map(str::to_string);
+        version.operations = operations;
+        version
+    }
+
+    /// Archive a workflow (soft delete). It is deactivated and hidden from
+    /// lookups until [`Self::unarchive`]d or purged.
     pub async fn archive(&self, id: &str) -> Result<bool, DbError> {
         let result = sqlx::query(
             r#"
             UPDATE workflow_entity
-            SET is_archived = true, updated_at = NOW()
+            SET is_archived = true, active = false,
+                deleted_at = COALESCE(deleted_at, NOW()), updated_at = NOW()
             WHERE id = $1
             "#,
         )
@@ -243,11 +450,45 @@ impl WorkflowRepository {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Restore an archived workflow. It stays inactive.
+    pub async fn unarchive(&self, id: &str) -> Result<bool, DbError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE workflow_entity
+            SET is_archived = false, deleted_at = NULL, updated_at = NOW()
+            WHERE id = $1 AND is_archived = true
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Permanently delete workflows archived before `cutoff`. Returns how
+    /// many were deleted.
+    pub async fn purge_deleted_before(&self, cutoff: DateTime<Utc>) -> Result<u64, DbError> {
+        let result = sqlx::query(
+            "DELETE FROM workflow_entity WHERE is_archived = true AND deleted_at < $1",
+        )
+        .bind(cutoff)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Permanently delete a workflow.
     pub async fn delete(&self, id: &str) -> Result<bool, DbError> {
+        Self::delete_in(&self.pool, id).await
+    }
+
+    /// [`Self::delete`] on `executor`.
+    pub async fn delete_in<'e>(executor: impl PgExecutor<'e>, id: &str) -> Result<bool, DbError> {
         let result = sqlx::query("DELETE FROM workflow_entity WHERE id = $1")
             .bind(id)
-            .execute(&self.pool)
+            .execute(executor)
             .await?;
 
         Ok(result.rows_affected() > 0)
@@ -291,17 +532,26 @@ impl WorkflowRepository {
 
     /// Create a history entry for a workflow.
     pub async fn create_history(&self, history: &WorkflowHistory) -> Result<WorkflowHistory, DbError> {
+        Self::create_history_in(&self.pool, history).await
+    }
+
+    /// [`Self::create_history`] on `executor`.
+    pub async fn create_history_in<'e>(
+        executor: impl PgExecutor<'e>,
+        history: &WorkflowHistory,
+    ) -> Result<WorkflowHistory, DbError> {
         let nodes_json = serde_json::to_value(&history.nodes)?;
 
         let created = sqlx::query_as::<_, WorkflowHistory>(
             r#"
             INSERT INTO workflow_history (
                 version_id, workflow_id, nodes, connections, authors,
-                name, description, autosaved
+                name, description, autosaved, previous_version_id, operations
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             RETURNING version_id, workflow_id, nodes, connections, authors,
-                      name, description, autosaved, created_at, updated_at
+                      name, description, autosaved, previous_version_id, operations,
+                      created_at, updated_at
             "#,
         )
         .bind(&history.version_id)
@@ -312,7 +562,9 @@ impl WorkflowRepository {
         .bind(&history.name)
         .bind(&history.description)
         .bind(history.autosaved)
-        .fetch_one(&self.pool)
+        .bind(&history.previous_version_id)
+        .bind(&history.operations)
+        .fetch_one(executor)
         .await?;
 
         Ok(created)
@@ -323,7 +575,8 @@ impl WorkflowRepository {
         let history = sqlx::query_as::<_, WorkflowHistory>(
             r#"
             SELECT version_id, workflow_id, nodes, connections, authors,
-                   name, description, autosaved, created_at, updated_at
+                   name, description, autosaved, previous_version_id, operations,
+                   created_at, updated_at
             FROM workflow_history
             WHERE workflow_id = $1
             ORDER BY created_at DESC
@@ -343,7 +596,8 @@ impl WorkflowRepository {
         let history = sqlx::query_as::<_, WorkflowHistory>(
             r#"
             SELECT version_id, workflow_id, nodes, connections, authors,
-                   name, description, autosaved, created_at, updated_at
+                   name, description, autosaved, previous_version_id, operations,
+                   created_at, updated_at
             FROM workflow_history
             WHERE version_id = $1
             "#,
@@ -365,6 +619,16 @@ impl WorkflowRepository {
         workflow_id: &str,
         project_id: &str,
         role: WorkflowSharingRole,
+    ) -> Result<SharedWorkflow, DbError> {
+        Self::share_in(&self.pool, workflow_id, project_id, role).await
+    }
+
+    /// [`Self::share`] on `executor`.
+    pub async fn share_in<'e>(
+        executor: impl PgExecutor<'e>,
+        workflow_id: &str,
+        project_id: &str,
+        role: WorkflowSharingRole,
     ) -> Result<SharedWorkflow, DbError> {
         let shared = sqlx::query_as::<_, SharedWorkflow>(
             r#"
@@ -377,7 +641,7 @@ impl WorkflowRepository {
         .bind(workflow_id)
         .bind(project_id)
         .bind(role.to_string())
-        .fetch_one(&self.pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(shared)
@@ -385,12 +649,21 @@ impl WorkflowRepository {
 
     /// Unshare a workflow from a project.
     pub async fn unshare(&self, workflow_id: &str, project_id: &str) -> Result<bool, DbError> {
+        Self::unshare_in(&self.pool, workflow_id, project_id).await
+    }
+
+    /// [`Self::unshare`] on `executor`.
+    pub async fn unshare_in<'e>(
+        executor: impl PgExecutor<'e>,
+        workflow_id: &str,
+        project_id: &str,
+    ) -> Result<bool, DbError> {
         let result = sqlx::query(
             "DELETE FROM shared_workflow WHERE workflow_id = $1 AND project_id = $2",
         )
         .bind(workflow_id)
         .bind(project_id)
-        .execute(&self.pool)
+        .execute(executor)
         .await?;
 
         Ok(result.rows_affected() > 0)
@@ -412,12 +685,34 @@ impl WorkflowRepository {
         Ok(shared)
     }
 
+    /// ID of the project owning a workflow.
+    pub async fn owner_project(&self, workflow_id: &str) -> Result<Option<String>, DbError> {
+        let project_id = sqlx::query_scalar::<_, String>(
+            "SELECT project_id FROM shared_workflow WHERE workflow_id = $1 AND role = $2",
+        )
+        .bind(workflow_id)
+        .bind(WorkflowSharingRole::Owner.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(project_id)
+    }
+
     // =========================================================================
     // Tags
     // =========================================================================
 
     /// Add a tag to a workflow.
     pub async fn add_tag(&self, workflow_id: &str, tag_id: &str) -> Result<(), DbError> {
+        Self::add_tag_in(&self.pool, workflow_id, tag_id).await
+    }
+
+    /// [`Self::add_tag`] on `executor`.
+    pub async fn add_tag_in<'e>(
+        executor: impl PgExecutor<'e>,
+        workflow_id: &str,
+        tag_id: &str,
+    ) -> Result<(), DbError> {
         sqlx::query(
             r#"
             INSERT INTO workflow_tag_mapping (workflow_id, tag_id)
@@ -427,7 +722,7 @@ impl WorkflowRepository {
         )
         .bind(workflow_id)
         .bind(tag_id)
-        .execute(&self.pool)
+        .execute(executor)
         .await?;
 
         Ok(())
@@ -435,12 +730,21 @@ impl WorkflowRepository {
 
     /// Remove a tag from a workflow.
     pub async fn remove_tag(&self, workflow_id: &str, tag_id: &str) -> Result<bool, DbError> {
+        Self::remove_tag_in(&self.pool, workflow_id, tag_id).await
+    }
+
+    /// [`Self::remove_tag`] on `executor`.
+    pub async fn remove_tag_in<'e>(
+        executor: impl PgExecutor<'e>,
+        workflow_id: &str,
+        tag_id: &str,
+    ) -> Result<bool, DbError> {
         let result = sqlx::query(
             "DELETE FROM workflow_tag_mapping WHERE workflow_id = $1 AND tag_id = $2",
         )
         .bind(workflow_id)
         .bind(tag_id)
-        .execute(&self.pool)
+        .execute(executor)
         .await?;
 
         Ok(result.rows_affected() > 0)
@@ -462,11 +766,21 @@ impl WorkflowRepository {
     pub async fn set_tags(&self, workflow_id: &str, tag_ids: &[String]) -> Result<(), DbError> {
         // Start transaction
         let mut tx = self.pool.begin().await?;
+        Self::set_tags_in(&mut tx, workflow_id, tag_ids).await?;
+        tx.commit().await?;
+        Ok(())
+    }
 
+    /// [`Self::set_tags`] on `conn`, which should be in a transaction.
+    pub async fn set_tags_in(
+        conn: &mut PgConnection,
+        workflow_id: &str,
+        tag_ids: &[String],
+    ) -> Result<(), DbError> {
         // Remove existing tags
         sqlx::query("DELETE FROM workflow_tag_mapping WHERE workflow_id = $1")
             .bind(workflow_id)
-            .execute(&mut *tx)
+            .execute(&mut *conn)
             .await?;
 
         // Add new tags
@@ -476,17 +790,81 @@ impl WorkflowRepository {
             )
             .bind(workflow_id)
             .bind(tag_id)
-            .execute(&mut *tx)
+            .execute(&mut *conn)
             .await?;
         }
 
+        Ok(())
+    }
+
+    /// Give a cloned workflow its source's tags and an owner: `project_id`
+    /// if given, otherwise the source's owning project.
+    pub async fn copy_tags_and_sharing(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        project_id: Option<&str>,
+    ) -> Result<(), DbError> {
+        let mut tx = self.pool.begin().await?;
+        Self::copy_tags_and_sharing_in(&mut tx, source_id, target_id, project_id).await?;
         tx.commit().await?;
         Ok(())
     }
+
+    /// [`Self::copy_tags_and_sharing`] on `conn`, which should be in a
+    /// transaction.
+    pub async fn copy_tags_and_sharing_in(
+        conn: &mut PgConnection,
+        source_id: &str,
+        target_id: &str,
+        project_id: Option<&str>,
+    ) -> Result<(), DbError> {
+        sqlx::query(
+            r#"
+            INSERT INTO workflow_tag_mapping (workflow_id, tag_id)
+            SELECT $2, tag_id FROM workflow_tag_mapping WHERE workflow_id = $1
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *conn)
+        .await?;
+
+        let owner = WorkflowSharingRole::Owner.to_string();
+        match project_id {
+            Some(project_id) => {
+                sqlx::query(
+                    "INSERT INTO shared_workflow (workflow_id, project_id, role) VALUES ($1, $2, $3)",
+                )
+                .bind(target_id)
+                .bind(project_id)
+                .bind(&owner)
+                .execute(&mut *conn)
+                .await?;
+            }
+            None => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO shared_workflow (workflow_id, project_id, role)
+                    SELECT $2, project_id, role FROM shared_workflow
+                    WHERE workflow_id = $1 AND role = $3
+                    "#,
+                )
+                .bind(source_id)
+                .bind(target_id)
+                .bind(&owner)
+                .execute(&mut *conn)
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl WorkflowSharingRole {
-    fn to_string(&self) -> String {
+    pub(crate) fn to_string(&self) -> String {
         match self {
             Self::Owner => "workflow:owner".to_string(),
             Self::Editor => "workflow:editor".to_string(),
@@ -494,3 +872,11 @@ impl WorkflowSharingRole {
         }
     }
 }
+
+/// Workflows belong to their owning project.
+#[async_trait]
+impl ProjectLookup for WorkflowRepository {
+    async fn project_of(&self, workflow_id: &str) -> Result<Option<String>, String> {
+        self.owner_project(workflow_id).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,142 @@
+//! Workflow statistics repository - execution counts per workflow.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use n8n_core::{StatisticsBackend, StatisticsName, WorkflowStatistic};
+use sqlx::PgPool;
+
+use crate::entities::WorkflowStatisticsEntity;
+use crate::error::DbError;
+
+/// Repository for workflow statistics.
+#[derive(Clone)]
+pub struct WorkflowStatisticsRepository {
+    pool: PgPool,
+}
+
+impl WorkflowStatisticsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Statistics of a workflow.
+    pub async fn find_by_workflow(
+        &self,
+        workflow_id: &str,
+    ) -> Result<Vec<WorkflowStatisticsEntity>, DbError> {
+        let statistics = sqlx::query_as::<_, WorkflowStatisticsEntity>(
+            r#"
+            SELECT workflow_id, name, count, latest_event FROM workflow_statistics
+            WHERE workflow_id = $1
+            ORDER BY name
+            "#,
+        )
+        .bind(workflow_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(statistics)
+    }
+
+    /// Add one to a statistic in a single statement, creating it if
+    /// needed. Returns the new count.
+    pub async fn increment(
+        &self,
+        workflow_id: &str,
+        name: &str,
+        at: DateTime<Utc>,
+    ) -> Result<i32, DbError> {
+        let count: i32 = sqlx::query_scalar(
+            r#"
+            INSERT INTO workflow_statistics (workflow_id, name, count, latest_event)
+            VALUES ($1, $2, 1, $3)
+            ON CONFLICT (workflow_id, name) DO UPDATE
+            SET count = workflow_statistics.count + 1, latest_event = EXCLUDED.latest_event
+            RETURNING count
+            "#,
+        )
+        .bind(workflow_id)
+        .bind(name)
+        .bind(at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Create a statistic with a count of one unless it exists. Returns
+    /// whether it was created.
+    pub async fn insert_once(
+        &self,
+        workflow_id: &str,
+        name: &str,
+        at: DateTime<Utc>,
+    ) -> Result<bool, DbError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO workflow_statistics (workflow_id, name, count, latest_event)
+            VALUES ($1, $2, 1, $3)
+            ON CONFLICT (workflow_id, name) DO NOTHING
+            "#,
+        )
+        .bind(workflow_id)
+        .bind(name)
+        .bind(at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Remove the statistics of a deleted workflow.
+    pub async fn delete_by_workflow(&self, workflow_id: &str) -> Result<u64, DbError> {
+        let result = sqlx::query("DELETE FROM workflow_statistics WHERE workflow_id = $1")
+            .bind(workflow_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// The engine's statistics, kept in `workflow_statistics`.
+#[async_trait]
+impl StatisticsBackend for WorkflowStatisticsRepository {
+    async fn increment(
+        &self,
+        workflow_id: &str,
+        name: StatisticsName,
+        at: DateTime<Utc>,
+    ) -> Result<u64, String> {
+        let count = WorkflowStatisticsRepository::increment(self, workflow_id, name.as_str(), at)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(count.max(0) as u64)
+    }
+
+    async fn insert_once(
+        &self,
+        workflow_id: &str,
+        name: StatisticsName,
+        at: DateTime<Utc>,
+    ) -> Result<bool, String> {
+        WorkflowStatisticsRepository::insert_once(self, workflow_id, name.as_str(), at)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn for_workflow(&self, workflow_id: &str) -> Result<Vec<WorkflowStatistic>, String> {
+        let statistics = self.find_by_workflow(workflow_id).await.map_err(|e| e.to_string())?;
+        Ok(statistics
+            .into_iter()
+            .filter_map(|row| {
+                Some(WorkflowStatistic {
+                    name: row.name.parse().ok()?,
+                    workflow_id: row.workflow_id,
+                    count: row.count.max(0) as u64,
+                    latest_event: row.latest_event,
+                })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,435 @@
+//! Durable timer scheduler.
+//!
+//! Timers live in `timer_entity`, so they survive restarts and are shared by
+//! every instance connected to the database. Each instance keeps a min-heap
+//! of the timers due soon and sleeps until exactly the earliest one, instead
+//! of polling on a fixed tick; the database is re-read every
+//! `poll_interval_secs` to pick up timers scheduled by other instances.
+//!
+//! Due timers are claimed with `FOR UPDATE SKIP LOCKED` and a lease, so a
+//! timer fires on one instance only, and a timer claimed by an instance that
+//! died is fired again once its lease expires. Failed timers are retried
+//! with exponential backoff up to `max_attempts`.
+//!
+//! [`WorkflowTimerHandler`] fires a timer by executing its workflow from the
+//! timer's node, usually an `n8n-nodes-base.timerTrigger`, subject to the
+//! workflow's run window: outside it the timer is deferred to the next
+//! opening (`queue`), dropped (`skip`) or recorded as a failed execution
+//! (`reject`). Timers of a trigger paused after repeated failures
+//! ([`n8n_core::trigger_health`]) are dropped, and every run counts towards
+//! the health of the timer's node.
+//!
+//! A timer whose payload holds a [`SPILLED_REQUEST_KEY`] item, written for a
+//! webhook request spilled under backpressure, replays that item instead.
+//! One holding [`THROTTLED_ITEMS_KEY`] items, deferred by a throttle node,
+//! runs the nodes after the throttle with them.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::sync::Arc;
+use tokio::sync::{watch, Mutex, Notify};
+
+use n8n_core::run_window::{self, WindowDecision};
+use n8n_core::storage::{ExecutionStorage, WorkflowStorage};
+use n8n_core::throttle::{release_targets, THROTTLED_ITEMS_KEY};
+use n8n_core::WorkflowEngine;
+use n8n_workflow::{
+    ExecutionError, ExecutionStatus, NodeExecutionData, Run, Workflow, WorkflowExecuteMode,
+};
+
+use crate::entities::{InsertTimer, TimerEntity};
+use crate::error::DbError;
+use crate::repositories::TimerRepository;
+
+/// Payload key of a spilled webhook request's item.
+pub const SPILLED_REQUEST_KEY: &str = "spilledRequest";
+
+/// Configuration for [`TimerScheduler`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TimerSchedulerConfig {
+    /// Seconds between database reads for timers scheduled elsewhere.
+    pub poll_interval_secs: u64,
+    /// Seconds an instance holds a claimed timer before others may retry it.
+    pub lease_secs: u64,
+    /// Maximum timers claimed per wakeup.
+    pub batch_size: i64,
+    /// Failed attempts after which a timer is dropped.
+    pub max_attempts: i32,
+    /// Delay before the first retry; doubles on every further attempt.
+    pub retry_backoff_secs: u64,
+}
+
+impl Default for TimerSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 30,
+            lease_secs: 300,
+            batch_size: 100,
+            max_attempts: 5,
+            retry_backoff_secs: 10,
+        }
+    }
+}
+
+impl TimerSchedulerConfig {
+    /// When to retry a timer that has failed `attempts` times, or `None` to
+    /// give up.
+    pub fn retry_at(&self, attempts: i32, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if attempts + 1 >= self.max_attempts {
+            return None;
+        }
+        let delay = self.retry_backoff_secs.saturating_mul(1 << attempts.clamp(0, 16));
+        Some(now + chrono::Duration::seconds(delay as i64))
+    }
+}
+
+/// What happened to a fired timer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerOutcome {
+    /// The timer is finished and removed.
+    Done,
+    /// The timer should fire again at the given time. Not counted as a
+    /// failed attempt.
+    Deferred(DateTime<Utc>),
+}
+
+/// Fires timers.
+#[async_trait]
+pub trait TimerHandler: Send + Sync {
+    async fn fire(&self, timer: &TimerEntity) -> Result<TimerOutcome, String>;
+}
+
+/// In-memory min-heap of upcoming wakeups.
+#[derive(Debug, Default)]
+pub struct TimerQueue {
+    heap: BinaryHeap<Reverse<(DateTime<Utc>, String)>>,
+    ids: HashSet<String>,
+}
+
+impl TimerQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a wakeup; a timer already queued is ignored.
+    pub fn push(&mut self, id: String, fire_at: DateTime<Utc>) {
+        if self.ids.insert(id.clone()) {
+            self.heap.push(Reverse((fire_at, id)));
+        }
+    }
+
+    /// Earliest queued wakeup.
+    pub fn next_fire_at(&self) -> Option<DateTime<Utc>> {
+        self.heap.peek().map(|Reverse((at, _))| *at)
+    }
+
+    /// Remove and return every timer due at `now`.
+    pub fn pop_due(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        let mut due = Vec::new();
+        while let Some(Reverse((at, _))) = self.heap.peek() {
+            if *at > now {
+                break;
+            }
+            let Reverse((_, id)) = self.heap.pop().expect("peeked");
+            self.ids.remove(&id);
+            due.push(id);
+        }
+        due
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+/// Database-backed timer scheduler.
+pub struct TimerScheduler<H> {
+    repo: TimerRepository,
+    handler: Arc<H>,
+    config: TimerSchedulerConfig,
+    instance_id: String,
+    queue: Mutex<TimerQueue>,
+    wake: Notify,
+    /// Whether the scheduler task is consuming the queue.
+    started: watch::Sender<bool>,
+}
+
+impl<H: TimerHandler + 'static> TimerScheduler<H> {
+    pub fn new(repo: TimerRepository, handler: H, config: TimerSchedulerConfig) -> Self {
+        Self {
+            repo,
+            handler: Arc::new(handler),
+            config,
+            instance_id: format!("{}-{}", std::process::id(), uuid::Uuid::new_v4()),
+            queue: Mutex::new(TimerQueue::new()),
+            wake: Notify::new(),
+            started: watch::channel(false).0,
+        }
+    }
+
+    /// Identifier this instance claims timers under.
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// Persist a timer and wake the scheduler if it is due sooner than
+    /// anything queued.
+    pub async fn schedule(&self, timer: InsertTimer) -> Result<TimerEntity, DbError> {
+        let created = self.repo.create(&timer).await?;
+        self.queue.lock().await.push(created.id.clone(), created.fire_at);
+        self.wake.notify_one();
+        Ok(created)
+    }
+
+    /// Cancel a pending timer.
+    pub async fn cancel(&self, id: &str) -> Result<bool, DbError> {
+        // A stale heap entry only causes a wakeup that claims nothing.
+        self.repo.delete(id).await
+    }
+
+    /// Whether the task from [`Self::spawn`] has started consuming timers,
+    /// e.g. for readiness checks.
+    pub fn subscribe_started(&self) -> watch::Receiver<bool> {
+        self.started.subscribe()
+    }
+
+    /// Run the scheduler until the task is aborted.
+    pub fn spawn(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move { self.run().await })
+    }
+
+    async fn run(&self) {
+        self.started.send_replace(true);
+        let poll = std::time::Duration::from_secs(self.config.poll_interval_secs.max(1));
+        let mut next_poll = tokio::time::Instant::now();
+
+        loop {
+            if tokio::time::Instant::now() >= next_poll {
+                if let Err(e) = self.refill(poll).await {
+                    tracing::warn!(error = %e, "Loading timers failed");
+                }
+                next_poll = tokio::time::Instant::now() + poll;
+            }
+
+            let now = Utc::now();
+            let due = self.queue.lock().await.pop_due(now);
+            if !due.is_empty() {
+                if let Err(e) = self.fire_due(now).await {
+                    tracing::warn!(error = %e, "Claiming timers failed");
+                }
+            }
+
+            let mut sleep_until = next_poll;
+            if let Some(at) = self.queue.lock().await.next_fire_at() {
+                let wait = (at - Utc::now()).to_std().unwrap_or_default();
+                sleep_until = sleep_until.min(tokio::time::Instant::now() + wait);
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep_until(sleep_until) => {}
+                _ = self.wake.notified() => {}
+            }
+        }
+    }
+
+    /// Queue timers due before the next poll.
+    async fn refill(&self, poll: std::time::Duration) -> Result<(), DbError> {
+        let horizon = Utc::now() + chrono::Duration::from_std(poll).unwrap_or_default();
+        let upcoming = self.repo.find_upcoming(horizon, self.config.batch_size * 10).await?;
+        let mut queue = self.queue.lock().await;
+        for (id, fire_at) in upcoming {
+            queue.push(id, fire_at);
+        }
+        Ok(())
+    }
+
+    /// Claim and fire everything due, batch by batch.
+    async fn fire_due(&self, now: DateTime<Utc>) -> Result<(), DbError> {
+        let lease = chrono::Duration::seconds(self.config.lease_secs as i64);
+        loop {
+            let claimed = self
+                .repo
+                .claim_due(&self.instance_id, now, lease, self.config.batch_size)
+                .await?;
+            let count = claimed.len() as i64;
+
+            for timer in claimed {
+                self.fire_one(timer).await?;
+            }
+            if count < self.config.batch_size {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn fire_one(&self, timer: TimerEntity) -> Result<(), DbError> {
+        match self.handler.fire(&timer).await {
+            Ok(TimerOutcome::Done) => {
+                self.repo.complete(&timer.id, &self.instance_id).await?;
+            }
+            Ok(TimerOutcome::Deferred(fire_at)) => {
+                self.repo.reschedule(&timer.id, &self.instance_id, fire_at).await?;
+                self.queue.lock().await.push(timer.id.clone(), fire_at);
+            }
+            Err(e) => match self.config.retry_at(timer.attempts, Utc::now()) {
+                Some(retry_at) => {
+                    tracing::warn!(timer_id = %timer.id, attempt = timer.attempts + 1, error = %e, "Timer failed; retrying");
+                    self.repo.release(&timer.id, &self.instance_id, retry_at).await?;
+                    self.queue.lock().await.push(timer.id.clone(), retry_at);
+                }
+                None => {
+                    tracing::error!(timer_id = %timer.id, error = %e, "Timer failed; giving up");
+                    self.repo.complete(&timer.id, &self.instance_id).await?;
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Fires a timer by executing its workflow from the timer's node.
+///
+/// The start node receives one item with `timerId`, `scheduledFor`,
+/// `firedAt` and the timer's `payload`, or the spilled request's item.
+pub struct WorkflowTimerHandler<W, E> {
+    engine: Arc<WorkflowEngine>,
+    workflows: W,
+    executions: E,
+}
+
+impl<W: WorkflowStorage, E: ExecutionStorage> WorkflowTimerHandler<W, E> {
+    pub fn new(engine: Arc<WorkflowEngine>, workflows: W, executions: E) -> Self {
+        Self { engine, workflows, executions }
+    }
+
+    async fn save(&self, workflow: &Workflow, run: &Run) -> Result<(), String> {
+        let execution_id = uuid::Uuid::new_v4().to_string();
+        let stored = self.engine.prepare_for_storage(workflow, &execution_id, run);
+        self.executions
+            .save_execution(&execution_id, &stored)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl<W: WorkflowStorage, E: ExecutionStorage> TimerHandler for WorkflowTimerHandler<W, E> {
+    async fn fire(&self, timer: &TimerEntity) -> Result<TimerOutcome, String> {
+        let workflow = self
+            .workflows
+            .get_workflow(&timer.workflow_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Workflow not found: {}", timer.workflow_id))?;
+
+        let throttled = timer.payload.as_ref().and_then(|p| p.get(THROTTLED_ITEMS_KEY));
+        let health = self.engine.trigger_health();
+        if throttled.is_none() && health.is_paused(&workflow.id, &timer.node) {
+            tracing::info!(timer_id = %timer.id, node = %timer.node, "Timer dropped: trigger paused");
+            return Ok(TimerOutcome::Done);
+        }
+
+        match run_window::decide(&workflow, &self.engine.config().timezone, Utc::now()) {
+            WindowDecision::Run => {}
+            WindowDecision::Queue { at, reason } => {
+                tracing::info!(timer_id = %timer.id, until = %at, %reason, "Timer deferred to run window");
+                return Ok(TimerOutcome::Deferred(at));
+            }
+            WindowDecision::Skip { reason } => {
+                tracing::info!(timer_id = %timer.id, %reason, "Timer skipped outside run window");
+                return Ok(TimerOutcome::Done);
+            }
+            WindowDecision::Reject { reason, .. } => {
+                let mut run = Run::new(WorkflowExecuteMode::Trigger);
+                run.data.result_data.error = Some(ExecutionError::new(format!(
+                    "Timer {} rejected: {}",
+                    timer.id, reason
+                )));
+                run.finish(ExecutionStatus::Error);
+                self.save(&workflow, &run).await?;
+                return Ok(TimerOutcome::Done);
+            }
+        }
+
+        if let Some(serde_json::Value::Array(values)) = throttled {
+            let items = values
+                .iter()
+                .cloned()
+                .map(NodeExecutionData::from_json_value)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?;
+            let targets = release_targets(&workflow, &timer.node);
+            if targets.is_empty() {
+                return Ok(TimerOutcome::Done);
+            }
+            let run = self
+                .engine
+                .execute_partial(&workflow, targets, None, Some(items))
+                .await
+                .map_err(|e| e.to_string())?;
+            self.save(&workflow, &run).await?;
+            return Ok(TimerOutcome::Done);
+        }
+
+        let spilled = timer.payload.as_ref().and_then(|p| p.get(SPILLED_REQUEST_KEY)).cloned();
+        let item = NodeExecutionData::from_json_value(spilled.unwrap_or_else(|| {
+            serde_json::json!({
+                "timerId": timer.id,
+                "scheduledFor": timer.fire_at.to_rfc3339(),
+                "firedAt": Utc::now().to_rfc3339(),
+                "payload": timer.payload.clone().unwrap_or(serde_json::Value::Null),
+            })
+        }))
+        .map_err(|e| e.to_string())?;
+
+        let result = self
+            .engine
+            .execute_partial(&workflow, vec![timer.node.clone()], None, Some(vec![item]))
+            .await;
+        let limits = self.engine.config().trigger_health;
+        health.record(&workflow.id, &timer.node, &result, &limits);
+        let run = result.map_err(|e| e.to_string())?;
+
+        self.save(&workflow, &run).await?;
+        Ok(TimerOutcome::Done)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_pops_in_fire_order() {
+        let now = Utc::now();
+        let mut queue = TimerQueue::new();
+        queue.push("late".into(), now + chrono::Duration::seconds(60));
+        queue.push("b".into(), now - chrono::Duration::seconds(1));
+        queue.push("a".into(), now - chrono::Duration::seconds(5));
+        queue.push("a".into(), now - chrono::Duration::seconds(5));
+
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.pop_due(now), vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(queue.next_fire_at(), Some(now + chrono::Duration::seconds(60)));
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let config = TimerSchedulerConfig {
+            max_attempts: 3,
+            retry_backoff_secs: 10,
+            ..Default::default()
+        };
+        let now = Utc::now();
+        assert_eq!(config.retry_at(0, now), Some(now + chrono::Duration::seconds(10)));
+        assert_eq!(config.retry_at(1, now), Some(now + chrono::Duration::seconds(20)));
+        assert_eq!(config.retry_at(2, now), None);
+    }
+}
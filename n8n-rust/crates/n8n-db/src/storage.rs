@@ -6,24 +6,33 @@
 
 use async_trait::async_trait;
 use sqlx::PgPool;
+use std::sync::Arc;
 
+use n8n_core::cost::cost_records;
 use n8n_core::error::ExecutionEngineError;
-use n8n_core::storage::{ExecutionStorage, WorkflowStorage};
+use n8n_core::migration::MigrationRegistry;
+use n8n_core::resource_usage::ResourceUsage;
+use n8n_core::patch::PatchOperation;
+use n8n_core::storage::{ExecutionStorage, WorkflowStorage, WorkflowVersion};
 use n8n_workflow::{ExecutionStatus, Run, Workflow, WorkflowExecuteMode};
 
 use crate::entities::{
-    ExecutionData, ExecutionEntity, InsertExecution, InsertWorkflow,
-    UpdateWorkflow, WorkflowEntity,
+    BinaryDataEntity, ExecutionData, ExecutionEntity, InsertExecution, InsertWorkflow,
+    UpdateWorkflow, WorkflowEntity, WorkflowHistory,
 };
 use crate::error::DbError;
-use crate::repositories::{ExecutionRepository, WorkflowRepository};
+use crate::repositories::{BinaryDataRepository, ExecutionRepository, WorkflowRepository};
 
 // =============================================================================
 // Error Conversion
 // =============================================================================
 
-/// Convert a DbError into an ExecutionEngineError::Storage.
+/// Convert a DbError into an ExecutionEngineError::Storage, or a
+/// VersionConflict for version conflicts.
 fn db_err(e: DbError) -> ExecutionEngineError {
+    if e.is_version_conflict() {
+        return ExecutionEngineError::VersionConflict(e.to_string());
+    }
     ExecutionEngineError::Storage(e.to_string())
 }
 
@@ -160,23 +169,48 @@ fn entity_to_run(
 ///
 /// Uses [`WorkflowRepository`] under the hood to perform all DB operations,
 /// converting between the domain `Workflow` type and the persistence
-/// `WorkflowEntity` type transparently.
+/// `WorkflowEntity` type transparently. Nodes saved with an older
+/// `typeVersion` are migrated when loaded; the stored row is only rewritten
+/// on the next save.
 #[derive(Clone)]
 pub struct SqlxWorkflowStorage {
     repo: WorkflowRepository,
+    migrations: Arc<MigrationRegistry>,
 }
 
 impl SqlxWorkflowStorage {
     /// Create a new storage backed by the given connection pool.
     pub fn new(pool: PgPool) -> Self {
-        Self {
-            repo: WorkflowRepository::new(pool),
-        }
+        Self::from_repo(WorkflowRepository::new(pool))
     }
 
     /// Create from an existing repository.
     pub fn from_repo(repo: WorkflowRepository) -> Self {
-        Self { repo }
+        Self {
+            repo,
+            migrations: Arc::new(MigrationRegistry::default()),
+        }
+    }
+
+    /// Use a custom migration registry.
+    pub fn with_migrations(mut self, migrations: MigrationRegistry) -> Self {
+        self.migrations = Arc::new(migrations);
+        self
+    }
+
+    /// Convert a row and upgrade outdated nodes.
+    fn load(&self, entity: &WorkflowEntity) -> Result<Workflow, ExecutionEngineError> {
+        let mut workflow = entity_to_workflow(entity)?;
+        let report = self.migrations.migrate_workflow(&mut workflow);
+        for failed in &report.failed {
+            tracing::warn!(
+                workflow_id = %workflow.id,
+                node = %failed.node,
+                error = %failed.message,
+                "Node migration failed on load"
+            );
+        }
+        Ok(workflow)
     }
 }
 
@@ -184,7 +218,7 @@ impl SqlxWorkflowStorage {
 impl WorkflowStorage for SqlxWorkflowStorage {
     async fn get_workflow(&self, id: &str) -> Result<Option<Workflow>, ExecutionEngineError> {
         match self.repo.find_by_id(id).await.map_err(db_err)? {
-            Some(entity) => Ok(Some(entity_to_workflow(&entity)?)),
+            Some(entity) => Ok(Some(self.load(&entity)?)),
             None => Ok(None),
         }
     }
@@ -193,10 +227,12 @@ impl WorkflowStorage for SqlxWorkflowStorage {
         // Try to update first; if the workflow doesn't exist yet, create it.
         let existing = self.repo.find_by_id(&workflow.id).await.map_err(db_err)?;
 
-        if existing.is_some() {
+        if let Some(existing) = existing {
+            // Without a version the caller overwrites whatever is stored.
+            let expected = workflow.version_id.as_deref().unwrap_or(&existing.version_id);
             let update = workflow_to_update(workflow)?;
             self.repo
-                .update(&workflow.id, &update)
+                .update(&workflow.id, expected, &update)
                 .await
                 .map_err(db_err)?;
         } else {
@@ -207,16 +243,69 @@ impl WorkflowStorage for SqlxWorkflowStorage {
         Ok(())
     }
 
+    async fn save_workflow_version(
+        &self,
+        workflow: &Workflow,
+        operations: Option<&[PatchOperation]>,
+    ) -> Result<(), ExecutionEngineError> {
+        let existing = self.repo.find_by_id(&workflow.id).await.map_err(db_err)?;
+
+        if let Some(existing) = existing {
+            let expected = workflow.version_id.as_deref().unwrap_or(&existing.version_id);
+            let update = workflow_to_update(workflow)?;
+            let operations = operations
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(json_err)?;
+            self.repo
+                .update_with_history(&workflow.id, expected, &update, operations)
+                .await
+                .map_err(db_err)?;
+        } else {
+            let insert = workflow_to_insert(workflow)?;
+            self.repo.create_with_history(&insert).await.map_err(db_err)?;
+        }
+
+        Ok(())
+    }
+
+    async fn workflow_history(
+        &self,
+        id: &str,
+        limit: usize,
+    ) -> Result<Vec<WorkflowVersion>, ExecutionEngineError> {
+        let history = self.repo.get_history(id, limit as i64).await.map_err(db_err)?;
+        history.into_iter().map(history_to_version).collect()
+    }
+
     async fn delete_workflow(&self, id: &str) -> Result<bool, ExecutionEngineError> {
         self.repo.delete(id).await.map_err(db_err)
     }
 
     async fn list_workflows(&self) -> Result<Vec<Workflow>, ExecutionEngineError> {
         let entities = self.repo.find_all(false).await.map_err(db_err)?;
-        entities.iter().map(entity_to_workflow).collect()
+        entities.iter().map(|e| self.load(e)).collect()
+    }
+
+    async fn search_workflows(&self, query: &str) -> Result<Vec<Workflow>, ExecutionEngineError> {
+        let entities = self.repo.search(query).await.map_err(db_err)?;
+        entities.iter().map(|e| self.load(e)).collect()
     }
 }
 
+/// Convert a `workflow_history` row into a [`WorkflowVersion`].
+fn history_to_version(history: WorkflowHistory) -> Result<WorkflowVersion, ExecutionEngineError> {
+    Ok(WorkflowVersion {
+        version_id: history.version_id,
+        previous_version_id: history.previous_version_id,
+        name: history.name.unwrap_or_default(),
+        nodes: history.nodes,
+        connections: serde_json::from_value(history.connections).map_err(json_err)?,
+        operations: history.operations.map(serde_json::from_value).transpose().map_err(json_err)?,
+        saved_at: history.created_at,
+    })
+}
+
 // =============================================================================
 // SqlxExecutionStorage
 // =============================================================================
@@ -230,22 +319,39 @@ impl WorkflowStorage for SqlxWorkflowStorage {
 #[derive(Clone)]
 pub struct SqlxExecutionStorage {
     repo: ExecutionRepository,
+    binary_data: BinaryDataRepository,
 }
 
 impl SqlxExecutionStorage {
     /// Create a new storage backed by the given connection pool.
     pub fn new(pool: PgPool) -> Self {
         Self {
-            repo: ExecutionRepository::new(pool),
+            repo: ExecutionRepository::new(pool.clone()),
+            binary_data: BinaryDataRepository::new(pool),
         }
     }
 
-    /// Create from an existing repository.
-    pub fn from_repo(repo: ExecutionRepository) -> Self {
-        Self { repo }
+    /// Create from existing repositories.
+    pub fn from_repo(repo: ExecutionRepository, binary_data: BinaryDataRepository) -> Self {
+        Self { repo, binary_data }
     }
 }
 
+/// References of the stored binary payloads in `run`'s node outputs.
+fn binary_references(run: &Run, execution_id: &str) -> Vec<BinaryDataEntity> {
+    run.data
+        .result_data
+        .run_data
+        .values()
+        .flatten()
+        .filter_map(|task| task.data.as_ref())
+        .flat_map(|outputs| outputs.values().flatten().flatten())
+        .filter_map(|item| item.binary.as_ref())
+        .flat_map(|binary| binary.values())
+        .filter_map(|data| BinaryDataEntity::from_reference(data, execution_id))
+        .collect()
+}
+
 #[async_trait]
 impl ExecutionStorage for SqlxExecutionStorage {
     async fn get_execution(&self, id: &str) -> Result<Option<Run>, ExecutionEngineError> {
@@ -302,6 +408,20 @@ impl ExecutionStorage for SqlxExecutionStorage {
         };
         self.repo.save_data(&exec_data).await.map_err(db_err)?;
 
+        let costs = cost_records(run);
+        if !costs.is_empty() {
+            self.repo.save_costs(id, &costs).await.map_err(db_err)?;
+        }
+
+        if let Some(usage) = ResourceUsage::of(run) {
+            self.repo.save_resource_usage(id, &usage).await.map_err(db_err)?;
+        }
+
+        let binary = binary_references(run, id);
+        if !binary.is_empty() {
+            self.binary_data.record(&binary).await.map_err(db_err)?;
+        }
+
         Ok(())
     }
 
@@ -369,4 +489,39 @@ mod tests {
         let run = entity_to_run(&entity, &exec_data).expect("conversion should succeed");
         assert_eq!(run.mode, WorkflowExecuteMode::Manual);
     }
+
+    #[test]
+    fn test_binary_references_skip_inline_data() {
+        let binary = |data: &str, id: Option<&str>| n8n_workflow::BinaryData {
+            data: data.to_string(),
+            mime_type: "image/png".to_string(),
+            file_name: Some("chart.png".to_string()),
+            file_extension: None,
+            file_size: None,
+            bytes: Some(2048),
+            id: id.map(str::to_string),
+            file_type: None,
+        };
+        let mut item = n8n_workflow::NodeExecutionData::new(Default::default());
+        item.binary = Some(
+            [
+                ("inline".to_string(), binary("aGVsbG8=", None)),
+                (
+                    "stored".to_string(),
+                    binary("filesystem", Some("filesystem:workflows/wf-1/executions/e1/x")),
+                ),
+            ]
+            .into(),
+        );
+        let mut task = n8n_workflow::TaskData::new();
+        task.data = Some([("main".to_string(), vec![vec![item]])].into());
+        let mut run = Run::new(WorkflowExecuteMode::Manual);
+        run.data.result_data.run_data.insert("Fetch".to_string(), vec![task]);
+
+        let refs = binary_references(&run, "e1");
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].mode, "filesystem");
+        assert_eq!(refs[0].workflow_id.as_deref(), Some("wf-1"));
+        assert_eq!(refs[0].file_size, Some(2048));
+    }
 }
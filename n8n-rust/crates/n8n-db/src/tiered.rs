@@ -0,0 +1,332 @@
+//! Tiered execution storage: in-memory hot tier with write-through to a
+//! persistent backend.
+//!
+//! Recent and in-flight executions live in memory so REST status polling
+//! never touches the database. Every save is acknowledged once the memory
+//! tier is updated and is then written to the backend by a background task;
+//! repeated saves of the same execution are coalesced into the latest
+//! version. Reads fall back to the backend for executions that are not (or
+//! no longer) held in memory.
+//!
+//! ```rust,no_run
+//! use n8n_db::{SqlxExecutionStorage, TieredExecutionStorage, TieredStorageConfig};
+//!
+//! # async fn example(pool: sqlx::PgPool) {
+//! let storage = TieredExecutionStorage::new(
+//!     SqlxExecutionStorage::new(pool),
+//!     TieredStorageConfig::default(),
+//! );
+//! // ... on shutdown:
+//! storage.flush().await.ok();
+//! # }
+//! ```
+
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex, RwLock};
+
+use n8n_core::error::ExecutionEngineError;
+use n8n_core::storage::ExecutionStorage;
+use n8n_workflow::Run;
+
+/// Configuration for [`TieredExecutionStorage`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TieredStorageConfig {
+    /// Maximum number of finished executions kept in memory. In-flight and
+    /// not yet persisted executions are never evicted.
+    pub capacity: usize,
+}
+
+impl Default for TieredStorageConfig {
+    fn default() -> Self {
+        Self { capacity: 1000 }
+    }
+}
+
+/// An execution held in the memory tier.
+struct Entry {
+    run: Run,
+    /// Incremented on every save.
+    version: u64,
+    /// Last version written to the backend.
+    persisted: Option<u64>,
+}
+
+impl Entry {
+    fn is_dirty(&self) -> bool {
+        self.persisted != Some(self.version)
+    }
+}
+
+struct Shared<B> {
+    backend: B,
+    entries: RwLock<HashMap<String, Entry>>,
+    /// Insertion order, oldest first, for eviction.
+    order: Mutex<VecDeque<String>>,
+    capacity: usize,
+}
+
+/// In-memory execution storage with async write-through to `B`.
+///
+/// Must be created inside a Tokio runtime; the write-through task stops
+/// once every clone of the storage has been dropped.
+pub struct TieredExecutionStorage<B> {
+    shared: Arc<Shared<B>>,
+    writes: mpsc::UnboundedSender<String>,
+}
+
+impl<B> Clone for TieredExecutionStorage<B> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            writes: self.writes.clone(),
+        }
+    }
+}
+
+impl<B: ExecutionStorage + 'static> TieredExecutionStorage<B> {
+    /// Wrap a backend and start the write-through task.
+    pub fn new(backend: B, config: TieredStorageConfig) -> Self {
+        let shared = Arc::new(Shared {
+            backend,
+            entries: RwLock::new(HashMap::new()),
+            order: Mutex::new(VecDeque::new()),
+            capacity: config.capacity,
+        });
+        let (writes, mut pending) = mpsc::unbounded_channel::<String>();
+
+        let worker = shared.clone();
+        tokio::spawn(async move {
+            while let Some(id) = pending.recv().await {
+                if let Err(e) = worker.persist(&id).await {
+                    tracing::warn!(execution_id = %id, error = %e, "Execution write-through failed");
+                }
+            }
+        });
+
+        Self { shared, writes }
+    }
+
+    /// The persistent backend.
+    pub fn backend(&self) -> &B {
+        &self.shared.backend
+    }
+
+    /// Number of executions currently held in memory.
+    pub async fn cached_len(&self) -> usize {
+        self.shared.entries.read().await.len()
+    }
+
+    /// Number of executions not yet written to the backend.
+    pub async fn pending_len(&self) -> usize {
+        self.shared
+            .entries
+            .read()
+            .await
+            .values()
+            .filter(|e| e.is_dirty())
+            .count()
+    }
+
+    /// Write every pending execution to the backend now. Returns the first
+    /// error after attempting all of them.
+    pub async fn flush(&self) -> Result<(), ExecutionEngineError> {
+        let dirty: Vec<String> = self
+            .shared
+            .entries
+            .read()
+            .await
+            .iter()
+            .filter(|(_, e)| e.is_dirty())
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        let mut first_error = None;
+        for id in dirty {
+            if let Err(e) = self.shared.persist(&id).await {
+                first_error.get_or_insert(e);
+            }
+        }
+        self.shared.evict().await;
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<B: ExecutionStorage> Shared<B> {
+    /// Write the latest version of an execution to the backend, then evict
+    /// persisted finished executions beyond capacity.
+    async fn persist(&self, id: &str) -> Result<(), ExecutionEngineError> {
+        let snapshot = {
+            let entries = self.entries.read().await;
+            match entries.get(id) {
+                Some(entry) if entry.is_dirty() => Some((entry.run.clone(), entry.version)),
+                _ => None,
+            }
+        };
+        let Some((run, version)) = snapshot else {
+            return Ok(());
+        };
+
+        self.backend.save_execution(id, &run).await?;
+
+        if let Some(entry) = self.entries.write().await.get_mut(id) {
+            // A newer save may have landed meanwhile; it stays dirty.
+            if entry.persisted.map_or(true, |p| p < version) {
+                entry.persisted = Some(version);
+            }
+        }
+
+        self.evict().await;
+        Ok(())
+    }
+
+    async fn evict(&self) {
+        let mut entries = self.entries.write().await;
+        let mut order = self.order.lock().await;
+        if entries.len() <= self.capacity {
+            return;
+        }
+
+        let mut excess = entries.len() - self.capacity;
+        order.retain(|id| {
+            if excess == 0 {
+                return true;
+            }
+            match entries.get(id) {
+                Some(entry) if !entry.is_dirty() && entry.run.status.is_finished() => {
+                    entries.remove(id);
+                    excess -= 1;
+                    false
+                }
+                Some(_) => true,
+                None => false,
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl<B: ExecutionStorage + 'static> ExecutionStorage for TieredExecutionStorage<B> {
+    async fn get_execution(&self, id: &str) -> Result<Option<Run>, ExecutionEngineError> {
+        if let Some(entry) = self.shared.entries.read().await.get(id) {
+            return Ok(Some(entry.run.clone()));
+        }
+        self.shared.backend.get_execution(id).await
+    }
+
+    async fn save_execution(&self, id: &str, run: &Run) -> Result<(), ExecutionEngineError> {
+        {
+            let mut entries = self.shared.entries.write().await;
+            match entries.get_mut(id) {
+                Some(entry) => {
+                    entry.run = run.clone();
+                    entry.version += 1;
+                }
+                None => {
+                    entries.insert(
+                        id.to_string(),
+                        Entry {
+                            run: run.clone(),
+                            version: 0,
+                            persisted: None,
+                        },
+                    );
+                    self.shared.order.lock().await.push_back(id.to_string());
+                }
+            }
+        }
+
+        if self.writes.send(id.to_string()).is_err() {
+            // Write-through task is gone; persist inline.
+            self.shared.persist(id).await?;
+        }
+        Ok(())
+    }
+
+    async fn delete_execution(&self, id: &str) -> Result<bool, ExecutionEngineError> {
+        let cached = self.shared.entries.write().await.remove(id).is_some();
+        self.shared.order.lock().await.retain(|o| o != id);
+        let persisted = self.shared.backend.delete_execution(id).await?;
+        Ok(cached || persisted)
+    }
+
+    /// Lists from the backend after flushing pending writes, so in-memory
+    /// executions are included.
+    async fn list_executions(
+        &self,
+        workflow_id: &str,
+    ) -> Result<Vec<(String, Run)>, ExecutionEngineError> {
+        self.flush().await?;
+        let mut runs = self.shared.backend.list_executions(workflow_id).await?;
+
+        // Prefer the memory tier's copy; it may be newer than the backend's.
+        let entries = self.shared.entries.read().await;
+        for (id, run) in &mut runs {
+            if let Some(entry) = entries.get(id) {
+                *run = entry.run.clone();
+            }
+        }
+        Ok(runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_core::storage::MemoryExecutionStorage;
+    use n8n_workflow::{ExecutionStatus, WorkflowExecuteMode};
+
+    fn finished_run() -> Run {
+        let mut run = Run::new(WorkflowExecuteMode::Manual);
+        run.finish(ExecutionStatus::Success);
+        run
+    }
+
+    #[tokio::test]
+    async fn test_write_through_and_fallback() {
+        let storage = TieredExecutionStorage::new(
+            MemoryExecutionStorage::new(),
+            TieredStorageConfig::default(),
+        );
+
+        let run = Run::new(WorkflowExecuteMode::Manual);
+        storage.save_execution("e1", &run).await.unwrap();
+        assert!(storage.get_execution("e1").await.unwrap().is_some());
+
+        storage.flush().await.unwrap();
+        assert_eq!(storage.pending_len().await, 0);
+        assert!(storage.backend().get_execution("e1").await.unwrap().is_some());
+
+        // Executions only in the backend are read through.
+        storage.backend().save_execution("e0", &finished_run()).await.unwrap();
+        assert!(storage.get_execution("e0").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_evicts_only_persisted_finished_executions() {
+        let storage = TieredExecutionStorage::new(
+            MemoryExecutionStorage::new(),
+            TieredStorageConfig { capacity: 1 },
+        );
+
+        storage
+            .save_execution("running", &Run::new(WorkflowExecuteMode::Manual))
+            .await
+            .unwrap();
+        storage.save_execution("done-1", &finished_run()).await.unwrap();
+        storage.save_execution("done-2", &finished_run()).await.unwrap();
+        storage.flush().await.unwrap();
+
+        // The running execution stays in memory; finished ones are evicted
+        // down to capacity but remain readable from the backend.
+        assert_eq!(storage.cached_len().await, 1);
+        assert!(storage.shared.entries.read().await.contains_key("running"));
+        assert!(storage.get_execution("done-1").await.unwrap().is_some());
+    }
+}
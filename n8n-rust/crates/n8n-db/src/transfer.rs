@@ -0,0 +1,348 @@
+//! Moving workflows between projects and owners.
+//!
+//! A workflow is owned by the project holding its `workflow:owner` row in
+//! `shared_workflow`; a user owns a workflow through their personal
+//! project. [`WorkflowTransfers::transfer`] replaces that row in one
+//! transaction, so the workflow never has zero or two owners. Shares with
+//! other projects are kept.
+//!
+//! Execution history either moves with the workflow
+//! ([`ExecutionHistory::Migrate`]) or is detached from it
+//! ([`ExecutionHistory::Detach`]): detached executions lose their
+//! `workflow_id` and keep only the workflow snapshot stored with their
+//! data, so the target project cannot see them.
+//!
+//! Before moving, every credential the workflow's nodes use is checked
+//! against the target project: credentials must exist and be global or
+//! shared with it. A move with [`CredentialIssue`]s is only applied when
+//! forced; a dry run reports issues and counts without changing anything.
+//!
+//! Projects isolated in their own schema ([`crate::isolation`]) cannot
+//! receive workflows from the shared schema.
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use n8n_workflow::Node;
+
+use crate::entities::WorkflowSharingRole;
+use crate::error::DbError;
+
+/// What happens to a moved workflow's executions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExecutionHistory {
+    /// Executions stay with the workflow and move to the target project.
+    #[default]
+    Migrate,
+    /// Executions are unlinked from the workflow and stay behind.
+    Detach,
+}
+
+/// Where to move a workflow. Exactly one of `project_id` and `user_id` is
+/// set; a user stands for their personal project.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferRequest {
+    pub project_id: Option<String>,
+    pub user_id: Option<Uuid>,
+    #[serde(default)]
+    pub execution_history: ExecutionHistory,
+    /// Report without changing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Move even if credentials are inaccessible in the target project.
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Why a credential would break in the target project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CredentialProblem {
+    /// The credential no longer exists.
+    Missing,
+    /// The credential is neither global nor shared with the project.
+    NotShared,
+}
+
+/// A node credential inaccessible in the target project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialIssue {
+    pub node: String,
+    pub credential_type: String,
+    pub credential_id: String,
+    pub credential_name: String,
+    pub problem: CredentialProblem,
+}
+
+/// Outcome of a transfer, or of a dry run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferReport {
+    pub workflow_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_project_id: Option<String>,
+    pub to_project_id: String,
+    /// Whether the move was made.
+    pub applied: bool,
+    pub dry_run: bool,
+    pub execution_history: ExecutionHistory,
+    /// Executions migrated or detached (or that would be).
+    pub executions: u64,
+    pub credential_issues: Vec<CredentialIssue>,
+}
+
+/// Credentials of `nodes` that are not accessible. `access` maps the
+/// existing credentials to whether the target project may use them.
+pub fn credential_issues(nodes: &[Node], access: &HashMap<String, bool>) -> Vec<CredentialIssue> {
+    let mut issues = Vec::new();
+    for node in nodes {
+        let Some(credentials) = &node.credentials else {
+            continue;
+        };
+        let mut credentials: Vec<_> = credentials.iter().collect();
+        credentials.sort_by(|a, b| a.0.cmp(b.0));
+        for (credential_type, credential) in credentials {
+            let problem = match access.get(&credential.id) {
+                None => CredentialProblem::Missing,
+                Some(false) => CredentialProblem::NotShared,
+                Some(true) => continue,
+            };
+            issues.push(CredentialIssue {
+                node: node.name.clone(),
+                credential_type: credential_type.clone(),
+                credential_id: credential.id.clone(),
+                credential_name: credential.name.clone(),
+                problem,
+            });
+        }
+    }
+    issues
+}
+
+/// Moves workflows between projects.
+#[derive(Clone)]
+pub struct WorkflowTransfers {
+    pool: PgPool,
+}
+
+impl WorkflowTransfers {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Move `workflow_id` as `request` says. Fails with
+    /// [`DbError::NotFound`] for an unknown workflow and
+    /// [`DbError::InvalidData`] for an unknown or unchanged target.
+    pub async fn transfer(
+        &self,
+        workflow_id: &str,
+        request: &TransferRequest,
+    ) -> Result<TransferReport, DbError> {
+        let mut tx = self.pool.begin().await?;
+
+        let nodes = sqlx::query_scalar::<_, Json<Vec<Node>>>(
+            "SELECT nodes FROM workflow_entity WHERE id = $1 AND is_archived = false FOR UPDATE",
+        )
+        .bind(workflow_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(DbError::NotFound)?
+        .0;
+
+        let to_project_id = match (&request.project_id, request.user_id) {
+            (Some(project_id), None) => {
+                sqlx::query_scalar::<_, String>("SELECT id FROM project WHERE id = $1")
+                    .bind(project_id)
+                    .fetch_optional(&mut *tx)
+                    .await?
+                    .ok_or_else(|| DbError::InvalidData(format!("Project {} not found", project_id)))?
+            }
+            (None, Some(user_id)) => sqlx::query_scalar::<_, String>(
+                r#"
+                SELECT p.id FROM project p
+                INNER JOIN project_relation pr ON p.id = pr.project_id
+                WHERE pr.user_id = $1 AND p.type = 'personal' AND pr.role = 'project:personalOwner'
+                "#,
+            )
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| {
+                DbError::InvalidData(format!("User {} has no personal project", user_id))
+            })?,
+            _ => {
+                return Err(DbError::InvalidData(
+                    "Exactly one of projectId and userId is required".to_string(),
+                ))
+            }
+        };
+
+        // Rows here would sit outside the project's own schema
+        let isolated = sqlx::query_scalar::<_, String>(
+            "SELECT schema_name FROM project_schema WHERE project_id = $1",
+        )
+        .bind(&to_project_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(schema) = isolated {
+            return Err(DbError::InvalidData(format!(
+                "Project {} keeps its workflows in schema {}; export and import the workflow instead",
+                to_project_id, schema
+            )));
+        }
+
+        let owner = WorkflowSharingRole::Owner.to_string();
+        let from_project_id = sqlx::query_scalar::<_, String>(
+            "SELECT project_id FROM shared_workflow WHERE workflow_id = $1 AND role = $2 FOR UPDATE",
+        )
+        .bind(workflow_id)
+        .bind(&owner)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if from_project_id.as_deref() == Some(to_project_id.as_str()) {
+            return Err(DbError::InvalidData(format!(
+                "Workflow is already owned by project {}",
+                to_project_id
+            )));
+        }
+
+        let credential_ids: Vec<String> = nodes
+            .iter()
+            .filter_map(|node| node.credentials.as_ref())
+            .flat_map(|credentials| credentials.values().map(|c| c.id.clone()))
+            .collect();
+        let access: HashMap<String, bool> = sqlx::query_as::<_, (String, bool)>(
+            r#"
+            SELECT c.id, c.is_global OR EXISTS (
+                SELECT 1 FROM shared_credentials sc
+                WHERE sc.credentials_id = c.id AND sc.project_id = $2
+            )
+            FROM credentials_entity c
+            WHERE c.id = ANY($1)
+            "#,
+        )
+        .bind(&credential_ids)
+        .bind(&to_project_id)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+        let issues = credential_issues(&nodes, &access);
+
+        let executions = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM execution_entity WHERE workflow_id = $1",
+        )
+        .bind(workflow_id)
+        .fetch_one(&mut *tx)
+        .await? as u64;
+
+        let applied = !request.dry_run && (issues.is_empty() || request.force);
+        if applied {
+            sqlx::query("DELETE FROM shared_workflow WHERE workflow_id = $1 AND role = $2")
+                .bind(workflow_id)
+                .bind(&owner)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                r#"
+                INSERT INTO shared_workflow (workflow_id, project_id, role)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (workflow_id, project_id) DO UPDATE SET role = $3, updated_at = NOW()
+                "#,
+            )
+            .bind(workflow_id)
+            .bind(&to_project_id)
+            .bind(&owner)
+            .execute(&mut *tx)
+            .await?;
+            if request.execution_history == ExecutionHistory::Detach {
+                sqlx::query("UPDATE execution_entity SET workflow_id = NULL WHERE workflow_id = $1")
+                    .bind(workflow_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+        } else {
+            tx.rollback().await?;
+        }
+
+        Ok(TransferReport {
+            workflow_id: workflow_id.to_string(),
+            from_project_id,
+            to_project_id,
+            applied,
+            dry_run: request.dry_run,
+            execution_history: request.execution_history,
+            executions,
+            credential_issues: issues,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n8n_workflow::NodeCredentialRef;
+
+    fn node(name: &str, credentials: &[(&str, &str)]) -> Node {
+        let mut node = Node::new(name, "n8n-nodes-base.httpRequest");
+        node.credentials = Some(
+            credentials
+                .iter()
+                .map(|(kind, id)| {
+                    let credential = NodeCredentialRef {
+                        id: id.to_string(),
+                        name: format!("{} credential", id),
+                    };
+                    (kind.to_string(), credential)
+                })
+                .collect(),
+        );
+        node
+    }
+
+    #[test]
+    fn test_credential_issues_name_missing_and_unshared() {
+        let nodes = vec![
+            node("Fetch", &[("httpBasicAuth", "shared"), ("oAuth2Api", "private")]),
+            node("Post", &[("slackApi", "deleted")]),
+            Node::new("Set", "n8n-nodes-base.set"),
+        ];
+        let access = HashMap::from([
+            ("shared".to_string(), true),
+            ("private".to_string(), false),
+        ]);
+
+        let issues = credential_issues(&nodes, &access);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].node, "Fetch");
+        assert_eq!(issues[0].credential_type, "oAuth2Api");
+        assert_eq!(issues[0].problem, CredentialProblem::NotShared);
+        assert_eq!(issues[1].credential_id, "deleted");
+        assert_eq!(issues[1].problem, CredentialProblem::Missing);
+    }
+
+    #[test]
+    fn test_transfer_request_defaults() {
+        let request: TransferRequest =
+            serde_json::from_value(serde_json::json!({ "projectId": "p1" })).unwrap();
+        assert_eq!(request.project_id.as_deref(), Some("p1"));
+        assert_eq!(request.execution_history, ExecutionHistory::Migrate);
+        assert!(!request.dry_run && !request.force);
+
+        let request: TransferRequest = serde_json::from_value(serde_json::json!({
+            "userId": "6f9619ff-8b86-d011-b42d-00cf4fc964ff",
+            "executionHistory": "detach",
+            "dryRun": true,
+        }))
+        .unwrap();
+        assert!(request.user_id.is_some());
+        assert_eq!(request.execution_history, ExecutionHistory::Detach);
+    }
+}
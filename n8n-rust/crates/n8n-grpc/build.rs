@@ -1,9 +1,15 @@
+use std::path::PathBuf;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
+
     // Compile protobuf definitions using tonic-prost-build (tonic 0.14+)
     tonic_prost_build::configure()
         .build_server(true)
         .build_client(true)
         .out_dir("src/generated")
+        // Descriptor set for gRPC server reflection
+        .file_descriptor_set_path(out_dir.join("n8n_descriptor.bin"))
         .compile_protos(&["proto/n8n.proto"], &["proto"])?;
 
     println!("cargo:rerun-if-changed=proto/n8n.proto");
@@ -290,6 +290,9 @@ pub struct NodeFinished {
     pub run_index: u32,
     #[prost(message, optional, tag = "3")]
     pub task_data: ::core::option::Option<TaskData>,
+    /// Items output across all connections
+    #[prost(uint32, tag = "4")]
+    pub item_count: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ExecutionFinished {
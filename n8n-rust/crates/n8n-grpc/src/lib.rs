@@ -7,12 +7,19 @@
 //! - gRPC service implementations
 //! - Arrow Flight integration for zero-copy streaming
 //! - Hamming similarity service
+//! - gRPC health checking and server reflection
 //! - Multi-transport support (REST, STDIO, gRPC, Flight)
 //! - Intelligent content negotiation
 
 pub mod services;
 pub mod transport;
 
+/// Types and client/server stubs generated from `proto/n8n.proto`.
+#[allow(clippy::all)]
+pub mod pb {
+    include!("generated/n8n.rs");
+}
+
 // Re-export generated protobuf types
 // Note: In a real build, these would be generated by build.rs
 // For now, we provide manual implementations
@@ -91,6 +91,17 @@ impl ArrowDataService {
         node_name: &str,
         run_index: Option<usize>,
     ) -> Result<Bytes, Status> {
+        let batch = self.node_output_batch(execution_id, node_name, run_index).await?;
+        batch_to_ipc_bytes(&batch).map_err(|e| Status::internal(e.to_string()))
+    }
+
+    /// Node output items of one run as a record batch.
+    pub async fn node_output_batch(
+        &self,
+        execution_id: &str,
+        node_name: &str,
+        run_index: Option<usize>,
+    ) -> Result<RecordBatch, Status> {
         let run = self
             .executions
             .get_execution(execution_id)
@@ -118,10 +129,28 @@ impl ArrowDataService {
             .map(|outputs| outputs.iter().flatten().cloned().collect())
             .unwrap_or_default();
 
-        let batch = node_execution_data_to_batch(&output_data)
-            .map_err(|e| Status::internal(e.to_string()))?;
+        node_execution_data_to_batch(&output_data).map_err(|e| Status::internal(e.to_string()))
+    }
 
-        batch_to_ipc_bytes(&batch).map_err(|e| Status::internal(e.to_string()))
+    /// Per-node task summaries of an execution, optionally limited to some
+    /// nodes, as a record batch.
+    pub async fn run_data_batch(
+        &self,
+        execution_id: &str,
+        node_names: Option<&[String]>,
+    ) -> Result<RecordBatch, Status> {
+        let run = self
+            .executions
+            .get_execution(execution_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::not_found(format!("Execution {} not found", execution_id)))?;
+
+        let mut run_data = run.data.result_data.run_data;
+        if let Some(names) = node_names {
+            run_data.retain(|name, _| names.contains(name));
+        }
+        run_data_to_batch(&run_data).map_err(|e| Status::internal(e.to_string()))
     }
 
     /// Get workflow structure as Arrow.
@@ -154,12 +183,20 @@ impl ArrowDataService {
     /// Store Arrow data as execution result.
     pub async fn put_arrow_data(
         &self,
-        _execution_id: &str,
+        execution_id: &str,
         data: Bytes,
     ) -> Result<PutArrowResult, Status> {
         let batches =
             ipc_bytes_to_batches(&data).map_err(|e| Status::internal(e.to_string()))?;
+        self.put_batches(execution_id, &batches).await
+    }
 
+    /// Store decoded record batches as execution result.
+    pub async fn put_batches(
+        &self,
+        _execution_id: &str,
+        batches: &[RecordBatch],
+    ) -> Result<PutArrowResult, Status> {
         let rows_written: usize = batches.iter().map(|b| b.num_rows()).sum();
 
         Ok(PutArrowResult {
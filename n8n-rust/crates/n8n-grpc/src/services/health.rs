@@ -0,0 +1,253 @@
+//! gRPC health checking and server reflection.
+//!
+//! Implements the standard `grpc.health.v1.Health` protocol so that
+//! `grpc-health-probe` and Kubernetes gRPC probes work out of the box, and
+//! exposes the n8n proto descriptors via server reflection for `grpcurl`.
+//!
+//! Every check is reported under its own health service name. The overall
+//! server status (the empty service name) is `SERVING` only while every
+//! critical component is serving.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tonic_health::pb::health_server::{Health, HealthServer};
+use tonic_health::server::{health_reporter, HealthReporter};
+use tonic_health::ServingStatus;
+use tonic_reflection::server::{ServerReflection, ServerReflectionServer};
+
+/// Encoded file descriptor set for `proto/n8n.proto` (written by build.rs).
+pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("n8n_descriptor");
+
+/// Health service name for the workflow service.
+pub const HEALTH_WORKFLOW_SERVICE: &str = "n8n.WorkflowService";
+/// Health service name for database connectivity.
+pub const HEALTH_DB: &str = "n8n.db";
+/// Health service name for connectivity of the durable timer queue.
+pub const HEALTH_QUEUE: &str = "n8n.queue";
+/// Health service name for the consumer of the durable timer queue.
+pub const HEALTH_QUEUE_CONSUMER: &str = "n8n.queue.consumer";
+/// Health service name for applied database migrations.
+pub const HEALTH_DB_MIGRATIONS: &str = "n8n.db.migrations";
+/// Health service name for Redis connectivity.
+pub const HEALTH_REDIS: &str = "n8n.redis";
+
+/// Status of a single health component.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentHealth {
+    /// Whether the component is currently serving.
+    pub serving: bool,
+    /// Whether a failure of this component fails the overall status.
+    pub critical: bool,
+    /// Failure detail from the last check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// When the component was last updated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+/// Shared health state backing the gRPC health service and the REST
+/// readiness endpoint.
+#[derive(Clone)]
+pub struct GrpcHealth {
+    reporter: HealthReporter,
+    components: Arc<RwLock<HashMap<String, ComponentHealth>>>,
+}
+
+impl GrpcHealth {
+    /// Create the health state along with the tonic service to mount.
+    pub fn new() -> (Self, HealthServer<impl Health>) {
+        let (reporter, service) = health_reporter();
+        let health = Self {
+            reporter,
+            components: Arc::new(RwLock::new(HashMap::new())),
+        };
+        (health, service)
+    }
+
+    /// Register a component. It reports `NOT_SERVING` until its first update.
+    pub async fn register(&self, name: &str, critical: bool) {
+        self.components.write().await.insert(
+            name.to_string(),
+            ComponentHealth {
+                serving: false,
+                critical,
+                detail: None,
+                checked_at: None,
+            },
+        );
+        self.reporter
+            .set_service_status(name, ServingStatus::NotServing)
+            .await;
+        self.update_overall().await;
+    }
+
+    /// Update the serving state of a component.
+    ///
+    /// Unregistered components are registered as critical.
+    pub async fn set_serving(&self, name: &str, serving: bool) {
+        let result = if serving { Ok(()) } else { Err(None) };
+        self.update(name, result).await;
+    }
+
+    /// Update a component from a check result, keeping the failure detail.
+    pub async fn set_result(&self, name: &str, result: Result<(), String>) {
+        self.update(name, result.map_err(Some)).await;
+    }
+
+    async fn update(&self, name: &str, result: Result<(), Option<String>>) {
+        let serving = result.is_ok();
+        {
+            let mut components = self.components.write().await;
+            let component = components
+                .entry(name.to_string())
+                .or_insert(ComponentHealth {
+                    serving,
+                    critical: true,
+                    detail: None,
+                    checked_at: None,
+                });
+            component.serving = serving;
+            component.detail = result.err().flatten();
+            component.checked_at = Some(Utc::now());
+        }
+
+        let status = if serving {
+            ServingStatus::Serving
+        } else {
+            ServingStatus::NotServing
+        };
+        self.reporter.set_service_status(name, status).await;
+        self.update_overall().await;
+    }
+
+    /// Periodically run `check` and report its result under `name`.
+    pub fn spawn_probe<F, Fut>(&self, name: &str, interval: Duration, check: F) -> JoinHandle<()>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send,
+    {
+        let health = self.clone();
+        let name = name.to_string();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            let mut last = None;
+            loop {
+                ticker.tick().await;
+                let result = check().await;
+                let serving = result.is_ok();
+                if last != Some(serving) {
+                    match &result {
+                        Ok(()) => tracing::info!("Health check '{}' is serving", name),
+                        Err(e) => tracing::warn!("Health check '{}' is not serving: {}", name, e),
+                    }
+                    last = Some(serving);
+                }
+                health.set_result(&name, result).await;
+            }
+        })
+    }
+
+    /// Mark every component and the overall server as `NOT_SERVING`.
+    ///
+    /// Called on shutdown so load balancers drain traffic first.
+    pub async fn shutdown(&self) {
+        let names: Vec<String> = {
+            let mut components = self.components.write().await;
+            for component in components.values_mut() {
+                component.serving = false;
+                component.detail = Some("shutting down".to_string());
+            }
+            components.keys().cloned().collect()
+        };
+
+        for name in &names {
+            self.reporter
+                .set_service_status(name, ServingStatus::NotServing)
+                .await;
+        }
+        self.reporter
+            .set_service_status("", ServingStatus::NotServing)
+            .await;
+    }
+
+    /// Get the current state of all components.
+    pub async fn snapshot(&self) -> HashMap<String, ComponentHealth> {
+        self.components.read().await.clone()
+    }
+
+    /// Whether all critical components are serving.
+    pub async fn is_serving(&self) -> bool {
+        self.components
+            .read()
+            .await
+            .values()
+            .all(|c| c.serving || !c.critical)
+    }
+
+    async fn update_overall(&self) {
+        let status = if self.is_serving().await {
+            ServingStatus::Serving
+        } else {
+            ServingStatus::NotServing
+        };
+        self.reporter.set_service_status("", status).await;
+    }
+}
+
+/// Build the server reflection service for the n8n and health protos.
+pub fn reflection_service(
+) -> Result<ServerReflectionServer<impl ServerReflection>, tonic_reflection::server::Error> {
+    tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .build_v1()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_critical_component_fails_overall() {
+        let (health, _service) = GrpcHealth::new();
+        health.set_serving(HEALTH_WORKFLOW_SERVICE, true).await;
+        assert!(health.is_serving().await);
+
+        health.register(HEALTH_DB, true).await;
+        assert!(!health.is_serving().await);
+
+        health.set_serving(HEALTH_DB, true).await;
+        assert!(health.is_serving().await);
+    }
+
+    #[tokio::test]
+    async fn test_non_critical_component_does_not_fail_overall() {
+        let (health, _service) = GrpcHealth::new();
+        health.register(HEALTH_QUEUE, false).await;
+        assert!(health.is_serving().await);
+
+        let snapshot = health.snapshot().await;
+        assert!(!snapshot[HEALTH_QUEUE].serving);
+    }
+
+    #[tokio::test]
+    async fn test_set_result_keeps_detail() {
+        let (health, _service) = GrpcHealth::new();
+        health
+            .set_result(HEALTH_DB, Err("connection refused".to_string()))
+            .await;
+
+        let snapshot = health.snapshot().await;
+        assert_eq!(snapshot[HEALTH_DB].detail.as_deref(), Some("connection refused"));
+        assert!(!health.is_serving().await);
+    }
+}
@@ -5,7 +5,7 @@
 
 use bytes::Bytes;
 use n8n_workflow::{
-    Node, NodeExecutionData, Run, TaskData, Workflow,
+    object_to_json, Node, NodeExecutionData, Run, TaskData, Workflow,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -189,7 +189,7 @@ pub struct NodeExecutionDataJson {
 impl From<&NodeExecutionData> for NodeExecutionDataJson {
     fn from(d: &NodeExecutionData) -> Self {
         Self {
-            json: serde_json::to_value(&d.json).unwrap_or_default(),
+            json: serde_json::Value::Object(object_to_json(&d.json)),
             binary: d.binary.as_ref().map(|b| {
                 b.iter()
                     .map(|(k, v)| (k.clone(), BinaryDataJson::from(v)))
@@ -2,10 +2,13 @@
 
 pub mod arrow_service;
 pub mod hamming_service;
+pub mod health;
 pub mod json_compat;
+pub mod workflow_rpc;
 pub mod workflow_service;
 
 pub use arrow_service::*;
 pub use hamming_service::*;
+pub use health::*;
 pub use json_compat::*;
 pub use workflow_service::*;
@@ -0,0 +1,493 @@
+//! `n8n.WorkflowService` over gRPC.
+//!
+//! Implements the generated [`WorkflowService`] trait for
+//! [`WorkflowGrpcService`]. `ExecuteWorkflowStream` starts an execution and
+//! streams `started`, `node_started`, `node_finished` (with the node's task
+//! data and output item count) and a final `finished` event carrying the
+//! full result, so clients need not poll for completion. Canceling the
+//! execution with `CancelExecution` ends the stream with a `finished` event
+//! in status `CANCELED`.
+//!
+//! Executions run the whole workflow; `start_nodes`, `destination_node` and
+//! `pin_data` are refused. Workflow management and retries go through the
+//! REST API and answer `UNIMPLEMENTED` here.
+
+use chrono::{DateTime, Utc};
+use n8n_workflow::{
+    object_to_json, BinaryFileType, ExecutionError, ExecutionStatus, NodeExecutionData, Run,
+    TaskData, WorkflowExecuteMode,
+};
+use prost_types::value::Kind;
+use std::pin::Pin;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+use super::{ExecutionEventMessage, WorkflowGrpcService};
+use crate::pb;
+use crate::pb::execution_event::Event;
+use crate::pb::workflow_service_server::WorkflowService;
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<pb::ExecutionEvent, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl WorkflowService for WorkflowGrpcService {
+    async fn create_workflow(
+        &self,
+        _request: Request<pb::CreateWorkflowRequest>,
+    ) -> Result<Response<pb::WorkflowResponse>, Status> {
+        Err(rest_only("Workflow management"))
+    }
+
+    async fn get_workflow(
+        &self,
+        _request: Request<pb::GetWorkflowRequest>,
+    ) -> Result<Response<pb::WorkflowResponse>, Status> {
+        Err(rest_only("Workflow management"))
+    }
+
+    async fn update_workflow(
+        &self,
+        _request: Request<pb::UpdateWorkflowRequest>,
+    ) -> Result<Response<pb::WorkflowResponse>, Status> {
+        Err(rest_only("Workflow management"))
+    }
+
+    async fn delete_workflow(
+        &self,
+        _request: Request<pb::DeleteWorkflowRequest>,
+    ) -> Result<Response<pb::DeleteWorkflowResponse>, Status> {
+        Err(rest_only("Workflow management"))
+    }
+
+    async fn list_workflows(
+        &self,
+        _request: Request<pb::ListWorkflowsRequest>,
+    ) -> Result<Response<pb::ListWorkflowsResponse>, Status> {
+        Err(rest_only("Workflow management"))
+    }
+
+    async fn execute_workflow(
+        &self,
+        request: Request<pb::ExecuteWorkflowRequest>,
+    ) -> Result<Response<pb::ExecutionResponse>, Status> {
+        let request = request.into_inner();
+        let input = input_items(&request)?;
+        let result = self
+            .execute_workflow(&request.workflow_id, input, execute_mode(request.mode))
+            .await?;
+        Ok(Response::new(pb::ExecutionResponse {
+            result: Some(execution_result(&result.execution_id, &result.workflow_id, &result.run)),
+        }))
+    }
+
+    type ExecuteWorkflowStreamStream = EventStream;
+
+    async fn execute_workflow_stream(
+        &self,
+        request: Request<pb::ExecuteWorkflowRequest>,
+    ) -> Result<Response<Self::ExecuteWorkflowStreamStream>, Status> {
+        let request = request.into_inner();
+        let input = input_items(&request)?;
+        let events = self
+            .execute_workflow_stream(&request.workflow_id, input, execute_mode(request.mode))
+            .await?;
+
+        let workflow_id = request.workflow_id;
+        let mut execution_id = String::new();
+        let events = events.map(move |event| {
+            event.map(|event| {
+                if let ExecutionEventMessage::Started { execution_id: id, .. } = &event {
+                    execution_id = id.clone();
+                }
+                execution_event(event, &execution_id, &workflow_id)
+            })
+        });
+        Ok(Response::new(Box::pin(events)))
+    }
+
+    async fn get_execution(
+        &self,
+        request: Request<pb::GetExecutionRequest>,
+    ) -> Result<Response<pb::ExecutionResponse>, Status> {
+        let execution_id = request.into_inner().execution_id;
+        let run = self.get_execution(&execution_id).await?;
+        // Stored runs do not carry their workflow ID.
+        Ok(Response::new(pb::ExecutionResponse {
+            result: Some(execution_result(&execution_id, "", &run)),
+        }))
+    }
+
+    async fn cancel_execution(
+        &self,
+        request: Request<pb::CancelExecutionRequest>,
+    ) -> Result<Response<pb::CancelExecutionResponse>, Status> {
+        let execution_id = request.into_inner().execution_id;
+        let success = self.cancel_execution(&execution_id).await?;
+        if !success {
+            return Err(Status::not_found(format!("Execution {} is not running", execution_id)));
+        }
+        Ok(Response::new(pb::CancelExecutionResponse {
+            success,
+            final_status: execution_status(ExecutionStatus::Canceled),
+        }))
+    }
+
+    async fn retry_execution(
+        &self,
+        _request: Request<pb::RetryExecutionRequest>,
+    ) -> Result<Response<pb::ExecutionResponse>, Status> {
+        Err(rest_only("Retrying executions"))
+    }
+}
+
+fn rest_only(what: &str) -> Status {
+    Status::unimplemented(format!("{} is only available over the REST API", what))
+}
+
+/// Input items of an execution request: `input_data` becomes one item.
+fn input_items(
+    request: &pb::ExecuteWorkflowRequest,
+) -> Result<Option<Vec<NodeExecutionData>>, Status> {
+    if !request.start_nodes.is_empty()
+        || request.destination_node.is_some()
+        || !request.pin_data.is_empty()
+    {
+        return Err(Status::invalid_argument(
+            "start_nodes, destination_node and pin_data are not supported over gRPC",
+        ));
+    }
+    request
+        .input_data
+        .clone()
+        .map(|data| {
+            let json = from_prost(prost_types::Value {
+                kind: Some(Kind::StructValue(data)),
+            });
+            NodeExecutionData::from_json_value(json)
+                .map(|item| vec![item])
+                .map_err(|e| Status::invalid_argument(format!("input_data: {}", e)))
+        })
+        .transpose()
+}
+
+/// Proto event for a streamed execution event.
+fn execution_event(
+    event: ExecutionEventMessage,
+    execution_id: &str,
+    workflow_id: &str,
+) -> pb::ExecutionEvent {
+    let event = match event {
+        ExecutionEventMessage::Started { execution_id, workflow_id } => {
+            Event::Started(pb::ExecutionStarted {
+                execution_id,
+                workflow_id,
+                started_at: Some(timestamp(Utc::now())),
+            })
+        }
+        ExecutionEventMessage::NodeStarted { node_name, run_index } => {
+            Event::NodeStarted(pb::NodeStarted {
+                node_name,
+                run_index: run_index as u32,
+            })
+        }
+        ExecutionEventMessage::NodeFinished {
+            node_name,
+            run_index,
+            output_count,
+            task_data: data,
+        } => Event::NodeFinished(pb::NodeFinished {
+            node_name,
+            run_index: run_index as u32,
+            task_data: Some(task_data(&data)),
+            item_count: output_count as u32,
+        }),
+        ExecutionEventMessage::Finished { run, .. } => Event::Finished(pb::ExecutionFinished {
+            result: Some(execution_result(execution_id, workflow_id, &run)),
+        }),
+        ExecutionEventMessage::Error { message } => Event::Error(pb::ExecutionError {
+            message,
+            timestamp: Some(timestamp(Utc::now())),
+            ..Default::default()
+        }),
+        ExecutionEventMessage::Canceled => Event::Finished(pb::ExecutionFinished {
+            result: Some(pb::ExecutionResult {
+                execution_id: execution_id.to_string(),
+                workflow_id: workflow_id.to_string(),
+                status: execution_status(ExecutionStatus::Canceled),
+                finished_at: Some(timestamp(Utc::now())),
+                ..Default::default()
+            }),
+        }),
+    };
+    pb::ExecutionEvent { event: Some(event) }
+}
+
+/// Proto result of a run.
+fn execution_result(execution_id: &str, workflow_id: &str, run: &Run) -> pb::ExecutionResult {
+    let result = &run.data.result_data;
+    pb::ExecutionResult {
+        execution_id: execution_id.to_string(),
+        workflow_id: workflow_id.to_string(),
+        status: execution_status(run.status),
+        mode: execute_mode_value(run.mode),
+        started_at: Some(timestamp(run.started_at)),
+        finished_at: run.finished_at.map(timestamp),
+        run_data: result
+            .run_data
+            .iter()
+            .map(|(node, runs)| {
+                let runs = runs.iter().map(task_data).collect();
+                (node.clone(), pb::NodeRunData { runs })
+            })
+            .collect(),
+        error: result.error.as_ref().map(execution_error),
+        wait_till: run.wait_till.map(timestamp),
+    }
+}
+
+fn task_data(task: &TaskData) -> pb::TaskData {
+    let data = task.data.iter().flatten().map(|(connection, outputs)| {
+        let outputs = outputs
+            .iter()
+            .map(|items| pb::NodeExecutionDataList {
+                items: items.iter().map(item).collect(),
+            })
+            .collect();
+        (connection.clone(), pb::TaskDataConnection { outputs })
+    });
+    pb::TaskData {
+        start_time: task.start_time,
+        execution_time: task.execution_time,
+        execution_status: execution_status(task.execution_status),
+        data: data.collect(),
+        error: task.error.as_ref().map(execution_error),
+    }
+}
+
+fn item(item: &NodeExecutionData) -> pb::NodeExecutionData {
+    let json = serde_json::Value::Object(object_to_json(&item.json));
+    let json = match to_prost(json).kind {
+        Some(Kind::StructValue(fields)) => Some(fields),
+        _ => None,
+    };
+    pb::NodeExecutionData {
+        json,
+        binary: item
+            .binary
+            .iter()
+            .flatten()
+            .map(|(key, binary)| {
+                let data = pb::BinaryData {
+                    data: binary.data.clone(),
+                    mime_type: binary.mime_type.clone(),
+                    file_name: binary.file_name.clone(),
+                    file_extension: binary.file_extension.clone(),
+                    file_size: binary.file_size.clone(),
+                    bytes: binary.bytes,
+                    id: binary.id.clone(),
+                    file_type: binary.file_type.map(|t| binary_file_type(t) as i32),
+                };
+                (key.clone(), data)
+            })
+            .collect(),
+        error: item.error.as_ref().map(execution_error),
+        paired_item: item
+            .paired_item
+            .iter()
+            .flatten()
+            .map(|paired| pb::PairedItemData {
+                item: paired.item as u32,
+                input: paired.input.map(|i| i as u32),
+                source_overwrite: paired.source_overwrite.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn execution_error(error: &ExecutionError) -> pb::ExecutionError {
+    pb::ExecutionError {
+        message: error.message.clone(),
+        node_name: error.context.node_name.clone(),
+        item_index: error.context.item_index.map(|i| i as u32),
+        run_index: error.context.run_index.map(|i| i as u32),
+        description: error.context.description.clone(),
+        cause: error.context.cause.clone(),
+        stack: error.stack.clone(),
+        timestamp: Some(timestamp(error.timestamp)),
+    }
+}
+
+fn timestamp(at: DateTime<Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: at.timestamp(),
+        nanos: at.timestamp_subsec_nanos() as i32,
+    }
+}
+
+fn execution_status(status: ExecutionStatus) -> i32 {
+    let status = match status {
+        ExecutionStatus::New => pb::ExecutionStatus::New,
+        ExecutionStatus::Running => pb::ExecutionStatus::Running,
+        ExecutionStatus::Success => pb::ExecutionStatus::Success,
+        ExecutionStatus::Error => pb::ExecutionStatus::Error,
+        ExecutionStatus::Waiting => pb::ExecutionStatus::Waiting,
+        ExecutionStatus::Canceled => pb::ExecutionStatus::Canceled,
+        ExecutionStatus::Crashed => pb::ExecutionStatus::Crashed,
+    };
+    status as i32
+}
+
+fn execute_mode_value(mode: WorkflowExecuteMode) -> i32 {
+    let mode = match mode {
+        WorkflowExecuteMode::Manual => pb::WorkflowExecuteMode::Manual,
+        WorkflowExecuteMode::Trigger => pb::WorkflowExecuteMode::Trigger,
+        WorkflowExecuteMode::Webhook => pb::WorkflowExecuteMode::Webhook,
+        WorkflowExecuteMode::Error => pb::WorkflowExecuteMode::Error,
+        WorkflowExecuteMode::Wait => pb::WorkflowExecuteMode::Wait,
+        WorkflowExecuteMode::Scheduled => pb::WorkflowExecuteMode::Scheduled,
+        WorkflowExecuteMode::Worker => pb::WorkflowExecuteMode::Worker,
+        WorkflowExecuteMode::Retry => pb::WorkflowExecuteMode::Retry,
+        WorkflowExecuteMode::Internal => pb::WorkflowExecuteMode::Internal,
+    };
+    mode as i32
+}
+
+/// Requested execution mode, manual when unset or unknown.
+fn execute_mode(mode: Option<i32>) -> WorkflowExecuteMode {
+    let mode = mode.and_then(|m| pb::WorkflowExecuteMode::try_from(m).ok());
+    match mode.unwrap_or(pb::WorkflowExecuteMode::Manual) {
+        pb::WorkflowExecuteMode::Manual => WorkflowExecuteMode::Manual,
+        pb::WorkflowExecuteMode::Trigger => WorkflowExecuteMode::Trigger,
+        pb::WorkflowExecuteMode::Webhook => WorkflowExecuteMode::Webhook,
+        pb::WorkflowExecuteMode::Error => WorkflowExecuteMode::Error,
+        pb::WorkflowExecuteMode::Wait => WorkflowExecuteMode::Wait,
+        pb::WorkflowExecuteMode::Scheduled => WorkflowExecuteMode::Scheduled,
+        pb::WorkflowExecuteMode::Worker => WorkflowExecuteMode::Worker,
+        pb::WorkflowExecuteMode::Retry => WorkflowExecuteMode::Retry,
+        pb::WorkflowExecuteMode::Internal => WorkflowExecuteMode::Internal,
+    }
+}
+
+fn binary_file_type(file_type: BinaryFileType) -> pb::BinaryFileType {
+    match file_type {
+        BinaryFileType::Text => pb::BinaryFileType::Text,
+        BinaryFileType::Json => pb::BinaryFileType::Json,
+        BinaryFileType::Image => pb::BinaryFileType::Image,
+        BinaryFileType::Audio => pb::BinaryFileType::Audio,
+        BinaryFileType::Video => pb::BinaryFileType::Video,
+        BinaryFileType::Pdf => pb::BinaryFileType::Pdf,
+        BinaryFileType::Html => pb::BinaryFileType::Html,
+        BinaryFileType::Other => pb::BinaryFileType::Other,
+    }
+}
+
+fn to_prost(value: serde_json::Value) -> prost_types::Value {
+    let kind = match value {
+        serde_json::Value::Null => Kind::NullValue(0),
+        serde_json::Value::Bool(b) => Kind::BoolValue(b),
+        serde_json::Value::Number(n) => Kind::NumberValue(n.as_f64().unwrap_or_default()),
+        serde_json::Value::String(s) => Kind::StringValue(s),
+        serde_json::Value::Array(values) => Kind::ListValue(prost_types::ListValue {
+            values: values.into_iter().map(to_prost).collect(),
+        }),
+        serde_json::Value::Object(map) => Kind::StructValue(prost_types::Struct {
+            fields: map.into_iter().map(|(k, v)| (k, to_prost(v))).collect(),
+        }),
+    };
+    prost_types::Value { kind: Some(kind) }
+}
+
+fn from_prost(value: prost_types::Value) -> serde_json::Value {
+    match value.kind {
+        None | Some(Kind::NullValue(_)) => serde_json::Value::Null,
+        Some(Kind::BoolValue(b)) => serde_json::Value::Bool(b),
+        // Struct numbers are doubles; keep integral ones integers.
+        Some(Kind::NumberValue(n)) if n.fract() == 0.0 && n.abs() < 9.0e15 => {
+            serde_json::Value::from(n as i64)
+        }
+        Some(Kind::NumberValue(n)) => serde_json::Value::from(n),
+        Some(Kind::StringValue(s)) => serde_json::Value::String(s),
+        Some(Kind::ListValue(list)) => {
+            serde_json::Value::Array(list.values.into_iter().map(from_prost).collect())
+        }
+        Some(Kind::StructValue(fields)) => serde_json::Value::Object(
+            fields.fields.into_iter().map(|(k, v)| (k, from_prost(v))).collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorkflowServiceState;
+    use n8n_core::WorkflowStorage;
+    use n8n_workflow::{Node, WorkflowBuilder};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_execute_workflow_stream_reports_progress_and_result() {
+        let state = Arc::new(WorkflowServiceState::new());
+        let workflow = WorkflowBuilder::new("Stream")
+            .node(Node::new("Start", "n8n-nodes-base.manualTrigger"))
+            .node(Node::new("Done", "n8n-nodes-base.noOp"))
+            .connect("Start", "Done", 0, 0)
+            .unwrap()
+            .build()
+            .unwrap();
+        state.workflows.save_workflow(&workflow).await.unwrap();
+        let service = WorkflowGrpcService::new(state);
+
+        let mut input = prost_types::Struct::default();
+        input.fields.insert("orderId".to_string(), to_prost(serde_json::json!(42)));
+        let request = pb::ExecuteWorkflowRequest {
+            workflow_id: workflow.id.clone(),
+            input_data: Some(input),
+            ..Default::default()
+        };
+        let stream = WorkflowService::execute_workflow_stream(&service, Request::new(request))
+            .await
+            .unwrap()
+            .into_inner();
+        let events: Vec<Event> = stream.map(|e| e.unwrap().event.unwrap()).collect().await;
+
+        let Some(Event::Started(started)) = events.first() else {
+            panic!("expected a started event, got {:?}", events.first());
+        };
+        let finished: Vec<&pb::NodeFinished> = events
+            .iter()
+            .filter_map(|e| match e {
+                Event::NodeFinished(finished) => Some(finished),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(finished.len(), 2);
+        assert!(finished.iter().all(|f| f.item_count == 1));
+
+        let Some(Event::Finished(done)) = events.last() else {
+            panic!("expected a finished event, got {:?}", events.last());
+        };
+        let result = done.result.as_ref().unwrap();
+        assert_eq!(result.execution_id, started.execution_id);
+        assert_eq!(result.workflow_id, workflow.id);
+        assert_eq!(result.status, pb::ExecutionStatus::Success as i32);
+        let output = &result.run_data["Done"].runs[0].data["main"].outputs[0].items[0];
+        let json = from_prost(prost_types::Value {
+            kind: output.json.clone().map(Kind::StructValue),
+        });
+        assert_eq!(json["orderId"], 42);
+
+        // The execution is stored under the streamed ID.
+        assert!(service.get_execution(&started.execution_id).await.is_ok());
+    }
+
+    #[test]
+    fn test_partial_execution_is_refused() {
+        let request = pb::ExecuteWorkflowRequest {
+            workflow_id: "w1".to_string(),
+            start_nodes: vec!["Done".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(input_items(&request).unwrap_err().code(), tonic::Code::InvalidArgument);
+        assert_eq!(execute_mode(Some(99)), WorkflowExecuteMode::Manual);
+    }
+}
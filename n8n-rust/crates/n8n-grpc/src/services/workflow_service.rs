@@ -2,16 +2,16 @@
 
 use n8n_arrow::{batch_to_ipc_bytes, run_data_to_batch, run_to_summary_batch};
 use n8n_core::{
-    ExecutionEvent, ExecutionStorage, WorkflowStorage,
+    current_correlation_id, with_correlation_id, ExecutionEvent, ExecutionStorage, WorkflowStorage,
     MemoryExecutionStorage, MemoryWorkflowStorage, RuntimeConfig, WorkflowEngine,
 };
 use n8n_workflow::{
-    ExecutionStatus, NodeExecutionData, Run, Workflow, WorkflowExecuteMode,
+    ExecutionStatus, NodeExecutionData, Run, TaskData, Workflow, WorkflowExecuteMode,
 };
 use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{mpsc, watch, RwLock};
 use tokio_stream::{wrappers::ReceiverStream, Stream};
 use tonic::Status;
 
@@ -25,10 +25,27 @@ pub struct WorkflowServiceState {
 
 impl WorkflowServiceState {
     pub fn new() -> Self {
+        Self::with_config(RuntimeConfig::default())
+    }
+
+    /// Create state whose engine uses the given runtime configuration.
+    pub fn with_config(config: RuntimeConfig) -> Self {
+        Self::with_engine(WorkflowEngine::new(config))
+    }
+
+    /// Create state whose engine follows runtime configuration reloads.
+    pub fn with_config_updates(updates: watch::Receiver<RuntimeConfig>) -> Self {
+        let initial = updates.borrow().clone();
+        Self::with_engine(WorkflowEngine::new(initial).with_config_updates(updates))
+    }
+
+    /// Sub-workflows are looked up in this state's workflow storage.
+    fn with_engine(engine: WorkflowEngine) -> Self {
+        let workflows = Arc::new(MemoryWorkflowStorage::new());
         Self {
-            workflows: Arc::new(MemoryWorkflowStorage::new()),
+            engine: Arc::new(engine.with_sub_workflows(workflows.clone())),
+            workflows,
             executions: Arc::new(MemoryExecutionStorage::new()),
-            engine: Arc::new(WorkflowEngine::new(RuntimeConfig::default())),
             running_executions: Arc::new(RwLock::new(HashMap::new())),
         }
     }
@@ -135,10 +152,14 @@ impl WorkflowGrpcService {
 
         let execution_id = uuid::Uuid::new_v4().to_string();
 
-        // Save execution
+        // Save execution, keeping full data only if sampled
+        let stored = self
+            .state
+            .engine
+            .prepare_for_storage(&workflow, &execution_id, &run);
         self.state
             .executions
-            .save_execution(&execution_id, &run)
+            .save_execution(&execution_id, &stored)
             .await
             .map_err(|e| Status::internal(e.to_string()))?;
 
@@ -172,24 +193,35 @@ impl WorkflowGrpcService {
 
         let engine = self.state.engine.clone();
         let executions = self.state.executions.clone();
+        let running = self.state.running_executions.clone();
+        let sampling = engine.config().sampling.for_workflow(&workflow.settings);
         let exec_id = execution_id.clone();
+        // Task-locals do not cross `tokio::spawn`; carry the correlation id over.
+        let correlation_id = current_correlation_id().unwrap_or_else(|| execution_id.clone());
 
         // Spawn execution task
         tokio::spawn(async move {
             let (internal_tx, mut internal_rx) = mpsc::channel(100);
 
-            let engine_handle = tokio::spawn(async move {
+            // Run under the stored ID so its progress can be looked up.
+            let engine_id = exec_id.clone();
+            let engine_handle = tokio::spawn(with_correlation_id(correlation_id, async move {
                 engine
-                    .execute_with_events(&workflow, mode, input_data, internal_tx)
+                    .execute_with_id(engine_id, &workflow, mode, input_data, internal_tx)
                     .await
-            });
+            }));
 
             loop {
                 tokio::select! {
                     Some(event) = internal_rx.recv() => {
                         let msg = match event {
-                            ExecutionEvent::Started { execution_id, workflow_id } => {
-                                ExecutionEventMessage::Started { execution_id, workflow_id }
+                            // Report the ID the execution is stored and
+                            // canceled under.
+                            ExecutionEvent::Started { workflow_id, .. } => {
+                                ExecutionEventMessage::Started {
+                                    execution_id: exec_id.clone(),
+                                    workflow_id,
+                                }
                             }
                             ExecutionEvent::NodeStarted { node_name, run_index } => {
                                 ExecutionEventMessage::NodeStarted { node_name, run_index }
@@ -201,13 +233,16 @@ impl WorkflowGrpcService {
                                     output_count: task_data.data.as_ref()
                                         .map(|d| d.values().flat_map(|v| v.iter().map(|i| i.len())).sum())
                                         .unwrap_or(0),
+                                    task_data,
                                 }
                             }
                             ExecutionEvent::Finished { result } => {
-                                // Save execution
-                                let _ = executions.save_execution(&exec_id, &result).await;
+                                // Save execution, keeping full data only if sampled
+                                let stored = sampling.apply(&exec_id, &result);
+                                let _ = executions.save_execution(&exec_id, &stored).await;
                                 ExecutionEventMessage::Finished {
                                     status: result.status,
+                                    run: result,
                                 }
                             }
                             ExecutionEvent::Error { error } => {
@@ -229,6 +264,7 @@ impl WorkflowGrpcService {
                     else => break,
                 }
             }
+            running.write().await.remove(&exec_id);
         });
 
         Ok(Box::pin(ReceiverStream::new(event_rx)))
@@ -292,6 +328,7 @@ impl WorkflowGrpcService {
 }
 
 /// Result of workflow execution.
+#[derive(Debug, Clone)]
 pub struct ExecutionResult {
     pub execution_id: String,
     pub workflow_id: String,
@@ -313,9 +350,11 @@ pub enum ExecutionEventMessage {
         node_name: String,
         run_index: usize,
         output_count: usize,
+        task_data: TaskData,
     },
     Finished {
         status: ExecutionStatus,
+        run: Run,
     },
     Error {
         message: String,
@@ -11,7 +11,11 @@ use axum::{
 };
 use n8n_core::{
     ExecutionStorage, WorkflowStorage, MemoryExecutionStorage, MemoryWorkflowStorage,
-    CompiledWorkflowCache, NodeExecutorRegistry,
+    CompiledWorkflowCache, DiagramFormat, ExecutionPlan, Linter, MigrationRegistry, NodeExecutorRegistry, RuleInfo,
+    ChainEntry, IntegrityConfig, VerificationReport, CloneOptions, CloneReport, CostConfig,
+    CostReport, ItemPage, LiveCounters, LiveSnapshot, OutputRef, RunDataSummary,
+    SchedulePreview, ExecutionProgress, InFlightExecution, TriggerHealth, TriggerState,
+    PatchOperation,
 };
 use n8n_workflow::{Connection, ExecutionStatus, Node, Run, Workflow, WorkflowExecuteMode, WorkflowSettings};
 use serde::{Deserialize, Serialize};
@@ -21,6 +25,8 @@ use chrono::{DateTime, Utc};
 use uuid::Uuid;
 use tokio::sync::RwLock;
 
+use super::patch::{patch_workflow, workflow_history};
+
 /// API state containing storage backends and compiled workflow cache.
 #[derive(Clone)]
 pub struct ApiState {
@@ -31,6 +37,18 @@ pub struct ApiState {
     pub compiled_cache: Arc<CompiledWorkflowCache>,
     /// Node executor registry for workflow compilation.
     pub executor_registry: Arc<NodeExecutorRegistry>,
+    /// Workflow linter.
+    pub linter: Arc<Linter>,
+    /// Node typeVersion migrations applied when workflows are saved.
+    pub migrations: Arc<MigrationRegistry>,
+    /// Hash chain settings used to verify stored executions.
+    pub integrity: IntegrityConfig,
+    /// Timezone of workflows that set none, for schedule previews.
+    pub timezone: String,
+    /// Executions in flight in the engine.
+    pub progress: ExecutionProgress,
+    /// Failures and pauses of the engine's triggers.
+    pub triggers: TriggerHealth,
 }
 
 /// Extended execution store that tracks execution metadata.
@@ -38,6 +56,8 @@ pub struct ExecutionStore {
     inner: MemoryExecutionStorage,
     /// Map of execution ID -> (workflow_id, workflow_name)
     execution_metadata: RwLock<HashMap<String, ExecutionMetadata>>,
+    /// Queued, running and waiting executions per workflow.
+    live: LiveCounters,
 }
 
 #[derive(Clone)]
@@ -46,11 +66,18 @@ pub struct ExecutionMetadata {
     workflow_name: String,
 }
 
+impl ExecutionMetadata {
+    pub fn workflow_id(&self) -> &str {
+        &self.workflow_id
+    }
+}
+
 impl ExecutionStore {
     pub fn new() -> Self {
         Self {
             inner: MemoryExecutionStorage::new(),
             execution_metadata: RwLock::new(HashMap::new()),
+            live: LiveCounters::new(),
         }
     }
 
@@ -62,7 +89,9 @@ impl ExecutionStore {
                 workflow_name: workflow_name.to_string(),
             },
         );
-        self.inner.save_execution(id, run).await
+        self.inner.save_execution(id, run).await?;
+        self.live.observe(id, workflow_id, run.status);
+        Ok(())
     }
 
     pub async fn get_execution(&self, id: &str) -> Result<Option<(Run, Option<ExecutionMetadata>)>, n8n_core::ExecutionEngineError> {
@@ -73,9 +102,24 @@ impl ExecutionStore {
 
     pub async fn delete_execution(&self, id: &str) -> Result<bool, n8n_core::ExecutionEngineError> {
         self.execution_metadata.write().await.remove(id);
+        self.live.remove(id);
         self.inner.delete_execution(id).await
     }
 
+    /// Current live execution counts.
+    pub fn live(&self) -> LiveSnapshot {
+        self.live.snapshot()
+    }
+
+    /// Rebuild the live counters from stored executions. Returns how many
+    /// executions had drifted.
+    pub async fn reconcile_live(&self) -> Result<usize, n8n_core::ExecutionEngineError> {
+        let stored = self.list_all_executions().await?.into_iter().map(|(id, run, meta)| {
+            (id, meta.map(|m| m.workflow_id).unwrap_or_default(), run.status)
+        });
+        Ok(self.live.reconcile(stored))
+    }
+
     pub async fn list_all_executions(&self) -> Result<Vec<(String, Run, Option<ExecutionMetadata>)>, n8n_core::ExecutionEngineError> {
         // Get all metadata
         let metadata = self.execution_metadata.read().await.clone();
@@ -107,6 +151,12 @@ impl ApiState {
             executions,
             compiled_cache: Arc::new(CompiledWorkflowCache::new()),
             executor_registry: Arc::new(NodeExecutorRegistry::new()),
+            linter: Arc::new(Linter::default()),
+            migrations: Arc::new(MigrationRegistry::default()),
+            integrity: IntegrityConfig::default(),
+            timezone: "UTC".to_string(),
+            progress: ExecutionProgress::new(),
+            triggers: TriggerHealth::new(),
         }
     }
 
@@ -121,6 +171,72 @@ impl ApiState {
             executions,
             compiled_cache: Arc::new(CompiledWorkflowCache::new()),
             executor_registry: Arc::new(registry),
+            linter: Arc::new(Linter::default()),
+            migrations: Arc::new(MigrationRegistry::default()),
+            integrity: IntegrityConfig::default(),
+            timezone: "UTC".to_string(),
+            progress: ExecutionProgress::new(),
+            triggers: TriggerHealth::new(),
+        }
+    }
+
+    /// Use a custom linter (e.g. with extra rules registered).
+    pub fn with_linter(mut self, linter: Linter) -> Self {
+        self.linter = Arc::new(linter);
+        self
+    }
+
+    /// Use a custom migration registry.
+    pub fn with_migrations(mut self, migrations: MigrationRegistry) -> Self {
+        self.migrations = Arc::new(migrations);
+        self
+    }
+
+    /// Verify execution hash chains with these settings; the secret must
+    /// match the one the engine sealed them with.
+    pub fn with_integrity(mut self, integrity: IntegrityConfig) -> Self {
+        self.integrity = integrity;
+        self
+    }
+
+    /// Read schedules of workflows without a timezone in `timezone`.
+    pub fn with_timezone(mut self, timezone: impl Into<String>) -> Self {
+        self.timezone = timezone.into();
+        self
+    }
+
+    /// Report in-flight executions from the engine's `progress`.
+    pub fn with_progress(mut self, progress: ExecutionProgress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Report and resume the engine's paused triggers from `triggers`.
+    pub fn with_trigger_health(mut self, triggers: TriggerHealth) -> Self {
+        self.triggers = triggers;
+        self
+    }
+
+    /// Upgrade outdated nodes to their current typeVersion. Nodes whose
+    /// migration fails are kept as-is and logged.
+    pub(super) fn migrate(&self, workflow: &mut Workflow) {
+        let report = self.migrations.migrate_workflow(workflow);
+        for applied in &report.applied {
+            tracing::info!(
+                workflow = %workflow.name,
+                node = %applied.node,
+                from = %format!("{}@{}", applied.from_type, applied.from_version),
+                to = %format!("{}@{}", applied.to_type, applied.to_version),
+                "Migrated node"
+            );
+        }
+        for failed in &report.failed {
+            tracing::warn!(
+                workflow = %workflow.name,
+                node = %failed.node,
+                error = %failed.message,
+                "Node migration failed"
+            );
         }
     }
 }
@@ -142,6 +258,10 @@ pub struct WorkflowRequest {
     pub settings: Option<serde_json::Value>,
     #[serde(default)]
     pub static_data: Option<serde_json::Value>,
+    /// Version an update is based on; the update is refused with 409 if the
+    /// workflow changed since.
+    #[serde(default)]
+    pub version_id: Option<String>,
 }
 
 /// Workflow response matching n8n's API format.
@@ -157,8 +277,13 @@ pub struct WorkflowResponse {
     pub settings: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub static_data: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Next planned runs of the schedule triggers; workflow detail only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<SchedulePreview>,
 }
 
 impl From<&Workflow> for WorkflowResponse {
@@ -171,12 +296,36 @@ impl From<&Workflow> for WorkflowResponse {
             connections: w.connections.clone(),
             settings: serde_json::to_value(&w.settings).ok(),
             static_data: w.static_data.as_ref().and_then(|d| serde_json::to_value(d).ok()),
+            version_id: w.version_id.clone(),
             created_at: w.created_at.unwrap_or_else(Utc::now),
             updated_at: w.updated_at.unwrap_or_else(Utc::now),
+            schedule: None,
         }
     }
 }
 
+/// Request body for cloning a workflow.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneWorkflowRequest {
+    /// Project to own the copy. Projects need database storage, so this
+    /// in-memory API rejects it.
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(flatten)]
+    pub options: CloneOptions,
+}
+
+/// A cloned workflow and what was remapped.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneWorkflowResponse {
+    #[serde(flatten)]
+    pub workflow: WorkflowResponse,
+    pub cloned_from: String,
+    pub remapping: CloneReport,
+}
+
 /// List workflows query parameters.
 #[derive(Debug, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -185,6 +334,9 @@ pub struct ListWorkflowsQuery {
     pub active: Option<bool>,
     #[serde(default)]
     pub tags: Option<String>,
+    /// Words to search for in names, tags, descriptions and node types.
+    #[serde(default)]
+    pub search: Option<String>,
     #[serde(default)]
     pub limit: Option<usize>,
     #[serde(default)]
@@ -253,7 +405,7 @@ pub struct ResultDataResponse {
 }
 
 impl ExecutionResponse {
-    fn from_run(id: String, run: &Run, workflow_id: Option<String>) -> Self {
+    pub(super) fn from_run(id: String, run: &Run, workflow_id: Option<String>) -> Self {
         let finished = run.status.is_finished();
 
         Self {
@@ -297,6 +449,40 @@ pub struct ListExecutionsQuery {
     pub cursor: Option<String>,
 }
 
+/// Query parameters of `GET /executions/:id`.
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GetExecutionQuery {
+    /// `false` leaves `runData` empty; fetch it with the data endpoints.
+    #[serde(default)]
+    pub include_data: Option<bool>,
+}
+
+/// Items returned per page when no `limit` is given.
+pub const DEFAULT_ITEM_PAGE: usize = 100;
+
+/// Largest accepted `limit` of an item page.
+pub const MAX_ITEM_PAGE: usize = 1000;
+
+/// Query parameters selecting a page of a node's output items.
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionItemsQuery {
+    /// Run index of the node; defaults to its first run.
+    #[serde(default)]
+    pub run: Option<usize>,
+    /// Connection type; defaults to `main`.
+    #[serde(default)]
+    pub connection: Option<String>,
+    /// Output index; defaults to 0.
+    #[serde(default)]
+    pub output: Option<usize>,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
 // ============================================================================
 // API Error Type
 // ============================================================================
@@ -315,16 +501,60 @@ impl IntoResponse for ApiError {
     }
 }
 
+/// Save `workflow` and return it as stored, with its new version ID. A save
+/// based on an outdated version is a 409.
+async fn save_workflow(state: &ApiState, workflow: &Workflow) -> Result<Workflow, ApiError> {
+    let saved = state.workflows.save_workflow(workflow).await;
+    stored(state, workflow, saved).await
+}
+
+/// [`save_workflow`], recording the saved version in the workflow's history
+/// in the same write, with the `operations` of the patch that produced it.
+pub(super) async fn save_workflow_version(
+    state: &ApiState,
+    workflow: &Workflow,
+    operations: Option<&[PatchOperation]>,
+) -> Result<Workflow, ApiError> {
+    let saved = state.workflows.save_workflow_version(workflow, operations).await;
+    stored(state, workflow, saved).await
+}
+
+async fn stored(
+    state: &ApiState,
+    workflow: &Workflow,
+    saved: Result<(), n8n_core::ExecutionEngineError>,
+) -> Result<Workflow, ApiError> {
+    saved.map_err(|e| ApiError {
+        code: match e {
+            n8n_core::ExecutionEngineError::VersionConflict(_) => 409,
+            _ => 500,
+        },
+        message: e.to_string(),
+    })?;
+    Ok(state
+        .workflows
+        .get_workflow(&workflow.id)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| workflow.clone()))
+}
+
 // ============================================================================
 // Workflow Handlers
 // ============================================================================
 
-/// GET /workflows - List all workflows.
+/// GET /workflows - List all workflows, or with `search` those matching
+/// it, best matches first.
 pub async fn list_workflows(
     State(state): State<ApiState>,
     Query(query): Query<ListWorkflowsQuery>,
 ) -> Result<Json<ListResponse<WorkflowResponse>>, ApiError> {
-    let workflows = state.workflows.list_workflows().await
+    let workflows = match query.search.as_deref().map(str::trim) {
+        Some(search) if !search.is_empty() => state.workflows.search_workflows(search).await,
+        _ => state.workflows.list_workflows().await,
+    };
+    let workflows = workflows
         .map_err(|e| ApiError {
             code: 500,
             message: e.to_string(),
@@ -365,7 +595,7 @@ pub async fn create_workflow(
         .and_then(|v| serde_json::from_value(v.clone()).ok())
         .unwrap_or_default();
 
-    let workflow = Workflow {
+    let mut workflow = Workflow {
         id: Uuid::new_v4().to_string(),
         name: request.name,
         active: false,
@@ -379,12 +609,9 @@ pub async fn create_workflow(
         created_at: Some(Utc::now()),
         updated_at: Some(Utc::now()),
     };
+    state.migrate(&mut workflow);
 
-    state.workflows.save_workflow(&workflow).await
-        .map_err(|e| ApiError {
-            code: 500,
-            message: e.to_string(),
-        })?;
+    let workflow = save_workflow_version(&state, &workflow, None).await?;
 
     Ok((StatusCode::CREATED, Json(WorkflowResponse::from(&workflow))))
 }
@@ -404,7 +631,14 @@ pub async fn get_workflow(
             message: format!("Workflow {} not found", id),
         })?;
 
-    Ok(Json(WorkflowResponse::from(&workflow)))
+    let mut response = WorkflowResponse::from(&workflow);
+    response.schedule = n8n_core::schedule::preview(
+        &workflow,
+        &state.timezone,
+        Utc::now(),
+        DEFAULT_SCHEDULE_PREVIEW_RUNS,
+    );
+    Ok(Json(response))
 }
 
 /// PUT /workflows/:id - Update a workflow.
@@ -434,7 +668,7 @@ pub async fn update_workflow(
         .and_then(|v| serde_json::from_value(v.clone()).ok())
         .unwrap_or(existing.settings);
 
-    let workflow = Workflow {
+    let mut workflow = Workflow {
         id,
         name: request.name,
         active: existing.active,
@@ -444,10 +678,14 @@ pub async fn update_workflow(
         static_data: existing.static_data, // Keep existing static data
         pin_data: existing.pin_data,
         description: existing.description,
-        version_id: existing.version_id,
+        version_id: request.version_id.or(existing.version_id),
         created_at: existing.created_at,
         updated_at: Some(Utc::now()),
     };
+    state.migrate(&mut workflow);
+
+    // Saved first: an update that lost to a concurrent one changes nothing.
+    let workflow = save_workflow_version(&state, &workflow, None).await?;
 
     // If workflow was active, recompile with new routing.
     if workflow.active {
@@ -456,12 +694,6 @@ pub async fn update_workflow(
         }
     }
 
-    state.workflows.save_workflow(&workflow).await
-        .map_err(|e| ApiError {
-            code: 500,
-            message: e.to_string(),
-        })?;
-
     Ok(Json(WorkflowResponse::from(&workflow)))
 }
 
@@ -524,11 +756,7 @@ pub async fn activate_workflow(
         }
     }
 
-    state.workflows.save_workflow(&workflow).await
-        .map_err(|e| ApiError {
-            code: 500,
-            message: e.to_string(),
-        })?;
+    let workflow = save_workflow(&state, &workflow).await?;
 
     Ok(Json(WorkflowResponse::from(&workflow)))
 }
@@ -558,13 +786,290 @@ pub async fn deactivate_workflow(
         tracing::info!(workflow = %workflow.name, "Invalidated compiled hot path cache");
     }
 
+    let workflow = save_workflow(&state, &workflow).await?;
+
+    Ok(Json(WorkflowResponse::from(&workflow)))
+}
+
+/// POST /workflows/:id/clone - Copy a workflow with new IDs, remapping
+/// credentials and variables.
+pub async fn clone_workflow(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    request: Option<Json<CloneWorkflowRequest>>,
+) -> Result<(StatusCode, Json<CloneWorkflowResponse>), ApiError> {
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    if request.project_id.is_some() {
+        return Err(ApiError {
+            code: 400,
+            message: "projectId requires database-backed workflow storage".to_string(),
+        });
+    }
+
+    let source = state.workflows.get_workflow(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        })?;
+
+    let (workflow, remapping) = n8n_core::clone_workflow(&source, &request.options);
     state.workflows.save_workflow(&workflow).await
         .map_err(|e| ApiError {
             code: 500,
             message: e.to_string(),
         })?;
 
-    Ok(Json(WorkflowResponse::from(&workflow)))
+    Ok((
+        StatusCode::CREATED,
+        Json(CloneWorkflowResponse {
+            workflow: WorkflowResponse::from(&workflow),
+            cloned_from: id,
+            remapping,
+        }),
+    ))
+}
+
+/// GET /workflows/:id/explain - Describe what executing a workflow would do.
+pub async fn explain_workflow(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<ExecutionPlan>, ApiError> {
+    let workflow = state.workflows.get_workflow(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        })?;
+
+    explain_plan(&state, &workflow)
+}
+
+/// POST /workflows/explain - Explain an unsaved workflow (e.g. from CI).
+pub async fn explain_workflow_body(
+    State(state): State<ApiState>,
+    Json(request): Json<WorkflowRequest>,
+) -> Result<Json<ExecutionPlan>, ApiError> {
+    explain_plan(&state, &unsaved_workflow(request))
+}
+
+/// Build a transient workflow from a request body.
+fn unsaved_workflow(request: WorkflowRequest) -> Workflow {
+    let mut workflow = Workflow::new(request.name);
+    workflow.nodes = request.nodes;
+    workflow.connections = request.connections;
+    workflow
+}
+
+fn explain_plan(state: &ApiState, workflow: &Workflow) -> Result<Json<ExecutionPlan>, ApiError> {
+    n8n_core::explain(workflow, &state.executor_registry)
+        .map(Json)
+        .map_err(|e| ApiError {
+            code: 422,
+            message: e.to_string(),
+        })
+}
+
+/// Lint query parameters.
+#[derive(Debug, Deserialize, Default)]
+pub struct LintQuery {
+    /// `json` (default) or `sarif`.
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// GET /workflows/:id/lint - Lint a stored workflow.
+pub async fn lint_workflow(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<LintQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let workflow = state.workflows.get_workflow(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        })?;
+
+    lint_response(&state, &workflow, &query)
+}
+
+/// POST /workflows/lint - Lint a workflow in the request body (e.g. from CI).
+pub async fn lint_workflow_body(
+    State(state): State<ApiState>,
+    Query(query): Query<LintQuery>,
+    Json(request): Json<WorkflowRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    lint_response(&state, &unsaved_workflow(request), &query)
+}
+
+/// GET /lint/rules - List registered lint rules and their levels.
+pub async fn list_lint_rules(State(state): State<ApiState>) -> Json<Vec<RuleInfo>> {
+    Json(state.linter.rules())
+}
+
+fn lint_response(
+    state: &ApiState,
+    workflow: &Workflow,
+    query: &LintQuery,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let report = state.linter.lint(workflow);
+    match query.format.as_deref() {
+        None | Some("json") => Ok(Json(serde_json::json!(report))),
+        Some("sarif") => Ok(Json(n8n_core::to_sarif(&state.linter, &[report]))),
+        Some(other) => Err(ApiError {
+            code: 400,
+            message: format!("Unknown lint format: {}", other),
+        }),
+    }
+}
+
+/// Diagram query parameters.
+#[derive(Debug, Deserialize, Default)]
+pub struct DiagramQuery {
+    /// `mermaid` (default) or `dot`.
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// GET /workflows/:id/diagram - Render a stored workflow as Mermaid or DOT.
+pub async fn workflow_diagram(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<DiagramQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let format = match query.format.as_deref() {
+        None => DiagramFormat::Mermaid,
+        Some(f) => DiagramFormat::from_str(f).ok_or_else(|| ApiError {
+            code: 400,
+            message: format!("Unknown diagram format: {}", f),
+        })?,
+    };
+
+    let workflow = state.workflows.get_workflow(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        })?;
+
+    Ok((
+        [(axum::http::header::CONTENT_TYPE, format.content_type())],
+        n8n_core::diagram::render(&workflow, format),
+    ))
+}
+
+/// GET /workflows/:id/docs - Render a stored workflow's Markdown documentation.
+pub async fn workflow_docs(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let workflow = state.workflows.get_workflow(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        })?;
+
+    Ok((
+        [(axum::http::header::CONTENT_TYPE, n8n_core::docs::CONTENT_TYPE)],
+        n8n_core::docs::render(&workflow),
+    ))
+}
+
+/// Runs listed by schedule previews unless asked for more.
+const DEFAULT_SCHEDULE_PREVIEW_RUNS: usize = 5;
+
+/// Schedule preview query parameters.
+#[derive(Debug, Deserialize, Default)]
+pub struct ScheduleQuery {
+    /// Runs to list, at most 100; 5 by default.
+    #[serde(default)]
+    pub count: Option<usize>,
+}
+
+/// GET /workflows/:id/schedule - Next planned runs of a workflow's schedule
+/// triggers, in its timezone and run window.
+pub async fn workflow_schedule(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<ScheduleQuery>,
+) -> Result<Json<SchedulePreview>, ApiError> {
+    let workflow = state.workflows.get_workflow(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        })?;
+
+    let count = query.count.unwrap_or(DEFAULT_SCHEDULE_PREVIEW_RUNS);
+    n8n_core::schedule::preview(&workflow, &state.timezone, Utc::now(), count)
+        .map(Json)
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} has no enabled schedule trigger", id),
+        })
+}
+
+/// GET /workflows/:id/triggers - Triggers of a workflow that failed since
+/// their last success or are paused.
+pub async fn workflow_triggers(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<TriggerState>>, ApiError> {
+    let workflow = state.workflows.get_workflow(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        })?;
+
+    Ok(Json(state.triggers.for_workflow(&workflow.id)))
+}
+
+/// POST /workflows/:id/triggers/:node/resume - Resume a paused trigger.
+pub async fn resume_trigger(
+    State(state): State<ApiState>,
+    Path((id, node)): Path<(String, String)>,
+) -> Result<Json<Vec<TriggerState>>, ApiError> {
+    let workflow = state.workflows.get_workflow(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        })?;
+
+    if !state.triggers.resume(&workflow.id, &node) {
+        return Err(ApiError {
+            code: 409,
+            message: format!("Trigger '{}' of workflow {} is not paused", node, id),
+        });
+    }
+    Ok(Json(state.triggers.for_workflow(&workflow.id)))
 }
 
 // ============================================================================
@@ -669,6 +1174,7 @@ pub async fn create_execution(
 pub async fn get_execution(
     State(state): State<ApiState>,
     Path(id): Path<String>,
+    Query(query): Query<GetExecutionQuery>,
 ) -> Result<Json<ExecutionResponse>, ApiError> {
     let (run, metadata) = state.executions.get_execution(&id).await
         .map_err(|e| ApiError {
@@ -680,7 +1186,67 @@ pub async fn get_execution(
             message: format!("Execution {} not found", id),
         })?;
 
-    Ok(Json(ExecutionResponse::from_run(id, &run, metadata.map(|m| m.workflow_id))))
+    let mut response = ExecutionResponse::from_run(id, &run, metadata.map(|m| m.workflow_id));
+    if query.include_data == Some(false) {
+        response.data.result_data.run_data = serde_json::json!({});
+    }
+    Ok(Json(response))
+}
+
+/// GET /executions/:id/data - Item counts and sizes per node, run and output.
+pub async fn get_execution_data_summary(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<RunDataSummary>, ApiError> {
+    let (run, _) = state.executions.get_execution(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Execution {} not found", id),
+        })?;
+
+    Ok(Json(n8n_core::run_data::summarize(&run)))
+}
+
+/// GET /executions/:id/data/:node - A page of one output of a node run.
+pub async fn get_execution_items(
+    State(state): State<ApiState>,
+    Path((id, node)): Path<(String, String)>,
+    Query(query): Query<ExecutionItemsQuery>,
+) -> Result<Json<ItemPage>, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_ITEM_PAGE);
+    if limit == 0 || limit > MAX_ITEM_PAGE {
+        return Err(ApiError {
+            code: 400,
+            message: format!("limit must be between 1 and {}", MAX_ITEM_PAGE),
+        });
+    }
+
+    let (run, _) = state.executions.get_execution(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Execution {} not found", id),
+        })?;
+
+    let output = OutputRef {
+        node: &node,
+        run_index: query.run.unwrap_or(0),
+        connection_type: query.connection.as_deref().unwrap_or("main"),
+        output_index: query.output.unwrap_or(0),
+    };
+    n8n_core::run_data::page_items(&run, &output, query.offset.unwrap_or(0), limit)
+        .map(Json)
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Node {} has no run {} in execution {}", node, output.run_index, id),
+        })
 }
 
 /// DELETE /executions/:id - Delete an execution.
@@ -697,6 +1263,23 @@ pub async fn delete_execution(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// GET /executions/live - Queued, running and waiting executions per workflow.
+pub async fn live_executions(State(state): State<ApiState>) -> Json<LiveSnapshot> {
+    Json(state.executions.live())
+}
+
+/// GET /executions/:id/status - Current node, queue position and timings of
+/// an execution that is queued or running.
+pub async fn execution_status(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<InFlightExecution>, ApiError> {
+    state.progress.status(&id).map(Json).ok_or_else(|| ApiError {
+        code: 404,
+        message: format!("Execution {} is not in flight", id),
+    })
+}
+
 /// POST /executions/:id/stop - Stop a running execution.
 pub async fn stop_execution(
     State(state): State<ApiState>,
@@ -767,6 +1350,152 @@ pub async fn retry_execution(
     ))
 }
 
+/// GET /executions/:id/integrity - Verify an execution's hash chain.
+pub async fn verify_execution_integrity(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<VerificationReport>, ApiError> {
+    let (run, metadata) = state.executions.get_execution(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Execution {} not found", id),
+        })?;
+
+    let workflow_id = metadata.map(|m| m.workflow_id).unwrap_or_default();
+    Ok(Json(n8n_core::integrity::verify(&run, &workflow_id, &state.integrity)))
+}
+
+/// GET /executions/:id/integrity/chain - Export an execution's hash chain.
+pub async fn export_execution_chain(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<ChainEntry>>, ApiError> {
+    let (run, _) = state.executions.get_execution(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Execution {} not found", id),
+        })?;
+
+    Ok(Json(n8n_core::integrity::export_chain(&run)))
+}
+
+/// Profile query parameters.
+#[derive(Debug, Deserialize, Default)]
+pub struct ProfileQuery {
+    /// `report` (default) or `flamegraph`.
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// GET /executions/:id/profile - Per-node profiles of a profiled execution,
+/// as a report or a flame graph.
+pub async fn get_execution_profile(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<ProfileQuery>,
+) -> Result<axum::response::Response, ApiError> {
+    let (run, _) = state.executions.get_execution(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Execution {} not found", id),
+        })?;
+
+    let report = n8n_core::profile_report(&run);
+    match query.format.as_deref() {
+        None | Some("report") => Ok(Json(report).into_response()),
+        Some("flamegraph") => Ok(Json(report.flamegraph(&id)).into_response()),
+        Some(f) => Err(ApiError {
+            code: 400,
+            message: format!("Unknown profile format: {}", f),
+        }),
+    }
+}
+
+/// Query parameters of workflow cost reports.
+#[derive(Debug, Deserialize)]
+pub struct CostQuery {
+    /// Only count executions started at or after this time.
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Currency of a run's recorded costs.
+fn cost_currency(run: &Run) -> Option<String> {
+    run.data.result_data.metadata.as_ref()?.get(n8n_core::cost::COST_CURRENCY_KEY).cloned()
+}
+
+/// GET /executions/:id/cost - Cost of an execution by node type and unit.
+pub async fn get_execution_cost(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<CostReport>, ApiError> {
+    let (run, _) = state.executions.get_execution(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Execution {} not found", id),
+        })?;
+
+    let currency = cost_currency(&run).unwrap_or_else(|| CostConfig::default().currency);
+    let mut report = CostReport::new(currency);
+    report.add_execution(&n8n_core::cost::cost_records(&run));
+    Ok(Json(report))
+}
+
+/// GET /workflows/:id/cost - Cost of a workflow's executions.
+pub async fn get_workflow_cost(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<CostQuery>,
+) -> Result<Json<CostReport>, ApiError> {
+    state.workflows.get_workflow(&id).await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        })?;
+
+    let executions = state.executions.list_all_executions().await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?;
+
+    let runs: Vec<Run> = executions
+        .into_iter()
+        .filter(|(_, _, meta)| meta.as_ref().is_some_and(|m| m.workflow_id == id))
+        .map(|(_, run, _)| run)
+        .filter(|run| query.since.map_or(true, |since| run.started_at >= since))
+        .collect();
+
+    let currency = runs
+        .iter()
+        .find_map(cost_currency)
+        .unwrap_or_else(|| CostConfig::default().currency);
+    let mut report = CostReport::new(currency);
+    for run in &runs {
+        report.add_execution(&n8n_core::cost::cost_records(run));
+    }
+    Ok(Json(report))
+}
+
 // ============================================================================
 // Router Setup
 // ============================================================================
@@ -778,13 +1507,37 @@ pub fn create_api_router(state: ApiState) -> Router {
     Router::new()
         // Workflow endpoints
         .route("/api/v1/workflows", axum_get(list_workflows).post(create_workflow))
-        .route("/api/v1/workflows/:id", axum_get(get_workflow).put(update_workflow).delete(delete_workflow))
+        .route(
+            "/api/v1/workflows/:id",
+            axum_get(get_workflow).put(update_workflow).patch(patch_workflow).delete(delete_workflow),
+        )
+        .route("/api/v1/workflows/:id/history", axum_get(workflow_history))
         .route("/api/v1/workflows/:id/activate", axum_post(activate_workflow))
         .route("/api/v1/workflows/:id/deactivate", axum_post(deactivate_workflow))
+        .route("/api/v1/workflows/:id/clone", axum_post(clone_workflow))
+        .route("/api/v1/workflows/:id/cost", axum_get(get_workflow_cost))
+        .route("/api/v1/workflows/:id/explain", axum_get(explain_workflow))
+        .route("/api/v1/workflows/explain", axum_post(explain_workflow_body))
+        .route("/api/v1/workflows/:id/lint", axum_get(lint_workflow))
+        .route("/api/v1/workflows/:id/diagram", axum_get(workflow_diagram))
+        .route("/api/v1/workflows/:id/docs", axum_get(workflow_docs))
+        .route("/api/v1/workflows/:id/schedule", axum_get(workflow_schedule))
+        .route("/api/v1/workflows/:id/triggers", axum_get(workflow_triggers))
+        .route("/api/v1/workflows/:id/triggers/:node/resume", axum_post(resume_trigger))
+        .route("/api/v1/workflows/lint", axum_post(lint_workflow_body))
+        .route("/api/v1/lint/rules", axum_get(list_lint_rules))
         // Execution endpoints
         .route("/api/v1/executions", axum_get(list_executions).post(create_execution))
+        .route("/api/v1/executions/live", axum_get(live_executions))
         .route("/api/v1/executions/:id", axum_get(get_execution).delete(delete_execution))
         .route("/api/v1/executions/:id/stop", axum_post(stop_execution))
+        .route("/api/v1/executions/:id/status", axum_get(execution_status))
         .route("/api/v1/executions/:id/retry", axum_post(retry_execution))
+        .route("/api/v1/executions/:id/integrity", axum_get(verify_execution_integrity))
+        .route("/api/v1/executions/:id/integrity/chain", axum_get(export_execution_chain))
+        .route("/api/v1/executions/:id/cost", axum_get(get_execution_cost))
+        .route("/api/v1/executions/:id/profile", axum_get(get_execution_profile))
+        .route("/api/v1/executions/:id/data", axum_get(get_execution_data_summary))
+        .route("/api/v1/executions/:id/data/:node", axum_get(get_execution_items))
         .with_state(state)
 }
@@ -0,0 +1,100 @@
+//! Webhook delivery endpoints.
+//!
+//! Deliveries of `n8n-nodes-base.webhookDelivery` nodes
+//! ([`n8n_core::delivery`]) with their attempts:
+//!
+//! - `GET /api/v1/deliveries` lists the latest, optionally of one
+//!   `workflowId`, at most `limit` (default 50, up to 500);
+//! - `GET /api/v1/deliveries/:id` returns one;
+//! - `POST /api/v1/deliveries/:id/redeliver` sends it once more, under the
+//!   egress rules of its workflow's project, and returns it with the new
+//!   attempt.
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use n8n_core::{Delivery, DeliveryError, WorkflowEngine};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use super::api::ApiError;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDeliveriesQuery {
+    pub workflow_id: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Router serving the delivery endpoints.
+pub fn create_deliveries_router(engine: Arc<WorkflowEngine>) -> Router {
+    Router::new()
+        .route("/api/v1/deliveries", get(list_deliveries))
+        .route("/api/v1/deliveries/:id", get(get_delivery))
+        .route("/api/v1/deliveries/:id/redeliver", post(redeliver))
+        .with_state(engine)
+}
+
+fn delivery_error(e: DeliveryError) -> ApiError {
+    let code = match e {
+        DeliveryError::NotFound(_) => 404,
+        DeliveryError::Refused(_) => 403,
+        _ => 500,
+    };
+    ApiError {
+        code,
+        message: e.to_string(),
+    }
+}
+
+/// GET /deliveries - Latest webhook deliveries.
+pub async fn list_deliveries(
+    State(engine): State<Arc<WorkflowEngine>>,
+    Query(query): Query<ListDeliveriesQuery>,
+) -> Result<Json<Vec<Delivery>>, ApiError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let deliveries = engine
+        .webhook_deliveries()
+        .list(query.workflow_id.as_deref(), limit)
+        .await
+        .map_err(delivery_error)?;
+    Ok(Json(deliveries))
+}
+
+/// GET /deliveries/:id - A delivery and its attempts.
+pub async fn get_delivery(
+    State(engine): State<Arc<WorkflowEngine>>,
+    Path(id): Path<String>,
+) -> Result<Json<Delivery>, ApiError> {
+    let delivery = engine.webhook_deliveries().get(&id).await.map_err(delivery_error)?;
+    Ok(Json(delivery))
+}
+
+/// POST /deliveries/:id/redeliver - Send a delivery again.
+pub async fn redeliver(
+    State(engine): State<Arc<WorkflowEngine>>,
+    Path(id): Path<String>,
+) -> Result<Json<Delivery>, ApiError> {
+    let deliveries = engine.webhook_deliveries();
+    let delivery = deliveries.get(&id).await.map_err(delivery_error)?;
+
+    let internal = |message: String| ApiError { code: 500, message };
+    let project = match &delivery.workflow_id {
+        Some(workflow_id) => engine
+            .workflow_projects()
+            .project_of(workflow_id)
+            .await
+            .map_err(internal)?,
+        None => None,
+    };
+    let egress = engine
+        .config()
+        .egress
+        .policy(project.as_deref())
+        .map_err(|e| internal(e.to_string()))?;
+
+    let delivery = deliveries.redeliver(&Arc::new(egress), &id).await.map_err(delivery_error)?;
+    Ok(Json(delivery))
+}
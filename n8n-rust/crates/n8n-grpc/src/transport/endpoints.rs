@@ -0,0 +1,636 @@
+//! Workflows published as REST endpoints.
+//!
+//! A [`WorkflowEndpoint`] maps a method and path below `/endpoint/` to a
+//! node of an active workflow, e.g. `GET /endpoint/orders/:id`. Unlike raw
+//! webhooks the mapping is managed over `/api/v1/endpoints` and persisted
+//! through an [`EndpointStore`], so it stays stable when the workflow's
+//! nodes change. The node receives the same item as a webhook node, with
+//! the path parameters in `params`.
+//!
+//! Callers authenticate with an API key in `X-N8N-API-KEY`; the key must
+//! carry every scope the endpoint lists ([`ApiKeyScopes`]). A declared
+//! `requestSchema` is checked before the workflow starts (`422` on
+//! mismatch); `requestSchema` and `responseSchema` also describe the
+//! endpoint in the OpenAPI fragment at `/api/v1/endpoints/openapi.json`.
+//! A single output item is returned as an object, several as an array.
+//!
+//! Endpoint calls count against the webhook admission limit.
+
+use async_trait::async_trait;
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{any, delete, get},
+    Json, Router,
+};
+use n8n_core::sub_execution::last_node_output;
+use n8n_core::WorkflowStorage;
+use n8n_workflow::object_to_json;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::api::ApiError;
+use super::openapi::openapi_path;
+use super::webhook::{request_item, WebhookState};
+
+/// Header carrying the caller's API key.
+pub const API_KEY_HEADER: &str = "x-n8n-api-key";
+
+/// A workflow node published at a method and path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowEndpoint {
+    #[serde(default)]
+    pub id: String,
+    pub workflow_id: String,
+    /// Node the request item is passed to.
+    pub node: String,
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// Path below `/endpoint/`, with `:name` parameters.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_schema: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<Value>,
+    /// API key scopes a caller needs; empty for any valid key.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+impl WorkflowEndpoint {
+    fn segments(&self) -> impl Iterator<Item = &str> {
+        self.path.split('/').filter(|s| !s.is_empty())
+    }
+
+    /// Path parameters if the endpoint serves `method` `path`.
+    pub fn matches(&self, method: &Method, path: &str) -> Option<Map<String, Value>> {
+        if !self.method.eq_ignore_ascii_case(method.as_str()) {
+            return None;
+        }
+        let mut params = Map::new();
+        let mut requested = path.split('/').filter(|s| !s.is_empty());
+        for segment in self.segments() {
+            let value = requested.next()?;
+            match segment.strip_prefix(':') {
+                Some(name) => {
+                    params.insert(name.to_string(), Value::String(value.to_string()));
+                }
+                None if segment == value => {}
+                None => return None,
+            }
+        }
+        requested.next().is_none().then_some(params)
+    }
+
+    /// Number of `:name` segments; literal paths win over parameters.
+    fn param_count(&self) -> usize {
+        self.segments().filter(|s| s.starts_with(':')).count()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.segments().next().is_none() {
+            return Err("path must not be empty".to_string());
+        }
+        if self.method.parse::<Method>().is_err() {
+            return Err(format!("invalid method {}", self.method));
+        }
+        let mut names: Vec<&str> = self.segments().filter_map(|s| s.strip_prefix(':')).collect();
+        if names.iter().any(|n| n.is_empty()) {
+            return Err("path parameters need a name".to_string());
+        }
+        let count = names.len();
+        names.sort_unstable();
+        names.dedup();
+        if names.len() != count {
+            return Err("duplicate path parameter".to_string());
+        }
+        Ok(())
+    }
+
+    /// Normalized `/`-separated path without leading or trailing slashes.
+    fn normalized_path(&self) -> String {
+        self.segments().collect::<Vec<_>>().join("/")
+    }
+}
+
+/// Persistent storage of published endpoints.
+#[async_trait]
+pub trait EndpointStore: Send + Sync {
+    async fn list(&self) -> Result<Vec<WorkflowEndpoint>, String>;
+    /// Save `endpoint`, replacing the one on the same method and path.
+    async fn save(&self, endpoint: &WorkflowEndpoint) -> Result<(), String>;
+    async fn delete(&self, id: &str) -> Result<bool, String>;
+}
+
+/// Resolves API keys to their scopes.
+#[async_trait]
+pub trait ApiKeyScopes: Send + Sync {
+    /// Scopes of `api_key`, or `None` if the key is unknown.
+    async fn scopes(&self, api_key: &str) -> Result<Option<Vec<String>>, String>;
+}
+
+/// Fixed API keys and their scopes.
+#[derive(Debug, Clone, Default)]
+pub struct StaticApiKeys(pub HashMap<String, Vec<String>>);
+
+#[async_trait]
+impl ApiKeyScopes for StaticApiKeys {
+    async fn scopes(&self, api_key: &str) -> Result<Option<Vec<String>>, String> {
+        Ok(self.0.get(api_key).cloned())
+    }
+}
+
+/// Endpoint routing state.
+#[derive(Clone)]
+pub struct EndpointState {
+    pub webhooks: WebhookState,
+    pub keys: Arc<dyn ApiKeyScopes>,
+    pub store: Option<Arc<dyn EndpointStore>>,
+    endpoints: Arc<RwLock<Vec<WorkflowEndpoint>>>,
+}
+
+impl EndpointState {
+    /// Run endpoint calls like webhooks of `webhooks`, authorizing them
+    /// with `keys`.
+    pub fn new(webhooks: WebhookState, keys: Arc<dyn ApiKeyScopes>) -> Self {
+        Self {
+            webhooks,
+            keys,
+            store: None,
+            endpoints: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// Persist published endpoints in `store`.
+    pub fn with_store(mut self, store: Arc<dyn EndpointStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Load the endpoints saved in the store. Returns how many were loaded.
+    pub async fn load(&self) -> Result<usize, String> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+        let endpoints = store.list().await?;
+        let count = endpoints.len();
+        *self.endpoints.write().await = endpoints;
+        Ok(count)
+    }
+
+    pub async fn list(&self) -> Vec<WorkflowEndpoint> {
+        self.endpoints.read().await.clone()
+    }
+
+    /// Publish `endpoint`, replacing the one on the same method and path.
+    pub async fn publish(&self, mut endpoint: WorkflowEndpoint) -> Result<WorkflowEndpoint, ApiError> {
+        endpoint.validate().map_err(|message| ApiError { code: 400, message })?;
+        endpoint.method = endpoint.method.to_ascii_uppercase();
+        endpoint.path = endpoint.normalized_path();
+
+        let workflow = self
+            .webhooks
+            .workflows
+            .get_workflow(&endpoint.workflow_id)
+            .await
+            .map_err(|e| ApiError {
+                code: 500,
+                message: e.to_string(),
+            })?
+            .ok_or_else(|| ApiError {
+                code: 404,
+                message: format!("Workflow {} not found", endpoint.workflow_id),
+            })?;
+        if workflow.get_node(&endpoint.node).is_none() {
+            return Err(ApiError {
+                code: 400,
+                message: format!("Workflow has no node {}", endpoint.node),
+            });
+        }
+
+        let mut endpoints = self.endpoints.write().await;
+        let existing = endpoints
+            .iter()
+            .position(|e| e.method == endpoint.method && e.path == endpoint.path);
+        endpoint.id = match existing {
+            Some(i) => endpoints[i].id.clone(),
+            None if endpoint.id.is_empty() => Uuid::new_v4().to_string(),
+            None => endpoint.id,
+        };
+        if let Some(store) = &self.store {
+            store.save(&endpoint).await.map_err(|message| ApiError { code: 500, message })?;
+        }
+        match existing {
+            Some(i) => endpoints[i] = endpoint.clone(),
+            None => endpoints.push(endpoint.clone()),
+        }
+        Ok(endpoint)
+    }
+
+    /// Remove an endpoint. Returns `false` if it did not exist.
+    pub async fn unpublish(&self, id: &str) -> Result<bool, ApiError> {
+        let mut endpoints = self.endpoints.write().await;
+        let Some(i) = endpoints.iter().position(|e| e.id == id) else {
+            return Ok(false);
+        };
+        if let Some(store) = &self.store {
+            store.delete(id).await.map_err(|message| ApiError { code: 500, message })?;
+        }
+        endpoints.remove(i);
+        Ok(true)
+    }
+
+    /// The most specific endpoint serving `method` `path`, with its path
+    /// parameters.
+    async fn find(&self, method: &Method, path: &str) -> Option<(WorkflowEndpoint, Map<String, Value>)> {
+        self.endpoints
+            .read()
+            .await
+            .iter()
+            .filter_map(|e| Some((e.clone(), e.matches(method, path)?)))
+            .min_by_key(|(e, _)| e.param_count())
+    }
+
+    /// Check the request's API key carries the endpoint's scopes.
+    async fn authorize(&self, endpoint: &WorkflowEndpoint, headers: &HeaderMap) -> Result<(), ApiError> {
+        let unauthorized = |message: &str| ApiError {
+            code: 401,
+            message: message.to_string(),
+        };
+        let key = headers
+            .get(API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| unauthorized("Missing API key"))?;
+        let scopes = self
+            .keys
+            .scopes(key)
+            .await
+            .map_err(|message| ApiError { code: 500, message })?
+            .ok_or_else(|| unauthorized("Unknown API key"))?;
+
+        let missing: Vec<&str> = endpoint
+            .scopes
+            .iter()
+            .filter(|s| !scopes.contains(s))
+            .map(String::as_str)
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError {
+                code: 403,
+                message: format!("API key lacks scopes: {}", missing.join(", ")),
+            })
+        }
+    }
+}
+
+/// Router serving `/endpoint/*path` and the `/api/v1/endpoints` management
+/// API.
+pub fn create_endpoint_router(state: EndpointState) -> Router {
+    Router::new()
+        .route("/api/v1/endpoints", get(list_endpoints).post(publish_endpoint))
+        .route("/api/v1/endpoints/openapi.json", get(endpoints_openapi))
+        .route("/api/v1/endpoints/:id", delete(unpublish_endpoint))
+        .route("/endpoint/*path", any(handle_endpoint))
+        .with_state(state)
+}
+
+/// GET /endpoints - List published endpoints.
+async fn list_endpoints(State(state): State<EndpointState>) -> Json<Vec<WorkflowEndpoint>> {
+    Json(state.list().await)
+}
+
+/// POST /endpoints - Publish a workflow node as an endpoint.
+async fn publish_endpoint(
+    State(state): State<EndpointState>,
+    Json(endpoint): Json<WorkflowEndpoint>,
+) -> Result<(StatusCode, Json<WorkflowEndpoint>), ApiError> {
+    let endpoint = state.publish(endpoint).await?;
+    Ok((StatusCode::CREATED, Json(endpoint)))
+}
+
+/// DELETE /endpoints/:id - Remove a published endpoint.
+async fn unpublish_endpoint(
+    State(state): State<EndpointState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    if state.unpublish(&id).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError {
+            code: 404,
+            message: format!("Endpoint {} not found", id),
+        })
+    }
+}
+
+/// GET /endpoints/openapi.json - OpenAPI fragment describing the endpoints.
+async fn endpoints_openapi(State(state): State<EndpointState>) -> Json<Value> {
+    Json(openapi_fragment(&state.list().await))
+}
+
+async fn handle_endpoint(
+    State(state): State<EndpointState>,
+    method: Method,
+    Path(path): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    match call_endpoint(&state, method, &path, query, &headers, &body).await {
+        Ok(response) => response,
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn call_endpoint(
+    state: &EndpointState,
+    method: Method,
+    path: &str,
+    query: HashMap<String, String>,
+    headers: &HeaderMap,
+    body: &Bytes,
+) -> Result<Response, ApiError> {
+    let not_found = || ApiError {
+        code: 404,
+        message: format!("No endpoint for {} /endpoint/{}", method, path),
+    };
+    let (endpoint, params) = state.find(&method, path).await.ok_or_else(not_found)?;
+    state.authorize(&endpoint, headers).await?;
+
+    let workflow = state
+        .webhooks
+        .workflows
+        .get_workflow(&endpoint.workflow_id)
+        .await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .filter(|w| w.active)
+        .ok_or_else(not_found)?;
+    state.webhooks.check_trigger(&workflow, &endpoint.node)?;
+
+    let url = format!("/endpoint/{}", path.trim_matches('/'));
+    let parser = state.webhooks.engine.config().serialization.ingest;
+    let item = request_item(&method, &url, params, headers, query, body, parser)
+        .map_err(|message| ApiError { code: 400, message })?;
+    if let Some(schema) = &endpoint.request_schema {
+        let body = item.json.get("body").map(Value::from).unwrap_or_default();
+        let errors = check_schema(&body, schema);
+        if !errors.is_empty() {
+            return Err(ApiError {
+                code: 422,
+                message: format!("Invalid request body: {}", errors.join("; ")),
+            });
+        }
+    }
+
+    let Some(_admitted) = state.webhooks.admit() else {
+        let mut response = ApiError {
+            code: 429,
+            message: "Too many pending requests".to_string(),
+        }
+        .into_response();
+        let retry_after = state.webhooks.admission.retry_after_secs.max(1);
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        return Ok(response);
+    };
+
+    let (execution_id, run) = state
+        .webhooks
+        .execute(&workflow, &endpoint.node, item)
+        .await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?;
+    if let Some(error) = &run.data.result_data.error {
+        return Err(ApiError {
+            code: 500,
+            message: error.message.clone(),
+        });
+    }
+
+    let mut items: Vec<Value> = last_node_output(&run)
+        .iter()
+        .map(|item| Value::Object(object_to_json(&item.json)))
+        .collect();
+    let body = if items.len() == 1 { items.remove(0) } else { Value::Array(items) };
+    let mut response = Json(body).into_response();
+    if let Ok(value) = HeaderValue::from_str(&execution_id) {
+        response.headers_mut().insert("x-n8n-execution-id", value);
+    }
+    Ok(response)
+}
+
+/// Check `value` against the subset of JSON Schema used for endpoint
+/// contracts: `type`, `enum`, `required`, `properties`,
+/// `additionalProperties: false` and `items`. Returns one message per
+/// violation.
+pub fn check_schema(value: &Value, schema: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    check_at(value, schema, "body", &mut errors);
+    errors
+}
+
+fn check_at(value: &Value, schema: &Value, at: &str, errors: &mut Vec<String>) {
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            errors.push(format!("{} must be {}", at, types.join(" or ")));
+            return;
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            errors.push(format!("{} must be one of {}", at, Value::Array(allowed.clone())));
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            let required = schema.get("required").and_then(Value::as_array);
+            for name in required.into_iter().flatten().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    errors.push(format!("{}.{} is required", at, name));
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+            for (name, field) in object {
+                match properties.and_then(|p| p.get(name)) {
+                    Some(field_schema) => {
+                        check_at(field, field_schema, &format!("{}.{}", at, name), errors)
+                    }
+                    None if closed => errors.push(format!("{}.{} is not allowed", at, name)),
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    check_at(item, item_schema, &format!("{}[{}]", at, i), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn has_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// OpenAPI paths and security scheme of the published endpoints, to merge
+/// into a client's API description.
+pub fn openapi_fragment(endpoints: &[WorkflowEndpoint]) -> Value {
+    let mut paths = Map::new();
+    for endpoint in endpoints {
+        // Endpoint paths share axum's `:name` syntax.
+        let route = format!("/endpoint/{}", endpoint.path);
+        let (path, params) = openapi_path(&route);
+        let parameters: Vec<Value> = params
+            .iter()
+            .map(|name| {
+                json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } })
+            })
+            .collect();
+        let response_schema = endpoint.response_schema.clone().unwrap_or_else(|| json!({}));
+
+        let mut spec = json!({
+            "operationId": format!("endpoint_{}", endpoint.id),
+            "summary": endpoint.summary.clone().unwrap_or_else(|| {
+                format!("Run workflow {} from {}", endpoint.workflow_id, endpoint.node)
+            }),
+            "tags": ["endpoints"],
+            "security": [{ "apiKey": [] }],
+            "x-scopes": endpoint.scopes,
+            "responses": {
+                "200": {
+                    "description": "Output of the workflow",
+                    "content": { "application/json": { "schema": response_schema } },
+                },
+                "401": { "description": "Missing or unknown API key" },
+                "403": { "description": "API key lacks a required scope" },
+                "422": { "description": "Request body does not match the schema" },
+            },
+        });
+        if !parameters.is_empty() {
+            spec["parameters"] = Value::Array(parameters);
+        }
+        if let Some(schema) = &endpoint.request_schema {
+            spec["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": schema } },
+            });
+        }
+
+        let entry = paths.entry(path).or_insert_with(|| Value::Object(Map::new()));
+        entry[endpoint.method.to_ascii_lowercase()] = spec;
+    }
+
+    json!({
+        "paths": paths,
+        "components": {
+            "securitySchemes": {
+                "apiKey": { "type": "apiKey", "in": "header", "name": "X-N8N-API-KEY" },
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(method: &str, path: &str) -> WorkflowEndpoint {
+        WorkflowEndpoint {
+            id: "e1".to_string(),
+            workflow_id: "w1".to_string(),
+            node: "Start".to_string(),
+            method: method.to_string(),
+            path: path.to_string(),
+            summary: None,
+            request_schema: None,
+            response_schema: None,
+            scopes: vec!["orders:read".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_endpoint_path_parameters() {
+        let by_id = endpoint("GET", "orders/:id");
+        let params = by_id.matches(&Method::GET, "/orders/42/").unwrap();
+        assert_eq!(params["id"], "42");
+        assert!(by_id.matches(&Method::POST, "orders/42").is_none());
+        assert!(by_id.matches(&Method::GET, "orders/42/items").is_none());
+        assert!(by_id.matches(&Method::GET, "invoices/42").is_none());
+
+        assert!(endpoint("GET", "orders/:id/:id").validate().is_err());
+        assert!(endpoint("GET", "/").validate().is_err());
+
+        let fragment = openapi_fragment(&[by_id]);
+        let get = &fragment["paths"]["/endpoint/orders/{id}"]["get"];
+        assert_eq!(get["parameters"][0]["name"], "id");
+        assert_eq!(get["x-scopes"][0], "orders:read");
+    }
+
+    #[test]
+    fn test_check_schema() {
+        let schema = json!({
+            "type": "object",
+            "required": ["sku", "quantity"],
+            "additionalProperties": false,
+            "properties": {
+                "sku": { "type": "string" },
+                "quantity": { "type": "integer" },
+                "tags": { "type": "array", "items": { "enum": ["gift", "rush"] } },
+            },
+        });
+
+        assert!(check_schema(&json!({ "sku": "A1", "quantity": 2, "tags": ["gift"] }), &schema)
+            .is_empty());
+        let errors = check_schema(&json!({ "quantity": 1.5, "tags": ["slow"], "x": 1 }), &schema);
+        assert_eq!(
+            errors,
+            vec![
+                "body.sku is required",
+                "body.quantity must be integer",
+                "body.tags[0] must be one of [\"gift\",\"rush\"]",
+                "body.x is not allowed",
+            ]
+        );
+        assert_eq!(check_schema(&json!("text"), &schema), vec!["body must be object"]);
+    }
+}
@@ -0,0 +1,343 @@
+//! Evaluation endpoints.
+//!
+//! Test sets ([`n8n_core::evaluation`]) replay past executions of a
+//! workflow against its current or a draft version:
+//!
+//! - `GET` and `POST /api/v1/workflows/:id/test-sets` list and create them.
+//!   `executionIds` turns stored executions into cases (the start node's
+//!   output as input, the checked node's as expected items); `cases` adds
+//!   hand-written ones.
+//! - `GET`, `PUT` and `DELETE /api/v1/test-sets/:id` read, replace and
+//!   remove one.
+//! - `POST /api/v1/test-sets/:id/runs` runs the cases against the stored
+//!   workflow, or the `workflow` in the body, and answers with the report;
+//!   `GET` lists the reports, newest first, and
+//!   `GET /api/v1/test-sets/:id/runs/:runId` returns one.
+//!
+//! Test runs are not stored as executions.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use n8n_core::{
+    evaluate, EvaluationError, EvaluationReport, Evaluations, Scorer, TestCase, TestSet,
+    WorkflowStorage,
+};
+use n8n_workflow::Workflow;
+use serde::Deserialize;
+
+use super::api::ApiError;
+use super::run::MANUAL_TRIGGER_NODE_TYPE;
+use super::webhook::WebhookState;
+
+/// State of the evaluation endpoints.
+#[derive(Clone)]
+pub struct EvaluationState {
+    pub webhooks: WebhookState,
+    pub evaluations: Evaluations,
+}
+
+impl EvaluationState {
+    /// Run test sets with the workflows, executions and engine of
+    /// `webhooks`.
+    pub fn new(webhooks: WebhookState) -> Self {
+        Self {
+            webhooks,
+            evaluations: Evaluations::new(),
+        }
+    }
+}
+
+/// Request body of `POST /api/v1/workflows/:id/test-sets`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTestSetRequest {
+    pub name: String,
+    /// Defaults to the workflow's manual trigger, or its only start node.
+    pub start_node: Option<String>,
+    pub output_node: Option<String>,
+    #[serde(default)]
+    pub ignore_fields: Vec<String>,
+    #[serde(default)]
+    pub scorers: Vec<Scorer>,
+    /// Stored executions of the workflow to take cases from.
+    #[serde(default)]
+    pub execution_ids: Vec<String>,
+    #[serde(default)]
+    pub cases: Vec<TestCase>,
+}
+
+/// Request body of `POST /api/v1/test-sets/:id/runs`.
+#[derive(Debug, Default, Deserialize)]
+pub struct RunTestSetRequest {
+    /// Draft version to test instead of the stored workflow.
+    pub workflow: Option<Workflow>,
+}
+
+/// Router serving the evaluation endpoints.
+pub fn create_evaluations_router(state: EvaluationState) -> Router {
+    Router::new()
+        .route("/api/v1/workflows/:id/test-sets", get(list_test_sets).post(create_test_set))
+        .route(
+            "/api/v1/test-sets/:id",
+            get(get_test_set).put(update_test_set).delete(delete_test_set),
+        )
+        .route("/api/v1/test-sets/:id/runs", get(list_runs).post(run_test_set))
+        .route("/api/v1/test-sets/:id/runs/:run_id", get(get_run))
+        .with_state(state)
+}
+
+fn evaluation_error(e: EvaluationError) -> ApiError {
+    let code = match e {
+        EvaluationError::NotFound(_) => 404,
+        EvaluationError::Invalid(_) => 400,
+    };
+    ApiError {
+        code,
+        message: e.to_string(),
+    }
+}
+
+fn internal(e: impl ToString) -> ApiError {
+    ApiError {
+        code: 500,
+        message: e.to_string(),
+    }
+}
+
+async fn load_workflow(state: &EvaluationState, id: &str) -> Result<Workflow, ApiError> {
+    state
+        .webhooks
+        .workflows
+        .get_workflow(id)
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        })
+}
+
+/// The manual trigger of `workflow`, or its only start node.
+fn default_start_node(workflow: &Workflow) -> Option<String> {
+    if let Some(node) = workflow
+        .nodes
+        .iter()
+        .find(|node| node.node_type == MANUAL_TRIGGER_NODE_TYPE && !node.disabled)
+    {
+        return Some(node.name.clone());
+    }
+    match workflow.get_start_nodes().as_slice() {
+        [node] => Some(node.name.clone()),
+        _ => None,
+    }
+}
+
+/// GET /workflows/:id/test-sets - Test sets of a workflow.
+pub async fn list_test_sets(
+    State(state): State<EvaluationState>,
+    Path(id): Path<String>,
+) -> Json<Vec<TestSet>> {
+    Json(state.evaluations.list_sets(Some(&id)))
+}
+
+/// POST /workflows/:id/test-sets - Create a test set, taking cases from
+/// stored executions.
+pub async fn create_test_set(
+    State(state): State<EvaluationState>,
+    Path(id): Path<String>,
+    Json(request): Json<CreateTestSetRequest>,
+) -> Result<(StatusCode, Json<TestSet>), ApiError> {
+    let workflow = load_workflow(&state, &id).await?;
+    let start_node = request
+        .start_node
+        .or_else(|| default_start_node(&workflow))
+        .ok_or_else(|| ApiError {
+            code: 400,
+            message: format!("Workflow {} has several start nodes; set startNode", id),
+        })?;
+
+    let mut cases = Vec::with_capacity(request.execution_ids.len() + request.cases.len());
+    for execution_id in &request.execution_ids {
+        let (run, metadata) = state
+            .webhooks
+            .executions
+            .get_execution(execution_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| ApiError {
+                code: 404,
+                message: format!("Execution {} not found", execution_id),
+            })?;
+        if metadata.as_ref().map(|m| m.workflow_id()) != Some(id.as_str()) {
+            return Err(ApiError {
+                code: 400,
+                message: format!("Execution {} is not of workflow {}", execution_id, id),
+            });
+        }
+        let case = TestCase::from_run(
+            execution_id,
+            &run,
+            &start_node,
+            request.output_node.as_deref(),
+        )
+        .map_err(evaluation_error)?;
+        cases.push(case);
+    }
+    cases.extend(request.cases);
+
+    let now = Utc::now();
+    let set = TestSet {
+        id: String::new(),
+        name: request.name,
+        workflow_id: workflow.id,
+        start_node,
+        output_node: request.output_node,
+        ignore_fields: request.ignore_fields,
+        scorers: request.scorers,
+        cases,
+        created_at: now,
+        updated_at: now,
+    };
+    let set = state.evaluations.save_set(set).map_err(evaluation_error)?;
+    Ok((StatusCode::CREATED, Json(set)))
+}
+
+/// GET /test-sets/:id - A test set with its cases.
+pub async fn get_test_set(
+    State(state): State<EvaluationState>,
+    Path(id): Path<String>,
+) -> Result<Json<TestSet>, ApiError> {
+    let set = state.evaluations.get_set(&id).map_err(evaluation_error)?;
+    Ok(Json(set))
+}
+
+/// PUT /test-sets/:id - Replace the cases and scoring of a test set.
+pub async fn update_test_set(
+    State(state): State<EvaluationState>,
+    Path(id): Path<String>,
+    Json(mut set): Json<TestSet>,
+) -> Result<Json<TestSet>, ApiError> {
+    let current = state.evaluations.get_set(&id).map_err(evaluation_error)?;
+    set.id = current.id;
+    set.workflow_id = current.workflow_id;
+    set.created_at = current.created_at;
+    let set = state.evaluations.save_set(set).map_err(evaluation_error)?;
+    Ok(Json(set))
+}
+
+/// DELETE /test-sets/:id - Delete a test set and its reports.
+pub async fn delete_test_set(
+    State(state): State<EvaluationState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    if !state.evaluations.delete_set(&id) {
+        return Err(evaluation_error(EvaluationError::NotFound(format!("Test set {}", id))));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /test-sets/:id/runs - Run a test set against the stored workflow or
+/// a draft version.
+pub async fn run_test_set(
+    State(state): State<EvaluationState>,
+    Path(id): Path<String>,
+    request: Option<Json<RunTestSetRequest>>,
+) -> Result<(StatusCode, Json<EvaluationReport>), ApiError> {
+    let set = state.evaluations.get_set(&id).map_err(evaluation_error)?;
+    let workflow = match request.and_then(|Json(r)| r.workflow) {
+        Some(mut draft) => {
+            draft.id = set.workflow_id.clone();
+            draft
+        }
+        None => load_workflow(&state, &set.workflow_id).await?,
+    };
+
+    let report = evaluate(&state.webhooks.engine, &workflow, &set).await;
+    state.evaluations.record(report.clone());
+    Ok((StatusCode::CREATED, Json(report)))
+}
+
+/// GET /test-sets/:id/runs - Reports of a test set, newest first.
+pub async fn list_runs(
+    State(state): State<EvaluationState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<EvaluationReport>>, ApiError> {
+    state.evaluations.get_set(&id).map_err(evaluation_error)?;
+    Ok(Json(state.evaluations.reports(&id)))
+}
+
+/// GET /test-sets/:id/runs/:runId - One report.
+pub async fn get_run(
+    State(state): State<EvaluationState>,
+    Path((id, run_id)): Path<(String, String)>,
+) -> Result<Json<EvaluationReport>, ApiError> {
+    let report = state
+        .evaluations
+        .report(&run_id)
+        .ok()
+        .filter(|report| report.test_set_id == id)
+        .ok_or_else(|| evaluation_error(EvaluationError::NotFound(format!("Report {}", run_id))))?;
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::api::ExecutionStore;
+    use n8n_core::{MemoryWorkflowStorage, RuntimeConfig, WorkflowEngine};
+    use n8n_workflow::{Node, NodeExecutionData, WorkflowBuilder};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_create_test_set_from_execution_and_run_it() {
+        let workflow = WorkflowBuilder::new("Classify")
+            .node(Node::new("Start", MANUAL_TRIGGER_NODE_TYPE))
+            .node(Node::new("Done", "n8n-nodes-base.noOp"))
+            .connect("Start", "Done", 0, 0)
+            .unwrap()
+            .build()
+            .unwrap();
+        let workflows = Arc::new(MemoryWorkflowStorage::new());
+        workflows.save_workflow(&workflow).await.unwrap();
+        let webhooks = WebhookState::new(
+            workflows,
+            Arc::new(ExecutionStore::new()),
+            Arc::new(WorkflowEngine::new(RuntimeConfig::default())),
+        );
+        let item = NodeExecutionData::from_json_value(json!({ "category": "billing" })).unwrap();
+        let (execution_id, _) = webhooks.execute(&workflow, "Start", item).await.unwrap();
+        let state = EvaluationState::new(webhooks);
+
+        let request = CreateTestSetRequest {
+            name: "Regression".to_string(),
+            scorers: vec![serde_json::from_value(json!({
+                "name": "category",
+                "expression": "={{ $json.category == $expected.category }}"
+            }))
+            .unwrap()],
+            execution_ids: vec![execution_id],
+            ..Default::default()
+        };
+        let (status, Json(set)) =
+            create_test_set(State(state.clone()), Path(workflow.id.clone()), Json(request))
+                .await
+                .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(set.start_node, "Start");
+        assert_eq!(set.cases[0].input, vec![json!({ "category": "billing" })]);
+
+        let (_, Json(report)) = run_test_set(State(state.clone()), Path(set.id.clone()), None)
+            .await
+            .unwrap();
+        assert_eq!((report.passed, report.failed), (1, 0));
+
+        let Json(reports) = list_runs(State(state), Path(set.id)).await.unwrap();
+        assert_eq!(reports[0].id, report.id);
+    }
+}
@@ -0,0 +1,104 @@
+//! Request rate limiting for the REST transport.
+//!
+//! The limit is read from a watch channel on every request, so it can be
+//! changed at runtime without rebuilding the router.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, Mutex};
+
+/// Fixed-window requests-per-second limiter.
+#[derive(Clone)]
+pub struct RateLimiter {
+    /// Maximum requests per second (0 = unlimited).
+    limit: watch::Receiver<u32>,
+    window: Arc<Mutex<(Instant, u32)>>,
+}
+
+impl RateLimiter {
+    /// Create a limiter that follows the given limit.
+    pub fn new(limit: watch::Receiver<u32>) -> Self {
+        Self {
+            limit,
+            window: Arc::new(Mutex::new((Instant::now(), 0))),
+        }
+    }
+
+    /// Current limit in requests per second (0 = unlimited).
+    pub fn limit(&self) -> u32 {
+        *self.limit.borrow()
+    }
+
+    /// Record a request. Returns the time until the window resets if the
+    /// request exceeds the limit.
+    pub async fn check(&self) -> Result<(), Duration> {
+        let limit = self.limit();
+        if limit == 0 {
+            return Ok(());
+        }
+
+        let mut window = self.window.lock().await;
+        let elapsed = window.0.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            *window = (Instant::now(), 0);
+        }
+
+        if window.1 >= limit {
+            return Err(Duration::from_secs(1).saturating_sub(window.0.elapsed()));
+        }
+        window.1 += 1;
+        Ok(())
+    }
+}
+
+/// Middleware rejecting requests over the limit with `429 Too Many Requests`.
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    match limiter.check().await {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let mut response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                axum::Json(serde_json::json!({
+                    "code": 429,
+                    "message": "Rate limit exceeded",
+                })),
+            )
+                .into_response();
+            let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            if let Ok(value) = HeaderValue::from_str(&secs.to_string()) {
+                response.headers_mut().insert(header::RETRY_AFTER, value);
+            }
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_limit_follows_watch_channel() {
+        let (tx, rx) = watch::channel(2);
+        let limiter = RateLimiter::new(rx);
+
+        assert!(limiter.check().await.is_ok());
+        assert!(limiter.check().await.is_ok());
+        assert!(limiter.check().await.is_err());
+
+        // Raising the limit takes effect immediately.
+        tx.send(0).unwrap();
+        assert!(limiter.check().await.is_ok());
+    }
+}
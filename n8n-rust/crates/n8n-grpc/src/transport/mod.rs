@@ -2,7 +2,9 @@
 //!
 //! This module provides:
 //! - STDIO transport for CLI/pipe communication
+//! - Shared-memory transport for co-located processes (Linux)
 //! - REST API with content negotiation
+//! - Liveness/readiness endpoints for orchestration platforms
 //! - Intelligent format/protocol negotiation
 //! - Graceful fallback between transports
 //!
@@ -67,14 +69,48 @@
 //! ```
 
 pub mod api;
+pub mod deliveries;
+pub mod endpoints;
+pub mod evaluations;
+pub mod limits;
 pub mod negotiate;
+pub mod openapi;
+pub mod patch;
+pub mod readiness;
 pub mod rest;
+pub mod run;
+#[cfg(target_os = "linux")]
+pub mod shm;
+pub mod smtp;
 pub mod stdio;
+pub mod webhook;
 
 pub use api::*;
+pub use deliveries::create_deliveries_router;
+pub use endpoints::{
+    create_endpoint_router, ApiKeyScopes, EndpointState, EndpointStore, StaticApiKeys,
+    WorkflowEndpoint,
+};
+pub use evaluations::{
+    create_evaluations_router, CreateTestSetRequest, EvaluationState, RunTestSetRequest,
+};
+pub use limits::*;
 pub use negotiate::*;
+pub use openapi::{create_openapi_router, openapi_document};
+pub use patch::{PatchWorkflowRequest, MAX_VERSIONS};
+pub use readiness::*;
 pub use rest::*;
+pub use run::{
+    create_run_router, BatchCounts, BatchExecution, BatchStatusResponse, RunBatchRequest,
+    RunBatchResponse, RunWorkflowRequest, WorkflowInputsResponse, MANUAL_TRIGGER_NODE_TYPE,
+};
+#[cfg(target_os = "linux")]
+pub use shm::{bind_shm, serve_shm, ShmClient, ShmFrame};
+pub use smtp::{serve_smtp, SmtpConfig, SmtpState, SMTP_TRIGGER_NODE_TYPE};
 pub use stdio::*;
+pub use webhook::{
+    create_webhook_router, WebhookAdmission, WebhookSpill, WebhookState, WEBHOOK_NODE_TYPE,
+};
 
 use std::sync::Arc;
 use tokio::sync::watch;
@@ -186,7 +222,8 @@ impl Default for TransportManager {
 }
 
 /// Transport configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct TransportConfig {
     /// Enable REST API.
     pub rest_enabled: bool,
@@ -202,6 +239,10 @@ pub struct TransportConfig {
     pub flight_addr: String,
     /// Enable STDIO.
     pub stdio_enabled: bool,
+    /// Enable the shared-memory transport for co-located processes.
+    pub shm_enabled: bool,
+    /// Unix socket of the shared-memory transport.
+    pub shm_path: String,
 }
 
 impl Default for TransportConfig {
@@ -214,6 +255,8 @@ impl Default for TransportConfig {
             flight_enabled: true,
             flight_addr: "0.0.0.0:50052".to_string(),
             stdio_enabled: false,
+            shm_enabled: false,
+            shm_path: "/tmp/n8n-rust-shm.sock".to_string(),
         }
     }
 }
@@ -102,6 +102,9 @@ pub enum Transport {
     Flight,
     Stdio,
     WebSocket,
+    /// Arrow IPC in shared memory over a unix socket, for processes on the
+    /// same host.
+    SharedMemory,
 }
 
 impl Transport {
@@ -112,6 +115,7 @@ impl Transport {
             "flight" | "arrow-flight" => Self::Flight,
             "stdio" | "pipe" => Self::Stdio,
             "ws" | "websocket" => Self::WebSocket,
+            "shm" | "shared-memory" | "memfd" => Self::SharedMemory,
             _ => Self::Rest,
         }
     }
@@ -123,15 +127,19 @@ impl Transport {
             Self::Flight => "flight",
             Self::Stdio => "stdio",
             Self::WebSocket => "websocket",
+            Self::SharedMemory => "shm",
         }
     }
 
     pub fn supports_streaming(&self) -> bool {
-        matches!(self, Self::Grpc | Self::Flight | Self::WebSocket)
+        matches!(self, Self::Grpc | Self::Flight | Self::WebSocket | Self::SharedMemory)
     }
 
     pub fn supports_bidirectional(&self) -> bool {
-        matches!(self, Self::Grpc | Self::Flight | Self::Stdio | Self::WebSocket)
+        matches!(
+            self,
+            Self::Grpc | Self::Flight | Self::Stdio | Self::WebSocket | Self::SharedMemory
+        )
     }
 }
 
@@ -298,6 +306,8 @@ pub struct NegotiatorConfig {
     pub transport_retry_delay: Duration,
     /// Score below which transport is considered unhealthy.
     pub unhealthy_threshold: u32,
+    /// Unix socket of the shared-memory transport; unset disables it.
+    pub shared_memory_socket: Option<String>,
 }
 
 impl Default for NegotiatorConfig {
@@ -307,6 +317,7 @@ impl Default for NegotiatorConfig {
             streaming_threshold_records: 1000,
             transport_retry_delay: Duration::from_secs(30),
             unhealthy_threshold: 50,
+            shared_memory_socket: None,
         }
     }
 }
@@ -322,12 +333,14 @@ impl FormatNegotiator {
         transport_health.insert(Transport::Grpc, TransportHealth::default());
         transport_health.insert(Transport::Flight, TransportHealth::default());
         transport_health.insert(Transport::Stdio, TransportHealth::default());
+        transport_health.insert(Transport::SharedMemory, TransportHealth::default());
 
         let mut request_counts = HashMap::new();
         request_counts.insert(Transport::Rest, AtomicU64::new(0));
         request_counts.insert(Transport::Grpc, AtomicU64::new(0));
         request_counts.insert(Transport::Flight, AtomicU64::new(0));
         request_counts.insert(Transport::Stdio, AtomicU64::new(0));
+        request_counts.insert(Transport::SharedMemory, AtomicU64::new(0));
 
         Self {
             transport_health: RwLock::new(transport_health),
@@ -336,6 +349,14 @@ impl FormatNegotiator {
         }
     }
 
+    /// Endpoint of the shared-memory transport, if enabled.
+    pub fn shared_memory_endpoint(&self) -> Option<String> {
+        self.config
+            .shared_memory_socket
+            .as_ref()
+            .map(|path| format!("unix://{}", path))
+    }
+
     /// Negotiate format from HTTP Accept headers.
     pub fn negotiate_from_headers(&self, headers: &HeaderMap) -> ContentFormat {
         // Check explicit format header first
@@ -440,6 +461,16 @@ impl FormatNegotiator {
         transport: Transport,
         hints: &DataHints,
     ) -> f64 {
+        // Segments can only be mapped by a process on the same host, and
+        // only carry Arrow IPC.
+        if transport == Transport::SharedMemory
+            && (self.config.shared_memory_socket.is_none()
+                || !hints.colocated.unwrap_or(false)
+                || format != ContentFormat::ArrowIpc)
+        {
+            return 0.0;
+        }
+
         let mut score = 50.0;
 
         // Data size considerations
@@ -483,6 +514,7 @@ impl FormatNegotiator {
 
         // Format-transport compatibility bonuses
         match (format, transport) {
+            (ContentFormat::ArrowIpc, Transport::SharedMemory) => score += 30.0,
             (ContentFormat::ArrowFlight, Transport::Flight) => score += 20.0,
             (ContentFormat::ArrowIpc, Transport::Flight) => score += 15.0,
             (ContentFormat::Protobuf, Transport::Grpc) => score += 15.0,
@@ -540,6 +572,7 @@ impl FormatNegotiator {
             Transport::Rest => vec![Transport::Grpc, Transport::Stdio],
             Transport::Stdio => vec![Transport::Rest, Transport::Grpc],
             Transport::WebSocket => vec![Transport::Rest, Transport::Grpc],
+            Transport::SharedMemory => vec![Transport::Flight, Transport::Grpc, Transport::Rest],
         };
 
         for fb in fallbacks {
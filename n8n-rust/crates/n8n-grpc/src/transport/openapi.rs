@@ -0,0 +1,1312 @@
+//! OpenAPI 3 document for the REST surface.
+//!
+//! The document is generated from [`OPERATIONS`], a table mirroring the
+//! routes registered by [`create_api_router`](super::create_api_router),
+//! [`create_router`](super::create_router),
+//! [`create_webhook_router`](super::create_webhook_router),
+//! [`create_run_router`](super::create_run_router),
+//! [`create_endpoint_router`](super::create_endpoint_router) and
+//! [`create_readiness_router`](super::create_readiness_router). Paths use
+//! axum syntax (`:id`, `*path`) and are converted to OpenAPI templates
+//! (`{id}`). A route added to a router needs an entry here to be
+//! documented. Credentials are not exposed over REST yet, so they have no
+//! entries.
+//!
+//! Served at `GET /api/v1/openapi.json`.
+
+use axum::{routing::get, Json, Router};
+use serde_json::{json, Map, Value};
+
+/// Response body of an operation.
+#[derive(Debug, Clone, Copy)]
+pub enum Body {
+    /// No body.
+    Empty,
+    /// JSON body matching a component schema.
+    Schema(&'static str),
+    /// `{ data: [...], nextCursor }` wrapping a component schema.
+    List(&'static str),
+    /// JSON array of a component schema.
+    Array(&'static str),
+    /// Free-form JSON.
+    Json,
+    /// Text body with the given media type.
+    Text(&'static str),
+}
+
+/// A query parameter: name, JSON schema type, description.
+pub type QueryParam = (&'static str, &'static str, &'static str);
+
+/// One documented route.
+#[derive(Debug, Clone, Copy)]
+pub struct Operation {
+    pub method: &'static str,
+    /// Axum-style path, e.g. `/api/v1/workflows/:id`.
+    pub path: &'static str,
+    pub operation_id: &'static str,
+    pub tag: &'static str,
+    pub summary: &'static str,
+    /// Component schema of the JSON request body.
+    pub request: Option<&'static str>,
+    pub query: &'static [QueryParam],
+    /// Success status code and body.
+    pub status: u16,
+    pub response: Body,
+}
+
+const fn op(
+    method: &'static str,
+    path: &'static str,
+    operation_id: &'static str,
+    tag: &'static str,
+    summary: &'static str,
+) -> Operation {
+    Operation {
+        method,
+        path,
+        operation_id,
+        tag,
+        summary,
+        request: None,
+        query: &[],
+        status: 200,
+        response: Body::Json,
+    }
+}
+
+/// Every documented operation, grouped by router.
+pub const OPERATIONS: &[Operation] = &[
+    // Workflows
+    Operation {
+        query: &[
+            ("active", "boolean", "Only active or inactive workflows"),
+            ("tags", "string", "Comma-separated tag filter"),
+            ("search", "string", "Words to search for, best matches first"),
+            ("limit", "integer", "Maximum number of items"),
+            ("cursor", "string", "Pagination cursor"),
+        ],
+        response: Body::List("WorkflowResponse"),
+        ..op("get", "/api/v1/workflows", "listWorkflows", "workflows", "List workflows")
+    },
+    Operation {
+        request: Some("WorkflowRequest"),
+        status: 201,
+        response: Body::Schema("WorkflowResponse"),
+        ..op("post", "/api/v1/workflows", "createWorkflow", "workflows", "Create a workflow")
+    },
+    Operation {
+        response: Body::Schema("WorkflowResponse"),
+        ..op("get", "/api/v1/workflows/:id", "getWorkflow", "workflows", "Get a workflow")
+    },
+    Operation {
+        request: Some("WorkflowRequest"),
+        response: Body::Schema("WorkflowResponse"),
+        ..op("put", "/api/v1/workflows/:id", "updateWorkflow", "workflows", "Update a workflow")
+    },
+    Operation {
+        request: Some("PatchWorkflowRequest"),
+        response: Body::Schema("WorkflowResponse"),
+        ..op("patch", "/api/v1/workflows/:id", "patchWorkflow", "workflows", "Apply operations to a workflow")
+    },
+    Operation {
+        response: Body::Array("WorkflowVersion"),
+        ..op("get", "/api/v1/workflows/:id/history", "getWorkflowHistory", "workflows", "List saved versions of a workflow")
+    },
+    Operation {
+        status: 204,
+        response: Body::Empty,
+        ..op("delete", "/api/v1/workflows/:id", "deleteWorkflow", "workflows", "Delete a workflow")
+    },
+    Operation {
+        response: Body::Schema("WorkflowResponse"),
+        ..op("post", "/api/v1/workflows/:id/activate", "activateWorkflow", "workflows", "Activate a workflow")
+    },
+    Operation {
+        response: Body::Schema("WorkflowResponse"),
+        ..op("post", "/api/v1/workflows/:id/deactivate", "deactivateWorkflow", "workflows", "Deactivate a workflow")
+    },
+    Operation {
+        request: Some("CloneWorkflowRequest"),
+        status: 201,
+        response: Body::Schema("CloneWorkflowResponse"),
+        ..op("post", "/api/v1/workflows/:id/clone", "cloneWorkflow", "workflows", "Copy a workflow, remapping credentials and variables")
+    },
+    Operation {
+        query: &[("since", "string", "Only executions started at or after this time")],
+        response: Body::Schema("CostReport"),
+        ..op("get", "/api/v1/workflows/:id/cost", "getWorkflowCost", "workflows", "Cost of a workflow's executions")
+    },
+    Operation {
+        response: Body::Schema("ExecutionPlan"),
+        ..op("get", "/api/v1/workflows/:id/explain", "explainWorkflow", "workflows", "Execution plan of a stored workflow")
+    },
+    Operation {
+        request: Some("WorkflowRequest"),
+        response: Body::Schema("ExecutionPlan"),
+        ..op("post", "/api/v1/workflows/explain", "explainWorkflowBody", "workflows", "Execution plan of a workflow in the request body")
+    },
+    Operation {
+        query: &[("format", "string", "`json` (default) or `sarif`")],
+        response: Body::Schema("LintReport"),
+        ..op("get", "/api/v1/workflows/:id/lint", "lintWorkflow", "lint", "Lint a stored workflow")
+    },
+    Operation {
+        request: Some("WorkflowRequest"),
+        query: &[("format", "string", "`json` (default) or `sarif`")],
+        response: Body::Schema("LintReport"),
+        ..op("post", "/api/v1/workflows/lint", "lintWorkflowBody", "lint", "Lint a workflow in the request body")
+    },
+    Operation {
+        response: Body::Array("RuleInfo"),
+        ..op("get", "/api/v1/lint/rules", "listLintRules", "lint", "Registered lint rules")
+    },
+    Operation {
+        query: &[("format", "string", "`mermaid` (default) or `dot`")],
+        response: Body::Text("text/vnd.mermaid"),
+        ..op("get", "/api/v1/workflows/:id/diagram", "workflowDiagram", "workflows", "Render a workflow diagram")
+    },
+    Operation {
+        response: Body::Text("text/markdown"),
+        ..op("get", "/api/v1/workflows/:id/docs", "workflowDocs", "workflows", "Render workflow documentation as Markdown")
+    },
+    Operation {
+        query: &[("count", "integer", "Runs to list, at most 100 (default 5)")],
+        response: Body::Schema("SchedulePreview"),
+        ..op("get", "/api/v1/workflows/:id/schedule", "workflowSchedule", "workflows", "Next planned runs of the schedule triggers")
+    },
+    Operation {
+        response: Body::Array("TriggerState"),
+        ..op("get", "/api/v1/workflows/:id/triggers", "workflowTriggers", "workflows", "Triggers that failed since their last success or are paused")
+    },
+    Operation {
+        response: Body::Array("TriggerState"),
+        ..op("post", "/api/v1/workflows/:id/triggers/:node/resume", "resumeTrigger", "workflows", "Resume a trigger paused after repeated failures")
+    },
+    // Executions
+    Operation {
+        query: &[
+            ("workflowId", "string", "Only executions of this workflow"),
+            ("status", "string", "Only executions with this status"),
+            ("finished", "boolean", "Only finished or running executions"),
+            ("limit", "integer", "Maximum number of items"),
+            ("cursor", "string", "Pagination cursor"),
+        ],
+        response: Body::List("ExecutionResponse"),
+        ..op("get", "/api/v1/executions", "listExecutions", "executions", "List executions")
+    },
+    Operation {
+        request: Some("ExecutionRequest"),
+        status: 201,
+        response: Body::Schema("ExecutionResponse"),
+        ..op("post", "/api/v1/executions", "createExecution", "executions", "Start an execution")
+    },
+    Operation {
+        response: Body::Schema("LiveSnapshot"),
+        ..op("get", "/api/v1/executions/live", "liveExecutions", "executions", "Queued, running and waiting executions per workflow")
+    },
+    Operation {
+        query: &[("includeData", "boolean", "`false` leaves runData empty")],
+        response: Body::Schema("ExecutionResponse"),
+        ..op("get", "/api/v1/executions/:id", "getExecution", "executions", "Get an execution")
+    },
+    Operation {
+        status: 204,
+        response: Body::Empty,
+        ..op("delete", "/api/v1/executions/:id", "deleteExecution", "executions", "Delete an execution")
+    },
+    Operation {
+        response: Body::Schema("ExecutionResponse"),
+        ..op("post", "/api/v1/executions/:id/stop", "stopExecution", "executions", "Stop a running execution")
+    },
+    Operation {
+        response: Body::Schema("InFlightExecution"),
+        ..op("get", "/api/v1/executions/:id/status", "executionStatus", "executions", "Current node, queue position and timings of an in-flight execution")
+    },
+    Operation {
+        status: 201,
+        response: Body::Schema("ExecutionResponse"),
+        ..op("post", "/api/v1/executions/:id/retry", "retryExecution", "executions", "Retry an execution")
+    },
+    Operation {
+        response: Body::Json,
+        ..op("get", "/api/v1/executions/:id/integrity", "verifyExecutionIntegrity", "executions", "Verify an execution's hash chain")
+    },
+    Operation {
+        response: Body::Json,
+        ..op("get", "/api/v1/executions/:id/integrity/chain", "exportExecutionChain", "executions", "Export an execution's hash chain")
+    },
+    Operation {
+        response: Body::Schema("CostReport"),
+        ..op("get", "/api/v1/executions/:id/cost", "getExecutionCost", "executions", "Cost of an execution by node type and unit")
+    },
+    Operation {
+        query: &[("format", "string", "`report` (default) or `flamegraph`")],
+        response: Body::Schema("ProfileReport"),
+        ..op("get", "/api/v1/executions/:id/profile", "getExecutionProfile", "executions", "Per-node wall time, CPU time, items, bytes and retries")
+    },
+    Operation {
+        response: Body::Schema("RunDataSummary"),
+        ..op("get", "/api/v1/executions/:id/data", "getExecutionDataSummary", "executions", "Item counts and sizes per node, run and output")
+    },
+    Operation {
+        query: &[
+            ("run", "integer", "Run index of the node (default 0)"),
+            ("connection", "string", "Connection type (default `main`)"),
+            ("output", "integer", "Output index (default 0)"),
+            ("offset", "integer", "First item to return"),
+            ("limit", "integer", "Items to return, 1–1000 (default 100)"),
+        ],
+        response: Body::Schema("ItemPage"),
+        ..op("get", "/api/v1/executions/:id/data/:node", "getExecutionItems", "executions", "A page of one output of a node run")
+    },
+    // Negotiation
+    Operation {
+        response: Body::Schema("TransportCapabilities"),
+        ..op("get", "/api/v1/capabilities", "getCapabilities", "negotiation", "Server transports and formats")
+    },
+    Operation {
+        request: Some("NegotiateRequest"),
+        response: Body::Schema("NegotiateResponse"),
+        ..op("post", "/api/v1/negotiate", "negotiateFormat", "negotiation", "Pick the best format and transport")
+    },
+    Operation {
+        request: Some("SwitchFormatRequest"),
+        response: Body::Schema("SwitchFormatResponse"),
+        ..op("post", "/api/v1/format/switch", "switchFormat", "negotiation", "Switch format mid-session")
+    },
+    Operation {
+        response: Body::Json,
+        ..op("get", "/api/v1/openapi.json", "getOpenApi", "meta", "This document")
+    },
+    // Webhooks
+    Operation {
+        response: Body::Json,
+        ..op("get", "/webhook/*path", "getWebhook", "webhooks", "Call a GET webhook")
+    },
+    Operation {
+        response: Body::Json,
+        ..op("post", "/webhook/*path", "postWebhook", "webhooks", "Call a POST webhook")
+    },
+    // Manual runs
+    Operation {
+        response: Body::Schema("WorkflowInputs"),
+        ..op("get", "/api/v1/workflows/:id/inputs", "getWorkflowInputs", "workflows", "Parameters a manual run accepts")
+    },
+    Operation {
+        request: Some("RunWorkflowRequest"),
+        status: 201,
+        response: Body::Schema("ExecutionResponse"),
+        ..op("post", "/api/v1/workflows/:id/run", "runWorkflow", "workflows", "Run a workflow with input parameters")
+    },
+    Operation {
+        request: Some("RunBatchRequest"),
+        status: 202,
+        response: Body::Schema("RunBatchResponse"),
+        ..op("post", "/api/v1/workflows/:id/run-batch", "runWorkflowBatch", "workflows", "Run a workflow once per payload")
+    },
+    Operation {
+        response: Body::Schema("BatchStatus"),
+        ..op("get", "/api/v1/batches/:id", "getBatch", "workflows", "Progress and results of a batch")
+    },
+    // Workflow endpoints
+    Operation {
+        response: Body::Array("WorkflowEndpoint"),
+        ..op("get", "/api/v1/endpoints", "listEndpoints", "endpoints", "List workflows published as endpoints")
+    },
+    Operation {
+        request: Some("WorkflowEndpoint"),
+        status: 201,
+        response: Body::Schema("WorkflowEndpoint"),
+        ..op("post", "/api/v1/endpoints", "publishEndpoint", "endpoints", "Publish a workflow node at a method and path")
+    },
+    Operation {
+        status: 204,
+        response: Body::Empty,
+        ..op("delete", "/api/v1/endpoints/:id", "unpublishEndpoint", "endpoints", "Remove a published endpoint")
+    },
+    Operation {
+        response: Body::Json,
+        ..op("get", "/api/v1/endpoints/openapi.json", "getEndpointsOpenApi", "endpoints", "OpenAPI fragment of the published endpoints")
+    },
+    Operation {
+        response: Body::Json,
+        ..op("get", "/endpoint/*path", "callEndpoint", "endpoints", "Call a published endpoint with an API key")
+    },
+    // Webhook deliveries
+    Operation {
+        query: &[
+            ("workflowId", "string", "Only deliveries of this workflow"),
+            ("limit", "integer", "Maximum number of items, up to 500 (default 50)"),
+        ],
+        response: Body::Array("Delivery"),
+        ..op("get", "/api/v1/deliveries", "listDeliveries", "deliveries", "Latest webhook deliveries")
+    },
+    Operation {
+        response: Body::Schema("Delivery"),
+        ..op("get", "/api/v1/deliveries/:id", "getDelivery", "deliveries", "A webhook delivery and its attempts")
+    },
+    Operation {
+        response: Body::Schema("Delivery"),
+        ..op("post", "/api/v1/deliveries/:id/redeliver", "redeliver", "deliveries", "Send a webhook delivery again")
+    },
+    // Evaluations
+    Operation {
+        response: Body::Array("TestSet"),
+        ..op("get", "/api/v1/workflows/:id/test-sets", "listTestSets", "evaluations", "Test sets of a workflow")
+    },
+    Operation {
+        request: Some("CreateTestSetRequest"),
+        status: 201,
+        response: Body::Schema("TestSet"),
+        ..op("post", "/api/v1/workflows/:id/test-sets", "createTestSet", "evaluations", "Create a test set from past executions")
+    },
+    Operation {
+        response: Body::Schema("TestSet"),
+        ..op("get", "/api/v1/test-sets/:id", "getTestSet", "evaluations", "A test set with its cases")
+    },
+    Operation {
+        request: Some("TestSet"),
+        response: Body::Schema("TestSet"),
+        ..op("put", "/api/v1/test-sets/:id", "updateTestSet", "evaluations", "Replace the cases and scoring of a test set")
+    },
+    Operation {
+        status: 204,
+        response: Body::Empty,
+        ..op("delete", "/api/v1/test-sets/:id", "deleteTestSet", "evaluations", "Delete a test set and its reports")
+    },
+    Operation {
+        response: Body::Array("EvaluationReport"),
+        ..op("get", "/api/v1/test-sets/:id/runs", "listTestRuns", "evaluations", "Reports of a test set, newest first")
+    },
+    Operation {
+        request: Some("RunTestSetRequest"),
+        status: 201,
+        response: Body::Schema("EvaluationReport"),
+        ..op("post", "/api/v1/test-sets/:id/runs", "runTestSet", "evaluations", "Run a test set against the stored or a draft workflow")
+    },
+    Operation {
+        response: Body::Schema("EvaluationReport"),
+        ..op("get", "/api/v1/test-sets/:id/runs/:run_id", "getTestRun", "evaluations", "A test set report")
+    },
+    // Health
+    Operation {
+        response: Body::Text("text/plain"),
+        ..op("get", "/health", "health", "health", "Basic health check")
+    },
+    Operation {
+        response: Body::Text("text/plain"),
+        ..op("get", "/ready", "ready", "health", "Basic readiness check")
+    },
+    Operation {
+        response: Body::Json,
+        ..op("get", "/healthz", "liveness", "health", "Liveness probe")
+    },
+    Operation {
+        response: Body::Json,
+        ..op("get", "/readyz", "readiness", "health", "Readiness probe with dependency checks")
+    },
+];
+
+/// Build the OpenAPI document.
+pub fn openapi_document() -> Value {
+    let mut paths = Map::new();
+
+    for operation in OPERATIONS {
+        let (path, params) = openapi_path(operation.path);
+        let entry = paths
+            .entry(path)
+            .or_insert_with(|| Value::Object(Map::new()));
+
+        let mut parameters: Vec<Value> = params
+            .iter()
+            .map(|name| {
+                json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "string" },
+                })
+            })
+            .collect();
+        parameters.extend(operation.query.iter().map(|(name, ty, description)| {
+            json!({
+                "name": name,
+                "in": "query",
+                "required": false,
+                "description": description,
+                "schema": { "type": ty },
+            })
+        }));
+
+        let mut responses = Map::new();
+        responses.insert(operation.status.to_string(), response(operation.response));
+        responses.insert(
+            "default".to_string(),
+            json!({
+                "description": "Error",
+                "content": { "application/json": { "schema": schema_ref("ApiError") } },
+            }),
+        );
+
+        let mut spec = json!({
+            "operationId": operation.operation_id,
+            "tags": [operation.tag],
+            "summary": operation.summary,
+            "responses": responses,
+        });
+        if !parameters.is_empty() {
+            spec["parameters"] = Value::Array(parameters);
+        }
+        if let Some(request) = operation.request {
+            spec["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": schema_ref(request) } },
+            });
+        }
+
+        entry[operation.method] = spec;
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "n8n-rust API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "n8n-compatible REST API with transport negotiation.",
+        },
+        "paths": paths,
+        "components": { "schemas": schemas() },
+    })
+}
+
+/// Router serving the document at `/api/v1/openapi.json`.
+pub fn create_openapi_router() -> Router {
+    Router::new().route("/api/v1/openapi.json", get(get_openapi))
+}
+
+async fn get_openapi() -> Json<Value> {
+    Json(openapi_document())
+}
+
+/// `/api/v1/workflows/:id` → (`/api/v1/workflows/{id}`, `["id"]`).
+pub(super) fn openapi_path(path: &str) -> (String, Vec<&str>) {
+    let mut params = Vec::new();
+    let segments: Vec<String> = path
+        .split('/')
+        .map(|segment| match segment.strip_prefix(':').or_else(|| segment.strip_prefix('*')) {
+            Some(name) => {
+                params.push(name);
+                format!("{{{}}}", name)
+            }
+            None => segment.to_string(),
+        })
+        .collect();
+    (segments.join("/"), params)
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn response(body: Body) -> Value {
+    let content = |media: &str, schema: Value| {
+        json!({ "description": "Success", "content": { media: { "schema": schema } } })
+    };
+
+    match body {
+        Body::Empty => json!({ "description": "Success" }),
+        Body::Schema(name) => content("application/json", schema_ref(name)),
+        Body::List(name) => content(
+            "application/json",
+            json!({
+                "type": "object",
+                "required": ["data"],
+                "properties": {
+                    "data": { "type": "array", "items": schema_ref(name) },
+                    "nextCursor": { "type": "string" },
+                },
+            }),
+        ),
+        Body::Array(name) => content(
+            "application/json",
+            json!({ "type": "array", "items": schema_ref(name) }),
+        ),
+        Body::Json => content("application/json", json!({ "type": "object" })),
+        Body::Text(media) => content(media, json!({ "type": "string" })),
+    }
+}
+
+/// Component schemas for the request and response types.
+fn schemas() -> Value {
+    let string = json!({ "type": "string" });
+    let datetime = json!({ "type": "string", "format": "date-time" });
+    let object = json!({ "type": "object" });
+    let strings = json!({ "type": "array", "items": { "type": "string" } });
+    let live_counts = json!({
+        "type": "object",
+        "required": ["queued", "running", "waiting"],
+        "properties": {
+            "queued": { "type": "integer" },
+            "running": { "type": "integer" },
+            "waiting": { "type": "integer" },
+        },
+    });
+    let output_size = json!({
+        "type": "object",
+        "required": ["items", "bytes", "binaryBytes"],
+        "properties": {
+            "items": { "type": "integer" },
+            "bytes": { "type": "integer", "description": "Serialized JSON size" },
+            "binaryBytes": { "type": "integer" },
+        },
+    });
+    let connections = json!({
+        "type": "object",
+        "description": "Source node → connection type → output index → targets",
+        "additionalProperties": {
+            "type": "object",
+            "additionalProperties": {
+                "type": "array",
+                "items": { "type": "array", "items": schema_ref("Connection") },
+            },
+        },
+    });
+
+    let scorer = json!({
+        "type": "object",
+        "required": ["name", "expression"],
+        "properties": {
+            "name": string,
+            "expression": { "type": "string", "description": "Boolean or 0..1 score; $json is the output item, $expected the expected one" },
+            "threshold": { "type": "number", "default": 1 },
+        },
+    });
+    let test_case = json!({
+        "type": "object",
+        "required": ["input"],
+        "properties": {
+            "id": string,
+            "name": string,
+            "executionId": string,
+            "input": { "type": "array", "items": object },
+            "expected": { "type": "array", "items": object },
+        },
+    });
+    let case_result = json!({
+        "type": "object",
+        "required": ["caseId", "passed", "score", "scores", "durationMs", "output"],
+        "properties": {
+            "caseId": string,
+            "name": string,
+            "passed": { "type": "boolean" },
+            "score": { "type": "number" },
+            "scores": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["name", "score", "threshold", "passed"],
+                    "properties": {
+                        "name": string,
+                        "score": { "type": "number" },
+                        "threshold": { "type": "number" },
+                        "passed": { "type": "boolean" },
+                        "error": string,
+                    },
+                },
+            },
+            "error": string,
+            "durationMs": { "type": "integer" },
+            "output": { "type": "array", "items": object },
+        },
+    });
+
+    json!({
+        "ApiError": {
+            "type": "object",
+            "required": ["code", "message"],
+            "properties": { "code": { "type": "integer" }, "message": string },
+        },
+        "Node": {
+            "type": "object",
+            "required": ["id", "name", "type"],
+            "properties": {
+                "id": string,
+                "name": string,
+                "type": string,
+                "typeVersion": { "type": "integer" },
+                "position": { "type": "array", "items": { "type": "number" } },
+                "parameters": object,
+                "credentials": object,
+                "disabled": { "type": "boolean" },
+                "notes": string,
+            },
+        },
+        "Connection": {
+            "type": "object",
+            "required": ["node", "type", "index"],
+            "properties": {
+                "node": string,
+                "type": string,
+                "index": { "type": "integer" },
+            },
+        },
+        "WorkflowRequest": {
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": string,
+                "nodes": { "type": "array", "items": schema_ref("Node") },
+                "connections": connections,
+                "settings": object,
+                "staticData": object,
+                "versionId": {
+                    "type": "string",
+                    "description": "Version the update is based on; 409 if outdated",
+                },
+            },
+        },
+        "WorkflowResponse": {
+            "type": "object",
+            "required": ["id", "name", "active", "nodes", "connections", "createdAt", "updatedAt"],
+            "properties": {
+                "id": string,
+                "name": string,
+                "active": { "type": "boolean" },
+                "nodes": { "type": "array", "items": schema_ref("Node") },
+                "connections": connections,
+                "settings": object,
+                "staticData": object,
+                "versionId": string,
+                "createdAt": datetime,
+                "updatedAt": datetime,
+                "schedule": schema_ref("SchedulePreview"),
+            },
+        },
+        "CloneWorkflowRequest": {
+            "type": "object",
+            "properties": {
+                "name": string,
+                "nameSuffix": { "type": "string", "default": " (copy)" },
+                "projectId": string,
+                "credentials": {
+                    "type": "object",
+                    "description": "Source credential ID or name → replacement",
+                    "additionalProperties": {
+                        "type": "object",
+                        "required": ["id", "name"],
+                        "properties": { "id": string, "name": string },
+                    },
+                },
+                "variables": {
+                    "type": "object",
+                    "description": "Source variable name → replacement name",
+                    "additionalProperties": string,
+                },
+            },
+        },
+        "CloneWorkflowResponse": {
+            "allOf": [
+                schema_ref("WorkflowResponse"),
+                {
+                    "type": "object",
+                    "required": ["clonedFrom", "remapping"],
+                    "properties": {
+                        "clonedFrom": string,
+                        "remapping": {
+                            "type": "object",
+                            "properties": {
+                                "remappedCredentials": { "type": "integer" },
+                                "remappedVariables": { "type": "integer" },
+                                "unmappedCredentials": strings,
+                            },
+                        },
+                    },
+                },
+            ],
+        },
+        "CostReport": {
+            "type": "object",
+            "required": ["currency", "total", "executions", "byNodeType", "byUnit"],
+            "properties": {
+                "currency": string,
+                "total": { "type": "number" },
+                "executions": { "type": "integer" },
+                "byNodeType": { "type": "object", "additionalProperties": { "type": "number" } },
+                "byUnit": {
+                    "type": "object",
+                    "additionalProperties": {
+                        "type": "object",
+                        "properties": {
+                            "quantity": { "type": "number" },
+                            "cost": { "type": "number" },
+                        },
+                    },
+                },
+            },
+        },
+        "SchedulePreview": {
+            "type": "object",
+            "required": ["timezone", "nextRuns"],
+            "properties": {
+                "timezone": string,
+                "nextRuns": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["node", "at"],
+                        "properties": {
+                            "node": string,
+                            "at": datetime,
+                            "deferredFrom": datetime,
+                        },
+                    },
+                },
+                "invalid": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["node", "message"],
+                        "properties": { "node": string, "message": string },
+                    },
+                },
+            },
+        },
+        "ProfileReport": {
+            "type": "object",
+            "required": ["total", "runs"],
+            "properties": {
+                "total": schema_ref("NodeProfile"),
+                "runs": {
+                    "type": "array",
+                    "description": "Node runs in execution order",
+                    "items": {
+                        "allOf": [
+                            schema_ref("NodeProfile"),
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "node": string,
+                                    "runIndex": { "type": "integer" },
+                                    "startTime": { "type": "integer" },
+                                },
+                            },
+                        ],
+                    },
+                },
+            },
+        },
+        "NodeProfile": {
+            "type": "object",
+            "properties": {
+                "nodeType": string,
+                "wallMs": { "type": "number" },
+                "cpuMs": { "type": "number" },
+                "itemsIn": { "type": "integer" },
+                "itemsOut": { "type": "integer" },
+                "bytesIn": { "type": "integer" },
+                "bytesOut": { "type": "integer" },
+                "retries": { "type": "integer" },
+            },
+        },
+        "LiveSnapshot": {
+            "type": "object",
+            "required": ["total", "workflows"],
+            "properties": {
+                "total": live_counts,
+                "workflows": {
+                    "type": "object",
+                    "description": "Workflow ID → counts, for workflows with unfinished executions",
+                    "additionalProperties": live_counts,
+                },
+            },
+        },
+        "InFlightExecution": {
+            "type": "object",
+            "required": [
+                "executionId", "workflowId", "status", "queuedAt", "elapsedMs", "itemsProcessed",
+                "nodes",
+            ],
+            "properties": {
+                "executionId": string,
+                "workflowId": string,
+                "status": { "type": "string", "enum": ["new", "running"] },
+                "queuePosition": {
+                    "type": "integer",
+                    "description": "Place among executions waiting for the same concurrency group",
+                },
+                "queuedAt": datetime,
+                "startedAt": datetime,
+                "elapsedMs": { "type": "integer" },
+                "currentNode": string,
+                "currentNodeElapsedMs": { "type": "integer" },
+                "itemsProcessed": { "type": "integer" },
+                "nodes": {
+                    "type": "array",
+                    "description": "Finished node runs in the order they finished",
+                    "items": {
+                        "type": "object",
+                        "required": ["node", "runIndex", "status", "elapsedMs", "items"],
+                        "properties": {
+                            "node": string,
+                            "runIndex": { "type": "integer" },
+                            "status": string,
+                            "elapsedMs": { "type": "integer" },
+                            "items": { "type": "integer" },
+                        },
+                    },
+                },
+            },
+        },
+        "TriggerState": {
+            "type": "object",
+            "required": ["workflowId", "node", "consecutiveFailures"],
+            "properties": {
+                "workflowId": string,
+                "node": string,
+                "consecutiveFailures": { "type": "integer" },
+                "lastError": string,
+                "lastFailureAt": datetime,
+                "pausedAt": {
+                    "type": "string",
+                    "format": "date-time",
+                    "description": "When the trigger was paused; absent while it runs",
+                },
+            },
+        },
+        "RunDataSummary": {
+            "type": "object",
+            "required": ["total", "nodes"],
+            "properties": {
+                "total": output_size,
+                "nodes": {
+                    "type": "object",
+                    "description": "Node name → its runs",
+                    "additionalProperties": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "runIndex": { "type": "integer" },
+                                "executionStatus": string,
+                                "outputs": {
+                                    "type": "object",
+                                    "description": "Connection type → sizes per output index",
+                                    "additionalProperties": { "type": "array", "items": output_size },
+                                },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        "ItemPage": {
+            "type": "object",
+            "required": ["node", "runIndex", "connectionType", "outputIndex", "offset", "total", "items"],
+            "properties": {
+                "node": string,
+                "runIndex": { "type": "integer" },
+                "connectionType": string,
+                "outputIndex": { "type": "integer" },
+                "offset": { "type": "integer" },
+                "total": { "type": "integer" },
+                "items": { "type": "array", "items": object },
+                "nextOffset": { "type": "integer" },
+            },
+        },
+        "Delivery": {
+            "type": "object",
+            "required": ["id", "node", "url", "payload", "status", "retry", "attempts", "createdAt", "updatedAt"],
+            "properties": {
+                "id": string,
+                "workflowId": string,
+                "node": string,
+                "url": string,
+                "payload": {},
+                "status": { "type": "string", "enum": ["pending", "succeeded", "failed"] },
+                "retry": {
+                    "type": "object",
+                    "properties": {
+                        "maxAttempts": { "type": "integer" },
+                        "initialDelayMs": { "type": "integer" },
+                        "maxDelayMs": { "type": "integer" },
+                    },
+                },
+                "attempts": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["attempt", "startedAt", "durationMs", "manual"],
+                        "properties": {
+                            "attempt": { "type": "integer" },
+                            "startedAt": { "type": "string", "format": "date-time" },
+                            "durationMs": { "type": "integer" },
+                            "statusCode": { "type": "integer" },
+                            "error": string,
+                            "manual": { "type": "boolean" },
+                        },
+                    },
+                },
+                "createdAt": { "type": "string", "format": "date-time" },
+                "updatedAt": { "type": "string", "format": "date-time" },
+            },
+        },
+        "TestSet": {
+            "type": "object",
+            "required": ["name", "startNode"],
+            "properties": {
+                "id": string,
+                "name": string,
+                "workflowId": string,
+                "startNode": string,
+                "outputNode": string,
+                "ignoreFields": strings,
+                "scorers": { "type": "array", "items": scorer },
+                "cases": { "type": "array", "items": test_case },
+                "createdAt": datetime,
+                "updatedAt": datetime,
+            },
+        },
+        "CreateTestSetRequest": {
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": string,
+                "startNode": string,
+                "outputNode": string,
+                "ignoreFields": strings,
+                "scorers": { "type": "array", "items": scorer },
+                "executionIds": strings,
+                "cases": { "type": "array", "items": test_case },
+            },
+        },
+        "RunTestSetRequest": {
+            "type": "object",
+            "properties": {
+                "workflow": { "type": "object", "description": "Draft version to test instead of the stored workflow" },
+            },
+        },
+        "EvaluationReport": {
+            "type": "object",
+            "required": ["id", "testSetId", "workflowId", "startedAt", "finishedAt", "total", "passed", "failed", "score", "cases"],
+            "properties": {
+                "id": string,
+                "testSetId": string,
+                "workflowId": string,
+                "versionId": string,
+                "startedAt": datetime,
+                "finishedAt": datetime,
+                "total": { "type": "integer" },
+                "passed": { "type": "integer" },
+                "failed": { "type": "integer" },
+                "score": { "type": "number" },
+                "cases": { "type": "array", "items": case_result },
+            },
+        },
+        "WorkflowEndpoint": {
+            "type": "object",
+            "required": ["workflowId", "node", "path"],
+            "properties": {
+                "id": string,
+                "workflowId": string,
+                "node": string,
+                "method": { "type": "string", "default": "GET" },
+                "path": { "type": "string", "description": "Path below /endpoint/, with :name parameters" },
+                "summary": string,
+                "requestSchema": object,
+                "responseSchema": object,
+                "scopes": strings,
+            },
+        },
+        "WorkflowInput": {
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": string,
+                "type": { "type": "string", "enum": ["string", "number", "boolean", "json"], "default": "string" },
+                "required": { "type": "boolean" },
+                "default": {},
+                "description": string,
+                "options": { "type": "array", "items": {} },
+            },
+        },
+        "WorkflowInputs": {
+            "type": "object",
+            "required": ["workflowId", "inputs", "schema"],
+            "properties": {
+                "workflowId": string,
+                "startNode": string,
+                "inputs": { "type": "array", "items": schema_ref("WorkflowInput") },
+                "schema": { "type": "object", "description": "JSON Schema of a run request's inputs" },
+            },
+        },
+        "RunWorkflowRequest": {
+            "type": "object",
+            "properties": {
+                "inputs": { "type": "object", "description": "Input name → value" },
+                "mocks": {
+                    "type": "object",
+                    "description": "Node name → items it outputs instead of running",
+                    "additionalProperties": { "type": "array", "items": object },
+                },
+            },
+        },
+        "PatchWorkflowRequest": {
+            "type": "object",
+            "required": ["versionId", "operations"],
+            "properties": {
+                "versionId": { "type": "string", "description": "Version the operations were written against" },
+                "operations": {
+                    "type": "array",
+                    "description": "Applied in order, all or none",
+                    "items": {
+                        "type": "object",
+                        "required": ["op"],
+                        "properties": {
+                            "op": {
+                                "type": "string",
+                                "enum": ["addNode", "removeNode", "updateNode", "addConnection", "removeConnection"],
+                            },
+                            "node": object,
+                            "name": string,
+                            "changes": { "type": "object", "description": "JSON merge patch of the node" },
+                            "source": string,
+                            "target": string,
+                            "sourceOutput": { "type": "integer" },
+                            "targetInput": { "type": "integer" },
+                            "type": { "type": "string", "description": "Connection type, main by default" },
+                        },
+                    },
+                },
+            },
+        },
+        "WorkflowVersion": {
+            "type": "object",
+            "required": ["versionId", "name", "nodes", "connections", "savedAt"],
+            "properties": {
+                "versionId": string,
+                "previousVersionId": string,
+                "name": string,
+                "nodes": { "type": "array", "items": object },
+                "connections": object,
+                "operations": { "type": "array", "items": object },
+                "savedAt": datetime,
+            },
+        },
+        "RunBatchRequest": {
+            "type": "object",
+            "required": ["payloads"],
+            "properties": {
+                "payloads": {
+                    "type": "array",
+                    "description": "Inputs of each execution",
+                    "items": object,
+                },
+                "mocks": {
+                    "type": "object",
+                    "description": "Node name → items it outputs instead of running",
+                    "additionalProperties": { "type": "array", "items": object },
+                },
+                "concurrency": { "type": "integer", "description": "Executions running at the same time" },
+            },
+        },
+        "RunBatchResponse": {
+            "type": "object",
+            "required": ["batchId", "workflowId", "executionIds"],
+            "properties": {
+                "batchId": string,
+                "workflowId": string,
+                "executionIds": strings,
+            },
+        },
+        "BatchStatus": {
+            "type": "object",
+            "required": ["batchId", "workflowId", "createdAt", "total", "counts", "finished", "executions"],
+            "properties": {
+                "batchId": string,
+                "workflowId": string,
+                "createdAt": datetime,
+                "total": { "type": "integer" },
+                "counts": {
+                    "type": "object",
+                    "properties": {
+                        "queued": { "type": "integer" },
+                        "running": { "type": "integer" },
+                        "waiting": { "type": "integer" },
+                        "success": { "type": "integer" },
+                        "error": { "type": "integer" },
+                    },
+                },
+                "finished": { "type": "boolean" },
+                "executions": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["id", "status"],
+                        "properties": {
+                            "id": string,
+                            "status": string,
+                            "error": string,
+                            "finishedAt": datetime,
+                        },
+                    },
+                },
+            },
+        },
+        "ExecutionRequest": {
+            "type": "object",
+            "required": ["workflowId"],
+            "properties": {
+                "workflowId": string,
+                "mode": string,
+                "data": object,
+            },
+        },
+        "ExecutionResponse": {
+            "type": "object",
+            "required": ["id", "finished", "mode", "status", "startedAt", "data"],
+            "properties": {
+                "id": string,
+                "workflowId": string,
+                "finished": { "type": "boolean" },
+                "mode": string,
+                "status": {
+                    "type": "string",
+                    "enum": ["new", "running", "success", "error", "waiting", "canceled", "crashed"],
+                },
+                "startedAt": datetime,
+                "stoppedAt": datetime,
+                "waitTill": datetime,
+                "data": {
+                    "type": "object",
+                    "properties": {
+                        "resultData": {
+                            "type": "object",
+                            "properties": {
+                                "runData": object,
+                                "lastNodeExecuted": string,
+                                "error": object,
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        "ExecutionPlan": {
+            "type": "object",
+            "properties": {
+                "workflowId": string,
+                "workflowName": string,
+                "startNodes": strings,
+                "order": strings,
+                "loops": { "type": "array", "items": strings },
+                "skipped": strings,
+                "nodes": { "type": "array", "items": object },
+                "estimatedExternalCalls": { "type": "integer" },
+                "credentials": { "type": "array", "items": object },
+                "warnings": strings,
+            },
+        },
+        "LintReport": {
+            "type": "object",
+            "properties": {
+                "source": string,
+                "workflowId": string,
+                "workflowName": string,
+                "diagnostics": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "ruleId": string,
+                            "severity": { "type": "string", "enum": ["info", "warning", "error"] },
+                            "message": string,
+                            "node": string,
+                            "parameter": string,
+                        },
+                    },
+                },
+            },
+        },
+        "RuleInfo": {
+            "type": "object",
+            "properties": {
+                "id": string,
+                "description": string,
+                "level": { "type": "string", "enum": ["off", "info", "warning", "error"] },
+            },
+        },
+        "TransportCapabilities": object,
+        "NegotiateRequest": {
+            "type": "object",
+            "required": ["formats", "transports"],
+            "properties": {
+                "formats": strings,
+                "transports": strings,
+                "data_hints": {
+                    "type": "object",
+                    "properties": {
+                        "sizeBytes": { "type": "integer" },
+                        "streaming": { "type": "boolean" },
+                        "columnar": { "type": "boolean" },
+                        "recordCount": { "type": "integer" },
+                    },
+                },
+            },
+        },
+        "NegotiateResponse": {
+            "type": "object",
+            "properties": {
+                "format": string,
+                "transport": string,
+                "endpoint": string,
+                "reason": string,
+                "alternatives": { "type": "array", "items": object },
+            },
+        },
+        "SwitchFormatRequest": {
+            "type": "object",
+            "required": ["format"],
+            "properties": {
+                "format": string,
+                "transport": string,
+                "session_id": string,
+            },
+        },
+        "SwitchFormatResponse": {
+            "type": "object",
+            "properties": {
+                "success": { "type": "boolean" },
+                "newFormat": string,
+                "newTransport": string,
+                "newEndpoint": string,
+                "headers": { "type": "object", "additionalProperties": string },
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_covers_operations() {
+        let doc = openapi_document();
+        assert_eq!(doc["openapi"], "3.0.3");
+
+        let get = &doc["paths"]["/api/v1/workflows/{id}"]["get"];
+        assert_eq!(get["operationId"], "getWorkflow");
+        assert_eq!(get["parameters"][0]["name"], "id");
+
+        // Every referenced schema is defined.
+        let text = doc.to_string();
+        for (i, _) in text.match_indices("#/components/schemas/") {
+            let name: String = text[i + 21..].chars().take_while(|c| *c != '"').collect();
+            assert!(
+                doc["components"]["schemas"].get(&name).is_some(),
+                "missing schema {}",
+                name
+            );
+        }
+    }
+}
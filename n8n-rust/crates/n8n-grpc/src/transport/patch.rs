@@ -0,0 +1,172 @@
+//! Differential workflow edits and version history.
+//!
+//! `PATCH /api/v1/workflows/:id` applies a [`WorkflowPatch`] to the
+//! workflow at the `versionId` the client read, instead of replacing it:
+//!
+//! ```json
+//! {
+//!   "versionId": "5b1f…",
+//!   "operations": [
+//!     { "op": "updateNode", "name": "Fetch", "changes": { "parameters": { "url": "…" } } },
+//!     { "op": "addConnection", "source": "Fetch", "target": "Store" }
+//!   ]
+//! }
+//! ```
+//!
+//! The patch applies completely or not at all: an operation that does not
+//! fit the workflow refuses it with `422`, and a workflow saved since
+//! `versionId` with `409`, checked again by the save itself. Every version
+//! saved through create, update and patch is recorded with its nodes and
+//! connections, and for patches its operations, by the workflow storage in
+//! the same write as the save ([`WorkflowStorage::save_workflow_version`]),
+//! in `workflow_history` for PostgreSQL. `GET /api/v1/workflows/:id/history`
+//! lists the last [`MAX_VERSIONS`] of them, newest first.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use n8n_core::{PatchOperation, WorkflowPatch, WorkflowStorage, WorkflowVersion};
+use serde::Deserialize;
+
+use super::api::{save_workflow_version, ApiError, ApiState, WorkflowResponse};
+
+/// Versions listed by the history endpoint.
+pub const MAX_VERSIONS: usize = 50;
+
+/// Request body of `PATCH /api/v1/workflows/:id`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchWorkflowRequest {
+    /// Version the operations were written against.
+    pub version_id: String,
+    pub operations: Vec<PatchOperation>,
+}
+
+/// PATCH /workflows/:id - Apply operations to a workflow.
+pub async fn patch_workflow(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Json(request): Json<PatchWorkflowRequest>,
+) -> Result<Json<WorkflowResponse>, ApiError> {
+    let existing = state
+        .workflows
+        .get_workflow(&id)
+        .await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        })?;
+    if existing.version_id.as_deref() != Some(request.version_id.as_str()) {
+        return Err(ApiError {
+            code: 409,
+            message: format!(
+                "Workflow {} is no longer at version {}",
+                id, request.version_id
+            ),
+        });
+    }
+
+    let patch = WorkflowPatch::new(request.operations);
+    let mut workflow = patch.apply(&existing).map_err(|e| ApiError {
+        code: 422,
+        message: e.to_string(),
+    })?;
+    state.migrate(&mut workflow);
+
+    // Saved against `versionId`: a concurrent save since the read wins.
+    let workflow = save_workflow_version(&state, &workflow, Some(&patch.operations)).await?;
+
+    state.compiled_cache.invalidate(&id);
+    if workflow.active {
+        let compiled = state.compiled_cache.compile_and_cache(&workflow, &state.executor_registry);
+        if let Err(e) = compiled {
+            tracing::warn!(
+                workflow = %workflow.name,
+                error = %e,
+                "Recompilation after patch failed"
+            );
+        }
+    }
+    Ok(Json(WorkflowResponse::from(&workflow)))
+}
+
+/// GET /workflows/:id/history - Saved versions, newest first.
+pub async fn workflow_history(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<WorkflowVersion>>, ApiError> {
+    if state.workflows.get_workflow(&id).await.ok().flatten().is_none() {
+        return Err(ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        });
+    }
+    let history = state
+        .workflows
+        .workflow_history(&id, MAX_VERSIONS)
+        .await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?;
+    Ok(Json(history))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::api::{create_workflow, ExecutionStore, WorkflowRequest};
+    use n8n_core::MemoryWorkflowStorage;
+    use n8n_workflow::Node;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_patch_checks_version_and_records_history() {
+        let state = ApiState::new(
+            Arc::new(MemoryWorkflowStorage::new()),
+            Arc::new(ExecutionStore::new()),
+        );
+        let request: WorkflowRequest = serde_json::from_value(serde_json::json!({
+            "name": "orders",
+            "nodes": [Node::new("Start", "n8n-nodes-base.manualTrigger")]
+        }))
+        .unwrap();
+        let (_, Json(created)) =
+            create_workflow(State(state.clone()), Json(request)).await.unwrap();
+        let base = created.version_id.clone().unwrap();
+
+        let patch = |version_id: &str| {
+            serde_json::from_value::<PatchWorkflowRequest>(serde_json::json!({
+                "versionId": version_id,
+                "operations": [
+                    { "op": "updateNode", "name": "Start", "changes": { "notes": "go" } }
+                ]
+            }))
+            .unwrap()
+        };
+        let Json(patched) =
+            patch_workflow(State(state.clone()), Path(created.id.clone()), Json(patch(&base)))
+                .await
+                .unwrap();
+        assert_eq!(patched.nodes[0].notes.as_deref(), Some("go"));
+
+        // A second patch against the old version loses.
+        let stale =
+            patch_workflow(State(state.clone()), Path(created.id.clone()), Json(patch(&base)))
+                .await
+                .unwrap_err();
+        assert_eq!(stale.code, 409);
+
+        let Json(history) =
+            workflow_history(State(state.clone()), Path(created.id.clone())).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].previous_version_id.as_deref(), Some(base.as_str()));
+        assert!(history[0].operations.is_some());
+        assert!(history[1].operations.is_none());
+    }
+}
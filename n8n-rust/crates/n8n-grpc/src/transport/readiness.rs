@@ -0,0 +1,209 @@
+//! Liveness and readiness endpoints for orchestration platforms.
+//!
+//! - `GET /healthz` (liveness): the process is up and serving HTTP.
+//! - `GET /readyz` (readiness): every critical dependency registered in
+//!   [`GrpcHealth`] is serving: the database answers, its migrations are
+//!   applied, Redis answers and the queue consumer has started, for those
+//!   configured. Returns `503 Service Unavailable` otherwise.
+//!
+//! Both return a JSON body with per-check detail.
+
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::services::health::{ComponentHealth, GrpcHealth};
+
+/// State for the health endpoints.
+#[derive(Clone)]
+pub struct ReadinessState {
+    pub health: GrpcHealth,
+    pub started_at: DateTime<Utc>,
+}
+
+impl ReadinessState {
+    pub fn new(health: GrpcHealth) -> Self {
+        Self {
+            health,
+            started_at: Utc::now(),
+        }
+    }
+}
+
+/// Liveness response body.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LivenessResponse {
+    pub status: &'static str,
+    pub started_at: DateTime<Utc>,
+    pub uptime_secs: i64,
+}
+
+/// Readiness response body.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessResponse {
+    /// `ready` or `not_ready`.
+    pub status: &'static str,
+    /// Per-dependency check results, keyed by health service name.
+    pub checks: BTreeMap<String, ComponentHealth>,
+}
+
+/// Create the router for `/healthz` and `/readyz`.
+pub fn create_readiness_router(state: ReadinessState) -> Router {
+    Router::new()
+        .route("/healthz", get(liveness))
+        .route("/readyz", get(readiness))
+        .with_state(state)
+}
+
+async fn liveness(State(state): State<ReadinessState>) -> Json<LivenessResponse> {
+    Json(LivenessResponse {
+        status: "ok",
+        started_at: state.started_at,
+        uptime_secs: (Utc::now() - state.started_at).num_seconds(),
+    })
+}
+
+async fn readiness(State(state): State<ReadinessState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let checks: BTreeMap<String, ComponentHealth> =
+        state.health.snapshot().await.into_iter().collect();
+    let ready = checks.values().all(|c| c.serving || !c.critical);
+
+    let (code, status) = if ready {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    };
+
+    (code, Json(ReadinessResponse { status, checks }))
+}
+
+/// Register the critical component `name` and report it ready while
+/// `started` is true, for background work such as the queue consumer that
+/// starts after the server. It is not ready once the sender is dropped.
+pub async fn track_started(
+    health: &GrpcHealth,
+    name: &str,
+    mut started: watch::Receiver<bool>,
+) -> JoinHandle<()> {
+    health.register(name, true).await;
+    let health = health.clone();
+    let name = name.to_string();
+    tokio::spawn(async move {
+        loop {
+            let result = if *started.borrow_and_update() {
+                Ok(())
+            } else {
+                Err("not started".to_string())
+            };
+            health.set_result(&name, result).await;
+            if started.changed().await.is_err() {
+                health.set_result(&name, Err("stopped".to_string())).await;
+                return;
+            }
+        }
+    })
+}
+
+/// Check that a Redis server answers `PING`.
+///
+/// Accepts `redis://[user:password@]host[:port][/db]` URLs and speaks just
+/// enough RESP to authenticate and ping, so no Redis client is required.
+pub async fn redis_ping(url: &str, timeout: Duration) -> Result<(), String> {
+    let rest = url
+        .strip_prefix("redis://")
+        .ok_or_else(|| format!("Unsupported Redis URL: {}", url))?;
+    let (auth, host) = match rest.rsplit_once('@') {
+        Some((auth, host)) => (Some(auth), host),
+        None => (None, rest),
+    };
+    let host = host.split('/').next().unwrap_or(host);
+    let addr = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:6379", host)
+    };
+
+    let ping = async {
+        let mut stream = TcpStream::connect(&addr)
+            .await
+            .map_err(|e| format!("Redis connect to {} failed: {}", addr, e))?;
+
+        if let Some(auth) = auth {
+            let command = match auth.split_once(':') {
+                Some(("", password)) => format!("AUTH {}\r\n", password),
+                Some((user, password)) => format!("AUTH {} {}\r\n", user, password),
+                None => format!("AUTH {}\r\n", auth),
+            };
+            let reply = send_command(&mut stream, &command).await?;
+            if !reply.starts_with("+OK") {
+                return Err(format!("Redis AUTH failed: {}", reply.trim()));
+            }
+        }
+
+        let reply = send_command(&mut stream, "PING\r\n").await?;
+        if reply.starts_with("+PONG") {
+            Ok(())
+        } else {
+            Err(format!("Unexpected Redis reply: {}", reply.trim()))
+        }
+    };
+
+    tokio::time::timeout(timeout, ping)
+        .await
+        .map_err(|_| format!("Redis ping to {} timed out", addr))?
+}
+
+async fn send_command(stream: &mut TcpStream, command: &str) -> Result<String, String> {
+    stream
+        .write_all(command.as_bytes())
+        .await
+        .map_err(|e| format!("Redis write failed: {}", e))?;
+
+    let mut buf = [0u8; 256];
+    let n = stream
+        .read(&mut buf)
+        .await
+        .map_err(|e| format!("Redis read failed: {}", e))?;
+    Ok(String::from_utf8_lossy(&buf[..n]).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::health::HEALTH_QUEUE_CONSUMER;
+
+    #[tokio::test]
+    async fn test_readiness_waits_for_the_queue_consumer() {
+        let (health, _service) = GrpcHealth::new();
+        let state = ReadinessState::new(health.clone());
+        let (started, receiver) = watch::channel(false);
+        let task = track_started(&health, HEALTH_QUEUE_CONSUMER, receiver).await;
+        tokio::task::yield_now().await;
+
+        let (code, Json(body)) = readiness(State(state.clone())).await;
+        assert_eq!(code, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!body.checks[HEALTH_QUEUE_CONSUMER].serving);
+
+        started.send_replace(true);
+        while !health.is_serving().await {
+            tokio::task::yield_now().await;
+        }
+        let (code, _) = readiness(State(state.clone())).await;
+        assert_eq!(code, StatusCode::OK);
+
+        drop(started);
+        task.await.unwrap();
+        let (code, Json(body)) = readiness(State(state)).await;
+        // The consumer is gone again once its sender is dropped.
+        assert_eq!(code, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body.checks[HEALTH_QUEUE_CONSUMER].detail.as_deref(), Some("stopped"));
+    }
+}
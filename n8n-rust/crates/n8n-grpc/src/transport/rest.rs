@@ -12,9 +12,11 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
+use n8n_core::{with_correlation_id, CORRELATION_ID_HEADER};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
+use tracing::Instrument;
 
 use super::negotiate::{ContentFormat, FormatNegotiator, TransportCapabilities};
 
@@ -160,6 +162,37 @@ pub async fn negotiate_content(
     response
 }
 
+/// Middleware assigning a correlation id to each request.
+///
+/// The id is taken from `X-Correlation-Id` (or `X-Request-Id`) when present,
+/// otherwise generated. It is in scope for the whole request, so executions
+/// started by the request log it, and it is echoed on the response.
+pub async fn correlate(request: Request<Body>, next: Next) -> Response {
+    let correlation_id = [CORRELATION_ID_HEADER, "x-request-id"]
+        .iter()
+        .find_map(|name| request.headers().get(*name)?.to_str().ok())
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        correlation_id = %correlation_id,
+    );
+
+    let mut response = with_correlation_id(correlation_id.clone(), next.run(request))
+        .instrument(span)
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(&correlation_id) {
+        response.headers_mut().insert(CORRELATION_ID_HEADER, value);
+    }
+
+    response
+}
+
 /// Capabilities endpoint - returns server capabilities and available formats.
 pub async fn get_capabilities() -> Json<TransportCapabilities> {
     Json(TransportCapabilities::default())
@@ -188,6 +221,8 @@ pub struct DataHints {
     pub columnar: Option<bool>,
     /// Expected record count.
     pub record_count: Option<u64>,
+    /// Is the client on the same host? Enables the shared-memory transport.
+    pub colocated: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -221,13 +256,15 @@ pub async fn negotiate_format(
     let (format, transport) = negotiator.negotiate(&request.formats, &request.transports, &request.data_hints);
 
     let endpoint = match transport.as_str() {
-        "grpc" => "/grpc",
-        "flight" => "/flight",
-        "stdio" => "stdio://",
-        _ => "/api/v1",
+        "grpc" => "/grpc".to_string(),
+        "flight" => "/flight".to_string(),
+        "stdio" => "stdio://".to_string(),
+        "shm" => negotiator.shared_memory_endpoint().unwrap_or_default(),
+        _ => "/api/v1".to_string(),
     };
 
     let reason = match (format.as_str(), transport.as_str()) {
+        ("arrow-ipc", "shm") => "Same host: Arrow IPC handed over in shared memory".to_string(),
         ("arrow-ipc", "flight") => "Best for large tabular data with streaming".to_string(),
         ("arrow-ipc", _) => "Efficient columnar format with zero-copy".to_string(),
         ("protobuf", "grpc") => "Efficient binary format with strong typing".to_string(),
@@ -259,7 +296,7 @@ pub async fn negotiate_format(
     Json(NegotiateResponse {
         format,
         transport,
-        endpoint: endpoint.to_string(),
+        endpoint,
         reason,
         alternatives,
     })
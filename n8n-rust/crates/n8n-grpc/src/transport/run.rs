@@ -0,0 +1,577 @@
+//! Manual runs with input parameters.
+//!
+//! `GET /api/v1/workflows/:id/inputs` describes the parameters declared in
+//! the workflow's `inputs` setting, with a JSON Schema UIs can render as a
+//! form. `POST /api/v1/workflows/:id/run` checks the supplied values with
+//! [`n8n_core::inputs::resolve_inputs`] and runs the workflow from its
+//! manual trigger with them as the trigger item, answering with the
+//! finished execution. These are manual executions: inactive workflows may
+//! be run and run windows do not apply.
+//!
+//! `mocks` maps node names to the items those nodes output in this run
+//! instead of running, so a test run does not reach production APIs. They
+//! are pinned on top of the workflow's own pin data for this run only; the
+//! stored workflow is not changed.
+//!
+//! `POST /api/v1/workflows/:id/run-batch` takes an array of `payloads`,
+//! each the `inputs` of one run, checks them all up front and answers
+//! `202 Accepted` with a batch id and one execution id per payload. The
+//! executions run in the background, at most `concurrency` at a time.
+//! `GET /api/v1/batches/:id` aggregates their statuses; executions are
+//! `new` until they start. Batches are held in memory and lost on restart;
+//! finished ones are forgotten after [`BATCH_RETENTION_HOURS`].
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
+use n8n_core::inputs::{input_schema, resolve_inputs};
+use n8n_core::WorkflowStorage;
+use n8n_workflow::{ExecutionStatus, NodeExecutionData, PinData, Workflow, WorkflowInput};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use super::api::{ApiError, ExecutionResponse};
+use super::webhook::WebhookState;
+
+/// Node type manual runs start from.
+pub const MANUAL_TRIGGER_NODE_TYPE: &str = "n8n-nodes-base.manualTrigger";
+
+/// Request body of `POST /api/v1/workflows/:id/run`.
+#[derive(Debug, Default, Deserialize)]
+pub struct RunWorkflowRequest {
+    #[serde(default)]
+    pub inputs: Map<String, Value>,
+    /// Items output by the named nodes instead of running them.
+    #[serde(default)]
+    pub mocks: PinData,
+}
+
+/// Payloads a batch may hold.
+pub const MAX_BATCH_SIZE: usize = 1000;
+
+/// Executions of a batch running at the same time unless the request says.
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// Hours a finished batch stays queryable.
+pub const BATCH_RETENTION_HOURS: i64 = 24;
+
+/// Request body of `POST /api/v1/workflows/:id/run-batch`.
+#[derive(Debug, Default, Deserialize)]
+pub struct RunBatchRequest {
+    /// `inputs` of each execution.
+    pub payloads: Vec<Map<String, Value>>,
+    /// Items output by the named nodes instead of running them, in every
+    /// execution.
+    #[serde(default)]
+    pub mocks: PinData,
+    /// Executions running at the same time.
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+}
+
+/// A started batch.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunBatchResponse {
+    pub batch_id: String,
+    pub workflow_id: String,
+    /// Execution of each payload, in order.
+    pub execution_ids: Vec<String>,
+}
+
+/// One execution of a batch.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchExecution {
+    pub id: String,
+    /// `new` until the execution starts.
+    pub status: ExecutionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Executions of a batch per status.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct BatchCounts {
+    pub queued: usize,
+    pub running: usize,
+    pub waiting: usize,
+    pub success: usize,
+    /// Failed, crashed or canceled executions.
+    pub error: usize,
+}
+
+/// Progress and results of a batch.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchStatusResponse {
+    pub batch_id: String,
+    pub workflow_id: String,
+    pub created_at: DateTime<Utc>,
+    pub total: usize,
+    pub counts: BatchCounts,
+    /// Whether every execution has finished.
+    pub finished: bool,
+    pub executions: Vec<BatchExecution>,
+}
+
+impl BatchStatusResponse {
+    fn new(batch_id: &str, batch: &Batch) -> Self {
+        let mut counts = BatchCounts::default();
+        for execution in &batch.executions {
+            match execution.status {
+                ExecutionStatus::New => counts.queued += 1,
+                ExecutionStatus::Running => counts.running += 1,
+                ExecutionStatus::Waiting => counts.waiting += 1,
+                ExecutionStatus::Success => counts.success += 1,
+                _ => counts.error += 1,
+            }
+        }
+        Self {
+            batch_id: batch_id.to_string(),
+            workflow_id: batch.workflow_id.clone(),
+            created_at: batch.created_at,
+            total: batch.executions.len(),
+            finished: batch.is_finished(),
+            counts,
+            executions: batch.executions.clone(),
+        }
+    }
+}
+
+struct Batch {
+    workflow_id: String,
+    created_at: DateTime<Utc>,
+    executions: Vec<BatchExecution>,
+}
+
+impl Batch {
+    fn is_finished(&self) -> bool {
+        self.executions.iter().all(|e| e.status.is_finished())
+    }
+
+    fn finished_at(&self) -> Option<DateTime<Utc>> {
+        if !self.is_finished() {
+            return None;
+        }
+        self.executions.iter().filter_map(|e| e.finished_at).max().or(Some(self.created_at))
+    }
+}
+
+/// Batches of manual runs by id.
+#[derive(Clone, Default)]
+pub struct BatchRegistry {
+    batches: Arc<Mutex<HashMap<String, Batch>>>,
+}
+
+impl BatchRegistry {
+    /// Register a batch of queued executions, forgetting batches finished
+    /// more than [`BATCH_RETENTION_HOURS`] ago.
+    fn insert(&self, batch_id: &str, workflow_id: &str, execution_ids: &[String]) {
+        let now = Utc::now();
+        let cutoff = now - Duration::hours(BATCH_RETENTION_HOURS);
+        let mut batches = self.batches.lock().unwrap();
+        batches.retain(|_, batch| !batch.finished_at().is_some_and(|at| at <= cutoff));
+        let executions = execution_ids
+            .iter()
+            .map(|id| BatchExecution {
+                id: id.clone(),
+                status: ExecutionStatus::New,
+                error: None,
+                finished_at: None,
+            })
+            .collect();
+        batches.insert(
+            batch_id.to_string(),
+            Batch {
+                workflow_id: workflow_id.to_string(),
+                created_at: now,
+                executions,
+            },
+        );
+    }
+
+    fn update(&self, batch_id: &str, index: usize, status: ExecutionStatus, error: Option<String>) {
+        let mut batches = self.batches.lock().unwrap();
+        let Some(execution) = batches
+            .get_mut(batch_id)
+            .and_then(|batch| batch.executions.get_mut(index))
+        else {
+            return;
+        };
+        execution.status = status;
+        execution.error = error;
+        if status.is_finished() {
+            execution.finished_at = Some(Utc::now());
+        }
+    }
+
+    /// Current state of a batch.
+    pub fn status(&self, batch_id: &str) -> Option<BatchStatusResponse> {
+        let batches = self.batches.lock().unwrap();
+        batches.get(batch_id).map(|batch| BatchStatusResponse::new(batch_id, batch))
+    }
+}
+
+/// Declared inputs of a workflow.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowInputsResponse {
+    pub workflow_id: String,
+    /// Manual trigger runs start from, if the workflow has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_node: Option<String>,
+    pub inputs: Vec<WorkflowInput>,
+    /// JSON Schema of the `inputs` object of a run request.
+    pub schema: Value,
+}
+
+/// Router serving the inputs and run endpoints.
+pub fn create_run_router(state: WebhookState) -> Router {
+    Router::new()
+        .route("/api/v1/workflows/:id/inputs", get(get_workflow_inputs))
+        .route("/api/v1/workflows/:id/run", post(run_workflow))
+        .route("/api/v1/workflows/:id/run-batch", post(run_batch))
+        .route("/api/v1/batches/:id", get(get_batch))
+        .with_state(state)
+}
+
+/// GET /workflows/:id/inputs - Parameters a manual run accepts.
+pub async fn get_workflow_inputs(
+    State(state): State<WebhookState>,
+    Path(id): Path<String>,
+) -> Result<Json<WorkflowInputsResponse>, ApiError> {
+    let workflow = load_workflow(&state, &id).await?;
+    let inputs = &workflow.settings.inputs;
+    Ok(Json(WorkflowInputsResponse {
+        workflow_id: workflow.id.clone(),
+        start_node: manual_trigger(&workflow).map(str::to_string),
+        schema: input_schema(inputs),
+        inputs: inputs.clone(),
+    }))
+}
+
+/// POST /workflows/:id/run - Run a workflow with input parameters.
+pub async fn run_workflow(
+    State(state): State<WebhookState>,
+    Path(id): Path<String>,
+    Json(request): Json<RunWorkflowRequest>,
+) -> Result<(StatusCode, Json<ExecutionResponse>), ApiError> {
+    let mut workflow = load_workflow(&state, &id).await?;
+    apply_mocks(&mut workflow, request.mocks)?;
+    let start = manual_trigger(&workflow).ok_or_else(|| no_manual_trigger(&id))?;
+    let item = trigger_item(&workflow, &request.inputs).map_err(|message| ApiError {
+        code: 422,
+        message,
+    })?;
+
+    let (execution_id, run) = state
+        .execute_manual(&workflow, start, item)
+        .await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?;
+    Ok((
+        StatusCode::CREATED,
+        Json(ExecutionResponse::from_run(execution_id, &run, Some(workflow.id.clone()))),
+    ))
+}
+
+/// POST /workflows/:id/run-batch - Run a workflow once per payload.
+pub async fn run_batch(
+    State(state): State<WebhookState>,
+    Path(id): Path<String>,
+    Json(request): Json<RunBatchRequest>,
+) -> Result<(StatusCode, Json<RunBatchResponse>), ApiError> {
+    let size = request.payloads.len();
+    if size == 0 || size > MAX_BATCH_SIZE {
+        return Err(ApiError {
+            code: 422,
+            message: format!("A batch holds 1 to {} payloads, got {}", MAX_BATCH_SIZE, size),
+        });
+    }
+    let mut workflow = load_workflow(&state, &id).await?;
+    apply_mocks(&mut workflow, request.mocks)?;
+    let start = manual_trigger(&workflow).ok_or_else(|| no_manual_trigger(&id))?.to_string();
+
+    let items = request
+        .payloads
+        .iter()
+        .enumerate()
+        .map(|(index, inputs)| {
+            trigger_item(&workflow, inputs).map_err(|message| ApiError {
+                code: 422,
+                message: format!("Payload {}: {}", index, message),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let batch_id = Uuid::new_v4().to_string();
+    let execution_ids: Vec<String> = (0..size).map(|_| Uuid::new_v4().to_string()).collect();
+    let workflow_id = workflow.id.clone();
+    state.batches.insert(&batch_id, &workflow_id, &execution_ids);
+
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_BATCH_CONCURRENCY).max(1);
+    let jobs: Vec<_> = execution_ids.iter().cloned().zip(items).enumerate().collect();
+    let (runner, batch) = (state.clone(), batch_id.clone());
+    tokio::spawn(async move {
+        let (state, workflow, start) = (&runner, &workflow, &start);
+        futures::stream::iter(jobs)
+            .for_each_concurrent(concurrency, |(index, (execution_id, item))| {
+                let batch = &batch;
+                async move {
+                    state.batches.update(batch, index, ExecutionStatus::Running, None);
+                    let (status, error) =
+                        match state.execute_manual_as(execution_id, workflow, start, item).await {
+                            Ok((_, run)) => {
+                                let error = run.data.result_data.error.map(|e| e.message);
+                                (run.status, error)
+                            }
+                            Err(e) => (ExecutionStatus::Error, Some(e.to_string())),
+                        };
+                    state.batches.update(batch, index, status, error);
+                }
+            })
+            .await;
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(RunBatchResponse {
+            batch_id,
+            workflow_id,
+            execution_ids,
+        }),
+    ))
+}
+
+/// GET /batches/:id - Progress and results of a batch.
+pub async fn get_batch(
+    State(state): State<WebhookState>,
+    Path(id): Path<String>,
+) -> Result<Json<BatchStatusResponse>, ApiError> {
+    state.batches.status(&id).map(Json).ok_or_else(|| ApiError {
+        code: 404,
+        message: format!("Batch {} not found", id),
+    })
+}
+
+/// Pin `mocks` on top of the workflow's pin data.
+fn apply_mocks(workflow: &mut Workflow, mocks: PinData) -> Result<(), ApiError> {
+    if let Some(node) = mocks.keys().find(|node| workflow.get_node(node).is_none()) {
+        return Err(ApiError {
+            code: 422,
+            message: format!("Cannot mock unknown node {}", node),
+        });
+    }
+    workflow.pin_data.get_or_insert_with(Default::default).extend(mocks);
+    Ok(())
+}
+
+/// The manual trigger item for `inputs`, or why they are invalid.
+fn trigger_item(
+    workflow: &Workflow,
+    inputs: &Map<String, Value>,
+) -> Result<NodeExecutionData, String> {
+    let json = resolve_inputs(&workflow.settings.inputs, inputs).map_err(|errors| {
+        let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        format!("Invalid inputs: {}", errors.join("; "))
+    })?;
+    NodeExecutionData::from_json_value(Value::Object(json)).map_err(|e| e.to_string())
+}
+
+fn no_manual_trigger(id: &str) -> ApiError {
+    ApiError {
+        code: 400,
+        message: format!("Workflow {} has no manual trigger", id),
+    }
+}
+
+async fn load_workflow(state: &WebhookState, id: &str) -> Result<Workflow, ApiError> {
+    state
+        .workflows
+        .get_workflow(id)
+        .await
+        .map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?
+        .ok_or_else(|| ApiError {
+            code: 404,
+            message: format!("Workflow {} not found", id),
+        })
+}
+
+fn manual_trigger(workflow: &Workflow) -> Option<&str> {
+    workflow
+        .nodes
+        .iter()
+        .find(|node| node.node_type == MANUAL_TRIGGER_NODE_TYPE && !node.disabled)
+        .map(|node| node.name.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::api::ExecutionStore;
+    use n8n_core::{MemoryWorkflowStorage, RuntimeConfig, WorkflowEngine};
+    use n8n_workflow::{Node, WorkflowBuilder, WorkflowInputType};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_run_maps_inputs_into_trigger_item() {
+        let mut workflow = WorkflowBuilder::new("Backfill")
+            .node(Node::new("Start", MANUAL_TRIGGER_NODE_TYPE))
+            .node(Node::new("Done", "n8n-nodes-base.noOp"))
+            .connect("Start", "Done", 0, 0)
+            .unwrap()
+            .build()
+            .unwrap();
+        workflow.settings.inputs = vec![WorkflowInput {
+            name: "days".to_string(),
+            input_type: WorkflowInputType::Number,
+            required: true,
+            default: None,
+            description: None,
+            options: vec![],
+        }];
+        let workflows = Arc::new(MemoryWorkflowStorage::new());
+        workflows.save_workflow(&workflow).await.unwrap();
+        let state = WebhookState::new(
+            workflows,
+            Arc::new(ExecutionStore::new()),
+            Arc::new(WorkflowEngine::new(RuntimeConfig::default())),
+        );
+
+        let request = RunWorkflowRequest {
+            inputs: json!({ "days": "7" }).as_object().unwrap().clone(),
+            ..Default::default()
+        };
+        let (status, Json(execution)) =
+            run_workflow(State(state.clone()), Path(workflow.id.clone()), Json(request))
+                .await
+                .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        let run_data = &execution.data.result_data.run_data;
+        assert_eq!(run_data["Done"][0]["data"]["main"][0][0]["json"]["days"], 7);
+
+        let missing = run_workflow(State(state), Path(workflow.id), Json(Default::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(missing.code, 422);
+    }
+
+    #[tokio::test]
+    async fn test_mocked_nodes_output_their_items() {
+        let workflow = WorkflowBuilder::new("Sync")
+            .node(Node::new("Start", MANUAL_TRIGGER_NODE_TYPE))
+            .node(Node::new("Fetch", "n8n-nodes-base.httpRequest"))
+            .connect("Start", "Fetch", 0, 0)
+            .unwrap()
+            .build()
+            .unwrap();
+        let workflows = Arc::new(MemoryWorkflowStorage::new());
+        workflows.save_workflow(&workflow).await.unwrap();
+        let state = WebhookState::new(
+            workflows.clone(),
+            Arc::new(ExecutionStore::new()),
+            Arc::new(WorkflowEngine::new(RuntimeConfig::default())),
+        );
+
+        let request: RunWorkflowRequest =
+            serde_json::from_value(json!({ "mocks": { "Fetch": [{ "json": { "id": 42 } }] } }))
+                .unwrap();
+        let (_, Json(execution)) =
+            run_workflow(State(state.clone()), Path(workflow.id.clone()), Json(request))
+                .await
+                .unwrap();
+        let fetch = &execution.data.result_data.run_data["Fetch"][0];
+        assert_eq!(fetch["data"]["main"][0][0]["json"]["id"], 42);
+        assert_eq!(fetch["metadata"]["pinned"], true);
+        let stored = workflows.get_workflow(&workflow.id).await.unwrap().unwrap();
+        assert!(stored.pin_data.is_none());
+
+        let request: RunWorkflowRequest =
+            serde_json::from_value(json!({ "mocks": { "Missing": [] } })).unwrap();
+        let unknown = run_workflow(State(state), Path(workflow.id), Json(request))
+            .await
+            .unwrap_err();
+        assert_eq!(unknown.code, 422);
+    }
+
+    #[tokio::test]
+    async fn test_batch_runs_once_per_payload() {
+        let mut workflow = WorkflowBuilder::new("Import")
+            .node(Node::new("Start", MANUAL_TRIGGER_NODE_TYPE))
+            .node(Node::new("Done", "n8n-nodes-base.noOp"))
+            .connect("Start", "Done", 0, 0)
+            .unwrap()
+            .build()
+            .unwrap();
+        workflow.settings.inputs = vec![WorkflowInput {
+            name: "row".to_string(),
+            input_type: WorkflowInputType::Number,
+            required: true,
+            default: None,
+            description: None,
+            options: vec![],
+        }];
+        let workflows = Arc::new(MemoryWorkflowStorage::new());
+        workflows.save_workflow(&workflow).await.unwrap();
+        let executions = Arc::new(ExecutionStore::new());
+        let state = WebhookState::new(
+            workflows,
+            executions.clone(),
+            Arc::new(WorkflowEngine::new(RuntimeConfig::default())),
+        );
+
+        let request: RunBatchRequest =
+            serde_json::from_value(json!({ "payloads": [{ "row": 1 }, { "row": 2 }, {}] }))
+                .unwrap();
+        let invalid = run_batch(State(state.clone()), Path(workflow.id.clone()), Json(request))
+            .await
+            .unwrap_err();
+        assert_eq!(invalid.code, 422);
+        assert!(invalid.message.starts_with("Payload 2:"));
+
+        let request: RunBatchRequest =
+            serde_json::from_value(json!({ "payloads": [{ "row": 1 }, { "row": 2 }] })).unwrap();
+        let (status, Json(batch)) =
+            run_batch(State(state.clone()), Path(workflow.id.clone()), Json(request))
+                .await
+                .unwrap();
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(batch.execution_ids.len(), 2);
+
+        let mut finished = None;
+        for _ in 0..100 {
+            let Json(progress) = get_batch(State(state.clone()), Path(batch.batch_id.clone()))
+                .await
+                .unwrap();
+            if progress.finished {
+                finished = Some(progress);
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let finished = finished.expect("batch did not finish");
+        assert_eq!(finished.counts.success, 2);
+        for (row, id) in batch.execution_ids.iter().enumerate() {
+            let (run, _) = executions.get_execution(id).await.unwrap().unwrap();
+            let done = serde_json::to_value(&run.data.result_data.run_data["Done"]).unwrap();
+            assert_eq!(done[0]["data"]["main"][0][0]["json"]["row"], row + 1);
+        }
+    }
+}
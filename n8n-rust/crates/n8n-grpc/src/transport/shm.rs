@@ -0,0 +1,442 @@
+//! Shared-memory transport for co-located processes.
+//!
+//! Crew and ladybug processes on the same host hand over Arrow data without
+//! TCP or serialization. Requests and replies are small JSON frames on a
+//! unix domain socket; bulk data travels as a sealed memfd
+//! [`SharedSegment`] whose file descriptor is passed with the frame
+//! (`SCM_RIGHTS`). The receiver maps the segment and reads record batches in
+//! place.
+//!
+//! A frame is a 4-byte big-endian length followed by a JSON [`ShmFrame`];
+//! a frame with `segment: true` carries exactly one descriptor. Methods:
+//!
+//! - `ping`
+//! - `execution.runData` `{executionId, nodeNames?}`: per-node task
+//!   summaries, replied with a segment;
+//! - `execution.nodeOutput` `{executionId, nodeName, runIndex?}`: a node's
+//!   output items, replied with a segment;
+//! - `data.put` `{executionId}` with a segment: stores the batches and
+//!   replies `{rowsWritten}`.
+//!
+//! The [`FormatNegotiator`](super::FormatNegotiator) only offers this
+//! transport (`shm`, endpoint `unix://<path>`) when it is enabled and the
+//! client declares `colocated` in its data hints. Access is governed by the
+//! socket file's permissions. Linux only.
+
+use n8n_arrow::SharedSegment;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io;
+use std::os::fd::{AsFd, AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use tokio::io::Interest;
+use tokio::net::{UnixListener, UnixStream};
+
+use crate::services::ArrowDataService;
+
+/// Largest JSON frame accepted; bulk data belongs in a segment.
+const MAX_FRAME: usize = 1024 * 1024;
+
+/// One request or reply on the shared-memory socket.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShmFrame {
+    /// Request ID, echoed in the reply.
+    pub id: String,
+    /// Method name; empty in replies.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub params: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Whether a segment descriptor comes with the frame.
+    #[serde(default)]
+    pub segment: bool,
+}
+
+/// Bind the socket at `path`, replacing a stale one, readable and writable
+/// by the owner and group only.
+pub fn bind_shm(path: impl AsRef<Path>) -> io::Result<UnixListener> {
+    let path = path.as_ref();
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o660))?;
+    Ok(listener)
+}
+
+/// Accept shared-memory connections on `listener` until accepting fails.
+pub async fn serve_shm(data: ArrowDataService, listener: UnixListener) -> io::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let data = data.clone();
+        tokio::spawn(async move {
+            if let Err(e) = session(&data, &stream).await {
+                tracing::debug!(error = %e, "Shared-memory session ended");
+            }
+        });
+    }
+}
+
+async fn session(data: &ArrowDataService, stream: &UnixStream) -> io::Result<()> {
+    while let Some((request, segment)) = recv_frame(stream).await? {
+        let (reply, segment) = match handle(data, &request, segment).await {
+            Ok((result, segment)) => (
+                ShmFrame {
+                    id: request.id,
+                    result: Some(result),
+                    segment: segment.is_some(),
+                    ..Default::default()
+                },
+                segment,
+            ),
+            Err(message) => (
+                ShmFrame {
+                    id: request.id,
+                    error: Some(message),
+                    ..Default::default()
+                },
+                None,
+            ),
+        };
+        send_frame(stream, &reply, segment.as_ref()).await?;
+    }
+    Ok(())
+}
+
+async fn handle(
+    data: &ArrowDataService,
+    request: &ShmFrame,
+    segment: Option<SharedSegment>,
+) -> Result<(Value, Option<SharedSegment>), String> {
+    let param = |name: &str| request.params.get(name).and_then(Value::as_str);
+    let execution_id = || param("executionId").ok_or("executionId is required");
+
+    let batch = match request.method.as_str() {
+        "ping" => return Ok((json!({ "pong": true }), None)),
+        "execution.runData" => {
+            let node_names: Option<Vec<String>> = request
+                .params
+                .get("nodeNames")
+                .map(|v| serde_json::from_value(v.clone()))
+                .transpose()
+                .map_err(|e| format!("nodeNames: {}", e))?;
+            data.run_data_batch(execution_id()?, node_names.as_deref())
+                .await
+                .map_err(|s| s.message().to_string())?
+        }
+        "execution.nodeOutput" => {
+            let node_name = param("nodeName").ok_or("nodeName is required")?;
+            let run_index = request
+                .params
+                .get("runIndex")
+                .and_then(Value::as_u64)
+                .map(|i| i as usize);
+            data.node_output_batch(execution_id()?, node_name, run_index)
+                .await
+                .map_err(|s| s.message().to_string())?
+        }
+        "data.put" => {
+            let segment = segment.ok_or("data.put requires a segment")?;
+            let batches = tokio::task::spawn_blocking(move || segment.read_batches())
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| e.to_string())?;
+            let result = data
+                .put_batches(execution_id()?, &batches)
+                .await
+                .map_err(|s| s.message().to_string())?;
+            return Ok((json!({ "rowsWritten": result.rows_written }), None));
+        }
+        other => return Err(format!("Unknown method: {}", other)),
+    };
+
+    let rows = batch.num_rows();
+    let segment = tokio::task::spawn_blocking(move || {
+        SharedSegment::from_batches("n8n-shm", &batch.schema(), &[batch])
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+    Ok((json!({ "rows": rows, "bytes": segment.len() }), Some(segment)))
+}
+
+/// Client side of the shared-memory transport.
+pub struct ShmClient {
+    stream: UnixStream,
+    next_id: u64,
+}
+
+impl ShmClient {
+    pub async fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::from_stream(UnixStream::connect(path).await?))
+    }
+
+    pub fn from_stream(stream: UnixStream) -> Self {
+        Self { stream, next_id: 0 }
+    }
+
+    /// Call `method`, optionally handing over `segment`. Returns the result
+    /// and the segment of the reply, if any.
+    pub async fn call(
+        &mut self,
+        method: &str,
+        params: Value,
+        segment: Option<&SharedSegment>,
+    ) -> io::Result<(Value, Option<SharedSegment>)> {
+        self.next_id += 1;
+        let request = ShmFrame {
+            id: self.next_id.to_string(),
+            method: method.to_string(),
+            params,
+            segment: segment.is_some(),
+            ..Default::default()
+        };
+        send_frame(&self.stream, &request, segment).await?;
+
+        let (reply, segment) = recv_frame(&self.stream)
+            .await?
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+        if reply.id != request.id {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Reply to another request"));
+        }
+        match reply.error {
+            Some(message) => Err(io::Error::other(message)),
+            None => Ok((reply.result.unwrap_or(Value::Null), segment)),
+        }
+    }
+}
+
+/// Send `frame`, passing `segment`'s descriptor with its first byte.
+pub async fn send_frame(
+    stream: &UnixStream,
+    frame: &ShmFrame,
+    segment: Option<&SharedSegment>,
+) -> io::Result<()> {
+    let body = serde_json::to_vec(frame)?;
+    if body.len() > MAX_FRAME {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Frame too large"));
+    }
+    let mut bytes = (body.len() as u32).to_be_bytes().to_vec();
+    bytes.extend_from_slice(&body);
+
+    let mut fd = segment.map(|s| s.as_fd().as_raw_fd());
+    let mut sent = 0;
+    while sent < bytes.len() {
+        let n = stream
+            .async_io(Interest::WRITABLE, || {
+                send_with_fd(stream.as_raw_fd(), &bytes[sent..], fd)
+            })
+            .await?;
+        fd = None;
+        sent += n;
+    }
+    Ok(())
+}
+
+/// Receive the next frame and its segment; `None` when the peer closed the
+/// connection between frames.
+pub async fn recv_frame(
+    stream: &UnixStream,
+) -> io::Result<Option<(ShmFrame, Option<SharedSegment>)>> {
+    let mut fds = Vec::new();
+    let mut header = [0u8; 4];
+    if !read_exact(stream, &mut header, &mut fds).await? {
+        return Ok(None);
+    }
+    let len = u32::from_be_bytes(header) as usize;
+    if len > MAX_FRAME {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Frame too large"));
+    }
+    let mut body = vec![0u8; len];
+    if !read_exact(stream, &mut body, &mut fds).await? {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    let frame: ShmFrame = serde_json::from_slice(&body)?;
+
+    // Extra descriptors are closed when `fds` is dropped.
+    let segment = match (frame.segment, fds.into_iter().next()) {
+        (true, Some(fd)) => Some(
+            SharedSegment::from_fd(fd)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?,
+        ),
+        (true, None) => {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Segment descriptor missing"))
+        }
+        (false, _) => None,
+    };
+    Ok(Some((frame, segment)))
+}
+
+/// Fill `buf`, collecting passed descriptors. `false` on end of stream
+/// before the first byte.
+async fn read_exact(
+    stream: &UnixStream,
+    buf: &mut [u8],
+    fds: &mut Vec<OwnedFd>,
+) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let n = stream
+            .async_io(Interest::READABLE, || {
+                recv_with_fds(stream.as_raw_fd(), &mut buf[filled..], fds)
+            })
+            .await?;
+        if n == 0 {
+            if filled == 0 {
+                return Ok(false);
+            }
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        filled += n;
+    }
+    Ok(true)
+}
+
+fn send_with_fd(socket: RawFd, bytes: &[u8], fd: Option<RawFd>) -> io::Result<usize> {
+    let mut iov = libc::iovec {
+        iov_base: bytes.as_ptr() as *mut libc::c_void,
+        iov_len: bytes.len(),
+    };
+    // u64 elements keep the control buffer aligned for cmsghdr.
+    let mut control = [0u64; 4];
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+
+    if let Some(fd) = fd {
+        let fd_len = std::mem::size_of::<RawFd>() as u32;
+        msg.msg_control = control.as_mut_ptr().cast();
+        msg.msg_controllen = unsafe { libc::CMSG_SPACE(fd_len) } as _;
+        // SAFETY: the control buffer holds one cmsghdr with one descriptor.
+        unsafe {
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(fd_len) as _;
+            std::ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>(), fd);
+        }
+    }
+
+    let n = unsafe { libc::sendmsg(socket, &msg, libc::MSG_NOSIGNAL) };
+    if n < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(n as usize)
+    }
+}
+
+fn recv_with_fds(socket: RawFd, buf: &mut [u8], fds: &mut Vec<OwnedFd>) -> io::Result<usize> {
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr().cast(),
+        iov_len: buf.len(),
+    };
+    let mut control = [0u64; 8];
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = std::mem::size_of_val(&control) as _;
+
+    let n = unsafe { libc::recvmsg(socket, &mut msg, libc::MSG_CMSG_CLOEXEC) };
+    if n < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: walks the control messages the kernel just wrote.
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                let data = libc::CMSG_DATA(cmsg).cast::<RawFd>();
+                let count = ((*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize)
+                    / std::mem::size_of::<RawFd>();
+                for i in 0..count {
+                    fds.push(OwnedFd::from_raw_fd(std::ptr::read_unaligned(data.add(i))));
+                }
+            }
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+    }
+    if msg.msg_flags & libc::MSG_CTRUNC != 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Descriptors truncated"));
+    }
+    Ok(n as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::{DataHints, FormatNegotiator, NegotiatorConfig};
+    use arrow_array::{Int64Array, RecordBatch};
+    use n8n_core::{MemoryExecutionStorage, MemoryWorkflowStorage};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_segments_pass_both_ways() {
+        let data = ArrowDataService::new(
+            Arc::new(MemoryWorkflowStorage::new()),
+            Arc::new(MemoryExecutionStorage::new()),
+        );
+        let (client, server) = UnixStream::pair().unwrap();
+        tokio::spawn(async move { session(&data, &server).await });
+        let mut client = ShmClient::from_stream(client);
+
+        let (pong, segment) = client.call("ping", Value::Null, None).await.unwrap();
+        assert_eq!(pong, json!({ "pong": true }));
+        assert!(segment.is_none());
+
+        let batch = RecordBatch::try_from_iter([(
+            "n",
+            Arc::new(Int64Array::from_iter_values(0..10_000)) as _,
+        )])
+        .unwrap();
+        let segment = SharedSegment::from_batches("test", &batch.schema(), &[batch]).unwrap();
+        let (result, _) = client
+            .call("data.put", json!({ "executionId": "e1" }), Some(&segment))
+            .await
+            .unwrap();
+        assert_eq!(result["rowsWritten"], 10_000);
+
+        let err = client
+            .call("execution.runData", json!({ "executionId": "missing" }), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not found"));
+        assert!(client.call("data.put", json!({ "executionId": "e1" }), None).await.is_err());
+    }
+
+    #[test]
+    fn test_negotiator_offers_shm_only_to_colocated_clients() {
+        let negotiator = FormatNegotiator::with_config(NegotiatorConfig {
+            shared_memory_socket: Some("/run/n8n/shm.sock".into()),
+            ..Default::default()
+        });
+        let formats = vec!["arrow-flight".to_string(), "arrow-ipc".to_string()];
+        let transports = vec!["flight".to_string(), "shm".to_string()];
+        let mut hints = DataHints {
+            size_bytes: Some(4 << 30),
+            ..Default::default()
+        };
+
+        let (_, transport) = negotiator.negotiate(&formats, &transports, &hints);
+        assert_eq!(transport, "flight");
+
+        hints.colocated = Some(true);
+        let (format, transport) = negotiator.negotiate(&formats, &transports, &hints);
+        assert_eq!((format.as_str(), transport.as_str()), ("arrow-ipc", "shm"));
+        assert_eq!(
+            negotiator.shared_memory_endpoint().as_deref(),
+            Some("unix:///run/n8n/shm.sock")
+        );
+
+        let disabled = FormatNegotiator::new();
+        let (_, transport) = disabled.negotiate(&formats, &transports, &hints);
+        assert_eq!(transport, "flight");
+    }
+}
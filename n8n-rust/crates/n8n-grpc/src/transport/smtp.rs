@@ -0,0 +1,825 @@
+//! Inbound email trigger.
+//!
+//! An embedded SMTP listener accepts mail for active workflows whose
+//! `n8n-nodes-base.smtpTrigger` node subscribes to a recipient address
+//! pattern (`address`, e.g. `*@orders.example.com`; `*` matches any run of
+//! characters, case-insensitively). Recipients no workflow subscribes to
+//! are refused with `550`. Each accepted message starts every subscribed
+//! workflow from its trigger node with one item, like a webhook request:
+//!
+//! - `from`, `to`, `cc`, `subject`, `date`, `messageId`, `text`, `html`
+//!   and `headers` (decoded, lower-case names);
+//! - `envelope.mailFrom` and `envelope.rcptTo` as given over SMTP;
+//! - attachments as binary data `attachment_0`, `attachment_1`, ..., with
+//!   those over [`SmtpConfig::max_attachment_bytes`] dropped and named in
+//!   `skippedAttachments`.
+//!
+//! Messages over [`SmtpConfig::max_message_bytes`] are refused with `552`.
+//! Executions count against the webhook admission limit; when it is
+//! reached the message is refused with a temporary `451`, so the sending
+//! server retries later. Text in charsets other than UTF-8 is decoded
+//! lossily.
+//!
+//! The listener speaks plain SMTP without TLS or authentication. Expose it
+//! only behind a mail server that relays the subscribed domains to it.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use n8n_core::WorkflowStorage;
+use n8n_workflow::{BinaryData, Node, NodeExecutionData, NodeParameterValue, Workflow};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
+use tokio::net::TcpListener;
+
+use super::webhook::{Pending, WebhookState};
+
+/// Node type of the email trigger.
+pub const SMTP_TRIGGER_NODE_TYPE: &str = "n8n-nodes-base.smtpTrigger";
+
+/// Longest command or message line accepted.
+const MAX_LINE: usize = 64 * 1024;
+
+/// Deepest nesting of multipart bodies that is unpacked.
+const MAX_DEPTH: usize = 8;
+
+/// SMTP listener settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmtpConfig {
+    pub enabled: bool,
+    pub addr: String,
+    /// Name announced in the greeting.
+    pub hostname: String,
+    /// Largest accepted message, headers and attachments included.
+    pub max_message_bytes: usize,
+    /// Largest attachment passed to the workflow; larger ones are dropped.
+    pub max_attachment_bytes: usize,
+    /// Most recipients of one message.
+    pub max_recipients: usize,
+    /// Seconds a connection may stay silent before it is closed.
+    pub idle_timeout_secs: u64,
+}
+
+impl Default for SmtpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            addr: "0.0.0.0:2525".to_string(),
+            hostname: "n8n-rust".to_string(),
+            max_message_bytes: 10 * 1024 * 1024,
+            max_attachment_bytes: 5 * 1024 * 1024,
+            max_recipients: 100,
+            idle_timeout_secs: 300,
+        }
+    }
+}
+
+/// Sender and recipients given over SMTP.
+#[derive(Debug, Clone, Default)]
+struct Envelope {
+    mail_from: String,
+    rcpt_to: Vec<String>,
+}
+
+/// Email trigger state.
+#[derive(Clone)]
+pub struct SmtpState {
+    /// Workflows, storage and admission shared with webhooks.
+    pub webhooks: WebhookState,
+    pub config: SmtpConfig,
+}
+
+impl SmtpState {
+    pub fn new(webhooks: WebhookState, config: SmtpConfig) -> Self {
+        Self { webhooks, config }
+    }
+
+    /// Active workflows, with their trigger node, subscribed to `address`.
+    /// Paused triggers are left out.
+    async fn subscribers(&self, address: &str) -> Result<Vec<(Workflow, String)>, String> {
+        let workflows = self.webhooks.workflows.list_workflows().await.map_err(|e| e.to_string())?;
+        let health = self.webhooks.engine.trigger_health();
+        Ok(workflows
+            .into_iter()
+            .filter(|w| w.active)
+            .flat_map(|workflow| {
+                let nodes: Vec<String> = workflow
+                    .nodes
+                    .iter()
+                    .filter(|node| subscribes(node, address))
+                    .filter(|node| !health.is_paused(&workflow.id, &node.name))
+                    .map(|node| node.name.clone())
+                    .collect();
+                nodes.into_iter().map(move |node| (workflow.clone(), node))
+            })
+            .collect())
+    }
+
+    /// Start every workflow subscribed to a recipient of `message`.
+    /// Returns how many executions were started, or the SMTP reply
+    /// refusing the message.
+    async fn deliver(&self, envelope: &Envelope, message: &[u8]) -> Result<usize, String> {
+        let mut targets: Vec<(Workflow, String)> = Vec::new();
+        for recipient in &envelope.rcpt_to {
+            let subscribers = self.subscribers(recipient).await.map_err(|e| {
+                tracing::warn!(error = %e, "Email trigger lookup failed");
+                "451 4.3.0 Temporary failure".to_string()
+            })?;
+            for (workflow, node) in subscribers {
+                if !targets.iter().any(|(w, n)| w.id == workflow.id && *n == node) {
+                    targets.push((workflow, node));
+                }
+            }
+        }
+
+        // All or nothing, so a retried message does not start some
+        // workflows twice.
+        let admitted: Option<Vec<Pending>> =
+            targets.iter().map(|_| self.webhooks.admit()).collect();
+        let Some(admitted) = admitted else {
+            tracing::warn!(pending = self.webhooks.pending(), "Email refused under backpressure");
+            return Err("451 4.3.2 Too many pending executions, try again later".to_string());
+        };
+
+        let email = parse_email(message);
+        let item = email_item(envelope, email, self.config.max_attachment_bytes)
+            .map_err(|e| format!("554 5.6.0 {}", e))?;
+        let started = targets.len();
+        for ((workflow, node), pending) in targets.into_iter().zip(admitted) {
+            let webhooks = self.webhooks.clone();
+            let item = item.clone();
+            tokio::spawn(async move {
+                let _pending = pending;
+                match webhooks.execute(&workflow, &node, item).await {
+                    Ok((execution_id, _)) => {
+                        tracing::info!(workflow = %workflow.name, %execution_id, "Email started workflow");
+                    }
+                    Err(e) => {
+                        tracing::warn!(workflow = %workflow.name, error = %e, "Email execution failed");
+                    }
+                }
+            });
+        }
+        Ok(started)
+    }
+}
+
+/// Accept SMTP connections on `listener` until accepting fails.
+pub async fn serve_smtp(state: SmtpState, listener: TcpListener) -> io::Result<()> {
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = session(&state, stream).await {
+                tracing::debug!(%peer, error = %e, "SMTP session ended");
+            }
+        });
+    }
+}
+
+/// Run one SMTP session over `stream`.
+async fn session<S>(state: &SmtpState, stream: S) -> io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let config = &state.config;
+    let idle = Duration::from_secs(config.idle_timeout_secs.max(1));
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+    let mut envelope: Option<Envelope> = None;
+
+    reply(&mut writer, &format!("220 {} ESMTP n8n-rust", config.hostname)).await?;
+    while let Some(line) = read_line(&mut reader, idle).await? {
+        let line = String::from_utf8_lossy(&line);
+        let (verb, arg) = match line.split_once(' ') {
+            Some((verb, arg)) => (verb, arg.trim()),
+            None => (line.trim(), ""),
+        };
+
+        let response = match verb.to_ascii_uppercase().as_str() {
+            "HELO" => format!("250 {}", config.hostname),
+            "EHLO" => format!(
+                "250-{}\r\n250-8BITMIME\r\n250 SIZE {}",
+                config.hostname, config.max_message_bytes
+            ),
+            "MAIL" => match parse_path(arg, "FROM:") {
+                Some((_, params)) if declared_size(params) > Some(config.max_message_bytes) => {
+                    format!("552 5.3.4 Message exceeds {} bytes", config.max_message_bytes)
+                }
+                Some((mail_from, _)) => {
+                    envelope = Some(Envelope {
+                        mail_from,
+                        rcpt_to: Vec::new(),
+                    });
+                    "250 2.1.0 OK".to_string()
+                }
+                None => "501 5.5.4 Syntax: MAIL FROM:<address>".to_string(),
+            },
+            "RCPT" => match (envelope.as_mut(), parse_path(arg, "TO:")) {
+                (None, _) => "503 5.5.1 MAIL first".to_string(),
+                (Some(_), None) => "501 5.5.4 Syntax: RCPT TO:<address>".to_string(),
+                (Some(_), Some((to, _))) if to.is_empty() => {
+                    "501 5.5.4 Syntax: RCPT TO:<address>".to_string()
+                }
+                (Some(envelope), Some(_)) if envelope.rcpt_to.len() >= config.max_recipients => {
+                    "452 4.5.3 Too many recipients".to_string()
+                }
+                (Some(envelope), Some((to, _))) => match state.subscribers(&to).await {
+                    Ok(subscribers) if subscribers.is_empty() => {
+                        format!("550 5.1.1 No workflow subscribed to <{}>", to)
+                    }
+                    Ok(_) => {
+                        envelope.rcpt_to.push(to);
+                        "250 2.1.5 OK".to_string()
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Email trigger lookup failed");
+                        "451 4.3.0 Temporary failure".to_string()
+                    }
+                },
+            },
+            "DATA" => match envelope.take() {
+                Some(ready) if !ready.rcpt_to.is_empty() => {
+                    reply(&mut writer, "354 End data with <CR><LF>.<CR><LF>").await?;
+                    let message = read_data(&mut reader, idle, config.max_message_bytes).await?;
+                    match message {
+                        None => {
+                            format!("552 5.3.4 Message exceeds {} bytes", config.max_message_bytes)
+                        }
+                        Some(message) => match state.deliver(&ready, &message).await {
+                            Ok(started) => format!("250 2.0.0 OK ({} started)", started),
+                            Err(response) => response,
+                        },
+                    }
+                }
+                pending => {
+                    envelope = pending;
+                    "503 5.5.1 RCPT first".to_string()
+                }
+            },
+            "RSET" => {
+                envelope = None;
+                "250 2.0.0 OK".to_string()
+            }
+            "NOOP" => "250 2.0.0 OK".to_string(),
+            "VRFY" => "252 2.5.2 Cannot verify, send the message".to_string(),
+            "QUIT" => {
+                reply(&mut writer, "221 2.0.0 Bye").await?;
+                return Ok(());
+            }
+            _ => "502 5.5.2 Command not recognized".to_string(),
+        };
+        reply(&mut writer, &response).await?;
+    }
+    Ok(())
+}
+
+async fn reply<W: AsyncWrite + Unpin>(writer: &mut W, response: &str) -> io::Result<()> {
+    writer.write_all(response.as_bytes()).await?;
+    writer.write_all(b"\r\n").await?;
+    writer.flush().await
+}
+
+/// One line without its line ending, or `None` once the client is gone.
+async fn read_line<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    idle: Duration,
+) -> io::Result<Option<Vec<u8>>> {
+    let mut line = Vec::new();
+    let mut limited = (&mut *reader).take(MAX_LINE as u64);
+    let read = tokio::time::timeout(idle, limited.read_until(b'\n', &mut line))
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "idle timeout"))??;
+    if read == 0 {
+        return Ok(None);
+    }
+    if line.pop() != Some(b'\n') {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "line too long"));
+    }
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    Ok(Some(line))
+}
+
+/// The message of a DATA section, undoing dot stuffing. `None` when it
+/// exceeded `max_bytes`; the rest of it is read and discarded.
+async fn read_data<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    idle: Duration,
+    max_bytes: usize,
+) -> io::Result<Option<Vec<u8>>> {
+    let mut message = Some(Vec::new());
+    loop {
+        let line = read_line(reader, idle)
+            .await?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "closed during DATA"))?;
+        if line == b"." {
+            return Ok(message);
+        }
+        let line = line.strip_prefix(b".").unwrap_or(&line);
+        if let Some(buffer) = &mut message {
+            if buffer.len() + line.len() + 2 > max_bytes {
+                message = None;
+            } else {
+                buffer.extend_from_slice(line);
+                buffer.extend_from_slice(b"\r\n");
+            }
+        }
+    }
+}
+
+/// `FROM:<address> PARAMS` → (`address`, `PARAMS`).
+fn parse_path<'a>(arg: &'a str, keyword: &str) -> Option<(String, &'a str)> {
+    let rest = arg.get(..keyword.len()).filter(|k| k.eq_ignore_ascii_case(keyword))?;
+    let rest = arg[rest.len()..].trim_start().strip_prefix('<')?;
+    let (address, params) = rest.split_once('>')?;
+    Some((address.trim().to_string(), params.trim()))
+}
+
+/// The `SIZE=` parameter of a MAIL command.
+fn declared_size(params: &str) -> Option<usize> {
+    params.split_whitespace().find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.eq_ignore_ascii_case("SIZE").then(|| value.parse().ok()).flatten()
+    })
+}
+
+/// Whether `node` is an enabled email trigger subscribed to `address`.
+fn subscribes(node: &Node, address: &str) -> bool {
+    if node.node_type != SMTP_TRIGGER_NODE_TYPE || node.disabled {
+        return false;
+    }
+    match node.parameters.get("address") {
+        Some(NodeParameterValue::String(pattern)) => address_matches(pattern, address),
+        _ => false,
+    }
+}
+
+/// Case-insensitive match of `address` against a pattern where `*` stands
+/// for any run of characters.
+fn address_matches(pattern: &str, address: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    let address = address.to_ascii_lowercase();
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = address.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// A received message, decoded.
+#[derive(Debug, Default)]
+struct Email {
+    headers: Map<String, Value>,
+    text: Option<String>,
+    html: Option<String>,
+    attachments: Vec<Attachment>,
+}
+
+#[derive(Debug)]
+struct Attachment {
+    file_name: Option<String>,
+    mime_type: String,
+    data: Vec<u8>,
+}
+
+fn parse_email(message: &[u8]) -> Email {
+    let mut email = Email::default();
+    let (headers, body) = split_headers(message);
+    for (name, value) in &headers {
+        email
+            .headers
+            .entry(name.clone())
+            .or_insert_with(|| Value::String(decode_words(value)));
+    }
+    collect_part(&headers, body, &mut email, 0);
+    email
+}
+
+/// Unfolded headers, with lower-case names, and the body of a message or
+/// MIME part.
+fn split_headers(part: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let (head, body) = if let Some(body) = part.strip_prefix(b"\r\n") {
+        (&[][..], body)
+    } else if let Some(i) = find(part, b"\r\n\r\n") {
+        (&part[..i], &part[i + 4..])
+    } else if let Some(i) = find(part, b"\n\n") {
+        (&part[..i], &part[i + 2..])
+    } else {
+        (part, &[][..])
+    };
+
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in String::from_utf8_lossy(head).lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    (headers, body)
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+}
+
+/// `text/plain; charset="utf-8"` → (`text/plain`, `{charset: utf-8}`).
+fn header_params(value: &str) -> (String, HashMap<String, String>) {
+    let mut fields = value.split(';');
+    let main = fields.next().unwrap_or_default().trim().to_ascii_lowercase();
+    let params = fields
+        .filter_map(|field| {
+            let (key, value) = field.split_once('=')?;
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim().trim_matches('"');
+            // RFC 2231 `filename*=utf-8''report.pdf`; percent escapes are kept.
+            match key.strip_suffix('*') {
+                Some(key) => {
+                    let value = value.split_once("''").map_or(value, |(_, v)| v);
+                    Some((key.to_string(), value.to_string()))
+                }
+                None => Some((key, value.to_string())),
+            }
+        })
+        .collect();
+    (main, params)
+}
+
+/// Add a MIME part, and the parts nested in it, to `email`.
+fn collect_part(headers: &[(String, String)], body: &[u8], email: &mut Email, depth: usize) {
+    let (content_type, params) =
+        header_params(header(headers, "content-type").unwrap_or("text/plain"));
+    if content_type.starts_with("multipart/") && depth < MAX_DEPTH {
+        if let Some(boundary) = params.get("boundary") {
+            for part in split_multipart(body, boundary) {
+                let (part_headers, part_body) = split_headers(part);
+                collect_part(&part_headers, part_body, email, depth + 1);
+            }
+            return;
+        }
+    }
+
+    let data = decode_body(body, header(headers, "content-transfer-encoding"));
+    let (disposition, disposition_params) =
+        header_params(header(headers, "content-disposition").unwrap_or("inline"));
+    let file_name = disposition_params
+        .get("filename")
+        .or_else(|| params.get("name"))
+        .map(|name| decode_words(name));
+    let inline = disposition != "attachment" && file_name.is_none();
+
+    match content_type.as_str() {
+        "text/plain" if inline && email.text.is_none() => {
+            email.text = Some(String::from_utf8_lossy(&data).into_owned());
+        }
+        "text/html" if inline && email.html.is_none() => {
+            email.html = Some(String::from_utf8_lossy(&data).into_owned());
+        }
+        _ => email.attachments.push(Attachment {
+            file_name,
+            mime_type: content_type,
+            data,
+        }),
+    }
+}
+
+/// The parts of a multipart body between its `--boundary` lines.
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+    for line in body.split_inclusive(|&b| b == b'\n') {
+        let content = line.trim_ascii_end();
+        if let Some(rest) = content.strip_prefix(delimiter.as_bytes()) {
+            let closing = rest == b"--";
+            if closing || rest.is_empty() {
+                if let Some(start) = start {
+                    // The line break before a delimiter belongs to it.
+                    let part: &[u8] = &body[start..offset];
+                    let part = part.strip_suffix(b"\n").unwrap_or(part);
+                    parts.push(part.strip_suffix(b"\r").unwrap_or(part));
+                }
+                if closing {
+                    return parts;
+                }
+                start = Some(offset + line.len());
+            }
+        }
+        offset += line.len();
+    }
+    // Unterminated multipart: keep the last part.
+    if let Some(start) = start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+fn decode_body(body: &[u8], encoding: Option<&str>) -> Vec<u8> {
+    match encoding.map(|e| e.trim().to_ascii_lowercase()).as_deref() {
+        Some("base64") => {
+            let compact: Vec<u8> =
+                body.iter().copied().filter(|b| !b.is_ascii_whitespace()).collect();
+            BASE64.decode(compact).unwrap_or_else(|_| body.to_vec())
+        }
+        Some("quoted-printable") => decode_quoted_printable(body),
+        _ => body.to_vec(),
+    }
+}
+
+fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let hex = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] == b'=' {
+            match (input.get(i + 1).copied(), input.get(i + 2).copied()) {
+                // Soft line breaks join lines.
+                (Some(b'\r'), Some(b'\n')) => {
+                    i += 3;
+                    continue;
+                }
+                (Some(b'\n'), _) => {
+                    i += 2;
+                    continue;
+                }
+                (Some(high), Some(low)) => {
+                    if let (Some(high), Some(low)) = (hex(high), hex(low)) {
+                        out.push(high << 4 | low);
+                        i += 3;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+        }
+        out.push(input[i]);
+        i += 1;
+    }
+    out
+}
+
+/// Decode RFC 2047 encoded words (`=?utf-8?B?...?=`) in a header value.
+fn decode_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut after_word = false;
+    while let Some(start) = rest.find("=?") {
+        let (before, word) = rest.split_at(start);
+        match decode_word(word) {
+            Some((decoded, len)) => {
+                // Whitespace between adjacent encoded words is dropped.
+                if !(after_word && before.trim().is_empty()) {
+                    out.push_str(before);
+                }
+                out.push_str(&decoded);
+                rest = &word[len..];
+                after_word = true;
+            }
+            None => {
+                out.push_str(before);
+                out.push_str("=?");
+                rest = &word[2..];
+                after_word = false;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The text of the encoded word starting `word`, and its encoded length.
+fn decode_word(word: &str) -> Option<(String, usize)> {
+    let (_charset, inner) = word[2..].split_once('?')?;
+    let (encoding, inner) = inner.split_once('?')?;
+    let end = inner.find("?=")?;
+    let text = &inner[..end];
+    let bytes = match encoding {
+        "B" | "b" => BASE64.decode(text).ok()?,
+        "Q" | "q" => decode_quoted_printable(text.replace('_', " ").as_bytes()),
+        _ => return None,
+    };
+    let len = word.len() - inner.len() + end + 2;
+    Some((String::from_utf8_lossy(&bytes).into_owned(), len))
+}
+
+/// Addresses in an address list header, without display names.
+fn addresses(value: &str) -> Vec<String> {
+    let mut list = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in value.chars().chain([',']) {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ',' if !quoted => {
+                let entry = current.trim();
+                let address = match (entry.rfind('<'), entry.rfind('>')) {
+                    (Some(open), Some(close)) if open < close => &entry[open + 1..close],
+                    _ => entry,
+                };
+                if !address.is_empty() {
+                    list.push(address.to_string());
+                }
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+    list
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// The item passed to the trigger node.
+fn email_item(
+    envelope: &Envelope,
+    email: Email,
+    max_attachment_bytes: usize,
+) -> Result<NodeExecutionData, String> {
+    let header = |name: &str| {
+        email.headers.get(name).and_then(Value::as_str).unwrap_or_default().to_string()
+    };
+    let from = addresses(&header("from")).into_iter().next();
+    let (to, cc) = (addresses(&header("to")), addresses(&header("cc")));
+    let (subject, date, message_id) = (header("subject"), header("date"), header("message-id"));
+
+    let (attachments, skipped): (Vec<Attachment>, Vec<Attachment>) = email
+        .attachments
+        .into_iter()
+        .partition(|a| a.data.len() <= max_attachment_bytes);
+    let skipped: Vec<Value> = skipped
+        .iter()
+        .map(|a| json!({ "fileName": a.file_name, "mimeType": a.mime_type, "bytes": a.data.len() }))
+        .collect();
+
+    let mut item = NodeExecutionData::from_json_value(json!({
+        "from": from.unwrap_or_else(|| envelope.mail_from.clone()),
+        "to": to,
+        "cc": cc,
+        "subject": subject,
+        "date": date,
+        "messageId": message_id,
+        "text": email.text,
+        "html": email.html,
+        "headers": email.headers,
+        "envelope": { "mailFrom": envelope.mail_from, "rcptTo": envelope.rcpt_to },
+        "skippedAttachments": skipped,
+    }))
+    .map_err(|e| e.to_string())?;
+
+    for (i, attachment) in attachments.into_iter().enumerate() {
+        let binary = BinaryData {
+            data: BASE64.encode(&attachment.data),
+            mime_type: attachment.mime_type,
+            file_extension: attachment
+                .file_name
+                .as_deref()
+                .and_then(|name| name.rsplit_once('.'))
+                .map(|(_, ext)| ext.to_ascii_lowercase()),
+            file_name: attachment.file_name,
+            file_size: None,
+            bytes: Some(attachment.data.len() as u64),
+            id: None,
+            file_type: None,
+        };
+        item = item.with_binary(format!("attachment_{}", i), binary);
+    }
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::ExecutionStore;
+    use n8n_core::{MemoryWorkflowStorage, WorkflowEngine};
+    use std::sync::Arc;
+
+    const MESSAGE: &str = concat!(
+        "From: \"Ada, L.\" <ada@example.com>\r\n",
+        "To: orders@shop.example.com, Bob <bob@example.com>\r\n",
+        "Subject: =?utf-8?B?T3JkZXIg4oSWMQ==?= =?utf-8?Q?_confirmed?=\r\n",
+        "Content-Type: multipart/mixed;\r\n boundary=\"outer\"\r\n",
+        "\r\n",
+        "--outer\r\n",
+        "Content-Type: multipart/alternative; boundary=inner\r\n",
+        "\r\n",
+        "--inner\r\n",
+        "Content-Type: text/plain; charset=utf-8\r\n",
+        "Content-Transfer-Encoding: quoted-printable\r\n",
+        "\r\n",
+        "Total: 10 =E2=82=AC, see the =\r\nattachment.\r\n",
+        "--inner\r\n",
+        "Content-Type: text/html\r\n",
+        "\r\n",
+        "<p>Total: 10</p>\r\n",
+        "--inner--\r\n",
+        "--outer\r\n",
+        "Content-Type: text/csv; name=\"order.csv\"\r\n",
+        "Content-Disposition: attachment; filename=\"order.csv\"\r\n",
+        "Content-Transfer-Encoding: base64\r\n",
+        "\r\n",
+        "c2t1LHF0eQpBMSwy\r\n",
+        "--outer--\r\n",
+    );
+
+    #[test]
+    fn test_email_item_from_multipart_message() {
+        let envelope = Envelope {
+            mail_from: "bounce@example.com".to_string(),
+            rcpt_to: vec!["orders@shop.example.com".to_string()],
+        };
+        let item = email_item(&envelope, parse_email(MESSAGE.as_bytes()), 1024).unwrap();
+        let json = serde_json::to_value(&item.json).unwrap();
+
+        assert_eq!(json["from"], "ada@example.com");
+        assert_eq!(json["to"], json!(["orders@shop.example.com", "bob@example.com"]));
+        assert_eq!(json["subject"], "Order №1 confirmed");
+        assert_eq!(json["text"], "Total: 10 €, see the attachment.");
+        assert_eq!(json["html"], "<p>Total: 10</p>");
+        assert_eq!(json["envelope"]["mailFrom"], "bounce@example.com");
+
+        let binary = &item.binary.as_ref().unwrap()["attachment_0"];
+        assert_eq!(binary.file_name.as_deref(), Some("order.csv"));
+        assert_eq!(binary.mime_type, "text/csv");
+        assert_eq!(BASE64.decode(&binary.data).unwrap(), b"sku,qty\nA1,2");
+
+        // Attachments over the limit are dropped and reported.
+        let item = email_item(&envelope, parse_email(MESSAGE.as_bytes()), 4).unwrap();
+        assert!(item.binary.is_none());
+        let json = serde_json::to_value(&item.json).unwrap();
+        assert_eq!(json["skippedAttachments"][0]["fileName"], "order.csv");
+    }
+
+    #[tokio::test]
+    async fn test_session_accepts_mail_for_subscribed_addresses() {
+        let workflows = Arc::new(MemoryWorkflowStorage::new());
+        let mut workflow = Workflow::new("Orders inbox");
+        workflow.active = true;
+        let mut trigger = Node::new("Email", SMTP_TRIGGER_NODE_TYPE);
+        trigger.parameters.insert(
+            "address".to_string(),
+            NodeParameterValue::String("*@Shop.Example.com".to_string()),
+        );
+        workflow.add_node(trigger);
+        workflows.save_workflow(&workflow).await.unwrap();
+
+        let webhooks = WebhookState::new(
+            workflows,
+            Arc::new(ExecutionStore::new()),
+            Arc::new(WorkflowEngine::new(n8n_core::RuntimeConfig::default())),
+        );
+        let state = SmtpState::new(
+            webhooks,
+            SmtpConfig {
+                max_message_bytes: 64 * 1024,
+                ..Default::default()
+            },
+        );
+
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(async move { session(&state, server).await });
+        let (mut reader, mut writer) = tokio::io::split(client);
+        let script = format!(
+            "EHLO client\r\nRCPT TO:<orders@shop.example.com>\r\nMAIL FROM:<ada@example.com> SIZE=900\r\n\
+             RCPT TO:<someone@elsewhere.example>\r\nRCPT TO:<orders@shop.example.com>\r\n\
+             DATA\r\n{}.\r\nQUIT\r\n",
+            MESSAGE
+        );
+        writer.write_all(script.as_bytes()).await.unwrap();
+        let mut transcript = String::new();
+        reader.read_to_string(&mut transcript).await.unwrap();
+        server.await.unwrap().unwrap();
+
+        let codes: Vec<&str> = transcript.lines().map(|l| &l[..4]).collect();
+        assert_eq!(
+            codes,
+            ["220 ", "250-", "250-", "250 ", "503 ", "250 ", "550 ", "250 ", "354 ", "250 ", "221 "]
+        );
+        assert!(transcript.contains("250 2.0.0 OK (1 started)"));
+    }
+}
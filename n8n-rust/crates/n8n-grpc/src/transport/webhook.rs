@@ -0,0 +1,642 @@
+//! Production webhook endpoint.
+//!
+//! `/webhook/{path}` starts the active workflow whose
+//! `n8n-nodes-base.webhook` node listens on `path` for the request's method
+//! (`httpMethod`, default `GET`). The node receives one item with the
+//! request's `headers`, `query` and `body`, and the response is the last
+//! node's output.
+//!
+//! Starts are subject to the workflow's run window
+//! ([`n8n_core::run_window`]). Outside it:
+//!
+//! - `reject` answers `503 Service Unavailable` with `Retry-After` when the
+//!   window's next opening is known;
+//! - `skip` answers `200 OK` without running the workflow;
+//! - `queue` answers `202 Accepted` and runs the workflow when the window
+//!   opens. Queued requests are held in memory and lost on restart.
+//!
+//! Admission control bounds the requests being executed or waiting for
+//! their window ([`WebhookAdmission::max_pending`]). Beyond it requests are
+//! answered `429 Too Many Requests` with `Retry-After`, unless the webhook
+//! node sets `options.mustNotDrop` and a [`WebhookSpill`] is configured:
+//! those are handed to the spill queue, answered `202 Accepted`, and run
+//! once the queue replays them.
+//!
+//! A webhook node with `options.signature` verifies the provider's request
+//! signature ([`n8n_core::webhook_signature`]) before anything else:
+//! unsigned or mis-signed requests are answered `401 Unauthorized`, and the
+//! item of a verified one carries the verification in `signature`.
+//!
+//! Each run counts towards the webhook node's health
+//! ([`n8n_core::trigger_health`]); once it is paused after repeated
+//! failures, requests are answered `503 Service Unavailable` until it is
+//! resumed.
+
+use async_trait::async_trait;
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    routing::any,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use n8n_core::run_window::{self, WindowDecision};
+use n8n_core::sub_execution::last_node_output;
+use n8n_core::webhook_signature::{
+    self, SignatureConfig, SignatureError, SignatureVerification, SignedRequest,
+};
+use n8n_core::{
+    ExecutionEngineError, IngestParser, MemoryWorkflowStorage, ScheduleHandler, WorkflowEngine,
+    WorkflowStorage,
+};
+use n8n_workflow::{
+    object_to_json, DataObject, GenericValue, Node, NodeExecutionData, NodeParameterValue, Run,
+    Workflow,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::api::{ApiError, ExecutionStore};
+use super::run::BatchRegistry;
+
+/// Node type that receives webhook requests.
+pub const WEBHOOK_NODE_TYPE: &str = "n8n-nodes-base.webhook";
+
+/// Webhook admission control settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookAdmission {
+    /// Requests executing or queued for their run window before new ones
+    /// are refused (0 = unlimited).
+    pub max_pending: usize,
+    /// `Retry-After` seconds of refused requests; spilled requests are
+    /// replayed after the same delay.
+    pub retry_after_secs: u64,
+}
+
+impl Default for WebhookAdmission {
+    fn default() -> Self {
+        Self {
+            max_pending: 0,
+            retry_after_secs: 5,
+        }
+    }
+}
+
+/// Durable queue for requests of must-not-drop webhooks refused by
+/// admission control.
+#[async_trait]
+pub trait WebhookSpill: Send + Sync {
+    /// Store the request to run `workflow` from `node` with `item` no
+    /// earlier than `run_at`.
+    async fn spill(
+        &self,
+        workflow: &Workflow,
+        node: &str,
+        item: NodeExecutionData,
+        run_at: DateTime<Utc>,
+    ) -> Result<(), String>;
+}
+
+/// Webhook endpoint state.
+#[derive(Clone)]
+pub struct WebhookState {
+    pub workflows: Arc<MemoryWorkflowStorage>,
+    pub executions: Arc<ExecutionStore>,
+    pub engine: Arc<WorkflowEngine>,
+    pub admission: WebhookAdmission,
+    pub spill: Option<Arc<dyn WebhookSpill>>,
+    pending: Arc<AtomicUsize>,
+    /// Batches of manual runs started with `run-batch`.
+    pub(super) batches: BatchRegistry,
+}
+
+/// A request counted against [`WebhookAdmission::max_pending`] until dropped.
+pub(super) struct Pending(Arc<AtomicUsize>);
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl WebhookState {
+    pub fn new(
+        workflows: Arc<MemoryWorkflowStorage>,
+        executions: Arc<ExecutionStore>,
+        engine: Arc<WorkflowEngine>,
+    ) -> Self {
+        Self {
+            workflows,
+            executions,
+            engine,
+            admission: WebhookAdmission::default(),
+            spill: None,
+            pending: Arc::new(AtomicUsize::new(0)),
+            batches: BatchRegistry::default(),
+        }
+    }
+
+    pub fn with_admission(mut self, admission: WebhookAdmission) -> Self {
+        self.admission = admission;
+        self
+    }
+
+    /// Spill refused requests of must-not-drop webhooks to `spill`.
+    pub fn with_spill(mut self, spill: Arc<dyn WebhookSpill>) -> Self {
+        self.spill = Some(spill);
+        self
+    }
+
+    /// Requests currently executing or queued for their run window.
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Count a request as pending, unless `max_pending` is reached.
+    pub(super) fn admit(&self) -> Option<Pending> {
+        let max = self.admission.max_pending;
+        self.pending
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (max == 0 || n < max).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Pending(self.pending.clone()))
+    }
+
+    /// The active workflow and webhook node listening on `method` `path`.
+    async fn find(&self, method: &Method, path: &str) -> Result<Option<(Workflow, String)>, ApiError> {
+        let workflows = self.workflows.list_workflows().await.map_err(|e| ApiError {
+            code: 500,
+            message: e.to_string(),
+        })?;
+
+        Ok(workflows.into_iter().filter(|w| w.active).find_map(|workflow| {
+            let node = workflow
+                .nodes
+                .iter()
+                .find(|node| listens_on(node, method, path))?
+                .name
+                .clone();
+            Some((workflow, node))
+        }))
+    }
+
+    /// Refuse requests for `node` of `workflow` while the trigger is paused.
+    pub(super) fn check_trigger(&self, workflow: &Workflow, node: &str) -> Result<(), ApiError> {
+        if !self.engine.trigger_health().is_paused(&workflow.id, node) {
+            return Ok(());
+        }
+        Err(ApiError {
+            code: 503,
+            message: format!(
+                "Trigger '{}' of workflow {} is paused after repeated failures",
+                node, workflow.id
+            ),
+        })
+    }
+
+    /// Run `workflow` from the trigger `node` and store the execution,
+    /// counting the run towards the trigger's health.
+    pub(super) async fn execute(
+        &self,
+        workflow: &Workflow,
+        node: &str,
+        item: NodeExecutionData,
+    ) -> Result<(String, Run), ExecutionEngineError> {
+        self.run_from(workflow, node, item, true, Uuid::new_v4().to_string()).await
+    }
+
+    /// [`Self::execute`] for a manual run, which does not count towards the
+    /// trigger's health.
+    pub(super) async fn execute_manual(
+        &self,
+        workflow: &Workflow,
+        node: &str,
+        item: NodeExecutionData,
+    ) -> Result<(String, Run), ExecutionEngineError> {
+        self.execute_manual_as(Uuid::new_v4().to_string(), workflow, node, item).await
+    }
+
+    /// [`Self::execute_manual`] storing the execution as `execution_id`,
+    /// for runs whose id is handed out before they start.
+    pub(super) async fn execute_manual_as(
+        &self,
+        execution_id: String,
+        workflow: &Workflow,
+        node: &str,
+        item: NodeExecutionData,
+    ) -> Result<(String, Run), ExecutionEngineError> {
+        self.run_from(workflow, node, item, false, execution_id).await
+    }
+
+    async fn run_from(
+        &self,
+        workflow: &Workflow,
+        node: &str,
+        item: NodeExecutionData,
+        trigger: bool,
+        execution_id: String,
+    ) -> Result<(String, Run), ExecutionEngineError> {
+        let result = self
+            .engine
+            .execute_partial(workflow, vec![node.to_string()], None, Some(vec![item]))
+            .await;
+        if trigger {
+            let limits = self.engine.config().trigger_health;
+            self.engine.trigger_health().record(&workflow.id, node, &result, &limits);
+        }
+        let run = result?;
+
+        let stored = self.engine.prepare_for_storage(workflow, &execution_id, &run);
+        self.executions
+            .save_execution(&execution_id, &workflow.id, &workflow.name, &stored)
+            .await?;
+        Ok((execution_id, run))
+    }
+}
+
+/// Scheduled runs count towards trigger health like webhook requests, so a
+/// trigger paused after repeated failures stops firing.
+#[async_trait]
+impl ScheduleHandler for WebhookState {
+    async fn fire(
+        &self,
+        workflow: &Workflow,
+        node: &str,
+        scheduled_for: DateTime<Utc>,
+    ) -> Result<(), String> {
+        self.check_trigger(workflow, node).map_err(|e| e.message)?;
+        let mut item = NodeExecutionData::new(DataObject::new());
+        item.json.insert(
+            "scheduledFor".to_string(),
+            GenericValue::String(scheduled_for.to_rfc3339()),
+        );
+        item.json.insert("firedAt".to_string(), GenericValue::String(Utc::now().to_rfc3339()));
+        self.execute(workflow, node, item).await.map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+/// Router serving `/webhook/*path` for every method.
+pub fn create_webhook_router(state: WebhookState) -> Router {
+    Router::new()
+        .route("/webhook/*path", any(handle_webhook))
+        .with_state(state)
+}
+
+async fn handle_webhook(
+    State(state): State<WebhookState>,
+    method: Method,
+    uri: Uri,
+    Path(path): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let (workflow, node) = match state.find(&method, &path).await {
+        Ok(Some(found)) => found,
+        Ok(None) => {
+            return ApiError {
+                code: 404,
+                message: format!("No active webhook for {} /webhook/{}", method, path),
+            }
+            .into_response()
+        }
+        Err(e) => return e.into_response(),
+    };
+
+    if let Err(e) = state.check_trigger(&workflow, &node) {
+        return e.into_response();
+    }
+
+    let verification = match verify_signature(&workflow, &node, &uri, &headers, &body) {
+        Ok(verification) => verification,
+        Err(e) => {
+            tracing::warn!(workflow = %workflow.name, error = %e, "Webhook signature rejected");
+            let code = match e {
+                SignatureError::InvalidConfig(_) => 500,
+                _ => 401,
+            };
+            return ApiError {
+                code,
+                message: e.to_string(),
+            }
+            .into_response();
+        }
+    };
+
+    let url = format!("/webhook/{}", path);
+    let parser = state.engine.config().serialization.ingest;
+    let mut item = match request_item(&method, &url, Map::new(), &headers, query, &body, parser) {
+        Ok(item) => item,
+        Err(e) => {
+            return ApiError {
+                code: 400,
+                message: e,
+            }
+            .into_response()
+        }
+    };
+    if let Some(verification) = verification {
+        if let Ok(value) = serde_json::to_value(&verification).and_then(serde_json::from_value) {
+            item.json.insert("signature".to_string(), value);
+        }
+    }
+
+    let decision = run_window::decide(&workflow, &state.engine.config().timezone, Utc::now());
+    // Skipped and rejected requests cost nothing and are always answered.
+    let admitted = match decision {
+        WindowDecision::Run | WindowDecision::Queue { .. } => match state.admit() {
+            Some(pending) => Some(pending),
+            None => return refuse(&state, &workflow, &node, item).await,
+        },
+        _ => None,
+    };
+
+    match decision {
+        WindowDecision::Run => {
+            let result = state.execute(&workflow, &node, item).await;
+            drop(admitted);
+            respond(result)
+        }
+        WindowDecision::Queue { at, reason } => {
+            tracing::info!(workflow = %workflow.name, until = %at, %reason, "Webhook queued until run window opens");
+            tokio::spawn(async move {
+                let _admitted = admitted;
+                let wait = (at - Utc::now()).to_std().unwrap_or_default();
+                tokio::time::sleep(wait).await;
+                if let Err(e) = state.execute(&workflow, &node, item).await {
+                    tracing::warn!(workflow = %workflow.name, error = %e, "Queued webhook execution failed");
+                }
+            });
+            (
+                StatusCode::ACCEPTED,
+                Json(json!({ "message": "Queued until the run window opens", "scheduledFor": at })),
+            )
+                .into_response()
+        }
+        WindowDecision::Skip { reason } => {
+            Json(json!({ "message": format!("Workflow not started: {}", reason) })).into_response()
+        }
+        WindowDecision::Reject { reason, retry_at } => {
+            let mut response = ApiError {
+                code: 503,
+                message: reason,
+            }
+            .into_response();
+            if let Some(at) = retry_at {
+                let secs = (at - Utc::now()).num_seconds().max(1) as u64;
+                response
+                    .headers_mut()
+                    .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+            }
+            response
+        }
+    }
+}
+
+/// Answer a request refused by admission control: spill it if its webhook
+/// must not drop requests, otherwise `429` with `Retry-After`.
+async fn refuse(
+    state: &WebhookState,
+    workflow: &Workflow,
+    node: &str,
+    item: NodeExecutionData,
+) -> Response {
+    let retry_after = state.admission.retry_after_secs.max(1);
+    let spill = state
+        .spill
+        .as_ref()
+        .filter(|_| workflow.get_node(node).is_some_and(must_not_drop));
+
+    if let Some(spill) = spill {
+        let run_at = Utc::now() + chrono::Duration::seconds(retry_after as i64);
+        match spill.spill(workflow, node, item, run_at).await {
+            Ok(()) => {
+                tracing::info!(workflow = %workflow.name, "Webhook spilled under backpressure");
+                let body = json!({
+                    "message": "Accepted for deferred execution",
+                    "scheduledFor": run_at,
+                });
+                return (StatusCode::ACCEPTED, Json(body))
+                    .into_response();
+            }
+            Err(e) => {
+                tracing::warn!(workflow = %workflow.name, error = %e, "Webhook spill failed");
+            }
+        }
+    }
+
+    tracing::warn!(
+        workflow = %workflow.name,
+        pending = state.pending(),
+        "Webhook refused under backpressure"
+    );
+    let mut response = ApiError {
+        code: 429,
+        message: "Too many pending webhook requests".to_string(),
+    }
+    .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    response
+}
+
+fn respond(result: Result<(String, Run), ExecutionEngineError>) -> Response {
+    let (execution_id, run) = match result {
+        Ok(done) => done,
+        Err(e) => {
+            return ApiError {
+                code: 500,
+                message: e.to_string(),
+            }
+            .into_response()
+        }
+    };
+
+    if let Some(error) = &run.data.result_data.error {
+        return ApiError {
+            code: 500,
+            message: error.message.clone(),
+        }
+        .into_response();
+    }
+
+    let items: Vec<Value> = last_node_output(&run)
+        .iter()
+        .map(|item| Value::Object(object_to_json(&item.json)))
+        .collect();
+    let mut response = Json(items).into_response();
+    if let Ok(value) = HeaderValue::from_str(&execution_id) {
+        response.headers_mut().insert("x-n8n-execution-id", value);
+    }
+    response
+}
+
+/// Whether `node` is an enabled webhook node for `method` `path`.
+fn listens_on(node: &Node, method: &Method, path: &str) -> bool {
+    if node.node_type != WEBHOOK_NODE_TYPE || node.disabled {
+        return false;
+    }
+    let string_param = |name: &str| match node.parameters.get(name) {
+        Some(NodeParameterValue::String(s)) => Some(s.as_str()),
+        _ => None,
+    };
+    let node_method = string_param("httpMethod").unwrap_or("GET");
+    let node_path = string_param("path").unwrap_or_default();
+    node_method.eq_ignore_ascii_case(method.as_str())
+        && node_path.trim_matches('/') == path.trim_matches('/')
+}
+
+/// Whether the webhook node asks for its requests to be spilled rather than
+/// refused under backpressure.
+fn must_not_drop(node: &Node) -> bool {
+    match node.parameters.get("options") {
+        Some(NodeParameterValue::Object(options)) => {
+            matches!(options.get("mustNotDrop"), Some(NodeParameterValue::Boolean(true)))
+        }
+        _ => false,
+    }
+}
+
+/// Verify the request's signature if the webhook node asks for it.
+fn verify_signature(
+    workflow: &Workflow,
+    node: &str,
+    uri: &Uri,
+    headers: &HeaderMap,
+    body: &Bytes,
+) -> Result<Option<SignatureVerification>, SignatureError> {
+    let config = match workflow.get_node(node) {
+        Some(node) => SignatureConfig::from_node(node)?,
+        None => None,
+    };
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    let secret = config.resolve_secret()?;
+
+    let headers: HashMap<String, String> = headers
+        .iter()
+        .filter_map(|(name, value)| {
+            Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+        })
+        .collect();
+    let url = public_url(&headers, uri);
+    let request = SignedRequest {
+        headers: &headers,
+        body,
+        url: &url,
+    };
+    webhook_signature::verify(&config, &secret, &request, Utc::now()).map(Some)
+}
+
+/// The URL the caller used, as far as the proxy headers tell.
+fn public_url(headers: &HashMap<String, String>, uri: &Uri) -> String {
+    let scheme = headers
+        .get("x-forwarded-proto")
+        .and_then(|p| p.split(',').next())
+        .map(str::trim)
+        .unwrap_or("http");
+    let host = headers
+        .get("x-forwarded-host")
+        .or_else(|| headers.get("host"))
+        .map(String::as_str)
+        .unwrap_or("localhost");
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    format!("{}://{}{}", scheme, host, path)
+}
+
+/// The item passed to the webhook node.
+pub(super) fn request_item(
+    method: &Method,
+    url: &str,
+    params: Map<String, Value>,
+    headers: &HeaderMap,
+    query: HashMap<String, String>,
+    body: &Bytes,
+    parser: IngestParser,
+) -> Result<NodeExecutionData, String> {
+    let headers: Map<String, Value> = headers
+        .iter()
+        .filter_map(|(name, value)| {
+            let value = value.to_str().ok()?;
+            Some((name.as_str().to_string(), Value::String(value.to_string())))
+        })
+        .collect();
+
+    let body = if body.is_empty() {
+        GenericValue::Object(DataObject::new())
+    } else {
+        parser
+            .parse(body)
+            .unwrap_or_else(|_| GenericValue::String(String::from_utf8_lossy(body).into_owned()))
+    };
+
+    let mut item = NodeExecutionData::from_json_value(json!({
+        "headers": headers,
+        "params": params,
+        "query": query,
+        "webhookUrl": url,
+        "httpMethod": method.as_str(),
+        "executionMode": "production",
+    }))
+    .map_err(|e| e.to_string())?;
+    // Parsed straight into item data, without a serde_json::Value
+    item.json.insert("body".to_string(), body);
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listens_on_method_and_path() {
+        let mut node = Node::new("Hook", WEBHOOK_NODE_TYPE);
+        node.parameters.insert(
+            "path".to_string(),
+            NodeParameterValue::String("/orders".to_string()),
+        );
+        node.parameters.insert(
+            "httpMethod".to_string(),
+            NodeParameterValue::String("POST".to_string()),
+        );
+
+        assert!(listens_on(&node, &Method::POST, "orders"));
+        assert!(listens_on(&node, &Method::POST, "orders/"));
+        assert!(!listens_on(&node, &Method::GET, "orders"));
+        assert!(!listens_on(&node, &Method::POST, "invoices"));
+
+        node.disabled = true;
+        assert!(!listens_on(&node, &Method::POST, "orders"));
+    }
+
+    #[test]
+    fn test_admission_bounds_pending_requests() {
+        let state = WebhookState::new(
+            Arc::new(MemoryWorkflowStorage::new()),
+            Arc::new(ExecutionStore::new()),
+            Arc::new(WorkflowEngine::new(n8n_core::RuntimeConfig::default())),
+        )
+        .with_admission(WebhookAdmission {
+            max_pending: 2,
+            ..Default::default()
+        });
+
+        let first = state.admit().unwrap();
+        let _second = state.admit().unwrap();
+        assert!(state.admit().is_none());
+        assert_eq!(state.pending(), 2);
+
+        drop(first);
+        assert!(state.admit().is_some());
+    }
+}
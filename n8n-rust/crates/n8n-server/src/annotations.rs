@@ -0,0 +1,188 @@
+//! Execution annotation endpoints.
+//!
+//! QA reviewers mark executions good or bad with a vote, a note and
+//! annotation tags (a vocabulary of their own, separate from workflow
+//! tags):
+//!
+//! - `GET`, `PATCH` and `DELETE /api/v1/executions/:id/annotation` read,
+//!   update (`{"vote": "down", "note": "...", "tags": [tagId]}`; `null`
+//!   clears the vote or note, absent fields are kept) and remove one.
+//! - `GET` and `POST /api/v1/annotation-tags`, `DELETE
+//!   /api/v1/annotation-tags/:id` manage the tags.
+//! - `GET /api/v1/executions/annotated?vote=&tags=&workflowId=` lists
+//!   executions by annotation; saved execution views filter the same way.
+//! - `GET /api/v1/insights/annotations?workflowId=&since=` counts votes,
+//!   down-voted successes and tag use.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use n8n_db::{
+    AnnotationInsights, AnnotationRepository, AnnotationTagEntity, AnnotationUpdate,
+    AnnotationVote, DbError, ExecutionAnnotation, ExecutionEntity, ExecutionFilters,
+    ExecutionRepository,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+type ApiResult<T> = Result<T, (StatusCode, Json<Value>)>;
+
+/// Repositories behind the annotation endpoints.
+#[derive(Clone)]
+pub struct AnnotationState {
+    pub annotations: AnnotationRepository,
+    pub executions: ExecutionRepository,
+}
+
+/// Router for the execution annotation endpoints.
+pub fn create_annotations_router(state: AnnotationState) -> Router {
+    Router::new()
+        .route(
+            "/api/v1/executions/:id/annotation",
+            get(get_annotation).patch(annotate).delete(delete_annotation),
+        )
+        .route("/api/v1/executions/annotated", get(list_annotated))
+        .route("/api/v1/annotation-tags", get(list_tags).post(create_tag))
+        .route("/api/v1/annotation-tags/:id", delete(delete_tag))
+        .route("/api/v1/insights/annotations", get(insights))
+        .with_state(state)
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateTagRequest {
+    name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnnotatedQuery {
+    vote: Option<AnnotationVote>,
+    /// Comma-separated annotation tag IDs.
+    tags: Option<String>,
+    workflow_id: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InsightsQuery {
+    workflow_id: Option<String>,
+    since: Option<DateTime<Utc>>,
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> (StatusCode, Json<Value>) {
+    (status, Json(json!({ "code": status.as_u16(), "message": message.into() })))
+}
+
+fn db_error(e: DbError) -> (StatusCode, Json<Value>) {
+    match e {
+        DbError::NotFound => error(StatusCode::NOT_FOUND, "Execution or tag not found"),
+        DbError::InvalidData(message) => error(StatusCode::BAD_REQUEST, message),
+        DbError::DuplicateKey(message) => error(StatusCode::CONFLICT, message),
+        e => error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn get_annotation(
+    State(state): State<AnnotationState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<ExecutionAnnotation>> {
+    let annotation = state
+        .annotations
+        .find_by_execution(&id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            error(StatusCode::NOT_FOUND, format!("Execution {} has no annotation", id))
+        })?;
+    Ok(Json(annotation))
+}
+
+async fn annotate(
+    State(state): State<AnnotationState>,
+    Path(id): Path<String>,
+    Json(update): Json<AnnotationUpdate>,
+) -> ApiResult<Json<ExecutionAnnotation>> {
+    let annotation = state.annotations.annotate(&id, &update).await.map_err(db_error)?;
+    Ok(Json(annotation))
+}
+
+async fn delete_annotation(
+    State(state): State<AnnotationState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    if !state.annotations.delete(&id).await.map_err(db_error)? {
+        return Err(error(StatusCode::NOT_FOUND, format!("Execution {} has no annotation", id)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_annotated(
+    State(state): State<AnnotationState>,
+    Query(query): Query<AnnotatedQuery>,
+) -> ApiResult<Json<Vec<ExecutionEntity>>> {
+    let tags: Vec<String> = query
+        .tags
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    if query.vote.is_none() && tags.is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "Filter by vote, tags or both"));
+    }
+
+    let filters = ExecutionFilters {
+        workflow_id: query.workflow_id,
+        vote: query.vote,
+        annotation_tag_ids: (!tags.is_empty()).then_some(tags),
+        limit: query.limit,
+        offset: query.offset,
+        ..Default::default()
+    };
+    let executions = state.executions.find_all(&filters).await.map_err(db_error)?;
+    Ok(Json(executions))
+}
+
+async fn list_tags(
+    State(state): State<AnnotationState>,
+) -> ApiResult<Json<Vec<AnnotationTagEntity>>> {
+    let tags = state.annotations.find_all_tags().await.map_err(db_error)?;
+    Ok(Json(tags))
+}
+
+async fn create_tag(
+    State(state): State<AnnotationState>,
+    Json(request): Json<CreateTagRequest>,
+) -> ApiResult<(StatusCode, Json<AnnotationTagEntity>)> {
+    let tag = state.annotations.create_tag(&request.name).await.map_err(db_error)?;
+    Ok((StatusCode::CREATED, Json(tag)))
+}
+
+async fn delete_tag(
+    State(state): State<AnnotationState>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    if !state.annotations.delete_tag(&id).await.map_err(db_error)? {
+        return Err(error(StatusCode::NOT_FOUND, format!("Annotation tag {} not found", id)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn insights(
+    State(state): State<AnnotationState>,
+    Query(query): Query<InsightsQuery>,
+) -> ApiResult<Json<AnnotationInsights>> {
+    let insights = state
+        .annotations
+        .insights(query.workflow_id.as_deref(), query.since)
+        .await
+        .map_err(db_error)?;
+    Ok(Json(insights))
+}
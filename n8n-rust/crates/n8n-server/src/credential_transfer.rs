@@ -0,0 +1,81 @@
+//! Credential export and import endpoints.
+//!
+//! `POST /api/v1/credentials/export` answers a bundle of the credentials
+//! `ids` (all when omitted), encrypted with `transferKey`.
+//! `POST /api/v1/credentials/import` imports a `bundle` encrypted with
+//! `transferKey`; the body may set `ids` (`preserve` or `remap`),
+//! `projectId` and `dryRun`, and the response is the import report. See
+//! [`CredentialsRepository::import`].
+
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use n8n_core::CredentialService;
+use n8n_db::{CredentialBundle, CredentialsRepository, DbError, ImportOptions, ImportReport};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+type ApiError = (StatusCode, Json<Value>);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportRequest {
+    transfer_key: String,
+    #[serde(default)]
+    ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportRequest {
+    transfer_key: String,
+    bundle: CredentialBundle,
+    #[serde(flatten)]
+    options: ImportOptions,
+}
+
+/// Router for the credential export and import endpoints.
+pub fn create_credential_transfer_router(credentials: CredentialsRepository) -> Router {
+    Router::new()
+        .route("/api/v1/credentials/export", post(export_credentials))
+        .route("/api/v1/credentials/import", post(import_credentials))
+        .with_state(credentials)
+}
+
+async fn export_credentials(
+    State(credentials): State<CredentialsRepository>,
+    Json(request): Json<ExportRequest>,
+) -> Result<Json<CredentialBundle>, ApiError> {
+    let transfer = transfer_key(&request.transfer_key)?;
+    let bundle = credentials.export(&request.ids, &transfer).await.map_err(db_error)?;
+    Ok(Json(bundle))
+}
+
+async fn import_credentials(
+    State(credentials): State<CredentialsRepository>,
+    Json(request): Json<ImportRequest>,
+) -> Result<Json<ImportReport>, ApiError> {
+    let transfer = transfer_key(&request.transfer_key)?;
+    let report = credentials
+        .import(&request.bundle, &transfer, &request.options)
+        .await
+        .map_err(db_error)?;
+    Ok(Json(report))
+}
+
+fn transfer_key(key: &str) -> Result<CredentialService, ApiError> {
+    if key.is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "transferKey must not be empty".into()));
+    }
+    Ok(CredentialService::new(key))
+}
+
+fn db_error(e: DbError) -> ApiError {
+    let status = match e {
+        DbError::InvalidData(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    error(status, e.to_string())
+}
+
+fn error(status: StatusCode, message: String) -> ApiError {
+    (status, Json(json!({ "code": status.as_u16(), "message": message })))
+}
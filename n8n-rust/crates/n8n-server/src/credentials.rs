@@ -0,0 +1,88 @@
+//! `credentials` commands.
+//!
+//! - `credentials rotate-key` re-encrypts stored credentials. The current
+//!   key comes from `db.encryption_key` (`N8N_ENCRYPTION_KEY`) and the new
+//!   one from `N8N_NEW_ENCRYPTION_KEY`. Afterwards, restart every instance
+//!   with the new key as `N8N_ENCRYPTION_KEY`.
+//! - `credentials export <file>` writes every credential to a bundle
+//!   encrypted with the transfer key in `N8N_TRANSFER_KEY`, and
+//!   `credentials import <file>` imports one into this instance; see
+//!   [`CredentialsRepository::import`].
+//!
+//! Keys are read from the environment so none shows up in the process
+//! list.
+
+use n8n_core::CredentialService;
+use n8n_db::{CredentialBundle, CredentialsRepository, DbConfig, ImportOptions, ImportReport};
+use std::path::Path;
+
+use crate::config::{ServerConfig, NEW_ENCRYPTION_KEY_ENV, TRANSFER_KEY_ENV};
+
+type CommandResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+fn db_config<'a>(config: &'a ServerConfig, command: &str) -> CommandResult<&'a DbConfig> {
+    let missing = || format!("credentials {} needs a database (set DATABASE_URL)", command);
+    Ok(config.db.as_ref().ok_or_else(missing)?)
+}
+
+/// The repository, with the instance key when one is set.
+async fn repository(db: &DbConfig) -> CommandResult<CredentialsRepository> {
+    let credentials = CredentialsRepository::new(db.connect().await?);
+    Ok(match &db.encryption_key {
+        Some(key) => credentials.with_encryption(CredentialService::new(key)),
+        None => credentials,
+    })
+}
+
+fn transfer_key(command: &str) -> CommandResult<CredentialService> {
+    match std::env::var(TRANSFER_KEY_ENV) {
+        Ok(key) if !key.is_empty() => Ok(CredentialService::new(&key)),
+        _ => {
+            let message = format!("credentials {} needs the transfer key in", command);
+            Err(format!("{} {}", message, TRANSFER_KEY_ENV).into())
+        }
+    }
+}
+
+/// Re-encrypt every credential with the new key. Returns how many were
+/// re-encrypted.
+pub async fn rotate_key(config: &ServerConfig) -> CommandResult<u64> {
+    let db = db_config(config, "rotate-key")?;
+    let old = db
+        .encryption_key
+        .as_deref()
+        .ok_or("credentials rotate-key needs the current key in N8N_ENCRYPTION_KEY")?;
+    let new = std::env::var(NEW_ENCRYPTION_KEY_ENV).map_err(|_| {
+        format!("credentials rotate-key needs the new key in {}", NEW_ENCRYPTION_KEY_ENV)
+    })?;
+    if new.is_empty() || new == old {
+        return Err(format!("{} must differ from the current key", NEW_ENCRYPTION_KEY_ENV).into());
+    }
+
+    let pool = db.connect().await?;
+    let rotated = CredentialsRepository::new(pool)
+        .rotate_key(&CredentialService::new(old), &CredentialService::new(&new))
+        .await?;
+    Ok(rotated)
+}
+
+/// Write every credential to `file`. Returns how many were exported.
+pub async fn export(config: &ServerConfig, file: &Path) -> CommandResult<usize> {
+    let transfer = transfer_key("export")?;
+    let credentials = repository(db_config(config, "export")?).await?;
+    let bundle = credentials.export(&[], &transfer).await?;
+    std::fs::write(file, serde_json::to_string_pretty(&bundle)?)?;
+    Ok(bundle.credentials.len())
+}
+
+/// Import the bundle in `file` as `options` say.
+pub async fn import(
+    config: &ServerConfig,
+    file: &Path,
+    options: &ImportOptions,
+) -> CommandResult<ImportReport> {
+    let transfer = transfer_key("import")?;
+    let bundle: CredentialBundle = serde_json::from_str(&std::fs::read_to_string(file)?)?;
+    let credentials = repository(db_config(config, "import")?).await?;
+    Ok(credentials.import(&bundle, &transfer, options).await?)
+}
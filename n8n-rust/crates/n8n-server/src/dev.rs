@@ -0,0 +1,127 @@
+//! Demo data for development mode (`--dev`).
+//!
+//! A sample workflow is loaded into the in-memory workflow store so there
+//! is something to run right away. Without `DATABASE_URL`, an embedded
+//! PostgreSQL server (feature `dev`, on by default) is started, keeping its
+//! data in [`EMBEDDED_DATA_DIR`] across restarts. Once migrated, a demo
+//! owner and their personal project are created when the database has no
+//! users yet; the user has no password, as there is no password hashing to
+//! log in with.
+
+use n8n_core::{ExecutionEngineError, MemoryWorkflowStorage, WorkflowStorage};
+use n8n_db::entities::user::role_names;
+#[cfg(feature = "dev")]
+use n8n_db::DbConfig;
+use n8n_db::{DbContext, DbError, Project, User};
+use n8n_workflow::{Node, NodeParameterValue, Workflow};
+#[cfg(feature = "dev")]
+use postgresql_embedded::{PostgreSQL, Settings};
+use std::collections::HashMap;
+#[cfg(feature = "dev")]
+use std::path::Path;
+#[cfg(feature = "dev")]
+use std::time::Duration;
+
+/// ID of the sample workflow.
+pub const SAMPLE_WORKFLOW_ID: &str = "dev-sample";
+
+/// Email of the demo user.
+pub const DEMO_USER_EMAIL: &str = "demo@n8n.local";
+
+/// Directory of the embedded PostgreSQL server's data, relative to the
+/// working directory.
+pub const EMBEDDED_DATA_DIR: &str = ".n8n-rust/postgres";
+
+/// Database created in the embedded PostgreSQL server.
+pub const EMBEDDED_DATABASE: &str = "n8n";
+
+/// Manual Trigger → Set → No Operation.
+pub fn sample_workflow() -> Workflow {
+    let mut workflow = Workflow::new("Sample: Hello n8n-rust");
+    workflow.id = SAMPLE_WORKFLOW_ID.to_string();
+    workflow.description = Some("Seeded by --dev; run it from the REST API.".to_string());
+
+    let mut trigger = Node::new("Manual Trigger", "n8n-nodes-base.manualTrigger");
+    trigger.position = [0.0, 0.0];
+    let mut set = Node::new("Set Greeting", "n8n-nodes-base.set");
+    set.position = [220.0, 0.0];
+    set.set_parameter(
+        "values",
+        NodeParameterValue::Object(HashMap::from([(
+            "greeting".to_string(),
+            NodeParameterValue::String("Hello from n8n-rust".to_string()),
+        )])),
+    );
+    let mut done = Node::new("Done", "n8n-nodes-base.noOp");
+    done.position = [440.0, 0.0];
+
+    workflow.add_node(trigger);
+    workflow.add_node(set);
+    workflow.add_node(done);
+    workflow
+        .connect("Manual Trigger", "Set Greeting", 0, 0)
+        .and_then(|_| workflow.connect("Set Greeting", "Done", 0, 0))
+        .expect("sample nodes exist");
+    workflow
+}
+
+/// Load the sample workflow unless it is already there. Returns whether it
+/// was added.
+pub async fn seed_workflows(storage: &MemoryWorkflowStorage) -> Result<bool, ExecutionEngineError> {
+    if storage.get_workflow(SAMPLE_WORKFLOW_ID).await?.is_some() {
+        return Ok(false);
+    }
+    storage.save_workflow(&sample_workflow()).await?;
+    Ok(true)
+}
+
+/// Start the embedded PostgreSQL server, downloading it on first use, and
+/// create its database unless there already is one. It stops when the
+/// returned server is stopped or dropped.
+#[cfg(feature = "dev")]
+pub async fn start_embedded_postgres(
+) -> Result<(PostgreSQL, DbConfig), postgresql_embedded::Error> {
+    let dir = Path::new(EMBEDDED_DATA_DIR);
+    let settings = Settings {
+        data_dir: dir.join("data"),
+        password_file: dir.join(".pgpass"),
+        // The data directory outlives the process, so the password must too
+        password: "n8n".to_string(),
+        temporary: false,
+        // The first start initializes the data directory
+        timeout: Some(Duration::from_secs(60)),
+        ..Settings::default()
+    };
+    let mut postgres = PostgreSQL::new(settings);
+    postgres.setup().await?;
+    postgres.start().await?;
+    if !postgres.database_exists(EMBEDDED_DATABASE).await? {
+        postgres.create_database(EMBEDDED_DATABASE).await?;
+    }
+    let config = DbConfig {
+        database_url: postgres.settings().url(EMBEDDED_DATABASE),
+        ..DbConfig::default()
+    };
+    Ok((postgres, config))
+}
+
+/// Create the demo owner and their personal project if there are no users.
+/// Returns the user created.
+pub async fn seed_demo_user(db: &DbContext) -> Result<Option<User>, DbError> {
+    if !db.users.find_all().await?.is_empty() {
+        return Ok(None);
+    }
+
+    let mut user = User::new(DEMO_USER_EMAIL);
+    user.first_name = Some("Demo".to_string());
+    user.last_name = Some("User".to_string());
+    user.role_id = db.users.get_role(role_names::GLOBAL_OWNER).await?.map(|role| role.id);
+    let user = db.users.create(&user).await?;
+
+    let project = Project::personal(format!("Demo User <{}>", DEMO_USER_EMAIL), user.id);
+    let project = db.projects.create(&project).await?;
+    db.projects
+        .add_member(&project.id, user.id, "project:personalOwner")
+        .await?;
+    Ok(Some(user))
+}
@@ -0,0 +1,132 @@
+//! Database-backed workflow endpoints.
+//!
+//! Published endpoints live in the `workflow_endpoint` table so every
+//! instance serves the same routes, and callers are authorized against the
+//! scopes of their row in `api_key`. Instances announce the endpoints they
+//! change on the event bus and reload theirs when others do.
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use n8n_db::{
+    ChangeOperation, ChangedEntity, EndpointEntity, EndpointRepository, InsertEndpoint, PgEventBus,
+};
+use n8n_grpc::{ApiKeyScopes, EndpointState, EndpointStore, WorkflowEndpoint};
+use tracing::{info, warn};
+
+/// [`EndpointStore`] backed by the `workflow_endpoint` table.
+pub struct DbEndpointStore {
+    repository: EndpointRepository,
+    events: Option<PgEventBus>,
+}
+
+impl DbEndpointStore {
+    pub fn new(repository: EndpointRepository) -> Self {
+        Self {
+            repository,
+            events: None,
+        }
+    }
+
+    /// Announce changes to other instances on `events`.
+    pub fn with_events(mut self, events: PgEventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    async fn announce(&self, operation: ChangeOperation, id: &str) {
+        let Some(events) = &self.events else {
+            return;
+        };
+        if let Err(e) = events.publish(ChangedEntity::Endpoint, operation, id).await {
+            warn!(endpoint = %id, error = %e, "Failed to announce endpoint change");
+        }
+    }
+}
+
+#[async_trait]
+impl EndpointStore for DbEndpointStore {
+    async fn list(&self) -> Result<Vec<WorkflowEndpoint>, String> {
+        let endpoints = self.repository.find_all().await.map_err(|e| e.to_string())?;
+        Ok(endpoints.into_iter().map(from_entity).collect())
+    }
+
+    async fn save(&self, endpoint: &WorkflowEndpoint) -> Result<(), String> {
+        let mut insert = InsertEndpoint::new(
+            &endpoint.workflow_id,
+            &endpoint.node,
+            &endpoint.method,
+            &endpoint.path,
+        );
+        insert.id = endpoint.id.clone();
+        insert.summary = endpoint.summary.clone();
+        insert.request_schema = endpoint.request_schema.clone();
+        insert.response_schema = endpoint.response_schema.clone();
+        insert.scopes = endpoint.scopes.clone();
+
+        self.repository.upsert(&insert).await.map_err(|e| e.to_string())?;
+        self.announce(ChangeOperation::Updated, &endpoint.id).await;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> Result<bool, String> {
+        let deleted = self.repository.delete(id).await.map_err(|e| e.to_string())?;
+        if deleted {
+            self.announce(ChangeOperation::Deleted, id).await;
+        }
+        Ok(deleted)
+    }
+}
+
+/// Reload `state`'s endpoints whenever another instance changes one, or
+/// changes may have been missed.
+pub async fn spawn_endpoint_sync(events: PgEventBus, state: EndpointState) -> Result<(), String> {
+    let mut changes = events.subscribe().await.map_err(|e| e.to_string())?;
+    tokio::spawn(async move {
+        while let Some(change) = changes.next().await {
+            match change {
+                Ok(change) if change.entity != ChangedEntity::Endpoint => continue,
+                Ok(change) => info!(endpoint = %change.id, "Endpoint changed on another instance"),
+                Err(e) => warn!(error = %e, "Reloading endpoints"),
+            }
+            if let Err(e) = state.load().await {
+                warn!(error = %e, "Failed to reload workflow endpoints");
+            }
+        }
+    });
+    Ok(())
+}
+
+/// [`ApiKeyScopes`] reading the scopes of keys in the `api_key` table.
+pub struct DbApiKeys {
+    repository: EndpointRepository,
+}
+
+impl DbApiKeys {
+    pub fn new(repository: EndpointRepository) -> Self {
+        Self { repository }
+    }
+}
+
+#[async_trait]
+impl ApiKeyScopes for DbApiKeys {
+    async fn scopes(&self, api_key: &str) -> Result<Option<Vec<String>>, String> {
+        self.repository
+            .api_key_scopes(api_key)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn from_entity(entity: EndpointEntity) -> WorkflowEndpoint {
+    WorkflowEndpoint {
+        id: entity.id,
+        workflow_id: entity.workflow_id,
+        node: entity.node,
+        method: entity.method,
+        path: entity.path,
+        summary: entity.summary,
+        request_schema: entity.request_schema,
+        response_schema: entity.response_schema,
+        scopes: entity.scopes,
+    }
+}
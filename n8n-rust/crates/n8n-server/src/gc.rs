@@ -0,0 +1,56 @@
+//! Admin endpoints for the garbage collector.
+//!
+//! - `GET /api/v1/admin/gc` returns the collector's metrics.
+//! - `POST /api/v1/admin/gc?dryRun=true` runs a sweep now and returns what
+//!   it removed (or would remove).
+//! - `GET /api/v1/admin/gc/blobs` returns the content-addressed blob
+//!   totals and the bytes deduplication saved.
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use n8n_db::{BlobStats, DbError, GarbageCollector, GcMetricsSnapshot, GcReport};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SweepQuery {
+    dry_run: Option<bool>,
+}
+
+/// Router for the garbage collector endpoints.
+pub fn create_gc_router(gc: Arc<GarbageCollector>) -> Router {
+    Router::new()
+        .route("/api/v1/admin/gc", get(gc_metrics).post(run_gc))
+        .route("/api/v1/admin/gc/blobs", get(blob_stats))
+        .with_state(gc)
+}
+
+async fn gc_metrics(State(gc): State<Arc<GarbageCollector>>) -> Json<GcMetricsSnapshot> {
+    Json(gc.metrics().snapshot())
+}
+
+async fn blob_stats(
+    State(gc): State<Arc<GarbageCollector>>,
+) -> Result<Json<BlobStats>, (StatusCode, Json<serde_json::Value>)> {
+    gc.blob_stats().await.map(Json).map_err(internal_error)
+}
+
+async fn run_gc(
+    State(gc): State<Arc<GarbageCollector>>,
+    Query(query): Query<SweepQuery>,
+) -> Result<Json<GcReport>, (StatusCode, Json<serde_json::Value>)> {
+    let dry_run = query.dry_run.unwrap_or(gc.config().dry_run);
+    gc.run_once(dry_run).await.map(Json).map_err(internal_error)
+}
+
+fn internal_error(e: DbError) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({ "code": 500, "message": e.to_string() })),
+    )
+}
@@ -0,0 +1,44 @@
+//! Workflow generation endpoint.
+//!
+//! `POST /api/v1/workflows/generate` takes `{"description": "..."}` and
+//! answers a draft workflow from the configured LLM with its diagnostics;
+//! see [`WorkflowGenerator`]. The draft is not saved: clients review it and
+//! create it with `POST /api/v1/workflows`.
+
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use n8n_core::{GenerationError, WorkflowDraft, WorkflowGenerator};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+type ApiError = (StatusCode, Json<Value>);
+
+#[derive(Debug, Deserialize)]
+struct GenerateRequest {
+    description: String,
+}
+
+/// Router for the workflow generation endpoint.
+pub fn create_generation_router(generator: Arc<WorkflowGenerator>) -> Router {
+    Router::new()
+        .route("/api/v1/workflows/generate", post(generate_workflow))
+        .with_state(generator)
+}
+
+async fn generate_workflow(
+    State(generator): State<Arc<WorkflowGenerator>>,
+    Json(request): Json<GenerateRequest>,
+) -> Result<Json<WorkflowDraft>, ApiError> {
+    generator
+        .generate(&request.description)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            let status = match e {
+                GenerationError::EmptyDescription => StatusCode::BAD_REQUEST,
+                GenerationError::Provider(_) => StatusCode::BAD_GATEWAY,
+                GenerationError::InvalidDraft(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            };
+            (status, Json(json!({ "code": status.as_u16(), "message": e.to_string() })))
+        })
+}
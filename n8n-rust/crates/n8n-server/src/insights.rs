@@ -0,0 +1,50 @@
+//! Resource usage insights.
+//!
+//! - `GET /api/v1/insights/resources?workflowId=&since=&limit=` lists
+//!   workflows by the CPU time their measured executions used, heaviest
+//!   first, with average and maximum CPU time and peak RSS rise (see
+//!   `runtime.resource_usage`). `limit` defaults to 20 and may be at most
+//!   1000.
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use n8n_db::{ExecutionRepository, WorkflowResourceUsage};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourcesQuery {
+    workflow_id: Option<String>,
+    since: Option<DateTime<Utc>>,
+    limit: Option<i64>,
+}
+
+/// Router for the resource usage insights.
+pub fn create_insights_router(executions: ExecutionRepository) -> Router {
+    Router::new()
+        .route("/api/v1/insights/resources", get(resources))
+        .with_state(executions)
+}
+
+async fn resources(
+    State(executions): State<ExecutionRepository>,
+    Query(query): Query<ResourcesQuery>,
+) -> Result<Json<Vec<WorkflowResourceUsage>>, (StatusCode, Json<Value>)> {
+    let limit = query.limit.unwrap_or(20).clamp(1, 1000);
+    executions
+        .resource_usage_by_workflow(query.workflow_id.as_deref(), query.since, limit)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "code": 500, "message": e.to_string() })),
+            )
+        })
+}
@@ -0,0 +1,58 @@
+//! Side-effect journal endpoints.
+//!
+//! - `GET /api/v1/executions/:id/journal` lists the journaled side effects
+//!   of an execution, in doubt or completed.
+//! - `GET /api/v1/journal/in-doubt?olderThan=` lists side effects in doubt:
+//!   intents never completed, by default those older than 300 seconds so
+//!   attempts still running are left out. After a crash, these are the
+//!   side effects a retry may repeat.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use n8n_core::{JournalEntry, SideEffectJournal};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+type ApiResult<T> = Result<T, (StatusCode, Json<Value>)>;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InDoubtQuery {
+    /// Minimum age in seconds.
+    older_than: Option<i64>,
+}
+
+/// Router for the side-effect journal endpoints.
+pub fn create_journal_router(journal: SideEffectJournal) -> Router {
+    Router::new()
+        .route("/api/v1/executions/:id/journal", get(execution_journal))
+        .route("/api/v1/journal/in-doubt", get(in_doubt))
+        .with_state(journal)
+}
+
+fn journal_error(message: String) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({ "code": 500, "message": message })),
+    )
+}
+
+async fn execution_journal(
+    State(journal): State<SideEffectJournal>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Vec<JournalEntry>>> {
+    journal.entries(&id).await.map(Json).map_err(journal_error)
+}
+
+async fn in_doubt(
+    State(journal): State<SideEffectJournal>,
+    Query(query): Query<InDoubtQuery>,
+) -> ApiResult<Json<Vec<JournalEntry>>> {
+    let before = Utc::now() - chrono::Duration::seconds(query.older_than.unwrap_or(300).max(0));
+    journal.in_doubt(before).await.map(Json).map_err(journal_error)
+}
@@ -0,0 +1,94 @@
+//! `lint` subcommand and linter setup.
+//!
+//! ```text
+//! n8n-server lint workflows/*.json --format sarif > lint.sarif
+//! ```
+//!
+//! Exits non-zero when any diagnostic is an error, so it can gate CI.
+
+use n8n_contract::{CredentialRoleRule, ImpactGate};
+use n8n_core::{to_sarif, LintReport, Linter};
+use n8n_workflow::Workflow;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::config::{ConfigError, LintConfig, LintFormat};
+
+/// Build the linter: built-in rules, the RBAC credential rule and the
+/// configured rule levels.
+pub fn build_linter(config: &LintConfig) -> Linter {
+    let mut linter = Linter::default();
+    linter.register(Arc::new(CredentialRoleRule::new(
+        ImpactGate::new(),
+        config.credential_roles.clone().into_iter().collect(),
+    )));
+    for (rule_id, level) in &config.rules {
+        linter.configure(rule_id.clone(), *level);
+    }
+    linter
+}
+
+/// Lint workflow files and print the result. Returns whether any error
+/// was found.
+pub fn run(linter: &Linter, files: &[PathBuf], format: LintFormat) -> Result<bool, ConfigError> {
+    let mut reports = Vec::new();
+    for path in files {
+        let workflow = load_workflow(path)?;
+        let mut report = linter.lint(&workflow);
+        report.source = path.display().to_string();
+        reports.push(report);
+    }
+
+    match format {
+        LintFormat::Text => {
+            let mut count = 0;
+            for report in &reports {
+                for d in &report.diagnostics {
+                    let node = d.node.as_deref().map(|n| format!("{}: ", n)).unwrap_or_default();
+                    println!(
+                        "{}: {:?} [{}] {}{}",
+                        report.source, d.severity, d.rule_id, node, d.message
+                    );
+                    count += 1;
+                }
+            }
+            println!("{} problem(s) in {} workflow(s)", count, reports.len());
+        }
+        LintFormat::Json => println!("{}", serde_json::to_string_pretty(&reports).unwrap_or_default()),
+        LintFormat::Sarif => println!(
+            "{}",
+            serde_json::to_string_pretty(&to_sarif(linter, &reports)).unwrap_or_default()
+        ),
+    }
+
+    Ok(reports.iter().any(LintReport::has_errors))
+}
+
+/// Read a workflow export (JSON or YAML). Exports without an `id` use the
+/// file stem.
+pub fn load_workflow(path: &Path) -> Result<Workflow, ConfigError> {
+    let parse_error = |message: String| ConfigError::Parse {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mut value: serde_json::Value = match path.extension().and_then(|e| e.to_str()) {
+        Some("yaml") | Some("yml") => {
+            serde_yaml::from_str(&contents).map_err(|e| parse_error(e.to_string()))?
+        }
+        _ => serde_json::from_str(&contents).map_err(|e| parse_error(e.to_string()))?,
+    };
+
+    if let Some(object) = value.as_object_mut() {
+        if !object.contains_key("id") {
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("workflow");
+            object.insert("id".into(), stem.into());
+        }
+    }
+
+    serde_json::from_value(value).map_err(|e| parse_error(e.to_string()))
+}
@@ -6,48 +6,434 @@
 //! - REST API with content negotiation
 //! - STDIO for CLI integration
 //! - Hamming similarity service
+//! - gRPC health checking and server reflection
 //! - Intelligent format/transport negotiation
 //! - Graceful fallback between transports
 
+mod annotations;
+mod config;
+mod credential_transfer;
+mod credentials;
+mod dev;
+mod endpoints;
+mod gc;
+mod generation;
+mod insights;
+mod journal;
+mod lint;
+mod masking;
+mod reload;
+mod run;
+mod schemas;
+mod spill;
+mod transfer;
+mod triggers;
+mod views;
+
+use config::{Cli, Command, LogFormat, ServerConfig};
+use credential_transfer::create_credential_transfer_router;
+use endpoints::{spawn_endpoint_sync, DbApiKeys, DbEndpointStore};
+use gc::create_gc_router;
+use generation::create_generation_router;
+use insights::create_insights_router;
+use journal::create_journal_router;
+use masking::MaskingMakeWriter;
+use n8n_core::{
+    AwsCredentialsProvider, BinaryStorageMode, CredentialService, FileSystemBinaryStore,
+    OpenAiCompatibleProvider, S3BinaryStore, ScheduleRunner, SecretMask, WorkflowGenerator,
+};
+use reload::{create_reload_router, spawn_sighup_listener, Reloader};
+use schemas::create_schemas_router;
+use spill::{TimerSpill, TimerThrottle};
+use annotations::{create_annotations_router, AnnotationState};
+use transfer::create_transfer_router;
+use triggers::spawn_trigger_sync;
+use views::{create_views_router, ViewState};
 use n8n_grpc::{
     ArrowDataService, HammingGrpcService, WorkflowGrpcService, WorkflowServiceState,
-    TransportConfig, FormatNegotiator, create_router,
+    TransportConfig, FormatNegotiator, NegotiatorConfig, create_router,
     TransportCapabilities, create_api_router, ApiState, ExecutionStore,
+    create_webhook_router, WebhookSpill, WebhookState, create_run_router,
+    create_endpoint_router, EndpointState, StaticApiKeys, serve_smtp, SmtpState,
+    GrpcHealth, reflection_service, HEALTH_DB, HEALTH_DB_MIGRATIONS, HEALTH_QUEUE,
+    HEALTH_QUEUE_CONSUMER, HEALTH_REDIS, HEALTH_WORKFLOW_SERVICE, track_started,
+    create_readiness_router, redis_ping, ReadinessState, rate_limit, RateLimiter, correlate,
+    create_openapi_router, create_deliveries_router, create_evaluations_router, EvaluationState,
+};
+use n8n_grpc::pb::workflow_service_server::WorkflowServiceServer;
+use n8n_db::{
+    CompressionAlgorithm, DbConfig, DbContext, GarbageCollector, LeaderElection,
+    PgConcurrencyLocks, PgEventBus, PgSideEffectJournal, ProjectSchemas, PruningService,
+    SqlxExecutionStorage, SqlxWorkflowStorage, TimerScheduler, WorkflowTimerHandler,
+    WorkflowTransfers,
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tracing::{info, warn, Level};
-use tracing_subscriber::FmtSubscriber;
+use std::time::Duration;
+use tracing::{info, warn};
+use tracing_subscriber::{
+    layer::SubscriberExt, reload as log_reload, util::SubscriberInitExt, EnvFilter, Layer,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize logging
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .with_target(false)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)?;
+    // Resolve configuration: defaults < file < env < CLI flags
+    let cli = Cli::parse(std::env::args().skip(1))?;
+    let server_config = ServerConfig::load(&cli)?;
+
+    if let Command::ConfigDump { format } = cli.command {
+        println!("{}", server_config.dump(format)?);
+        return Ok(());
+    }
 
-    // Parse configuration
-    let config = parse_config();
+    if let Command::Lint { format, files } = &cli.command {
+        let linter = lint::build_linter(&server_config.lint);
+        if lint::run(&linter, files, *format)? {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Command::Diagram { format, file } = &cli.command {
+        let workflow = lint::load_workflow(file)?;
+        print!("{}", n8n_core::diagram::render(&workflow, *format));
+        return Ok(());
+    }
+
+    if let Command::Docs { file } = &cli.command {
+        let workflow = lint::load_workflow(file)?;
+        print!("{}", n8n_core::docs::render(&workflow));
+        return Ok(());
+    }
+
+    if let Command::Run = cli.command {
+        if !run::run(&server_config.runtime).await? {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    match &cli.command {
+        Command::RotateKey => {
+            let rotated = credentials::rotate_key(&server_config).await?;
+            println!("Re-encrypted {} credentials", rotated);
+            return Ok(());
+        }
+        Command::ExportCredentials { file } => {
+            let exported = credentials::export(&server_config, file).await?;
+            println!("Exported {} credentials to {}", exported, file.display());
+            return Ok(());
+        }
+        Command::ImportCredentials { file, options } => {
+            let report = credentials::import(&server_config, file, options).await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // Initialize logging; the filter can be swapped on reload
+    let (log_filter, log_handle) =
+        log_reload::Layer::new(EnvFilter::try_new(&server_config.log_level)?);
+    let log_writer = MaskingMakeWriter::new(
+        server_config.runtime.masking.enabled.then(SecretMask::global),
+    );
+    let log_format = match server_config.log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer()
+            .with_target(false)
+            .with_writer(log_writer)
+            .boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .with_writer(log_writer)
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed(),
+    };
+    tracing_subscriber::registry()
+        .with(log_filter)
+        .with(log_format)
+        .try_init()?;
+
+    // Hot reload of safe-to-change settings (SIGHUP or POST /api/v1/admin/reload)
+    let reloader = Arc::new(Reloader::new(cli, server_config.clone(), log_handle)?);
+    spawn_sighup_listener(reloader.clone())?;
+
+    let config = server_config.transport.clone();
+    let health_interval = Duration::from_secs(server_config.health_interval_secs);
 
     print_banner();
 
     // Initialize core services
-    let state = Arc::new(WorkflowServiceState::new());
-    let negotiator = Arc::new(FormatNegotiator::new());
+    let state = Arc::new(WorkflowServiceState::with_config_updates(reloader.runtime_updates()));
+    let negotiator = Arc::new(FormatNegotiator::with_config(NegotiatorConfig {
+        shared_memory_socket: (config.shm_enabled && cfg!(target_os = "linux"))
+            .then(|| config.shm_path.clone()),
+        ..Default::default()
+    }));
 
     info!("Initializing services...");
 
-    let _workflow_service = WorkflowGrpcService::new(state.clone());
-    let _arrow_service = ArrowDataService::new(state.workflows.clone(), state.executions.clone());
+    let workflow_service = WorkflowGrpcService::new(state.clone());
+    let arrow_service = ArrowDataService::new(state.workflows.clone(), state.executions.clone());
     let _hamming_service = HammingGrpcService::new();
 
     info!("  [✓] WorkflowService: workflow CRUD and execution");
     info!("  [✓] ArrowDataService: zero-copy data streaming");
     info!("  [✓] HammingService: 10kbit similarity search");
+
+    // Binary payloads go to the store instead of travelling inline
+    match server_config.runtime.binary_mode {
+        BinaryStorageMode::FileSystem => {
+            if let Some(dir) = &server_config.gc.binary_data_dir {
+                state
+                    .engine
+                    .binary_data()
+                    .set_store(Arc::new(FileSystemBinaryStore::new(dir.clone())));
+                info!("  [✓] Binary data: filesystem at {}", dir.display());
+            }
+        }
+        BinaryStorageMode::S3 => {
+            let s3 = server_config.binary_data.s3.clone();
+            info!("  [✓] Binary data: S3 bucket {}", s3.bucket);
+            state.engine.binary_data().set_store(Arc::new(S3BinaryStore::new(
+                s3,
+                AwsCredentialsProvider::shared_chain(),
+            )));
+        }
+        BinaryStorageMode::Memory => {}
+    }
+    if server_config.binary_data.deduplicate {
+        state.engine.binary_data().set_deduplicate(true);
+        info!("  [✓] Binary data: content-addressed, deduplicated");
+    }
     info!("");
 
+    // Embedded PostgreSQL of `--dev` without a database, run until shutdown
+    #[cfg(feature = "dev")]
+    let mut server_config = server_config;
+    #[cfg(feature = "dev")]
+    let mut embedded_postgres = None;
+    if server_config.dev {
+        info!("Development mode: listeners on localhost, executions in-process");
+        if dev::seed_workflows(&state.workflows).await? {
+            info!("  [✓] Sample workflow: {}", dev::SAMPLE_WORKFLOW_ID);
+        }
+        #[cfg(feature = "dev")]
+        if server_config.db.is_none() {
+            match dev::start_embedded_postgres().await {
+                Ok((postgres, db_config)) => {
+                    info!("  [✓] Database: embedded PostgreSQL in {}", dev::EMBEDDED_DATA_DIR);
+                    server_config.db = Some(db_config);
+                    embedded_postgres = Some(postgres);
+                }
+                Err(e) => warn!("  [✗] Embedded PostgreSQL: {}", e),
+            }
+        }
+        if server_config.db.is_none() {
+            info!("  [-] Database: none, data is kept in memory (set DATABASE_URL to persist)");
+        }
+        info!("");
+    }
+
+    // Health state shared by the gRPC health service. Only services the gRPC
+    // listener mounts are reported.
+    let (health, health_service) = GrpcHealth::new();
+    health.set_serving(HEALTH_WORKFLOW_SERVICE, true).await;
+
+    // Database (optional)
+    let mut gc = None;
+    let mut election: Option<(Arc<LeaderElection>, tokio::task::JoinHandle<()>)> = None;
+    let mut webhook_spill: Option<Arc<dyn WebhookSpill>> = None;
+    let mut endpoint_repository = None;
+    let mut event_bus = None;
+    let mut transfers = None;
+    let mut credential_transfers = None;
+    let mut views = None;
+    let mut annotations = None;
+    let mut insights = None;
+    let mut project_schemas = None;
+    if let Some(db) = connect_db(server_config.db.as_ref()).await {
+        if server_config.dev {
+            match db.migrate().await {
+                Ok(()) => info!("  [✓] Migrations: applied"),
+                Err(e) => warn!("  [✗] Migrations: {}", e),
+            }
+            match dev::seed_demo_user(&db).await {
+                Ok(Some(_)) => info!("  [✓] Demo user: {}", dev::DEMO_USER_EMAIL),
+                Ok(None) => {}
+                Err(e) => warn!("  [✗] Demo user: {}", e),
+            }
+        }
+
+        // One instance leads pruning and schedule triggers
+        let leader = Arc::new(LeaderElection::new(db.pool.clone()));
+        election = Some((leader.clone(), leader.clone().spawn()));
+        info!("  [✓] Leader election: advisory lock '{}'", leader.key());
+
+        // Durable timers: fire timer-triggered workflows from the database
+        let timer_handler = WorkflowTimerHandler::new(
+            state.engine.clone(),
+            SqlxWorkflowStorage::new(db.pool.clone()),
+            SqlxExecutionStorage::from_repo(db.executions.clone(), db.binary_data.clone()),
+        );
+        let timers = Arc::new(TimerScheduler::new(
+            db.timers.clone(),
+            timer_handler,
+            server_config.timers.clone(),
+        ));
+        track_started(&health, HEALTH_QUEUE_CONSUMER, timers.subscribe_started()).await;
+        timers.clone().spawn();
+        state
+            .engine
+            .throttles()
+            .set_queue(Arc::new(TimerThrottle::new(timers.clone())));
+        webhook_spill = Some(Arc::new(TimerSpill::new(timers)));
+        info!("  [✓] TimerScheduler: durable timers");
+        endpoint_repository = Some(db.endpoints.clone());
+
+        // Instances tell each other which cached rows changed
+        event_bus = Some(PgEventBus::new(db.pool.clone()));
+        info!("  [✓] Event bus: Postgres LISTEN/NOTIFY");
+
+        // Triggers paused on one instance pause on every instance
+        if let Some(events) = event_bus.clone() {
+            let health = state.engine.trigger_health().clone();
+            if let Err(e) = spawn_trigger_sync(events, health).await {
+                warn!("Failed to subscribe to trigger changes: {}", e);
+            }
+        }
+
+        // Concurrency groups serialize across every instance
+        state
+            .engine
+            .concurrency_groups()
+            .set_backend(Arc::new(PgConcurrencyLocks::new(db.pool.clone())));
+        info!("  [✓] Concurrency groups: Postgres advisory locks");
+
+        // Side-effect intents survive the crash they would reveal
+        if server_config.runtime.journal.enabled {
+            let journal = state.engine.journal();
+            journal.set_backend(Arc::new(PgSideEffectJournal::new(db.pool.clone())));
+            info!("  [✓] Side-effect journal: Postgres");
+            match journal.in_doubt(chrono::Utc::now()).await {
+                Ok(entries) if !entries.is_empty() => warn!(
+                    "  [!] Side-effect journal: {} side effects in doubt, see \
+                     /api/v1/journal/in-doubt",
+                    entries.len()
+                ),
+                Ok(_) => {}
+                Err(e) => warn!("  [✗] Side-effect journal: {}", e),
+            }
+        }
+
+        // Execution counts per workflow, as n8n's workflow_statistics
+        state.engine.statistics().set_backend(Arc::new(db.statistics.clone()));
+        info!("  [✓] Workflow statistics: Postgres");
+
+        // Values seen by Remove Duplicates, kept across executions
+        state.engine.processed_data().set_store(Arc::new(db.processed_data.clone()));
+        info!("  [✓] Processed data: Postgres");
+
+        // Project egress rules follow the workflow's owning project
+        state
+            .engine
+            .workflow_projects()
+            .set_lookup(Arc::new(db.workflows.clone()));
+
+        // $vars holds the global variables and the workflow project's
+        state.engine.workflow_variables().set_source(Arc::new(db.variables.clone()));
+        info!("  [✓] Variables: Postgres");
+
+        // Moving workflows between projects
+        transfers = Some(WorkflowTransfers::new(db.pool.clone()));
+
+        // Promoting credentials between instances
+        credential_transfers = Some(db.credentials.clone());
+
+        // Saved execution list filters per user
+        views = Some(ViewState {
+            users: db.users.clone(),
+            executions: db.executions.clone(),
+        });
+
+        // QA votes, notes and tags on executions
+        annotations = Some(AnnotationState {
+            annotations: db.annotations.clone(),
+            executions: db.executions.clone(),
+        });
+
+        // CPU and memory of measured executions per workflow
+        insights = Some(db.executions.clone());
+
+        // Compliance-separated projects in schemas of their own
+        if let Some(db_config) = server_config.db.as_ref().filter(|c| c.schema_isolation.enabled) {
+            project_schemas = Some(ProjectSchemas::new(db.clone(), db_config));
+            info!("  [✓] Project schemas: per-project isolation");
+        }
+
+        // Stale webhooks, expired OAuth states, orphaned binary data
+        if server_config.gc.enabled {
+            let collector = Arc::new(
+                GarbageCollector::new(db.pool.clone(), server_config.gc.clone())
+                    .with_binary_data(state.engine.binary_data().clone()),
+            );
+            collector.clone().spawn();
+            gc = Some(collector);
+            info!("  [✓] GarbageCollector: stale and orphaned resource cleanup");
+        }
+
+        // Old executions: soft-delete, then hard-delete after a buffer
+        if server_config.pruning.enabled {
+            let pruning = PruningService::new(db.pool.clone(), server_config.pruning.clone())
+                .with_leader(leader.subscribe());
+            Arc::new(pruning).spawn();
+            info!("  [✓] Execution pruning: age and count limits");
+        }
+
+        health.register(HEALTH_DB, true).await;
+        health.register(HEALTH_DB_MIGRATIONS, true).await;
+        health.register(HEALTH_QUEUE, true).await;
+
+        let ping_db = db.clone();
+        health.spawn_probe(HEALTH_DB, health_interval, move || {
+            let db = ping_db.clone();
+            async move { db.ping().await.map_err(|e| e.to_string()) }
+        });
+        // The durable timer queue webhooks spill to and throttles defer to
+        let queue = db.timers.clone();
+        health.spawn_probe(HEALTH_QUEUE, health_interval, move || {
+            let queue = queue.clone();
+            async move {
+                queue
+                    .find_upcoming(chrono::Utc::now(), 1)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| format!("Timer queue unreachable: {}", e))
+            }
+        });
+        health.spawn_probe(HEALTH_DB_MIGRATIONS, health_interval, move || {
+            let db = db.clone();
+            async move {
+                match db.pending_migrations().await {
+                    Ok(pending) if pending.is_empty() => Ok(()),
+                    Ok(pending) => Err(format!("Pending migrations: {:?}", pending)),
+                    Err(e) => Err(e.to_string()),
+                }
+            }
+        });
+    }
+
+    // Redis (optional)
+    if let Some(redis_url) = server_config.queue.redis_url.clone() {
+        health.register(HEALTH_REDIS, true).await;
+        health.spawn_probe(HEALTH_REDIS, health_interval, move || {
+            let url = redis_url.clone();
+            async move { redis_ping(&url, Duration::from_secs(2)).await }
+        });
+    }
+
     // Start transports
     let mut handles = vec![];
 
@@ -60,11 +446,151 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // Create the n8n-compatible API state and router
         let execution_store = Arc::new(ExecutionStore::new());
-        let api_state = ApiState::new(state.workflows.clone(), execution_store);
+        let api_state = ApiState::new(state.workflows.clone(), execution_store.clone())
+            .with_linter(lint::build_linter(&server_config.lint))
+            .with_integrity(server_config.runtime.integrity.clone())
+            .with_timezone(server_config.runtime.timezone.clone())
+            .with_progress(state.engine.progress().clone())
+            .with_trigger_health(state.engine.trigger_health().clone());
         let api_router = create_api_router(api_state);
 
-        // Merge routers: API endpoints + negotiation endpoints
-        let router = api_router.merge(negotiation_router);
+        // Live execution counters drift when other instances write to the
+        // same storage; rebuild them periodically
+        if server_config.live_reconcile_secs > 0 {
+            let store = execution_store.clone();
+            let every = Duration::from_secs(server_config.live_reconcile_secs);
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(every);
+                loop {
+                    ticker.tick().await;
+                    match store.reconcile_live().await {
+                        Ok(0) => {}
+                        Ok(drift) => info!("Reconciled {} live execution counters", drift),
+                        Err(e) => warn!("Live counter reconciliation failed: {}", e),
+                    }
+                }
+            });
+        }
+
+        // Production webhooks, subject to workflow run windows and
+        // admission control
+        let mut webhook_state =
+            WebhookState::new(state.workflows.clone(), execution_store, state.engine.clone())
+                .with_admission(server_config.webhooks.clone());
+        if let Some(spill) = webhook_spill.clone() {
+            webhook_state = webhook_state.with_spill(spill);
+        }
+        let webhook_router = create_webhook_router(webhook_state.clone());
+
+        // Schedule triggers of active workflows, fired like webhooks
+        let mut schedules = ScheduleRunner::new(
+            state.workflows.clone(),
+            Arc::new(webhook_state.clone()),
+            server_config.runtime.timezone.clone(),
+        );
+        if let Some((leader, _)) = &election {
+            schedules = schedules.with_leader(leader.subscribe());
+        }
+        Arc::new(schedules).spawn();
+        info!("  [✓] ScheduleRunner: schedule triggers of active workflows");
+
+        // Manual runs with declared input parameters
+        let run_router = create_run_router(webhook_state.clone());
+
+        // Workflows published as REST endpoints; without a database no API
+        // keys exist, so endpoints can be published but not called
+        let endpoint_state = match endpoint_repository {
+            Some(repository) => {
+                let keys = Arc::new(DbApiKeys::new(repository.clone()));
+                let mut store = DbEndpointStore::new(repository);
+                if let Some(events) = event_bus.clone() {
+                    store = store.with_events(events);
+                }
+                EndpointState::new(webhook_state.clone(), keys).with_store(Arc::new(store))
+            }
+            None => EndpointState::new(webhook_state.clone(), Arc::new(StaticApiKeys::default())),
+        };
+        match endpoint_state.load().await {
+            Ok(count) => info!("  [✓] Endpoints: {} workflow endpoints", count),
+            Err(e) => warn!("Failed to load workflow endpoints: {}", e),
+        }
+        if let Some(events) = event_bus.clone() {
+            if let Err(e) = spawn_endpoint_sync(events, endpoint_state.clone()).await {
+                warn!("Failed to subscribe to endpoint changes: {}", e);
+            }
+        }
+        let endpoint_router = create_endpoint_router(endpoint_state);
+
+        // Deliveries of webhook delivery nodes, with manual redelivery
+        let deliveries_router = create_deliveries_router(state.engine.clone());
+
+        // Test sets replaying past executions against workflow versions
+        let evaluations_router =
+            create_evaluations_router(EvaluationState::new(webhook_state.clone()));
+
+        // Inbound email trigger, sharing webhook admission control
+        if server_config.smtp.enabled {
+            let smtp_addr: SocketAddr = server_config.smtp.addr.parse()?;
+            let smtp_state = SmtpState::new(webhook_state.clone(), server_config.smtp.clone());
+            info!("Starting SMTP trigger on {}", smtp_addr);
+            let handle = tokio::spawn(async move {
+                let listener = tokio::net::TcpListener::bind(smtp_addr).await?;
+                serve_smtp(smtp_state, listener).await?;
+                Ok::<_, anyhow::Error>(())
+            });
+            handles.push(("SMTP", handle));
+        }
+
+        // Liveness/readiness for orchestration platforms
+        let readiness_router = create_readiness_router(ReadinessState::new(health.clone()));
+
+        // Merge routers: API endpoints + negotiation endpoints + admin,
+        // rate limited; health probes are never limited
+        let limiter = RateLimiter::new(reloader.rate_limit_updates());
+        let mut api_router = api_router
+            .merge(webhook_router)
+            .merge(run_router)
+            .merge(endpoint_router)
+            .merge(deliveries_router)
+            .merge(evaluations_router);
+        if let Some(gc) = gc.clone() {
+            api_router = api_router.merge(create_gc_router(gc));
+        }
+        if let Some(transfers) = transfers.clone() {
+            api_router = api_router.merge(create_transfer_router(transfers));
+        }
+        if let Some(credentials) = credential_transfers.clone() {
+            api_router = api_router.merge(create_credential_transfer_router(credentials));
+        }
+        if let Some(views) = views.clone() {
+            api_router = api_router.merge(create_views_router(views));
+        }
+        if let Some(annotations) = annotations.clone() {
+            api_router = api_router.merge(create_annotations_router(annotations));
+        }
+        if server_config.runtime.journal.enabled {
+            api_router = api_router.merge(create_journal_router(state.engine.journal().clone()));
+        }
+        if let Some(executions) = insights.clone() {
+            api_router = api_router.merge(create_insights_router(executions));
+        }
+        if server_config.generation.enabled {
+            let provider = OpenAiCompatibleProvider::new(server_config.generation.clone());
+            let generator = WorkflowGenerator::new(Arc::new(provider))
+                .with_linter(lint::build_linter(&server_config.lint));
+            api_router = api_router.merge(create_generation_router(Arc::new(generator)));
+            info!("  [✓] Workflow generation: {}", server_config.generation.model);
+        }
+        if let Some(schemas) = project_schemas.clone() {
+            api_router = api_router.merge(create_schemas_router(schemas));
+        }
+        let router = api_router
+            .merge(negotiation_router)
+            .merge(create_openapi_router())
+            .merge(create_reload_router(reloader.clone()))
+            .layer(axum::middleware::from_fn_with_state(limiter, rate_limit))
+            .merge(readiness_router)
+            .layer(axum::middleware::from_fn(correlate));
 
         info!("Starting REST API on http://{}", rest_addr);
         let handle = tokio::spawn(async move {
@@ -78,8 +604,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // gRPC
     if config.grpc_enabled {
         let grpc_addr: SocketAddr = config.grpc_addr.parse()?;
+        let reflection = reflection_service()?;
+
         info!("Starting gRPC on {}", grpc_addr);
-        // In full implementation: tonic server would start here
+        let handle = tokio::spawn(async move {
+            tonic::transport::Server::builder()
+                .add_service(health_service)
+                .add_service(reflection)
+                .add_service(WorkflowServiceServer::new(workflow_service))
+                .serve(grpc_addr)
+                .await?;
+            Ok::<_, anyhow::Error>(())
+        });
+        handles.push(("gRPC", handle));
     }
 
     // Arrow Flight
@@ -89,6 +626,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         // In full implementation: Flight server would start here
     }
 
+    // Shared memory for co-located crew/ladybug processes
+    if config.shm_enabled {
+        #[cfg(target_os = "linux")]
+        {
+            let listener = n8n_grpc::bind_shm(&config.shm_path)?;
+            let data = arrow_service.clone();
+            info!("Starting shared-memory transport on {}", config.shm_path);
+            let handle = tokio::spawn(async move {
+                n8n_grpc::serve_shm(data, listener).await?;
+                Ok::<_, anyhow::Error>(())
+            });
+            handles.push(("Shared memory", handle));
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = &arrow_service;
+            warn!("Shared-memory transport is only available on Linux");
+        }
+    }
+
     // STDIO (if requested)
     if config.stdio_enabled {
         info!("STDIO transport enabled (reading from stdin)");
@@ -120,6 +677,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         _ = tokio::signal::ctrl_c() => {
             info!("");
             info!("Shutdown signal received...");
+            health.shutdown().await;
+            if let Some((leader, renewal)) = &election {
+                renewal.abort();
+                if let Err(e) = leader.resign().await {
+                    warn!("Failed to resign leadership: {}", e);
+                }
+            }
         }
         _ = async {
             for (name, handle) in handles {
@@ -130,30 +694,43 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         } => {}
     }
 
+    #[cfg(feature = "dev")]
+    if let Some(postgres) = embedded_postgres {
+        if let Err(e) = postgres.stop().await {
+            warn!("Failed to stop the embedded PostgreSQL: {}", e);
+        }
+    }
+
     info!("Goodbye!");
     Ok(())
 }
 
-fn parse_config() -> TransportConfig {
-    TransportConfig {
-        rest_enabled: std::env::var("N8N_REST_ENABLED")
-            .map(|v| v != "0" && v.to_lowercase() != "false")
-            .unwrap_or(true),
-        rest_addr: std::env::var("N8N_REST_ADDR")
-            .unwrap_or_else(|_| "0.0.0.0:8080".to_string()),
-        grpc_enabled: std::env::var("N8N_GRPC_ENABLED")
-            .map(|v| v != "0" && v.to_lowercase() != "false")
-            .unwrap_or(true),
-        grpc_addr: std::env::var("N8N_GRPC_ADDR")
-            .unwrap_or_else(|_| "0.0.0.0:50051".to_string()),
-        flight_enabled: std::env::var("N8N_FLIGHT_ENABLED")
-            .map(|v| v != "0" && v.to_lowercase() != "false")
-            .unwrap_or(true),
-        flight_addr: std::env::var("N8N_FLIGHT_ADDR")
-            .unwrap_or_else(|_| "0.0.0.0:50052".to_string()),
-        stdio_enabled: std::env::var("N8N_STDIO_ENABLED")
-            .map(|v| v == "1" || v.to_lowercase() == "true")
-            .unwrap_or(false),
+/// Connect to the database if one is configured.
+async fn connect_db(config: Option<&DbConfig>) -> Option<DbContext> {
+    let config = config?;
+    match config.connect().await {
+        Ok(pool) => {
+            info!("  [✓] Database: connected");
+            let db = DbContext::new(pool).with_compression(config.compression.clone());
+            if config.compression.algorithm != CompressionAlgorithm::None {
+                info!(
+                    "  [✓] Execution data: {} above {} bytes",
+                    config.compression.algorithm.format(),
+                    config.compression.threshold_bytes
+                );
+            }
+            match &config.encryption_key {
+                Some(key) => {
+                    info!("  [✓] Credentials: encrypted at rest");
+                    Some(db.with_encryption(CredentialService::new(key)))
+                }
+                None => Some(db),
+            }
+        }
+        Err(e) => {
+            warn!("  [✗] Database: {}", e);
+            None
+        }
     }
 }
 
@@ -172,21 +749,39 @@ fn print_endpoints(config: &TransportConfig) {
     info!("Available endpoints:");
     if config.rest_enabled {
         info!("  REST:    http://{}", config.rest_addr);
+        info!("           Health:");
+        info!("             GET    /healthz   (liveness)");
+        info!("             GET    /readyz    (readiness)");
         info!("           Workflows:");
         info!("             GET    /api/v1/workflows");
         info!("             POST   /api/v1/workflows");
         info!("             GET    /api/v1/workflows/:id");
         info!("             PUT    /api/v1/workflows/:id");
+        info!("             PATCH  /api/v1/workflows/:id");
+        info!("             GET    /api/v1/workflows/:id/history");
         info!("             DELETE /api/v1/workflows/:id");
         info!("             POST   /api/v1/workflows/:id/activate");
         info!("             POST   /api/v1/workflows/:id/deactivate");
+        info!("             GET    /api/v1/workflows/:id/schedule");
+        info!("             GET    /api/v1/workflows/:id/triggers");
+        info!("             POST   /api/v1/workflows/:id/triggers/:node/resume");
+        info!("             POST   /api/v1/workflows/generate");
+        info!("             POST   /api/v1/workflows/:id/run-batch");
+        info!("             GET    /api/v1/batches/:id");
         info!("           Executions:");
         info!("             GET    /api/v1/executions");
         info!("             POST   /api/v1/executions");
         info!("             GET    /api/v1/executions/:id");
         info!("             DELETE /api/v1/executions/:id");
         info!("             POST   /api/v1/executions/:id/stop");
+        info!("             GET    /api/v1/executions/:id/status");
         info!("             POST   /api/v1/executions/:id/retry");
+        info!("           Admin:");
+        info!("             POST   /api/v1/admin/reload");
+        info!("             GET    /api/v1/admin/gc");
+        info!("             POST   /api/v1/admin/gc");
+        info!("             GET    /api/v1/admin/gc/blobs");
+        info!("             GET    /api/v1/interfaces");
         info!("           Negotiation:");
         info!("             GET    /api/v1/capabilities");
         info!("             POST   /api/v1/negotiate");
@@ -194,6 +789,7 @@ fn print_endpoints(config: &TransportConfig) {
     }
     if config.grpc_enabled {
         info!("  gRPC:    grpc://{}", config.grpc_addr);
+        info!("           n8n.WorkflowService, grpc.health.v1.Health, grpc.reflection.v1");
     }
     if config.flight_enabled {
         info!("  Flight:  flight://{}", config.flight_addr);
@@ -201,6 +797,9 @@ fn print_endpoints(config: &TransportConfig) {
     if config.stdio_enabled {
         info!("  STDIO:   stdin/stdout (NDJSON)");
     }
+    if config.shm_enabled {
+        info!("  SHM:     unix://{} (Arrow IPC in shared memory)", config.shm_path);
+    }
     info!("");
 }
 
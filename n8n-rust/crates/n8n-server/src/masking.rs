@@ -0,0 +1,68 @@
+//! Secret masking for log output.
+//!
+//! Log lines are buffered per event and written through
+//! [`SecretMask::mask`], so decrypted credentials and auth headers that end
+//! up in a log message or field never reach stdout.
+
+use n8n_core::SecretMask;
+use std::io::{self, Write};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// [`MakeWriter`] that masks each log line before writing it to stdout.
+/// Without a mask lines are written unchanged.
+#[derive(Clone)]
+pub struct MaskingMakeWriter {
+    mask: Option<SecretMask>,
+}
+
+impl MaskingMakeWriter {
+    pub fn new(mask: Option<SecretMask>) -> Self {
+        Self { mask }
+    }
+}
+
+impl<'a> MakeWriter<'a> for MaskingMakeWriter {
+    type Writer = MaskingWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        MaskingWriter {
+            mask: self.mask.clone(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+/// Buffers one log event and writes it masked when dropped.
+pub struct MaskingWriter {
+    mask: Option<SecretMask>,
+    buffer: Vec<u8>,
+}
+
+impl Write for MaskingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let mut stdout = io::stdout().lock();
+        match &self.mask {
+            Some(mask) => {
+                let line = String::from_utf8_lossy(&self.buffer);
+                stdout.write_all(mask.mask(&line).as_bytes())?;
+            }
+            None => stdout.write_all(&self.buffer)?,
+        }
+        self.buffer.clear();
+        stdout.flush()
+    }
+}
+
+impl Drop for MaskingWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
@@ -0,0 +1,248 @@
+//! Runtime configuration reload without restart.
+//!
+//! On `SIGHUP` or `POST /api/v1/admin/reload` the configuration is resolved
+//! again from the same layered sources. Settings that are safe to change at
+//! runtime are published on watch channels:
+//!
+//! - `log_level` → the tracing filter
+//! - `rate_limit_rps` → the REST rate limiter
+//! - `runtime` (concurrency caps, timeouts) → the workflow engine; running
+//!   executions keep the settings they started with
+//! - `interfaces_file` → the interface gateway
+//!
+//! Changes to anything else (transport addresses, database, queue, timers,
+//! garbage collection, log format, lint rules) are
+//! reported as requiring a restart and are not applied.
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use n8n_contract::{InterfaceDefinition, InterfaceGateway};
+use n8n_core::RuntimeConfig;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::{watch, Mutex};
+use tracing::{info, warn};
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+use crate::config::{Cli, ConfigError, ServerConfig};
+
+/// Handle for swapping the active log filter.
+pub type LogReloadHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Top-level settings that only take effect after a restart.
+const RESTART_REQUIRED: &[&str] = &[
+    "transport",
+    "db",
+    "queue",
+    "timers",
+    "gc",
+    "pruning",
+    "binary_data",
+    "webhooks",
+    "smtp",
+    "health_interval_secs",
+    "live_reconcile_secs",
+    "log_format",
+    "lint",
+    "generation",
+    "dev",
+];
+
+/// Outcome of a reload.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadReport {
+    /// Settings that changed and were applied.
+    pub applied: Vec<String>,
+    /// Settings that changed but need a restart.
+    pub requires_restart: Vec<String>,
+    /// Interface definitions now registered in the gateway.
+    pub interfaces: usize,
+}
+
+/// Re-resolves configuration and publishes safe-to-change settings.
+pub struct Reloader {
+    cli: Cli,
+    current: Mutex<ServerConfig>,
+    log_filter: LogReloadHandle,
+    runtime: watch::Sender<RuntimeConfig>,
+    rate_limit: watch::Sender<u32>,
+    gateway: watch::Sender<Arc<InterfaceGateway>>,
+}
+
+impl Reloader {
+    /// Create a reloader seeded with the startup configuration.
+    pub fn new(
+        cli: Cli,
+        config: ServerConfig,
+        log_filter: LogReloadHandle,
+    ) -> Result<Self, ConfigError> {
+        let gateway = load_gateway(config.interfaces_file.as_deref())?;
+
+        Ok(Self {
+            cli,
+            runtime: watch::channel(config.runtime.clone()).0,
+            rate_limit: watch::channel(config.rate_limit_rps).0,
+            gateway: watch::channel(Arc::new(gateway)).0,
+            current: Mutex::new(config),
+            log_filter,
+        })
+    }
+
+    /// Subscribe to runtime configuration updates.
+    pub fn runtime_updates(&self) -> watch::Receiver<RuntimeConfig> {
+        self.runtime.subscribe()
+    }
+
+    /// Subscribe to REST rate limit updates.
+    pub fn rate_limit_updates(&self) -> watch::Receiver<u32> {
+        self.rate_limit.subscribe()
+    }
+
+    /// Subscribe to interface gateway updates.
+    pub fn gateway_updates(&self) -> watch::Receiver<Arc<InterfaceGateway>> {
+        self.gateway.subscribe()
+    }
+
+    /// Reload configuration and apply safe-to-change settings.
+    ///
+    /// Nothing is applied if the new configuration fails to load or validate.
+    pub async fn reload(&self) -> Result<ReloadReport, ConfigError> {
+        let next = ServerConfig::load(&self.cli)?;
+        let gateway = load_gateway(next.interfaces_file.as_deref())?;
+        let filter = EnvFilter::try_new(&next.log_level).map_err(|e| ConfigError::InvalidValue {
+            key: "log_level".into(),
+            message: e.to_string(),
+        })?;
+
+        let mut current = self.current.lock().await;
+        let mut report = ReloadReport {
+            applied: Vec::new(),
+            requires_restart: Vec::new(),
+            interfaces: gateway.count(),
+        };
+
+        for key in RESTART_REQUIRED {
+            if section(&current, key) != section(&next, key) {
+                report.requires_restart.push(key.to_string());
+            }
+        }
+
+        if next.log_level != current.log_level {
+            self.log_filter
+                .reload(filter)
+                .map_err(|e| ConfigError::InvalidValue {
+                    key: "log_level".into(),
+                    message: e.to_string(),
+                })?;
+            report.applied.push("log_level".to_string());
+        }
+
+        if next.rate_limit_rps != current.rate_limit_rps {
+            self.rate_limit.send_replace(next.rate_limit_rps);
+            report.applied.push("rate_limit_rps".to_string());
+        }
+
+        if section(&current, "runtime") != section(&next, "runtime") {
+            self.runtime.send_replace(next.runtime.clone());
+            report.applied.push("runtime".to_string());
+        }
+
+        // The file contents may have changed even if the path did not.
+        self.gateway.send_replace(Arc::new(gateway));
+        report.applied.push("interfaces".to_string());
+
+        for key in &report.requires_restart {
+            warn!("Config reload: '{}' changed but requires a restart", key);
+        }
+        info!("Config reload applied: {}", report.applied.join(", "));
+
+        *current = next;
+        Ok(report)
+    }
+}
+
+fn section(config: &ServerConfig, key: &str) -> Option<serde_json::Value> {
+    serde_json::to_value(config).ok()?.get(key).cloned()
+}
+
+/// Build the interface gateway from the defaults plus an optional file.
+pub fn load_gateway(path: Option<&Path>) -> Result<InterfaceGateway, ConfigError> {
+    let mut gateway = InterfaceGateway::default();
+
+    if let Some(path) = path {
+        let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let defs: Vec<InterfaceDefinition> =
+            serde_yaml::from_str(&contents).map_err(|e| ConfigError::Parse {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            })?;
+        for def in defs {
+            gateway.register(def);
+        }
+    }
+
+    Ok(gateway)
+}
+
+/// Reload on `SIGHUP`.
+#[cfg(unix)]
+pub fn spawn_sighup_listener(reloader: Arc<Reloader>) -> std::io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            info!("SIGHUP received, reloading configuration");
+            if let Err(e) = reloader.reload().await {
+                warn!("Config reload failed: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Signals are not available; reload via the API only.
+#[cfg(not(unix))]
+pub fn spawn_sighup_listener(_reloader: Arc<Reloader>) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Router for the reload and interface endpoints.
+pub fn create_reload_router(reloader: Arc<Reloader>) -> Router {
+    Router::new()
+        .route("/api/v1/admin/reload", post(reload_config))
+        .route("/api/v1/interfaces", get(list_interfaces))
+        .with_state(reloader)
+}
+
+async fn reload_config(
+    State(reloader): State<Arc<Reloader>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    match reloader.reload().await {
+        Ok(report) => (StatusCode::OK, Json(serde_json::json!(report))),
+        Err(e) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({ "code": 422, "message": e.to_string() })),
+        ),
+    }
+}
+
+async fn list_interfaces(State(reloader): State<Arc<Reloader>>) -> Json<Vec<InterfaceDefinition>> {
+    let gateway = reloader.gateway_updates().borrow().clone();
+    let mut defs: Vec<InterfaceDefinition> = gateway
+        .interface_ids()
+        .into_iter()
+        .filter_map(|id| gateway.get(id).cloned())
+        .collect();
+    defs.sort_by(|a, b| a.id.cmp(&b.id));
+    Json(defs)
+}
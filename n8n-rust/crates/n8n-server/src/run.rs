@@ -0,0 +1,54 @@
+//! `run` subcommand: execute one workflow read from stdin.
+//!
+//! ```text
+//! n8n-server run < workflow.json > run.json
+//! echo '{"workflow": {...}, "input": [{"json": {"id": 1}}]}' | n8n-server run
+//! ```
+//!
+//! Stdin holds a workflow, or an object with the `workflow` and the
+//! `input` items its start nodes receive. The workflow runs once with
+//! in-memory storage and the `Run` is written to stdout as JSON. Exits
+//! non-zero when the execution did not succeed, so it can gate CI jobs
+//! and cron scripts.
+
+use n8n_core::{MemoryWorkflowStorage, RuntimeConfig, WorkflowEngine, WorkflowStorage};
+use n8n_workflow::{ExecutionStatus, NodeExecutionData, Workflow, WorkflowExecuteMode};
+use serde::Deserialize;
+use std::io::Read;
+use std::sync::Arc;
+
+/// What stdin may hold.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RunInput {
+    WithInput {
+        workflow: serde_json::Value,
+        #[serde(default)]
+        input: Option<Vec<NodeExecutionData>>,
+    },
+    Workflow(serde_json::Value),
+}
+
+/// Execute the workflow on stdin and print its run. Returns whether it
+/// succeeded.
+pub async fn run(config: &RuntimeConfig) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut stdin = String::new();
+    std::io::stdin().read_to_string(&mut stdin)?;
+    let (mut workflow, input) = match serde_json::from_str(&stdin)? {
+        RunInput::WithInput { workflow, input } => (workflow, input),
+        RunInput::Workflow(workflow) => (workflow, None),
+    };
+    if let Some(object) = workflow.as_object_mut() {
+        object.entry("id").or_insert_with(|| "stdin".into());
+    }
+    let workflow: Workflow = serde_json::from_value(workflow)?;
+
+    // Sub-workflows can only refer to the workflow itself.
+    let workflows = Arc::new(MemoryWorkflowStorage::new());
+    workflows.save_workflow(&workflow).await?;
+    let engine = WorkflowEngine::new(config.clone()).with_sub_workflows(workflows);
+
+    let run = engine.execute(&workflow, WorkflowExecuteMode::Manual, input).await?;
+    println!("{}", serde_json::to_string_pretty(&run)?);
+    Ok(run.status == ExecutionStatus::Success)
+}
@@ -0,0 +1,61 @@
+//! Admin endpoints for per-project schemas.
+//!
+//! - `GET /api/v1/admin/project-schemas` lists the shared and every project
+//!   schema with the migrations each still lacks.
+//! - `POST /api/v1/admin/project-schemas/migrate` migrates them all; see
+//!   [`n8n_db::isolation`].
+//! - `POST /api/v1/projects/:id/schema` moves an empty project into a
+//!   schema of its own.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use n8n_db::{DbError, ProjectSchema, ProjectSchemas, SchemaStatus};
+use serde_json::{json, Value};
+
+type ApiResult<T> = Result<T, (StatusCode, Json<Value>)>;
+
+/// Router for the project schema endpoints.
+pub fn create_schemas_router(schemas: ProjectSchemas) -> Router {
+    Router::new()
+        .route("/api/v1/admin/project-schemas", get(schema_status))
+        .route("/api/v1/admin/project-schemas/migrate", post(migrate_schemas))
+        .route("/api/v1/projects/:id/schema", post(isolate_project))
+        .with_state(schemas)
+}
+
+fn db_error(e: DbError) -> (StatusCode, Json<Value>) {
+    let status = match e {
+        DbError::NotFound => StatusCode::NOT_FOUND,
+        DbError::InvalidData(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    let message = match e {
+        DbError::NotFound => "Project not found".to_string(),
+        e => e.to_string(),
+    };
+    (status, Json(json!({ "code": status.as_u16(), "message": message })))
+}
+
+async fn schema_status(
+    State(schemas): State<ProjectSchemas>,
+) -> ApiResult<Json<Vec<SchemaStatus>>> {
+    schemas.status().await.map(Json).map_err(db_error)
+}
+
+async fn migrate_schemas(
+    State(schemas): State<ProjectSchemas>,
+) -> ApiResult<Json<Vec<SchemaStatus>>> {
+    schemas.migrate_all().await.map(Json).map_err(db_error)
+}
+
+async fn isolate_project(
+    State(schemas): State<ProjectSchemas>,
+    Path(id): Path<String>,
+) -> ApiResult<(StatusCode, Json<ProjectSchema>)> {
+    let schema = schemas.isolate(&id).await.map_err(db_error)?;
+    Ok((StatusCode::CREATED, Json(schema)))
+}
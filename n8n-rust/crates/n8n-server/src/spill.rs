@@ -0,0 +1,85 @@
+//! Durable spill queue for must-not-drop webhooks.
+//!
+//! Requests refused under backpressure are stored as durable timers carrying
+//! the request item; the timer scheduler replays them from the webhook node
+//! once due, on whichever instance claims the timer. Items a throttle node
+//! holds back wait the same way for their slot.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use n8n_core::{ThrottleQueue, THROTTLED_ITEMS_KEY};
+use n8n_db::{InsertTimer, TimerHandler, TimerScheduler, SPILLED_REQUEST_KEY};
+use n8n_grpc::WebhookSpill;
+use n8n_workflow::{object_to_json, NodeExecutionData, Workflow};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// [`WebhookSpill`] backed by the durable timer scheduler.
+pub struct TimerSpill<H> {
+    scheduler: Arc<TimerScheduler<H>>,
+}
+
+impl<H> TimerSpill<H> {
+    pub fn new(scheduler: Arc<TimerScheduler<H>>) -> Self {
+        Self { scheduler }
+    }
+}
+
+#[async_trait]
+impl<H: TimerHandler + 'static> WebhookSpill for TimerSpill<H> {
+    async fn spill(
+        &self,
+        workflow: &Workflow,
+        node: &str,
+        item: NodeExecutionData,
+        run_at: DateTime<Utc>,
+    ) -> Result<(), String> {
+        let request = Value::Object(object_to_json(&item.json));
+        let mut payload = Map::new();
+        payload.insert(SPILLED_REQUEST_KEY.to_string(), request);
+
+        let timer =
+            InsertTimer::new(&workflow.id, node, run_at).with_payload(Value::Object(payload));
+        self.scheduler
+            .schedule(timer)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// [`ThrottleQueue`] backed by the durable timer scheduler.
+pub struct TimerThrottle<H> {
+    scheduler: Arc<TimerScheduler<H>>,
+}
+
+impl<H> TimerThrottle<H> {
+    pub fn new(scheduler: Arc<TimerScheduler<H>>) -> Self {
+        Self { scheduler }
+    }
+}
+
+#[async_trait]
+impl<H: TimerHandler + 'static> ThrottleQueue for TimerThrottle<H> {
+    async fn defer(
+        &self,
+        workflow_id: &str,
+        node: &str,
+        items: Vec<NodeExecutionData>,
+        at: DateTime<Utc>,
+    ) -> Result<(), String> {
+        let items = items
+            .iter()
+            .map(|item| Value::Object(object_to_json(&item.json)))
+            .collect();
+        let mut payload = Map::new();
+        payload.insert(THROTTLED_ITEMS_KEY.to_string(), Value::Array(items));
+
+        let timer = InsertTimer::new(workflow_id, node, at).with_payload(Value::Object(payload));
+        self.scheduler
+            .schedule(timer)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,50 @@
+//! Workflow transfer endpoint.
+//!
+//! `POST /api/v1/workflows/:id/transfer` moves a workflow to another
+//! project (`projectId`) or to a user's personal project (`userId`); see
+//! [`n8n_db::transfer`]. The body may set `executionHistory` (`migrate` or
+//! `detach`), `dryRun` and `force`. The response is the transfer report;
+//! a move refused because of inaccessible credentials answers `409` with
+//! the report listing them.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::post,
+    Json, Router,
+};
+use n8n_db::{DbError, TransferReport, TransferRequest, WorkflowTransfers};
+use serde_json::{json, Value};
+
+/// Router for the workflow transfer endpoint.
+pub fn create_transfer_router(transfers: WorkflowTransfers) -> Router {
+    Router::new()
+        .route("/api/v1/workflows/:id/transfer", post(transfer_workflow))
+        .with_state(transfers)
+}
+
+async fn transfer_workflow(
+    State(transfers): State<WorkflowTransfers>,
+    Path(id): Path<String>,
+    Json(request): Json<TransferRequest>,
+) -> Result<(StatusCode, Json<TransferReport>), (StatusCode, Json<Value>)> {
+    let report = transfers.transfer(&id, &request).await.map_err(|e| {
+        let status = match e {
+            DbError::NotFound => StatusCode::NOT_FOUND,
+            DbError::InvalidData(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let message = match e {
+            DbError::NotFound => format!("Workflow {} not found", id),
+            e => e.to_string(),
+        };
+        (status, Json(json!({ "code": status.as_u16(), "message": message })))
+    })?;
+
+    let status = if report.applied || report.dry_run {
+        StatusCode::OK
+    } else {
+        StatusCode::CONFLICT
+    };
+    Ok((status, Json(report)))
+}
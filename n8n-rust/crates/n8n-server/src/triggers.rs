@@ -0,0 +1,71 @@
+//! Trigger pauses shared between instances.
+//!
+//! Each instance counts the failures of the triggers it runs. When one
+//! pauses or resumes a trigger it announces it on the event bus, and the
+//! other instances pause or resume theirs, so a poison-pill trigger stops
+//! everywhere.
+
+use futures::StreamExt;
+use n8n_core::{TriggerEvent, TriggerHealth};
+use n8n_db::{ChangeOperation, ChangedEntity, PgEventBus};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+/// Event bus ID of `node` of `workflow_id`. Workflow IDs hold no `:`.
+fn trigger_id(workflow_id: &str, node: &str) -> String {
+    format!("{}:{}", workflow_id, node)
+}
+
+/// Announce `health`'s pauses and resumes on `events`, and apply those of
+/// other instances.
+pub async fn spawn_trigger_sync(events: PgEventBus, health: TriggerHealth) -> Result<(), String> {
+    let mut changes = events.subscribe().await.map_err(|e| e.to_string())?;
+    let mut local = health.subscribe();
+
+    let remote = health.clone();
+    tokio::spawn(async move {
+        while let Some(change) = changes.next().await {
+            let change = match change {
+                Ok(change) if change.entity == ChangedEntity::Trigger => change,
+                Ok(_) => continue,
+                Err(e) => {
+                    warn!(error = %e, "Trigger pauses may have been missed");
+                    continue;
+                }
+            };
+            let Some((workflow_id, node)) = change.id.split_once(':') else {
+                continue;
+            };
+            let paused = match change.operation {
+                ChangeOperation::Paused => true,
+                ChangeOperation::Resumed => false,
+                _ => continue,
+            };
+            if remote.set_paused(workflow_id, node, paused) {
+                info!(workflow_id, node, paused, "Trigger changed on another instance");
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        loop {
+            let (operation, id) = match local.recv().await {
+                Ok(TriggerEvent::Paused(state)) => {
+                    (ChangeOperation::Paused, trigger_id(&state.workflow_id, &state.node))
+                }
+                Ok(TriggerEvent::Resumed { workflow_id, node }) => {
+                    (ChangeOperation::Resumed, trigger_id(&workflow_id, &node))
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(missed, "Trigger pauses not announced");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if let Err(e) = events.publish(ChangedEntity::Trigger, operation, &id).await {
+                warn!(trigger = %id, error = %e, "Failed to announce trigger change");
+            }
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,215 @@
+//! Saved execution views.
+//!
+//! Users keep named filters of the execution list (status, workflow, tags,
+//! date range) in their settings, with one of them as the default:
+//!
+//! - `GET /api/v1/users/:userId/execution-views` lists the views and the
+//!   default view's ID.
+//! - `POST /api/v1/users/:userId/execution-views` saves a new view.
+//! - `PUT` and `DELETE /api/v1/users/:userId/execution-views/:viewId`
+//!   replace or remove one.
+//! - `PUT /api/v1/users/:userId/execution-views/default` sets or clears
+//!   (`{"viewId": null}`) the default view.
+//! - `GET /api/v1/users/:userId/executions?view=` lists the executions a
+//!   view selects, the default view without `view`.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use chrono::Utc;
+use n8n_db::{
+    generate_nano_id, DbError, ExecutionEntity, ExecutionFilters, ExecutionRepository,
+    ExecutionView, UserRepository,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+type ApiResult<T> = Result<T, (StatusCode, Json<Value>)>;
+
+/// Repositories behind the view endpoints.
+#[derive(Clone)]
+pub struct ViewState {
+    pub users: UserRepository,
+    pub executions: ExecutionRepository,
+}
+
+/// Router for the saved execution view endpoints.
+pub fn create_views_router(state: ViewState) -> Router {
+    Router::new()
+        .route(
+            "/api/v1/users/:user_id/execution-views",
+            get(list_views).post(create_view),
+        )
+        .route("/api/v1/users/:user_id/execution-views/default", put(set_default_view))
+        .route(
+            "/api/v1/users/:user_id/execution-views/:view_id",
+            put(update_view).delete(delete_view),
+        )
+        .route("/api/v1/users/:user_id/executions", get(list_view_executions))
+        .with_state(state)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ViewList {
+    data: Vec<ExecutionView>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_view: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DefaultViewRequest {
+    view_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ViewExecutionsQuery {
+    view: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> (StatusCode, Json<Value>) {
+    (status, Json(json!({ "code": status.as_u16(), "message": message.into() })))
+}
+
+fn db_error(e: DbError) -> (StatusCode, Json<Value>) {
+    match e {
+        DbError::NotFound => error(StatusCode::NOT_FOUND, "User or view not found"),
+        DbError::InvalidData(message) => error(StatusCode::BAD_REQUEST, message),
+        e => error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn list_views(
+    State(state): State<ViewState>,
+    Path(user_id): Path<Uuid>,
+) -> ApiResult<Json<ViewList>> {
+    let user = state
+        .users
+        .find_by_id(user_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, format!("User {} not found", user_id)))?;
+    let settings = user.settings.unwrap_or_default();
+    Ok(Json(ViewList {
+        data: settings.execution_views,
+        default_view: settings.default_execution_view,
+    }))
+}
+
+async fn create_view(
+    State(state): State<ViewState>,
+    Path(user_id): Path<Uuid>,
+    Json(mut view): Json<ExecutionView>,
+) -> ApiResult<(StatusCode, Json<ExecutionView>)> {
+    view.validate().map_err(|m| error(StatusCode::BAD_REQUEST, m))?;
+    view.id = generate_nano_id();
+    let view = state
+        .users
+        .modify_settings(user_id, |settings| {
+            settings.execution_views.push(view.clone());
+            Ok(view)
+        })
+        .await
+        .map_err(db_error)?;
+    Ok((StatusCode::CREATED, Json(view)))
+}
+
+async fn update_view(
+    State(state): State<ViewState>,
+    Path((user_id, view_id)): Path<(Uuid, String)>,
+    Json(mut view): Json<ExecutionView>,
+) -> ApiResult<Json<ExecutionView>> {
+    view.validate().map_err(|m| error(StatusCode::BAD_REQUEST, m))?;
+    view.id = view_id;
+    let view = state
+        .users
+        .modify_settings(user_id, |settings| {
+            let saved = settings
+                .execution_views
+                .iter_mut()
+                .find(|v| v.id == view.id)
+                .ok_or(DbError::NotFound)?;
+            *saved = view.clone();
+            Ok(view)
+        })
+        .await
+        .map_err(db_error)?;
+    Ok(Json(view))
+}
+
+async fn delete_view(
+    State(state): State<ViewState>,
+    Path((user_id, view_id)): Path<(Uuid, String)>,
+) -> ApiResult<StatusCode> {
+    state
+        .users
+        .modify_settings(user_id, |settings| {
+            let count = settings.execution_views.len();
+            settings.execution_views.retain(|v| v.id != view_id);
+            if settings.execution_views.len() == count {
+                return Err(DbError::NotFound);
+            }
+            if settings.default_execution_view.as_deref() == Some(view_id.as_str()) {
+                settings.default_execution_view = None;
+            }
+            Ok(())
+        })
+        .await
+        .map_err(db_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn set_default_view(
+    State(state): State<ViewState>,
+    Path(user_id): Path<Uuid>,
+    Json(request): Json<DefaultViewRequest>,
+) -> ApiResult<StatusCode> {
+    state
+        .users
+        .modify_settings(user_id, |settings| {
+            if let Some(id) = &request.view_id {
+                if settings.execution_view(id).is_none() {
+                    return Err(DbError::InvalidData(format!("Unknown view {}", id)));
+                }
+            }
+            settings.default_execution_view = request.view_id;
+            Ok(())
+        })
+        .await
+        .map_err(db_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_view_executions(
+    State(state): State<ViewState>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<ViewExecutionsQuery>,
+) -> ApiResult<Json<Vec<ExecutionEntity>>> {
+    let user = state
+        .users
+        .find_by_id(user_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, format!("User {} not found", user_id)))?;
+    let settings = user.settings.unwrap_or_default();
+
+    let mut filters = match query.view.as_deref().or(settings.default_execution_view.as_deref()) {
+        Some(id) => settings
+            .execution_view(id)
+            .ok_or_else(|| error(StatusCode::NOT_FOUND, format!("View {} not found", id)))?
+            .filters(Utc::now()),
+        None => ExecutionFilters::default(),
+    };
+    filters.limit = query.limit;
+    filters.offset = query.offset;
+
+    let executions = state.executions.find_all(&filters).await.map_err(db_error)?;
+    Ok(Json(executions))
+}
@@ -13,6 +13,88 @@ pub const CONNECTION_AI_TOOL: &str = "ai_tool";
 pub const CONNECTION_AI_LANGUAGE_MODEL: &str = "ai_languageModel";
 pub const CONNECTION_AI_MEMORY: &str = "ai_memory";
 pub const CONNECTION_AI_OUTPUT_PARSER: &str = "ai_outputParser";
+pub const CONNECTION_AI_EMBEDDING: &str = "ai_embedding";
+pub const CONNECTION_AI_VECTOR_STORE: &str = "ai_vectorStore";
+pub const CONNECTION_AI_DOCUMENT: &str = "ai_document";
+pub const CONNECTION_AI_TEXT_SPLITTER: &str = "ai_textSplitter";
+pub const CONNECTION_AI_RETRIEVER: &str = "ai_retriever";
+pub const CONNECTION_AI_RERANKER: &str = "ai_reranker";
+pub const CONNECTION_AI_CHAIN: &str = "ai_chain";
+pub const CONNECTION_AI_AGENT: &str = "ai_agent";
+
+/// How a connection type links its nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionKind {
+    /// Items flow from the source node to the destination, which runs on
+    /// them (`main`, `error`).
+    Data,
+    /// The source is a sub-node supplying something, such as a language
+    /// model, a tool or a memory, to the destination root node, which
+    /// requests it while it runs. Sub-nodes never run on their own.
+    Supply,
+}
+
+/// Metadata of a connection type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionTypeInfo {
+    pub name: &'static str,
+    pub kind: ConnectionKind,
+}
+
+impl ConnectionTypeInfo {
+    const fn data(name: &'static str) -> Self {
+        Self {
+            name,
+            kind: ConnectionKind::Data,
+        }
+    }
+
+    const fn supply(name: &'static str) -> Self {
+        Self {
+            name,
+            kind: ConnectionKind::Supply,
+        }
+    }
+}
+
+/// The connection types n8n defines.
+pub const CONNECTION_TYPES: &[ConnectionTypeInfo] = &[
+    ConnectionTypeInfo::data(CONNECTION_MAIN),
+    ConnectionTypeInfo::data(CONNECTION_ERROR),
+    ConnectionTypeInfo::supply(CONNECTION_AI_AGENT),
+    ConnectionTypeInfo::supply(CONNECTION_AI_CHAIN),
+    ConnectionTypeInfo::supply(CONNECTION_AI_DOCUMENT),
+    ConnectionTypeInfo::supply(CONNECTION_AI_EMBEDDING),
+    ConnectionTypeInfo::supply(CONNECTION_AI_LANGUAGE_MODEL),
+    ConnectionTypeInfo::supply(CONNECTION_AI_MEMORY),
+    ConnectionTypeInfo::supply(CONNECTION_AI_OUTPUT_PARSER),
+    ConnectionTypeInfo::supply(CONNECTION_AI_RERANKER),
+    ConnectionTypeInfo::supply(CONNECTION_AI_RETRIEVER),
+    ConnectionTypeInfo::supply(CONNECTION_AI_TEXT_SPLITTER),
+    ConnectionTypeInfo::supply(CONNECTION_AI_TOOL),
+    ConnectionTypeInfo::supply(CONNECTION_AI_VECTOR_STORE),
+];
+
+/// Metadata of a known connection type.
+pub fn connection_type_info(name: &str) -> Option<&'static ConnectionTypeInfo> {
+    CONNECTION_TYPES.iter().find(|info| info.name == name)
+}
+
+/// How `connection_type` links its nodes. Unknown `ai_` types supply, as
+/// all of n8n's do; other unknown types carry data.
+pub fn connection_kind(connection_type: &str) -> ConnectionKind {
+    match connection_type_info(connection_type) {
+        Some(info) => info.kind,
+        None if connection_type.starts_with("ai_") => ConnectionKind::Supply,
+        None => ConnectionKind::Data,
+    }
+}
+
+/// Whether `connection_type` attaches a sub-node to a root node.
+pub fn is_supply_connection(connection_type: &str) -> bool {
+    connection_kind(connection_type) == ConnectionKind::Supply
+}
 
 /// Single connection endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
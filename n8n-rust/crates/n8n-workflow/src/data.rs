@@ -1,5 +1,9 @@
 //! Data types for workflow execution data.
 
+pub mod convert;
+
+pub use convert::{binaries_to_json, binary_to_json, item_to_json, object_from_json, object_to_json};
+
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -68,9 +72,14 @@ impl From<DataObject> for GenericValue {
 pub type DataObject = HashMap<String, GenericValue>;
 
 /// Binary data descriptor.
+///
+/// Small payloads travel inline as base64 in `data`. Payloads moved to a
+/// binary data store travel as a reference instead: `id` is
+/// `{mode}:{key}` and `data` holds just the mode, so items stay small
+/// however large the file.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BinaryData {
-    /// Base64 encoded data or file reference ID.
+    /// Base64 encoded data, or the storage mode of stored data.
     pub data: String,
     /// MIME type of the binary data.
     pub mime_type: String,
@@ -94,6 +103,18 @@ pub struct BinaryData {
     pub file_type: Option<BinaryFileType>,
 }
 
+impl BinaryData {
+    /// Whether the bytes are in a binary data store rather than inline.
+    pub fn is_stored(&self) -> bool {
+        self.id.is_some()
+    }
+
+    /// Storage mode and key of stored data.
+    pub fn reference(&self) -> Option<(&str, &str)> {
+        self.id.as_deref()?.split_once(':')
+    }
+}
+
 /// Categories of binary file types.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
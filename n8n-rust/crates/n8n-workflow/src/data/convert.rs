@@ -0,0 +1,216 @@
+//! Conversions between `serde_json::Value` and [`GenericValue`].
+//!
+//! Every conversion of item data goes through these, so values come out
+//! the same wherever they are converted:
+//!
+//! - generic to JSON and back gives the same value, except that NaN and
+//!   infinities become `null`, as `JSON.stringify` makes them.
+//! - JSON to generic and back gives the same value, except that integers
+//!   beyond `i64` become floats, the numbers they are in JavaScript.
+//! - Dates are strings in RFC 3339 with milliseconds in UTC, the form of
+//!   JavaScript's `Date.toJSON()`. [`GenericValue::from`] a `DateTime`
+//!   makes one; [`GenericValue::as_datetime`] reads any RFC 3339 string.
+//! - Binary data appears by reference, as [`binary_to_json`] describes it:
+//!   its metadata and, for stored data, the store's `id`, never the bytes.
+
+use super::{BinaryData, DataObject, GenericValue, NodeExecutionData};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+impl From<Value> for GenericValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(b) => Self::Bool(b),
+            Value::Number(n) => number(&n),
+            Value::String(s) => Self::String(s),
+            Value::Array(items) => Self::Array(items.into_iter().map(Self::from).collect()),
+            Value::Object(map) => Self::Object(object_from_json(map)),
+        }
+    }
+}
+
+impl From<&Value> for GenericValue {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(b) => Self::Bool(*b),
+            Value::Number(n) => number(n),
+            Value::String(s) => Self::String(s.clone()),
+            Value::Array(items) => Self::Array(items.iter().map(Self::from).collect()),
+            Value::Object(map) => Self::Object(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), Self::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<&GenericValue> for Value {
+    fn from(value: &GenericValue) -> Self {
+        match value {
+            GenericValue::Null => Value::Null,
+            GenericValue::Bool(b) => Value::Bool(*b),
+            GenericValue::Integer(i) => Value::Number((*i).into()),
+            GenericValue::Float(f) => Number::from_f64(*f).map_or(Value::Null, Value::Number),
+            GenericValue::String(s) => Value::String(s.clone()),
+            GenericValue::Array(items) => Value::Array(items.iter().map(Value::from).collect()),
+            GenericValue::Object(object) => Value::Object(object_to_json(object)),
+        }
+    }
+}
+
+impl From<GenericValue> for Value {
+    fn from(value: GenericValue) -> Self {
+        Value::from(&value)
+    }
+}
+
+impl From<DateTime<Utc>> for GenericValue {
+    fn from(at: DateTime<Utc>) -> Self {
+        Self::String(at.to_rfc3339_opts(SecondsFormat::Millis, true))
+    }
+}
+
+impl GenericValue {
+    /// The date of an RFC 3339 string, in UTC.
+    pub fn as_datetime(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::String(s) => DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|at| at.with_timezone(&Utc)),
+            _ => None,
+        }
+    }
+}
+
+/// An integer when it fits `i64`, a float otherwise.
+fn number(n: &Number) -> GenericValue {
+    match n.as_i64() {
+        Some(i) => GenericValue::Integer(i),
+        None => n.as_f64().map_or(GenericValue::Null, GenericValue::Float),
+    }
+}
+
+/// A JSON object as item data.
+pub fn object_from_json(map: Map<String, Value>) -> DataObject {
+    map.into_iter()
+        .map(|(key, value)| (key, GenericValue::from(value)))
+        .collect()
+}
+
+/// Item data as a JSON object.
+pub fn object_to_json(object: &DataObject) -> Map<String, Value> {
+    object
+        .iter()
+        .map(|(key, value)| (key.clone(), Value::from(value)))
+        .collect()
+}
+
+/// A binary attachment as `$binary` shows it: its metadata and, for stored
+/// data, the store's `id`.
+pub fn binary_to_json(binary: &BinaryData) -> Value {
+    let mut map = Map::new();
+    map.insert("mimeType".to_string(), binary.mime_type.clone().into());
+    let optional = [
+        ("fileName", binary.file_name.clone().map(Value::from)),
+        ("fileExtension", binary.file_extension.clone().map(Value::from)),
+        ("fileSize", binary.file_size.clone().map(Value::from)),
+        ("bytes", binary.bytes.map(Value::from)),
+        ("id", binary.id.clone().map(Value::from)),
+        (
+            "fileType",
+            binary.file_type.and_then(|file_type| serde_json::to_value(file_type).ok()),
+        ),
+    ];
+    for (key, value) in optional {
+        map.insert(key.to_string(), value.unwrap_or(Value::Null));
+    }
+    Value::Object(map)
+}
+
+/// The binary attachments of an item by name; an empty object without any.
+pub fn binaries_to_json(binary: Option<&HashMap<String, BinaryData>>) -> Value {
+    Value::Object(
+        binary
+            .into_iter()
+            .flatten()
+            .map(|(key, binary)| (key.clone(), binary_to_json(binary)))
+            .collect(),
+    )
+}
+
+/// The `{ json, binary }` view of an item.
+pub fn item_to_json(item: &NodeExecutionData) -> Value {
+    serde_json::json!({
+        "json": object_to_json(&item.json),
+        "binary": binaries_to_json(item.binary.as_ref()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::BinaryFileType;
+    use serde_json::json;
+
+    #[test]
+    fn test_values_round_trip() {
+        let value = json!({
+            "id": 42,
+            "negative": -7,
+            "price": 9.5,
+            "paid": true,
+            "note": null,
+            "tags": ["a", 1, 2.25, [false]],
+            "customer": {"name": "Ada", "orders": [{"total": 0.1}]},
+        });
+        let generic = GenericValue::from(&value);
+        assert_eq!(generic, GenericValue::from(value.clone()));
+        assert_eq!(Value::from(&generic), value);
+
+        // Integers stay integers and floats stay floats.
+        let GenericValue::Object(object) = &generic else {
+            panic!("expected an object");
+        };
+        assert_eq!(object["id"], GenericValue::Integer(42));
+        assert_eq!(object["price"], GenericValue::Float(9.5));
+        // The same as deserializing.
+        let deserialized: GenericValue = serde_json::from_value(value).unwrap();
+        assert_eq!(deserialized, generic);
+    }
+
+    #[test]
+    fn test_lossy_values_convert_like_javascript() {
+        for f in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(Value::from(GenericValue::Float(f)), Value::Null);
+        }
+        let big = json!(u64::MAX);
+        assert_eq!(GenericValue::from(&big), GenericValue::Float(u64::MAX as f64));
+
+        let at = DateTime::parse_from_rfc3339("2026-03-01T09:30:00.5+01:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let value = GenericValue::from(at);
+        assert_eq!(value, GenericValue::from("2026-03-01T08:30:00.500Z"));
+        assert_eq!(value.as_datetime(), Some(at));
+        assert_eq!(GenericValue::from("tomorrow").as_datetime(), None);
+
+        let stored = BinaryData {
+            data: "filesystem-v2".to_string(),
+            mime_type: "application/pdf".to_string(),
+            file_name: Some("invoice.pdf".to_string()),
+            file_extension: Some("pdf".to_string()),
+            file_size: None,
+            bytes: Some(2048),
+            id: Some("filesystem-v2:workflows/1/invoice".to_string()),
+            file_type: Some(BinaryFileType::Pdf),
+        };
+        let json = binary_to_json(&stored);
+        assert_eq!(json["id"], "filesystem-v2:workflows/1/invoice");
+        assert_eq!(json["fileType"], "pdf");
+        assert!(json.get("data").is_none());
+    }
+}
@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::connection::{WorkflowConnections, CONNECTION_MAIN};
+use crate::connection::{is_supply_connection, WorkflowConnections, CONNECTION_MAIN};
 use crate::data::{DataObject, PinData};
 use crate::node::Node;
 
@@ -93,6 +93,155 @@ pub struct WorkflowSettings {
     /// Save execution progress.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub save_execution_progress: Option<bool>,
+
+    /// Percentage (0–100) of successful executions saved with full data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub save_data_success_sample_percent: Option<u32>,
+
+    /// Always save full execution data, regardless of sampling.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub always_save_data: Option<bool>,
+
+    /// When triggers and webhooks may start the workflow.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_window: Option<RunWindowSettings>,
+
+    /// Record per-node profiles of executions, overriding the runtime
+    /// default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<bool>,
+
+    /// Key under which executions of this workflow run one at a time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concurrency_group: Option<ConcurrencyGroupSettings>,
+
+    /// Parameters a manual run accepts; they become the trigger item.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<WorkflowInput>,
+}
+
+/// A parameter of a manual run, e.g. the date range of a backfill job.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowInput {
+    /// Field of the trigger item the value is stored in.
+    pub name: String,
+
+    #[serde(rename = "type", default)]
+    pub input_type: WorkflowInputType,
+
+    /// Runs without the parameter and without a default are refused.
+    #[serde(default)]
+    pub required: bool,
+
+    /// Value used when the run does not supply one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Allowed values; empty allows any value of the type.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<serde_json::Value>,
+}
+
+/// Value type of a [`WorkflowInput`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkflowInputType {
+    #[default]
+    String,
+    Number,
+    Boolean,
+    /// Any JSON value.
+    Json,
+}
+
+/// Serializes executions that resolve to the same key.
+///
+/// `key` is usually an expression over the trigger item, e.g.
+/// `{{ $json.customerId }}`: executions for one customer run in order while
+/// different customers run in parallel. Executions whose key resolves to an
+/// empty string or null are not grouped.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcurrencyGroupSettings {
+    /// Group key, a literal or an expression.
+    pub key: String,
+
+    /// Seconds an execution may wait for its group before failing; waits
+    /// indefinitely when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_wait_secs: Option<u64>,
+}
+
+/// Times a workflow may be started by its triggers.
+///
+/// Outside every window, or inside a blackout, trigger and webhook starts
+/// are handled according to `outside_window`. Manual executions are not
+/// restricted.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunWindowSettings {
+    /// Allowed windows; empty means always allowed (blackouts still apply).
+    #[serde(default)]
+    pub windows: Vec<RunWindow>,
+
+    /// Periods during which the workflow never runs, e.g. a deploy freeze.
+    #[serde(default)]
+    pub blackouts: Vec<BlackoutPeriod>,
+
+    /// IANA timezone for `windows`; defaults to the workflow timezone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+
+    /// What happens to starts outside the windows.
+    #[serde(default)]
+    pub outside_window: OutsideWindowPolicy,
+}
+
+/// A recurring daily window, e.g. 08:00–20:00 on weekdays.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunWindow {
+    /// Days the window opens on; empty means every day.
+    #[serde(default)]
+    pub days: Vec<chrono::Weekday>,
+
+    /// Local opening time (`"08:00"`).
+    pub start: chrono::NaiveTime,
+
+    /// Local closing time, exclusive. Earlier than `start` for windows
+    /// that span midnight.
+    pub end: chrono::NaiveTime,
+}
+
+/// A one-off period during which the workflow must not run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlackoutPeriod {
+    pub start: chrono::DateTime<chrono::Utc>,
+
+    /// Exclusive.
+    pub end: chrono::DateTime<chrono::Utc>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Handling of trigger starts outside a workflow's run window.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OutsideWindowPolicy {
+    /// Hold the start and run it when the window next opens.
+    Queue,
+    /// Drop the start without recording an execution.
+    Skip,
+    /// Refuse the start; webhooks answer with an error and timers record
+    /// a failed execution.
+    #[default]
+    Reject,
 }
 
 /// Save data options.
@@ -104,8 +253,15 @@ pub enum SaveDataOption {
 }
 
 /// Execution order algorithm version.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+///
+/// `v0`, the legacy order, runs the children of a node after every node
+/// already queued, in connection order. `v1`, the order of workflows
+/// created by current n8n, runs a node's children before the rest of the
+/// queue, finishing one branch before starting the next, and starts them
+/// by canvas position: top to bottom, then left to right.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ExecutionOrder {
+    #[default]
     #[serde(rename = "v0")]
     V0,
     #[serde(rename = "v1")]
@@ -217,6 +373,19 @@ impl Workflow {
         target: &str,
         source_index: usize,
         target_index: usize,
+    ) -> Result<(), crate::WorkflowError> {
+        self.connect_with_type(source, target, CONNECTION_MAIN, source_index, target_index)
+    }
+
+    /// Connect two nodes through `connection_type`, e.g. to attach a
+    /// sub-node to a root node with `ai_languageModel`.
+    pub fn connect_with_type(
+        &mut self,
+        source: &str,
+        target: &str,
+        connection_type: &str,
+        source_index: usize,
+        target_index: usize,
     ) -> Result<(), crate::WorkflowError> {
         // Verify both nodes exist
         if self.get_node(source).is_none() {
@@ -226,21 +395,15 @@ impl Workflow {
             return Err(crate::WorkflowError::NodeNotFound(target.to_string()));
         }
 
-        let conn = crate::Connection::new(target, CONNECTION_MAIN, target_index);
+        let conn = crate::Connection::new(target, connection_type, target_index);
 
-        self.connections
+        let by_index = self
+            .connections
             .entry(source.to_string())
             .or_default()
-            .entry(CONNECTION_MAIN.to_string())
+            .entry(connection_type.to_string())
             .or_default();
 
-        let by_index = self
-            .connections
-            .get_mut(source)
-            .unwrap()
-            .get_mut(CONNECTION_MAIN)
-            .unwrap();
-
         // Extend the vector if needed
         while by_index.len() <= source_index {
             by_index.push(Vec::new());
@@ -257,16 +420,57 @@ impl Workflow {
         self.nodes.iter().filter(|n| n.is_trigger()).collect()
     }
 
-    /// Find start nodes (nodes with no incoming connections).
+    /// Find start nodes (nodes with no incoming connections), leaving out
+    /// sub-nodes.
     pub fn get_start_nodes(&self) -> Vec<&Node> {
         let conns_by_dest = crate::connection::graph::map_connections_by_destination(&self.connections);
 
         self.nodes
             .iter()
-            .filter(|n| !conns_by_dest.contains_key(&n.name))
+            .filter(|n| !conns_by_dest.contains_key(&n.name) && !self.is_sub_node(&n.name))
             .collect()
     }
 
+    /// Whether `name` is a sub-node: it has outgoing connections, and all
+    /// of them supply root nodes.
+    pub fn is_sub_node(&self, name: &str) -> bool {
+        let Some(node_conns) = self.connections.get(name) else {
+            return false;
+        };
+        let mut types = node_conns
+            .iter()
+            .filter(|(_, by_index)| by_index.iter().any(|conns| !conns.is_empty()))
+            .map(|(conn_type, _)| conn_type)
+            .peekable();
+        types.peek().is_some() && types.all(|conn_type| is_supply_connection(conn_type))
+    }
+
+    /// Sub-nodes supplying `root` through `connection_type`, by input
+    /// index, then name.
+    pub fn supply_nodes(&self, root: &str, connection_type: &str) -> Vec<&Node> {
+        let mut sources: Vec<(usize, &str)> = self
+            .connections
+            .iter()
+            .filter_map(|(source, node_conns)| Some((source, node_conns.get(connection_type)?)))
+            .flat_map(|(source, by_index)| {
+                by_index
+                    .iter()
+                    .flatten()
+                    .filter(|conn| conn.node == root)
+                    .map(move |conn| (conn.index, source.as_str()))
+            })
+            .collect();
+        sources.sort();
+        sources.dedup();
+        sources.into_iter().filter_map(|(_, name)| self.get_node(name)).collect()
+    }
+
+    /// Execution order of the workflow, `v0` unless its settings say
+    /// otherwise.
+    pub fn execution_order(&self) -> ExecutionOrder {
+        self.settings.execution_order.unwrap_or_default()
+    }
+
     /// Get all node names.
     pub fn node_names(&self) -> Vec<String> {
         self.nodes.iter().map(|n| n.name.clone()).collect()